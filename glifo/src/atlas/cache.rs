@@ -2,11 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 //! Glyph atlas cache with LRU eviction.
+//!
+//! Glyphs are packed into the pages of a shared [`ImageCache`], which grows by
+//! another page whenever the existing ones are full. Three mechanisms keep the
+//! atlas from growing without bound, all of which run in
+//! [`GlyphAtlas::maintain`]:
+//!
+//! - Entries that haven't been used for [`GlyphCacheConfig::max_entry_age`]
+//!   frames are evicted.
+//! - If the cached glyphs take up more than [`GlyphCacheConfig::max_atlas_bytes`],
+//!   the least recently used entries are evicted until they fit again.
+//! - A page whose occupancy drops below [`GlyphCacheConfig::min_page_occupancy`]
+//!   is compacted: its entries are evicted, so that they are re-rasterized into
+//!   the free space of the other pages the next time they are drawn.
 
 use super::commands::AtlasCommandRecorder;
-use super::key::GlyphCacheKey;
 #[cfg(all(debug_assertions, feature = "std"))]
-use super::key::SUBPIXEL_BUCKETS;
+use super::key::clamp_subpixel_buckets;
+use super::key::{DEFAULT_SUBPIXEL_BUCKETS, DEFAULT_VARIATION_STEPS, GlyphCacheKey};
 use super::region::{AtlasSlot, RasterMetrics};
 use crate::Pixmap;
 use alloc::sync::Arc;
@@ -48,6 +61,10 @@ const EMPTY_VAR_MAP: FixedHashMap<VarKey, FixedHashMap<GlyphCacheKey, GlyphCache
 /// native bilinear sampling in the hybrid renderer.
 pub const GLYPH_PADDING: u16 = 1;
 
+/// Number of bytes per atlas pixel, used to account cached glyphs against
+/// [`GlyphCacheConfig::max_atlas_bytes`].
+const BYTES_PER_PIXEL: usize = 4;
+
 /// Configuration for glyph cache behavior.
 #[derive(Clone, Debug)]
 pub struct GlyphCacheConfig {
@@ -59,6 +76,44 @@ pub struct GlyphCacheConfig {
     /// sizes above this threshold are drawn directly each frame, since very
     /// large glyphs consume disproportionate atlas space.
     pub max_cached_font_size: f32,
+    /// Number of horizontal subpixel positions that outline glyphs are
+    /// quantized to, clamped to `1..=MAX_SUBPIXEL_BUCKETS`.
+    ///
+    /// Each glyph is rasterized pre-shifted by the offset of its bucket, so
+    /// higher values position small text more precisely at the cost of up to
+    /// that many atlas entries per glyph. Common values: 1 (disabled), 2,
+    /// 4 (default), 8.
+    ///
+    /// [`MAX_SUBPIXEL_BUCKETS`]: crate::atlas::key::MAX_SUBPIXEL_BUCKETS
+    pub subpixel_buckets: u8,
+    /// Number of steps per normalized unit that the variation coordinates of
+    /// variable fonts are quantized to, clamped to `1..=MAX_VARIATION_STEPS`.
+    ///
+    /// Glyph runs drawn with the atlas cache enabled are rendered at the
+    /// quantized coordinates, so animating an axis like `wght` reuses cached
+    /// instances instead of creating new entries every frame. The default of
+    /// 128 splits each half of an axis into 128 instances; use
+    /// [`MAX_VARIATION_STEPS`] to render at the exact coordinates.
+    ///
+    /// [`MAX_VARIATION_STEPS`]: crate::atlas::key::MAX_VARIATION_STEPS
+    pub variation_steps: u16,
+    /// Maximum number of bytes that cached glyphs may take up in the atlas,
+    /// including their padding, or `None` for no limit.
+    ///
+    /// When the cached glyphs exceed the budget, the least recently used ones
+    /// are evicted until they fit again. Glyphs used during the last frame are
+    /// never evicted, so a single frame that needs more than the budget still
+    /// renders from the cache.
+    pub max_atlas_bytes: Option<usize>,
+    /// Fraction of a page's area that cached glyphs must occupy for the page
+    /// to be kept as is.
+    ///
+    /// During each eviction pass, the sparsest page below this threshold is
+    /// compacted if its glyphs fit into the free space of the other pages.
+    /// Its entries are evicted and re-rasterized elsewhere when they are next
+    /// drawn, which lets the page drain instead of pinning a few glyphs. Use
+    /// `0.0` to disable compaction.
+    pub min_page_occupancy: f32,
 }
 
 impl Default for GlyphCacheConfig {
@@ -67,6 +122,10 @@ impl Default for GlyphCacheConfig {
             max_entry_age: 64,
             eviction_frequency: 64,
             max_cached_font_size: 128.0,
+            subpixel_buckets: DEFAULT_SUBPIXEL_BUCKETS,
+            variation_steps: DEFAULT_VARIATION_STEPS,
+            max_atlas_bytes: None,
+            min_page_occupancy: 0.25,
         }
     }
 }
@@ -129,6 +188,8 @@ pub struct GlyphAtlas {
     last_eviction_serial: u64,
     /// Total cached glyph count (across all maps).
     entry_count: usize,
+    /// Number of atlas bytes taken up by cached glyphs, including padding.
+    atlas_bytes: usize,
     /// Bitmap glyphs awaiting GPU upload.
     pending_uploads: Vec<PendingBitmapUpload>,
     /// Atlas regions that must be cleared to transparent before compositing.
@@ -158,6 +219,7 @@ impl GlyphAtlas {
             serial: 0,
             last_eviction_serial: 0,
             entry_count: 0,
+            atlas_bytes: 0,
             pending_uploads: Vec::new(),
             pending_clear_rects: Vec::new(),
             pending_atlas_commands: SmallVec::new(),
@@ -257,6 +319,7 @@ impl GlyphAtlas {
 
         entries.insert(key, entry);
         self.entry_count += 1;
+        self.atlas_bytes += slot_bytes(&atlas_slot);
 
         Some(atlas_slot)
     }
@@ -336,9 +399,15 @@ impl GlyphAtlas {
             .get_or_insert_with(|| AtlasCommandRecorder::new(page_index, atlas_width, atlas_height))
     }
 
-    /// Advance the frame counter and potentially evict old entries.
+    /// Advance the frame counter and evict entries as configured.
+    ///
+    /// The byte budget is enforced every frame, while old entries are only
+    /// evicted and sparse pages only compacted every
+    /// [`eviction_frequency`](GlyphCacheConfig::eviction_frequency) frames.
     pub fn maintain(&mut self, image_cache: &mut ImageCache) {
         self.tick();
+        self.enforce_budget(image_cache);
+
         let frames_since_eviction = self.serial - self.last_eviction_serial;
         if frames_since_eviction < self.eviction_config.eviction_frequency {
             return;
@@ -346,6 +415,7 @@ impl GlyphAtlas {
 
         self.last_eviction_serial = self.serial;
         self.evict_old_entries(image_cache);
+        self.compact_sparse_page(image_cache);
     }
 
     /// Advance the frame counter.
@@ -363,14 +433,104 @@ impl GlyphAtlas {
     fn evict_old_entries(&mut self, image_cache: &mut ImageCache) {
         let serial = self.serial;
         let max_entry_age = self.eviction_config.max_entry_age;
+        self.evict_where(image_cache, |entry| serial - entry.serial > max_entry_age);
+    }
+
+    /// Evict the least recently used entries until the cached glyphs fit into
+    /// [`GlyphCacheConfig::max_atlas_bytes`].
+    ///
+    /// Entries are evicted a whole frame at a time, starting with the oldest,
+    /// and entries used during the last frame are always kept.
+    fn enforce_budget(&mut self, image_cache: &mut ImageCache) {
+        let Some(budget) = self.eviction_config.max_atlas_bytes else {
+            return;
+        };
+        if self.atlas_bytes <= budget {
+            return;
+        }
+
+        // Sum up the bytes used by the entries of each frame, oldest first.
+        let mut frames: Vec<(u64, usize)> = self
+            .all_entries()
+            .map(|entry| (entry.serial, slot_bytes(&entry.atlas_slot)))
+            .collect();
+        frames.sort_unstable_by_key(|(serial, _)| *serial);
+
+        let last_frame = self.serial.saturating_sub(1);
+        let mut remaining = self.atlas_bytes;
+        let mut cutoff = None;
+        for (serial, bytes) in frames {
+            if remaining <= budget || serial >= last_frame {
+                break;
+            }
+            remaining -= bytes;
+            cutoff = Some(serial);
+        }
+
+        if let Some(cutoff) = cutoff {
+            self.evict_where(image_cache, |entry| entry.serial <= cutoff);
+        }
+    }
+
+    /// Compact the sparsest page whose occupancy is below
+    /// [`GlyphCacheConfig::min_page_occupancy`], if its glyphs fit into the
+    /// free space of the other pages.
+    fn compact_sparse_page(&mut self, image_cache: &mut ImageCache) {
+        let atlas_manager = image_cache.atlas_manager();
+        if atlas_manager.atlas_count() < 2 {
+            return;
+        }
+
+        let mut glyph_areas: SmallVec<[u32; 4]> =
+            SmallVec::from_elem(0, atlas_manager.atlas_count());
+        for entry in self.all_entries() {
+            if let Some(area) = glyph_areas.get_mut(entry.atlas_slot.page_index as usize) {
+                *area += slot_area(&entry.atlas_slot);
+            }
+        }
+
+        let (width, height) = atlas_manager.config().atlas_size;
+        let min_area = self.eviction_config.min_page_occupancy * (width * height) as f32;
+        let Some((page_index, glyph_area)) = (0_u32..)
+            .zip(glyph_areas)
+            .filter(|(_, area)| *area > 0 && (*area as f32) < min_area)
+            .min_by_key(|(_, area)| *area)
+        else {
+            return;
+        };
+
+        // The atlas is shared with other images, so use its own statistics for
+        // the free space that's left.
+        let free_elsewhere: u64 = atlas_manager
+            .atlas_stats()
+            .iter()
+            .filter(|(id, _)| id.as_u32() != page_index)
+            .map(|(_, stats)| u64::from(stats.total_area - stats.allocated_area))
+            .sum();
+        if u64::from(glyph_area) > free_elsewhere {
+            return;
+        }
+
+        self.evict_where(image_cache, |entry| {
+            entry.atlas_slot.page_index == page_index
+        });
+    }
+
+    /// Evict all entries for which `should_evict` returns `true`.
+    fn evict_where(
+        &mut self,
+        image_cache: &mut ImageCache,
+        mut should_evict: impl FnMut(&GlyphCacheEntry) -> bool,
+    ) {
         let entry_count = &mut self.entry_count;
+        let atlas_bytes = &mut self.atlas_bytes;
         let pending_clear_rects = &mut self.pending_clear_rects;
 
         let mut should_retain = |entry: &GlyphCacheEntry| -> bool {
-            let age = serial - entry.serial;
-            if age > max_entry_age {
+            if should_evict(entry) {
                 image_cache.deallocate(entry.atlas_slot.image_id);
                 *entry_count = entry_count.saturating_sub(1);
+                *atlas_bytes = atlas_bytes.saturating_sub(slot_bytes(&entry.atlas_slot));
                 push_clear_rect_for_slot(pending_clear_rects, &entry.atlas_slot);
                 false
             } else {
@@ -386,6 +546,13 @@ impl GlyphAtlas {
         });
     }
 
+    /// Iterate over all cache entries, static and variable.
+    fn all_entries(&self) -> impl Iterator<Item = &GlyphCacheEntry> {
+        self.static_entries
+            .values()
+            .chain(self.variable_entries.values().flat_map(|e| e.values()))
+    }
+
     /// Clear all cache entries, pending work queues, and statistics.
     pub fn clear(&mut self) {
         self.static_entries.clear();
@@ -393,6 +560,7 @@ impl GlyphAtlas {
         self.serial = 0;
         self.last_eviction_serial = 0;
         self.entry_count = 0;
+        self.atlas_bytes = 0;
         self.pending_uploads.clear();
         self.pending_clear_rects.clear();
         self.pending_atlas_commands.clear();
@@ -412,6 +580,13 @@ impl GlyphAtlas {
         self.entry_count == 0
     }
 
+    /// Get the number of atlas bytes taken up by cached glyphs, including
+    /// their padding.
+    #[inline]
+    pub fn atlas_bytes(&self) -> usize {
+        self.atlas_bytes
+    }
+
     /// Get the number of cache hits since last `clear_stats()`.
     #[inline]
     pub fn cache_hits(&self) -> u64 {
@@ -446,6 +621,16 @@ fn push_clear_rect_for_slot(pending: &mut Vec<PendingClearRect>, slot: &AtlasSlo
     });
 }
 
+/// Area of the padded atlas region of a slot, in pixels.
+fn slot_area(slot: &AtlasSlot) -> u32 {
+    u32::from(slot.width + 2 * GLYPH_PADDING) * u32::from(slot.height + 2 * GLYPH_PADDING)
+}
+
+/// Number of atlas bytes taken up by the padded region of a slot.
+fn slot_bytes(slot: &AtlasSlot) -> usize {
+    slot_area(slot) as usize * BYTES_PER_PIXEL
+}
+
 /// Statistics about cached glyphs.
 #[cfg(all(debug_assertions, feature = "std"))]
 #[derive(Debug)]
@@ -458,8 +643,8 @@ pub struct GlyphCacheStats {
     pub page_count: usize,
     /// Number of unique glyph IDs (same glyph may have multiple entries due to subpixel).
     pub unique_glyph_ids: usize,
-    /// Distribution of entries across subpixel buckets.
-    pub subpixel_distribution: [usize; SUBPIXEL_BUCKETS as usize],
+    /// Distribution of outline entries across subpixel buckets.
+    pub subpixel_distribution: Vec<usize>,
     /// List of unique font sizes used.
     pub sizes_used: Vec<f32>,
 }
@@ -479,12 +664,15 @@ impl GlyphAtlas {
         use std::collections::HashSet;
 
         let mut unique_ids = HashSet::new();
-        let mut subpixel_dist = [0; SUBPIXEL_BUCKETS as usize];
+        let buckets = clamp_subpixel_buckets(self.eviction_config.subpixel_buckets);
+        let mut subpixel_dist = alloc::vec![0; usize::from(buckets)];
         let mut sizes = HashSet::new();
 
         for key in self.static_entries.keys() {
             unique_ids.insert(key.glyph_id);
-            subpixel_dist[key.subpixel_x as usize] += 1;
+            if let Some(count) = subpixel_dist.get_mut(usize::from(key.subpixel_x)) {
+                *count += 1;
+            }
             sizes.insert(key.size_bits);
         }
 
@@ -493,7 +681,9 @@ impl GlyphAtlas {
         for entries in self.variable_entries.values() {
             for key in entries.keys() {
                 unique_ids.insert(key.glyph_id);
-                subpixel_dist[key.subpixel_x as usize] += 1;
+                if let Some(count) = subpixel_dist.get_mut(usize::from(key.subpixel_x)) {
+                    *count += 1;
+                }
                 sizes.insert(key.size_bits);
             }
         }
@@ -584,11 +774,11 @@ impl GlyphAtlas {
             log::debug!("glyph_id {:4} ({} {}):", glyph_id, keys.len(), suffix);
             for (k, source) in keys {
                 log::debug!(
-                    "    [{}] subpx: {}, size: {:.2}, hinted: {}, font_id: {:016x}, font_index: {}",
+                    "    [{}] subpx: {}, size: {:.2}, hinting: {:?}, font_id: {:016x}, font_index: {}",
                     source,
                     k.subpixel_x,
                     f32::from_bits(k.size_bits),
-                    k.hinted,
+                    k.hinting,
                     k.font_id,
                     k.font_index,
                 );
@@ -640,3 +830,131 @@ impl From<VarLookupKey<'_>> for VarKey {
         Self::from_slice(key.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{AtlasConfig, GlyphAtlas, GlyphCacheConfig, ImageCache};
+    use crate::FontEmbolden;
+    use crate::atlas::key::GlyphCacheKey;
+    use crate::atlas::region::RasterMetrics;
+    use crate::peniko::color::palette::css::BLACK;
+
+    fn key(glyph_id: u32) -> GlyphCacheKey {
+        GlyphCacheKey::new(
+            1,
+            0,
+            glyph_id,
+            16.0,
+            None,
+            None,
+            0.0,
+            1,
+            BLACK,
+            0,
+            FontEmbolden::default(),
+            &[],
+        )
+    }
+
+    fn metrics(size: u16) -> RasterMetrics {
+        RasterMetrics {
+            width: size,
+            height: size,
+            bearing_x: 0,
+            bearing_y: 0,
+        }
+    }
+
+    fn image_cache() -> ImageCache {
+        ImageCache::new_with_config(AtlasConfig {
+            atlas_size: (64, 64),
+            ..AtlasConfig::default()
+        })
+    }
+
+    #[test]
+    fn atlas_grows_by_pages() {
+        let mut atlas = GlyphAtlas::new();
+        let mut image_cache = image_cache();
+        for glyph_id in 0..4 {
+            atlas.insert_entry(&mut image_cache, key(glyph_id), metrics(38));
+        }
+
+        assert_eq!(atlas.len(), 4);
+        assert_eq!(image_cache.atlas_count(), 4);
+        assert_eq!(atlas.atlas_bytes(), 4 * 40 * 40 * 4);
+    }
+
+    #[test]
+    fn budget_evicts_least_recently_used() {
+        let mut atlas = GlyphAtlas::with_config(GlyphCacheConfig {
+            max_atlas_bytes: Some(2 * 10 * 10 * 4),
+            ..GlyphCacheConfig::default()
+        });
+        let mut image_cache = image_cache();
+        for glyph_id in 0..2 {
+            atlas.insert_entry(&mut image_cache, key(glyph_id), metrics(8));
+            atlas.maintain(&mut image_cache);
+        }
+        // Glyph 0 is the oldest, but was used more recently than glyph 1.
+        assert!(atlas.get(&key(0)).is_some());
+        atlas.insert_entry(&mut image_cache, key(2), metrics(8));
+        atlas.maintain(&mut image_cache);
+
+        assert!(atlas.get(&key(1)).is_none());
+        assert!(atlas.get(&key(0)).is_some());
+        assert!(atlas.get(&key(2)).is_some());
+        assert_eq!(atlas.atlas_bytes(), 2 * 10 * 10 * 4);
+        assert_eq!(atlas.drain_pending_clear_rects().count(), 1);
+    }
+
+    #[test]
+    fn glyphs_of_last_frame_exceed_budget() {
+        let mut atlas = GlyphAtlas::with_config(GlyphCacheConfig {
+            max_atlas_bytes: Some(10 * 10 * 4),
+            ..GlyphCacheConfig::default()
+        });
+        let mut image_cache = image_cache();
+        for glyph_id in 0..3 {
+            atlas.insert_entry(&mut image_cache, key(glyph_id), metrics(8));
+        }
+        atlas.maintain(&mut image_cache);
+
+        assert_eq!(atlas.len(), 3);
+    }
+
+    #[test]
+    fn sparse_pages_are_compacted() {
+        let run = |min_page_occupancy: f32| {
+            let mut atlas = GlyphAtlas::with_config(GlyphCacheConfig {
+                eviction_frequency: 1,
+                max_entry_age: 1,
+                min_page_occupancy,
+                ..GlyphCacheConfig::default()
+            });
+            let mut image_cache = image_cache();
+            // Fill the first page, then spill a small glyph onto a second one.
+            atlas.insert_entry(&mut image_cache, key(0), metrics(62));
+            let slot = atlas
+                .insert_entry(&mut image_cache, key(1), metrics(8))
+                .unwrap();
+            assert_eq!(slot.page_index, 1);
+
+            // Keep using the small glyph until the large one is evicted.
+            atlas.maintain(&mut image_cache);
+            atlas.get(&key(1));
+            atlas.maintain(&mut image_cache);
+            (atlas, image_cache)
+        };
+
+        let (atlas, _) = run(0.0);
+        assert_eq!(atlas.len(), 1);
+
+        let (mut atlas, mut image_cache) = run(0.25);
+        assert!(atlas.is_empty());
+        let slot = atlas
+            .insert_entry(&mut image_cache, key(1), metrics(8))
+            .unwrap();
+        assert_eq!(slot.page_index, 0);
+    }
+}
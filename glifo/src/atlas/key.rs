@@ -9,31 +9,49 @@
 //! equal produce identical bitmaps and can safely share a single atlas entry.
 
 use crate::color::{AlphaColor, Srgb};
-use crate::glyph::FontEmbolden;
+use crate::glyph::{FontEmbolden, HintingEngine, SubpixelOrder};
 use crate::kurbo::Join;
+use alloc::vec::Vec;
 use core::hash::{Hash, Hasher};
 #[cfg(not(feature = "std"))]
 use core_maths::CoreFloat as _;
 use skrifa::instance::NormalizedCoord;
 use smallvec::SmallVec;
 
-/// Number of horizontal subpixel quantization buckets (valid range: 1–253).
+/// Default number of horizontal subpixel quantization buckets.
 ///
-/// Higher values improve rendering quality at the cost of more atlas entries
-/// per glyph. Common values: 1 (disabled), 2, 4 (default), 8.
-pub(crate) const SUBPIXEL_BUCKETS: u8 = 4;
+/// See [`GlyphCacheConfig::subpixel_buckets`](crate::atlas::GlyphCacheConfig::subpixel_buckets).
+pub(crate) const DEFAULT_SUBPIXEL_BUCKETS: u8 = 4;
+
+/// Largest supported number of horizontal subpixel quantization buckets.
+///
+/// The two values above the largest bucket index are reserved for the
+/// [`SUBPIXEL_COLR`] and [`SUBPIXEL_BITMAP`] sentinels.
+pub const MAX_SUBPIXEL_BUCKETS: u8 = u8::MAX - 1;
+
+/// Default number of steps per normalized unit that variation coordinates are
+/// quantized to.
+///
+/// See [`GlyphCacheConfig::variation_steps`](crate::atlas::GlyphCacheConfig::variation_steps).
+pub(crate) const DEFAULT_VARIATION_STEPS: u16 = 128;
+
+/// Largest supported number of variation coordinate steps per normalized unit.
+///
+/// This is the resolution of the underlying F2DOT14 format, so using it keeps
+/// coordinates unchanged.
+pub const MAX_VARIATION_STEPS: u16 = 1 << 14;
 
 /// Sentinel `subpixel_x` for COLR glyph cache entries.
 ///
-/// `quantize_subpixel` returns values in `0..SUBPIXEL_BUCKETS`, so values
-/// above that range can never appear in an outline key. Using distinct
+/// `quantize_subpixel` returns values below [`MAX_SUBPIXEL_BUCKETS`], so
+/// values at or above it can never appear in an outline key. Using distinct
 /// sentinels for COLR and bitmap entries prevents cache collisions between
 /// glyph types that would otherwise produce identical keys (same font, glyph
 /// id, size, and color).
-pub(crate) const SUBPIXEL_COLR: u8 = SUBPIXEL_BUCKETS;
+pub(crate) const SUBPIXEL_COLR: u8 = MAX_SUBPIXEL_BUCKETS;
 
 /// Sentinel `subpixel_x` for bitmap glyph cache entries. See [`SUBPIXEL_COLR`].
-pub(crate) const SUBPIXEL_BITMAP: u8 = SUBPIXEL_BUCKETS + 1;
+pub(crate) const SUBPIXEL_BITMAP: u8 = MAX_SUBPIXEL_BUCKETS + 1;
 
 /// Unique identifier for a cached glyph bitmap.
 ///
@@ -56,9 +74,11 @@ pub struct GlyphCacheKey {
     pub glyph_id: u32,
     /// Font size as f32 bits (exact match, no quantization).
     pub size_bits: u32,
-    /// Whether hinting was applied.
-    pub hinted: bool,
-    /// Horizontal subpixel position (0 to SUBPIXEL_BUCKETS-1 for outlines),
+    /// The engine used for hinting, if hinting was applied.
+    pub hinting: Option<HintingEngine>,
+    /// The subpixel order of the coverage, if the glyph is subpixel antialiased.
+    pub subpixel_order: Option<SubpixelOrder>,
+    /// Horizontal subpixel bucket (0 to `subpixel_buckets - 1` for outlines),
     /// or a sentinel (`SUBPIXEL_COLR` / `SUBPIXEL_BITMAP`) for non-outline glyphs.
     pub subpixel_x: u8,
     /// Context color for COLR glyphs. Only used for rendering, not for Hash/Eq.
@@ -83,15 +103,17 @@ impl GlyphCacheKey {
     /// Creates a new cache key.
     ///
     /// `fractional_x` (the fractional pixel offset) is quantized into
-    /// `SUBPIXEL_BUCKETS` buckets, so nearby positions share the same entry.
+    /// `subpixel_buckets` buckets, so nearby positions share the same entry.
     #[inline]
     pub fn new(
         font_id: u64,
         font_index: u32,
         glyph_id: u32,
         size: f32,
-        hinted: bool,
+        hinting: Option<HintingEngine>,
+        subpixel_order: Option<SubpixelOrder>,
         fractional_x: f32,
+        subpixel_buckets: u8,
         context_color: AlphaColor<Srgb>,
         context_color_packed: u32,
         embolden: FontEmbolden,
@@ -102,8 +124,9 @@ impl GlyphCacheKey {
             font_index,
             glyph_id,
             size_bits: size.to_bits(),
-            hinted,
-            subpixel_x: quantize_subpixel(fractional_x),
+            hinting,
+            subpixel_order,
+            subpixel_x: quantize_subpixel(fractional_x, subpixel_buckets),
             context_color,
             context_color_packed,
             embolden_x_bits: f32_bits(embolden.amount.xx),
@@ -127,7 +150,8 @@ impl Hash for GlyphCacheKey {
         self.font_index.hash(state);
         self.glyph_id.hash(state);
         self.size_bits.hash(state);
-        self.hinted.hash(state);
+        self.hinting.hash(state);
+        self.subpixel_order.hash(state);
         self.subpixel_x.hash(state);
         self.context_color_packed.hash(state);
         self.embolden_x_bits.hash(state);
@@ -146,7 +170,8 @@ impl PartialEq for GlyphCacheKey {
             && self.font_id == other.font_id
             && self.font_index == other.font_index
             && self.size_bits == other.size_bits
-            && self.hinted == other.hinted
+            && self.hinting == other.hinting
+            && self.subpixel_order == other.subpixel_order
             && self.context_color_packed == other.context_color_packed
             && self.embolden_x_bits == other.embolden_x_bits
             && self.embolden_y_bits == other.embolden_y_bits
@@ -182,7 +207,13 @@ pub(crate) fn pack_color(color: AlphaColor<Srgb>) -> u32 {
     color.premultiply().to_rgba8().to_u32()
 }
 
-/// Quantize a fractional pixel offset into one of [`SUBPIXEL_BUCKETS`] buckets.
+/// Clamp a configured number of subpixel buckets to the supported range.
+#[inline]
+pub(crate) fn clamp_subpixel_buckets(buckets: u8) -> u8 {
+    buckets.clamp(1, MAX_SUBPIXEL_BUCKETS)
+}
+
+/// Quantize a fractional pixel offset into one of `buckets` buckets.
 ///
 /// Values near 1.0 (>= 0.875 with 4 buckets) are clamped to the last bucket
 /// rather than wrapping to 0. Wrapping to bucket 0 without also incrementing the
@@ -190,23 +221,50 @@ pub(crate) fn pack_color(color: AlphaColor<Srgb>) -> u32 {
 /// direction. Clamping keeps the worst-case error to 0.125px.
 #[expect(
     clippy::cast_possible_truncation,
-    reason = "result is clamped to SUBPIXEL_BUCKETS-1 which fits in u8"
+    reason = "result is clamped to buckets-1 which fits in u8"
 )]
 #[inline]
-fn quantize_subpixel(frac: f32) -> u8 {
+fn quantize_subpixel(frac: f32, buckets: u8) -> u8 {
+    let buckets = clamp_subpixel_buckets(buckets);
     let normalized = frac.fract();
     let normalized = if normalized < 0.0 {
         normalized + 1.0
     } else {
         normalized
     };
-    ((normalized * SUBPIXEL_BUCKETS as f32).round() as u8).min(SUBPIXEL_BUCKETS - 1)
+    ((normalized * buckets as f32).round() as u8).min(buckets - 1)
 }
 
-/// Convert a quantized bucket index back to the fractional pixel offset it represents.
+/// Convert a quantized bucket index back to the fractional pixel offset it
+/// represents, given the number of buckets it was quantized with.
 #[inline]
-pub fn subpixel_offset(quantized: u8) -> f32 {
-    quantized as f32 / SUBPIXEL_BUCKETS as f32
+pub fn subpixel_offset(quantized: u8, buckets: u8) -> f32 {
+    quantized as f32 / clamp_subpixel_buckets(buckets) as f32
+}
+
+/// Quantize normalized variation coordinates to `steps` steps per normalized
+/// unit and write them to `out`.
+///
+/// Snapping animated coordinates (for example a changing `wght` axis) to a
+/// fixed grid bounds the number of distinct instances that end up in the glyph
+/// caches.
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "result is clamped to the range of i16"
+)]
+pub(crate) fn quantize_var_coords(
+    coords: &[NormalizedCoord],
+    steps: u16,
+    out: &mut Vec<NormalizedCoord>,
+) {
+    let steps = i32::from(steps.clamp(1, MAX_VARIATION_STEPS));
+    let one = i32::from(MAX_VARIATION_STEPS);
+    out.clear();
+    out.extend(coords.iter().map(|coord| {
+        let step = (i32::from(coord.to_bits()) * steps + one / 2).div_euclid(one);
+        let bits = (step * one + steps / 2).div_euclid(steps);
+        NormalizedCoord::from_bits(bits.clamp(i16::MIN.into(), i16::MAX.into()) as i16)
+    }));
 }
 
 #[cfg(test)]
@@ -218,25 +276,61 @@ mod tests {
     #[test]
     fn test_quantize_subpixel() {
         // Test bucket boundaries
-        assert_eq!(quantize_subpixel(0.0), 0);
-        assert_eq!(quantize_subpixel(0.1), 0);
-        assert_eq!(quantize_subpixel(0.2), 1);
-        assert_eq!(quantize_subpixel(0.25), 1);
-        assert_eq!(quantize_subpixel(0.4), 2);
-        assert_eq!(quantize_subpixel(0.5), 2);
-        assert_eq!(quantize_subpixel(0.6), 2);
-        assert_eq!(quantize_subpixel(0.7), 3);
-        assert_eq!(quantize_subpixel(0.75), 3);
-        assert_eq!(quantize_subpixel(0.9), 3);
-        assert_eq!(quantize_subpixel(1.0), 0);
+        assert_eq!(quantize_subpixel(0.0, 4), 0);
+        assert_eq!(quantize_subpixel(0.1, 4), 0);
+        assert_eq!(quantize_subpixel(0.2, 4), 1);
+        assert_eq!(quantize_subpixel(0.25, 4), 1);
+        assert_eq!(quantize_subpixel(0.4, 4), 2);
+        assert_eq!(quantize_subpixel(0.5, 4), 2);
+        assert_eq!(quantize_subpixel(0.6, 4), 2);
+        assert_eq!(quantize_subpixel(0.7, 4), 3);
+        assert_eq!(quantize_subpixel(0.75, 4), 3);
+        assert_eq!(quantize_subpixel(0.9, 4), 3);
+        assert_eq!(quantize_subpixel(1.0, 4), 0);
+    }
+
+    #[test]
+    fn test_quantize_subpixel_bucket_counts() {
+        // A single bucket disables subpixel positioning.
+        assert_eq!(quantize_subpixel(0.3, 1), 0);
+        assert_eq!(quantize_subpixel(0.9, 1), 0);
+        // Zero buckets behave like a single one.
+        assert_eq!(quantize_subpixel(0.6, 0), 0);
+        assert_eq!(quantize_subpixel(0.3, 2), 1);
+        assert_eq!(quantize_subpixel(0.2, 8), 2);
+        assert_eq!(quantize_subpixel(0.95, 8), 7);
+        assert_eq!(subpixel_offset(2, 8), 0.25);
+        assert_eq!(subpixel_offset(0, 0), 0.0);
+    }
+
+    #[test]
+    fn test_quantize_var_coords() {
+        let coords = |bits: &[i16]| -> Vec<NormalizedCoord> {
+            bits.iter()
+                .map(|b| NormalizedCoord::from_bits(*b))
+                .collect()
+        };
+        let mut out = Vec::new();
+
+        // Nearby coordinates snap to the same step.
+        quantize_var_coords(&coords(&[8030, -8030]), 128, &mut out);
+        assert_eq!(out, coords(&[8064, -8064]));
+        quantize_var_coords(&coords(&[8090, -8090]), 128, &mut out);
+        assert_eq!(out, coords(&[8064, -8064]));
+        // The ends of the normalized range are preserved.
+        quantize_var_coords(&coords(&[16384, -16384, 0]), 3, &mut out);
+        assert_eq!(out, coords(&[16384, -16384, 0]));
+        // The maximum number of steps keeps coordinates unchanged.
+        quantize_var_coords(&coords(&[8001, -3]), MAX_VARIATION_STEPS, &mut out);
+        assert_eq!(out, coords(&[8001, -3]));
     }
 
     #[test]
     fn test_subpixel_offset() {
-        assert_eq!(subpixel_offset(0), 0.0);
-        assert_eq!(subpixel_offset(1), 0.25);
-        assert_eq!(subpixel_offset(2), 0.5);
-        assert_eq!(subpixel_offset(3), 0.75);
+        assert_eq!(subpixel_offset(0, 4), 0.0);
+        assert_eq!(subpixel_offset(1, 4), 0.25);
+        assert_eq!(subpixel_offset(2, 4), 0.5);
+        assert_eq!(subpixel_offset(3, 4), 0.75);
     }
 
     #[test]
@@ -247,8 +341,10 @@ mod tests {
             0,
             42,
             16.0,
-            true,
+            Some(HintingEngine::AutoFallback),
+            None,
             0.3,
+            DEFAULT_SUBPIXEL_BUCKETS,
             BLACK,
             packed,
             FontEmbolden::default(),
@@ -259,14 +355,27 @@ mod tests {
             0,
             42,
             16.0,
-            true,
+            Some(HintingEngine::AutoFallback),
+            None,
             0.3,
+            DEFAULT_SUBPIXEL_BUCKETS,
             BLACK,
             packed,
             FontEmbolden::default(),
             &[],
         );
         assert_eq!(key1, key2);
+
+        let subpixel_key = GlyphCacheKey {
+            subpixel_order: Some(SubpixelOrder::Rgb),
+            ..key1.clone()
+        };
+        assert_ne!(key1, subpixel_key);
+        let bgr_key = GlyphCacheKey {
+            subpixel_order: Some(SubpixelOrder::Bgr),
+            ..key1.clone()
+        };
+        assert_ne!(subpixel_key, bgr_key);
     }
 
     #[test]
@@ -277,8 +386,10 @@ mod tests {
             0,
             42,
             16.0,
-            false,
+            None,
+            None,
             0.0,
+            DEFAULT_SUBPIXEL_BUCKETS,
             BLACK,
             packed,
             FontEmbolden::default(),
@@ -289,7 +400,8 @@ mod tests {
             font_index: 0,
             glyph_id: 42,
             size_bits: 16.0_f32.to_bits(),
-            hinted: false,
+            hinting: None,
+            subpixel_order: None,
             subpixel_x: SUBPIXEL_COLR,
             context_color: BLACK,
             context_color_packed: packed,
@@ -305,7 +417,8 @@ mod tests {
             font_index: 0,
             glyph_id: 42,
             size_bits: 16.0_f32.to_bits(),
-            hinted: false,
+            hinting: None,
+            subpixel_order: None,
             subpixel_x: SUBPIXEL_BITMAP,
             context_color: BLACK,
             context_color_packed: packed,
@@ -323,10 +436,12 @@ mod tests {
 
     #[test]
     fn test_sentinels_unreachable_by_quantize() {
-        for i in 0..=255_u8 {
-            let frac = i as f32 / 255.0;
-            let bucket = quantize_subpixel(frac);
-            assert!(bucket < SUBPIXEL_BUCKETS, "bucket {bucket} for frac {frac}");
+        for buckets in [1, DEFAULT_SUBPIXEL_BUCKETS, MAX_SUBPIXEL_BUCKETS, u8::MAX] {
+            for i in 0..=255_u8 {
+                let frac = i as f32 / 255.0;
+                let bucket = quantize_subpixel(frac, buckets);
+                assert!(bucket < SUBPIXEL_COLR, "bucket {bucket} for frac {frac}");
+            }
         }
     }
 
@@ -340,8 +455,10 @@ mod tests {
             0,
             42,
             16.0,
-            true,
+            Some(HintingEngine::AutoFallback),
+            None,
             0.3,
+            DEFAULT_SUBPIXEL_BUCKETS,
             BLACK,
             packed,
             FontEmbolden::default(),
@@ -352,8 +469,10 @@ mod tests {
             0,
             42,
             16.0,
-            true,
+            Some(HintingEngine::AutoFallback),
+            None,
             0.3,
+            DEFAULT_SUBPIXEL_BUCKETS,
             BLACK,
             packed,
             FontEmbolden::default(),
@@ -12,7 +12,7 @@ only break in edge cases, and some of them are also only related to conversions
 use crate::Pixmap;
 use crate::atlas::AtlasSlot;
 use crate::atlas::GlyphCacheKey;
-use crate::atlas::key::{SUBPIXEL_BITMAP, SUBPIXEL_COLR, pack_color};
+use crate::atlas::key::{SUBPIXEL_BITMAP, SUBPIXEL_COLR, pack_color, quantize_var_coords};
 use crate::atlas::{GlyphAtlas, ImageCache};
 use crate::color::PremulRgba8;
 use crate::color::palette::css::BLACK;
@@ -56,6 +56,38 @@ pub struct Glyph {
     pub y: f32,
 }
 
+/// A kind of text decoration line.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DecorationLine {
+    /// A line below the baseline.
+    Underline,
+    /// A line through the middle of lowercase letters.
+    Strikethrough,
+    /// A line above the ascent of the font.
+    Overline,
+}
+
+impl DecorationLine {
+    /// Whether the line is interrupted where it would overlap with glyph outlines.
+    fn skips_ink(self) -> bool {
+        match self {
+            Self::Underline | Self::Overline => true,
+            Self::Strikethrough => false,
+        }
+    }
+}
+
+/// The position and thickness of a text decoration line, in the coordinate space of the
+/// glyph run.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DecorationMetrics {
+    /// The distance of the top of the line above the baseline. Negative values are below the
+    /// baseline.
+    pub offset: f32,
+    /// The thickness of the line.
+    pub thickness: f32,
+}
+
 /// Synthetic embolden settings for a glyph run.
 #[derive(Clone, Copy, Debug)]
 pub struct FontEmbolden {
@@ -133,8 +165,9 @@ pub(crate) enum GlyphType<'a> {
 /// Used when rendering directly from the atlas cache to skip glyph preparation.
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum CachedGlyphType {
-    /// An outline glyph cached in the atlas.
-    Outline,
+    /// An outline glyph cached in the atlas, with the subpixel order of its
+    /// coverage if it is subpixel antialiased.
+    Outline(Option<SubpixelOrder>),
     /// A bitmap glyph cached in the atlas.
     Bitmap,
     /// A COLR glyph cached in the atlas.
@@ -218,6 +251,8 @@ pub struct GlyphPrepCache {
     pub(crate) hinting_cache: HintCache,
     /// Horizontal spans excluded from "ink-skipping" underlines.
     pub(crate) underline_exclusions: Vec<(f64, f64)>,
+    /// Quantized variation coordinates of the current glyph run.
+    pub(crate) var_coords: Vec<skrifa::instance::NormalizedCoord>,
 }
 
 impl GlyphPrepCache {
@@ -227,6 +262,7 @@ impl GlyphPrepCache {
             outline_cache: &mut self.outline_cache,
             hinting_cache: &mut self.hinting_cache,
             underline_exclusions: &mut self.underline_exclusions,
+            var_coords: &mut self.var_coords,
         }
     }
 
@@ -235,6 +271,7 @@ impl GlyphPrepCache {
         self.outline_cache.clear();
         self.hinting_cache.clear();
         self.underline_exclusions.clear();
+        self.var_coords.clear();
     }
 
     /// Maintain the glyph preparation caches.
@@ -252,6 +289,8 @@ pub struct GlyphPrepCacheMut<'a> {
     pub(crate) hinting_cache: &'a mut HintCache,
     /// Horizontal spans excluded from "ink-skipping" underlines.
     pub(crate) underline_exclusions: &'a mut Vec<(f64, f64)>,
+    /// Quantized variation coordinates of the current glyph run.
+    pub(crate) var_coords: &'a mut Vec<skrifa::instance::NormalizedCoord>,
 }
 
 /// Determines whether atlas-backed glyph caching is available for a draw.
@@ -354,17 +393,22 @@ impl<'a, 'b, Glyphs: Iterator<Item = Glyph> + Clone> GlyphRunRenderer<'a, 'b, Gl
             font_embolden,
             normalized_coords,
             hinting_instance,
+            hinting_engine,
+            subpixel_order,
             ..
         } = self.prepared_run;
 
         let font_id = self.prepared_run.font.data.id();
         let font_index = self.prepared_run.font.index;
-        let hinted = hinting_instance.is_some();
 
         let colr_bitmap_cache_enabled = self
             .atlas_cacher
             .config()
             .is_some_and(|config| draw_props.font_size <= config.max_cached_font_size);
+        let subpixel_buckets = self
+            .atlas_cacher
+            .config()
+            .map_or(1, |config| config.subpixel_buckets);
         let outline_cache_enabled = colr_bitmap_cache_enabled
             // Due to the various parameters that would need to be considered in the cache key,
             // we never cache stroked outlines for now. For COLR and bitmap, this doesn't matter
@@ -373,8 +417,12 @@ impl<'a, 'b, Glyphs: Iterator<Item = Glyph> + Clone> GlyphRunRenderer<'a, 'b, Gl
 
         let context_color = renderer.get_context_color();
         let context_color_packed = pack_color(context_color);
-        let scale_props =
-            GlyphScaleProperties::new(draw_props.font_size, self.prepared_run.upem, hinted, style);
+        let scale_props = GlyphScaleProperties::new(
+            draw_props.font_size,
+            self.prepared_run.upem,
+            hinting_instance.is_some(),
+            style,
+        );
 
         for glyph in self.glyph_iterator.clone() {
             // TODO: Add a mechanism such that glyphs that are completely outside of the viewport
@@ -396,8 +444,10 @@ impl<'a, 'b, Glyphs: Iterator<Item = Glyph> + Clone> GlyphRunRenderer<'a, 'b, Gl
                     font_index,
                     glyph.id,
                     draw_props.font_size,
-                    hinted,
+                    hinting_engine,
+                    subpixel_order,
                     fractional_x,
+                    subpixel_buckets,
                     BLACK,
                     BLACK_PACKED,
                     font_embolden,
@@ -411,7 +461,7 @@ impl<'a, 'b, Glyphs: Iterator<Item = Glyph> + Clone> GlyphRunRenderer<'a, 'b, Gl
                     renderer,
                     cached_slot,
                     outline_transform,
-                    CachedGlyphType::Outline,
+                    CachedGlyphType::Outline(subpixel_order),
                 );
                 continue;
             }
@@ -437,7 +487,8 @@ impl<'a, 'b, Glyphs: Iterator<Item = Glyph> + Clone> GlyphRunRenderer<'a, 'b, Gl
                     font_index,
                     glyph_id: glyph.id,
                     size_bits: draw_props.font_size.to_bits(),
-                    hinted: false,
+                    hinting: None,
+                    subpixel_order: None,
                     subpixel_x: SUBPIXEL_COLR,
                     context_color,
                     context_color_packed,
@@ -521,7 +572,8 @@ impl<'a, 'b, Glyphs: Iterator<Item = Glyph> + Clone> GlyphRunRenderer<'a, 'b, Gl
                     font_index,
                     glyph_id: glyph.id,
                     size_bits: bitmap_ppem.to_bits(),
-                    hinted: false,
+                    hinting: None,
+                    subpixel_order: None,
                     subpixel_x: SUBPIXEL_BITMAP,
                     context_color: BLACK,
                     context_color_packed: BLACK_PACKED,
@@ -575,6 +627,7 @@ impl<'a, 'b, Glyphs: Iterator<Item = Glyph> + Clone> GlyphRunRenderer<'a, 'b, Gl
                 font_embolden,
                 &outline,
                 hinting_instance,
+                hinting_engine,
                 normalized_coords,
             );
 
@@ -640,6 +693,7 @@ impl<'a, 'b, Glyphs: Iterator<Item = Glyph> + Clone> GlyphRunRenderer<'a, 'b, Gl
             draw_props,
             font_embolden,
             hinting_instance,
+            hinting_engine,
             ..
         } = self.prepared_run;
 
@@ -701,6 +755,7 @@ impl<'a, 'b, Glyphs: Iterator<Item = Glyph> + Clone> GlyphRunRenderer<'a, 'b, Gl
                 var_key,
                 &outline,
                 hinting_instance,
+                hinting_engine,
             );
 
             // If the glyph's bounding box doesn't intersect the underline at all, we don't need to calculate
@@ -791,6 +846,8 @@ impl<'a, B> GlyphRunBuilder<'a, B> {
                 transform,
                 glyph_transform: None,
                 hint: true,
+                hinting_engine: HintingEngine::default(),
+                subpixel_order: None,
                 normalized_coords: &[],
             },
             backend,
@@ -825,14 +882,120 @@ impl<'a, B> GlyphRunBuilder<'a, B> {
         self
     }
 
+    /// Set the hinter used if hinting is enabled.
+    ///
+    /// Hinted glyphs are cached separately per engine.
+    pub fn hinting_engine(mut self, engine: HintingEngine) -> Self {
+        self.run.hinting_engine = engine;
+        self
+    }
+
+    /// Set the subpixel order of the display, to draw the glyphs with subpixel antialiasing.
+    ///
+    /// With `Some`, each color channel of a glyph gets the coverage of its own subpixel,
+    /// which is sharper than grayscale antialiasing on standard-DPI displays. This is only
+    /// correct on an opaque backdrop, and only applies to filled outline glyphs that are
+    /// drawn from the glyph atlas. All other glyphs use grayscale antialiasing.
+    pub fn subpixel_order(mut self, order: Option<SubpixelOrder>) -> Self {
+        self.run.subpixel_order = order;
+        self
+    }
+
     /// Set normalized variation coordinates for variable fonts.
     pub fn normalized_coords(mut self, coords: &'a [NormalizedCoord]) -> Self {
         self.run.normalized_coords = bytemuck::cast_slice(coords);
         self
     }
+
+    /// Returns the position and thickness of a decoration line for the current settings.
+    ///
+    /// See [`GlyphRun::decoration_metrics`].
+    pub fn decoration_metrics(&self, line: DecorationLine) -> DecorationMetrics {
+        self.run.decoration_metrics(line)
+    }
 }
 
 impl<'a> GlyphRun<'a> {
+    /// Returns the position and thickness of a decoration line, as suggested by the font.
+    ///
+    /// Fonts without the relevant metrics fall back to a thickness of 1/20 em, an underline
+    /// 1/10 em below the baseline and a strikethrough centered at half the x-height.
+    pub fn decoration_metrics(&self, line: DecorationLine) -> DecorationMetrics {
+        let metrics = self.font.as_skrifa().metrics(
+            Size::new(self.font_size),
+            LocationRef::new(self.normalized_coords),
+        );
+        let fallback_thickness = self.font_size / 20.0;
+        let thickness = |decoration: Option<skrifa::metrics::Decoration>| {
+            decoration
+                .map(|d| d.thickness)
+                .filter(|thickness| *thickness > 0.0)
+                .unwrap_or(fallback_thickness)
+        };
+
+        match line {
+            DecorationLine::Underline => DecorationMetrics {
+                offset: metrics
+                    .underline
+                    .map_or(-self.font_size / 10.0, |d| d.offset),
+                thickness: thickness(metrics.underline),
+            },
+            DecorationLine::Strikethrough => {
+                let thickness = thickness(metrics.strikeout);
+                let offset = metrics.strikeout.map_or_else(
+                    || {
+                        let x_height = metrics.x_height.unwrap_or(self.font_size / 2.0);
+                        (x_height + thickness) / 2.0
+                    },
+                    |d| d.offset,
+                );
+                DecorationMetrics { offset, thickness }
+            }
+            DecorationLine::Overline => DecorationMetrics {
+                offset: metrics.ascent,
+                thickness: thickness(metrics.underline),
+            },
+        }
+    }
+
+    /// Returns the device-space box of each glyph, for example for accessibility purposes.
+    ///
+    /// The box of a glyph spans its advance horizontally and the ascent and descent of the
+    /// font vertically. Its corners are returned in the order top-left, top-right,
+    /// bottom-right and bottom-left, before applying the run and glyph transforms.
+    pub fn glyph_quads(
+        &self,
+        glyphs: impl Iterator<Item = Glyph>,
+    ) -> impl Iterator<Item = [Point; 4]> {
+        let font_ref = self.font.as_skrifa();
+        let size = Size::new(self.font_size);
+        let location = LocationRef::new(self.normalized_coords);
+        let metrics = font_ref.metrics(size, location);
+        let glyph_metrics = font_ref.glyph_metrics(size, location);
+        // Font metrics are y-up, while glyphs are drawn y-down.
+        let top = -f64::from(metrics.ascent);
+        let bottom = -f64::from(metrics.descent);
+        let glyph_transform = self.glyph_transform.unwrap_or(Affine::IDENTITY);
+        let run_transform = self.transform;
+
+        glyphs.map(move |glyph| {
+            let advance = glyph_metrics
+                .advance_width(GlyphId::new(glyph.id))
+                .map(f64::from)
+                .unwrap_or_default();
+            let transform = run_transform
+                * Affine::translate((f64::from(glyph.x), f64::from(glyph.y)))
+                * glyph_transform;
+
+            [
+                transform * Point::new(0.0, top),
+                transform * Point::new(advance, top),
+                transform * Point::new(advance, bottom),
+                transform * Point::new(0.0, bottom),
+            ]
+        })
+    }
+
     // Note: Not sure if we should just remove that method and let each backend
     // call `prepare_glyph_run` manually, it might allow us to reduce the number of
     // generics we need to use. But for now, it seems nice to be able to abstract away
@@ -845,7 +1008,17 @@ impl<'a> GlyphRun<'a> {
         prep_cache: GlyphPrepCacheMut<'b>,
         atlas_cacher: AtlasCacher<'b>,
     ) -> GlyphRunRenderer<'a, 'b, Glyphs> {
-        let prepared_run = prepare_glyph_run(self, prep_cache.hinting_cache);
+        let mut run = self;
+        // Quantize variation coordinates before preparing the run, so that hinting instances,
+        // outlines and atlas entries are all shared between nearby coordinates.
+        if let Some(config) = atlas_cacher.config()
+            && !run.normalized_coords.is_empty()
+        {
+            let var_coords = prep_cache.var_coords;
+            quantize_var_coords(run.normalized_coords, config.variation_steps, var_coords);
+            run.normalized_coords = var_coords;
+        }
+        let prepared_run = prepare_glyph_run(run, prep_cache.hinting_cache);
         GlyphRunRenderer {
             prepared_run,
             glyph_iterator: glyphs,
@@ -903,6 +1076,36 @@ where
         let GlyphRunBuilder { run, backend } = self;
         backend.render_decoration(run, glyphs, x_range, baseline_y, offset, size, buffer);
     }
+
+    /// Render a decoration line at the position and thickness suggested by the font.
+    ///
+    /// Underlines and overlines skip over the glyphs they would overlap with, leaving a gap
+    /// as wide as the line is thick. Strikethroughs are drawn uninterrupted.
+    ///
+    /// See [`GlyphRun::decoration_metrics`].
+    pub fn render_decoration_line<Glyphs>(
+        self,
+        glyphs: Glyphs,
+        line: DecorationLine,
+        x_range: RangeInclusive<f32>,
+        baseline_y: f32,
+    ) where
+        Glyphs: Iterator<Item = Glyph> + Clone,
+    {
+        let DecorationMetrics { offset, thickness } = self.run.decoration_metrics(line);
+        if line.skips_ink() {
+            self.render_decoration(glyphs, x_range, baseline_y, offset, thickness, thickness);
+        } else {
+            self.render_decoration(
+                core::iter::empty(),
+                x_range,
+                baseline_y,
+                offset,
+                thickness,
+                0.0,
+            );
+        }
+    }
 }
 
 /// Insert a range into a sorted list, merging with any overlapping ranges.
@@ -1026,6 +1229,7 @@ fn create_outline_glyph<'a>(
     embolden: FontEmbolden,
     outline_glyph: &skrifa::outline::OutlineGlyph<'a>,
     hinting_instance: Option<&HintingInstance>,
+    hinting_engine: Option<HintingEngine>,
     normalized_coords: &[skrifa::instance::NormalizedCoord],
 ) -> GlyphType<'a> {
     let cached = outline_cache.get_or_insert(
@@ -1037,6 +1241,7 @@ fn create_outline_glyph<'a>(
         VarLookupKey(normalized_coords),
         outline_glyph,
         hinting_instance,
+        hinting_engine,
     );
 
     GlyphType::Outline(GlyphOutline {
@@ -1365,6 +1570,10 @@ pub struct GlyphRun<'a> {
     normalized_coords: &'a [skrifa::instance::NormalizedCoord],
     /// Controls whether font hinting is enabled.
     hint: bool,
+    /// The hinter used if hinting is enabled.
+    hinting_engine: HintingEngine,
+    /// The subpixel order used for subpixel antialiasing, if enabled.
+    subpixel_order: Option<SubpixelOrder>,
 }
 
 struct PreparedGlyphRun<'a> {
@@ -1404,6 +1613,9 @@ struct PreparedGlyphRun<'a> {
     draw_props: DrawProps,
     normalized_coords: &'a [skrifa::instance::NormalizedCoord],
     hinting_instance: Option<&'a HintingInstance>,
+    /// The engine of `hinting_instance`, if the run is hinted.
+    hinting_engine: Option<HintingEngine>,
+    subpixel_order: Option<SubpixelOrder>,
 }
 
 /// Properties for easily calculating the transform of a positioned glyph.
@@ -1453,6 +1665,8 @@ impl Debug for PreparedGlyphRun<'_> {
             .field("glyph_transform", &self.glyph_transform)
             .field("transforms", &self.draw_props)
             .field("normalized_coords", &self.normalized_coords)
+            .field("hinting_engine", &self.hinting_engine)
+            .field("subpixel_order", &self.subpixel_order)
             .finish()
     }
 }
@@ -1521,6 +1735,7 @@ fn prepare_glyph_run<'a>(run: GlyphRun<'a>, hint_cache: &'a mut HintCache) -> Pr
                 outlines: &outlines,
                 size: vertical_font_size,
                 coords: run.normalized_coords,
+                engine: run.hinting_engine,
             });
 
             (
@@ -1559,20 +1774,54 @@ fn prepare_glyph_run<'a>(run: GlyphRun<'a>, hint_cache: &'a mut HintCache) -> Pr
             font_size: draw_font_size,
         },
         normalized_coords: run.normalized_coords,
+        hinting_engine: hinting_instance.map(|_| run.hinting_engine),
         hinting_instance,
+        subpixel_order: run.subpixel_order,
     }
 }
 
-// TODO: Although these are sane defaults, we might want to make them
-// configurable.
-const HINTING_OPTIONS: HintingOptions = HintingOptions {
-    engine: skrifa::outline::Engine::AutoFallback,
-    target: skrifa::outline::Target::Smooth {
-        mode: skrifa::outline::SmoothMode::Lcd,
-        symmetric_rendering: false,
-        preserve_linear_metrics: true,
-    },
-};
+/// The hinter used for hinted glyph runs.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum HintingEngine {
+    /// Use the hints of the font if it has any, and the autohinter otherwise.
+    #[default]
+    AutoFallback,
+    /// Always use the autohinter, ignoring the hints of the font.
+    Autohint,
+    /// Use the hints of the font, i.e. TrueType bytecode or CFF hints. Fonts without hints
+    /// are drawn unhinted.
+    Bytecode,
+}
+
+impl HintingEngine {
+    fn options(self) -> HintingOptions {
+        let engine = match self {
+            Self::AutoFallback => skrifa::outline::Engine::AutoFallback,
+            Self::Autohint => skrifa::outline::Engine::Auto(None),
+            Self::Bytecode => skrifa::outline::Engine::Interpreter,
+        };
+
+        // TODO: Although this is a sane default, we might want to make the target
+        // configurable as well.
+        HintingOptions {
+            engine,
+            target: skrifa::outline::Target::Smooth {
+                mode: skrifa::outline::SmoothMode::Lcd,
+                symmetric_rendering: false,
+                preserve_linear_metrics: true,
+            },
+        }
+    }
+}
+
+/// The order of the color subpixels of a display, used for subpixel antialiasing.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SubpixelOrder {
+    /// Red, green and blue subpixels from left to right.
+    Rgb,
+    /// Blue, green and red subpixels from left to right.
+    Bgr,
+}
 
 #[derive(Clone, Default)]
 pub(crate) struct OutlinePath {
@@ -1680,7 +1929,7 @@ struct OutlineKey {
     embolden_join_bits: u8,
     embolden_miter_limit_bits: u32,
     embolden_tolerance_bits: u32,
-    hint: bool,
+    hint: Option<HintingEngine>,
 }
 
 #[inline(always)]
@@ -1857,6 +2106,7 @@ impl<'a> OutlineCacheSession<'a> {
         var_key: VarLookupKey<'_>,
         outline_glyph: &skrifa::outline::OutlineGlyph<'_>,
         hinting_instance: Option<&HintingInstance>,
+        hinting_engine: Option<HintingEngine>,
     ) -> CachedOutline<'_> {
         let key = OutlineKey {
             glyph_id,
@@ -1868,7 +2118,7 @@ impl<'a> OutlineCacheSession<'a> {
             embolden_join_bits: join_bits(embolden.join),
             embolden_miter_limit_bits: f32_bits(embolden.miter_limit),
             embolden_tolerance_bits: f32_bits(embolden.tolerance),
-            hint: hinting_instance.is_some(),
+            hint: hinting_engine,
         };
 
         match self.map.entry(key) {
@@ -1950,6 +2200,7 @@ pub struct HintKey<'a> {
     outlines: &'a OutlineGlyphCollection<'a>,
     size: f32,
     coords: &'a [skrifa::instance::NormalizedCoord],
+    engine: HintingEngine,
 }
 
 impl HintKey<'_> {
@@ -1958,7 +2209,7 @@ impl HintKey<'_> {
             self.outlines,
             Size::new(self.size),
             self.coords,
-            HINTING_OPTIONS,
+            self.engine.options(),
         )
         .ok()
     }
@@ -2003,13 +2254,14 @@ impl HintCache {
         if !is_current {
             entry.font_id = key.font_id;
             entry.font_index = key.font_index;
+            entry.engine = key.engine;
             entry
                 .instance
                 .reconfigure(
                     key.outlines,
                     Size::new(key.size),
                     key.coords,
-                    HINTING_OPTIONS,
+                    key.engine.options(),
                 )
                 .ok()?;
         }
@@ -2028,6 +2280,7 @@ impl HintCache {
 struct HintEntry {
     font_id: u64,
     font_index: u32,
+    engine: HintingEngine,
     instance: HintingInstance,
     serial: u64,
 }
@@ -2038,6 +2291,7 @@ fn find_hint_entry(entries: &mut Vec<HintEntry>, key: &HintKey<'_>) -> Option<(u
     for (ix, entry) in entries.iter().enumerate() {
         if entry.font_id == key.font_id
             && entry.font_index == key.font_index
+            && entry.engine == key.engine
             && entry.instance.size() == Size::new(key.size)
             && entry.instance.location().coords() == key.coords
         {
@@ -2054,6 +2308,7 @@ fn find_hint_entry(entries: &mut Vec<HintEntry>, key: &HintKey<'_>) -> Option<(u
         entries.push(HintEntry {
             font_id: key.font_id,
             font_index: key.font_index,
+            engine: key.engine,
             instance,
             // This should be updated by the caller.
             serial: 0,
@@ -2230,6 +2485,8 @@ mod tests {
             glyph_transform: None,
             normalized_coords: &[],
             hint: false,
+            hinting_engine: HintingEngine::default(),
+            subpixel_order: None,
         }
         .build(
             core::iter::once(glyph),
@@ -2304,6 +2561,45 @@ mod tests {
         ensure_no_cache(TestGlyphKind::Outline, Style::Stroke, false);
     }
 
+    #[test]
+    fn hinting_engines_are_cached_separately() {
+        let font = test_font(TestGlyphKind::Outline);
+        let glyph = test_glyph(&font, TestGlyphKind::Outline);
+        let mut resources = TestResources::default();
+
+        for (hint, hinting_engine) in [
+            (false, HintingEngine::AutoFallback),
+            (true, HintingEngine::AutoFallback),
+            (true, HintingEngine::Autohint),
+            (true, HintingEngine::Bytecode),
+            // Without hinting, the engine doesn't matter.
+            (false, HintingEngine::Autohint),
+        ] {
+            let atlas_cacher =
+                AtlasCacher::Enabled(&mut resources.glyph_atlas, &mut resources.image_cache);
+            GlyphRun {
+                font: font.clone(),
+                font_size: 12.0,
+                font_embolden: FontEmbolden::default(),
+                transform: Affine::translate((0.0, 20.0)),
+                glyph_transform: None,
+                normalized_coords: &[],
+                hint,
+                hinting_engine,
+                subpixel_order: None,
+            }
+            .build(
+                core::iter::once(glyph),
+                resources.prep_cache.as_mut(),
+                atlas_cacher,
+            )
+            .fill_glyphs(&mut resources.renderer);
+        }
+
+        assert_eq!(resources.glyph_atlas.len(), 4);
+        assert_eq!(resources.glyph_atlas.cache_hits(), 1);
+    }
+
     #[test]
     fn colr_glyph_is_cached_when_atlas_cache_is_enabled() {
         ensure_cache(TestGlyphKind::Colr, Style::Fill);
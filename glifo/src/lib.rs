@@ -59,8 +59,9 @@ pub use atlas::{
     GlyphAtlas, GlyphCacheConfig, GlyphCacheKey, ImageCache, PendingClearRect, RasterMetrics,
 };
 pub use glyph::{
-    AtlasCacher, FontEmbolden, Glyph, GlyphCaches, GlyphColr, GlyphPrepCache, GlyphPrepCacheMut,
-    GlyphRun, GlyphRunBackend, GlyphRunBuilder, GlyphRunRenderer, HintCache, HintKey,
-    NormalizedCoord, OutlineCache,
+    AtlasCacher, DecorationLine, DecorationMetrics, FontEmbolden, Glyph, GlyphCaches, GlyphColr,
+    GlyphPrepCache, GlyphPrepCacheMut, GlyphRun, GlyphRunBackend, GlyphRunBuilder,
+    GlyphRunRenderer, HintCache, HintKey, HintingEngine, NormalizedCoord, OutlineCache,
+    SubpixelOrder,
 };
 pub use interface::{DrawSink, GlyphRenderer};
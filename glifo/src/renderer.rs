@@ -9,6 +9,7 @@ use crate::atlas::{AtlasSlot, GlyphAtlas, GlyphCacheKey, ImageCache, RasterMetri
 use crate::colr::ColrPainter;
 use crate::glyph::{
     AtlasCacher, CachedGlyphType, GlyphBitmap, GlyphColr, GlyphOutline, GlyphType, PreparedGlyph,
+    SubpixelOrder,
 };
 use crate::interface::{DrawSink, GlyphRenderer};
 use crate::util::AffineExt;
@@ -18,9 +19,9 @@ use alloc::vec::Vec;
 #[cfg(not(feature = "std"))]
 use core_maths::CoreFloat as _;
 use kurbo::{Affine, BezPath, Rect, Shape};
-use peniko::color::palette::css::BLACK;
+use peniko::color::palette::css::{BLACK, BLUE, LIME, RED};
 use peniko::color::{AlphaColor, Srgb};
-use peniko::{Extend, ImageQuality, ImageSampler};
+use peniko::{BlendMode, Compose, Extend, ImageQuality, ImageSampler, Mix};
 use vello_common::paint::{Image, ImageSource, Tint, TintMode};
 
 /// Outcome of a cache-first render attempt.
@@ -251,9 +252,9 @@ pub(crate) fn render_cached_glyph(
     glyph_type: CachedGlyphType,
 ) {
     match glyph_type {
-        CachedGlyphType::Outline => {
+        CachedGlyphType::Outline(subpixel_order) => {
             let tint = renderer.get_context_color();
-            render_outline_glyph_from_atlas(renderer, cached_slot, transform, tint);
+            render_outline_glyph_from_atlas(renderer, cached_slot, transform, tint, subpixel_order);
         }
         CachedGlyphType::Bitmap => {
             render_bitmap_glyph_from_atlas(renderer, cached_slot, transform);
@@ -300,6 +301,7 @@ fn render_outline_to_atlas(
     path: &Arc<BezPath>,
     scale: f64,
     subpixel_offset: f32,
+    subpixel_order: Option<SubpixelOrder>,
     recorder: &mut AtlasCommandRecorder,
     atlas_slot: AtlasSlot,
     raster_metrics: RasterMetrics,
@@ -309,9 +311,48 @@ fn render_outline_to_atlas(
             atlas_slot.x as f64 - raster_metrics.bearing_x as f64 + subpixel_offset as f64,
             atlas_slot.y as f64 - raster_metrics.bearing_y as f64,
         ));
-    recorder.set_transform(outline_transform);
-    recorder.set_paint(BLACK.into());
-    recorder.fill_path(path);
+
+    let Some(subpixel_order) = subpixel_order else {
+        recorder.set_transform(outline_transform);
+        recorder.set_paint(BLACK.into());
+        recorder.fill_path(path);
+        return;
+    };
+
+    // Each color channel gets the coverage of a pixel-wide box centered on its subpixel,
+    // which is the same as rasterizing at three times the horizontal resolution and
+    // filtering with a box of three samples. The channels are added up in an isolated
+    // layer, so that they don't cover each other.
+    let slot_rect = Rect::new(
+        atlas_slot.x as f64,
+        atlas_slot.y as f64,
+        atlas_slot.x as f64 + atlas_slot.width as f64,
+        atlas_slot.y as f64 + atlas_slot.height as f64,
+    );
+    recorder.set_transform(Affine::IDENTITY);
+    recorder.push_clip_layer(&slot_rect.to_path(0.1));
+    for (shift, channel) in subpixel_channels(subpixel_order) {
+        recorder.set_transform(outline_transform.then_translate(kurbo::Vec2::new(shift, 0.0)));
+        recorder.push_blend_layer(BlendMode::new(Mix::Normal, Compose::Plus));
+        recorder.set_paint(channel.into());
+        recorder
+            .commands
+            .push(AtlasCommand::FillPath(Arc::clone(path)));
+        recorder.pop_layer();
+    }
+    recorder.pop_layer();
+}
+
+/// Return the horizontal shift of the outline and the channel color for each subpixel.
+///
+/// Shifting the outline right by a third of a pixel samples the coverage of the subpixel
+/// on the left.
+fn subpixel_channels(order: SubpixelOrder) -> [(f64, AlphaColor<Srgb>); 3] {
+    let (left, right) = match order {
+        SubpixelOrder::Rgb => (RED, BLUE),
+        SubpixelOrder::Bgr => (BLUE, RED),
+    };
+    [(1.0 / 3.0, left), (0.0, LIME), (-1.0 / 3.0, right)]
 }
 
 /// Record COLR glyph draw commands into the atlas command recorder.
@@ -358,14 +399,21 @@ fn insert_and_render_outline(
     image_cache: &mut ImageCache,
     tint_color: AlphaColor<Srgb>,
 ) -> CacheResult {
-    if !supports_atlas_caching(&transform, CachedGlyphType::Outline) {
+    if !supports_atlas_caching(&transform, CachedGlyphType::Outline(None)) {
         return CacheResult::UnsupportedTransform;
     }
 
     let bounds = glyph.bbox.scale_from_origin(glyph.scale);
-    let raster_metrics = calculate_raster_metrics(&bounds);
+    let mut raster_metrics = calculate_raster_metrics(&bounds);
+    let subpixel_order = cache_key.subpixel_order;
+    if subpixel_order.is_some() {
+        // The channels are shifted by up to a third of a pixel in both directions.
+        raster_metrics.bearing_x -= 1;
+        raster_metrics.width += 2;
+    }
 
-    let subpixel_offset = subpixel_offset(cache_key.subpixel_x);
+    let subpixel_offset =
+        subpixel_offset(cache_key.subpixel_x, glyph_atlas.config().subpixel_buckets);
 
     let Some((atlas_slot, recorder)) = glyph_atlas.insert(image_cache, cache_key, raster_metrics)
     else {
@@ -376,12 +424,13 @@ fn insert_and_render_outline(
         &glyph.path,
         glyph.scale,
         subpixel_offset,
+        subpixel_order,
         recorder,
         atlas_slot,
         raster_metrics,
     );
 
-    render_outline_glyph_from_atlas(renderer, atlas_slot, transform, tint_color);
+    render_outline_glyph_from_atlas(renderer, atlas_slot, transform, tint_color, subpixel_order);
     CacheResult::CachedAndRendered
 }
 
@@ -478,6 +527,7 @@ fn render_outline_glyph_from_atlas(
     atlas_slot: AtlasSlot,
     transform: Affine,
     tint_color: AlphaColor<Srgb>,
+    subpixel_order: Option<SubpixelOrder>,
 ) {
     let [_, _, _, _, tx, ty] = transform.as_coeffs();
     let rect_transform = Affine::translate((
@@ -485,17 +535,34 @@ fn render_outline_glyph_from_atlas(
         ty.floor() + atlas_slot.bearing_y as f64,
     ));
     let area = Rect::new(0.0, 0.0, atlas_slot.width as f64, atlas_slot.height as f64);
-    render_from_atlas(
-        renderer,
-        atlas_slot,
-        rect_transform,
-        area,
-        ImageQuality::Low,
-        Some(Tint {
-            color: tint_color,
-            mode: TintMode::AlphaMask,
-        }),
-    );
+    let render = |renderer: &mut _, mode| {
+        render_from_atlas(
+            renderer,
+            atlas_slot,
+            rect_transform,
+            area,
+            ImageQuality::Low,
+            Some(Tint {
+                color: tint_color,
+                mode,
+            }),
+        );
+    };
+
+    if subpixel_order.is_none() {
+        render(renderer, TintMode::AlphaMask);
+        return;
+    }
+
+    // Subpixel coverage needs a separate alpha per color channel, which source-over can't
+    // express. Instead, first remove the covered part of each channel from the backdrop,
+    // then add the tint color weighted by the coverage of each channel.
+    renderer.push_blend_layer(BlendMode::new(Mix::Multiply, Compose::SrcOver));
+    render(renderer, TintMode::InverseCoverage);
+    renderer.pop_layer();
+    renderer.push_blend_layer(BlendMode::new(Mix::Normal, Compose::Plus));
+    render(renderer, TintMode::Multiply);
+    renderer.pop_layer();
 }
 
 /// Render a bitmap glyph from the atlas cache.
@@ -628,7 +695,7 @@ pub(crate) fn supports_atlas_caching(transform: &Affine, glyph_type: CachedGlyph
     match glyph_type {
         // For those glyphs, we expect any scaling factor to have been completely absorbed. Due to the fact
         // that we had to apply a flip transform for outlines, the y-scaling factor is expected to be negative.
-        CachedGlyphType::Outline | CachedGlyphType::Colr(_) => {
+        CachedGlyphType::Outline(_) | CachedGlyphType::Colr(_) => {
             !transform.has_non_unit_skew_or_scale() && a.is_sign_positive() && d.is_sign_negative()
         }
         // For bitmap glyphs, we need to relax the condition a bit, since bitmap glyphs already have a fixed
@@ -667,7 +734,7 @@ mod tests {
         ));
         assert!(!supports_atlas_caching(
             &Affine::skew(0.2, 0.0),
-            CachedGlyphType::Outline
+            CachedGlyphType::Outline(None)
         ));
         assert!(!supports_atlas_caching(
             &Affine::skew(0.2, 0.0),
@@ -679,11 +746,11 @@ mod tests {
     fn outline_and_colr_reject_non_unit_scales() {
         assert!(!supports_atlas_caching(
             &Affine::scale(2.0),
-            CachedGlyphType::Outline
+            CachedGlyphType::Outline(None)
         ));
         assert!(!supports_atlas_caching(
             &Affine::scale_non_uniform(1.0, -0.5),
-            CachedGlyphType::Outline
+            CachedGlyphType::Outline(None)
         ));
         assert!(!supports_atlas_caching(
             &Affine::scale(2.0),
@@ -695,7 +762,7 @@ mod tests {
     fn outline_and_colr_requires_negative_y_and_positive_x() {
         assert!(supports_atlas_caching(
             &Affine::scale_non_uniform(1.0, -1.0),
-            CachedGlyphType::Outline
+            CachedGlyphType::Outline(None)
         ));
         assert!(supports_atlas_caching(
             &Affine::scale_non_uniform(1.0, -1.0),
@@ -703,11 +770,11 @@ mod tests {
         ));
         assert!(!supports_atlas_caching(
             &Affine::scale_non_uniform(-1.0, -1.0),
-            CachedGlyphType::Outline
+            CachedGlyphType::Outline(None)
         ));
         assert!(!supports_atlas_caching(
             &Affine::scale_non_uniform(1.0, 1.0),
-            CachedGlyphType::Outline
+            CachedGlyphType::Outline(None)
         ));
     }
 
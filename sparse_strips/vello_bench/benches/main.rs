@@ -27,6 +27,8 @@ criterion_group!(render_rect, strip::render_rect);
 criterion_group!(glyph, glyph::glyph);
 criterion_group!(sort_tiles, sort::sort);
 criterion_group!(integration_bench, integration::images);
+criterion_group!(chart_grid, integration::chart_grid);
+criterion_group!(lod_path, integration::lod_path);
 criterion_main!(
     allocator_bench,
     coarse_bench,
@@ -47,5 +49,7 @@ criterion_main!(
     fine_blend,
     fine_image,
     sort_tiles,
-    integration_bench
+    integration_bench,
+    chart_grid,
+    lod_path
 );
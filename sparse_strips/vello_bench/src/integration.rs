@@ -6,11 +6,13 @@
 use std::sync::Arc;
 
 use criterion::Criterion;
-use vello_common::kurbo::{Affine, Rect};
+use vello_common::chart::Hairlines;
+use vello_common::kurbo::{Affine, BezPath, Point, Rect, Stroke};
 use vello_common::paint::{Image, ImageSource};
 use vello_common::peniko::ImageSampler;
 use vello_common::peniko::{Extend, ImageQuality};
 use vello_common::pixmap::Pixmap;
+use vello_common::simplify::LodPath;
 use vello_cpu::color::AlphaColor;
 use vello_cpu::{RenderContext, Resources};
 
@@ -65,6 +67,84 @@ pub fn images(c: &mut Criterion) {
     g.finish();
 }
 
+/// Chart grid rendering benchmark, comparing pixel-snapped hairlines with stroked paths.
+pub fn chart_grid(c: &mut Criterion) {
+    let mut g = c.benchmark_group("chart_grid");
+
+    const VIEWPORT_WIDTH: u16 = 1280;
+    const VIEWPORT_HEIGHT: u16 = 960;
+    const LINES: u16 = 200;
+
+    // Data coordinates span 0..LINES on both axes, with the y axis pointing upwards.
+    let transform = Affine::new([
+        f64::from(VIEWPORT_WIDTH) / f64::from(LINES + 2),
+        0.0,
+        0.0,
+        -f64::from(VIEWPORT_HEIGHT) / f64::from(LINES + 2),
+        f64::from(VIEWPORT_WIDTH) / f64::from(LINES + 2),
+        f64::from(VIEWPORT_HEIGHT) * f64::from(LINES + 1) / f64::from(LINES + 2),
+    ]);
+    let area = Rect::new(0.0, 0.0, f64::from(LINES), f64::from(LINES));
+    let positions = || (0..=LINES).map(f64::from);
+    let color = AlphaColor::from_rgba8(128, 128, 128, 255);
+
+    g.bench_function("hairlines", |b| {
+        let mut renderer = RenderContext::new(VIEWPORT_WIDTH, VIEWPORT_HEIGHT);
+        let mut resources = Resources::default();
+        let mut pixmap = Pixmap::new(VIEWPORT_WIDTH, VIEWPORT_HEIGHT);
+        let mut lines = Hairlines::new();
+
+        b.iter(|| {
+            renderer.reset();
+
+            lines.clear();
+            lines.push_grid(transform, area, positions(), positions(), 1.0);
+            lines.push_x_ticks(transform, 0.0, positions(), 4.0, 1.0);
+            lines.push_y_ticks(transform, 0.0, positions(), 4.0, 1.0);
+            renderer.set_paint(color);
+            renderer.fill_hairlines(&lines);
+
+            renderer.flush();
+            renderer.render_to_pixmap(&mut resources, &mut pixmap);
+            std::hint::black_box(&pixmap);
+        });
+    });
+
+    g.bench_function("stroke_path", |b| {
+        let mut renderer = RenderContext::new(VIEWPORT_WIDTH, VIEWPORT_HEIGHT);
+        let mut resources = Resources::default();
+        let mut pixmap = Pixmap::new(VIEWPORT_WIDTH, VIEWPORT_HEIGHT);
+
+        b.iter(|| {
+            renderer.reset();
+
+            let mut path = BezPath::new();
+            for p in positions() {
+                path.move_to(transform * Point::new(p, area.y0));
+                path.line_to(transform * Point::new(p, area.y1));
+                path.move_to(transform * Point::new(area.x0, p));
+                path.line_to(transform * Point::new(area.x1, p));
+
+                let x_tick = transform * Point::new(p, 0.0);
+                path.move_to(x_tick);
+                path.line_to(x_tick + (0.0, 4.0));
+                let y_tick = transform * Point::new(0.0, p);
+                path.move_to(y_tick);
+                path.line_to(y_tick - (4.0, 0.0));
+            }
+            renderer.set_paint(color);
+            renderer.set_stroke(Stroke::new(1.0));
+            renderer.stroke_path(&path);
+
+            renderer.flush();
+            renderer.render_to_pixmap(&mut resources, &mut pixmap);
+            std::hint::black_box(&pixmap);
+        });
+    });
+
+    g.finish();
+}
+
 fn load_flower_image() -> ImageSource {
     let image_data = include_bytes!("../../../examples/assets/splash-flower.jpg");
     let image = image::load_from_memory(image_data).expect("Failed to decode image");
@@ -97,3 +177,55 @@ fn load_flower_image() -> ImageSource {
 
     ImageSource::Pixmap(Arc::new(pixmap))
 }
+
+/// Zoomed-out rendering of a detailed path, comparing the full path with its levels of detail.
+pub fn lod_path(c: &mut Criterion) {
+    let mut g = c.benchmark_group("lod_path");
+
+    const VIEWPORT_WIDTH: u16 = 1280;
+    const VIEWPORT_HEIGHT: u16 = 960;
+    const POINTS: u32 = 100_000;
+
+    // A closed outline with a jagged edge, like a coastline, spanning 40000 units.
+    let mut path = BezPath::new();
+    for i in 0..POINTS {
+        let angle = f64::from(i) / f64::from(POINTS) * std::f64::consts::TAU;
+        let radius = 15000.0 + 4000.0 * (7.0 * angle).sin() + 20.0 * f64::from(i % 3);
+        let point = Point::new(
+            20000.0 + radius * angle.cos(),
+            20000.0 + radius * angle.sin(),
+        );
+        if i == 0 {
+            path.move_to(point);
+        } else {
+            path.line_to(point);
+        }
+    }
+    path.close_path();
+    let lod = LodPath::new(path, 1.0);
+    let transform = Affine::scale(f64::from(VIEWPORT_HEIGHT) / 40000.0);
+    let color = AlphaColor::from_rgba8(64, 128, 64, 255);
+
+    let mut render = |name: &str, fill: fn(&mut RenderContext, &LodPath)| {
+        g.bench_function(name, |b| {
+            let mut renderer = RenderContext::new(VIEWPORT_WIDTH, VIEWPORT_HEIGHT);
+            let mut resources = Resources::default();
+            let mut pixmap = Pixmap::new(VIEWPORT_WIDTH, VIEWPORT_HEIGHT);
+
+            b.iter(|| {
+                renderer.reset();
+                renderer.set_transform(transform);
+                renderer.set_paint(color);
+                fill(&mut renderer, &lod);
+                renderer.flush();
+                renderer.render_to_pixmap(&mut resources, &mut pixmap);
+                std::hint::black_box(&pixmap);
+            });
+        });
+    };
+
+    render("full", |renderer, lod| renderer.fill_path(lod.path()));
+    render("lod", |renderer, lod| renderer.fill_lod_path(lod));
+
+    g.finish();
+}
@@ -7,7 +7,7 @@ use vello_common::fearless_simd::Level;
 use vello_common::flatten::Line;
 use vello_common::kurbo::{Affine, Rect, Shape};
 use vello_common::peniko::Fill;
-use vello_common::strip_generator::{StripGenerator, StripStorage};
+use vello_common::strip_generator::{Aliasing, StripGenerator, StripStorage};
 use vello_common::tile::Tiles;
 
 pub fn shift_lines_50_percent(lines: &[Line]) -> Vec<Line> {
@@ -135,7 +135,7 @@ pub fn render_rect(c: &mut Criterion) {
                 rect.to_path(0.1),
                 Fill::NonZero,
                 Affine::IDENTITY,
-                None,
+                Aliasing::default(),
                 &mut storage,
                 None,
             );
@@ -0,0 +1,237 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Exporting the geometry of tagged primitives.
+//!
+//! Platform accessibility APIs need to know where each element of a user interface ended up
+//! on screen, and screen readers highlight the text that is currently being read. Instead of
+//! computing that geometry a second time, embedders can tag the primitives they draw with an
+//! [`AccessTag`]. While a tag is set, the renderer records the device-space bounds of every
+//! primitive it draws into an [`AccessGeometry`], as well as a [quad](Quad) for every glyph of
+//! a glyph run.
+//!
+//! The bounds are those of the geometry itself: they are neither clipped nor do they account
+//! for filter effects.
+//!
+//! The same tags also tell which primitives actually contributed visible pixels to a frame,
+//! which is useful to only load detail for content that isn't hidden behind other content.
+//! The renderers derive this from the coarse rasterization commands that survive culling
+//! against opaque fills, see for example [`Wide::visible_access_tags`].
+//!
+//! [`Wide::visible_access_tags`]: crate::coarse::Wide::visible_access_tags
+
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+
+use crate::gradient_bands::stroke_bounds;
+use crate::kurbo::{Affine, BezPath, Point, Rect, Shape, Stroke};
+
+/// Identifies a primitive, or a group of primitives, in the exported [`AccessGeometry`].
+///
+/// Drawing several primitives with the same tag merges their geometry into a single
+/// [`AccessNode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AccessTag(pub u64);
+
+/// A quadrilateral in device space.
+///
+/// For glyphs, the corners are the top-left, top-right, bottom-right and bottom-left corner
+/// of the glyph box, before transformation. The glyph box spans the advance of the glyph
+/// horizontally and the ascent and descent of the font vertically.
+pub type Quad = [Point; 4];
+
+/// The geometry recorded for a single [`AccessTag`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessNode {
+    tag: AccessTag,
+    bounds: Rect,
+    glyph_quads: Vec<Quad>,
+}
+
+impl AccessNode {
+    /// The tag of the node.
+    pub fn tag(&self) -> AccessTag {
+        self.tag
+    }
+
+    /// The device-space bounds of all primitives drawn with the tag.
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    /// The quads of all glyphs drawn with the tag, in the order they were drawn.
+    pub fn glyph_quads(&self) -> &[Quad] {
+        &self.glyph_quads
+    }
+
+    /// Merge the glyph quads into one quad per cluster.
+    ///
+    /// The renderer doesn't know how glyphs map to clusters, so `clusters` needs to contain the
+    /// cluster index of each glyph (as reported by the shaper), in the same order as
+    /// [`AccessNode::glyph_quads`]. Consecutive glyphs with the same cluster index are merged
+    /// into a quad spanning from the start of the first to the end of the last glyph.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `clusters` doesn't have the same length as [`AccessNode::glyph_quads`].
+    pub fn cluster_quads(&self, clusters: &[u32]) -> Vec<Quad> {
+        assert_eq!(
+            clusters.len(),
+            self.glyph_quads.len(),
+            "expected a cluster index for every glyph"
+        );
+
+        let mut quads: Vec<Quad> = Vec::new();
+        for (i, quad) in self.glyph_quads.iter().enumerate() {
+            match quads.last_mut() {
+                Some(last) if i > 0 && clusters[i - 1] == clusters[i] => {
+                    last[1] = quad[1];
+                    last[2] = quad[2];
+                }
+                _ => quads.push(*quad),
+            }
+        }
+
+        quads
+    }
+}
+
+/// The geometry of all tagged primitives drawn since the last [clear](AccessGeometry::clear).
+#[derive(Debug, Clone, Default)]
+pub struct AccessGeometry {
+    nodes: Vec<AccessNode>,
+    indices: HashMap<AccessTag, usize>,
+}
+
+impl AccessGeometry {
+    /// Create an empty set of geometry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The recorded nodes, in the order in which their tag was first drawn.
+    pub fn nodes(&self) -> &[AccessNode] {
+        &self.nodes
+    }
+
+    /// The node recorded for `tag`, if any primitive was drawn with it.
+    pub fn get(&self, tag: AccessTag) -> Option<&AccessNode> {
+        self.indices.get(&tag).map(|&index| &self.nodes[index])
+    }
+
+    /// Whether no geometry has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Remove all recorded geometry.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.indices.clear();
+    }
+
+    /// Add device-space `bounds` to the node of `tag`.
+    pub fn add_bounds(&mut self, tag: AccessTag, bounds: Rect) {
+        let node = self.node_mut(tag, bounds);
+        node.bounds = node.bounds.union(bounds);
+    }
+
+    /// Add the bounds of `shape`, drawn with `transform`, to the node of `tag`.
+    pub fn add_fill(&mut self, tag: AccessTag, shape: &impl Shape, transform: Affine) {
+        self.add_bounds(tag, transform.transform_rect_bbox(shape.bounding_box()));
+    }
+
+    /// Add the bounds of `path`, stroked with `stroke` and drawn with `transform`, to the node
+    /// of `tag`.
+    ///
+    /// The bounds are conservative, since they account for the longest possible miter joins.
+    pub fn add_stroke(
+        &mut self,
+        tag: AccessTag,
+        path: &BezPath,
+        stroke: &Stroke,
+        transform: Affine,
+    ) {
+        self.add_bounds(
+            tag,
+            transform.transform_rect_bbox(stroke_bounds(path, stroke)),
+        );
+    }
+
+    /// Add device-space glyph quads to the node of `tag`, extending its bounds to contain them.
+    pub fn add_glyph_quads(&mut self, tag: AccessTag, quads: impl IntoIterator<Item = Quad>) {
+        for quad in quads {
+            let bounds = quad_bounds(&quad);
+            let node = self.node_mut(tag, bounds);
+            node.bounds = node.bounds.union(bounds);
+            node.glyph_quads.push(quad);
+        }
+    }
+
+    /// Get the node of `tag`, creating it with `bounds` if it doesn't exist yet.
+    fn node_mut(&mut self, tag: AccessTag, bounds: Rect) -> &mut AccessNode {
+        let nodes = &mut self.nodes;
+        let index = *self.indices.entry(tag).or_insert_with(|| {
+            nodes.push(AccessNode {
+                tag,
+                bounds,
+                glyph_quads: Vec::new(),
+            });
+            nodes.len() - 1
+        });
+
+        &mut self.nodes[index]
+    }
+}
+
+fn quad_bounds(quad: &Quad) -> Rect {
+    quad[1..]
+        .iter()
+        .fold(Rect::from_points(quad[0], quad[0]), |bounds, p| {
+            bounds.union_pt(*p)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AccessGeometry, AccessTag};
+    use crate::kurbo::{Affine, Point, Rect};
+
+    const TAG: AccessTag = AccessTag(7);
+
+    #[test]
+    fn bounds_are_merged_per_tag() {
+        let mut geometry = AccessGeometry::new();
+        geometry.add_fill(TAG, &Rect::new(0.0, 0.0, 10.0, 10.0), Affine::scale(2.0));
+        geometry.add_bounds(AccessTag(8), Rect::new(1.0, 1.0, 2.0, 2.0));
+        geometry.add_fill(TAG, &Rect::new(30.0, 5.0, 40.0, 10.0), Affine::IDENTITY);
+
+        assert_eq!(geometry.nodes().len(), 2);
+        assert_eq!(geometry.nodes()[0].tag(), TAG);
+        assert_eq!(
+            geometry.get(TAG).unwrap().bounds(),
+            Rect::new(0.0, 0.0, 40.0, 20.0)
+        );
+    }
+
+    #[test]
+    fn cluster_quads_merge_consecutive_glyphs() {
+        let quad = |x0: f64, x1: f64| {
+            [
+                Point::new(x0, 0.0),
+                Point::new(x1, 0.0),
+                Point::new(x1, 10.0),
+                Point::new(x0, 10.0),
+            ]
+        };
+        let mut geometry = AccessGeometry::new();
+        geometry.add_glyph_quads(TAG, [quad(0.0, 5.0), quad(5.0, 8.0), quad(8.0, 12.0)]);
+        let node = geometry.get(TAG).unwrap();
+
+        assert_eq!(node.bounds(), Rect::new(0.0, 0.0, 12.0, 10.0));
+        assert_eq!(
+            node.cluster_quads(&[0, 0, 1]),
+            [quad(0.0, 8.0), quad(8.0, 12.0)]
+        );
+    }
+}
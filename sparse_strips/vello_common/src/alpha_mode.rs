@@ -0,0 +1,20 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! How the alpha channel of rendered pixels relates to their color channels.
+
+/// Whether the color channels of rendered pixels are multiplied with their alpha.
+///
+/// Rendering always happens with premultiplied alpha. Straight alpha is only produced when
+/// the pixels are written to the render target, for consumers like video encoders or GUI
+/// toolkits that expect it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlphaMode {
+    /// The color channels are multiplied with the alpha channel, which is what compositors
+    /// usually expect.
+    #[default]
+    Premultiplied,
+    /// The color channels are independent of the alpha channel. Fully transparent pixels are
+    /// transparent black.
+    Straight,
+}
@@ -0,0 +1,219 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Axis and grid lines for charts.
+//!
+//! Plots draw hundreds of thin, axis-aligned lines: grid lines, axis lines and tick marks.
+//! Stroking each of them as a path is wasteful, and a line that doesn't fall on the pixel grid
+//! ends up blurred across two rows or columns of pixels. [`Hairlines`] instead converts the
+//! lines into device-space rectangles whose edges are snapped to the pixel grid, which the
+//! renderers fill with their rectangle fast paths and without anti-aliasing fringes.
+
+use alloc::vec::Vec;
+
+use crate::kurbo::{Affine, Point, Rect, Vec2};
+#[cfg(not(feature = "std"))]
+use peniko::kurbo::common::FloatFuncs as _;
+
+/// A set of pixel-snapped, axis-aligned lines in device space.
+///
+/// Lines are added in a chart coordinate system, together with the transform from it into
+/// device space, and a width in device pixels. The transform should be axis-aligned, which
+/// includes scales, translations and rotations by multiples of 90 degrees.
+#[derive(Debug, Clone, Default)]
+pub struct Hairlines {
+    rects: Vec<Rect>,
+}
+
+impl Hairlines {
+    /// Create an empty set of lines.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The device-space rectangles of the lines, in the order they were added.
+    pub fn rects(&self) -> &[Rect] {
+        &self.rects
+    }
+
+    /// Return the number of lines.
+    pub fn len(&self) -> usize {
+        self.rects.len()
+    }
+
+    /// Whether no lines have been added.
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    /// Remove all lines.
+    pub fn clear(&mut self) {
+        self.rects.clear();
+    }
+
+    /// Add a horizontal line at `y` from `x0` to `x1`.
+    pub fn push_horizontal(&mut self, transform: Affine, y: f64, x0: f64, x1: f64, width: f64) {
+        self.push_line(transform, Point::new(x0, y), Point::new(x1, y), true, width);
+    }
+
+    /// Add a vertical line at `x` from `y0` to `y1`.
+    pub fn push_vertical(&mut self, transform: Affine, x: f64, y0: f64, y1: f64, width: f64) {
+        self.push_line(
+            transform,
+            Point::new(x, y0),
+            Point::new(x, y1),
+            false,
+            width,
+        );
+    }
+
+    /// Add the lines of a grid spanning `area`, with vertical lines at `xs` and horizontal
+    /// lines at `ys`.
+    pub fn push_grid(
+        &mut self,
+        transform: Affine,
+        area: Rect,
+        xs: impl IntoIterator<Item = f64>,
+        ys: impl IntoIterator<Item = f64>,
+        width: f64,
+    ) {
+        for x in xs {
+            self.push_vertical(transform, x, area.y0, area.y1, width);
+        }
+        for y in ys {
+            self.push_horizontal(transform, y, area.x0, area.x1, width);
+        }
+    }
+
+    /// Add tick marks along a horizontal axis at `y`, one at each of `xs`.
+    ///
+    /// The ticks extend `length` device pixels from the axis, downwards for positive lengths.
+    pub fn push_x_ticks(
+        &mut self,
+        transform: Affine,
+        y: f64,
+        xs: impl IntoIterator<Item = f64>,
+        length: f64,
+        width: f64,
+    ) {
+        for x in xs {
+            let start = transform * Point::new(x, y);
+            let end = start + (0.0, length);
+            self.push_line(Affine::IDENTITY, start, end, false, width);
+        }
+    }
+
+    /// Add tick marks along a vertical axis at `x`, one at each of `ys`.
+    ///
+    /// The ticks extend `length` device pixels from the axis, to the left for positive lengths.
+    pub fn push_y_ticks(
+        &mut self,
+        transform: Affine,
+        x: f64,
+        ys: impl IntoIterator<Item = f64>,
+        length: f64,
+        width: f64,
+    ) {
+        for y in ys {
+            let start = transform * Point::new(x, y);
+            let end = start - (length, 0.0);
+            self.push_line(Affine::IDENTITY, start, end, true, width);
+        }
+    }
+
+    /// Add a line from `p0` to `p1`, which is horizontal before the transform if `horizontal`
+    /// is set, and vertical otherwise.
+    fn push_line(&mut self, transform: Affine, p0: Point, p1: Point, horizontal: bool, width: f64) {
+        let bounds = Rect::from_points(transform * p0, transform * p1);
+        if !bounds.is_finite() {
+            return;
+        }
+        // Rotations by 90 degrees turn horizontal lines into vertical ones and vice versa.
+        let axis = if horizontal {
+            Vec2::new(1.0, 0.0)
+        } else {
+            Vec2::new(0.0, 1.0)
+        };
+        let direction = transform * (p0 + axis) - transform * p0;
+
+        // Lines are at least one pixel wide, so that they never disappear.
+        let width = width.round().max(1.0);
+        // Center the line across its direction, and cover the pixels the ends fall into along
+        // it, so that a line never shrinks to nothing.
+        let across = |center: f64| {
+            let start = (center - 0.5 * width).round();
+            (start, start + width)
+        };
+        let along = |start: f64, end: f64| (start.round(), end.round().max(start.round() + 1.0));
+
+        let rect = if direction.x.abs() >= direction.y.abs() {
+            let (x0, x1) = along(bounds.x0, bounds.x1);
+            let (y0, y1) = across(bounds.center().y);
+            Rect::new(x0, y0, x1, y1)
+        } else {
+            let (x0, x1) = across(bounds.center().x);
+            let (y0, y1) = along(bounds.y0, bounds.y1);
+            Rect::new(x0, y0, x1, y1)
+        };
+
+        self.rects.push(rect);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Hairlines;
+    use crate::kurbo::{Affine, Rect};
+
+    #[test]
+    fn lines_are_snapped_to_pixels() {
+        let mut lines = Hairlines::new();
+        let transform = Affine::translate((0.3, 0.0)).then_scale(2.0);
+        lines.push_vertical(transform, 5.0, 0.0, 10.0, 1.0);
+        lines.push_horizontal(transform, 2.2, 0.0, 10.0, 2.0);
+
+        assert_eq!(
+            lines.rects(),
+            [
+                Rect::new(10.0, 0.0, 11.0, 20.0),
+                Rect::new(1.0, 3.0, 21.0, 5.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn grid_and_ticks() {
+        let mut lines = Hairlines::new();
+        // Flip the y axis, like most charts do.
+        let transform = Affine::new([10.0, 0.0, 0.0, -10.0, 5.0, 105.0]);
+        lines.push_grid(
+            transform,
+            Rect::new(0.0, 0.0, 10.0, 10.0),
+            [0.0, 5.0],
+            [10.0],
+            1.0,
+        );
+        lines.push_x_ticks(transform, 0.0, [5.0], 4.0, 1.0);
+        lines.push_y_ticks(transform, 0.0, [10.0], 4.0, 1.0);
+
+        assert_eq!(
+            lines.rects(),
+            [
+                Rect::new(5.0, 5.0, 6.0, 105.0),
+                Rect::new(55.0, 5.0, 56.0, 105.0),
+                Rect::new(5.0, 5.0, 105.0, 6.0),
+                Rect::new(55.0, 105.0, 56.0, 109.0),
+                Rect::new(1.0, 5.0, 5.0, 6.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn degenerate_lines_cover_a_pixel() {
+        let mut lines = Hairlines::new();
+        lines.push_vertical(Affine::IDENTITY, 3.5, 2.2, 2.2, 0.0);
+        lines.push_vertical(Affine::IDENTITY, f64::NAN, 0.0, 1.0, 1.0);
+
+        assert_eq!(lines.rects(), [Rect::new(3.0, 2.0, 4.0, 3.0)]);
+    }
+}
@@ -6,7 +6,7 @@
 use crate::geometry::RectU16;
 use crate::kurbo::{Affine, BezPath, PathEl, Rect};
 use crate::strip::Strip;
-use crate::strip_generator::{GenerationMode, StripGenerator, StripStorage};
+use crate::strip_generator::{Aliasing, GenerationMode, StripGenerator, StripStorage};
 use crate::tile::Tile;
 use crate::util::normalized_mul_u8x16;
 use alloc::vec;
@@ -95,7 +95,7 @@ impl ClipContext {
         strip_generator: &mut StripGenerator,
         fill_rule: Fill,
         transform: Affine,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
     ) {
         self.temp_storage.clear();
 
@@ -142,7 +142,7 @@ impl ClipContext {
             clip_path,
             fill_rule,
             transform,
-            aliasing_threshold,
+            aliasing,
             &mut self.temp_storage,
             existing_clip,
         );
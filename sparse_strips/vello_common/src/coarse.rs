@@ -3,12 +3,13 @@
 
 //! Generating and processing wide tiles.
 
+use crate::accessibility::AccessTag;
 use crate::color::palette::css::TRANSPARENT;
 use crate::encode::EncodedPaint;
 use crate::filter_effects::Filter;
 use crate::geometry::RectU16;
 use crate::kurbo::{Affine, Rect};
-use crate::mask::Mask;
+use crate::mask::{Mask, MaskKind};
 use crate::paint::{Paint, PremulColor};
 use crate::peniko::{BlendMode, Compose, Mix};
 use crate::render_graph::{DependencyKind, LayerId, RenderGraph, RenderNodeKind};
@@ -42,6 +43,9 @@ struct Layer {
     /// Bounding box of wide tiles containing geometry.
     /// Starts with inverted bounds, shrinks to actual content during drawing.
     wtile_bbox: WideTilesBbox,
+    /// Whether this layer holds the masked content of a mask group, in which case popping it
+    /// also pops the mask group. See [`Wide::end_mask`].
+    ends_mask_group: bool,
 }
 
 impl Layer {
@@ -148,6 +152,9 @@ pub struct Wide<const MODE: u8 = MODE_CPU> {
     layers_needing_buf_stack: NeedsBufLayerStack,
     /// The stack of active clip regions.
     clip_stack: Vec<Clip>,
+    /// The length of [`Self::layer_stack`] right after each mask group whose mask is still
+    /// being drawn was pushed.
+    mask_group_depths: Vec<usize>,
     /// Stack of filter layer node IDs for render graph dependency tracking.
     /// Initialized with node 0 (the root node representing the final output).
     /// As layers with filters are pushed, their node IDs are added to this stack.
@@ -169,6 +176,8 @@ pub struct Wide<const MODE: u8 = MODE_CPU> {
     /// Whether at least one of the wide tiles has been mutated and thus they
     /// need to be reset.
     tiles_dirty: bool,
+    /// The tag that is attached to the commands of subsequent fills.
+    access_tag: Option<AccessTag>,
 }
 
 /// A clip region.
@@ -390,12 +399,14 @@ impl<const MODE: u8> Wide<MODE> {
             enable_bg_optimization,
             layer_stack: vec![],
             clip_stack: vec![],
+            mask_group_depths: vec![],
             // Start with root node 0.
             filter_node_stack: vec![0],
             clipped_filter_layer_depth: 0,
             layers_needing_buf_stack: NeedsBufLayerStack::default(),
             batch_count: 0,
             tiles_dirty: false,
+            access_tag: None,
         }
     }
 
@@ -418,9 +429,46 @@ impl<const MODE: u8> Wide<MODE> {
         self.layer_stack.clear();
         self.layers_needing_buf_stack.clear();
         self.clip_stack.clear();
+        self.mask_group_depths.clear();
         self.filter_node_stack.truncate(1);
         self.clipped_filter_layer_depth = 0;
         self.batch_count = 0;
+        self.access_tag = None;
+    }
+
+    /// Set the tag that is attached to the commands of subsequent fills, see
+    /// [`Wide::visible_access_tags`].
+    pub fn set_access_tag(&mut self, tag: Option<AccessTag>) {
+        self.access_tag = tag;
+    }
+
+    /// Get the tag that is attached to the commands of subsequent fills.
+    pub fn access_tag(&self) -> Option<AccessTag> {
+        self.access_tag
+    }
+
+    /// Return the tags of all fills that still contribute to at least one wide tile, sorted and
+    /// without duplicates.
+    ///
+    /// Fills that are entirely outside of the viewport or the active clip don't generate any
+    /// commands, and fills that are covered by a later opaque fill spanning the whole wide tile
+    /// are culled. The result is conservative though: a fill can still be hidden by content
+    /// that is drawn on top of it without being culled, or by the layer it is drawn into.
+    pub fn visible_access_tags(&self) -> Vec<AccessTag> {
+        let tag_of = |attrs_idx: u32| self.attrs.fill[attrs_idx as usize].access_tag;
+        let mut tags = Vec::new();
+        for tile in &self.tiles {
+            tags.extend(tile.bg_attrs_idx.and_then(tag_of));
+            tags.extend(tile.cmds.iter().filter_map(|cmd| match cmd {
+                Cmd::Fill(fill) => tag_of(fill.attrs_idx),
+                Cmd::AlphaFill(fill) => tag_of(fill.attrs_idx),
+                _ => None,
+            }));
+        }
+        tags.sort_unstable();
+        tags.dedup();
+
+        tags
     }
 
     /// Return the number of horizontal tiles.
@@ -518,6 +566,7 @@ impl<const MODE: u8> Wide<MODE> {
             blend_mode,
             mask,
             alpha_base_idx,
+            access_tag: self.access_tag,
         });
 
         // Get current clip bounding box or full viewport if no clip is active
@@ -526,6 +575,9 @@ impl<const MODE: u8> Wide<MODE> {
         // Save current_layer_id and batch_count to avoid borrowing issues
         let current_layer_id = self.get_current_layer_id();
         let batch_count = self.batch_count;
+        // In hybrid mode, a path drawn with a non-default blend mode needs to read back the
+        // buffer it is drawn into.
+        let blends_into_dest = MODE == MODE_HYBRID && blend_mode != BlendMode::default();
 
         for i in 0..strip_buf.len() - 1 {
             let strip = &strip_buf[i];
@@ -599,6 +651,9 @@ impl<const MODE: u8> Wide<MODE> {
                     cmd,
                     current_layer_id,
                 );
+                if blends_into_dest {
+                    self.tiles[idx].mark_blend_target(0);
+                }
                 self.update_current_layer_bbox(wtile_x, strip_y);
             }
 
@@ -623,7 +678,11 @@ impl<const MODE: u8> Wide<MODE> {
 
                 // Compute fill hint based on paint type
                 let fill_attrs = &self.attrs.fill[attrs_idx as usize];
-                let fill_hint = if fill_attrs.mask.is_none() && self.enable_bg_optimization {
+                // An opaque fill only replaces its backdrop with source-over compositing.
+                let fill_hint = if fill_attrs.mask.is_none()
+                    && fill_attrs.blend_mode == BlendMode::default()
+                    && self.enable_bg_optimization
+                {
                     match &fill_attrs.paint {
                         Paint::Solid(s) if s.is_opaque() => FillHint::OpaqueSolid(*s),
                         Paint::Indexed(idx) => match encoded_paints.get(idx.index()) {
@@ -670,6 +729,9 @@ impl<const MODE: u8> Wide<MODE> {
                         current_layer_id,
                         fill_hint,
                     );
+                    if blends_into_dest {
+                        self.tiles[idx].mark_blend_target(0);
+                    }
                     // TODO: This bbox update might be redundant since filled regions are always
                     // bounded by strip regions (which already update the bbox). Consider removing
                     // this in a follow-up with proper benchmarks to verify correctness.
@@ -685,6 +747,8 @@ impl<const MODE: u8> Wide<MODE> {
     /// This is used for filter effects that require access to a fully-rendered layer.
     ///
     /// If `filter` is Some, builds render graph nodes for filter effects.
+    ///
+    /// `opacity` is clamped to the range `[0, 1]`.
     pub fn push_layer(
         &mut self,
         layer_id: LayerId,
@@ -696,8 +760,101 @@ impl<const MODE: u8> Wide<MODE> {
         transform: Affine,
         render_graph: &mut RenderGraph,
         thread_idx: u8,
+    ) {
+        self.push_layer_with_node(
+            layer_id,
+            clip_path,
+            blend_mode,
+            mask,
+            opacity,
+            filter,
+            transform,
+            render_graph,
+            thread_idx,
+            None,
+        );
+    }
+
+    /// Apply a filter to everything that has been drawn so far, and composite the result
+    /// inside of the clip path on top of it.
+    ///
+    /// This is the building block of backdrop filters like the "frosted glass" effect. The
+    /// filtered backdrop is composited in place of a layer with `layer_id`, so that anything
+    /// drawn afterwards ends up on top of it.
+    ///
+    /// The backdrop is the content of the innermost isolated layer that is currently pushed,
+    /// or of the root layer, see [`RenderNodeKind::BackdropLayer`].
+    pub fn push_backdrop_filter(
+        &mut self,
+        layer_id: LayerId,
+        clip_path: impl Into<Box<[Strip]>>,
+        filter: Filter,
+        transform: Affine,
+        render_graph: &mut RenderGraph,
+        thread_idx: u8,
+    ) {
+        let in_root_layer = !self.layer_stack.iter().any(Layer::needs_buf);
+        let clip_path = clip_path.into();
+        let clip_bbox = strips_bbox(&clip_path).intersect(self.full_viewport_bbox());
+
+        self.push_layer_with_node(
+            layer_id,
+            Some(clip_path),
+            BlendMode::default(),
+            None,
+            1.0,
+            Some(filter),
+            transform,
+            render_graph,
+            thread_idx,
+            Some(in_root_layer),
+        );
+        // The layer doesn't have any content of its own, but the filter needs to be applied
+        // within the clip path, and `pop_layer` expands this by the reach of the filter.
+        self.layer_stack.last_mut().unwrap().wtile_bbox = clip_bbox;
+        self.pop_layer(render_graph);
+
+        // Instead of its own (empty) commands, the layer renders the commands of its parent layer
+        // that precede it. Skipping the layer in the parent layer still uses the actual range of
+        // the layer, since `render_range.end` lies before the layer and is therefore ignored.
+        for tile in &mut self.tiles {
+            let Some(ranges) = tile.layer_cmd_ranges.get_mut(&layer_id) else {
+                continue;
+            };
+            let start = ranges.full_range.start;
+            if matches!(
+                tile.cmds.get(start),
+                Some(Cmd::PushBuf(LayerKind::Filtered(id), _)) if *id == layer_id
+            ) {
+                let backdrop_start = if in_root_layer {
+                    0
+                } else {
+                    isolated_buf_start(&tile.cmds[..start]).unwrap_or(start)
+                };
+                ranges.render_range = backdrop_start..start;
+            } else {
+                // The buffer of the layer was elided in this wide tile.
+                tile.layer_cmd_ranges.remove(&layer_id);
+            }
+        }
+    }
+
+    fn push_layer_with_node(
+        &mut self,
+        layer_id: LayerId,
+        clip_path: Option<impl Into<Box<[Strip]>>>,
+        blend_mode: BlendMode,
+        mask: Option<Mask>,
+        opacity: f32,
+        filter: Option<Filter>,
+        transform: Affine,
+        render_graph: &mut RenderGraph,
+        thread_idx: u8,
+        backdrop: Option<bool>,
     ) {
         self.tiles_dirty = true;
+        // Opacities above 1 would push premultiplied color components out of range.
+        let opacity = opacity.clamp(0.0, 1.0);
 
         // Some explanations about what is going on here: We support the concept of
         // layers, where a user can push a new layer (with certain properties), draw some
@@ -723,12 +880,22 @@ impl<const MODE: u8> Wide<MODE> {
         // The render graph tracks dependencies and execution order for filter effects.
         if let Some(filter) = &filter {
             // Create a FilterLayer node that combines render + filter + other operations
-            let child_node = render_graph.add_node(RenderNodeKind::FilterLayer {
-                layer_id,
-                filter: filter.clone(),
-                // Bounding box starts inverted and will be updated in pop_layer with actual bounds
-                wtile_bbox: WideTilesBbox::INVERTED,
-                transform,
+            // Bounding box starts inverted and will be updated in pop_layer with actual bounds
+            let child_node = render_graph.add_node(if let Some(in_root_layer) = backdrop {
+                RenderNodeKind::BackdropLayer {
+                    layer_id,
+                    filter: filter.clone(),
+                    wtile_bbox: WideTilesBbox::INVERTED,
+                    transform,
+                    in_root_layer,
+                }
+            } else {
+                RenderNodeKind::FilterLayer {
+                    layer_id,
+                    filter: filter.clone(),
+                    wtile_bbox: WideTilesBbox::INVERTED,
+                    transform,
+                }
             });
 
             // Connect to parent node if there is one
@@ -773,6 +940,7 @@ impl<const MODE: u8> Wide<MODE> {
             mask,
             filter,
             wtile_bbox: WideTilesBbox::INVERTED,
+            ends_mask_group: false,
         };
 
         // In case we do blending, masking, opacity, or filtering, push one buffer per wide tile.
@@ -830,6 +998,89 @@ impl<const MODE: u8> Wide<MODE> {
         self.layer_stack.push(layer);
     }
 
+    /// Push a new mask group.
+    ///
+    /// A mask group is a regular layer without a clip path, so that it is isolated from its
+    /// backdrop. Everything drawn into it until [`Wide::end_mask`] is called is the mask.
+    pub fn push_mask_group(
+        &mut self,
+        layer_id: LayerId,
+        render_graph: &mut RenderGraph,
+        thread_idx: u8,
+    ) {
+        let no_clip_path: Option<Box<[Strip]>> = None;
+        self.push_layer(
+            layer_id,
+            no_clip_path,
+            BlendMode::default(),
+            None,
+            1.0,
+            None,
+            Affine::IDENTITY,
+            render_graph,
+            thread_idx,
+        );
+        self.mask_group_depths.push(self.layer_stack.len());
+    }
+
+    /// End the mask of the last-pushed mask group and start its masked content.
+    ///
+    /// Everything drawn into the mask group so far is the mask, which is turned into coverage
+    /// according to `kind`. Then, a new layer with `layer_id` is pushed for the masked content,
+    /// which is composited into the mask using [`Compose::SrcIn`]. Popping that layer also pops
+    /// the mask group, so that the masked result is composited into the backdrop.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no mask group whose mask is being drawn, or if a layer that was
+    /// pushed after the mask group hasn't been popped yet.
+    pub fn end_mask(
+        &mut self,
+        layer_id: LayerId,
+        kind: MaskKind,
+        render_graph: &mut RenderGraph,
+        thread_idx: u8,
+    ) {
+        let depth = self
+            .mask_group_depths
+            .pop()
+            .expect("`end_mask` called without a matching `push_mask_group`");
+        assert_eq!(
+            depth,
+            self.layer_stack.len(),
+            "layers pushed after a mask group must be popped before ending its mask"
+        );
+
+        if kind == MaskKind::Luminance {
+            for &tile_idx in self
+                .layers_needing_buf_stack
+                .last()
+                .unwrap()
+                .occupied_tiles
+                .iter()
+            {
+                let tile = &mut self.tiles[tile_idx];
+                if !matches!(tile.cmds.last().unwrap(), &Cmd::PushBuf(..)) {
+                    tile.cmds.push(Cmd::LuminanceToAlpha);
+                }
+            }
+        }
+
+        let no_clip_path: Option<Box<[Strip]>> = None;
+        self.push_layer(
+            layer_id,
+            no_clip_path,
+            BlendMode::new(Mix::Normal, Compose::SrcIn),
+            None,
+            1.0,
+            None,
+            Affine::IDENTITY,
+            render_graph,
+            thread_idx,
+        );
+        self.layer_stack.last_mut().unwrap().ends_mask_group = true;
+    }
+
     /// Pop a previously pushed layer.
     ///
     /// This method finalizes the layer by:
@@ -842,6 +1093,11 @@ impl<const MODE: u8> Wide<MODE> {
     pub fn pop_layer(&mut self, render_graph: &mut RenderGraph) {
         self.tiles_dirty = true;
 
+        // A mask group that is popped before its mask was ended has no content.
+        if self.mask_group_depths.last() == Some(&self.layer_stack.len()) {
+            self.mask_group_depths.pop();
+        }
+
         // This method basically unwinds everything we did in `push_layer`.
         let mut layer = self.layer_stack.pop().unwrap();
         let batch_count = self.batch_count;
@@ -857,6 +1113,11 @@ impl<const MODE: u8> Wide<MODE> {
                         wtile_bbox,
                         transform,
                         ..
+                    }
+                    | RenderNodeKind::BackdropLayer {
+                        wtile_bbox,
+                        transform,
+                        ..
                     } = &mut node.kind
                 {
                     // Calculate expansion in device/pixel space, accounting for the full transform.
@@ -948,6 +1209,10 @@ impl<const MODE: u8> Wide<MODE> {
         if in_clipped_filter_layer {
             self.clipped_filter_layer_depth -= 1;
         }
+
+        if layer.ends_mask_group {
+            self.pop_layer(render_graph);
+        }
     }
 
     /// Adds a clipping region defined by the provided strips.
@@ -967,27 +1232,7 @@ impl<const MODE: u8> Wide<MODE> {
         let n_strips = strips.len();
 
         // Calculate the bounding box of the clip path in strip coordinates
-        let path_bbox = if n_strips <= 1 {
-            WideTilesBbox::ZERO
-        } else {
-            // Calculate the y range from first to last strip in wide tile coordinates
-            let wtile_y0 = strips[0].strip_y();
-            let wtile_y1 = strips[n_strips.saturating_sub(1)].strip_y() + 1;
-
-            // Calculate the x range by examining all strips in wide tile coordinates
-            let mut wtile_x0 = strips[0].x / WideTile::WIDTH;
-            let mut wtile_x1 = wtile_x0;
-            for i in 0..n_strips.saturating_sub(1) {
-                let strip = &strips[i];
-                let next_strip = &strips[i + 1];
-                let width =
-                    ((next_strip.alpha_idx() - strip.alpha_idx()) / u32::from(Tile::HEIGHT)) as u16;
-                let x = strip.x;
-                wtile_x0 = wtile_x0.min(x / WideTile::WIDTH);
-                wtile_x1 = wtile_x1.max((x + width).div_ceil(WideTile::WIDTH));
-            }
-            WideTilesBbox::new(wtile_x0, wtile_y0, wtile_x1, wtile_y1)
-        };
+        let path_bbox = strips_bbox(&strips);
 
         let parent_bbox = self.active_bbox();
         // Determine which tiles need clip processing:
@@ -1328,6 +1573,55 @@ impl<const MODE: u8> Wide<MODE> {
     }
 }
 
+/// Return the index of the first command drawn into the innermost buffer of an isolated layer
+/// that is still open at the end of `cmds`, skipping the buffers of clip layers.
+fn isolated_buf_start(cmds: &[Cmd]) -> Option<usize> {
+    let mut depth = 0_usize;
+    for (idx, cmd) in cmds.iter().enumerate().rev() {
+        match cmd {
+            Cmd::PopBuf => depth += 1,
+            Cmd::PushBuf(..) if depth > 0 => depth -= 1,
+            Cmd::PushBuf(LayerKind::Clip(_), _) => {}
+            Cmd::PushBuf(..) => {
+                // Tiles outside of the clip path of a clipped filter layer still render its
+                // content for the filter, after marking the zero clip.
+                return Some(match cmds.get(idx + 1) {
+                    Some(Cmd::PushZeroClip(_)) => idx + 2,
+                    _ => idx + 1,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Calculate the bounding box of a path in wide tile coordinates from its strips.
+fn strips_bbox(strips: &[Strip]) -> WideTilesBbox {
+    let n_strips = strips.len();
+    if n_strips <= 1 {
+        return WideTilesBbox::ZERO;
+    }
+
+    // Calculate the y range from first to last strip in wide tile coordinates
+    let wtile_y0 = strips[0].strip_y();
+    let wtile_y1 = strips[n_strips - 1].strip_y() + 1;
+
+    // Calculate the x range by examining all strips in wide tile coordinates
+    let mut wtile_x0 = strips[0].x / WideTile::WIDTH;
+    let mut wtile_x1 = wtile_x0;
+    for i in 0..n_strips - 1 {
+        let strip = &strips[i];
+        let next_strip = &strips[i + 1];
+        let width = ((next_strip.alpha_idx() - strip.alpha_idx()) / u32::from(Tile::HEIGHT)) as u16;
+        let x = strip.x;
+        wtile_x0 = wtile_x0.min(x / WideTile::WIDTH);
+        wtile_x1 = wtile_x1.max((x + width).div_ceil(WideTile::WIDTH));
+    }
+    WideTilesBbox::new(wtile_x0, wtile_y0, wtile_x1, wtile_y1)
+}
+
 /// A wide tile.
 #[derive(Debug)]
 pub struct WideTile<const MODE: u8 = MODE_CPU> {
@@ -1339,6 +1633,8 @@ pub struct WideTile<const MODE: u8 = MODE_CPU> {
     pub bg: PremulColor,
     /// The draw commands of the tile.
     pub cmds: Vec<Cmd>,
+    /// The index of the fill attributes of the opaque fill that set [`WideTile::bg`], if any.
+    bg_attrs_idx: Option<u32>,
     /// The number of zero-winding clips.
     n_zero_clip: usize,
     /// The number of non-zero-winding clips.
@@ -1402,6 +1698,7 @@ impl<const MODE: u8> WideTile<MODE> {
             y,
             bg: PremulColor::from_alpha_color(TRANSPARENT),
             cmds: vec![],
+            bg_attrs_idx: None,
             n_zero_clip: 0,
             n_clip: 0,
             n_bufs: 0,
@@ -1419,6 +1716,7 @@ impl<const MODE: u8> WideTile<MODE> {
     fn reset(&mut self) {
         self.bg = PremulColor::from_alpha_color(TRANSPARENT);
         self.cmds.clear();
+        self.bg_attrs_idx = None;
         self.n_zero_clip = 0;
         self.n_clip = 0;
         self.n_bufs = 0;
@@ -1537,6 +1835,7 @@ impl<const MODE: u8> WideTile<MODE> {
                     FillHint::OpaqueSolid(color) => {
                         self.cmds.clear();
                         self.bg = color;
+                        self.bg_attrs_idx = Some(attrs_idx);
 
                         // We need to invalidate the ranges of all layers that have been drawn so far
                         // in that wide tile.
@@ -1549,6 +1848,7 @@ impl<const MODE: u8> WideTile<MODE> {
                         // Opaque image: clear previous commands but still emit the fill.
                         self.cmds.clear();
                         self.bg = PremulColor::from_alpha_color(TRANSPARENT);
+                        self.bg_attrs_idx = None;
 
                         // We need to invalidate the ranges of all layers that have been drawn so far
                         // in that wide tile.
@@ -1786,23 +2086,38 @@ impl<const MODE: u8> WideTile<MODE> {
             let blends_into_dest =
                 blend_mode.mix != Mix::Normal || blend_mode.compose != Compose::SrcOver;
 
-            if blends_into_dest && self.push_buf_indices.len() >= 2 {
-                let nos_idx = self.push_buf_indices[self.push_buf_indices.len() - 2];
-
-                if nos_idx == TARGET_SURFACE_PUSH_BUF_IDX {
-                    self.surface_is_blend_target = true;
-                } else {
-                    match &mut self.cmds[nos_idx] {
-                        Cmd::PushBuf(_, is_blend_target) => *is_blend_target = true,
-                        // Anything else shouldn't be possible.
-                        _ => unreachable!(),
-                    }
-                }
+            if blends_into_dest {
+                self.mark_blend_target(1);
             }
         }
 
         self.cmds.push(Cmd::Blend(blend_mode));
     }
+
+    /// Mark a buffer as the destination of a blending operation with a non-default blend
+    /// mode, where `depth` is 0 for the current buffer and 1 for the one below it.
+    ///
+    /// Only used in `HYBRID` mode.
+    fn mark_blend_target(&mut self, depth: usize) {
+        let Some(&buf_idx) = self
+            .push_buf_indices
+            .len()
+            .checked_sub(depth + 1)
+            .and_then(|idx| self.push_buf_indices.get(idx))
+        else {
+            return;
+        };
+
+        if buf_idx == TARGET_SURFACE_PUSH_BUF_IDX {
+            self.surface_is_blend_target = true;
+        } else {
+            match &mut self.cmds[buf_idx] {
+                Cmd::PushBuf(_, is_blend_target) => *is_blend_target = true,
+                // Anything else shouldn't be possible.
+                _ => unreachable!(),
+            }
+        }
+    }
 }
 
 /// Debug utilities for wide tiles.
@@ -1941,6 +2256,12 @@ pub enum Cmd {
     ///
     /// Modulates the alpha channel of the buffer using the provided mask.
     Mask(Mask),
+    /// Turn the current buffer into a luminance mask.
+    ///
+    /// Every pixel is replaced by a transparent black pixel whose alpha is the luminance of
+    /// the original pixel. `vello_hybrid` instead defers this to the next blend into the
+    /// buffer, which is the blend of the masked content of the mask group.
+    LuminanceToAlpha,
     /// Marks a boundary between rendering fast path strips and coarse rasterized strips.
     ///
     /// Only meaningful in `MODE_HYBRID`.
@@ -1978,6 +2299,7 @@ impl Cmd {
             Self::Blend(_) => "Blend",
             Self::Opacity(_) => "Opacity",
             Self::Mask(_) => "Mask",
+            Self::LuminanceToAlpha => "LuminanceToAlpha",
             Self::BatchEnd => "BatchEnd",
         }
     }
@@ -2038,6 +2360,7 @@ fn paint_name(paint: &Paint, encoded_paints: &[EncodedPaint]) -> String {
                     EncodedPaint::Image(_) => "Image",
                     EncodedPaint::ExternalTexture(_) => "ExternalTexture",
                     EncodedPaint::BlurredRoundedRect(_) => "BlurredRoundedRect",
+                    EncodedPaint::Custom(_) => "Custom",
                 };
                 format!("{}[{}]", kind, index)
             } else {
@@ -2065,6 +2388,8 @@ pub struct FillAttrs {
     /// Base index into the alpha buffer for this path's commands.
     /// Commands store a relative offset that is added to this base.
     alpha_base_idx: u32,
+    /// The tag of the fill, if any.
+    pub access_tag: Option<AccessTag>,
 }
 
 impl FillAttrs {
@@ -2202,14 +2527,18 @@ impl LayerCommandRanges {
 
 #[cfg(test)]
 mod tests {
-    use crate::coarse::{FillHint, LayerKind, MODE_CPU, NeedsBufLayerStack, Wide, WideTile};
+    use crate::coarse::{
+        Cmd, FillHint, LayerKind, MODE_CPU, MODE_HYBRID, NeedsBufLayerStack, Wide, WideTile,
+        isolated_buf_start,
+    };
     use crate::kurbo::Affine;
+    use crate::mask::MaskKind;
     use crate::paint::{Paint, PremulColor};
     use crate::peniko;
     use crate::peniko::{BlendMode, Compose, Mix};
     use crate::render_graph::RenderGraph;
     use crate::strip::Strip;
-    use alloc::{boxed::Box, vec};
+    use alloc::{boxed::Box, vec, vec::Vec};
 
     #[test]
     fn optimize_empty_layers() {
@@ -2277,6 +2606,36 @@ mod tests {
         assert_eq!(tile_2.y, 60);
     }
 
+    #[test]
+    fn push_layer_clamps_opacity() {
+        type ClipPath = Option<Box<[Strip]>>;
+
+        let mut wide = Wide::<MODE_CPU>::new(100, 100);
+        let mut render_graph = RenderGraph::new();
+
+        for (layer_id, opacity) in [(1, 1.5), (2, -0.5), (3, 0.25)] {
+            let no_clip_path: ClipPath = None;
+            wide.push_layer(
+                layer_id,
+                no_clip_path,
+                BlendMode::default(),
+                None,
+                opacity,
+                None,
+                Affine::IDENTITY,
+                &mut render_graph,
+                0,
+            );
+        }
+
+        let opacities = wide
+            .layer_stack
+            .iter()
+            .map(|l| l.opacity)
+            .collect::<Vec<_>>();
+        assert_eq!(opacities, [1.0, 0.0, 0.25]);
+    }
+
     #[test]
     fn reset_clears_layer_and_clip_stacks() {
         type ClipPath = Option<Box<[Strip]>>;
@@ -2324,6 +2683,97 @@ mod tests {
         assert_eq!(wide.tiles[0].n_bufs, 0);
     }
 
+    #[test]
+    #[should_panic(expected = "layers pushed after a mask group must be popped")]
+    fn end_mask_panics_inside_nested_layer() {
+        let mut wide = Wide::<MODE_CPU>::new(1000, 258);
+        let mut render_graph = RenderGraph::new();
+        let no_clip_path: Option<Box<[Strip]>> = None;
+        wide.push_mask_group(1, &mut render_graph, 0);
+        // An unclipped layer on top of the mask group must not be mistaken for it.
+        wide.push_layer(
+            2,
+            no_clip_path,
+            BlendMode::default(),
+            None,
+            0.5,
+            None,
+            Affine::IDENTITY,
+            &mut render_graph,
+            0,
+        );
+        wide.end_mask(3, MaskKind::Alpha, &mut render_graph, 0);
+    }
+
+    #[test]
+    fn end_mask_pairs_with_innermost_mask_group() {
+        let mut wide = Wide::<MODE_CPU>::new(1000, 258);
+        let mut render_graph = RenderGraph::new();
+        wide.push_mask_group(1, &mut render_graph, 0);
+        wide.push_mask_group(2, &mut render_graph, 0);
+        wide.end_mask(3, MaskKind::Alpha, &mut render_graph, 0);
+        wide.pop_layer(&mut render_graph);
+        wide.end_mask(4, MaskKind::Luminance, &mut render_graph, 0);
+        wide.pop_layer(&mut render_graph);
+
+        assert!(!wide.has_layers());
+        assert!(wide.mask_group_depths.is_empty());
+    }
+
+    #[test]
+    fn blended_paths_mark_their_buffer_as_blend_target() {
+        type ClipPath = Option<Box<[Strip]>>;
+
+        let mut wide = Wide::<MODE_HYBRID>::new(256, 4, true);
+        let mut render_graph = RenderGraph::new();
+        let strips = [Strip::new(0, 0, 0, false), Strip::new(10, 0, 4, true)];
+        let paint = Paint::Solid(PremulColor::from_alpha_color(
+            peniko::color::palette::css::RED,
+        ));
+        let multiply = BlendMode::new(Mix::Multiply, Compose::SrcOver);
+
+        wide.generate(&strips, paint.clone(), BlendMode::default(), 0, None, &[]);
+        assert!(!wide.tiles[0].surface_is_blend_target());
+        wide.generate(&strips, paint.clone(), multiply, 0, None, &[]);
+        assert!(wide.tiles[0].surface_is_blend_target());
+
+        wide.reset();
+        let no_clip: ClipPath = None;
+        wide.push_layer(
+            1,
+            no_clip,
+            BlendMode::default(),
+            None,
+            0.5,
+            None,
+            Affine::IDENTITY,
+            &mut render_graph,
+            0,
+        );
+        wide.generate(&strips, paint, multiply, 0, None, &[]);
+        assert!(!wide.tiles[0].surface_is_blend_target());
+        assert!(matches!(wide.tiles[0].cmds[0], Cmd::PushBuf(_, true)));
+    }
+
+    #[test]
+    fn backdrop_starts_in_innermost_isolated_layer() {
+        let cmds = [
+            Cmd::PushBuf(LayerKind::Regular(1), false),
+            Cmd::PushBuf(LayerKind::Clip(2), false),
+            Cmd::PushBuf(LayerKind::Regular(3), false),
+            Cmd::PopBuf,
+            Cmd::PushBuf(LayerKind::Clip(4), false),
+        ];
+        assert_eq!(isolated_buf_start(&cmds), Some(1));
+        assert_eq!(isolated_buf_start(&cmds[1..]), None);
+
+        let cmds = [
+            Cmd::PushBuf(LayerKind::Filtered(1), false),
+            Cmd::PushZeroClip(1),
+        ];
+        assert_eq!(isolated_buf_start(&cmds), Some(2));
+    }
+
     #[test]
     fn tiles_dirty_flag() {
         type ClipPath = Option<Box<[Strip]>>;
@@ -2382,4 +2832,38 @@ mod tests {
         );
         assert!(!wide.tiles_dirty);
     }
+
+    #[test]
+    fn visible_access_tags_skip_culled_fills() {
+        use crate::accessibility::AccessTag;
+        use peniko::color::palette::css::{BLUE, RED};
+
+        let mut wide = Wide::<MODE_CPU>::new(256, 4);
+        let partial = [Strip::new(0, 0, 0, false), Strip::new(10, 0, 4, true)];
+        let full = [Strip::new(0, 0, 0, false), Strip::new(256, 0, 0, true)];
+        let fill = |wide: &mut Wide, tag: Option<u64>, strips: &[Strip], color| {
+            wide.set_access_tag(tag.map(AccessTag));
+            wide.generate(
+                strips,
+                Paint::Solid(PremulColor::from_alpha_color(color)),
+                BlendMode::default(),
+                0,
+                None,
+                &[],
+            );
+        };
+
+        fill(&mut wide, Some(1), &partial, RED);
+        fill(&mut wide, None, &partial, RED);
+        assert_eq!(wide.visible_access_tags(), [AccessTag(1)]);
+
+        // The opaque fill spans the whole wide tile, so the first fill is culled.
+        fill(&mut wide, Some(2), &full, BLUE);
+        fill(&mut wide, Some(3), &partial, RED.with_alpha(0.5));
+        assert_eq!(wide.visible_access_tags(), [AccessTag(2), AccessTag(3)]);
+
+        wide.reset();
+        assert!(wide.visible_access_tags().is_empty());
+        assert_eq!(wide.access_tag(), None);
+    }
 }
@@ -0,0 +1,381 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Compact storage for large sets of static paths.
+//!
+//! Some scenes keep a huge number of paths resident that never change, like the country
+//! boundaries of a world map. Stored as [`BezPath`]s, every element takes 56 bytes, most of
+//! which is spent on `f64` coordinates with far more precision than the screen can show.
+//!
+//! [`CompactPaths`] stores a whole set of paths in two flat buffers instead. The points of all
+//! paths are quantized to a grid that is shared by the set, described by a [`Quantization`],
+//! and each point is stored as the 16-bit difference to the previous point. The kind of each
+//! element takes another byte, so a line takes 5 bytes instead of 56.
+//!
+//! A [`CompactPath`] decodes its elements on the fly while iterating over them, so it can be
+//! passed to anything that consumes path elements, like the flattener and the strip generator,
+//! without converting it back into a [`BezPath`] first.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+use thiserror::Error;
+
+use crate::kurbo::{Affine, BezPath, PathEl, Point, Rect};
+#[cfg(not(feature = "std"))]
+use peniko::kurbo::common::FloatFuncs as _;
+
+const MOVE_TO: u8 = 0;
+const LINE_TO: u8 = 1;
+const QUAD_TO: u8 = 2;
+const CURVE_TO: u8 = 3;
+const CLOSE_PATH: u8 = 4;
+
+/// The grid that the points of a [`CompactPaths`] are quantized to.
+///
+/// Quantized coordinates are 16-bit integers, so the grid spans 65535 steps around its origin
+/// in both directions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantization {
+    /// The point that quantized coordinates are relative to.
+    pub origin: Point,
+    /// The distance between two neighboring grid points.
+    pub step: f64,
+}
+
+impl Quantization {
+    /// Create a grid with the given origin and step.
+    pub fn new(origin: Point, step: f64) -> Self {
+        Self { origin, step }
+    }
+
+    /// Create the finest grid that covers `bounds`.
+    pub fn for_bounds(bounds: Rect) -> Self {
+        let extent = bounds.width().max(bounds.height());
+        Self {
+            origin: bounds.center(),
+            step: (extent / f64::from(u16::MAX - 1)).max(f64::EPSILON),
+        }
+    }
+
+    /// Return the transform from quantized coordinates to the original coordinates.
+    pub fn transform(&self) -> Affine {
+        Affine::scale(self.step).then_translate(self.origin.to_vec2())
+    }
+
+    /// Quantize `point`, or return `None` if it lies outside of the grid.
+    fn quantize(&self, point: Point) -> Option<[i16; 2]> {
+        let quantize = |value: f64, origin: f64| {
+            let value = ((value - origin) / self.step).round();
+            (f64::from(i16::MIN)..=f64::from(i16::MAX))
+                .contains(&value)
+                .then_some(value as i16)
+        };
+
+        Some([
+            quantize(point.x, self.origin.x)?,
+            quantize(point.y, self.origin.y)?,
+        ])
+    }
+}
+
+/// An error that can occur when adding a path to a [`CompactPaths`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum CompactPathError {
+    /// A point of the path lies outside of the quantization grid, or isn't finite.
+    #[error("Point outside of the quantization grid")]
+    OutOfRange,
+}
+
+/// A handle to a path stored in a [`CompactPaths`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CompactPathId(u32);
+
+/// A read-only set of paths in a compact encoding.
+///
+/// See the [module documentation](self) for details.
+#[derive(Debug, Clone)]
+pub struct CompactPaths {
+    quantization: Quantization,
+    transform: Affine,
+    verbs: Vec<u8>,
+    coords: Vec<i16>,
+    /// The start of the verbs and coordinates of each path.
+    starts: Vec<(u32, u32)>,
+}
+
+impl CompactPaths {
+    /// Create an empty set whose points are quantized to `quantization`.
+    pub fn new(quantization: Quantization) -> Self {
+        Self {
+            quantization,
+            transform: quantization.transform(),
+            verbs: Vec::new(),
+            coords: Vec::new(),
+            starts: Vec::new(),
+        }
+    }
+
+    /// The grid the points are quantized to.
+    pub fn quantization(&self) -> Quantization {
+        self.quantization
+    }
+
+    /// Return the number of paths.
+    pub fn len(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Whether the set has no paths.
+    pub fn is_empty(&self) -> bool {
+        self.starts.is_empty()
+    }
+
+    /// Return the number of bytes used by the encoded paths.
+    pub fn encoded_size(&self) -> usize {
+        self.verbs.len()
+            + self.coords.len() * size_of::<i16>()
+            + self.starts.len() * size_of::<(u32, u32)>()
+    }
+
+    /// Add a path to the set.
+    ///
+    /// Returns an error if a point doesn't fit into the quantization grid, in which case the
+    /// set is left unchanged.
+    pub fn push(
+        &mut self,
+        path: impl IntoIterator<Item = PathEl>,
+    ) -> Result<CompactPathId, CompactPathError> {
+        let (verb_start, coord_start) = (self.verbs.len(), self.coords.len());
+        let id = CompactPathId(self.starts.len() as u32);
+
+        let mut last = [0_i16; 2];
+        let mut encode = |verbs: &mut Vec<u8>, coords: &mut Vec<i16>, verb, points: &[Point]| {
+            verbs.push(verb);
+            for point in points {
+                let point = self
+                    .quantization
+                    .quantize(*point)
+                    .ok_or(CompactPathError::OutOfRange)?;
+                // Wrapping arithmetic makes the difference between any two 16-bit values fit
+                // into 16 bits.
+                coords.push(point[0].wrapping_sub(last[0]));
+                coords.push(point[1].wrapping_sub(last[1]));
+                last = point;
+            }
+            Ok(())
+        };
+
+        let result = path.into_iter().try_for_each(|el| {
+            let (verbs, coords) = (&mut self.verbs, &mut self.coords);
+            match el {
+                PathEl::MoveTo(p) => encode(verbs, coords, MOVE_TO, &[p]),
+                PathEl::LineTo(p) => encode(verbs, coords, LINE_TO, &[p]),
+                PathEl::QuadTo(p1, p2) => encode(verbs, coords, QUAD_TO, &[p1, p2]),
+                PathEl::CurveTo(p1, p2, p3) => encode(verbs, coords, CURVE_TO, &[p1, p2, p3]),
+                PathEl::ClosePath => encode(verbs, coords, CLOSE_PATH, &[]),
+            }
+        });
+
+        if let Err(error) = result {
+            self.verbs.truncate(verb_start);
+            self.coords.truncate(coord_start);
+            return Err(error);
+        }
+
+        self.starts.push((verb_start as u32, coord_start as u32));
+        Ok(id)
+    }
+
+    /// Return the path with the given id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path doesn't belong to this set.
+    pub fn get(&self, id: CompactPathId) -> CompactPath<'_> {
+        let index = id.0 as usize;
+        let (verbs, coords) = self.ranges(index);
+
+        CompactPath {
+            verbs: &self.verbs[verbs],
+            coords: &self.coords[coords],
+            transform: self.transform,
+        }
+    }
+
+    /// Iterate over all paths, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = CompactPath<'_>> {
+        (0..self.starts.len()).map(|index| self.get(CompactPathId(index as u32)))
+    }
+
+    fn ranges(&self, index: usize) -> (Range<usize>, Range<usize>) {
+        let (verb_start, coord_start) = self.starts[index];
+        let (verb_end, coord_end) = self
+            .starts
+            .get(index + 1)
+            .copied()
+            .unwrap_or((self.verbs.len() as u32, self.coords.len() as u32));
+
+        (
+            verb_start as usize..verb_end as usize,
+            coord_start as usize..coord_end as usize,
+        )
+    }
+}
+
+/// A path stored in a [`CompactPaths`].
+///
+/// Iterating over the path decodes its elements.
+#[derive(Debug, Clone, Copy)]
+pub struct CompactPath<'a> {
+    verbs: &'a [u8],
+    coords: &'a [i16],
+    transform: Affine,
+}
+
+impl<'a> CompactPath<'a> {
+    /// Iterate over the elements of the path.
+    pub fn elements(&self) -> CompactPathIter<'a> {
+        CompactPathIter {
+            verbs: self.verbs.iter(),
+            coords: self.coords.iter(),
+            transform: self.transform,
+            last: [0, 0],
+        }
+    }
+
+    /// Whether the path has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.verbs.is_empty()
+    }
+
+    /// Decode the path into `path`, replacing its contents.
+    pub fn decode_into(&self, path: &mut BezPath) {
+        path.truncate(0);
+        path.extend(self.elements());
+    }
+}
+
+impl<'a> IntoIterator for CompactPath<'a> {
+    type Item = PathEl;
+    type IntoIter = CompactPathIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.elements()
+    }
+}
+
+/// An iterator over the decoded elements of a [`CompactPath`].
+#[derive(Debug, Clone)]
+pub struct CompactPathIter<'a> {
+    verbs: core::slice::Iter<'a, u8>,
+    coords: core::slice::Iter<'a, i16>,
+    transform: Affine,
+    last: [i16; 2],
+}
+
+impl CompactPathIter<'_> {
+    fn point(&mut self) -> Point {
+        let dx = *self.coords.next().unwrap();
+        let dy = *self.coords.next().unwrap();
+        self.last = [self.last[0].wrapping_add(dx), self.last[1].wrapping_add(dy)];
+
+        self.transform * Point::new(f64::from(self.last[0]), f64::from(self.last[1]))
+    }
+}
+
+impl Iterator for CompactPathIter<'_> {
+    type Item = PathEl;
+
+    fn next(&mut self) -> Option<PathEl> {
+        Some(match *self.verbs.next()? {
+            MOVE_TO => PathEl::MoveTo(self.point()),
+            LINE_TO => PathEl::LineTo(self.point()),
+            QUAD_TO => PathEl::QuadTo(self.point(), self.point()),
+            CURVE_TO => PathEl::CurveTo(self.point(), self.point(), self.point()),
+            _ => PathEl::ClosePath,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.verbs.size_hint()
+    }
+}
+
+impl ExactSizeIterator for CompactPathIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompactPathError, CompactPaths, Quantization};
+    use crate::kurbo::{BezPath, Circle, PathEl, Point, Rect, Shape};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn paths_round_trip_within_a_step() {
+        let bounds = Rect::new(-180.0, -90.0, 180.0, 90.0);
+        let quantization = Quantization::for_bounds(bounds);
+        let mut paths = CompactPaths::new(quantization);
+        let circle = Circle::new((-100.0, 40.0), 45.5).to_path(0.01);
+        let mut square = Rect::new(170.0, -90.0, 180.0, 90.0).to_path(0.1);
+        square.quad_to((0.0, 0.0), (-180.0, 90.0));
+
+        let a = paths.push(&circle).unwrap();
+        let b = paths.push(&square).unwrap();
+        assert_eq!(paths.len(), 2);
+
+        for (id, original) in [(a, &circle), (b, &square)] {
+            let decoded: Vec<_> = paths.get(id).elements().collect();
+            assert_eq!(decoded.len(), original.elements().len());
+            for (decoded, original) in decoded.iter().zip(original.elements()) {
+                let points = |el: &PathEl| match *el {
+                    PathEl::MoveTo(p) | PathEl::LineTo(p) => [p, p, p],
+                    PathEl::QuadTo(p1, p2) => [p1, p2, p2],
+                    PathEl::CurveTo(p1, p2, p3) => [p1, p2, p3],
+                    PathEl::ClosePath => [Point::ZERO; 3],
+                };
+                assert_eq!(
+                    core::mem::discriminant(decoded),
+                    core::mem::discriminant(original)
+                );
+                for (d, o) in points(decoded).into_iter().zip(points(original)) {
+                    assert!((d - o).hypot() <= quantization.step, "{d:?} != {o:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn encoding_is_compact() {
+        let mut paths = CompactPaths::new(Quantization::for_bounds(Rect::new(0., 0., 1e3, 1e3)));
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        for i in 1..1000 {
+            path.line_to((f64::from(i), f64::from(i % 7) * 100.0));
+        }
+        path.close_path();
+        paths.push(&path).unwrap();
+
+        let bez_path_size = size_of_val(path.elements());
+        assert!(paths.encoded_size() * 10 < bez_path_size);
+    }
+
+    #[test]
+    fn out_of_range_paths_are_rejected() {
+        let mut paths = CompactPaths::new(Quantization::new(Point::ZERO, 1.0));
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        path.line_to((40_000.0, 0.0));
+
+        assert_eq!(paths.push(&path), Err(CompactPathError::OutOfRange));
+        assert!(paths.is_empty());
+        assert_eq!(paths.encoded_size(), 0);
+
+        // The largest deltas still round-trip exactly.
+        let mut path = BezPath::new();
+        path.move_to((-32768.0, 32767.0));
+        path.line_to((32767.0, -32768.0));
+        let id = paths.push(&path).unwrap();
+        let mut decoded = BezPath::new();
+        paths.get(id).decode_into(&mut decoded);
+        assert_eq!(decoded, path);
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Paints that are defined outside of the renderers.
+//!
+//! The built-in paints cover solid colors, gradients and images. For anything else, like a
+//! procedural wood grain texture, a [`CustomPaintKind`] describes how to evaluate a family of
+//! paints, both on the CPU and as a WGSL function for the GPU. Kinds are registered with the
+//! [`CustomPaintRegistry`] of a renderer, which returns the [`CustomPaintId`] that
+//! [`CustomPaint`]s refer to.
+//!
+//! The WGSL source of a kind is the body of a function with the signature
+//! `fn(pos: vec2<f32>, params: u32) -> vec4<f32>`. It returns the premultiplied color at `pos`,
+//! in the coordinate space of the paint, and can read the parameters of the paint with
+//! `custom_paint_param(params, i)`.
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::hash::{BuildHasher, Hasher};
+
+use hashbrown::DefaultHashBuilder;
+
+use crate::encode::EncodedPaint;
+use crate::kurbo::{Affine, Point};
+use crate::paint::{IndexedPaint, Paint};
+use crate::peniko::color::palette::css::TRANSPARENT;
+
+/// The CPU and GPU implementation of a family of custom paints.
+pub trait CustomPaintKind: Debug + Send + Sync {
+    /// Return the premultiplied RGBA color of the paint with `params` at `pos`, in the
+    /// coordinate space of the paint.
+    fn evaluate(&self, params: &[f32], pos: Point) -> [f32; 4];
+
+    /// Return the body of the WGSL function that evaluates the paint on the GPU.
+    ///
+    /// See the [module documentation](self) for its signature.
+    fn wgsl(&self) -> &str;
+
+    /// Return whether the paint with `params` can produce colors that aren't opaque.
+    fn may_have_transparency(&self, _params: &[f32]) -> bool {
+        true
+    }
+
+    /// Feed everything about `params` that affects the colors of the paint into `state`.
+    ///
+    /// Consecutive draws with paints of the same kind, transform and cache key share a
+    /// single encoded paint. By default, all parameters are hashed bitwise.
+    fn cache_key(&self, params: &[f32], state: &mut dyn Hasher) {
+        for param in params {
+            state.write_u32(param.to_bits());
+        }
+    }
+}
+
+/// The identifier of a [`CustomPaintKind`] in a [`CustomPaintRegistry`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CustomPaintId(u32);
+
+impl CustomPaintId {
+    /// Return the index of the kind in its registry.
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+/// The custom paint kinds that a renderer knows about.
+#[derive(Debug, Clone, Default)]
+pub struct CustomPaintRegistry {
+    kinds: Vec<Arc<dyn CustomPaintKind>>,
+    hasher: DefaultHashBuilder,
+}
+
+impl CustomPaintRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a paint kind and return its identifier.
+    ///
+    /// Identifiers are assigned in order, so registries that register the same kinds in the
+    /// same order agree on them.
+    pub fn register(&mut self, kind: Arc<dyn CustomPaintKind>) -> CustomPaintId {
+        let id = CustomPaintId(u32::try_from(self.kinds.len()).unwrap());
+        self.kinds.push(kind);
+        id
+    }
+
+    /// Return the kind with the given identifier.
+    pub fn get(&self, id: CustomPaintId) -> Option<&Arc<dyn CustomPaintKind>> {
+        self.kinds.get(id.0 as usize)
+    }
+
+    /// Return all registered kinds in the order of their identifiers.
+    pub fn kinds(&self) -> impl Iterator<Item = (CustomPaintId, &Arc<dyn CustomPaintKind>)> {
+        (0..).map(CustomPaintId).zip(&self.kinds)
+    }
+
+    /// Return the number of registered kinds.
+    pub fn len(&self) -> usize {
+        self.kinds.len()
+    }
+
+    /// Return whether no kinds are registered.
+    pub fn is_empty(&self) -> bool {
+        self.kinds.is_empty()
+    }
+}
+
+/// A paint of a registered [`CustomPaintKind`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomPaint {
+    /// The kind of the paint.
+    pub kind: CustomPaintId,
+    /// The parameters that are passed to the kind.
+    pub params: Arc<[f32]>,
+}
+
+impl CustomPaint {
+    /// Create a new custom paint.
+    pub fn new(kind: CustomPaintId, params: impl Into<Arc<[f32]>>) -> Self {
+        Self {
+            kind,
+            params: params.into(),
+        }
+    }
+
+    /// Encode the paint with the given paint transform and push it into `paints`.
+    ///
+    /// If the last encoded paint is the same custom paint, it is reused instead. Paints of
+    /// kinds that aren't in `registry` are encoded as transparent.
+    pub fn encode_into(
+        &self,
+        registry: &CustomPaintRegistry,
+        paints: &mut Vec<EncodedPaint>,
+        transform: Affine,
+    ) -> Paint {
+        let Some(kind) = registry.get(self.kind) else {
+            log::warn!("custom paint {:?} is not registered", self.kind);
+            return TRANSPARENT.into();
+        };
+
+        let mut hasher = registry.hasher.build_hasher();
+        kind.cache_key(&self.params, &mut hasher);
+        let cache_key = hasher.finish();
+        let transform = transform.inverse();
+
+        if let Some(EncodedPaint::Custom(last)) = paints.last()
+            && last.id == self.kind
+            && last.cache_key == cache_key
+            && last.transform == transform
+        {
+            return Paint::Indexed(IndexedPaint::new(paints.len() - 1));
+        }
+
+        let idx = paints.len();
+        paints.push(EncodedPaint::Custom(EncodedCustomPaint {
+            id: self.kind,
+            kind: kind.clone(),
+            params: self.params.clone(),
+            transform,
+            may_have_transparency: kind.may_have_transparency(&self.params),
+            cache_key,
+        }));
+
+        Paint::Indexed(IndexedPaint::new(idx))
+    }
+}
+
+/// An encoded custom paint.
+#[derive(Debug)]
+pub struct EncodedCustomPaint {
+    /// The identifier of the kind of the paint.
+    pub id: CustomPaintId,
+    /// The kind of the paint.
+    pub kind: Arc<dyn CustomPaintKind>,
+    /// The parameters of the paint.
+    pub params: Arc<[f32]>,
+    /// The transform from scene coordinates to the coordinate space of the paint.
+    pub transform: Affine,
+    /// Whether the paint can produce colors that aren't opaque.
+    pub may_have_transparency: bool,
+    /// The hash of the parameters, see [`CustomPaintKind::cache_key`].
+    pub cache_key: u64,
+}
+
+impl EncodedCustomPaint {
+    /// Return the color of the paint at `pos`, in scene coordinates.
+    pub fn evaluate(&self, pos: Point) -> [f32; 4] {
+        self.kind.evaluate(&self.params, self.transform * pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CustomPaint, CustomPaintKind, CustomPaintRegistry};
+    use crate::encode::EncodedPaint;
+    use crate::kurbo::{Affine, Point};
+    use crate::paint::Paint;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+
+    #[derive(Debug)]
+    struct Stripes;
+
+    impl CustomPaintKind for Stripes {
+        fn evaluate(&self, params: &[f32], pos: Point) -> [f32; 4] {
+            let on = (pos.x as f32 / params[0]).rem_euclid(2.0) < 1.0;
+            if on { [1.0; 4] } else { [0.0; 4] }
+        }
+
+        fn wgsl(&self) -> &str {
+            "return select(vec4(0.0), vec4(1.0), (pos.x / custom_paint_param(params, 0u)) % 2.0 < 1.0);"
+        }
+    }
+
+    #[test]
+    fn consecutive_equal_paints_share_an_encoding() {
+        let mut registry = CustomPaintRegistry::new();
+        let stripes = registry.register(Arc::new(Stripes));
+        let mut paints = Vec::new();
+
+        let paint = CustomPaint::new(stripes, [4.0]);
+        let first = paint.encode_into(&registry, &mut paints, Affine::IDENTITY);
+        let second = paint.encode_into(&registry, &mut paints, Affine::IDENTITY);
+        assert_eq!(first, second);
+        assert_eq!(paints.len(), 1);
+
+        CustomPaint::new(stripes, [8.0]).encode_into(&registry, &mut paints, Affine::IDENTITY);
+        paint.encode_into(&registry, &mut paints, Affine::translate((1.0, 0.0)));
+        assert_eq!(paints.len(), 3);
+
+        let EncodedPaint::Custom(encoded) = &paints[2] else {
+            panic!("expected a custom paint");
+        };
+        assert_eq!(encoded.evaluate(Point::new(4.5, 0.0)), [1.0; 4]);
+        assert_eq!(encoded.evaluate(Point::new(0.5, 0.0)), [0.0; 4]);
+    }
+
+    #[test]
+    fn unregistered_paints_are_transparent() {
+        let mut registry = CustomPaintRegistry::new();
+        let stripes = registry.register(Arc::new(Stripes));
+        let mut paints = Vec::new();
+
+        let paint = CustomPaint::new(stripes, [4.0]);
+        let encoded = paint.encode_into(&CustomPaintRegistry::new(), &mut paints, Affine::IDENTITY);
+        assert!(matches!(encoded, Paint::Solid(color) if color.as_premul_rgba8().a == 0));
+        assert!(paints.is_empty());
+    }
+}
@@ -0,0 +1,404 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Dashing of paths with cached segment lengths.
+//!
+//! Strokes with a dash pattern are dashed during stroke expansion, which measures the arc
+//! length of every segment of the path each time the path is drawn. When the same path is
+//! drawn over and over with different dash offsets (for example for the "marching ants" of
+//! a selection marquee), a [`MeasuredPath`] allows measuring the path once and cutting it
+//! into dashes cheaply for every offset.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::kurbo::{
+    BezPath, CubicBez, Line, ParamCurve, ParamCurveArclen, PathEl, PathSeg, Point, QuadBez,
+};
+
+/// The accuracy used to measure the segments of a path, matching the accuracy `kurbo` uses
+/// for dashing.
+const ACCURACY: f64 = 1e-6;
+
+/// The parameter range of the piece of a segment emitted for a zero-length dash.
+const DOT_PARAM: f64 = 1e-6;
+
+#[derive(Clone, Debug)]
+struct Subpath {
+    segments: Range<usize>,
+    closed: bool,
+}
+
+/// A path whose segment lengths have been measured, so that it can be dashed with different
+/// dash offsets without measuring it again.
+///
+/// Like dashed strokes, dashing restarts at the beginning of each subpath, and the last and
+/// the first dash of a closed subpath are joined if they meet at its start.
+#[derive(Clone, Debug, Default)]
+pub struct MeasuredPath {
+    segments: Vec<PathSeg>,
+    lengths: Vec<f64>,
+    subpaths: Vec<Subpath>,
+}
+
+impl MeasuredPath {
+    /// Measure a path.
+    pub fn new(path: impl IntoIterator<Item = PathEl>) -> Self {
+        let mut measured = Self::default();
+        let mut first = 0;
+        let mut start = Point::ZERO;
+        let mut last = Point::ZERO;
+
+        for el in path {
+            match el {
+                PathEl::MoveTo(p) => {
+                    measured.finish_subpath(first, false);
+                    first = measured.segments.len();
+                    start = p;
+                    last = p;
+                }
+                PathEl::LineTo(p) => {
+                    measured.push(PathSeg::Line(Line::new(last, p)));
+                    last = p;
+                }
+                PathEl::QuadTo(p1, p2) => {
+                    measured.push(PathSeg::Quad(QuadBez::new(last, p1, p2)));
+                    last = p2;
+                }
+                PathEl::CurveTo(p1, p2, p3) => {
+                    measured.push(PathSeg::Cubic(CubicBez::new(last, p1, p2, p3)));
+                    last = p3;
+                }
+                PathEl::ClosePath => {
+                    if last != start {
+                        measured.push(PathSeg::Line(Line::new(last, start)));
+                    }
+                    measured.finish_subpath(first, true);
+                    first = measured.segments.len();
+                    last = start;
+                }
+            }
+        }
+        measured.finish_subpath(first, false);
+
+        measured
+    }
+
+    /// The total length of the path.
+    pub fn length(&self) -> f64 {
+        self.lengths.iter().sum()
+    }
+
+    /// Cut the path into dashes, replacing the contents of `out`.
+    ///
+    /// The dash pattern and offset have the same meaning as for a
+    /// [`Stroke`](crate::kurbo::Stroke). If the pattern is empty, has negative or non-finite
+    /// entries or only zeros, the whole path is written to `out`.
+    pub fn dash(&self, offset: f64, pattern: &[f64], out: &mut BezPath) {
+        out.truncate(0);
+
+        let Some(dash) = DashState::new(offset, pattern) else {
+            for subpath in &self.subpaths {
+                self.append_subpath(subpath, out);
+            }
+            return;
+        };
+
+        let mut first_dash = BezPath::new();
+        for subpath in &self.subpaths {
+            self.dash_subpath(subpath, dash, out, &mut first_dash);
+        }
+    }
+
+    fn push(&mut self, seg: PathSeg) {
+        self.lengths.push(seg.arclen(ACCURACY));
+        self.segments.push(seg);
+    }
+
+    fn finish_subpath(&mut self, first: usize, closed: bool) {
+        if first < self.segments.len() {
+            self.subpaths.push(Subpath {
+                segments: first..self.segments.len(),
+                closed,
+            });
+        }
+    }
+
+    fn append_subpath(&self, subpath: &Subpath, out: &mut BezPath) {
+        let segments = &self.segments[subpath.segments.clone()];
+        out.move_to(segments[0].start());
+        out.extend(segments.iter().map(PathSeg::as_path_el));
+        if subpath.closed {
+            out.close_path();
+        }
+    }
+
+    fn dash_subpath(
+        &self,
+        subpath: &Subpath,
+        mut dash: DashState<'_>,
+        out: &mut BezPath,
+        first_dash: &mut BezPath,
+    ) {
+        // The first dash of a closed subpath is kept separately, since the last dash may
+        // continue into it.
+        first_dash.truncate(0);
+        let starts_on = dash.on;
+        let mut in_first_dash = starts_on && subpath.closed;
+        let mut toggled = false;
+        let mut pen_down = false;
+
+        for idx in subpath.segments.clone() {
+            let seg = self.segments[idx];
+            let len = self.lengths[idx];
+            let mut pos = 0.0;
+
+            loop {
+                let sink = if in_first_dash {
+                    &mut *first_dash
+                } else {
+                    &mut *out
+                };
+                let end = pos + dash.remaining;
+                if end >= len {
+                    // The current interval of the pattern extends into the next segment.
+                    if dash.on {
+                        emit(sink, seg, len, pos..len, &mut pen_down);
+                    }
+                    dash.remaining = end - len;
+                    break;
+                }
+
+                if dash.on {
+                    emit(sink, seg, len, pos..end, &mut pen_down);
+                    pen_down = false;
+                    in_first_dash = false;
+                }
+                pos = end;
+                dash.advance();
+                toggled = true;
+            }
+        }
+
+        if !toggled {
+            // The whole subpath is covered by a single interval of the pattern.
+            out.extend(first_dash.iter());
+            if starts_on && subpath.closed {
+                out.close_path();
+            }
+        } else if subpath.closed && starts_on && dash.on && pen_down {
+            // Join the last dash with the first one, skipping the move of the first dash.
+            out.extend(first_dash.iter().skip(1));
+        } else {
+            out.extend(first_dash.iter());
+        }
+    }
+}
+
+/// Append the part of `seg` between the arc lengths in `range` to `out`.
+fn emit(out: &mut BezPath, seg: PathSeg, len: f64, range: Range<f64>, pen_down: &mut bool) {
+    let mut t = param(seg, len, range.start)..param(seg, len, range.end);
+    if range.start == range.end {
+        if *pen_down {
+            return;
+        }
+        // A zero-length dash still gets caps. Since the stroker drops segments without
+        // length, emit a tiny piece of the segment which gives the caps their direction.
+        t = if t.start < 1.0 {
+            t.start..(t.start + DOT_PARAM).min(1.0)
+        } else {
+            1.0 - DOT_PARAM..1.0
+        };
+    }
+
+    let piece = seg.subsegment(t);
+    if !*pen_down {
+        out.move_to(piece.start());
+        *pen_down = true;
+    }
+    out.push(piece.as_path_el());
+}
+
+/// The parameter of the point at arc length `s` of `seg`.
+fn param(seg: PathSeg, len: f64, s: f64) -> f64 {
+    if s <= 0.0 || len <= 0.0 {
+        0.0
+    } else if s >= len {
+        1.0
+    } else if let PathSeg::Line(_) = seg {
+        s / len
+    } else {
+        seg.inv_arclen(s, ACCURACY)
+    }
+}
+
+/// The position within a dash pattern.
+#[derive(Clone, Copy, Debug)]
+struct DashState<'a> {
+    pattern: &'a [f64],
+    idx: usize,
+    on: bool,
+    /// The length left in the current interval of the pattern.
+    remaining: f64,
+}
+
+impl<'a> DashState<'a> {
+    fn new(offset: f64, pattern: &'a [f64]) -> Option<Self> {
+        if pattern.iter().any(|d| !d.is_finite() || *d < 0.0) {
+            return None;
+        }
+        let sum: f64 = pattern.iter().sum();
+        if sum <= 0.0 {
+            return None;
+        }
+
+        // Patterns with an odd number of entries are repeated to get an even number.
+        let period = if pattern.len() % 2 == 1 {
+            2.0 * sum
+        } else {
+            sum
+        };
+        let mut offset = if offset.is_finite() {
+            offset % period
+        } else {
+            0.0
+        };
+        if offset < 0.0 {
+            offset += period;
+        }
+
+        let mut state = Self {
+            pattern,
+            idx: 0,
+            on: true,
+            remaining: pattern[0],
+        };
+        while offset >= state.remaining {
+            offset -= state.remaining;
+            state.advance();
+        }
+        state.remaining -= offset;
+
+        Some(state)
+    }
+
+    fn advance(&mut self) {
+        self.idx = (self.idx + 1) % self.pattern.len();
+        self.on = !self.on;
+        self.remaining = self.pattern[self.idx];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kurbo::{Rect, Shape};
+
+    fn dash(path: &MeasuredPath, offset: f64, pattern: &[f64]) -> Vec<PathEl> {
+        let mut out = BezPath::new();
+        path.dash(offset, pattern, &mut out);
+        out.elements().to_vec()
+    }
+
+    fn line(x0: f64, x1: f64) -> MeasuredPath {
+        let mut path = BezPath::new();
+        path.move_to((x0, 0.0));
+        path.line_to((x1, 0.0));
+        MeasuredPath::new(&path)
+    }
+
+    #[test]
+    fn dashes_follow_the_offset() {
+        let path = line(0.0, 10.0);
+        assert_eq!(path.length(), 10.0);
+
+        assert_eq!(
+            dash(&path, 0.0, &[2.0, 3.0]),
+            [
+                PathEl::MoveTo((0.0, 0.0).into()),
+                PathEl::LineTo((2.0, 0.0).into()),
+                PathEl::MoveTo((5.0, 0.0).into()),
+                PathEl::LineTo((7.0, 0.0).into()),
+            ]
+        );
+        // Negative offsets are wrapped into the pattern.
+        for offset in [1.0, -4.0] {
+            assert_eq!(
+                dash(&path, offset, &[2.0, 3.0]),
+                [
+                    PathEl::MoveTo((0.0, 0.0).into()),
+                    PathEl::LineTo((1.0, 0.0).into()),
+                    PathEl::MoveTo((4.0, 0.0).into()),
+                    PathEl::LineTo((6.0, 0.0).into()),
+                    PathEl::MoveTo((9.0, 0.0).into()),
+                    PathEl::LineTo((10.0, 0.0).into()),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn odd_patterns_are_repeated() {
+        let path = line(0.0, 4.0);
+        assert_eq!(
+            dash(&path, 0.0, &[1.0]),
+            [
+                PathEl::MoveTo((0.0, 0.0).into()),
+                PathEl::LineTo((1.0, 0.0).into()),
+                PathEl::MoveTo((2.0, 0.0).into()),
+                PathEl::LineTo((3.0, 0.0).into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_patterns_keep_the_path() {
+        let path = line(0.0, 4.0);
+        let solid = [
+            PathEl::MoveTo((0.0, 0.0).into()),
+            PathEl::LineTo((4.0, 0.0).into()),
+        ];
+        assert_eq!(dash(&path, 0.0, &[]), solid);
+        assert_eq!(dash(&path, 0.0, &[0.0, 0.0]), solid);
+        assert_eq!(dash(&path, 0.0, &[1.0, -1.0]), solid);
+    }
+
+    #[test]
+    fn dashes_of_closed_subpaths_are_joined_at_the_start() {
+        let rect = MeasuredPath::new(Rect::new(0.0, 0.0, 10.0, 10.0).path_elements(0.1));
+        assert_eq!(rect.length(), 40.0);
+
+        // The dash from 35 to 25 crosses the start of the rectangle.
+        let dashed = dash(&rect, 5.0, &[30.0, 10.0]);
+        let moves = dashed
+            .iter()
+            .filter(|el| matches!(el, PathEl::MoveTo(_)))
+            .count();
+        assert_eq!(moves, 1);
+        assert_eq!(dashed[0], PathEl::MoveTo((0.0, 5.0).into()));
+        assert_eq!(dashed.last(), Some(&PathEl::LineTo((5.0, 10.0).into())));
+
+        // A dash covering the whole rectangle keeps it closed.
+        assert_eq!(
+            dash(&rect, 0.0, &[50.0, 10.0]).last(),
+            Some(&PathEl::ClosePath)
+        );
+    }
+
+    #[test]
+    fn dashes_continue_across_segments() {
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        path.line_to((2.0, 0.0));
+        path.quad_to((3.0, 0.0), (4.0, 0.0));
+
+        let dashed = dash(&MeasuredPath::new(&path), 0.0, &[3.0, 1.0]);
+        assert_eq!(dashed.len(), 3);
+        assert_eq!(dashed[0], PathEl::MoveTo((0.0, 0.0).into()));
+        assert_eq!(dashed[1], PathEl::LineTo((2.0, 0.0).into()));
+        let PathEl::QuadTo(_, end) = dashed[2] else {
+            panic!("expected the dash to continue with a quad");
+        };
+        assert!((end.x - 3.0).abs() < 1e-6);
+    }
+}
@@ -7,13 +7,16 @@ use crate::TextureId;
 use crate::blurred_rounded_rect::BlurredRoundedRectangle;
 use crate::color::palette::css::BLACK;
 use crate::color::{ColorSpaceTag, HueDirection, Srgb, gradient};
+use crate::custom_paint::EncodedCustomPaint;
 use crate::geometry::RectU16;
+use crate::gradient_stops::{GradientStopArena, InternedStops};
 use crate::kurbo::{Affine, Point, Vec2};
 use crate::math::{FloatExt, compute_erf7};
 use crate::paint::{Image, ImageSource, IndexedPaint, Paint, PremulColor, Tint};
 use crate::peniko::{ColorStop, ColorStops, Extend, Gradient, GradientKind, ImageQuality};
 use alloc::borrow::Cow;
 use alloc::fmt::Debug;
+use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
 #[cfg(not(feature = "multithreading"))]
@@ -30,6 +33,8 @@ use smallvec::ToSmallVec;
 // So we can just use `OnceCell` regardless of which feature is activated.
 #[cfg(feature = "multithreading")]
 use std::sync::OnceLock as OnceCell;
+#[cfg(feature = "std")]
+use {hashbrown::HashMap, std::sync::Mutex};
 
 use crate::simd::{Splat4thExt, element_wise_splat};
 #[cfg(not(feature = "std"))]
@@ -70,183 +75,271 @@ impl EncodeExt for Gradient {
         transform: Affine,
         _tint: Option<Tint>,
     ) -> Paint {
-        // First make sure that the gradient is valid and not degenerate.
-        if let Err(paint) = validate(self) {
-            return paint;
+        encode_gradient(self, &GradientHints::default(), paints, transform)
+    }
+}
+
+/// Interpolation hints between the color stops of a gradient, like the color hints of CSS
+/// gradients.
+///
+/// The hint at index `i` lies between the color stops `i` and `i + 1` and is given as an offset
+/// along the gradient, like the offsets of the stops. At the hint, the color is halfway between
+/// the colors of the two stops, so moving the hint towards one of the stops skews the
+/// transition towards it. Hints are clamped to the range between their stops, and missing
+/// hints default to the midpoint, which is the same as a linear transition.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GradientHints(Option<Arc<[Option<f32>]>>);
+
+impl GradientHints {
+    /// Create hints for the color stops of a gradient.
+    pub fn new(hints: &[Option<f32>]) -> Self {
+        if hints.iter().all(Option::is_none) {
+            Self::default()
+        } else {
+            Self(Some(hints.into()))
         }
+    }
 
-        let mut may_have_transparency = self.stops.iter().any(|s| s.color.components[3] != 1.0);
+    /// Whether there are no hints, so all transitions are linear.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
 
-        let mut base_transform;
+    /// Return the hint between the color stops `index` and `index + 1`.
+    pub fn get(&self, index: usize) -> Option<f32> {
+        self.as_slice().get(index).copied().flatten()
+    }
 
-        let mut stops = Cow::Borrowed(&self.stops.0);
+    /// Return the hints.
+    pub fn as_slice(&self) -> &[Option<f32>] {
+        self.0.as_deref().unwrap_or_default()
+    }
+}
 
-        let first_stop = &stops[0];
-        let last_stop = &stops[stops.len() - 1];
+/// Encode a gradient with interpolation hints into a paint.
+///
+/// This is the same as [`EncodeExt::encode_into`] for the gradient, but the transitions between
+/// the color stops follow the given hints.
+pub fn encode_gradient(
+    gradient: &Gradient,
+    hints: &GradientHints,
+    paints: &mut Vec<EncodedPaint>,
+    transform: Affine,
+) -> Paint {
+    encode_gradient_with_stops(gradient, hints, paints, transform, |stops| {
+        InternedStops::new(stops.clone())
+    })
+}
 
-        if first_stop.offset != 0.0 || last_stop.offset != 1.0 {
-            let mut vec = stops.to_smallvec();
+/// Encode a gradient with interpolation hints into a paint, interning its color stops in
+/// `arena`.
+///
+/// This is the same as [`encode_gradient`], but renderers that keep an arena across frames
+/// don't clone the stops of gradients they have encoded before, and the resulting
+/// [`GradientCacheKey`]s are cheaper to hash and compare.
+pub fn encode_gradient_interned(
+    gradient: &Gradient,
+    hints: &GradientHints,
+    arena: &mut GradientStopArena,
+    paints: &mut Vec<EncodedPaint>,
+    transform: Affine,
+) -> Paint {
+    encode_gradient_with_stops(gradient, hints, paints, transform, |stops| {
+        arena.intern(stops)
+    })
+}
 
-            if first_stop.offset != 0.0 {
-                let mut first_stop = *first_stop;
-                first_stop.offset = 0.0;
-                vec.insert(0, first_stop);
-            }
+fn encode_gradient_with_stops(
+    gradient: &Gradient,
+    hints: &GradientHints,
+    paints: &mut Vec<EncodedPaint>,
+    transform: Affine,
+    intern: impl FnOnce(&ColorStops) -> InternedStops,
+) -> Paint {
+    // First make sure that the gradient is valid and not degenerate.
+    if let Err(paint) = validate(gradient) {
+        return paint;
+    }
 
-            if last_stop.offset != 1.0 {
-                let mut last_stop = *last_stop;
-                last_stop.offset = 1.0;
-                vec.push(last_stop);
-            }
+    let mut may_have_transparency = gradient.stops.iter().any(|s| s.color.components[3] != 1.0);
+
+    let mut base_transform;
+
+    let mut stops = Cow::Borrowed(&gradient.stops.0);
+    // The hint of the transition between each pair of adjacent stops.
+    let mut stop_hints: Vec<Option<f32>> = (0..stops.len() - 1).map(|i| hints.get(i)).collect();
+
+    let first_stop = &stops[0];
+    let last_stop = &stops[stops.len() - 1];
 
-            stops = Cow::Owned(vec);
+    if first_stop.offset != 0.0 || last_stop.offset != 1.0 {
+        let mut vec = stops.to_smallvec();
+
+        if first_stop.offset != 0.0 {
+            let mut first_stop = *first_stop;
+            first_stop.offset = 0.0;
+            vec.insert(0, first_stop);
+            stop_hints.insert(0, None);
         }
 
-        let kind = match self.kind {
-            GradientKind::Linear(LinearGradientPosition { start: p0, end: p1 }) => {
-                // We update the transform currently in-place, such that the gradient line always
-                // starts at the point (0, 0) and ends at the point (1, 0). This simplifies the
-                // calculation for the current position along the gradient line a lot.
-                base_transform = ts_from_line_to_line(p0, p1, Point::ZERO, Point::new(1.0, 0.0));
+        if last_stop.offset != 1.0 {
+            let mut last_stop = *last_stop;
+            last_stop.offset = 1.0;
+            vec.push(last_stop);
+            stop_hints.push(None);
+        }
 
-                EncodedKind::Linear(LinearKind)
-            }
-            GradientKind::Radial(RadialGradientPosition {
-                start_center: c0,
-                start_radius: r0,
-                end_center: c1,
-                end_radius: r1,
-            }) => {
-                // The implementation of radial gradients is translated from Skia.
-                // See:
-                // - <https://skia.org/docs/dev/design/conical/>
-                // - <https://github.com/google/skia/blob/main/src/shaders/gradients/SkConicalGradient.h>
-                // - <https://github.com/google/skia/blob/main/src/shaders/gradients/SkConicalGradient.cpp>
-                let d_radius = r1 - r0;
-
-                // <https://github.com/google/skia/blob/1e07a4b16973cf716cb40b72dd969e961f4dd950/src/shaders/gradients/SkConicalGradient.cpp#L83-L112>
-                let radial_kind = if ((c1 - c0).length() as f32).is_nearly_zero() {
-                    base_transform = Affine::translate((-c1.x, -c1.y));
-                    base_transform = base_transform.then_scale(1.0 / r0.max(r1) as f64);
-
-                    let scale = r1.max(r0) / d_radius;
-                    let bias = -r0 / d_radius;
-
-                    RadialKind::Radial { bias, scale }
-                } else {
-                    base_transform =
-                        ts_from_line_to_line(c0, c1, Point::ZERO, Point::new(1.0, 0.0));
-
-                    if (r1 - r0).is_nearly_zero() {
-                        let scaled_r0 = r1 / (c1 - c0).length() as f32;
-                        RadialKind::Strip {
-                            scaled_r0_squared: scaled_r0 * scaled_r0,
-                        }
-                    } else {
-                        let d_center = (c0 - c1).length() as f32;
-
-                        let focal_data =
-                            FocalData::create(r0 / d_center, r1 / d_center, &mut base_transform);
-
-                        let fp0 = 1.0 / focal_data.fr1;
-                        let fp1 = focal_data.f_focal_x;
-
-                        RadialKind::Focal {
-                            focal_data,
-                            fp0,
-                            fp1,
-                        }
+        stops = Cow::Owned(vec);
+    }
+
+    let kind = match gradient.kind {
+        GradientKind::Linear(LinearGradientPosition { start: p0, end: p1 }) => {
+            // We update the transform currently in-place, such that the gradient line always
+            // starts at the point (0, 0) and ends at the point (1, 0). This simplifies the
+            // calculation for the current position along the gradient line a lot.
+            base_transform = ts_from_line_to_line(p0, p1, Point::ZERO, Point::new(1.0, 0.0));
+
+            EncodedKind::Linear(LinearKind)
+        }
+        GradientKind::Radial(RadialGradientPosition {
+            start_center: c0,
+            start_radius: r0,
+            end_center: c1,
+            end_radius: r1,
+        }) => {
+            // The implementation of radial gradients is translated from Skia.
+            // See:
+            // - <https://skia.org/docs/dev/design/conical/>
+            // - <https://github.com/google/skia/blob/main/src/shaders/gradients/SkConicalGradient.h>
+            // - <https://github.com/google/skia/blob/main/src/shaders/gradients/SkConicalGradient.cpp>
+            let d_radius = r1 - r0;
+
+            // <https://github.com/google/skia/blob/1e07a4b16973cf716cb40b72dd969e961f4dd950/src/shaders/gradients/SkConicalGradient.cpp#L83-L112>
+            let radial_kind = if ((c1 - c0).length() as f32).is_nearly_zero() {
+                base_transform = Affine::translate((-c1.x, -c1.y));
+                base_transform = base_transform.then_scale(1.0 / r0.max(r1) as f64);
+
+                let scale = r1.max(r0) / d_radius;
+                let bias = -r0 / d_radius;
+
+                RadialKind::Radial { bias, scale }
+            } else {
+                base_transform = ts_from_line_to_line(c0, c1, Point::ZERO, Point::new(1.0, 0.0));
+
+                if (r1 - r0).is_nearly_zero() {
+                    let scaled_r0 = r1 / (c1 - c0).length() as f32;
+                    RadialKind::Strip {
+                        scaled_r0_squared: scaled_r0 * scaled_r0,
                     }
-                };
+                } else {
+                    let d_center = (c0 - c1).length() as f32;
 
-                // Even if the gradient has no stops with transparency, we might have to force
-                // alpha-compositing in case the radial gradient is undefined in certain positions,
-                // in which case the resulting color will be transparent and thus the gradient overall
-                // must be treated as non-opaque.
-                may_have_transparency |= radial_kind.has_undefined();
+                    let focal_data =
+                        FocalData::create(r0 / d_center, r1 / d_center, &mut base_transform);
 
-                EncodedKind::Radial(radial_kind)
-            }
-            GradientKind::Sweep(SweepGradientPosition {
-                center,
-                start_angle,
-                end_angle,
-            }) => {
-                // Make sure the center of the gradient falls on the origin (0, 0), to make
-                // angle calculation easier.
-                let x_offset = -center.x as f32;
-                let y_offset = -center.y as f32;
-                base_transform = Affine::translate((x_offset as f64, y_offset as f64));
-
-                EncodedKind::Sweep(SweepKind {
-                    start_angle,
-                    // Save the inverse so that we can use a multiplication in the shader instead.
-                    inv_angle_delta: 1.0 / (end_angle - start_angle),
-                })
-            }
-        };
+                    let fp0 = 1.0 / focal_data.fr1;
+                    let fp1 = focal_data.f_focal_x;
 
-        let ranges = encode_stops(
-            &stops,
-            self.interpolation_cs,
-            self.hue_direction,
-            self.interpolation_alpha_space,
-        );
+                    RadialKind::Focal {
+                        focal_data,
+                        fp0,
+                        fp1,
+                    }
+                }
+            };
 
-        // This represents the transform that needs to be applied to the starting point of a
-        // command before starting with the rendering.
-        // First we need to account for the base transform of the shader, then
-        // we need to apply the _inverse_ paint transform to the point so that we can account
-        // for the paint transform of the render context.
-        let transform = base_transform * transform.inverse();
-
-        // One possible approach of calculating the positions would be to apply the above
-        // transform to _each_ pixel that we render in the wide tile. However, a much better
-        // approach is to apply the transform once for the first pixel in each wide tile,
-        // and from then on only apply incremental updates to the current x/y position
-        // that we calculate based on the transform.
-        //
-        // Remember that we render wide tiles in column major order (i.e. we first calculate the
-        // values for a specific x for all Tile::HEIGHT y by incrementing y by 1, and then finally
-        // we increment the x position by 1 and start from the beginning). If we want to implement
-        // the above approach of incrementally updating the position, we need to calculate
-        // how the x/y unit vectors are affected by the transform, and then use this as the
-        // step delta for a step in the x/y direction.
-        let (x_advance, y_advance) = x_y_advances(&transform);
+            // Even if the gradient has no stops with transparency, we might have to force
+            // alpha-compositing in case the radial gradient is undefined in certain positions,
+            // in which case the resulting color will be transparent and thus the gradient overall
+            // must be treated as non-opaque.
+            may_have_transparency |= radial_kind.has_undefined();
 
-        let cache_key = CacheKey(GradientCacheKey {
-            stops: self.stops.clone(),
-            interpolation_cs: self.interpolation_cs,
-            hue_direction: self.hue_direction,
-        });
+            EncodedKind::Radial(radial_kind)
+        }
+        GradientKind::Sweep(SweepGradientPosition {
+            center,
+            start_angle,
+            end_angle,
+        }) => {
+            // Make sure the center of the gradient falls on the origin (0, 0), to make
+            // angle calculation easier.
+            let x_offset = -center.x as f32;
+            let y_offset = -center.y as f32;
+            base_transform = Affine::translate((x_offset as f64, y_offset as f64));
 
-        let has_undefined = kind.has_undefined();
+            EncodedKind::Sweep(SweepKind {
+                start_angle,
+                // Save the inverse so that we can use a multiplication in the shader instead.
+                inv_angle_delta: 1.0 / (end_angle - start_angle),
+            })
+        }
+    };
 
-        let encoded = EncodedGradient {
-            cache_key,
-            kind,
-            has_undefined,
-            transform,
-            x_advance,
-            y_advance,
-            ranges,
-            extend: self.extend,
-            may_have_transparency,
-            u8_lut: OnceCell::new(),
-            f32_lut: OnceCell::new(),
-        };
+    let ranges = encode_stops(
+        &stops,
+        &stop_hints,
+        gradient.interpolation_cs,
+        gradient.hue_direction,
+        gradient.interpolation_alpha_space,
+    );
+
+    // This represents the transform that needs to be applied to the starting point of a
+    // command before starting with the rendering.
+    // First we need to account for the base transform of the shader, then
+    // we need to apply the _inverse_ paint transform to the point so that we can account
+    // for the paint transform of the render context.
+    let transform = base_transform * transform.inverse();
+
+    // One possible approach of calculating the positions would be to apply the above
+    // transform to _each_ pixel that we render in the wide tile. However, a much better
+    // approach is to apply the transform once for the first pixel in each wide tile,
+    // and from then on only apply incremental updates to the current x/y position
+    // that we calculate based on the transform.
+    //
+    // Remember that we render wide tiles in column major order (i.e. we first calculate the
+    // values for a specific x for all Tile::HEIGHT y by incrementing y by 1, and then finally
+    // we increment the x position by 1 and start from the beginning). If we want to implement
+    // the above approach of incrementally updating the position, we need to calculate
+    // how the x/y unit vectors are affected by the transform, and then use this as the
+    // step delta for a step in the x/y direction.
+    let (x_advance, y_advance) = x_y_advances(&transform);
+
+    let cache_key = CacheKey(GradientCacheKey {
+        stops: intern(&gradient.stops),
+        hints: hints.clone(),
+        interpolation_cs: gradient.interpolation_cs,
+        hue_direction: gradient.hue_direction,
+        max_lut_size: MAX_GRADIENT_LUT_SIZE,
+    });
+
+    let has_undefined = kind.has_undefined();
+
+    let encoded = EncodedGradient {
+        cache_key,
+        kind,
+        has_undefined,
+        transform,
+        x_advance,
+        y_advance,
+        ranges,
+        extend: gradient.extend,
+        may_have_transparency,
+        u8_lut: OnceCell::new(),
+        f32_lut: OnceCell::new(),
+        #[cfg(feature = "std")]
+        lut_cache: None,
+    };
 
-        let idx = paints.len();
-        paints.push(encoded.into());
+    let idx = paints.len();
+    paints.push(encoded.into());
 
-        Paint::Indexed(IndexedPaint::new(idx))
-    }
+    Paint::Indexed(IndexedPaint::new(idx))
 }
 
 /// Returns a fallback paint in case the gradient is invalid.
 ///
 /// The paint will be either black or contain the color of the first stop of the gradient.
-fn validate(gradient: &Gradient) -> Result<(), Paint> {
+pub(crate) fn validate(gradient: &Gradient) -> Result<(), Paint> {
     let black = Err(BLACK.into());
 
     // Gradients need at least two stops.
@@ -335,6 +428,7 @@ fn validate(gradient: &Gradient) -> Result<(), Paint> {
 /// Encode all stops into a sequence of ranges.
 fn encode_stops(
     stops: &[ColorStop],
+    hints: &[Option<f32>],
     cs: ColorSpaceTag,
     hue_dir: HueDirection,
     interpolation_alpha_space: InterpolationAlphaSpace,
@@ -343,9 +437,18 @@ fn encode_stops(
     struct EncodedColorStop {
         offset: f32,
         color: crate::color::AlphaColor<Srgb>,
+        /// The index of the pair of original stops this stop was interpolated between.
+        pair: usize,
     }
 
-    let create_range = |left_stop: &EncodedColorStop, right_stop: &EncodedColorStop| {
+    let hint = |i: usize| {
+        let (left_stop, right_stop) = (&stops[i], &stops[i + 1]);
+        hints[i].and_then(|hint| GradientHint::new(left_stop.offset, right_stop.offset, hint))
+    };
+
+    let create_range = |left_stop: &EncodedColorStop,
+                        right_stop: &EncodedColorStop,
+                        hint: Option<GradientHint>| {
         let clamp = |mut color: [f32; 4]| {
             // The linear approximation of the gradient can produce values slightly outside of
             // [0.0, 1.0], so clamp them.
@@ -388,6 +491,7 @@ fn encode_stops(
             bias,
             scale,
             interpolation_alpha_space,
+            hint,
         }
     };
 
@@ -399,23 +503,26 @@ fn encode_stops(
         {
             stops
                 .windows(2)
-                .flat_map(|s| {
+                .enumerate()
+                .flat_map(|(pair, s)| {
                     let left_stop = &s[0];
                     let right_stop = &s[1];
 
                     let interpolated =
                         gradient::<Srgb>(left_stop.color, right_stop.color, cs, hue_dir, 0.01);
 
-                    interpolated.map(|st| EncodedColorStop {
+                    interpolated.map(move |st| EncodedColorStop {
                         offset: left_stop.offset + (right_stop.offset - left_stop.offset) * st.0,
                         color: st.1.un_premultiply(),
+                        pair,
                     })
                 })
                 .collect::<Vec<_>>()
         } else {
             stops
                 .windows(2)
-                .flat_map(|s| {
+                .enumerate()
+                .flat_map(|(pair, s)| {
                     let left_stop = &s[0];
                     let right_stop = &s[1];
 
@@ -427,9 +534,10 @@ fn encode_stops(
                         0.01,
                     );
 
-                    interpolated.map(|st| EncodedColorStop {
+                    interpolated.map(move |st| EncodedColorStop {
                         offset: left_stop.offset + (right_stop.offset - left_stop.offset) * st.0,
                         color: st.1,
+                        pair,
                     })
                 })
                 .collect::<Vec<_>>()
@@ -440,25 +548,32 @@ fn encode_stops(
             .map(|s| {
                 let left_stop = &s[0];
                 let right_stop = &s[1];
+                // Ranges that connect two pairs of stops have a length of zero.
+                let hint = (left_stop.pair == right_stop.pair)
+                    .then(|| hint(left_stop.pair))
+                    .flatten();
 
-                create_range(left_stop, right_stop)
+                create_range(left_stop, right_stop, hint)
             })
             .collect()
     } else {
         stops
             .windows(2)
-            .map(|c| {
+            .enumerate()
+            .map(|(pair, c)| {
                 let c0 = EncodedColorStop {
                     offset: c[0].offset,
                     color: c[0].color.to_alpha_color::<Srgb>(),
+                    pair,
                 };
 
                 let c1 = EncodedColorStop {
                     offset: c[1].offset,
                     color: c[1].color.to_alpha_color::<Srgb>(),
+                    pair,
                 };
 
-                create_range(&c0, &c1)
+                create_range(&c0, &c1, hint(pair))
             })
             .collect()
     }
@@ -546,6 +661,8 @@ pub enum EncodedPaint {
     ExternalTexture(EncodedExternalTexture),
     /// A blurred, rounded rectangle.
     BlurredRoundedRect(EncodedBlurredRoundedRectangle),
+    /// A custom paint.
+    Custom(EncodedCustomPaint),
 }
 
 impl From<EncodedGradient> for EncodedPaint {
@@ -579,6 +696,40 @@ pub struct EncodedImage {
     pub tint: Option<Tint>,
 }
 
+impl EncodedImage {
+    /// Return the level of detail at which the image is sampled.
+    ///
+    /// This is the base-2 logarithm of the number of image pixels covered by one device
+    /// pixel along the more strongly minified axis. Values of zero or below mean that the
+    /// image isn't minified.
+    pub fn mip_lod(&self) -> f32 {
+        let scale = self.x_advance.length().max(self.y_advance.length());
+        (scale as f32).log2()
+    }
+
+    /// Return a copy of this paint that samples a downscaled version of the image instead.
+    ///
+    /// `base_size` is the size of the image the paint was encoded for, and `level_size`
+    /// the size of the downscaled version (for example a level of a
+    /// [`MipChain`](crate::mipmap::MipChain)). This also works for other sizes, which is
+    /// used to stretch the second image of a [`CrossFade`](crate::paint::CrossFade).
+    pub fn for_mip_level(&self, base_size: (u16, u16), level_size: (u16, u16)) -> Self {
+        let sx = f64::from(level_size.0) / f64::from(base_size.0);
+        let sy = f64::from(level_size.1) / f64::from(base_size.1);
+        let scale = |v: Vec2| Vec2::new(v.x * sx, v.y * sy);
+
+        Self {
+            source: self.source.clone(),
+            sampler: self.sampler,
+            may_have_transparency: self.may_have_transparency,
+            transform: Affine::scale_non_uniform(sx, sy) * self.transform,
+            x_advance: scale(self.x_advance),
+            y_advance: scale(self.y_advance),
+            tint: self.tint,
+        }
+    }
+}
+
 /// An encoded external texture.
 ///
 /// The texture must be bound by the user at render-time in order for us to be able to sample from
@@ -772,21 +923,179 @@ pub struct EncodedGradient {
     pub extend: Extend,
     /// Whether the gradient requires `source_over` compositing.
     pub may_have_transparency: bool,
-    u8_lut: OnceCell<GradientLut<u8>>,
-    f32_lut: OnceCell<GradientLut<f32>>,
+    u8_lut: OnceCell<Arc<GradientLut<u8>>>,
+    f32_lut: OnceCell<Arc<GradientLut<f32>>>,
+    #[cfg(feature = "std")]
+    lut_cache: Option<GradientLutCache>,
 }
 
 impl EncodedGradient {
     /// Get the lookup table for sampling u8-based gradient values.
     pub fn u8_lut<S: Simd>(&self, simd: S) -> &GradientLut<u8> {
-        self.u8_lut
-            .get_or_init(|| GradientLut::new(simd, &self.ranges))
+        self.u8_lut.get_or_init(|| {
+            #[cfg(feature = "std")]
+            if let Some(cache) = &self.lut_cache {
+                return cache.u8_lut(simd, self);
+            }
+
+            Arc::new(GradientLut::new(simd, &self.ranges, self.max_lut_size()))
+        })
     }
 
     /// Get the lookup table for sampling f32-based gradient values.
     pub fn f32_lut<S: Simd>(&self, simd: S) -> &GradientLut<f32> {
-        self.f32_lut
-            .get_or_init(|| GradientLut::new(simd, &self.ranges))
+        self.f32_lut.get_or_init(|| {
+            #[cfg(feature = "std")]
+            if let Some(cache) = &self.lut_cache {
+                return cache.f32_lut(simd, self);
+            }
+
+            Arc::new(GradientLut::new(simd, &self.ranges, self.max_lut_size()))
+        })
+    }
+
+    /// Limit the number of entries in the lookup tables of this gradient, which is at most
+    /// [`MAX_GRADIENT_LUT_SIZE`] by default.
+    ///
+    /// Smaller tables are faster to generate, but steep transitions between stops become
+    /// blurry. This has no effect on lookup tables that have already been created.
+    pub fn set_max_lut_size(&mut self, max_lut_size: usize) {
+        self.cache_key.0.max_lut_size = max_lut_size.clamp(2, MAX_GRADIENT_LUT_SIZE);
+    }
+
+    /// Return the maximum number of entries in the lookup tables of this gradient.
+    pub fn max_lut_size(&self) -> usize {
+        self.cache_key.0.max_lut_size
+    }
+
+    /// Resolve the lookup tables of this gradient through `cache` instead of
+    /// generating them for this gradient alone.
+    ///
+    /// This has no effect on lookup tables that have already been created.
+    #[cfg(feature = "std")]
+    pub fn set_lut_cache(&mut self, cache: GradientLutCache) {
+        self.lut_cache = Some(cache);
+    }
+}
+
+/// A cache of gradient lookup tables that can be shared between renderers.
+///
+/// Lookup tables only depend on the color-affecting properties of a gradient (see
+/// [`GradientCacheKey`]), so gradients that only differ in their geometry or transform
+/// can share the same table. Cloning the cache is cheap and yields a handle to the same
+/// storage, which makes it possible to hand one cache to several renderers (for example
+/// `vello_hybrid` for the main view and `vello_cpu` for exporting thumbnails), so that
+/// each gradient ramp is only computed once.
+///
+/// Entries are never evicted automatically; call [`trim`](Self::trim) periodically
+/// (for example once per frame) to bound the size of the cache.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Default)]
+pub struct GradientLutCache {
+    inner: Arc<Mutex<LutCacheInner>>,
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+struct LutCacheInner {
+    /// Incremented on every access, used for LRU eviction.
+    epoch: u64,
+    entries: HashMap<CacheKey<GradientCacheKey>, CachedLuts>,
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+struct CachedLuts {
+    u8_lut: Option<Arc<GradientLut<u8>>>,
+    f32_lut: Option<Arc<GradientLut<f32>>>,
+    last_used: u64,
+}
+
+#[cfg(feature = "std")]
+impl GradientLutCache {
+    /// Create a new, empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the u8 lookup table for `gradient`, generating it if it isn't cached yet.
+    pub fn u8_lut<S: Simd>(&self, simd: S, gradient: &EncodedGradient) -> Arc<GradientLut<u8>> {
+        self.get_or_insert(
+            gradient,
+            |e| &mut e.u8_lut,
+            || GradientLut::new(simd, &gradient.ranges, gradient.max_lut_size()),
+        )
+    }
+
+    /// Get the f32 lookup table for `gradient`, generating it if it isn't cached yet.
+    pub fn f32_lut<S: Simd>(&self, simd: S, gradient: &EncodedGradient) -> Arc<GradientLut<f32>> {
+        self.get_or_insert(
+            gradient,
+            |e| &mut e.f32_lut,
+            || GradientLut::new(simd, &gradient.ranges, gradient.max_lut_size()),
+        )
+    }
+
+    /// Return the number of cached gradient ramps.
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().entries.len()
+    }
+
+    /// Return whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Evict the least recently used gradient ramps until at most `max_entries` remain.
+    ///
+    /// Lookup tables that are still referenced by an encoded gradient stay alive until that
+    /// gradient is dropped.
+    pub fn trim(&self, max_entries: usize) {
+        let mut inner = self.inner.lock().unwrap();
+        let excess = inner.entries.len().saturating_sub(max_entries);
+
+        if excess == 0 {
+            return;
+        }
+
+        let mut last_used = inner
+            .entries
+            .values()
+            .map(|e| e.last_used)
+            .collect::<Vec<_>>();
+        last_used.sort_unstable();
+        // Epochs are unique, so this removes exactly `excess` entries.
+        let threshold = last_used[excess - 1];
+        inner.entries.retain(|_, e| e.last_used > threshold);
+    }
+
+    /// Remove all cached gradient ramps.
+    pub fn clear(&self) {
+        self.inner.lock().unwrap().entries.clear();
+    }
+
+    fn get_or_insert<T: FromF32Color>(
+        &self,
+        gradient: &EncodedGradient,
+        slot: impl FnOnce(&mut CachedLuts) -> &mut Option<Arc<GradientLut<T>>>,
+        create: impl FnOnce() -> GradientLut<T>,
+    ) -> Arc<GradientLut<T>> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.epoch += 1;
+        let epoch = inner.epoch;
+
+        if !inner.entries.contains_key(&gradient.cache_key) {
+            inner
+                .entries
+                .insert(gradient.cache_key.clone(), CachedLuts::default());
+        }
+
+        let entry = inner.entries.get_mut(&gradient.cache_key).unwrap();
+        entry.last_used = epoch;
+
+        slot(entry)
+            .get_or_insert_with(|| Arc::new(create()))
+            .clone()
     }
 }
 
@@ -794,26 +1103,42 @@ impl EncodedGradient {
 #[derive(Debug, Clone)]
 pub struct GradientCacheKey {
     /// The color stops (offsets + colors).
-    pub stops: ColorStops,
+    pub stops: InternedStops,
+    /// The interpolation hints between the color stops.
+    pub hints: GradientHints,
     /// Color space used for interpolation.
     pub interpolation_cs: ColorSpaceTag,
     /// Hue direction used for interpolation.
     pub hue_direction: HueDirection,
+    /// The maximum number of entries in the lookup table.
+    pub max_lut_size: usize,
 }
 
 impl BitHash for GradientCacheKey {
     fn bit_hash<H: Hasher>(&self, state: &mut H) {
         self.stops.bit_hash(state);
+        for hint in self.hints.as_slice() {
+            hint.map(f32::to_bits).hash(state);
+        }
         core::mem::discriminant(&self.interpolation_cs).hash(state);
         core::mem::discriminant(&self.hue_direction).hash(state);
+        self.max_lut_size.hash(state);
     }
 }
 
 impl BitEq for GradientCacheKey {
     fn bit_eq(&self, other: &Self) -> bool {
         self.stops.bit_eq(&other.stops)
+            && self.hints.as_slice().len() == other.hints.as_slice().len()
+            && self
+                .hints
+                .as_slice()
+                .iter()
+                .zip(other.hints.as_slice())
+                .all(|(a, b)| a.map(f32::to_bits) == b.map(f32::to_bits))
             && self.interpolation_cs == other.interpolation_cs
             && self.hue_direction == other.hue_direction
+            && self.max_lut_size == other.max_lut_size
     }
 }
 
@@ -830,6 +1155,51 @@ pub struct GradientRange {
     pub scale: [f32; 4],
     /// The alpha space in which the interpolation was performed.
     pub interpolation_alpha_space: InterpolationAlphaSpace,
+    /// The interpolation hint of the two color stops the range lies between, if the
+    /// transition between them isn't linear.
+    pub hint: Option<GradientHint>,
+}
+
+/// An interpolation hint that skews the transition between two color stops.
+///
+/// A position `x` between the stops is mapped to `x0 + (x1 - x0) * t.powf(exponent)`, where
+/// `t` is the relative position of `x` between `x0` and `x1`, before the color is evaluated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradientHint {
+    /// The offset of the first stop.
+    pub x0: f32,
+    /// The offset of the second stop.
+    pub x1: f32,
+    /// The exponent applied to the relative position between the stops.
+    pub exponent: f32,
+}
+
+impl GradientHint {
+    /// Create the hint for the transition between two stops, with the color halfway between
+    /// them at offset `hint`.
+    ///
+    /// Returns `None` if the transition is linear.
+    fn new(x0: f32, x1: f32, hint: f32) -> Option<Self> {
+        if x1 <= x0 || hint.is_nan() {
+            return None;
+        }
+
+        // The relative position `h` of the hint is mapped to 0.5, which is `h^exponent`.
+        let h = ((hint - x0) / (x1 - x0)).clamp(0.0, 1.0);
+        let exponent = if h >= 1.0 {
+            f32::INFINITY
+        } else {
+            0.5_f32.ln() / h.ln()
+        };
+
+        (exponent != 1.0).then_some(Self { x0, x1, exponent })
+    }
+
+    /// Map a position between the stops to the position at which the color is evaluated.
+    fn apply(self, x: f32) -> f32 {
+        let t = ((x - self.x0) / (self.x1 - self.x0)).clamp(0.0, 1.0);
+        self.x0 + (self.x1 - self.x0) * t.powf(self.exponent)
+    }
 }
 
 /// An encoded blurred, rounded rectangle.
@@ -1014,9 +1384,9 @@ pub struct GradientLut<T: FromF32Color> {
 }
 
 impl<T: FromF32Color> GradientLut<T> {
-    /// Create a new lookup table.
-    fn new<S: Simd>(simd: S, ranges: &[GradientRange]) -> Self {
-        let lut_size = determine_lut_size(ranges);
+    /// Create a new lookup table for the given color ranges, with at most `max_size` entries.
+    pub fn new<S: Simd>(simd: S, ranges: &[GradientRange], max_size: usize) -> Self {
+        let lut_size = determine_lut_size(ranges).min(max_size);
         let mut lut = vec![[T::ZERO; 4]; lut_size];
 
         // Calculate how many indices are covered by each range.
@@ -1039,7 +1409,24 @@ impl<T: FromF32Color> GradientLut<T> {
         let inv_lut_scale = f32x4::splat(simd, 1.0 / scale);
         let add_factor = f32x4::from_slice(simd, &[0.0, 1.0, 2.0, 3.0]) * inv_lut_scale;
 
-        for (ramp_range, range) in ramps {
+        for (index, (ramp_range, range)) in ramps.into_iter().enumerate() {
+            if let Some(hint) = range.hint {
+                // The transition isn't linear, so every entry is evaluated separately at the
+                // position the hint maps it to, which can lie in any range between the same two
+                // stops.
+                let same_pair = |r: &&GradientRange| r.hint == range.hint;
+                let start = index - ranges[..index].iter().rev().take_while(same_pair).count();
+                let end = index + ranges[index..].iter().take_while(same_pair).count();
+                let pair = &ranges[start..end];
+
+                for idx in ramp_range {
+                    let x = hint.apply(idx as f32 / scale);
+                    let range = &pair[pair.partition_point(|r| r.x1 < x).min(pair.len() - 1)];
+                    lut[idx] = T::from_f32(evaluate_range(simd, range, x));
+                }
+                continue;
+            }
+
             let biases = f32x16::block_splat(f32x4::from_slice(simd, &range.bias));
             let scales = f32x16::block_splat(f32x4::from_slice(simd, &range.scale));
 
@@ -1105,6 +1492,24 @@ impl<T: FromF32Color> GradientLut<T> {
     }
 }
 
+/// Evaluate the premultiplied color of a range at position `x`.
+fn evaluate_range<S: Simd>(simd: S, range: &GradientRange, x: f32) -> f32x4<S> {
+    let mut color: [f32; 4] = core::array::from_fn(|i| range.bias[i] + x * range.scale[i]);
+
+    let alpha = color[3];
+    if range.interpolation_alpha_space == InterpolationAlphaSpace::Unpremultiplied {
+        for c in &mut color[..3] {
+            *c *= alpha;
+        }
+    }
+    // See the comment in `GradientLut::new`.
+    for c in &mut color {
+        *c = c.min(1.0).min(alpha);
+    }
+
+    f32x4::from_slice(simd, &color)
+}
+
 /// The maximum size of the gradient LUT.
 // Of course in theory we could still have a stop at 0.0001 in which case this resolution
 // wouldn't be enough, but for all intents and purposes this should be more than sufficient
@@ -1122,6 +1527,12 @@ fn determine_lut_size(ranges: &[GradientRange]) -> usize {
         2 => 512,
         _ => 1024,
     };
+    // Hinted transitions can be much steeper than linear ones.
+    let stop_len = if ranges.iter().any(|r| r.hint.is_some()) {
+        stop_len.max(1024)
+    } else {
+        stop_len
+    };
 
     // In case we have some tricky stops (for example 3 stops with 0.0, 0.001, 1.0), we might
     // increase the resolution.
@@ -1151,7 +1562,7 @@ mod private {
 
 #[cfg(test)]
 mod tests {
-    use super::{EncodeExt, Gradient};
+    use super::{EncodeExt, Gradient, GradientHints, encode_gradient};
     use crate::color::DynamicColor;
     use crate::color::palette::css::{BLACK, BLUE, GREEN};
     use crate::kurbo::{Affine, Point};
@@ -1351,4 +1762,217 @@ mod tests {
             GREEN.into()
         );
     }
+
+    #[cfg(feature = "std")]
+    fn encode_linear(
+        buf: &mut vec::Vec<super::EncodedPaint>,
+        end: f64,
+        stops: ColorStops,
+    ) -> &super::EncodedGradient {
+        let gradient = Gradient {
+            kind: LinearGradientPosition {
+                start: Point::new(0.0, 0.0),
+                end: Point::new(end, 0.0),
+            }
+            .into(),
+            stops,
+            ..Default::default()
+        };
+        gradient.encode_into(buf, Affine::IDENTITY, None);
+
+        match buf.last().unwrap() {
+            super::EncodedPaint::Gradient(g) => g,
+            _ => unreachable!(),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn two_stops(from: crate::color::AlphaColor<crate::color::Srgb>) -> ColorStops {
+        ColorStops(smallvec![
+            ColorStop {
+                offset: 0.0,
+                color: DynamicColor::from_alpha_color(from),
+            },
+            ColorStop {
+                offset: 1.0,
+                color: DynamicColor::from_alpha_color(BLUE),
+            },
+        ])
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn lut_cache_shares_ramps() {
+        use super::GradientLutCache;
+        use alloc::sync::Arc;
+        use fearless_simd::Fallback;
+
+        let simd = Fallback::new();
+        let cache = GradientLutCache::new();
+
+        let mut a = vec![];
+        let mut b = vec![];
+        // Different geometry, same colors.
+        let a = encode_linear(&mut a, 20.0, two_stops(GREEN));
+        let b = encode_linear(&mut b, 50.0, two_stops(GREEN));
+
+        let lut_a = cache.u8_lut(simd, a);
+        let lut_b = cache.u8_lut(simd, b);
+        assert!(Arc::ptr_eq(&lut_a, &lut_b));
+        assert_eq!(lut_a.lut(), a.u8_lut(simd).lut());
+        assert_eq!(cache.len(), 1);
+
+        // The f32 table of the same ramp lives in the same entry.
+        let _ = cache.f32_lut(simd, a);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn lut_cache_is_used_by_encoded_gradient() {
+        use super::{EncodedPaint, GradientLutCache};
+        use fearless_simd::Fallback;
+
+        let simd = Fallback::new();
+        let cache = GradientLutCache::new();
+
+        let mut buf = vec![];
+        let _ = encode_linear(&mut buf, 20.0, two_stops(GREEN));
+        let EncodedPaint::Gradient(gradient) = buf.last_mut().unwrap() else {
+            unreachable!()
+        };
+        gradient.set_lut_cache(cache.clone());
+        let from_gradient = gradient.u8_lut(simd).lut().as_ptr();
+
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.u8_lut(simd, gradient).lut().as_ptr(), from_gradient);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn lut_cache_trim_evicts_least_recently_used() {
+        use super::GradientLutCache;
+        use fearless_simd::Fallback;
+
+        let simd = Fallback::new();
+        let cache = GradientLutCache::new();
+
+        let mut bufs = [vec![], vec![], vec![]];
+        let [a, b, c] = &mut bufs;
+        let a = encode_linear(a, 20.0, two_stops(GREEN));
+        let b = encode_linear(b, 20.0, two_stops(BLACK));
+        let c = encode_linear(c, 20.0, two_stops(BLUE));
+
+        let _ = cache.u8_lut(simd, a);
+        let _ = cache.u8_lut(simd, b);
+        let _ = cache.u8_lut(simd, c);
+        // Touch `a` again, so that `b` is now the least recently used entry.
+        let lut_a = cache.u8_lut(simd, a);
+        assert_eq!(cache.len(), 3);
+
+        cache.trim(2);
+        assert_eq!(cache.len(), 2);
+        assert!(alloc::sync::Arc::ptr_eq(&lut_a, &cache.u8_lut(simd, a)));
+        let _ = cache.u8_lut(simd, b);
+        assert_eq!(cache.len(), 3);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    fn encode_hinted<'a>(
+        buf: &'a mut vec::Vec<super::EncodedPaint>,
+        hints: &[Option<f32>],
+    ) -> &'a super::EncodedGradient {
+        let gradient = Gradient {
+            kind: LinearGradientPosition {
+                start: Point::new(0.0, 0.0),
+                end: Point::new(20.0, 0.0),
+            }
+            .into(),
+            stops: ColorStops(smallvec![
+                ColorStop {
+                    offset: 0.0,
+                    color: DynamicColor::from_alpha_color(GREEN),
+                },
+                ColorStop {
+                    offset: 1.0,
+                    color: DynamicColor::from_alpha_color(BLUE),
+                },
+            ]),
+            ..Default::default()
+        };
+        encode_gradient(&gradient, &GradientHints::new(hints), buf, Affine::IDENTITY);
+
+        match buf.last().unwrap() {
+            super::EncodedPaint::Gradient(g) => g,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn gradient_hint_is_halfway_between_stops() {
+        use fearless_simd::Fallback;
+
+        let simd = Fallback::new();
+        let mut buf = vec![];
+        let lut = encode_hinted(&mut buf, &[Some(0.25)]).f32_lut(simd);
+
+        let [r, g, b, a] = lut.get((0.25 * lut.scale_factor()).round() as usize);
+        let green = GREEN.components[1];
+        for (actual, expected) in [r, g, b, a].into_iter().zip([0.0, green / 2.0, 0.5, 1.0]) {
+            assert!((actual - expected).abs() < 0.01, "{actual} != {expected}");
+        }
+    }
+
+    #[test]
+    fn gradient_hint_at_midpoint_is_linear() {
+        use fearless_simd::Fallback;
+
+        let simd = Fallback::new();
+        let (mut a, mut b) = (vec![], vec![]);
+        let hinted = encode_hinted(&mut a, &[Some(0.5)]);
+        let linear = encode_hinted(&mut b, &[]);
+
+        assert_eq!(hinted.u8_lut(simd).lut(), linear.u8_lut(simd).lut());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn lut_cache_distinguishes_hints() {
+        use super::GradientLutCache;
+        use fearless_simd::Fallback;
+
+        let simd = Fallback::new();
+        let cache = GradientLutCache::new();
+
+        let (mut a, mut b, mut c) = (vec![], vec![], vec![]);
+        let _ = cache.u8_lut(simd, encode_hinted(&mut a, &[Some(0.25)]));
+        let _ = cache.u8_lut(simd, encode_hinted(&mut b, &[Some(0.75)]));
+        let _ = cache.u8_lut(simd, encode_hinted(&mut c, &[Some(0.25)]));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn lut_cache_distinguishes_max_lut_sizes() {
+        use super::{EncodedPaint, GradientLutCache};
+        use fearless_simd::Fallback;
+
+        let simd = Fallback::new();
+        let cache = GradientLutCache::new();
+
+        let (mut full, mut small) = (vec![], vec![]);
+        let full = encode_linear(&mut full, 20.0, two_stops(GREEN));
+        let _ = encode_linear(&mut small, 20.0, two_stops(GREEN));
+        let EncodedPaint::Gradient(small) = small.last_mut().unwrap() else {
+            unreachable!()
+        };
+        small.set_max_lut_size(64);
+
+        assert_eq!(cache.u8_lut(simd, full).width(), 256);
+        assert_eq!(cache.u8_lut(simd, small).width(), 64);
+        assert_eq!(small.u8_lut(simd).width(), 64);
+        assert_eq!(cache.len(), 2);
+    }
 }
@@ -0,0 +1,132 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Running the work of multi-threaded rendering on threads that the application controls.
+//!
+//! By default, the renderers create their own thread pool when they are asked to use worker
+//! threads. Applications that already manage their threads, for example with a pool whose
+//! threads are pinned to specific cores, can instead implement [`Executor`] for their pool
+//! and hand it to the renderer, which then doesn't spawn any threads of its own.
+//!
+//! [`Executor`] is implemented for [`rayon::ThreadPool`], so an existing rayon pool can be
+//! used as it is.
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+/// A unit of work that is run by an [`Executor`].
+pub type Task<'a> = Box<dyn FnOnce() + Send + 'a>;
+
+/// Runs the tasks of multi-threaded rendering.
+pub trait Executor: Debug + Send + Sync {
+    /// The number of threads that the tasks are spread across.
+    ///
+    /// Renderers split their work into about this many parts.
+    fn num_threads(&self) -> usize;
+
+    /// Run `task` in the background and return without waiting for it.
+    ///
+    /// The task doesn't wait for the renderer to hand it more work, so the threads can be
+    /// shared with other renderers and other work. Tasks that can't start right away may be
+    /// queued until a thread becomes available.
+    fn spawn(&self, task: Task<'static>);
+
+    /// Run all of `tasks`, possibly in parallel, and return once all of them have finished.
+    ///
+    /// Unlike [`spawn`](Self::spawn), the tasks never block, so they may also run on the
+    /// calling thread.
+    fn join(&self, tasks: Vec<Task<'_>>);
+}
+
+impl<E: Executor + ?Sized> Executor for Arc<E> {
+    fn num_threads(&self) -> usize {
+        (**self).num_threads()
+    }
+
+    fn spawn(&self, task: Task<'static>) {
+        (**self).spawn(task);
+    }
+
+    fn join(&self, tasks: Vec<Task<'_>>) {
+        (**self).join(tasks);
+    }
+}
+
+impl Executor for rayon::ThreadPool {
+    fn num_threads(&self) -> usize {
+        self.current_num_threads()
+    }
+
+    fn spawn(&self, task: Task<'static>) {
+        rayon::ThreadPool::spawn(self, task);
+    }
+
+    fn join(&self, tasks: Vec<Task<'_>>) {
+        self.scope(|scope| {
+            for task in tasks {
+                scope.spawn(move |_| task());
+            }
+        });
+    }
+}
+
+/// Create a thread pool with `num_threads` threads for the renderers to use by default.
+///
+/// # Panics
+///
+/// Panics if the threads can't be spawned, for example on WebAssembly targets without
+/// threading support.
+pub fn thread_pool(num_threads: u16) -> Arc<dyn Executor> {
+    Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(usize::from(num_threads))
+            .build()
+            .unwrap(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Executor, Task, thread_pool};
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+    use std::sync::mpsc;
+
+    #[test]
+    fn join_runs_all_tasks() {
+        let executor = thread_pool(3);
+        assert_eq!(executor.num_threads(), 3);
+
+        let mut results = [0_u32; 16];
+        let tasks: Vec<Task<'_>> = results
+            .iter_mut()
+            .zip(1..)
+            .map(|(result, value)| Box::new(move || *result = value * value) as Task<'_>)
+            .collect();
+        executor.join(tasks);
+
+        assert!(
+            results
+                .iter()
+                .zip(1_u32..)
+                .all(|(result, value)| *result == value * value)
+        );
+    }
+
+    #[test]
+    fn spawned_tasks_run_in_the_background() {
+        let executor = thread_pool(2);
+        let (sender, receiver) = mpsc::channel();
+        for i in 0..4 {
+            let sender = sender.clone();
+            executor.spawn(Box::new(move || sender.send(i).unwrap()));
+        }
+        drop(sender);
+
+        let mut received: Vec<_> = receiver.iter().collect();
+        received.sort_unstable();
+        assert_eq!(received, [0, 1, 2, 3]);
+    }
+}
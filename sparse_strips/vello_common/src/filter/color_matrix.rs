@@ -0,0 +1,39 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The color matrix filter.
+
+/// A color matrix filter.
+#[derive(Clone, Copy, Debug)]
+pub struct ColorMatrix {
+    /// The 4x5 matrix in row-major order, see
+    /// [`FilterPrimitive::ColorMatrix`](crate::filter_effects::FilterPrimitive::ColorMatrix).
+    pub matrix: [f32; 20],
+}
+
+impl ColorMatrix {
+    /// Create a new color matrix filter.
+    pub fn new(matrix: [f32; 20]) -> Self {
+        Self { matrix }
+    }
+
+    /// Apply the matrix to a premultiplied color with components in the range [0, 1].
+    ///
+    /// The color is unpremultiplied before applying the matrix, and the clamped result is
+    /// premultiplied again.
+    #[inline]
+    pub fn apply(&self, premul: [f32; 4]) -> [f32; 4] {
+        let a = premul[3];
+        let inv_a = if a > 0.0 { 1.0 / a } else { 0.0 };
+        let src = [premul[0] * inv_a, premul[1] * inv_a, premul[2] * inv_a, a];
+
+        let mut out = [0.0; 4];
+        for (channel, row) in out.iter_mut().zip(self.matrix.chunks_exact(5)) {
+            let value = row[0] * src[0] + row[1] * src[1] + row[2] * src[2] + row[3] * src[3];
+            *channel = (value + row[4]).clamp(0.0, 1.0);
+        }
+
+        let alpha = out[3];
+        [out[0] * alpha, out[1] * alpha, out[2] * alpha, alpha]
+    }
+}
@@ -4,7 +4,9 @@
 //! The drop shadow filter.
 
 use crate::color::{AlphaColor, Srgb};
-use crate::filter::gaussian_blur::{MAX_KERNEL_SIZE, plan_decimated_blur, transform_blur_params};
+use crate::filter::gaussian_blur::{
+    MAX_KERNEL_SIZE, plan_decimated_blur_with_max_kernel_size, transform_blur_params,
+};
 use crate::filter::transform_offset_params;
 use crate::filter_effects::EdgeMode;
 use crate::kurbo::Affine;
@@ -41,9 +43,22 @@ impl DropShadow {
         std_deviation: f32,
         edge_mode: EdgeMode,
         color: AlphaColor<Srgb>,
+    ) -> Self {
+        Self::with_max_kernel_size(dx, dy, std_deviation, edge_mode, color, MAX_KERNEL_SIZE)
+    }
+
+    /// Create a new drop shadow filter whose blur kernel has at most `max_kernel_size` taps.
+    pub fn with_max_kernel_size(
+        dx: f32,
+        dy: f32,
+        std_deviation: f32,
+        edge_mode: EdgeMode,
+        color: AlphaColor<Srgb>,
+        max_kernel_size: usize,
     ) -> Self {
         // Precompute blur plan (same logic as GaussianBlur::new)
-        let (n_decimations, kernel, kernel_size) = plan_decimated_blur(std_deviation);
+        let (n_decimations, kernel, kernel_size) =
+            plan_decimated_blur_with_max_kernel_size(std_deviation, max_kernel_size);
 
         Self {
             dx,
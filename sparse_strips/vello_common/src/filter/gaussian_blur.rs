@@ -71,7 +71,19 @@ impl GaussianBlur {
     ///
     /// This precomputes the decimation plan, kernel, and radius for optimal performance.
     pub fn new(std_deviation: f32, edge_mode: EdgeMode) -> Self {
-        let (n_decimations, kernel, kernel_size) = plan_decimated_blur(std_deviation);
+        Self::with_max_kernel_size(std_deviation, edge_mode, MAX_KERNEL_SIZE)
+    }
+
+    /// Create a new Gaussian blur filter whose kernel has at most `max_kernel_size` taps.
+    ///
+    /// See [`plan_decimated_blur_with_max_kernel_size`].
+    pub fn with_max_kernel_size(
+        std_deviation: f32,
+        edge_mode: EdgeMode,
+        max_kernel_size: usize,
+    ) -> Self {
+        let (n_decimations, kernel, kernel_size) =
+            plan_decimated_blur_with_max_kernel_size(std_deviation, max_kernel_size);
 
         Self {
             std_deviation,
@@ -90,6 +102,18 @@ impl GaussianBlur {
 /// - `kernel`: Pre-computed Gaussian kernel weights (fixed-size array)
 /// - `kernel_size`: Actual length of the kernel (rest is zero-padded)
 pub fn plan_decimated_blur(std_deviation: f32) -> (usize, [f32; MAX_KERNEL_SIZE], u8) {
+    plan_decimated_blur_with_max_kernel_size(std_deviation, MAX_KERNEL_SIZE)
+}
+
+/// Compute the blur execution plan like [`plan_decimated_blur`], but with a kernel of at most
+/// `max_kernel_size` taps.
+///
+/// Shorter kernels are faster to apply, but cut off more of the tails of the Gaussian, which
+/// makes large blurs look boxier.
+pub fn plan_decimated_blur_with_max_kernel_size(
+    std_deviation: f32,
+    max_kernel_size: usize,
+) -> (usize, [f32; MAX_KERNEL_SIZE], u8) {
     if std_deviation <= 0.0 {
         // Invalid standard deviation, return identity kernel (no blur)
         let mut kernel = [0.0; MAX_KERNEL_SIZE];
@@ -120,7 +144,8 @@ pub fn plan_decimated_blur(std_deviation: f32) -> (usize, [f32; MAX_KERNEL_SIZE]
     // Compute the reduced standard deviation to apply at the decimated resolution
     let remaining_sigma = remaining_variance.sqrt();
     // Compute Gaussian kernel for the reduced blur
-    let (kernel, kernel_size) = compute_gaussian_kernel(remaining_sigma);
+    let (kernel, kernel_size) =
+        compute_gaussian_kernel_with_max_size(remaining_sigma, max_kernel_size);
 
     (n_decimations, kernel, kernel_size)
 }
@@ -131,10 +156,22 @@ pub fn plan_decimated_blur(std_deviation: f32) -> (usize, [f32; MAX_KERNEL_SIZE]
 /// The kernel is stored in a fixed-size array to avoid heap allocation.
 /// Uses the standard Gaussian formula: G(x) = exp(-x² / (2σ²)), normalized to sum to 1.
 pub fn compute_gaussian_kernel(std_deviation: f32) -> ([f32; MAX_KERNEL_SIZE], u8) {
+    compute_gaussian_kernel_with_max_size(std_deviation, MAX_KERNEL_SIZE)
+}
+
+/// Compute 1D Gaussian kernel weights like [`compute_gaussian_kernel`], but with at most
+/// `max_kernel_size` weights.
+///
+/// The maximum is rounded up to an odd number and clamped to [`MAX_KERNEL_SIZE`].
+pub fn compute_gaussian_kernel_with_max_size(
+    std_deviation: f32,
+    max_kernel_size: usize,
+) -> ([f32; MAX_KERNEL_SIZE], u8) {
     // Use radius = 3σ to capture 99.7% of the Gaussian distribution.
     // Beyond ±3σ, the Gaussian values are negligible (<0.3%).
     let radius = (3.0 * std_deviation).ceil() as usize;
-    let kernel_size = (1 + radius * 2).min(MAX_KERNEL_SIZE) as u8;
+    let max_kernel_size = max_kernel_size.clamp(1, MAX_KERNEL_SIZE) | 1;
+    let kernel_size = (1 + radius * 2).min(max_kernel_size) as u8;
 
     let mut kernel = [0.0; MAX_KERNEL_SIZE];
     // Compute Gaussian weights using the formula: G(x) = exp(-x² / (2σ²))
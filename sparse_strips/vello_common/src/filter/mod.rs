@@ -7,13 +7,16 @@
 //! represent a special representation of each filter to be used as the basis for rendering in
 //! `vello_hybrid` and `vello_cpu`.
 
+use crate::filter::color_matrix::ColorMatrix;
 use crate::filter::drop_shadow::{DropShadow, transform_shadow_params};
 use crate::filter::flood::Flood;
 use crate::filter::gaussian_blur::{GaussianBlur, transform_blur_params};
 use crate::filter::offset::Offset;
 use crate::filter_effects::{Filter, FilterPrimitive};
 use crate::kurbo::{Affine, Vec2};
+use crate::quality::Quality;
 
+pub mod color_matrix;
 pub mod drop_shadow;
 pub mod flood;
 pub mod gaussian_blur;
@@ -30,11 +33,15 @@ pub enum PreparedFilter {
     Offset(Offset),
     /// A drop shadow filter.
     DropShadow(DropShadow),
+    /// A color matrix filter.
+    ColorMatrix(ColorMatrix),
 }
 
 impl PreparedFilter {
     /// Build a new prepared filter for the given transform.
-    pub fn new(filter: &Filter, transform: &Affine) -> Self {
+    ///
+    /// The `quality` limits the number of taps of blurs.
+    pub fn new(filter: &Filter, transform: &Affine, quality: Quality) -> Self {
         // Multi-primitive filter graphs are not yet implemented.
         if filter.graph.primitives.len() != 1 {
             unimplemented!("Multi-primitive filter graphs are not yet supported");
@@ -50,7 +57,11 @@ impl PreparedFilter {
                 edge_mode,
             } => {
                 let scaled_std_dev = transform_blur_params(*std_deviation, transform);
-                let blur = GaussianBlur::new(scaled_std_dev, *edge_mode);
+                let blur = GaussianBlur::with_max_kernel_size(
+                    scaled_std_dev,
+                    *edge_mode,
+                    quality.max_blur_kernel_size(),
+                );
                 Self::GaussianBlur(blur)
             }
             FilterPrimitive::DropShadow {
@@ -62,8 +73,14 @@ impl PreparedFilter {
             } => {
                 let (scaled_dx, scaled_dy, scaled_std_dev) =
                     transform_shadow_params(*dx, *dy, *std_deviation, transform);
-                let drop_shadow =
-                    DropShadow::new(scaled_dx, scaled_dy, scaled_std_dev, *edge_mode, *color);
+                let drop_shadow = DropShadow::with_max_kernel_size(
+                    scaled_dx,
+                    scaled_dy,
+                    scaled_std_dev,
+                    *edge_mode,
+                    *color,
+                    quality.max_blur_kernel_size(),
+                );
 
                 Self::DropShadow(drop_shadow)
             }
@@ -73,8 +90,9 @@ impl PreparedFilter {
 
                 Self::Offset(offset)
             }
+            FilterPrimitive::ColorMatrix { matrix } => Self::ColorMatrix(ColorMatrix::new(*matrix)),
             _ => {
-                // Other primitives like Blend, ColorMatrix, ComponentTransfer, etc.
+                // Other primitives like Blend, ComponentTransfer, etc.
                 // are not yet implemented
                 unimplemented!("Other filter primitives not yet implemented");
             }
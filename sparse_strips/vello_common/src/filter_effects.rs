@@ -15,12 +15,15 @@
 //!
 //! **Filter Functions:**
 //! - `Blur` - Gaussian blur effect
+//! - `Brightness`, `Contrast`, `Grayscale`, `HueRotate`, `Invert`,
+//!   `Opacity`, `Saturate`, `Sepia` - Color adjustments (as color matrices)
 //!
 //! **Filter Primitives (Single Use Only):**
 //! - `Flood` - Solid color fill
 //! - `GaussianBlur` - Gaussian blur filter
 //! - `DropShadow` - Drop shadow effect (compound primitive)
 //! - `Offset` - Translation/shift (single primitive)
+//! - `ColorMatrix` - Matrix-based color transformation
 //!
 //! **Note:** Currently only single primitive filters are supported. Filter graphs with
 //! multiple connected primitives are not yet implemented.
@@ -31,12 +34,7 @@
 //! - `FilterGraph` execution - Chaining multiple filter primitives together
 //! - `FilterInputs` - Connecting primitives to create complex effects
 //!
-//! **Filter Functions:**
-//! - `Brightness`, `Contrast`, `Grayscale`, `HueRotate`, `Invert`,
-//!   `Opacity`, `Saturate`, `Sepia`
-//!
 //! **Filter Primitives:**
-//! - `ColorMatrix` - Matrix-based color transformation
 //! - `Composite` - Porter-Duff compositing operations
 //! - `Blend` - Blend mode operations
 //! - `Morphology` - Dilate/erode operations
@@ -80,12 +78,70 @@ impl Filter {
                 std_deviation: radius,
                 edge_mode: EdgeMode::default(),
             },
-            _ => unimplemented!("Filter function {:?} not supported", function),
+            FilterFunction::Brightness { amount } => FilterPrimitive::ColorMatrix {
+                matrix: matrices::brightness(amount),
+            },
+            FilterFunction::Contrast { amount } => FilterPrimitive::ColorMatrix {
+                matrix: matrices::contrast(amount),
+            },
+            FilterFunction::Grayscale { amount } => FilterPrimitive::ColorMatrix {
+                matrix: matrices::grayscale(amount),
+            },
+            FilterFunction::HueRotate { angle } => FilterPrimitive::ColorMatrix {
+                matrix: matrices::hue_rotate(angle),
+            },
+            FilterFunction::Invert { amount } => FilterPrimitive::ColorMatrix {
+                matrix: matrices::invert(amount),
+            },
+            FilterFunction::Opacity { amount } => FilterPrimitive::ColorMatrix {
+                matrix: matrices::opacity(amount),
+            },
+            FilterFunction::Saturate { amount } => FilterPrimitive::ColorMatrix {
+                matrix: matrices::saturate(amount),
+            },
+            FilterFunction::Sepia { amount } => FilterPrimitive::ColorMatrix {
+                matrix: matrices::sepia(amount),
+            },
         };
 
         Self::from_primitive(primitive)
     }
 
+    /// Create a filter that transforms colors with a 4x5 color matrix, like the SVG
+    /// `feColorMatrix` primitive.
+    ///
+    /// See [`FilterPrimitive::ColorMatrix`] for the layout of the matrix, and [`matrices`]
+    /// for common matrices.
+    pub fn color_matrix(matrix: [f32; 20]) -> Self {
+        Self::from_primitive(FilterPrimitive::ColorMatrix { matrix })
+    }
+
+    /// Create a filter that converts colors to grayscale, like the CSS `grayscale()` function.
+    pub fn grayscale(amount: f32) -> Self {
+        Self::from_function(FilterFunction::Grayscale { amount })
+    }
+
+    /// Create a filter that applies a sepia tone, like the CSS `sepia()` function.
+    pub fn sepia(amount: f32) -> Self {
+        Self::from_function(FilterFunction::Sepia { amount })
+    }
+
+    /// Create a filter that rotates hues by `angle` degrees, like the CSS `hue-rotate()`
+    /// function.
+    pub fn hue_rotate(angle: f32) -> Self {
+        Self::from_function(FilterFunction::HueRotate { angle })
+    }
+
+    /// Create a filter that adjusts the brightness, like the CSS `brightness()` function.
+    pub fn brightness(amount: f32) -> Self {
+        Self::from_function(FilterFunction::Brightness { amount })
+    }
+
+    /// Create a filter that adjusts the contrast, like the CSS `contrast()` function.
+    pub fn contrast(amount: f32) -> Self {
+        Self::from_function(FilterFunction::Contrast { amount })
+    }
+
     /// Create a filter system from a filter primitive.
     ///
     /// Creates a simple filter graph with a single primitive.
@@ -238,11 +294,6 @@ pub enum FilterFunction {
         /// approximately 3 times this value in each direction.
         radius: f32,
     },
-    //
-    // ============================================================
-    // TODO: The following filter functions are not yet implemented
-    // ============================================================
-    //
     /// Brightness adjustment.
     ///
     /// Adjusts the brightness of the input image using a linear multiplier.
@@ -400,15 +451,13 @@ pub enum FilterPrimitive {
         /// Default is `EdgeMode::None` per SVG spec.
         edge_mode: EdgeMode,
     },
-    //
-    // ============================================================
-    // TODO: The following filter primitives are not yet implemented
-    // ============================================================
-    //
     /// Matrix-based color transformation.
     ///
     /// Applies a 4x5 matrix transformation to colors, allowing arbitrary
     /// color space transformations, hue shifts, and color adjustments.
+    ///
+    /// Like in SVG, the matrix is applied to unpremultiplied color components in the
+    /// range [0, 1], and the result is clamped to that range.
     ColorMatrix {
         /// 4x5 color transformation matrix: 4 rows (R,G,B,A) × 5 columns (R,G,B,A,offset).
         /// Each output channel is computed as a linear combination of input channels plus offset.
@@ -424,7 +473,11 @@ pub enum FilterPrimitive {
         /// Vertical offset in pixels. Positive values shift down.
         dy: f32,
     },
-
+    //
+    // ============================================================
+    // TODO: The following filter primitives are not yet implemented
+    // ============================================================
+    //
     /// Composite two inputs using Porter-Duff compositing operations.
     ///
     /// Combines two input images using standard compositing operators
@@ -1002,6 +1055,9 @@ pub enum LightSource {
 /// These 4x5 matrices are used with the `ColorMatrix` filter primitive.
 /// Each row transforms a color channel: [R, G, B, A, offset].
 pub mod matrices {
+    #[cfg(not(feature = "std"))]
+    use peniko::kurbo::common::FloatFuncs as _;
+
     /// Identity matrix (no change).
     pub const IDENTITY: [f32; 20] = [
         1.0, 0.0, 0.0, 0.0, 0.0, // Red
@@ -1033,6 +1089,163 @@ pub mod matrices {
         0.272, 0.534, 0.131, 0.0, 0.0, // Blue
         0.0, 0.0, 0.0, 1.0, 0.0, // Alpha
     ];
+
+    // The following matrices are the ones used by the equivalent CSS filter functions, see
+    // <https://drafts.fxtf.org/filter-effects/#ShorthandEquivalents>.
+
+    /// Brightness matrix: 0 = black, 1 = no change, >1 = brighter.
+    pub fn brightness(amount: f32) -> [f32; 20] {
+        let a = amount.max(0.0);
+        [
+            a, 0.0, 0.0, 0.0, 0.0, // Red
+            0.0, a, 0.0, 0.0, 0.0, // Green
+            0.0, 0.0, a, 0.0, 0.0, // Blue
+            0.0, 0.0, 0.0, 1.0, 0.0, // Alpha
+        ]
+    }
+
+    /// Contrast matrix: 0 = uniform gray, 1 = no change, >1 = higher contrast.
+    pub fn contrast(amount: f32) -> [f32; 20] {
+        let a = amount.max(0.0);
+        let o = 0.5 - 0.5 * a;
+        [
+            a, 0.0, 0.0, 0.0, o, // Red
+            0.0, a, 0.0, 0.0, o, // Green
+            0.0, 0.0, a, 0.0, o, // Blue
+            0.0, 0.0, 0.0, 1.0, 0.0, // Alpha
+        ]
+    }
+
+    /// Grayscale matrix: 0 = original colors, 1 = [`GRAYSCALE`].
+    pub fn grayscale(amount: f32) -> [f32; 20] {
+        let s = 1.0 - amount.clamp(0.0, 1.0);
+        [
+            0.2126 + 0.7874 * s,
+            0.7152 - 0.7152 * s,
+            0.0722 - 0.0722 * s,
+            0.0,
+            0.0, // Red
+            0.2126 - 0.2126 * s,
+            0.7152 + 0.2848 * s,
+            0.0722 - 0.0722 * s,
+            0.0,
+            0.0, // Green
+            0.2126 - 0.2126 * s,
+            0.7152 - 0.7152 * s,
+            0.0722 + 0.9278 * s,
+            0.0,
+            0.0, // Blue
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+            0.0, // Alpha
+        ]
+    }
+
+    /// Sepia matrix: 0 = original colors, 1 = [`SEPIA`].
+    pub fn sepia(amount: f32) -> [f32; 20] {
+        let s = 1.0 - amount.clamp(0.0, 1.0);
+        [
+            0.393 + 0.607 * s,
+            0.769 - 0.769 * s,
+            0.189 - 0.189 * s,
+            0.0,
+            0.0, // Red
+            0.349 - 0.349 * s,
+            0.686 + 0.314 * s,
+            0.168 - 0.168 * s,
+            0.0,
+            0.0, // Green
+            0.272 - 0.272 * s,
+            0.534 - 0.534 * s,
+            0.131 + 0.869 * s,
+            0.0,
+            0.0, // Blue
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+            0.0, // Alpha
+        ]
+    }
+
+    /// Saturation matrix: 0 = fully desaturated, 1 = no change, >1 = oversaturated.
+    pub fn saturate(amount: f32) -> [f32; 20] {
+        let s = amount.max(0.0);
+        [
+            0.213 + 0.787 * s,
+            0.715 - 0.715 * s,
+            0.072 - 0.072 * s,
+            0.0,
+            0.0, // Red
+            0.213 - 0.213 * s,
+            0.715 + 0.285 * s,
+            0.072 - 0.072 * s,
+            0.0,
+            0.0, // Green
+            0.213 - 0.213 * s,
+            0.715 - 0.715 * s,
+            0.072 + 0.928 * s,
+            0.0,
+            0.0, // Blue
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+            0.0, // Alpha
+        ]
+    }
+
+    /// Hue rotation matrix for a rotation by `angle` degrees.
+    pub fn hue_rotate(angle: f32) -> [f32; 20] {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        [
+            0.213 + cos * 0.787 - sin * 0.213,
+            0.715 - cos * 0.715 - sin * 0.715,
+            0.072 - cos * 0.072 + sin * 0.928,
+            0.0,
+            0.0, // Red
+            0.213 - cos * 0.213 + sin * 0.143,
+            0.715 + cos * 0.285 + sin * 0.140,
+            0.072 - cos * 0.072 - sin * 0.283,
+            0.0,
+            0.0, // Green
+            0.213 - cos * 0.213 - sin * 0.787,
+            0.715 - cos * 0.715 + sin * 0.715,
+            0.072 + cos * 0.928 + sin * 0.072,
+            0.0,
+            0.0, // Blue
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+            0.0, // Alpha
+        ]
+    }
+
+    /// Inversion matrix: 0 = original colors, 1 = fully inverted.
+    pub fn invert(amount: f32) -> [f32; 20] {
+        let a = amount.clamp(0.0, 1.0);
+        let s = 1.0 - 2.0 * a;
+        [
+            s, 0.0, 0.0, 0.0, a, // Red
+            0.0, s, 0.0, 0.0, a, // Green
+            0.0, 0.0, s, 0.0, a, // Blue
+            0.0, 0.0, 0.0, 1.0, 0.0, // Alpha
+        ]
+    }
+
+    /// Opacity matrix: 0 = fully transparent, 1 = no change.
+    pub fn opacity(amount: f32) -> [f32; 20] {
+        let a = amount.clamp(0.0, 1.0);
+        [
+            1.0, 0.0, 0.0, 0.0, 0.0, // Red
+            0.0, 1.0, 0.0, 0.0, 0.0, // Green
+            0.0, 0.0, 1.0, 0.0, 0.0, // Blue
+            0.0, 0.0, 0.0, a, 0.0, // Alpha
+        ]
+    }
 }
 
 /// Common convolution kernels.
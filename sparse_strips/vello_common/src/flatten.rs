@@ -5,18 +5,16 @@
 
 use crate::flatten_simd::{Callback, LinePathEl};
 use crate::geometry::RectU16;
-use crate::kurbo::{self, Affine, PathEl, Stroke, StrokeCtx, StrokeOpts};
+use crate::kurbo::{self, Affine, BezPath, PathEl, Stroke, StrokeCtx, StrokeOpts};
+use crate::util::extract_scales;
 use alloc::vec::Vec;
 use fearless_simd::{Level, Simd, dispatch};
 use log::warn;
 
 pub use crate::flatten_simd::FlattenCtx;
 
-// The current tolerance is set to 0.25. Since `sqrt` doesn't work in const contexts, we instead
-// hardcode the squared tolerance and derive the others from that.
-pub(crate) const SQRT_TOL: f64 = 0.5;
-pub(crate) const TOL: f64 = SQRT_TOL * SQRT_TOL;
-pub(crate) const TOL_2: f64 = TOL * TOL;
+/// The default flattening tolerance, see [`FlattenCtx::set_tolerance`].
+pub(crate) const TOL: f64 = 0.25;
 
 /// A point.
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -228,14 +226,12 @@ pub fn stroke(
     stroke_ctx: &mut StrokeCtx,
     cull_bbox: RectU16,
 ) {
-    // TODO: Temporary hack to ensure that strokes are scaled properly by the transform.
-    let tolerance = TOL
-        / affine.as_coeffs()[0]
-            .abs()
-            .max(affine.as_coeffs()[3].abs())
-            .max(1.);
-
-    expand_stroke(path, style, tolerance, stroke_ctx);
+    expand_stroke(
+        path,
+        style,
+        stroke_tolerance_with(affine, flatten_ctx.tolerance()),
+        stroke_ctx,
+    );
     fill(
         level,
         stroke_ctx.output(),
@@ -246,6 +242,22 @@ pub fn stroke(
     );
 }
 
+/// The tolerance for expanding strokes in user space, such that the expanded path is still
+/// accurate after applying `affine`.
+pub fn stroke_tolerance(affine: Affine) -> f64 {
+    stroke_tolerance_with(affine, TOL)
+}
+
+/// The tolerance for expanding strokes in user space, such that the expanded path is still
+/// accurate to `tolerance` device pixels after applying `affine`.
+pub fn stroke_tolerance_with(affine: Affine, tolerance: f64) -> f64 {
+    // An error in user space is stretched by at most the largest singular value of the
+    // linear part of the transform. Unlike the diagonal coefficients, this also accounts for
+    // rotations, skews and non-uniform scales.
+    let (max_scale, _) = extract_scales(&affine);
+    tolerance / f64::from(max_scale).max(1.)
+}
+
 /// Expand a stroked path to a filled path.
 pub fn expand_stroke(
     path: impl IntoIterator<Item = PathEl>,
@@ -256,6 +268,60 @@ pub fn expand_stroke(
     kurbo::stroke_with(path, style, &StrokeOpts::default(), tolerance, stroke_ctx);
 }
 
+/// Return the outline of a stroked path, for example to export it or to use it as a hit
+/// region.
+///
+/// The joins, caps, miter limit and dashes of `style` are applied like when the path is
+/// stroked by the renderers, and the outline covers the same pixels when it is filled with
+/// the non-zero fill rule. To match a path that is drawn with a transform, use
+/// [`stroke_tolerance`] with that transform as the `tolerance`.
+pub fn stroke_outline(
+    path: impl IntoIterator<Item = PathEl>,
+    style: &Stroke,
+    tolerance: f64,
+) -> BezPath {
+    let mut stroke_ctx = StrokeCtx::default();
+    expand_stroke(path, style, tolerance, &mut stroke_ctx);
+    stroke_ctx.output().clone()
+}
+
+/// Flatten `path` and call `line` with the end points of each of its lines, closing all
+/// subpaths like when the path is filled.
+pub(crate) fn flatten_closed(
+    path: impl IntoIterator<Item = PathEl>,
+    tolerance: f64,
+    mut line: impl FnMut(kurbo::Point, kurbo::Point),
+) {
+    let mut start = None;
+    let mut last = None;
+    kurbo::flatten(path, tolerance, |el| match el {
+        PathEl::MoveTo(p) => {
+            if let (Some(start), Some(last)) = (start, last) {
+                line(last, start);
+            }
+            start = Some(p);
+            last = start;
+        }
+        PathEl::LineTo(p) => {
+            if let Some(last) = last {
+                line(last, p);
+            }
+            last = Some(p);
+        }
+        PathEl::ClosePath => {
+            if let (Some(start), Some(last)) = (start, last) {
+                line(last, start);
+            }
+            last = start;
+        }
+        // `flatten` only emits move, line and close elements.
+        PathEl::QuadTo(..) | PathEl::CurveTo(..) => unreachable!(),
+    });
+    if let (Some(start), Some(last)) = (start, last) {
+        line(last, start);
+    }
+}
+
 struct FlattenerCallback<'a> {
     line_buf: &'a mut Vec<Line>,
     start: Point,
@@ -284,3 +350,27 @@ impl Callback for FlattenerCallback<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{TOL, stroke_outline};
+    use crate::kurbo::{Cap, Join, Line, Point, Rect, Shape, Stroke};
+
+    #[test]
+    fn stroke_outline_applies_caps_and_joins() {
+        let line = Line::new((0.0, 0.0), (100.0, 0.0)).to_path(0.1);
+        let butt = stroke_outline(&line, &Stroke::new(4.0).with_caps(Cap::Butt), TOL);
+        assert_eq!(butt.bounding_box(), Rect::new(0.0, -2.0, 100.0, 2.0));
+        let square = stroke_outline(&line, &Stroke::new(4.0).with_caps(Cap::Square), TOL);
+        assert_eq!(square.bounding_box(), Rect::new(-2.0, -2.0, 102.0, 2.0));
+
+        let rect = Rect::new(0.0, 0.0, 10.0, 10.0).to_path(0.1);
+        let miter = stroke_outline(&rect, &Stroke::new(2.0).with_join(Join::Miter), TOL);
+        assert_eq!(miter.bounding_box(), Rect::new(-1.0, -1.0, 11.0, 11.0));
+        let bevel = stroke_outline(&rect, &Stroke::new(2.0).with_join(Join::Bevel), TOL);
+        // Only the miter join covers the outer corner.
+        let corner = Point::new(-0.9, -0.9);
+        assert_ne!(miter.winding(corner), 0);
+        assert_eq!(bevel.winding(corner), 0);
+    }
+}
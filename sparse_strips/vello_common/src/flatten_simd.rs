@@ -8,12 +8,7 @@
 #[cfg(not(feature = "std"))]
 use crate::kurbo::common::FloatFuncs as _;
 use crate::kurbo::{CubicBez, Line, ParamCurve, ParamCurveNearest, PathEl, Point, QuadBez};
-use crate::{
-    flatten::{SQRT_TOL, TOL, TOL_2},
-    geometry::RectU16,
-    kurbo::Affine,
-    tile::Tile,
-};
+use crate::{flatten::TOL, geometry::RectU16, kurbo::Affine, tile::Tile};
 use alloc::vec::Vec;
 use bytemuck::{Pod, Zeroable};
 use fearless_simd::*;
@@ -59,6 +54,9 @@ pub(crate) fn flatten<S: Simd>(
     cull_bbox: RectU16,
 ) {
     flatten_ctx.flattened_cubics.clear();
+    let tol = flatten_ctx.tolerance;
+    let sqrt_tol = tol.sqrt();
+    let tol_2 = tol * tol;
 
     // For the culling performed here to be correct, the top y coordinate of the cull bbox must be
     // aligned to strip row boundaries. Consider the alternative: for example, a strip row starting
@@ -150,13 +148,13 @@ pub(crate) fn flatten<S: Simd>(
                 // The following takes the square to elide the square root of the Euclidean
                 // distance.
                 else if [p0, p1, p2].into_iter().all(|p| p.x < left)
-                    || line.nearest(p1, 0.).distance_sq <= 4. * TOL_2
+                    || line.nearest(p1, 0.).distance_sq <= 4. * tol_2
                 {
                     callback.callback(LinePathEl::LineTo(p2));
                 } else {
                     let q = QuadBez::new(p0, p1, p2);
-                    let params = q.estimate_subdiv(SQRT_TOL);
-                    let n = ((0.5 / SQRT_TOL * params.val).ceil() as usize).max(1);
+                    let params = q.estimate_subdiv(sqrt_tol);
+                    let n = ((0.5 / sqrt_tol * params.val).ceil() as usize).max(1);
                     let step = 1.0 / (n as f64);
                     for i in 1..n {
                         let u = (i as f64) * step;
@@ -208,7 +206,7 @@ pub(crate) fn flatten<S: Simd>(
                     || f64::max(
                         line.nearest(p1, 0.).distance_sq,
                         line.nearest(p2, 0.).distance_sq,
-                    ) <= 16. / 9. * TOL_2
+                    ) <= 16. / 9. * tol_2
                 {
                     callback.callback(LinePathEl::LineTo(p3));
                 } else {
@@ -338,7 +336,7 @@ struct FlattenParams {
 const MAX_QUADS: usize = 16;
 
 /// The context needed for flattening curves.
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct FlattenCtx {
     // The +4 is to encourage alignment; might be better to be explicit
     even_pts: [Point32; MAX_QUADS + 4],
@@ -351,6 +349,42 @@ pub struct FlattenCtx {
     n_quads: usize,
     /// Reusable buffer for flattened cubic points.
     flattened_cubics: Vec<Point32>,
+    /// The maximum distance in device pixels between a curve and its flattened lines.
+    tolerance: f64,
+}
+
+impl Default for FlattenCtx {
+    fn default() -> Self {
+        Self {
+            even_pts: Default::default(),
+            odd_pts: Default::default(),
+            a0: Default::default(),
+            da: Default::default(),
+            u0: Default::default(),
+            uscale: Default::default(),
+            val: Default::default(),
+            n_quads: 0,
+            flattened_cubics: Vec::new(),
+            tolerance: TOL,
+        }
+    }
+}
+
+impl FlattenCtx {
+    /// Set the maximum distance in device pixels between a curve and the lines it is
+    /// flattened to.
+    ///
+    /// Larger tolerances produce fewer lines, which speeds up rendering, at the cost of
+    /// visibly polygonal curves. The default is 0.25.
+    pub fn set_tolerance(&mut self, tolerance: f64) {
+        self.tolerance = tolerance;
+    }
+
+    /// Return the maximum distance in device pixels between a curve and the lines it is
+    /// flattened to.
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
 }
 
 #[inline(always)]
@@ -585,9 +619,10 @@ fn output_lines_simd<S: Simd>(
 
 #[inline(always)]
 fn flatten_cubic_simd<S: Simd>(simd: S, c: CubicBez, ctx: &mut FlattenCtx) -> usize {
-    let n_quads = estimate_num_quads(c, TOL as f32);
+    let accuracy = ctx.tolerance as f32;
+    let n_quads = estimate_num_quads(c, accuracy);
     eval_cubics_simd(simd, &c, n_quads, ctx);
-    let tol = (TOL as f32) * (1.0 - TO_QUAD_TOL);
+    let tol = accuracy * (1.0 - TO_QUAD_TOL);
     let sqrt_tol = tol.sqrt();
     estimate_subdiv_simd(simd, sqrt_tol, ctx);
     let sum: f32 = ctx.val[..n_quads].iter().sum();
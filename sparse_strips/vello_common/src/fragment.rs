@@ -0,0 +1,206 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Reusable scene fragments that are drawn many times.
+//!
+//! Drawing the same shape thousands of times, like the markers on a map, normally flattens
+//! (and for strokes, expands) the same geometry once per draw. A [`Fragment`] does this work
+//! once and keeps the resulting lines. Each [`FragmentInstance`] then only transforms the
+//! lines and generates strips from them, optionally with a paint that overrides the current
+//! one.
+//!
+//! The lines are flattened for a fixed scale, so a fragment should be created for the largest
+//! scale it is going to be drawn at.
+
+use alloc::vec::Vec;
+
+use crate::fearless_simd::Level;
+use crate::flatten::{self, FlattenCtx, Line, Point, expand_stroke, stroke_tolerance};
+use crate::geometry::RectU16;
+use crate::kurbo::{Affine, BezPath, Rect, Shape, Stroke, StrokeCtx};
+use crate::paint::PaintType;
+use crate::peniko::Fill;
+
+/// A flattened shape that can be drawn many times with different transforms.
+///
+/// See the [module documentation](self) for details.
+#[derive(Clone, Debug)]
+pub struct Fragment {
+    lines: Vec<Line>,
+    fill_rule: Fill,
+    bounds: Rect,
+    /// Maps the flattened lines back into fragment space.
+    to_fragment: Affine,
+}
+
+impl Fragment {
+    /// Create a fragment that fills `path` with the given fill rule.
+    ///
+    /// `scale` is the largest scale the fragment is drawn at, which determines how finely
+    /// curves are flattened.
+    pub fn fill(level: Level, path: &BezPath, fill_rule: Fill, scale: f64) -> Self {
+        Self::new(level, path, fill_rule, scale)
+    }
+
+    /// Create a fragment that strokes `path` with the given stroke.
+    ///
+    /// `scale` is the largest scale the fragment is drawn at, which determines how finely
+    /// the stroke is expanded and flattened.
+    pub fn stroke(level: Level, path: &BezPath, stroke: &Stroke, scale: f64) -> Self {
+        let mut stroke_ctx = StrokeCtx::default();
+        expand_stroke(
+            path.iter(),
+            stroke,
+            stroke_tolerance(Affine::scale(scale)),
+            &mut stroke_ctx,
+        );
+        Self::new(level, stroke_ctx.output(), Fill::NonZero, scale)
+    }
+
+    fn new(level: Level, path: &BezPath, fill_rule: Fill, scale: f64) -> Self {
+        let scale = if scale.is_finite() && scale > 0.0 {
+            scale
+        } else {
+            1.0
+        };
+        let bounds = path.bounding_box();
+
+        // Flatten in a space where the fragment starts at the origin, so that none of it is
+        // culled. Fragments larger than `u16::MAX` pixels at `scale` are cut off.
+        let to_flat = Affine::scale(scale) * Affine::translate(-bounds.origin().to_vec2());
+        let mut lines = Vec::new();
+        flatten::fill(
+            level,
+            path.iter(),
+            to_flat,
+            &mut lines,
+            &mut FlattenCtx::default(),
+            RectU16::new(0, 0, u16::MAX, u16::MAX),
+        );
+
+        Self {
+            lines,
+            fill_rule,
+            bounds,
+            to_fragment: to_flat.inverse(),
+        }
+    }
+
+    /// Return the fill rule the fragment is drawn with.
+    pub fn fill_rule(&self) -> Fill {
+        self.fill_rule
+    }
+
+    /// Return the bounding box of the fragment, in fragment space.
+    pub fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    /// Return whether the fragment doesn't cover anything.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Write the lines of the fragment, mapped through `transform`, into `out`.
+    ///
+    /// Lines that lie completely above, below or to the right of a `width` x `height`
+    /// viewport don't affect the coverage inside of it and are dropped.
+    pub fn transform_lines(&self, transform: Affine, width: u16, height: u16, out: &mut Vec<Line>) {
+        out.clear();
+
+        let [a, b, c, d, e, f] = (transform * self.to_fragment).as_coeffs();
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "lines are stored with f32 precision"
+        )]
+        let map = |p: Point| {
+            let (x, y) = (f64::from(p.x), f64::from(p.y));
+            Point::new((a * x + c * y + e) as f32, (b * x + d * y + f) as f32)
+        };
+        let (width, height) = (f32::from(width), f32::from(height));
+
+        out.extend(self.lines.iter().filter_map(|line| {
+            let (p0, p1) = (map(line.p0), map(line.p1));
+            let outside = (p0.y < 0.0 && p1.y < 0.0)
+                || (p0.y > height && p1.y > height)
+                || (p0.x > width && p1.x > width);
+            (!outside).then(|| Line::new(p0, p1))
+        }));
+    }
+}
+
+/// A single placement of a [`Fragment`].
+#[derive(Clone, Debug)]
+pub struct FragmentInstance {
+    /// The transform from fragment space, applied after the current transform.
+    pub transform: Affine,
+    /// The paint of this instance, or `None` to use the current paint.
+    pub paint: Option<PaintType>,
+}
+
+impl FragmentInstance {
+    /// Create an instance that is drawn with the current paint.
+    pub fn new(transform: Affine) -> Self {
+        Self {
+            transform,
+            paint: None,
+        }
+    }
+
+    /// Draw this instance with `paint` instead of the current paint.
+    pub fn with_paint(mut self, paint: impl Into<PaintType>) -> Self {
+        self.paint = Some(paint.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fragment;
+    use crate::fearless_simd::Level;
+    use crate::flatten::{self, FlattenCtx, Line};
+    use crate::geometry::RectU16;
+    use crate::kurbo::{Affine, Circle, Shape};
+    use crate::peniko::Fill;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn transformed_lines_match_direct_flattening() {
+        let path = Circle::new((-3.0, -2.0), 5.0).to_path(0.1);
+        let fragment = Fragment::fill(Level::baseline(), &path, Fill::NonZero, 1.0);
+        let transform = Affine::translate((20.0, 30.0));
+
+        let mut instanced = Vec::new();
+        fragment.transform_lines(transform, 100, 100, &mut instanced);
+
+        let mut direct: Vec<Line> = Vec::new();
+        flatten::fill(
+            Level::baseline(),
+            path.iter(),
+            transform,
+            &mut direct,
+            &mut FlattenCtx::default(),
+            RectU16::new(0, 0, 100, 100),
+        );
+
+        assert_eq!(instanced.len(), direct.len());
+        for (a, b) in instanced.iter().zip(&direct) {
+            assert!((a.p0.x - b.p0.x).abs() < 1e-3, "{a:?} != {b:?}");
+            assert!((a.p0.y - b.p0.y).abs() < 1e-3, "{a:?} != {b:?}");
+        }
+    }
+
+    #[test]
+    fn lines_outside_of_the_viewport_are_dropped() {
+        let path = Circle::new((0.0, 0.0), 5.0).to_path(0.1);
+        let fragment = Fragment::fill(Level::baseline(), &path, Fill::NonZero, 1.0);
+
+        let mut lines = Vec::new();
+        fragment.transform_lines(Affine::translate((50.0, -20.0)), 100, 100, &mut lines);
+        assert!(lines.is_empty());
+
+        // Lines to the left still contribute to the winding number.
+        fragment.transform_lines(Affine::translate((-20.0, 50.0)), 100, 100, &mut lines);
+        assert!(!lines.is_empty());
+    }
+}
@@ -0,0 +1,548 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Rendering hard-stop gradients as solid bands.
+//!
+//! Gradients that only consist of hard stops (for example the slices of a pie chart or
+//! a striped fill) don't need to be sampled from a lookup table at all: every point of the
+//! gradient has one of a small number of solid colors. Sampling such gradients from a LUT
+//! makes the band boundaries snap to the LUT resolution, which is clearly visible on large
+//! gradients. [`GradientBands`] instead decomposes the gradient into a number of solid fills,
+//! each restricted to the region of its band, so that band edges are anti-aliased just like
+//! any other geometry.
+
+use alloc::vec::Vec;
+use core::f64::consts::{FRAC_PI_8, TAU};
+
+use crate::color::{AlphaColor, Srgb};
+use crate::encode::validate;
+use crate::kurbo::{Affine, BezPath, Circle, Point, Rect, Shape, Stroke, Vec2};
+use crate::math::FloatExt;
+use crate::peniko::{
+    ColorStop, Extend, Gradient, GradientKind, LinearGradientPosition, RadialGradientPosition,
+    SweepGradientPosition,
+};
+#[cfg(not(feature = "std"))]
+use peniko::kurbo::common::FloatFuncs as _;
+
+/// The maximum number of regions that a gradient is decomposed into.
+///
+/// Repeating gradients that are decomposed into more regions than this (e.g. because they
+/// are tiny compared to the filled geometry) are better served by the LUT.
+const MAX_BAND_REGIONS: usize = 64;
+
+/// The tolerance used for flattening the arcs of band regions, in device pixels.
+const BAND_TOLERANCE: f64 = 0.05;
+
+/// A single solid band of a [`GradientBands`] decomposition.
+#[derive(Debug, Clone)]
+pub struct GradientBand {
+    color: AlphaColor<Srgb>,
+    region: BezPath,
+}
+
+impl GradientBand {
+    /// The color of the band.
+    pub fn color(&self) -> AlphaColor<Srgb> {
+        self.color
+    }
+
+    /// The region covered by the band, in user space.
+    ///
+    /// The region must be used with the [even-odd](crate::peniko::Fill::EvenOdd) fill rule.
+    pub fn region(&self) -> &BezPath {
+        &self.region
+    }
+}
+
+/// A gradient consisting only of hard stops, decomposed into solid bands.
+///
+/// To render the gradient, push the geometry as a clip layer, fill the
+/// [bounds](Self::bounds) with the [base color](Self::base), and then fill the region of each
+/// band, in order, with the band color. The clip layer applies the anti-aliased edges of the
+/// geometry exactly once, and the geometry is only rasterized once.
+///
+/// The region of each band contains the regions of all following bands. Since all colors are
+/// opaque, the edge of a band is blended exactly between two band colors, without any seams
+/// between them. This requires the default blend mode, which the caller has to ensure.
+#[derive(Debug, Clone)]
+pub struct GradientBands {
+    base: AlphaColor<Srgb>,
+    bounds: BezPath,
+    bands: Vec<GradientBand>,
+}
+
+impl GradientBands {
+    /// Try to decompose `gradient` into solid bands.
+    ///
+    /// `bounds` are the bounds of the geometry that is going to be drawn in user space,
+    /// `transform` is the geometry transform and `paint_transform` the paint transform. The
+    /// band regions are only guaranteed to be correct inside of `bounds`.
+    ///
+    /// This returns `None` if the gradient has any stops that aren't hard stops, any stops
+    /// that aren't fully opaque, is invalid, or if the decomposition would be too expensive.
+    /// Two-point radial gradients are only supported if both circles share the same center.
+    pub fn new(
+        gradient: &Gradient,
+        bounds: Rect,
+        transform: Affine,
+        paint_transform: Affine,
+    ) -> Option<Self> {
+        if validate(gradient).is_err() {
+            return None;
+        }
+
+        let (base, steps) = hard_stop_steps(&gradient.stops)?;
+
+        let paint_to_device = transform * paint_transform;
+        if transform.determinant().is_nearly_zero()
+            || paint_to_device.determinant().is_nearly_zero()
+        {
+            return None;
+        }
+
+        // Leave a margin of one pixel so that anti-aliased edges of the geometry are never
+        // clipped by the band regions.
+        let device_bounds = transform.transform_rect_bbox(bounds).inflate(1.0, 1.0);
+        let paint_bounds = paint_to_device.inverse().transform_rect_bbox(device_bounds);
+        // The tolerance for arcs, in paint space.
+        let tolerance = BAND_TOLERANCE
+            / paint_to_device.as_coeffs()[..4]
+                .iter()
+                .fold(0.0_f64, |max, c| max.max(c.abs()));
+
+        let geometry = BandGeometry::new(&gradient.kind, paint_bounds, tolerance)?;
+        let (t_min, t_max) = geometry.t_range();
+
+        let mut budget = MAX_BAND_REGIONS;
+        let mut bands = Vec::with_capacity(steps.len());
+
+        for (offset, color) in steps {
+            let intervals = covered_intervals(offset, gradient.extend, t_min, t_max)?;
+            if intervals.len() > budget {
+                return None;
+            }
+            budget -= intervals.len();
+
+            let mut region = BezPath::new();
+            for (t0, t1) in intervals {
+                geometry.append_region(&mut region, t0, t1);
+            }
+
+            if !region.is_empty() {
+                bands.push(GradientBand {
+                    color,
+                    region: paint_transform * region,
+                });
+            }
+        }
+
+        Some(Self {
+            base,
+            bounds: transform.inverse() * device_bounds.to_path(0.1),
+            bands,
+        })
+    }
+
+    /// The color of the area that isn't covered by any band.
+    pub fn base(&self) -> AlphaColor<Srgb> {
+        self.base
+    }
+
+    /// A region in user space that covers the geometry including its anti-aliased edges.
+    ///
+    /// The region must be used with the [non-zero](crate::peniko::Fill::NonZero) fill rule.
+    pub fn bounds(&self) -> &BezPath {
+        &self.bounds
+    }
+
+    /// The bands, in the order they need to be drawn.
+    pub fn bands(&self) -> &[GradientBand] {
+        &self.bands
+    }
+}
+
+/// Return conservative bounds of `path` stroked with `stroke`.
+pub fn stroke_bounds(path: &BezPath, stroke: &Stroke) -> Rect {
+    // Miter joins extend the furthest, square caps at most by `sqrt(2)` times the half width.
+    let extent = 0.5 * stroke.width * stroke.miter_limit.max(core::f64::consts::SQRT_2);
+    path.bounding_box().inflate(extent, extent)
+}
+
+/// The position of a hard stop and the color after it.
+type Step = (f32, AlphaColor<Srgb>);
+
+/// Split `stops` into the color below the first hard stop and the position and color of
+/// each hard stop, or return `None` if the stops don't describe a banded gradient.
+fn hard_stop_steps(stops: &[ColorStop]) -> Option<(AlphaColor<Srgb>, Vec<Step>)> {
+    if stops.iter().any(|s| s.color.components[3] != 1.0) {
+        return None;
+    }
+
+    let base = stops.first()?.color.to_alpha_color::<Srgb>();
+    let mut steps = Vec::new();
+
+    for pair in stops.windows(2) {
+        let left = pair[0].color.to_alpha_color::<Srgb>();
+        let right = pair[1].color.to_alpha_color::<Srgb>();
+
+        if left.components == right.components {
+            continue;
+        }
+
+        // Colors may only change at a hard stop.
+        if pair[0].offset != pair[1].offset {
+            return None;
+        }
+
+        steps.push((pair[1].offset, right));
+    }
+
+    Some((base, steps))
+}
+
+/// Return the intervals of `t_min..t_max` for which the extended gradient position is at
+/// least `offset`, or `None` if there are more than [`MAX_BAND_REGIONS`] of them.
+fn covered_intervals(
+    offset: f32,
+    extend: Extend,
+    t_min: f64,
+    t_max: f64,
+) -> Option<Vec<(f64, f64)>> {
+    let offset = f64::from(offset);
+
+    if offset <= 0.0 {
+        return Some(alloc::vec![(t_min, t_max)]);
+    }
+
+    // The positions where the extended gradient reaches `offset` repeat with this period,
+    // and within each period the covered part is `start..end`.
+    let (period, start, end) = match extend {
+        Extend::Pad => {
+            return Some(if offset <= t_max {
+                alloc::vec![(offset.max(t_min), t_max)]
+            } else {
+                Vec::new()
+            });
+        }
+        Extend::Repeat => (1.0, offset, 1.0),
+        Extend::Reflect => (2.0, offset, 2.0 - offset),
+    };
+
+    if start >= end {
+        return Some(Vec::new());
+    }
+
+    let first = (t_min / period).floor();
+    let last = (t_max / period).floor();
+    if last - first >= MAX_BAND_REGIONS as f64 {
+        return None;
+    }
+
+    let mut intervals = Vec::new();
+    let mut k = first;
+    while k <= last {
+        let t0 = (k * period + start).max(t_min);
+        let t1 = (k * period + end).min(t_max);
+        if t0 < t1 {
+            intervals.push((t0, t1));
+        }
+        k += 1.0;
+    }
+
+    Some(intervals)
+}
+
+/// The geometry of the regions between two gradient positions, in paint space.
+enum BandGeometry {
+    Linear {
+        /// Maps `(t, v)` to paint space, where `v` runs along the band edges.
+        transform: Affine,
+        /// The bounds in `(t, v)` space.
+        bounds: Rect,
+    },
+    Radial {
+        center: Point,
+        r0: f64,
+        r1: f64,
+        /// The closest and furthest distance of the bounds from the center.
+        distances: (f64, f64),
+        tolerance: f64,
+    },
+    Sweep {
+        center: Point,
+        start_angle: f64,
+        end_angle: f64,
+        /// The furthest distance of the bounds from the center.
+        radius: f64,
+    },
+}
+
+impl BandGeometry {
+    fn new(kind: &GradientKind, bounds: Rect, tolerance: f64) -> Option<Self> {
+        let corners = [
+            Point::new(bounds.x0, bounds.y0),
+            Point::new(bounds.x1, bounds.y0),
+            Point::new(bounds.x0, bounds.y1),
+            Point::new(bounds.x1, bounds.y1),
+        ];
+        let max_distance = |center: Point| {
+            corners
+                .iter()
+                .fold(0.0_f64, |max, p| max.max(p.distance(center)))
+        };
+
+        let geometry = match *kind {
+            GradientKind::Linear(LinearGradientPosition { start, end }) => {
+                let d = end - start;
+                // The second axis is perpendicular to the gradient line and has the same
+                // length, so the transform is guaranteed to be invertible.
+                let transform = Affine::new([d.x, d.y, -d.y, d.x, start.x, start.y]);
+
+                Self::Linear {
+                    transform,
+                    bounds: transform.inverse().transform_rect_bbox(bounds),
+                }
+            }
+            GradientKind::Radial(RadialGradientPosition {
+                start_center,
+                start_radius,
+                end_center,
+                end_radius,
+            }) => {
+                if !((end_center - start_center).length() as f32).is_nearly_zero() {
+                    return None;
+                }
+
+                let closest = Point::new(
+                    end_center.x.clamp(bounds.x0, bounds.x1),
+                    end_center.y.clamp(bounds.y0, bounds.y1),
+                );
+
+                Self::Radial {
+                    center: end_center,
+                    r0: f64::from(start_radius),
+                    r1: f64::from(end_radius),
+                    distances: (closest.distance(end_center), max_distance(end_center)),
+                    tolerance,
+                }
+            }
+            GradientKind::Sweep(SweepGradientPosition {
+                center,
+                start_angle,
+                end_angle,
+            }) => Self::Sweep {
+                center,
+                start_angle: f64::from(start_angle),
+                end_angle: f64::from(end_angle),
+                radius: max_distance(center),
+            },
+        };
+
+        Some(geometry)
+    }
+
+    /// Return the range of gradient positions inside of the bounds.
+    fn t_range(&self) -> (f64, f64) {
+        match self {
+            Self::Linear { bounds, .. } => (bounds.x0, bounds.x1),
+            Self::Radial {
+                r0,
+                r1,
+                distances: (min, max),
+                ..
+            } => {
+                let t_min = (min - r0) / (r1 - r0);
+                let t_max = (max - r0) / (r1 - r0);
+                (t_min.min(t_max), t_min.max(t_max))
+            }
+            Self::Sweep {
+                start_angle,
+                end_angle,
+                ..
+            } => (
+                -start_angle / (end_angle - start_angle),
+                (TAU - start_angle) / (end_angle - start_angle),
+            ),
+        }
+    }
+
+    /// Append the region between the gradient positions `t0` and `t1` to `path`.
+    fn append_region(&self, path: &mut BezPath, t0: f64, t1: f64) {
+        match self {
+            Self::Linear { transform, bounds } => {
+                let rect = Rect::new(t0, bounds.y0, t1, bounds.y1);
+                path.extend(*transform * rect.to_path(0.1));
+            }
+            Self::Radial {
+                center,
+                r0,
+                r1,
+                tolerance,
+                ..
+            } => {
+                let ra = r0 + t0 * (r1 - r0);
+                let rb = r0 + t1 * (r1 - r0);
+                let inner = ra.min(rb).max(0.0);
+                let outer = ra.max(rb);
+
+                if outer <= inner {
+                    return;
+                }
+
+                path.extend(Circle::new(*center, outer).path_elements(*tolerance));
+                if inner > 0.0 {
+                    path.extend(Circle::new(*center, inner).path_elements(*tolerance));
+                }
+            }
+            Self::Sweep {
+                center,
+                start_angle,
+                end_angle,
+                radius,
+            } => {
+                let delta = end_angle - start_angle;
+                let a0 = (start_angle + t0 * delta).clamp(0.0, TAU);
+                let a1 = (start_angle + t1 * delta).clamp(0.0, TAU);
+
+                if a1 <= a0 {
+                    return;
+                }
+
+                // Approximate the arc with a polyline whose segments are at most `PI / 8`
+                // long, pushed outwards far enough for the polyline to contain the bounds.
+                let radius = (radius + 1.0) / (FRAC_PI_8 / 2.0).cos();
+                let point = |angle: f64| *center + radius * Vec2::from_angle(angle);
+                #[expect(
+                    clippy::cast_possible_truncation,
+                    reason = "the number of segments is at most 16"
+                )]
+                let segments = ((a1 - a0) / FRAC_PI_8).ceil().max(1.0) as usize;
+
+                path.move_to(*center);
+                for i in 0..=segments {
+                    path.line_to(point(a0 + (a1 - a0) * i as f64 / segments as f64));
+                }
+                path.close_path();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::DynamicColor;
+    use crate::color::palette::css::{BLUE, GREEN, RED};
+    use crate::kurbo::PathEl;
+    use crate::peniko::ColorStops;
+
+    fn stop(offset: f32, color: AlphaColor<Srgb>) -> ColorStop {
+        ColorStop {
+            offset,
+            color: DynamicColor::from_alpha_color(color),
+        }
+    }
+
+    fn linear(stops: &[ColorStop], extend: Extend, length: f64) -> Gradient {
+        Gradient {
+            kind: LinearGradientPosition {
+                start: Point::new(0.0, 0.0),
+                end: Point::new(length, 0.0),
+            }
+            .into(),
+            stops: ColorStops(stops.into()),
+            extend,
+            ..Default::default()
+        }
+    }
+
+    fn bands(gradient: &Gradient) -> Option<GradientBands> {
+        GradientBands::new(
+            gradient,
+            Rect::new(0.0, 0.0, 100.0, 100.0),
+            Affine::IDENTITY,
+            Affine::IDENTITY,
+        )
+    }
+
+    #[test]
+    fn hard_stops_are_split_into_bands() {
+        let stops = [
+            stop(0.0, RED),
+            stop(0.25, RED),
+            stop(0.25, GREEN),
+            stop(0.5, GREEN),
+            stop(0.5, BLUE),
+            stop(1.0, BLUE),
+        ];
+        let bands = bands(&linear(&stops, Extend::Pad, 100.0)).unwrap();
+
+        assert_eq!(bands.base(), RED);
+        // The bounds include a margin of one pixel for anti-aliased edges.
+        assert_eq!(
+            bands.bounds().bounding_box(),
+            Rect::new(-1.0, -1.0, 101.0, 101.0)
+        );
+        assert_eq!(bands.bands().len(), 2);
+        assert_eq!(bands.bands()[0].color(), GREEN);
+        assert_eq!(bands.bands()[1].color(), BLUE);
+        let region = bands.bands()[1].region().bounding_box();
+        assert!((region.x0 - 50.0).abs() < 1e-9);
+        assert!(region.x1 >= 101.0);
+    }
+
+    #[test]
+    fn smooth_or_transparent_stops_are_rejected() {
+        let smooth = [stop(0.0, RED), stop(1.0, BLUE)];
+        assert!(bands(&linear(&smooth, Extend::Pad, 100.0)).is_none());
+
+        let transparent = [
+            stop(0.0, RED.with_alpha(0.5)),
+            stop(0.5, RED),
+            stop(0.5, BLUE),
+        ];
+        assert!(bands(&linear(&transparent, Extend::Pad, 100.0)).is_none());
+    }
+
+    #[test]
+    fn repeat_produces_one_region_per_period() {
+        let stops = [stop(0.5, RED), stop(0.5, BLUE)];
+
+        let bands_10 = bands(&linear(&stops, Extend::Repeat, 10.0)).unwrap();
+        let subpaths = bands_10.bands()[0]
+            .region()
+            .elements()
+            .iter()
+            .filter(|el| matches!(el, PathEl::MoveTo(_)))
+            .count();
+        // The bounds (with the one pixel margin) span the periods -1 to 10, but the last one
+        // ends before reaching the blue half.
+        assert_eq!(subpaths, 11);
+
+        // Too many periods to be worth decomposing.
+        assert!(bands(&linear(&stops, Extend::Repeat, 1.0)).is_none());
+    }
+
+    #[test]
+    fn reflect_intervals_merge_across_periods() {
+        let intervals = covered_intervals(0.75, Extend::Reflect, 0.0, 4.0).unwrap();
+        assert_eq!(intervals, [(0.75, 1.25), (2.75, 3.25)]);
+    }
+
+    #[test]
+    fn non_concentric_radial_is_rejected() {
+        let gradient = Gradient {
+            kind: RadialGradientPosition {
+                start_center: Point::new(10.0, 10.0),
+                start_radius: 0.0,
+                end_center: Point::new(20.0, 10.0),
+                end_radius: 50.0,
+            }
+            .into(),
+            stops: ColorStops([stop(0.5, RED), stop(0.5, BLUE)].as_slice().into()),
+            ..Default::default()
+        };
+        assert!(bands(&gradient).is_none());
+    }
+}
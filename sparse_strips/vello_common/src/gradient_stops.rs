@@ -0,0 +1,207 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Interning of gradient color stops.
+//!
+//! Every encoded gradient carries its color stops in its [`GradientCacheKey`], which the
+//! gradient ramp caches hash and compare on each lookup. Scenes that rebuild the same
+//! gradients every frame would otherwise clone and rehash the full stop lists for every
+//! draw. A [`GradientStopArena`] hands out [`InternedStops`] instead: a shared handle to the
+//! stops together with their precomputed hash, so that keys built from the same arena are
+//! hashed in constant time and compared by pointer.
+//!
+//! [`GradientCacheKey`]: crate::encode::GradientCacheKey
+
+use alloc::sync::Arc;
+use core::hash::{BuildHasher, Hash, Hasher};
+
+use foldhash::fast::FixedState;
+use hashbrown::HashTable;
+use peniko::color::cache_key::{BitEq, BitHash};
+
+use crate::peniko::ColorStops;
+
+/// The number of [`GradientStopArena::maintain`] calls after which unused stops are dropped.
+const MAX_UNUSED_EPOCHS: u64 = 8;
+
+/// Hash the stops with a fixed seed, so that stops interned by different arenas hash the same.
+fn hash_stops(stops: &ColorStops) -> u64 {
+    let mut hasher = FixedState::with_seed(0).build_hasher();
+    stops.bit_hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A shared list of color stops with a precomputed hash.
+///
+/// Stops interned by the same [`GradientStopArena`] share their allocation and compare in
+/// constant time. Stops from different arenas, or created with [`InternedStops::new`],
+/// still compare equal if their contents do.
+#[derive(Clone, Debug)]
+pub struct InternedStops(Arc<InternedInner>);
+
+#[derive(Debug)]
+struct InternedInner {
+    hash: u64,
+    stops: ColorStops,
+}
+
+impl InternedStops {
+    /// Wrap the stops without interning them.
+    pub fn new(stops: ColorStops) -> Self {
+        Self(Arc::new(InternedInner {
+            hash: hash_stops(&stops),
+            stops,
+        }))
+    }
+
+    /// Return the color stops.
+    pub fn stops(&self) -> &ColorStops {
+        &self.0.stops
+    }
+}
+
+impl BitHash for InternedStops {
+    fn bit_hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash.hash(state);
+    }
+}
+
+impl BitEq for InternedStops {
+    fn bit_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+            || (self.0.hash == other.0.hash && self.0.stops.bit_eq(&other.0.stops))
+    }
+}
+
+#[derive(Debug)]
+struct ArenaEntry {
+    stops: InternedStops,
+    last_used: u64,
+}
+
+/// An interner for the color stops of gradients.
+///
+/// The arena is meant to live as long as the renderer that encodes the gradients, with
+/// [`maintain`](Self::maintain) called once per frame, so that stops that are used every
+/// frame are only hashed once when they are interned and never cloned.
+#[derive(Debug, Default)]
+pub struct GradientStopArena {
+    entries: HashTable<ArenaEntry>,
+    epoch: u64,
+}
+
+impl GradientStopArena {
+    /// Create an empty arena.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the interned version of `stops`, interning them if they aren't yet.
+    pub fn intern(&mut self, stops: &ColorStops) -> InternedStops {
+        let hash = hash_stops(stops);
+        let epoch = self.epoch;
+
+        if let Some(entry) = self
+            .entries
+            .find_mut(hash, |entry| entry.stops.stops().bit_eq(stops))
+        {
+            entry.last_used = epoch;
+            return entry.stops.clone();
+        }
+
+        let interned = InternedStops(Arc::new(InternedInner {
+            hash,
+            stops: stops.clone(),
+        }));
+        self.entries.insert_unique(
+            hash,
+            ArenaEntry {
+                stops: interned.clone(),
+                last_used: epoch,
+            },
+            |entry| entry.stops.0.hash,
+        );
+        interned
+    }
+
+    /// Start a new epoch and drop the stops that haven't been interned for a while.
+    ///
+    /// Dropped stops stay alive for as long as they are referenced elsewhere, for example by
+    /// a gradient cache, and are interned anew when they are used again.
+    pub fn maintain(&mut self) {
+        self.epoch += 1;
+        let epoch = self.epoch;
+        self.entries
+            .retain(|entry| epoch - entry.last_used <= MAX_UNUSED_EPOCHS);
+    }
+
+    /// Return the number of interned stop lists.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return whether no stops are interned.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Drop all interned stops.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GradientStopArena, InternedStops, MAX_UNUSED_EPOCHS};
+    use crate::color::DynamicColor;
+    use crate::color::palette::css::{BLUE, RED};
+    use crate::peniko::{ColorStop, ColorStops};
+    use peniko::color::cache_key::BitEq;
+    use smallvec::smallvec;
+
+    fn stops(offset: f32) -> ColorStops {
+        ColorStops(smallvec![
+            ColorStop {
+                offset: 0.0,
+                color: DynamicColor::from_alpha_color(RED),
+            },
+            ColorStop {
+                offset,
+                color: DynamicColor::from_alpha_color(BLUE),
+            },
+        ])
+    }
+
+    #[test]
+    fn equal_stops_are_shared() {
+        let mut arena = GradientStopArena::new();
+        let a = arena.intern(&stops(1.0));
+        let b = arena.intern(&stops(1.0));
+        let c = arena.intern(&stops(0.5));
+
+        assert!(core::ptr::eq(a.stops(), b.stops()));
+        assert!(a.bit_eq(&b));
+        assert!(!a.bit_eq(&c));
+        assert_eq!(arena.len(), 2);
+
+        // Stops from elsewhere compare by their contents.
+        assert!(a.bit_eq(&InternedStops::new(stops(1.0))));
+        assert!(a.bit_eq(&GradientStopArena::new().intern(&stops(1.0))));
+    }
+
+    #[test]
+    fn unused_stops_are_dropped() {
+        let mut arena = GradientStopArena::new();
+        let a = arena.intern(&stops(1.0));
+        for _ in 0..MAX_UNUSED_EPOCHS {
+            arena.intern(&stops(0.5));
+            arena.maintain();
+        }
+        assert_eq!(arena.len(), 2);
+
+        arena.maintain();
+        assert_eq!(arena.len(), 1);
+        assert!(!core::ptr::eq(a.stops(), arena.intern(&stops(1.0)).stops()));
+    }
+}
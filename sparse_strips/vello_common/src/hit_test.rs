@@ -0,0 +1,237 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Hit testing of filled and stroked paths.
+//!
+//! Interactive applications need to know which of the drawn shapes is under the cursor.
+//! [`hit_test_fill`] and [`hit_test_stroke`] flatten paths, expand strokes and apply fill
+//! rules like the renderers do, so that a point hits a shape where the shape is painted, up to
+//! the flattening tolerance and anti-aliasing.
+//!
+//! Points are given in the coordinate space of the path. To hit test a shape that was drawn
+//! with a transform, apply the inverse of that transform to the point first.
+
+use crate::flatten::{TOL, flatten_closed, stroke_outline, stroke_tolerance};
+use crate::kurbo::{Affine, PathEl, Point, Stroke};
+use crate::peniko::Fill;
+
+/// Return whether `point` is inside `path` when it is filled with `fill_rule`.
+///
+/// Open subpaths are closed, like they are when they are filled.
+pub fn hit_test_fill(
+    path: impl IntoIterator<Item = PathEl>,
+    fill_rule: Fill,
+    point: Point,
+) -> bool {
+    let (winding, _) = winding_and_distance(path, TOL, point);
+    match fill_rule {
+        Fill::NonZero => winding != 0,
+        Fill::EvenOdd => winding % 2 != 0,
+    }
+}
+
+/// Return whether `point` is on `path` when it is stroked with `stroke`, or at most
+/// `tolerance` away from it.
+///
+/// The stroke is expanded including its joins, caps and dashes, and filled with the non-zero
+/// fill rule like the renderers do. `transform` is the transform the path is drawn with, which
+/// determines how accurately the stroke is expanded, see
+/// [`stroke_tolerance`](crate::flatten::stroke_tolerance). A positive `tolerance` makes thin
+/// strokes, and hairlines with a width of zero, easier to hit.
+pub fn hit_test_stroke(
+    path: impl IntoIterator<Item = PathEl>,
+    stroke: &Stroke,
+    transform: Affine,
+    point: Point,
+    tolerance: f64,
+) -> bool {
+    let flatten_tolerance = stroke_tolerance(transform);
+    let outline = stroke_outline(path, stroke, flatten_tolerance);
+    let (winding, distance_sq) = winding_and_distance(&outline, flatten_tolerance, point);
+    winding != 0 || distance_sq <= tolerance * tolerance
+}
+
+/// Flatten `path` with `flatten_tolerance`, and return its winding number around `point` and
+/// the squared distance from `point` to the closest of its lines.
+fn winding_and_distance(
+    path: impl IntoIterator<Item = PathEl>,
+    flatten_tolerance: f64,
+    point: Point,
+) -> (i32, f64) {
+    let mut winding = 0;
+    let mut distance_sq = f64::INFINITY;
+    let line = |p0: Point, p1: Point| {
+        // Count the lines that cross a ray from the point to the right. Lines include their
+        // upper end point but not their lower one, so that points on the ray count once.
+        if (p0.y > point.y) != (p1.y > point.y) {
+            let upwards = p1.y > p0.y;
+            let side = (p1 - p0).cross(point - p0);
+            if (upwards && side > 0.0) || (!upwards && side < 0.0) {
+                winding += if upwards { 1 } else { -1 };
+            }
+        }
+        distance_sq = distance_sq.min(segment_distance_sq(point, p0, p1));
+    };
+    flatten_closed(path, flatten_tolerance, line);
+
+    (winding, distance_sq)
+}
+
+/// The squared distance from `point` to the line segment from `p0` to `p1`.
+fn segment_distance_sq(point: Point, p0: Point, p1: Point) -> f64 {
+    let segment = p1 - p0;
+    let length_sq = segment.hypot2();
+    let t = if length_sq > 0.0 {
+        ((point - p0).dot(segment) / length_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (point - (p0 + segment * t)).hypot2()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hit_test_fill, hit_test_stroke};
+    use crate::kurbo::{Affine, BezPath, Cap, Circle, Line, Point, Rect, Shape, Stroke};
+    use crate::peniko::Fill;
+
+    #[test]
+    fn fill_rules_decide_about_overlapping_subpaths() {
+        let mut path = Rect::new(0.0, 0.0, 20.0, 20.0).to_path(0.1);
+        path.extend(Rect::new(5.0, 5.0, 15.0, 15.0).to_path(0.1));
+
+        for fill_rule in [Fill::NonZero, Fill::EvenOdd] {
+            assert!(hit_test_fill(&path, fill_rule, Point::new(2.0, 10.0)));
+            assert!(!hit_test_fill(&path, fill_rule, Point::new(25.0, 10.0)));
+        }
+        assert!(hit_test_fill(&path, Fill::NonZero, Point::new(10.0, 10.0)));
+        assert!(!hit_test_fill(&path, Fill::EvenOdd, Point::new(10.0, 10.0)));
+    }
+
+    #[test]
+    fn open_subpaths_and_curves_are_filled() {
+        let mut triangle = BezPath::new();
+        triangle.move_to((0.0, 0.0));
+        triangle.line_to((10.0, 0.0));
+        triangle.line_to((0.0, 10.0));
+        assert!(hit_test_fill(
+            &triangle,
+            Fill::NonZero,
+            Point::new(2.0, 2.0)
+        ));
+        assert!(!hit_test_fill(
+            &triangle,
+            Fill::NonZero,
+            Point::new(6.0, 6.0)
+        ));
+
+        let circle = Circle::new((50.0, 50.0), 10.0).to_path(0.1);
+        assert!(hit_test_fill(
+            &circle,
+            Fill::NonZero,
+            Point::new(56.0, 56.0)
+        ));
+        assert!(!hit_test_fill(
+            &circle,
+            Fill::NonZero,
+            Point::new(58.0, 58.0)
+        ));
+    }
+
+    #[test]
+    fn strokes_are_hit_within_their_width_and_tolerance() {
+        let line = Line::new((0.0, 0.0), (100.0, 0.0)).to_path(0.1);
+        let stroke = Stroke::new(4.0).with_caps(Cap::Butt);
+
+        assert!(hit_test_stroke(
+            &line,
+            &stroke,
+            Affine::IDENTITY,
+            Point::new(50.0, 1.9),
+            0.0
+        ));
+        assert!(!hit_test_stroke(
+            &line,
+            &stroke,
+            Affine::IDENTITY,
+            Point::new(50.0, 2.5),
+            0.0
+        ));
+        assert!(hit_test_stroke(
+            &line,
+            &stroke,
+            Affine::IDENTITY,
+            Point::new(50.0, 2.5),
+            1.0
+        ));
+        // Butt caps end at the end points.
+        assert!(!hit_test_stroke(
+            &line,
+            &stroke,
+            Affine::IDENTITY,
+            Point::new(101.0, 0.0),
+            0.0
+        ));
+        // Round caps extend beyond them.
+        assert!(hit_test_stroke(
+            &line,
+            &Stroke::new(4.0).with_caps(Cap::Round),
+            Affine::IDENTITY,
+            Point::new(101.0, 0.0),
+            0.0
+        ));
+
+        // Hairlines can only be hit with a tolerance.
+        let hairline = Stroke::new(0.0);
+        assert!(!hit_test_stroke(
+            &line,
+            &hairline,
+            Affine::IDENTITY,
+            Point::new(50.0, 0.5),
+            0.0
+        ));
+        assert!(hit_test_stroke(
+            &line,
+            &hairline,
+            Affine::IDENTITY,
+            Point::new(50.0, 0.5),
+            1.0
+        ));
+    }
+
+    #[test]
+    fn dash_gaps_are_not_hit() {
+        let line = Line::new((0.0, 0.0), (100.0, 0.0)).to_path(0.1);
+        let stroke = Stroke::new(4.0).with_dashes(0.0, [10.0, 10.0]);
+
+        assert!(hit_test_stroke(
+            &line,
+            &stroke,
+            Affine::IDENTITY,
+            Point::new(5.0, 0.0),
+            0.5
+        ));
+        assert!(!hit_test_stroke(
+            &line,
+            &stroke,
+            Affine::IDENTITY,
+            Point::new(15.0, 0.0),
+            0.5
+        ));
+    }
+
+    #[test]
+    fn strokes_are_expanded_for_their_transform() {
+        // A thin circle that is drawn a hundred times larger must be flattened much more
+        // finely than in user space, otherwise points on it fall outside the stroke.
+        let circle = Circle::new((0.0, 0.0), 1.0).to_path(0.001);
+        let stroke = Stroke::new(0.02);
+        let transform = Affine::scale(100.0);
+
+        for i in 0..36 {
+            let angle = f64::from(i) * 10.0_f64.to_radians() + 0.1;
+            let point = Point::new(angle.cos(), angle.sin());
+            assert!(hit_test_stroke(&circle, &stroke, transform, point, 0.0));
+        }
+    }
+}
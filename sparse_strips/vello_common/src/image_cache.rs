@@ -28,6 +28,8 @@ pub struct ImageResource {
     pub padding: u16,
     /// The atlas allocation ID for deallocation.
     atlas_alloc_id: AllocId,
+    /// The downsampled mip levels of this image, starting with the largest one.
+    mip_levels: Vec<ImageId>,
 }
 
 impl ImageResource {
@@ -40,6 +42,12 @@ impl ImageResource {
     pub fn size(&self) -> [u32; 2] {
         [self.width as u32, self.height as u32]
     }
+
+    /// Returns the ids of the downsampled mip levels of this image, starting with the
+    /// largest one. This is empty for images without a mip chain.
+    pub fn mip_levels(&self) -> &[ImageId] {
+        &self.mip_levels
+    }
 }
 
 /// Manages image resources for the renderer.
@@ -141,12 +149,24 @@ impl ImageCache {
             ],
             padding,
             atlas_alloc_id: atlas_alloc.allocation.id,
+            mip_levels: Vec::new(),
         };
         self.slots[slot_idx] = Some(image_resource);
 
         Ok(image_id)
     }
 
+    /// Attach downsampled mip levels to an image.
+    ///
+    /// Each level must itself have been allocated in this cache. The levels are not
+    /// deallocated together with the image; use [`ImageResource::mip_levels`] on the
+    /// resource returned by [`deallocate`](Self::deallocate) to release them as well.
+    pub fn set_mip_levels(&mut self, id: ImageId, levels: Vec<ImageId>) {
+        if let Some(Some(image_resource)) = self.slots.get_mut(id.as_u32() as usize) {
+            image_resource.mip_levels = levels;
+        }
+    }
+
     /// Deallocate an image from the cache, returning the image resource if it existed.
     pub fn deallocate(&mut self, id: ImageId) -> Option<ImageResource> {
         let index = id.as_u32() as usize;
@@ -0,0 +1,485 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Screen-space label placement.
+//!
+//! Maps and charts annotate their content with far more labels than fit on screen at once. A
+//! [`LabelPlacer`] decides which of them are shown: labels are placed greedily in order of
+//! descending priority, each at the first of its candidate [positions](LabelPosition) that
+//! doesn't collide with a label placed before it. Labels that don't fit next to their anchor
+//! can optionally be moved further away and connected to it with a leader line.
+//!
+//! The size of a label usually comes from its text. Drawing the glyph run of the text with an
+//! [`AccessTag`](crate::accessibility::AccessTag) records its bounds, which
+//! [`Label::from_access_node`] turns into a label.
+
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+
+use crate::accessibility::AccessNode;
+use crate::kurbo::{Line, Point, Rect, Size, Vec2};
+#[cfg(not(feature = "std"))]
+use peniko::kurbo::common::FloatFuncs as _;
+
+/// The size of the cells of the grid used to find colliding labels.
+const CELL_SIZE: f64 = 64.0;
+
+/// A label that should be placed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Label {
+    /// An identifier that is passed back with the placement, for example the index of the
+    /// label text.
+    pub id: u64,
+    /// The point the label describes, in screen space.
+    pub anchor: Point,
+    /// The bounds of the label at its preferred position, in screen space.
+    ///
+    /// Other [positions](LabelPosition) move the label, but keep its size.
+    pub bounds: Rect,
+    /// The priority of the label. If labels collide, the ones with a higher priority are
+    /// shown.
+    pub priority: i32,
+}
+
+impl Label {
+    /// Create a label centered on its anchor.
+    pub fn centered(id: u64, anchor: Point, size: Size, priority: i32) -> Self {
+        Self {
+            id,
+            anchor,
+            bounds: Rect::from_center_size(anchor, size),
+            priority,
+        }
+    }
+
+    /// Create a label from the geometry recorded for its text.
+    ///
+    /// The bounds of `node`, which include all glyph runs drawn with its tag, become the
+    /// preferred position of the label, and its tag becomes the identifier. The text should
+    /// therefore be measured at the position where the label is preferably shown.
+    pub fn from_access_node(node: &AccessNode, anchor: Point, priority: i32) -> Self {
+        Self {
+            id: node.tag().0,
+            anchor,
+            bounds: node.bounds(),
+            priority,
+        }
+    }
+}
+
+/// A candidate position of a label relative to its anchor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LabelPosition {
+    /// The bounds of the label as given.
+    Preferred,
+    /// Centered on the anchor.
+    Center,
+    /// To the right of the anchor, centered vertically.
+    Right,
+    /// To the left of the anchor, centered vertically.
+    Left,
+    /// Above the anchor, centered horizontally.
+    Above,
+    /// Below the anchor, centered horizontally.
+    Below,
+}
+
+impl LabelPosition {
+    /// Return the bounds of `label` at this position, `gap` away from the anchor.
+    fn bounds(self, label: &Label, gap: f64) -> Rect {
+        let Size { width, height } = label.bounds.size();
+        let Point { x, y } = label.anchor;
+        let origin = match self {
+            Self::Preferred => return label.bounds,
+            Self::Center => Point::new(x - 0.5 * width, y - 0.5 * height),
+            Self::Right => Point::new(x + gap, y - 0.5 * height),
+            Self::Left => Point::new(x - gap - width, y - 0.5 * height),
+            Self::Above => Point::new(x - 0.5 * width, y - gap - height),
+            Self::Below => Point::new(x - 0.5 * width, y + gap),
+        };
+
+        Rect::from_origin_size(origin, label.bounds.size())
+    }
+}
+
+/// A label that was placed by a [`LabelPlacer`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlacedLabel {
+    /// The identifier of the label.
+    pub id: u64,
+    /// The bounds of the label in screen space.
+    pub bounds: Rect,
+    /// The line from the anchor to the closest point of the label, if the label was moved
+    /// away from its anchor.
+    pub leader: Option<Line>,
+}
+
+/// Places labels in screen space so that they don't collide.
+///
+/// The placer remembers the labels it placed, so labels can be placed in several batches, for
+/// example one per layer of a map. Call [`LabelPlacer::clear`] before placing the labels of
+/// the next frame.
+#[derive(Debug, Clone)]
+pub struct LabelPlacer {
+    viewport: Rect,
+    positions: Vec<LabelPosition>,
+    gap: f64,
+    padding: f64,
+    leader_length: f64,
+    grid: CollisionGrid,
+}
+
+impl LabelPlacer {
+    /// Create a placer for labels shown in `viewport`.
+    ///
+    /// By default, labels are only tried at their [preferred](LabelPosition::Preferred)
+    /// position, may touch each other and don't fall back to leader lines.
+    pub fn new(viewport: Rect) -> Self {
+        Self {
+            viewport,
+            positions: alloc::vec![LabelPosition::Preferred],
+            gap: 0.0,
+            padding: 0.0,
+            leader_length: 0.0,
+            grid: CollisionGrid::new(viewport),
+        }
+    }
+
+    /// Set the positions that are tried for each label, in order.
+    ///
+    /// `gap` is the distance between the anchor and the label for the positions on a side of
+    /// the anchor.
+    pub fn with_positions(mut self, positions: &[LabelPosition], gap: f64) -> Self {
+        self.positions = positions.to_vec();
+        self.gap = gap;
+        self
+    }
+
+    /// Set the minimum distance between two labels.
+    pub fn with_padding(mut self, padding: f64) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Move labels that don't fit at any of their positions up to `max_length` away from their
+    /// anchor, connected to it with a leader line.
+    ///
+    /// Leader lines don't cross labels placed before them, but later labels can cover them.
+    pub fn with_leader_lines(mut self, max_length: f64) -> Self {
+        self.leader_length = max_length;
+        self
+    }
+
+    /// Reserve an area in which no labels are placed, for example for a legend.
+    pub fn add_obstacle(&mut self, bounds: Rect) {
+        self.grid.insert(bounds);
+    }
+
+    /// Remove all placed labels and obstacles.
+    pub fn clear(&mut self) {
+        self.grid.clear();
+    }
+
+    /// Place `labels` in order of descending priority, and return the placed ones in that
+    /// order.
+    ///
+    /// Labels of the same priority are placed in the order in which they are given. Labels
+    /// that lie completely outside of the viewport or collide with every candidate position
+    /// are dropped.
+    pub fn place(&mut self, labels: &[Label]) -> Vec<PlacedLabel> {
+        let mut order: Vec<_> = labels.iter().collect();
+        // The sort is stable, so labels of the same priority keep their order.
+        order.sort_by_key(|label| core::cmp::Reverse(label.priority));
+
+        order
+            .into_iter()
+            .filter_map(|label| self.try_place(label))
+            .collect()
+    }
+
+    /// Place a single label, ignoring its priority.
+    ///
+    /// Returns `None` if the label lies outside of the viewport or collides with every
+    /// candidate position.
+    pub fn try_place(&mut self, label: &Label) -> Option<PlacedLabel> {
+        let placed = self
+            .positions
+            .iter()
+            .map(|position| position.bounds(label, self.gap))
+            .find(|bounds| self.fits(*bounds))
+            .map(|bounds| PlacedLabel {
+                id: label.id,
+                bounds,
+                leader: None,
+            })
+            .or_else(|| self.place_with_leader(label))?;
+        self.grid.insert(placed.bounds);
+
+        Some(placed)
+    }
+
+    /// Search for a position away from the anchor, going outwards in rings of increasing
+    /// distance.
+    fn place_with_leader(&self, label: &Label) -> Option<PlacedLabel> {
+        const DIRECTIONS: [(f64, f64); 8] = [
+            (1.0, 0.0),
+            (1.0, 1.0),
+            (0.0, 1.0),
+            (-1.0, 1.0),
+            (-1.0, 0.0),
+            (-1.0, -1.0),
+            (0.0, -1.0),
+            (1.0, -1.0),
+        ];
+
+        let size = label.bounds.size();
+        let step = size.height.min(size.width);
+        if self.leader_length <= self.gap || step <= 0.0 {
+            return None;
+        }
+
+        let mut distance = self.gap + step;
+        while distance <= self.leader_length {
+            for (x, y) in DIRECTIONS {
+                let direction = Vec2::new(x, y).normalize();
+                // Move the center of the label so that its closest edge is `distance` away.
+                let extent =
+                    0.5 * (direction.x.abs() * size.width + direction.y.abs() * size.height);
+                let center = label.anchor + direction * (distance + extent);
+                let bounds = Rect::from_center_size(center, size);
+                let leader = Line::new(label.anchor, closest_point(bounds, label.anchor));
+
+                if self.fits(bounds) && !self.grid.crosses(leader) {
+                    return Some(PlacedLabel {
+                        id: label.id,
+                        bounds,
+                        leader: Some(leader),
+                    });
+                }
+            }
+            distance += step;
+        }
+
+        None
+    }
+
+    /// Whether a label with `bounds` can be shown without colliding with placed labels.
+    fn fits(&self, bounds: Rect) -> bool {
+        bounds.overlaps(self.viewport)
+            && !self
+                .grid
+                .collides(bounds.inflate(self.padding, self.padding))
+    }
+}
+
+/// A uniform grid over the viewport, storing the bounds of placed labels in every cell they
+/// touch.
+///
+/// Cells outside of the viewport are clamped to its border, which keeps the grid bounded
+/// while still finding every collision.
+#[derive(Debug, Clone)]
+struct CollisionGrid {
+    origin: Point,
+    columns: i32,
+    rows: i32,
+    rects: Vec<Rect>,
+    cells: HashMap<(i32, i32), Vec<u32>>,
+}
+
+impl CollisionGrid {
+    fn new(viewport: Rect) -> Self {
+        let count = |length: f64| (length / CELL_SIZE).ceil().clamp(1.0, 4096.0) as i32;
+        Self {
+            origin: viewport.origin(),
+            columns: count(viewport.width()),
+            rows: count(viewport.height()),
+            rects: Vec::new(),
+            cells: HashMap::new(),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.rects.clear();
+        self.cells.clear();
+    }
+
+    /// Return the cells touched by `bounds`.
+    fn cells(&self, bounds: Rect) -> impl Iterator<Item = (i32, i32)> {
+        let cell = |value: f64, origin: f64, count: i32| {
+            ((value - origin) / CELL_SIZE)
+                .floor()
+                .clamp(0.0, f64::from(count - 1)) as i32
+        };
+        let (x0, x1) = (
+            cell(bounds.x0, self.origin.x, self.columns),
+            cell(bounds.x1, self.origin.x, self.columns),
+        );
+        let (y0, y1) = (
+            cell(bounds.y0, self.origin.y, self.rows),
+            cell(bounds.y1, self.origin.y, self.rows),
+        );
+
+        (y0..=y1).flat_map(move |y| (x0..=x1).map(move |x| (x, y)))
+    }
+
+    fn insert(&mut self, bounds: Rect) {
+        let index = self.rects.len() as u32;
+        self.rects.push(bounds);
+        for cell in self.cells(bounds).collect::<Vec<_>>() {
+            self.cells.entry(cell).or_default().push(index);
+        }
+    }
+
+    /// Return the rects stored in the cells touched by `bounds`, possibly more than once.
+    fn candidates(&self, bounds: Rect) -> impl Iterator<Item = Rect> + '_ {
+        self.cells(bounds)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .map(|&index| self.rects[index as usize])
+    }
+
+    /// Whether `bounds` overlaps any stored rect. Rects that only touch don't collide.
+    fn collides(&self, bounds: Rect) -> bool {
+        self.candidates(bounds)
+            .any(|other| !other.intersect(bounds).is_zero_area())
+    }
+
+    /// Whether `line` passes through the interior of any stored rect.
+    fn crosses(&self, line: Line) -> bool {
+        let bounds = Rect::from_points(line.p0, line.p1);
+        self.candidates(bounds)
+            .any(|other| line_crosses_rect(line, other))
+    }
+}
+
+/// Return the point of `rect` closest to `point`.
+fn closest_point(rect: Rect, point: Point) -> Point {
+    Point::new(
+        point.x.clamp(rect.x0, rect.x1),
+        point.y.clamp(rect.y0, rect.y1),
+    )
+}
+
+/// Whether `line` passes through the interior of `rect`, using Liang-Barsky clipping.
+fn line_crosses_rect(line: Line, rect: Rect) -> bool {
+    let delta = line.p1 - line.p0;
+    let (mut t0, mut t1) = (0.0_f64, 1.0_f64);
+
+    for (p, q) in [
+        (-delta.x, line.p0.x - rect.x0),
+        (delta.x, rect.x1 - line.p0.x),
+        (-delta.y, line.p0.y - rect.y0),
+        (delta.y, rect.y1 - line.p0.y),
+    ] {
+        if p == 0.0 {
+            // The line is parallel to this edge, and either inside or outside of it.
+            if q <= 0.0 {
+                return false;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+
+    t0 < t1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Label, LabelPlacer, LabelPosition, line_crosses_rect};
+    use crate::accessibility::{AccessGeometry, AccessTag};
+    use crate::kurbo::{Line, Point, Rect, Size};
+    use alloc::vec::Vec;
+
+    fn label(id: u64, x: f64, y: f64, priority: i32) -> Label {
+        Label::centered(id, Point::new(x, y), Size::new(20.0, 10.0), priority)
+    }
+
+    #[test]
+    fn higher_priority_labels_win() {
+        let mut placer = LabelPlacer::new(Rect::new(0.0, 0.0, 100.0, 100.0));
+        let placed = placer.place(&[
+            label(0, 20.0, 20.0, 0),
+            label(1, 25.0, 22.0, 5),
+            // Touches the second label, but doesn't overlap it.
+            label(2, 45.0, 22.0, 0),
+            // Outside of the viewport.
+            label(3, 20.0, 200.0, 9),
+        ]);
+
+        let ids: Vec<_> = placed.iter().map(|label| label.id).collect();
+        assert_eq!(ids, [1, 2]);
+        assert_eq!(placed[0].bounds, Rect::new(15.0, 17.0, 35.0, 27.0));
+    }
+
+    #[test]
+    fn labels_fall_back_to_other_positions() {
+        let mut placer = LabelPlacer::new(Rect::new(0.0, 0.0, 100.0, 100.0))
+            .with_positions(&[LabelPosition::Right, LabelPosition::Left], 2.0);
+        placer.add_obstacle(Rect::new(50.0, 0.0, 100.0, 100.0));
+
+        let placed = placer.place(&[label(0, 10.0, 10.0, 0), label(1, 40.0, 30.0, 0)]);
+        assert_eq!(placed[0].bounds, Rect::new(12.0, 5.0, 32.0, 15.0));
+        // The right side is blocked by the obstacle.
+        assert_eq!(placed[1].bounds, Rect::new(18.0, 25.0, 38.0, 35.0));
+    }
+
+    #[test]
+    fn padding_keeps_labels_apart() {
+        let mut placer = LabelPlacer::new(Rect::new(0.0, 0.0, 100.0, 100.0)).with_padding(4.0);
+        let placed = placer.place(&[label(0, 20.0, 20.0, 0), label(1, 42.0, 20.0, 0)]);
+        assert_eq!(placed.len(), 1);
+    }
+
+    #[test]
+    fn leader_lines_connect_moved_labels() {
+        let mut placer =
+            LabelPlacer::new(Rect::new(0.0, 0.0, 200.0, 200.0)).with_leader_lines(50.0);
+        let placed = placer.place(&[label(0, 100.0, 100.0, 1), label(1, 100.0, 108.0, 0)]);
+
+        assert_eq!(placed.len(), 2);
+        assert_eq!(placed[0].leader, None);
+        let moved = placed[1];
+        let leader = moved.leader.unwrap();
+        assert_eq!(leader.p0, Point::new(100.0, 108.0));
+        assert!(moved.bounds.intersect(placed[0].bounds).is_zero_area());
+        assert!(leader.length() <= 50.0);
+        // The leader line ends on the edge of the moved label.
+        assert!(moved.bounds.inflate(1e-9, 1e-9).contains(leader.p1));
+    }
+
+    #[test]
+    fn leader_lines_avoid_placed_labels() {
+        assert!(line_crosses_rect(
+            Line::new((0.0, 5.0), (20.0, 5.0)),
+            Rect::new(5.0, 0.0, 10.0, 10.0)
+        ));
+        assert!(!line_crosses_rect(
+            Line::new((0.0, 0.0), (20.0, 0.0)),
+            Rect::new(5.0, 0.0, 10.0, 10.0)
+        ));
+        assert!(!line_crosses_rect(
+            Line::new((0.0, 5.0), (5.0, 5.0)),
+            Rect::new(5.0, 0.0, 10.0, 10.0)
+        ));
+    }
+
+    #[test]
+    fn labels_from_glyph_geometry() {
+        let tag = AccessTag(42);
+        let mut geometry = AccessGeometry::new();
+        geometry.add_bounds(tag, Rect::new(10.0, 0.0, 50.0, 12.0));
+        let label = Label::from_access_node(geometry.get(tag).unwrap(), Point::new(5.0, 6.0), 3);
+
+        assert_eq!(label.id, 42);
+        assert_eq!(
+            LabelPosition::Left.bounds(&label, 1.0),
+            Rect::new(-36.0, 0.0, 4.0, 12.0)
+        );
+    }
+}
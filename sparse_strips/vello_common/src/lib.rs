@@ -23,6 +23,8 @@
 //! - `png` (enabled by default): Allow loading [`Pixmap`][crate::pixmap::Pixmap]s from PNG images.
 //!   Also required for rendering glyphs with an embedded PNG.
 //!   Implies `std`.
+//! - `multithreading`: Make the shared state of the renderers thread-safe, and add the
+//!   `executor` module for running their work on worker threads. Implies `std`.
 //!
 //! At least one of `std` and `libm` is required; `std` overrides `libm`.
 //!
@@ -63,20 +65,37 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+pub mod accessibility;
+pub mod alpha_mode;
 pub mod blurred_rounded_rect;
+pub mod chart;
 pub mod clip;
 pub mod coarse;
+pub mod compact_path;
+pub mod custom_paint;
+pub mod dash;
 pub mod encode;
+#[cfg(feature = "multithreading")]
+pub mod executor;
 pub mod filter;
 pub mod filter_effects;
 pub mod flatten;
 pub(crate) mod flatten_simd;
+pub mod fragment;
 pub mod geometry;
+pub mod gradient_bands;
+pub mod gradient_stops;
+pub mod hit_test;
 pub mod image_cache;
+pub mod label;
+pub mod load_op;
 pub mod mask;
 pub mod math;
+pub mod mipmap;
 pub mod multi_atlas;
 pub mod paint;
+pub mod path_ops;
+pub mod pattern;
 #[doc(hidden)]
 #[cfg(feature = "pico_svg")]
 pub mod pico_svg;
@@ -84,14 +103,22 @@ pub mod pixmap;
 #[doc(hidden)]
 #[cfg(feature = "probe")]
 pub mod probe;
+pub mod quality;
+pub mod recording;
 pub mod rect;
 pub mod render_graph;
 pub mod render_state;
+pub mod render_stats;
 pub mod simd;
+pub mod simplify;
 pub mod strip;
+pub mod strip_debug;
 pub mod strip_generator;
 pub mod tile;
+pub mod tile_cache;
 pub mod util;
+pub mod variable_width;
+pub mod winding;
 
 pub use fearless_simd;
 pub use peniko;
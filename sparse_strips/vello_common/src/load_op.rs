@@ -0,0 +1,33 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Initialization of the render target before a scene is drawn into it.
+
+use crate::color::{AlphaColor, Srgb};
+use crate::paint::PremulColor;
+
+/// The operation applied to the render target before a scene is drawn into it.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LoadOp {
+    /// Clear the target to transparent black.
+    #[default]
+    ClearTransparent,
+    /// Clear the target to a color.
+    Clear(AlphaColor<Srgb>),
+    /// Preserve the existing contents of the target and composite the scene on top of them.
+    ///
+    /// This allows drawing an overlay onto a frame that was rendered by someone else
+    /// without copying it first.
+    Load,
+}
+
+impl LoadOp {
+    /// The color the target is cleared to, or `None` if its contents are preserved.
+    pub fn clear_color(&self) -> Option<PremulColor> {
+        match self {
+            Self::ClearTransparent => Some(PremulColor::from_alpha_color(AlphaColor::TRANSPARENT)),
+            Self::Clear(color) => Some(PremulColor::from_alpha_color(*color)),
+            Self::Load => None,
+        }
+    }
+}
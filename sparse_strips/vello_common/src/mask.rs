@@ -14,6 +14,30 @@ struct MaskRepr {
     height: u16,
 }
 
+/// How the content of a mask is turned into coverage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MaskKind {
+    /// The alpha channel of the mask content is used as coverage.
+    #[default]
+    Alpha,
+    /// The luminance of the mask content is used as coverage.
+    ///
+    /// The luminance is computed from premultiplied colors, so transparent parts of the mask
+    /// content also mask out the masked content.
+    Luminance,
+}
+
+/// Compute the luminance of a premultiplied color, which is used as coverage by luminance masks.
+///
+/// See CSS Masking Module Level 1 § 7.10.1
+/// <https://www.w3.org/TR/css-masking-1/#MaskValues>
+/// and Filter Effects Module Level 1 § 9.6
+/// <https://www.w3.org/TR/filter-effects-1/#elementdef-fecolormatrix>.
+#[inline(always)]
+pub fn luminance(r: f32, g: f32, b: f32) -> f32 {
+    r * 0.2126 + g * 0.7152 + b * 0.0722
+}
+
 // Note that we are on purpose storing width and height inside the `Arc`
 // to reduce the memory footprint of the struct.
 /// A mask.
@@ -66,12 +90,8 @@ impl Mask {
                     let g = f32::from(pixel.g) / 255.;
                     let b = f32::from(pixel.b) / 255.;
 
-                    // See CSS Masking Module Level 1 § 7.10.1
-                    // <https://www.w3.org/TR/css-masking-1/#MaskValues>
-                    // and Filter Effects Module Level 1 § 9.6
-                    // <https://www.w3.org/TR/filter-effects-1/#elementdef-fecolormatrix>.
                     // Note r, g and b are premultiplied by alpha.
-                    let luma = r * 0.2126 + g * 0.7152 + b * 0.0722;
+                    let luma = luminance(r, g, b);
                     #[expect(clippy::cast_possible_truncation, reason = "This cannot overflow")]
                     {
                         (luma * 255.0 + 0.5) as u8
@@ -0,0 +1,198 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Mip chains for minified image sampling.
+//!
+//! Sampling an image at full resolution while it is drawn much smaller than its native
+//! size skips over most of its pixels, which shows up as shimmering when the image moves.
+//! A [`MipChain`] stores successively halved versions of an image, so that renderers can
+//! sample from a level whose resolution roughly matches the area covered on screen.
+
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::peniko::color::PremulRgba8;
+use crate::pixmap::Pixmap;
+
+/// A chain of successively downsampled versions of an image.
+///
+/// Level 0 is the original image, and every following level has half the width and height
+/// of the previous one (rounded up), down to a single pixel.
+#[derive(Debug, Clone)]
+pub struct MipChain {
+    levels: Vec<Arc<Pixmap>>,
+}
+
+impl MipChain {
+    /// Build the full mip chain of `base`.
+    ///
+    /// Images in other color spaces are converted to sRGB first, so that all levels are
+    /// in sRGB.
+    pub fn new(base: Arc<Pixmap>) -> Self {
+        let mut levels = vec![base.into_srgb()];
+
+        loop {
+            let last = levels.last().unwrap();
+            if last.width() == 1 && last.height() == 1 {
+                break;
+            }
+            let next = downsample(last);
+            levels.push(Arc::new(next));
+        }
+
+        Self { levels }
+    }
+
+    /// Return all levels of the chain, starting with the original image.
+    pub fn levels(&self) -> &[Arc<Pixmap>] {
+        &self.levels
+    }
+
+    /// Return the level at `index`, or the smallest level if the chain is shorter than that.
+    pub fn level(&self, index: usize) -> &Arc<Pixmap> {
+        &self.levels[index.min(self.levels.len() - 1)]
+    }
+
+    /// Return the number of levels, including the original image.
+    pub fn len(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Return whether the chain is empty. A mip chain always contains at least the
+    /// original image, so this always returns `false`.
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+}
+
+/// Return the mip level that is closest to the given level of detail.
+///
+/// `lod` is the base-2 logarithm of the number of image pixels covered by one device pixel,
+/// as returned by [`EncodedImage::mip_lod`](crate::encode::EncodedImage::mip_lod).
+/// Magnified images always use level 0.
+pub fn nearest_level(lod: f32, level_count: usize) -> usize {
+    if lod.is_nan() || lod <= 0.5 {
+        return 0;
+    }
+
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "the level of detail is positive and clamped to the chain length below"
+    )]
+    let level = (lod + 0.5) as usize;
+    level.min(level_count.saturating_sub(1))
+}
+
+/// Downsample a pixmap to half its width and height (rounded up) using a box filter.
+///
+/// For odd dimensions, the last row or column is averaged with itself.
+pub fn downsample(pixmap: &Pixmap) -> Pixmap {
+    let src_width = pixmap.width();
+    let src_height = pixmap.height();
+    let width = src_width.div_ceil(2).max(1);
+    let height = src_height.div_ceil(2).max(1);
+    let src = pixmap.data();
+    let mut data = Vec::with_capacity(usize::from(width) * usize::from(height));
+
+    for y in 0..height {
+        let y0 = usize::from((2 * y).min(src_height - 1));
+        let y1 = usize::from((2 * y + 1).min(src_height - 1));
+
+        for x in 0..width {
+            let x0 = usize::from((2 * x).min(src_width - 1));
+            let x1 = usize::from((2 * x + 1).min(src_width - 1));
+            let row0 = y0 * usize::from(src_width);
+            let row1 = y1 * usize::from(src_width);
+
+            data.push(average([
+                src[row0 + x0],
+                src[row0 + x1],
+                src[row1 + x0],
+                src[row1 + x1],
+            ]));
+        }
+    }
+
+    let mut downsampled = Pixmap::from_parts(data, width, height);
+    downsampled.set_may_have_transparency(pixmap.may_have_transparency());
+    downsampled.set_color_space(pixmap.color_space());
+    downsampled
+}
+
+fn average(pixels: [PremulRgba8; 4]) -> PremulRgba8 {
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "the average of four u8 values fits into a u8"
+    )]
+    let channel = |f: fn(&PremulRgba8) -> u8| {
+        ((pixels.iter().map(|p| u16::from(f(p))).sum::<u16>() + 2) / 4) as u8
+    };
+
+    PremulRgba8 {
+        r: channel(|p| p.r),
+        g: channel(|p| p.g),
+        b: channel(|p| p.b),
+        a: channel(|p| p.a),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(v: u8) -> PremulRgba8 {
+        PremulRgba8 {
+            r: v,
+            g: v,
+            b: v,
+            a: 255,
+        }
+    }
+
+    #[test]
+    fn downsample_averages_quads() {
+        let data = vec![pixel(0), pixel(100), pixel(200), pixel(40)];
+        let pixmap = Pixmap::from_parts(data, 2, 2);
+        let downsampled = downsample(&pixmap);
+
+        assert_eq!((downsampled.width(), downsampled.height()), (1, 1));
+        assert_eq!(downsampled.data()[0], pixel(85));
+    }
+
+    #[test]
+    fn downsample_rounds_odd_sizes_up() {
+        let data = (0..15).map(|v| pixel(v * 10)).collect();
+        let pixmap = Pixmap::from_parts(data, 5, 3);
+        let downsampled = downsample(&pixmap);
+
+        assert_eq!((downsampled.width(), downsampled.height()), (3, 2));
+        // The last column only covers a single source column.
+        assert_eq!(downsampled.data()[2], pixel(65));
+        // The last row only covers a single source row.
+        assert_eq!(downsampled.data()[3], pixel(105));
+    }
+
+    #[test]
+    fn chain_ends_at_single_pixel() {
+        let chain = MipChain::new(Arc::new(Pixmap::new(16, 5)));
+        let sizes = chain
+            .levels()
+            .iter()
+            .map(|p| (p.width(), p.height()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(sizes, [(16, 5), (8, 3), (4, 2), (2, 1), (1, 1)]);
+        assert_eq!(chain.level(10).width(), 1);
+    }
+
+    #[test]
+    fn nearest_level_rounds_and_clamps() {
+        assert_eq!(nearest_level(-1.0, 5), 0);
+        assert_eq!(nearest_level(0.4, 5), 0);
+        assert_eq!(nearest_level(0.6, 5), 1);
+        assert_eq!(nearest_level(2.49, 5), 2);
+        assert_eq!(nearest_level(9.0, 5), 4);
+        assert_eq!(nearest_level(f32::NAN, 5), 0);
+    }
+}
@@ -81,6 +81,8 @@ pub enum ImageSource {
         /// Whether the image may contain non-opaque pixels.
         may_have_transparency: bool,
     },
+    /// Two registered images that are blended into each other, like CSS `cross-fade()`.
+    CrossFade(CrossFade),
 }
 
 impl ImageSource {
@@ -104,6 +106,19 @@ impl ImageSource {
         }
     }
 
+    /// Create an [`ImageSource`] that blends the pre-registered image `from` into `to`.
+    ///
+    /// `t` is clamped to the range between 0, which shows only `from`, and 1, which shows
+    /// only `to`. Conservatively assumes the images may have non-opaque pixels.
+    pub fn cross_fade(from: ImageId, to: ImageId, t: f32) -> Self {
+        Self::CrossFade(CrossFade {
+            from,
+            to,
+            t: t.clamp(0.0, 1.0),
+            may_have_transparency: true,
+        })
+    }
+
     /// Returns whether this image source may contain non-opaque pixels.
     pub fn may_have_transparency(&self) -> bool {
         match self {
@@ -112,6 +127,7 @@ impl ImageSource {
                 may_have_transparency,
                 ..
             } => *may_have_transparency,
+            Self::CrossFade(cross_fade) => cross_fade.may_have_transparency,
         }
     }
 
@@ -173,6 +189,23 @@ impl ImageSource {
     }
 }
 
+/// A cross-fade between two pre-registered images.
+///
+/// Each pixel is `from * (1 - t) + to * t`, evaluated on the premultiplied colors of both
+/// images. The result has the size of `from`, and `to` is stretched to cover the same
+/// area, so transitions between images of different sizes don't shift their content.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrossFade {
+    /// The image that is shown at `t = 0`.
+    pub from: ImageId,
+    /// The image that is shown at `t = 1`.
+    pub to: ImageId,
+    /// How far the transition has progressed, between 0 and 1.
+    pub t: f32,
+    /// Whether either of the images may contain non-opaque pixels.
+    pub may_have_transparency: bool,
+}
+
 /// An image.
 pub type Image = peniko::ImageBrush<ImageSource>;
 
@@ -190,6 +223,19 @@ pub trait ImageResolver: Send + Sync {
     ///
     /// Returns `None` if the image ID is not found in the registry.
     fn resolve(&self, id: ImageId) -> Option<Arc<Pixmap>>;
+
+    /// Resolve a downsampled version of an image for sampling at the given level of detail.
+    ///
+    /// `lod` is the level of detail as returned by
+    /// [`EncodedImage::mip_lod`](crate::encode::EncodedImage::mip_lod). Implementations that
+    /// keep mip chains for some of their images return the best matching level here.
+    ///
+    /// Returns `None` if the image should be sampled at full resolution, which is the
+    /// default.
+    fn resolve_mip(&self, id: ImageId, lod: f32) -> Option<Arc<Pixmap>> {
+        let _ = (id, lod);
+        None
+    }
 }
 
 /// A no-op image resolver that always returns `None`.
@@ -254,6 +300,12 @@ pub enum TintMode {
     /// Each channel of the source pixel is multiplied by the corresponding
     /// channel of the tint color. This works well for full-color images.
     Multiply = 1,
+    /// Inverted per-channel coverage: `1 - tint.alpha * source.rgb`, with an opaque alpha.
+    ///
+    /// The color channels of the source image are used as separate coverage masks, as
+    /// stored for subpixel-antialiased glyphs. Drawn with a multiply blend, this removes
+    /// the covered part of each channel from the backdrop.
+    InverseCoverage = 2,
 }
 
 impl TintMode {
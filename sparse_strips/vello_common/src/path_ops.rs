@@ -0,0 +1,514 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Boolean operations on the areas of paths.
+//!
+//! [`path_op`] combines the areas filled by two paths into a new path, for example to merge
+//! two shapes or to cut one out of another. The result can be filled or stroked like any other
+//! path.
+//!
+//! Both paths are flattened, and the end points of the lines are snapped to a fine grid, so
+//! that all intersection tests are exact. The lines are split at every point where they meet,
+//! and each of the resulting pieces is classified by the winding numbers of both paths on
+//! either side of it. The pieces that separate the inside of the result from the outside are
+//! then joined into closed subpaths.
+
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+
+use crate::flatten::flatten_closed;
+use crate::kurbo::{BezPath, PathEl, Point};
+use crate::peniko::Fill;
+#[cfg(not(feature = "std"))]
+use peniko::kurbo::common::FloatFuncs as _;
+
+/// The number of grid cells per unit of tolerance that points are snapped to.
+const GRID_DIVISIONS: f64 = 1024.0;
+
+/// A point on the snapping grid, in units of grid cells.
+type Key = (i64, i64);
+
+/// A boolean operation on the areas of two paths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathOp {
+    /// The area covered by either path.
+    Union,
+    /// The area covered by both paths.
+    Intersection,
+    /// The area covered by the first path, but not by the second one.
+    Difference,
+    /// The area covered by exactly one of the paths.
+    Xor,
+}
+
+impl PathOp {
+    /// Whether a point is in the result, given whether it is inside each of the paths.
+    fn contains(self, a: bool, b: bool) -> bool {
+        match self {
+            Self::Union => a || b,
+            Self::Intersection => a && b,
+            Self::Difference => a && !b,
+            Self::Xor => a != b,
+        }
+    }
+}
+
+/// A line of one of the paths, between two grid points.
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    p0: Key,
+    p1: Key,
+    /// The index of the path the edge belongs to.
+    operand: usize,
+}
+
+/// Combine the areas of `a` and `b`, filled with the fill rules `a_fill` and `b_fill`.
+///
+/// Curves are flattened with `tolerance`, and the points of the result are accurate to a
+/// small fraction of it, so the result only contains lines. Open subpaths are closed, like
+/// they would be when they are filled. The subpaths of the result don't cross each other, and
+/// are oriented so that the combined area is filled with both [`Fill::NonZero`] and
+/// [`Fill::EvenOdd`].
+///
+/// # Panics
+///
+/// Panics if `tolerance` is not positive.
+pub fn path_op(
+    a: impl IntoIterator<Item = PathEl>,
+    a_fill: Fill,
+    b: impl IntoIterator<Item = PathEl>,
+    b_fill: Fill,
+    op: PathOp,
+    tolerance: f64,
+) -> BezPath {
+    assert!(tolerance > 0.0, "the tolerance must be positive");
+    let grid = tolerance / GRID_DIVISIONS;
+
+    let mut edges = Vec::new();
+    flatten_edges(a, 0, tolerance, grid, &mut edges);
+    flatten_edges(b, 1, tolerance, grid, &mut edges);
+    let edges = split_edges(&edges);
+
+    let fills = [a_fill, b_fill];
+    let boundary = classify_edges(&edges, |winding| {
+        let inside = |operand: usize| match fills[operand] {
+            Fill::NonZero => winding[operand] != 0,
+            Fill::EvenOdd => winding[operand] % 2 != 0,
+        };
+        op.contains(inside(0), inside(1))
+    });
+
+    let mut path = BezPath::new();
+    let to_point = |(x, y): Key| Point::new(x as f64 * grid, y as f64 * grid);
+    for points in join_edges(&boundary) {
+        path.move_to(to_point(points[0]));
+        for &point in &points[1..] {
+            path.line_to(to_point(point));
+        }
+        path.close_path();
+    }
+    path
+}
+
+/// Flatten `path` and append its lines to `edges`, closing all subpaths.
+fn flatten_edges(
+    path: impl IntoIterator<Item = PathEl>,
+    operand: usize,
+    tolerance: f64,
+    grid: f64,
+    edges: &mut Vec<Edge>,
+) {
+    let key = |p: Point| ((p.x / grid).round() as i64, (p.y / grid).round() as i64);
+    flatten_closed(path, tolerance, |p0, p1| {
+        let (p0, p1) = (key(p0), key(p1));
+        if p0 != p1 {
+            edges.push(Edge { p0, p1, operand });
+        }
+    });
+}
+
+/// Split the edges at all points where they cross or touch another edge.
+fn split_edges(edges: &[Edge]) -> Vec<Edge> {
+    let min_y = |edge: &Edge| edge.p0.1.min(edge.p1.1);
+    let max_y = |edge: &Edge| edge.p0.1.max(edge.p1.1);
+
+    // Only edges whose vertical extents overlap can meet, so sweep over the edges from top to
+    // bottom.
+    let mut order: Vec<usize> = (0..edges.len()).collect();
+    order.sort_unstable_by_key(|&i| min_y(&edges[i]));
+    let mut splits: Vec<Vec<Key>> = edges.iter().map(|_| Vec::new()).collect();
+    for (n, &i) in order.iter().enumerate() {
+        let bottom = max_y(&edges[i]);
+        for &j in &order[n + 1..] {
+            if min_y(&edges[j]) > bottom {
+                break;
+            }
+            intersect(&edges[i], &edges[j], |on_first, point| {
+                splits[if on_first { i } else { j }].push(point);
+            });
+        }
+    }
+
+    let mut split = Vec::with_capacity(edges.len());
+    for (edge, points) in edges.iter().zip(&mut splits) {
+        let direction = sub(edge.p1, edge.p0);
+        points.sort_unstable_by_key(|&p| dot(sub(p, edge.p0), direction));
+        points.dedup();
+
+        let mut p0 = edge.p0;
+        for &p1 in points.iter().chain([&edge.p1]) {
+            if p1 != p0 {
+                split.push(Edge { p0, p1, ..*edge });
+                p0 = p1;
+            }
+        }
+    }
+    split
+}
+
+/// Report the points where `e` and `f` meet in the interior of one of them.
+///
+/// `split` is called with whether the point is in the interior of `e` rather than `f`.
+fn intersect(e: &Edge, f: &Edge, mut split: impl FnMut(bool, Key)) {
+    let (e_min, e_max) = (e.p0.0.min(e.p1.0), e.p0.0.max(e.p1.0));
+    if f.p0.0.max(f.p1.0) < e_min || f.p0.0.min(f.p1.0) > e_max {
+        return;
+    }
+
+    let r = sub(e.p1, e.p0);
+    let s = sub(f.p1, f.p0);
+    let qp = sub(f.p0, e.p0);
+    let denom = cross(r, s);
+    if denom == 0 {
+        if cross(qp, r) != 0 {
+            // Parallel, but not on the same line.
+            return;
+        }
+        // Collinear: split each edge at the end points of the other one that lie within it.
+        let within = |p: Key, edge: &Edge| {
+            let direction = sub(edge.p1, edge.p0);
+            let t = dot(sub(p, edge.p0), direction);
+            t > 0 && t < dot(direction, direction)
+        };
+        for p in [f.p0, f.p1] {
+            if within(p, e) {
+                split(true, p);
+            }
+        }
+        for p in [e.p0, e.p1] {
+            if within(p, f) {
+                split(false, p);
+            }
+        }
+        return;
+    }
+
+    // The intersection is at `e.p0 + r * t` and at `f.p0 + s * u`, with `t = t_num / denom`
+    // and `u = u_num / denom`.
+    let (mut t_num, mut u_num, mut denom) = (cross(qp, s), cross(qp, r), denom);
+    if denom < 0 {
+        (t_num, u_num, denom) = (-t_num, -u_num, -denom);
+    }
+    if !(0..=denom).contains(&t_num) || !(0..=denom).contains(&u_num) {
+        return;
+    }
+
+    let point = if t_num == 0 {
+        e.p0
+    } else if t_num == denom {
+        e.p1
+    } else if u_num == 0 {
+        f.p0
+    } else if u_num == denom {
+        f.p1
+    } else {
+        let t = t_num as f64 / denom as f64;
+        (
+            (e.p0.0 as f64 + r.0 as f64 * t).round() as i64,
+            (e.p0.1 as f64 + r.1 as f64 * t).round() as i64,
+        )
+    };
+    if t_num > 0 && t_num < denom {
+        split(true, point);
+    }
+    if u_num > 0 && u_num < denom {
+        split(false, point);
+    }
+}
+
+/// Return the edges that have the inside of the result on one side and the outside on the
+/// other, oriented so that the inside is on their left.
+///
+/// `contains` decides whether a point is inside the result, given the winding numbers of
+/// both paths around it. Edges that lie on top of each other are classified together, and
+/// yield at most one edge.
+fn classify_edges(edges: &[Edge], contains: impl Fn([i32; 2]) -> bool) -> Vec<(Key, Key)> {
+    // The edges between the same points, in the direction from the smaller point to the
+    // larger one, with the sum of their directions for each path.
+    let canonical = |edge: &Edge| {
+        if edge.p0 < edge.p1 {
+            (edge.p0, edge.p1)
+        } else {
+            (edge.p1, edge.p0)
+        }
+    };
+    let mut groups: Vec<((Key, Key), [i32; 2])> = Vec::new();
+    let mut group_indices = HashMap::new();
+    for edge in edges {
+        let key = canonical(edge);
+        let idx = *group_indices.entry(key).or_insert_with(|| {
+            groups.push((key, [0, 0]));
+            groups.len() - 1
+        });
+        groups[idx].1[edge.operand] += if edge.p0 == key.0 { 1 } else { -1 };
+    }
+
+    let mut boundary = Vec::new();
+    for &((p0, p1), directions) in &groups {
+        // Cast a ray from the middle of the edges along the axis they are least aligned with,
+        // in doubled coordinates so that the middle is on the grid.
+        let direction = sub(p1, p0);
+        let horizontal = direction.1.abs() >= direction.0.abs();
+        let mid = (p0.0 + p1.0, p0.1 + p1.1);
+        let far = winding_along_ray(edges, mid, horizontal, |edge| canonical(edge) == (p0, p1));
+
+        // Crossing the edges towards the ray changes the winding numbers by their
+        // contributions to it.
+        let crossing = if horizontal {
+            direction.1.signum()
+        } else {
+            -direction.0.signum()
+        } as i32;
+        let near = [
+            far[0] + crossing * directions[0],
+            far[1] + crossing * directions[1],
+        ];
+        let far_is_left = if horizontal {
+            direction.1 < 0
+        } else {
+            direction.0 > 0
+        };
+        let (left, right) = if far_is_left {
+            (far, near)
+        } else {
+            (near, far)
+        };
+
+        match (contains(left), contains(right)) {
+            (true, false) => boundary.push((p0, p1)),
+            (false, true) => boundary.push((p1, p0)),
+            _ => {}
+        }
+    }
+    boundary
+}
+
+/// Return the winding numbers of both paths at the doubled point `mid`, from the crossings of
+/// a ray in the positive x or y direction with the edges that are not `excluded`.
+fn winding_along_ray(
+    edges: &[Edge],
+    mid: Key,
+    horizontal: bool,
+    excluded: impl Fn(&Edge) -> bool,
+) -> [i32; 2] {
+    // Swapping the axes turns a vertical ray into a horizontal one, but mirrors the edges.
+    let (mid, sign) = if horizontal {
+        (mid, 1)
+    } else {
+        ((mid.1, mid.0), -1)
+    };
+    let mut winding = [0, 0];
+    for edge in edges {
+        let (mut a, mut b) = (
+            (2 * edge.p0.0, 2 * edge.p0.1),
+            (2 * edge.p1.0, 2 * edge.p1.1),
+        );
+        if !horizontal {
+            a = (a.1, a.0);
+            b = (b.1, b.0);
+        }
+        if (a.1 > mid.1) == (b.1 > mid.1) || excluded(edge) {
+            continue;
+        }
+        // The edge crosses the ray if the middle is to its left when it goes up, or to its
+        // right when it goes down.
+        let upwards = b.1 > a.1;
+        let side = cross(sub(b, a), sub(mid, a));
+        if (upwards && side > 0) || (!upwards && side < 0) {
+            winding[edge.operand] += if upwards { sign } else { -sign };
+        }
+    }
+    winding
+}
+
+/// Join directed edges into closed loops, dropping points in the middle of straight lines.
+fn join_edges(edges: &[(Key, Key)]) -> Vec<Vec<Key>> {
+    let mut outgoing: HashMap<Key, Vec<Key>> = HashMap::new();
+    for &(p0, p1) in edges.iter().rev() {
+        outgoing.entry(p0).or_default().push(p1);
+    }
+
+    let mut loops = Vec::new();
+    for &(start, _) in edges {
+        let mut points = Vec::new();
+        let mut current = start;
+        // Every point of the boundary has as many incoming as outgoing edges, so the loop
+        // returns to its start.
+        while let Some(next) = outgoing.get_mut(&current).and_then(Vec::pop) {
+            points.push(current);
+            current = next;
+            if current == start {
+                break;
+            }
+        }
+        remove_straight_points(&mut points);
+        if points.len() >= 3 {
+            loops.push(points);
+        }
+    }
+    loops
+}
+
+/// Remove the points of a closed loop that are in the middle of a straight line.
+fn remove_straight_points(points: &mut Vec<Key>) {
+    let straight = |a: Key, b: Key, c: Key| {
+        let (ab, bc) = (sub(b, a), sub(c, b));
+        cross(ab, bc) == 0 && dot(ab, bc) > 0
+    };
+
+    let mut kept: Vec<Key> = Vec::with_capacity(points.len());
+    for &p in points.iter() {
+        while kept.len() >= 2 && straight(kept[kept.len() - 2], kept[kept.len() - 1], p) {
+            kept.pop();
+        }
+        kept.push(p);
+    }
+    // The loop wraps around, so the first and last points can be straight as well.
+    while kept.len() >= 3 {
+        let n = kept.len();
+        if straight(kept[n - 2], kept[n - 1], kept[0]) {
+            kept.pop();
+        } else if straight(kept[n - 1], kept[0], kept[1]) {
+            kept.remove(0);
+        } else {
+            break;
+        }
+    }
+    *points = kept;
+}
+
+fn sub(a: Key, b: Key) -> Key {
+    (a.0 - b.0, a.1 - b.1)
+}
+
+/// The cross product, which can exceed the range of `i64` for large coordinates.
+fn cross(a: Key, b: Key) -> i128 {
+    i128::from(a.0) * i128::from(b.1) - i128::from(a.1) * i128::from(b.0)
+}
+
+fn dot(a: Key, b: Key) -> i128 {
+    i128::from(a.0) * i128::from(b.0) + i128::from(a.1) * i128::from(b.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PathOp, path_op};
+    use crate::kurbo::{BezPath, Circle, PathEl, Rect, Shape};
+    use crate::peniko::Fill;
+    use core::f64::consts::PI;
+
+    const TOLERANCE: f64 = 0.1;
+
+    fn op(a: &BezPath, b: &BezPath, op: PathOp) -> BezPath {
+        path_op(a, Fill::NonZero, b, Fill::NonZero, op, TOLERANCE)
+    }
+
+    fn subpaths(path: &BezPath) -> usize {
+        path.elements()
+            .iter()
+            .filter(|el| matches!(el, PathEl::MoveTo(_)))
+            .count()
+    }
+
+    #[test]
+    fn overlapping_rectangles() {
+        let a = Rect::new(0.0, 0.0, 10.0, 10.0).to_path(TOLERANCE);
+        let b = Rect::new(5.0, 5.0, 15.0, 15.0).to_path(TOLERANCE);
+
+        for (path_op, area) in [
+            (PathOp::Union, 175.0),
+            (PathOp::Intersection, 25.0),
+            (PathOp::Difference, 75.0),
+            (PathOp::Xor, 150.0),
+        ] {
+            let result = op(&a, &b, path_op);
+            assert!(
+                (result.area().abs() - area).abs() < 1e-6,
+                "{path_op:?}: {}",
+                result.area()
+            );
+        }
+    }
+
+    #[test]
+    fn holes_are_oriented_against_their_outline() {
+        let a = Rect::new(0.0, 0.0, 20.0, 20.0).to_path(TOLERANCE);
+        let b = Rect::new(5.0, 5.0, 15.0, 15.0).to_path(TOLERANCE);
+
+        let result = op(&a, &b, PathOp::Difference);
+        assert_eq!(subpaths(&result), 2);
+        // The signed areas of the outline and the hole cancel out, so the hole is not filled
+        // with either fill rule.
+        assert!((result.area().abs() - 300.0).abs() < 1e-6);
+        assert_eq!(result.winding((10.0, 10.0).into()), 0);
+        assert_ne!(result.winding((2.0, 10.0).into()), 0);
+    }
+
+    #[test]
+    fn shared_edges_are_merged() {
+        let a = Rect::new(0.0, 0.0, 10.0, 10.0).to_path(TOLERANCE);
+        let b = Rect::new(10.0, 0.0, 20.0, 10.0).to_path(TOLERANCE);
+
+        let union = op(&a, &b, PathOp::Union);
+        assert!((union.area().abs() - 200.0).abs() < 1e-6);
+        // A single rectangle, without points in the middle of its edges.
+        assert_eq!(union.elements().len(), 5);
+
+        let intersection = op(&a, &b, PathOp::Intersection);
+        assert!(intersection.elements().is_empty());
+        let identical = op(&a, &a, PathOp::Xor);
+        assert!(identical.elements().is_empty());
+    }
+
+    #[test]
+    fn curves_are_flattened_within_tolerance() {
+        let a = Circle::new((0.0, 0.0), 10.0).to_path(0.01);
+        let b = Circle::new((10.0, 0.0), 10.0).to_path(0.01);
+
+        // Two circular segments of the lens between the circles, each with an angle of 120°.
+        let lens = 2.0 * (100.0 * (PI / 3.0 - 0.75_f64.sqrt() / 2.0));
+        let circle = PI * 100.0;
+        for (path_op, area) in [
+            (PathOp::Intersection, lens),
+            (PathOp::Union, 2.0 * circle - lens),
+            (PathOp::Difference, circle - lens),
+        ] {
+            let result = op(&a, &b, path_op);
+            let error = (result.area().abs() - area).abs();
+            assert!(error < 0.02 * area, "{path_op:?}: {}", result.area());
+        }
+    }
+
+    #[test]
+    fn fill_rules_of_the_operands_are_respected() {
+        // Two nested squares with the same orientation.
+        let mut nested = Rect::new(0.0, 0.0, 20.0, 20.0).to_path(TOLERANCE);
+        nested.extend(Rect::new(5.0, 5.0, 15.0, 15.0).to_path(TOLERANCE));
+        let empty = BezPath::new();
+
+        for (fill, area) in [(Fill::NonZero, 400.0), (Fill::EvenOdd, 300.0)] {
+            let result = path_op(&nested, fill, &empty, fill, PathOp::Union, TOLERANCE);
+            assert!((result.area().abs() - area).abs() < 1e-6, "{fill:?}");
+        }
+    }
+}
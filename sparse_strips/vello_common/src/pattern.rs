@@ -0,0 +1,407 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Repeating vector patterns.
+//!
+//! A [`Pattern`] is a small recorded scene fragment together with a tile rectangle and
+//! spacing. Renderers rasterize the fragment once into a single cell (the tile plus the
+//! spacing, rounded up to whole pixels) and then tile that cell using an image paint with
+//! [`Extend::Repeat`], so hatching and repeating backgrounds can be described as vector
+//! geometry instead of being converted to bitmaps up front.
+//!
+//! The fragment can be painted with solid colors, gradients and images, including the cells
+//! of other registered patterns. Images are referenced the same way as in the scene the
+//! pattern is used in, so they have to be registered with the same renderer.
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::color::palette::css::BLACK;
+use crate::kurbo::{Affine, BezPath, Rect, Shape, Stroke, Vec2};
+use crate::paint::{Image, ImageSource, PaintType};
+use crate::peniko::{Extend, Fill, ImageQuality, ImageSampler};
+#[cfg(not(feature = "std"))]
+use peniko::kurbo::common::FloatFuncs as _;
+
+/// A single draw command recorded into a [`Pattern`].
+///
+/// The variants correspond 1:1 to the methods on [`PatternSink`].
+#[derive(Clone, Debug)]
+pub enum PatternCommand {
+    /// Set the current transform, relative to the pattern space.
+    SetTransform(Affine),
+    /// Set the current paint.
+    SetPaint(PaintType),
+    /// Set the current paint transform, relative to the current transform.
+    SetPaintTransform(Affine),
+    /// Set the fill rule used by subsequent fills.
+    SetFillRule(Fill),
+    /// Set the stroke used by subsequent strokes.
+    SetStroke(Stroke),
+    /// Fill a path with the current paint and transform.
+    FillPath(Arc<BezPath>),
+    /// Stroke a path with the current paint, stroke and transform.
+    StrokePath(Arc<BezPath>),
+    /// Fill a rectangle with the current paint and transform.
+    FillRect(Rect),
+    /// Push a clip path.
+    PushClipPath(Arc<BezPath>),
+    /// Pop the most recent clip path.
+    PopClipPath,
+}
+
+/// A render target that recorded pattern commands can be replayed into.
+///
+/// This is implemented by the render contexts of the different backends.
+pub trait PatternSink {
+    /// Set the current transform.
+    fn set_transform(&mut self, transform: Affine);
+    /// Set the current paint.
+    fn set_paint(&mut self, paint: PaintType);
+    /// Set the current paint transform.
+    fn set_paint_transform(&mut self, paint_transform: Affine);
+    /// Set the current fill rule.
+    fn set_fill_rule(&mut self, fill_rule: Fill);
+    /// Set the current stroke.
+    fn set_stroke(&mut self, stroke: Stroke);
+    /// Fill a path with the current paint and transform.
+    fn fill_path(&mut self, path: &BezPath);
+    /// Stroke a path with the current paint, stroke and transform.
+    fn stroke_path(&mut self, path: &BezPath);
+    /// Fill a rectangle with the current paint and transform.
+    fn fill_rect(&mut self, rect: &Rect);
+    /// Push a clip path.
+    fn push_clip_path(&mut self, path: &BezPath);
+    /// Pop the most recent clip path.
+    fn pop_clip_path(&mut self);
+}
+
+/// A recorded scene fragment that is repeated across the plane.
+///
+/// Draw commands are recorded in pattern space. The part of pattern space covered by
+/// [`tile`](Self::tile) forms one cell of the pattern, and consecutive cells are
+/// separated by [`spacing`](Self::spacing). Content outside the tile rectangle is
+/// clipped away.
+///
+/// A pattern can't be used as a paint directly; it first has to be registered with
+/// a renderer, which yields an image id that can then be turned into a paint with
+/// [`Pattern::paint`].
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    tile: Rect,
+    spacing: Vec2,
+    quality: ImageQuality,
+    commands: Vec<PatternCommand>,
+}
+
+impl Pattern {
+    /// Create a new, empty pattern with the given tile rectangle and spacing between cells.
+    ///
+    /// Negative spacing is clamped to zero.
+    pub fn new(tile: Rect, spacing: Vec2) -> Self {
+        Self {
+            tile: tile.abs(),
+            spacing: Vec2::new(spacing.x.max(0.0), spacing.y.max(0.0)),
+            quality: ImageQuality::Low,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Set the quality used when sampling the rasterized cell.
+    ///
+    /// The default is [`ImageQuality::Low`], which keeps the cells crisp when the pattern is
+    /// drawn at its native scale. Use a higher quality if the pattern is going to be scaled
+    /// or rotated.
+    pub fn with_quality(mut self, quality: ImageQuality) -> Self {
+        self.quality = quality;
+        self
+    }
+
+    /// Return the quality used when sampling the rasterized cell.
+    pub fn quality(&self) -> ImageQuality {
+        self.quality
+    }
+
+    /// Return the tile rectangle, in pattern space.
+    pub fn tile(&self) -> Rect {
+        self.tile
+    }
+
+    /// Return the spacing between two consecutive cells.
+    pub fn spacing(&self) -> Vec2 {
+        self.spacing
+    }
+
+    /// Return the recorded commands.
+    pub fn commands(&self) -> &[PatternCommand] {
+        &self.commands
+    }
+
+    /// Return the size of a single rasterized cell in pixels.
+    ///
+    /// This is the size of the tile plus the spacing, rounded up to whole pixels.
+    pub fn cell_size(&self) -> (u16, u16) {
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "pattern cells are clamped to the u16 range"
+        )]
+        let dim = |v: f64| v.ceil().clamp(1.0, f64::from(u16::MAX)) as u16;
+
+        (
+            dim(self.tile.width() + self.spacing.x),
+            dim(self.tile.height() + self.spacing.y),
+        )
+    }
+
+    /// Set the current transform.
+    pub fn set_transform(&mut self, transform: Affine) {
+        self.commands.push(PatternCommand::SetTransform(transform));
+    }
+
+    /// Set the current paint.
+    pub fn set_paint(&mut self, paint: impl Into<PaintType>) {
+        self.commands.push(PatternCommand::SetPaint(paint.into()));
+    }
+
+    /// Set the current paint transform.
+    ///
+    /// Like in a scene, the paint transform positions gradients and images relative to the
+    /// current transform.
+    pub fn set_paint_transform(&mut self, paint_transform: Affine) {
+        self.commands
+            .push(PatternCommand::SetPaintTransform(paint_transform));
+    }
+
+    /// Whether any of the recorded paints samples an image.
+    ///
+    /// Renderers that rasterize cells into an image atlas use this to avoid sampling the
+    /// atlas while rendering into it.
+    pub fn uses_images(&self) -> bool {
+        self.commands
+            .iter()
+            .any(|command| matches!(command, PatternCommand::SetPaint(PaintType::Image(_))))
+    }
+
+    /// Set the current fill rule.
+    pub fn set_fill_rule(&mut self, fill_rule: Fill) {
+        self.commands.push(PatternCommand::SetFillRule(fill_rule));
+    }
+
+    /// Set the current stroke.
+    pub fn set_stroke(&mut self, stroke: Stroke) {
+        self.commands.push(PatternCommand::SetStroke(stroke));
+    }
+
+    /// Fill a path with the current paint and transform.
+    pub fn fill_path(&mut self, path: &BezPath) {
+        self.commands
+            .push(PatternCommand::FillPath(Arc::new(path.clone())));
+    }
+
+    /// Stroke a path with the current paint, stroke and transform.
+    pub fn stroke_path(&mut self, path: &BezPath) {
+        self.commands
+            .push(PatternCommand::StrokePath(Arc::new(path.clone())));
+    }
+
+    /// Fill a rectangle with the current paint and transform.
+    pub fn fill_rect(&mut self, rect: &Rect) {
+        self.commands.push(PatternCommand::FillRect(*rect));
+    }
+
+    /// Push a clip path.
+    pub fn push_clip_path(&mut self, path: &BezPath) {
+        self.commands
+            .push(PatternCommand::PushClipPath(Arc::new(path.clone())));
+    }
+
+    /// Pop the most recent clip path.
+    pub fn pop_clip_path(&mut self) {
+        self.commands.push(PatternCommand::PopClipPath);
+    }
+
+    /// Replay the pattern into `sink` so that a single cell is drawn with its top-left
+    /// corner at `origin`.
+    ///
+    /// The sink is left with identity transforms and all clip paths pushed by the
+    /// pattern popped again.
+    pub fn replay(&self, sink: &mut impl PatternSink, origin: Vec2) {
+        let base = Affine::translate(origin - self.tile.origin().to_vec2());
+        let cell = Rect::from_origin_size(origin.to_point(), self.tile.size());
+
+        sink.set_transform(Affine::IDENTITY);
+        sink.set_fill_rule(Fill::NonZero);
+        sink.push_clip_path(&cell.to_path(0.1));
+
+        sink.set_transform(base);
+        sink.set_paint_transform(Affine::IDENTITY);
+        sink.set_paint(BLACK.into());
+        sink.set_stroke(Stroke::default());
+
+        let mut clip_depth = 0_usize;
+
+        for command in &self.commands {
+            match command {
+                PatternCommand::SetTransform(t) => sink.set_transform(base * *t),
+                PatternCommand::SetPaint(paint) => sink.set_paint(paint.clone()),
+                PatternCommand::SetPaintTransform(t) => sink.set_paint_transform(*t),
+                PatternCommand::SetFillRule(fill_rule) => sink.set_fill_rule(*fill_rule),
+                PatternCommand::SetStroke(stroke) => sink.set_stroke(stroke.clone()),
+                PatternCommand::FillPath(path) => sink.fill_path(path),
+                PatternCommand::StrokePath(path) => sink.stroke_path(path),
+                PatternCommand::FillRect(rect) => sink.fill_rect(rect),
+                PatternCommand::PushClipPath(path) => {
+                    clip_depth += 1;
+                    sink.push_clip_path(path);
+                }
+                PatternCommand::PopClipPath => {
+                    // Unbalanced pops would otherwise remove the cell clip.
+                    if clip_depth > 0 {
+                        clip_depth -= 1;
+                        sink.pop_clip_path();
+                    }
+                }
+            }
+        }
+
+        for _ in 0..clip_depth {
+            sink.pop_clip_path();
+        }
+
+        sink.pop_clip_path();
+        sink.set_paint_transform(Affine::IDENTITY);
+        sink.set_transform(Affine::IDENTITY);
+    }
+
+    /// Create a paint that tiles the rasterized cell stored in `source`.
+    ///
+    /// The first cell is anchored at the origin of the paint space; use
+    /// [`cell_transform`](Self::cell_transform) as (part of) the paint transform to line it
+    /// up with the recorded geometry.
+    pub fn paint(&self, source: ImageSource) -> Image {
+        Image {
+            image: source,
+            sampler: ImageSampler {
+                x_extend: Extend::Repeat,
+                y_extend: Extend::Repeat,
+                quality: self.quality,
+                alpha: 1.0,
+            },
+        }
+    }
+
+    /// Return the transform that maps the rasterized cell back into pattern space.
+    ///
+    /// Renderers combine this with the paint transform so that the cell at pattern-space
+    /// position [`tile`](Self::tile) lines up with the recorded geometry.
+    pub fn cell_transform(&self) -> Affine {
+        Affine::translate(self.tile.origin().to_vec2())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::palette::css::RED;
+
+    #[derive(Default)]
+    struct Log(Vec<&'static str>);
+
+    impl PatternSink for Log {
+        fn set_transform(&mut self, _: Affine) {
+            self.0.push("transform");
+        }
+        fn set_paint(&mut self, _: PaintType) {
+            self.0.push("paint");
+        }
+        fn set_paint_transform(&mut self, _: Affine) {
+            self.0.push("paint_transform");
+        }
+        fn set_fill_rule(&mut self, _: Fill) {
+            self.0.push("fill_rule");
+        }
+        fn set_stroke(&mut self, _: Stroke) {
+            self.0.push("stroke");
+        }
+        fn fill_path(&mut self, _: &BezPath) {
+            self.0.push("fill_path");
+        }
+        fn stroke_path(&mut self, _: &BezPath) {
+            self.0.push("stroke_path");
+        }
+        fn fill_rect(&mut self, _: &Rect) {
+            self.0.push("fill_rect");
+        }
+        fn push_clip_path(&mut self, _: &BezPath) {
+            self.0.push("push_clip");
+        }
+        fn pop_clip_path(&mut self) {
+            self.0.push("pop_clip");
+        }
+    }
+
+    #[test]
+    fn cell_size_includes_spacing() {
+        let pattern = Pattern::new(Rect::new(2.0, 2.0, 10.5, 6.0), Vec2::new(3.0, 0.2));
+        assert_eq!(pattern.cell_size(), (12, 5));
+    }
+
+    #[test]
+    fn negative_spacing_is_clamped() {
+        let pattern = Pattern::new(Rect::new(0.0, 0.0, 4.0, 4.0), Vec2::new(-2.0, -1.0));
+        assert_eq!(pattern.spacing(), Vec2::ZERO);
+        assert_eq!(pattern.cell_size(), (4, 4));
+    }
+
+    #[test]
+    fn paint_uses_pattern_quality() {
+        let pattern = Pattern::new(Rect::new(0.0, 0.0, 4.0, 4.0), Vec2::ZERO);
+        let source = ImageSource::opaque_id(crate::paint::ImageId::new(0));
+        assert_eq!(
+            pattern.paint(source.clone()).sampler.quality,
+            ImageQuality::Low
+        );
+
+        let pattern = pattern.with_quality(ImageQuality::High);
+        assert_eq!(pattern.paint(source).sampler.quality, ImageQuality::High);
+    }
+
+    #[test]
+    fn replay_balances_clips() {
+        let mut pattern = Pattern::new(Rect::new(0.0, 0.0, 4.0, 4.0), Vec2::ZERO);
+        pattern.set_paint(RED);
+        pattern.push_clip_path(&Rect::new(0.0, 0.0, 2.0, 2.0).to_path(0.1));
+        pattern.fill_rect(&Rect::new(0.0, 0.0, 4.0, 4.0));
+        pattern.pop_clip_path();
+        pattern.pop_clip_path();
+        pattern.push_clip_path(&Rect::new(0.0, 0.0, 2.0, 2.0).to_path(0.1));
+
+        let mut log = Log::default();
+        pattern.replay(&mut log, Vec2::ZERO);
+
+        let pushes = log.0.iter().filter(|c| **c == "push_clip").count();
+        let pops = log.0.iter().filter(|c| **c == "pop_clip").count();
+        assert_eq!(pushes, 3);
+        assert_eq!(pops, 3);
+        assert_eq!(log.0.last(), Some(&"transform"));
+    }
+
+    #[test]
+    fn image_paints_are_detected() {
+        let mut pattern = Pattern::new(Rect::new(0.0, 0.0, 4.0, 4.0), Vec2::ZERO);
+        pattern.set_paint(RED);
+        assert!(!pattern.uses_images());
+
+        let source = ImageSource::opaque_id(crate::paint::ImageId::new(0));
+        pattern.set_paint_transform(Affine::scale(0.5));
+        pattern.set_paint(pattern.paint(source));
+        assert!(pattern.uses_images());
+
+        // The paint transform doesn't leak out of the pattern.
+        let mut log = Log::default();
+        pattern.replay(&mut log, Vec2::ZERO);
+        let transforms = log.0.iter().filter(|c| **c == "paint_transform").count();
+        assert_eq!(transforms, 3);
+        assert_eq!(log.0[log.0.len() - 2], "paint_transform");
+    }
+}
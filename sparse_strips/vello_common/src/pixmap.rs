@@ -3,12 +3,13 @@
 
 //! A simple pixmap type.
 
+use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
 #[cfg(feature = "png")]
 use std::io::{BufRead, Seek};
 
-use crate::peniko::color::{PremulRgba8, Rgba8};
+use crate::peniko::color::{ColorSpaceTag, PremulRgba8, Rgba8};
 
 #[cfg(feature = "png")]
 extern crate std;
@@ -27,6 +28,8 @@ pub struct Pixmap {
     /// Note: This may become stale if pixels are modified via [`data_mut()`](Self::data_mut),
     /// [`data_as_u8_slice_mut()`](Self::data_as_u8_slice_mut), or [`set_pixel()`](Self::set_pixel).
     may_have_transparency: bool,
+    /// The color space the pixel data is encoded in.
+    color_space: ColorSpaceTag,
 }
 
 impl Pixmap {
@@ -40,6 +43,7 @@ impl Pixmap {
             height,
             buf,
             may_have_transparency: true,
+            color_space: ColorSpaceTag::Srgb,
         }
     }
 
@@ -88,6 +92,7 @@ impl Pixmap {
             height,
             buf: data,
             may_have_transparency,
+            color_space: ColorSpaceTag::Srgb,
         }
     }
 
@@ -161,6 +166,89 @@ impl Pixmap {
         self.may_have_transparency = self.buf.iter().any(|pixel| pixel.a != 255);
     }
 
+    /// Return the color space the pixel data is encoded in.
+    ///
+    /// Pixmaps are assumed to contain sRGB data unless declared otherwise with
+    /// [`set_color_space()`](Self::set_color_space).
+    pub fn color_space(&self) -> ColorSpaceTag {
+        self.color_space
+    }
+
+    /// Declare the color space the pixel data is encoded in, without changing the pixels.
+    ///
+    /// Renderers composite in sRGB, so images in other color spaces are converted to sRGB
+    /// when they are registered or uploaded. Only RGB color spaces such as
+    /// [`DisplayP3`](ColorSpaceTag::DisplayP3) or [`LinearSrgb`](ColorSpaceTag::LinearSrgb)
+    /// are meaningful here. Note that linear data stored in 8 bits loses precision in dark
+    /// tones, so it's best to convert such images before quantizing them if possible.
+    pub fn set_color_space(&mut self, color_space: ColorSpaceTag) {
+        self.color_space = color_space;
+    }
+
+    /// Convert the pixel data from its current color space into `target`.
+    ///
+    /// Colors that lie outside of the target gamut are clamped.
+    pub fn convert_color_space(&mut self, target: ColorSpaceTag) {
+        let source = self.color_space;
+        self.color_space = target;
+
+        if source == target {
+            return;
+        }
+
+        // Images usually contain long runs of the same color, so remember the last
+        // conversion.
+        let mut last: Option<(PremulRgba8, PremulRgba8)> = None;
+
+        for pixel in &mut self.buf {
+            if pixel.a == 0 {
+                continue;
+            }
+
+            if let Some((_, to)) = last.filter(|(from, _)| *from == *pixel) {
+                *pixel = to;
+                continue;
+            }
+
+            let from = *pixel;
+            let alpha = f32::from(from.a) / 255.0;
+            let unpremultiply = |component: u8| f32::from(component) / 255.0 / alpha;
+            let [r, g, b] = source.convert(
+                target,
+                [
+                    unpremultiply(from.r),
+                    unpremultiply(from.g),
+                    unpremultiply(from.b),
+                ],
+            );
+            #[expect(clippy::cast_possible_truncation, reason = "deliberate quantization")]
+            let premultiply =
+                |component: f32| (component.clamp(0.0, 1.0) * alpha * 255.0 + 0.5) as u8;
+
+            *pixel = PremulRgba8 {
+                r: premultiply(r),
+                g: premultiply(g),
+                b: premultiply(b),
+                a: from.a,
+            };
+            last = Some((from, *pixel));
+        }
+    }
+
+    /// Return the pixmap converted to sRGB.
+    ///
+    /// The pixel data is only copied if the pixmap is in a different color space and
+    /// shared with other owners.
+    pub fn into_srgb(self: Arc<Self>) -> Arc<Self> {
+        if self.color_space == ColorSpaceTag::Srgb {
+            return self;
+        }
+
+        let mut pixmap = Arc::unwrap_or_clone(self);
+        pixmap.convert_color_space(ColorSpaceTag::Srgb);
+        Arc::new(pixmap)
+    }
+
     /// Apply an alpha value to the whole pixmap.
     pub fn multiply_alpha(&mut self, alpha: u8) {
         #[expect(
@@ -286,6 +374,52 @@ impl Pixmap {
         writer.finish().map(|_| data)
     }
 
+    /// Encode the pixmap as a PNG that is tagged with its color space.
+    ///
+    /// sRGB, Display P3 and linear sRGB pixmaps are written as they are, and tagged with a
+    /// `cICP` chunk, which takes precedence in decoders that support it, as well as `sRGB`,
+    /// `gAMA` and `cHRM` chunks for older decoders. Pixmaps in other color spaces are converted
+    /// to sRGB first.
+    ///
+    /// Unlike [`into_png`](Self::into_png), which writes untagged data, this keeps the colors of
+    /// Display P3 content intact in viewers that manage color.
+    #[cfg(feature = "png")]
+    pub fn encode_png(&self) -> Result<Vec<u8>, png::EncodingError> {
+        use alloc::borrow::Cow;
+        use png::chunk::ChunkType;
+
+        let (pixmap, color_space) = match PngColorSpace::new(self.color_space) {
+            Some(color_space) => (Cow::Borrowed(self), color_space),
+            None => {
+                let mut pixmap = self.clone();
+                pixmap.convert_color_space(ColorSpaceTag::Srgb);
+                (Cow::Owned(pixmap), PngColorSpace::Srgb)
+            }
+        };
+
+        let mut data = Vec::new();
+        let mut encoder = png::Encoder::new(&mut data, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        // All of these chunks need to come before the image data.
+        if color_space == PngColorSpace::Srgb {
+            // Perceptual rendering intent.
+            writer.write_chunk(ChunkType(*b"sRGB"), &[0])?;
+        }
+        writer.write_chunk(ChunkType(*b"gAMA"), &color_space.gamma().to_be_bytes())?;
+        let chromaticities: Vec<u8> = color_space
+            .chromaticities()
+            .iter()
+            .flat_map(|value| value.to_be_bytes())
+            .collect();
+        writer.write_chunk(ChunkType(*b"cHRM"), &chromaticities)?;
+        writer.write_chunk(ChunkType(*b"cICP"), &color_space.code_points())?;
+        let pixels: Vec<Rgba8> = pixmap.buf.iter().copied().map(unpremultiply).collect();
+        writer.write_image_data(bytemuck::cast_slice(&pixels))?;
+        writer.finish().map(|_| data)
+    }
+
     /// Returns a reference to the underlying data as premultiplied RGBA8.
     ///
     /// The pixels are in row-major order.
@@ -356,23 +490,202 @@ impl Pixmap {
     ///
     /// The pixels are in row-major order.
     pub fn take_unpremultiplied(self) -> Vec<Rgba8> {
-        self.buf
-            .into_iter()
-            .map(|PremulRgba8 { r, g, b, a }| {
-                let alpha = 255.0 / f32::from(a);
-                if a != 0 {
-                    #[expect(clippy::cast_possible_truncation, reason = "deliberate quantization")]
-                    let unpremultiply = |component| (f32::from(component) * alpha + 0.5) as u8;
-                    Rgba8 {
-                        r: unpremultiply(r),
-                        g: unpremultiply(g),
-                        b: unpremultiply(b),
-                        a,
-                    }
-                } else {
-                    Rgba8 { r, g, b, a }
+        self.buf.into_iter().map(unpremultiply).collect()
+    }
+}
+
+fn unpremultiply(PremulRgba8 { r, g, b, a }: PremulRgba8) -> Rgba8 {
+    let alpha = 255.0 / f32::from(a);
+    if a != 0 {
+        #[expect(clippy::cast_possible_truncation, reason = "deliberate quantization")]
+        let unpremultiply = |component| (f32::from(component) * alpha + 0.5) as u8;
+        Rgba8 {
+            r: unpremultiply(r),
+            g: unpremultiply(g),
+            b: unpremultiply(b),
+            a,
+        }
+    } else {
+        Rgba8 { r, g, b, a }
+    }
+}
+
+/// A color space that PNG files can be tagged with.
+#[cfg(feature = "png")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PngColorSpace {
+    Srgb,
+    DisplayP3,
+    LinearSrgb,
+}
+
+#[cfg(feature = "png")]
+impl PngColorSpace {
+    fn new(color_space: ColorSpaceTag) -> Option<Self> {
+        match color_space {
+            ColorSpaceTag::Srgb => Some(Self::Srgb),
+            ColorSpaceTag::DisplayP3 => Some(Self::DisplayP3),
+            ColorSpaceTag::LinearSrgb => Some(Self::LinearSrgb),
+            _ => None,
+        }
+    }
+
+    /// The payload of the `cICP` chunk: the color primaries, the transfer function and the
+    /// matrix coefficients as defined in ITU-T H.273, and whether the image uses the full range.
+    fn code_points(self) -> [u8; 4] {
+        match self {
+            Self::Srgb => [1, 13, 0, 1],
+            Self::DisplayP3 => [12, 13, 0, 1],
+            Self::LinearSrgb => [1, 8, 0, 1],
+        }
+    }
+
+    /// The approximate gamma of the `gAMA` chunk, times 100000.
+    fn gamma(self) -> u32 {
+        match self {
+            Self::Srgb | Self::DisplayP3 => 45455,
+            Self::LinearSrgb => 100000,
+        }
+    }
+
+    /// The white point and the red, green and blue primaries of the `cHRM` chunk, times 100000.
+    fn chromaticities(self) -> [u32; 8] {
+        match self {
+            Self::Srgb | Self::LinearSrgb => {
+                [31270, 32900, 64000, 33000, 30000, 60000, 15000, 6000]
+            }
+            Self::DisplayP3 => [31270, 32900, 68000, 32000, 26500, 69000, 15000, 6000],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_linear_to_srgb() {
+        let data = vec![
+            PremulRgba8 {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 0,
+            },
+            // Linear 0.5 at 50% opacity.
+            PremulRgba8 {
+                r: 64,
+                g: 64,
+                b: 64,
+                a: 128,
+            },
+            PremulRgba8 {
+                r: 255,
+                g: 0,
+                b: 255,
+                a: 255,
+            },
+        ];
+        let mut pixmap = Pixmap::from_parts(data, 3, 1);
+        pixmap.set_color_space(ColorSpaceTag::LinearSrgb);
+        let pixmap = Arc::new(pixmap).into_srgb();
+
+        assert_eq!(pixmap.color_space(), ColorSpaceTag::Srgb);
+        assert_eq!(pixmap.data()[0].a, 0);
+        // sRGB encodes linear 0.5 as ~0.735.
+        assert_eq!(
+            pixmap.data()[1],
+            PremulRgba8 {
+                r: 94,
+                g: 94,
+                b: 94,
+                a: 128,
+            }
+        );
+        assert_eq!(
+            pixmap.data()[2],
+            PremulRgba8 {
+                r: 255,
+                g: 0,
+                b: 255,
+                a: 255,
+            }
+        );
+    }
+
+    #[test]
+    fn convert_display_p3_clamps_to_srgb_gamut() {
+        let data = vec![PremulRgba8 {
+            r: 0,
+            g: 255,
+            b: 0,
+            a: 255,
+        }];
+        let mut pixmap = Pixmap::from_parts(data, 1, 1);
+        pixmap.set_color_space(ColorSpaceTag::DisplayP3);
+        pixmap.convert_color_space(ColorSpaceTag::Srgb);
+
+        // The most saturated P3 green lies outside of sRGB.
+        let pixel = pixmap.data()[0];
+        assert_eq!((pixel.r, pixel.g, pixel.b), (0, 255, 0));
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn encoded_pngs_are_tagged_with_the_color_space() {
+        // Return the chunks of a PNG file that come before the image data.
+        fn header_chunks(png: &[u8]) -> Vec<([u8; 4], &[u8])> {
+            let mut chunks = Vec::new();
+            let mut rest = &png[8..];
+            loop {
+                let len = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+                let name: [u8; 4] = rest[4..8].try_into().unwrap();
+                if &name == b"IDAT" {
+                    return chunks;
                 }
-            })
-            .collect()
+                chunks.push((name, &rest[8..8 + len]));
+                // Skip the CRC.
+                rest = &rest[12 + len..];
+            }
+        }
+        let cicp = |png: &[u8]| {
+            header_chunks(png)
+                .into_iter()
+                .find(|(name, _)| name == b"cICP")
+                .map(|(_, payload)| payload.to_vec())
+        };
+
+        let data = vec![PremulRgba8 {
+            r: 0,
+            g: 128,
+            b: 0,
+            a: 128,
+        }];
+        let mut pixmap = Pixmap::from_parts(data, 1, 1);
+        pixmap.set_color_space(ColorSpaceTag::DisplayP3);
+        let png = pixmap.encode_png().unwrap();
+        assert_eq!(cicp(&png), Some(vec![12, 13, 0, 1]));
+        assert!(!header_chunks(&png).iter().any(|(name, _)| name == b"sRGB"));
+
+        let decoded = Pixmap::from_png(std::io::Cursor::new(&png)).unwrap();
+        assert_eq!(decoded.data(), pixmap.data());
+
+        pixmap.set_color_space(ColorSpaceTag::Srgb);
+        let png = pixmap.encode_png().unwrap();
+        assert_eq!(cicp(&png), Some(vec![1, 13, 0, 1]));
+        assert!(header_chunks(&png).iter().any(|(name, _)| name == b"sRGB"));
+
+        // Color spaces that can't be tagged are converted to sRGB.
+        pixmap.set_color_space(ColorSpaceTag::A98Rgb);
+        let png = pixmap.encode_png().unwrap();
+        assert_eq!(cicp(&png), Some(vec![1, 13, 0, 1]));
+    }
+
+    #[test]
+    fn srgb_pixmaps_are_not_copied() {
+        let pixmap = Arc::new(Pixmap::new(2, 2));
+        let converted = pixmap.clone().into_srgb();
+
+        assert!(Arc::ptr_eq(&pixmap, &converted));
     }
 }
@@ -0,0 +1,85 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Presets that trade rendering quality for speed.
+
+use crate::encode::MAX_GRADIENT_LUT_SIZE;
+use crate::filter::gaussian_blur::MAX_KERNEL_SIZE;
+use crate::flatten::TOL;
+
+/// A preset for the settings that trade the accuracy of the rendered image for speed.
+///
+/// The same preset yields the same settings in all renderers, so that a preview drawn with one
+/// of them looks like the preview drawn with another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Quality {
+    /// Fast rendering for previews during interaction, for example while panning or zooming.
+    ///
+    /// Curves are approximated coarsely, edges are aliased, gradients are sampled from small
+    /// lookup tables, and blurs use shorter kernels.
+    Fast,
+    /// The default balance between speed and quality, which is good enough for most uses.
+    #[default]
+    Balanced,
+    /// High quality for final output, like exports or print.
+    ///
+    /// Curves are approximated more closely than with [`Balanced`](Self::Balanced).
+    High,
+}
+
+impl Quality {
+    /// The maximum distance in device pixels between a curve and the lines that approximate it.
+    pub fn flatten_tolerance(self) -> f64 {
+        match self {
+            Self::Fast => 1.0,
+            Self::Balanced => TOL,
+            Self::High => 0.1,
+        }
+    }
+
+    /// The coverage threshold for aliased edges, or `None` if edges are anti-aliased.
+    ///
+    /// See [`Aliasing::threshold`](crate::strip_generator::Aliasing::threshold).
+    pub fn aliasing_threshold(self) -> Option<u8> {
+        match self {
+            Self::Fast => Some(128),
+            Self::Balanced | Self::High => None,
+        }
+    }
+
+    /// The maximum number of entries in the lookup table of a gradient.
+    pub fn max_gradient_lut_size(self) -> usize {
+        match self {
+            Self::Fast => 256,
+            Self::Balanced | Self::High => MAX_GRADIENT_LUT_SIZE,
+        }
+    }
+
+    /// The maximum number of taps of the kernels of Gaussian blurs, at most
+    /// [`MAX_KERNEL_SIZE`].
+    pub fn max_blur_kernel_size(self) -> usize {
+        match self {
+            Self::Fast => 7,
+            Self::Balanced | Self::High => MAX_KERNEL_SIZE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Quality;
+
+    #[test]
+    fn presets_are_ordered_by_quality() {
+        let presets = [Quality::Fast, Quality::Balanced, Quality::High];
+        for pair in presets.windows(2) {
+            let (lower, higher) = (pair[0], pair[1]);
+            assert!(lower.flatten_tolerance() > higher.flatten_tolerance());
+            assert!(lower.max_gradient_lut_size() <= higher.max_gradient_lut_size());
+            assert!(lower.max_blur_kernel_size() <= higher.max_blur_kernel_size());
+            assert!(!lower.max_blur_kernel_size().is_multiple_of(2));
+        }
+        assert!(Quality::Fast.aliasing_threshold().is_some());
+        assert_eq!(Quality::default().aliasing_threshold(), None);
+    }
+}
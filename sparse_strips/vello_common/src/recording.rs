@@ -0,0 +1,399 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Retained scene recordings.
+//!
+//! A [`Recording`] captures a sequence of draw commands once, so that static content can be
+//! appended to a scene in every frame without rebuilding it. Paths are stored behind an
+//! [`Arc`], which makes both replaying and cloning a recording cheap. Renderers replay a
+//! recording with an additional transform, so the same recording can be drawn at different
+//! positions and scales.
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::color::palette::css::BLACK;
+use crate::kurbo::{Affine, BezPath, Rect, Stroke};
+use crate::paint::PaintType;
+use crate::pattern::PatternSink;
+use crate::peniko::{BlendMode, Fill};
+
+/// A single draw command recorded into a [`Recording`].
+#[derive(Clone, Debug)]
+pub enum RecordingCommand {
+    /// Set the current transform, relative to the recording space.
+    SetTransform(Affine),
+    /// Set the current paint.
+    SetPaint(PaintType),
+    /// Set the current paint transform, relative to the current transform.
+    SetPaintTransform(Affine),
+    /// Set the fill rule used by subsequent fills.
+    SetFillRule(Fill),
+    /// Set the stroke used by subsequent strokes.
+    SetStroke(Stroke),
+    /// Fill a path with the current paint and transform.
+    FillPath(Arc<BezPath>),
+    /// Stroke a path with the current paint, stroke and transform.
+    StrokePath(Arc<BezPath>),
+    /// Fill a rectangle with the current paint and transform.
+    FillRect(Rect),
+    /// Stroke a rectangle with the current paint, stroke and transform.
+    StrokeRect(Rect),
+    /// Push a clip path.
+    PushClipPath(Arc<BezPath>),
+    /// Pop the most recent clip path.
+    PopClipPath,
+    /// Push a layer with an optional clip path, blend mode and opacity.
+    PushLayer {
+        /// The clip path of the layer.
+        clip_path: Option<Arc<BezPath>>,
+        /// The blend mode used to composite the layer.
+        blend_mode: Option<BlendMode>,
+        /// The opacity of the layer.
+        opacity: Option<f32>,
+    },
+    /// Pop the most recent layer.
+    PopLayer,
+}
+
+/// A render target that recordings can be replayed into.
+///
+/// This extends [`PatternSink`] with the commands that only make sense in a full scene, and
+/// is implemented by the render contexts of the different backends.
+pub trait RecordingSink: PatternSink {
+    /// Stroke a rectangle with the current paint, stroke and transform.
+    fn stroke_rect(&mut self, rect: &Rect);
+    /// Push a layer with an optional clip path, blend mode and opacity.
+    fn push_layer(
+        &mut self,
+        clip_path: Option<&BezPath>,
+        blend_mode: Option<BlendMode>,
+        opacity: Option<f32>,
+    );
+    /// Pop the most recent layer.
+    fn pop_layer(&mut self);
+}
+
+/// What a push command on the replay stack has to be balanced with.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Pushed {
+    Clip,
+    Layer,
+}
+
+/// A recorded sequence of draw commands that can be appended to a scene.
+///
+/// Commands are recorded in recording space. When the recording is replayed, the transform
+/// passed to [`replay`](Self::replay) is prepended to every recorded transform, and clips and
+/// layers that the recording leaves open are closed again.
+#[derive(Clone, Debug, Default)]
+pub struct Recording {
+    commands: Vec<RecordingCommand>,
+}
+
+impl Recording {
+    /// Create a new, empty recording.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the recorded commands.
+    pub fn commands(&self) -> &[RecordingCommand] {
+        &self.commands
+    }
+
+    /// Return the number of recorded commands.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Return whether nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Remove all recorded commands.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Set the current transform.
+    pub fn set_transform(&mut self, transform: Affine) {
+        self.commands
+            .push(RecordingCommand::SetTransform(transform));
+    }
+
+    /// Set the current paint.
+    pub fn set_paint(&mut self, paint: impl Into<PaintType>) {
+        self.commands.push(RecordingCommand::SetPaint(paint.into()));
+    }
+
+    /// Set the current paint transform.
+    pub fn set_paint_transform(&mut self, paint_transform: Affine) {
+        self.commands
+            .push(RecordingCommand::SetPaintTransform(paint_transform));
+    }
+
+    /// Set the current fill rule.
+    pub fn set_fill_rule(&mut self, fill_rule: Fill) {
+        self.commands.push(RecordingCommand::SetFillRule(fill_rule));
+    }
+
+    /// Set the current stroke.
+    pub fn set_stroke(&mut self, stroke: Stroke) {
+        self.commands.push(RecordingCommand::SetStroke(stroke));
+    }
+
+    /// Fill a path with the current paint and transform.
+    pub fn fill_path(&mut self, path: &BezPath) {
+        self.commands
+            .push(RecordingCommand::FillPath(Arc::new(path.clone())));
+    }
+
+    /// Stroke a path with the current paint, stroke and transform.
+    pub fn stroke_path(&mut self, path: &BezPath) {
+        self.commands
+            .push(RecordingCommand::StrokePath(Arc::new(path.clone())));
+    }
+
+    /// Fill a rectangle with the current paint and transform.
+    pub fn fill_rect(&mut self, rect: &Rect) {
+        self.commands.push(RecordingCommand::FillRect(*rect));
+    }
+
+    /// Stroke a rectangle with the current paint, stroke and transform.
+    pub fn stroke_rect(&mut self, rect: &Rect) {
+        self.commands.push(RecordingCommand::StrokeRect(*rect));
+    }
+
+    /// Push a clip path.
+    pub fn push_clip_path(&mut self, path: &BezPath) {
+        self.commands
+            .push(RecordingCommand::PushClipPath(Arc::new(path.clone())));
+    }
+
+    /// Pop the most recent clip path.
+    pub fn pop_clip_path(&mut self) {
+        self.commands.push(RecordingCommand::PopClipPath);
+    }
+
+    /// Push a layer with an optional clip path, blend mode and opacity.
+    pub fn push_layer(
+        &mut self,
+        clip_path: Option<&BezPath>,
+        blend_mode: Option<BlendMode>,
+        opacity: Option<f32>,
+    ) {
+        self.commands.push(RecordingCommand::PushLayer {
+            clip_path: clip_path.map(|path| Arc::new(path.clone())),
+            blend_mode,
+            opacity,
+        });
+    }
+
+    /// Pop the most recent layer.
+    pub fn pop_layer(&mut self) {
+        self.commands.push(RecordingCommand::PopLayer);
+    }
+
+    /// Append all commands of `other` to this recording.
+    ///
+    /// The paths are shared with `other` instead of being copied.
+    pub fn extend(&mut self, other: &Self) {
+        self.commands.extend_from_slice(&other.commands);
+    }
+
+    /// Replay the recording into `sink`, with `transform` applied on top of the recorded
+    /// transforms.
+    ///
+    /// The sink starts out with a black paint, the default stroke and the non-zero fill rule.
+    /// Pops that don't match the most recent push are ignored, and clips and layers that are
+    /// still open at the end are closed, so the clip and layer stacks of the sink are left as
+    /// they were. The remaining render state is left as the recording set it; callers that
+    /// want to preserve it save and restore it around the replay.
+    pub fn replay(&self, sink: &mut impl RecordingSink, transform: Affine) {
+        sink.set_transform(transform);
+        sink.set_paint_transform(Affine::IDENTITY);
+        sink.set_paint(BLACK.into());
+        sink.set_stroke(Stroke::default());
+        sink.set_fill_rule(Fill::NonZero);
+
+        let mut stack = Vec::new();
+
+        for command in &self.commands {
+            match command {
+                RecordingCommand::SetTransform(t) => sink.set_transform(transform * *t),
+                RecordingCommand::SetPaint(paint) => sink.set_paint(paint.clone()),
+                RecordingCommand::SetPaintTransform(t) => sink.set_paint_transform(*t),
+                RecordingCommand::SetFillRule(fill_rule) => sink.set_fill_rule(*fill_rule),
+                RecordingCommand::SetStroke(stroke) => sink.set_stroke(stroke.clone()),
+                RecordingCommand::FillPath(path) => sink.fill_path(path),
+                RecordingCommand::StrokePath(path) => sink.stroke_path(path),
+                RecordingCommand::FillRect(rect) => sink.fill_rect(rect),
+                RecordingCommand::StrokeRect(rect) => sink.stroke_rect(rect),
+                RecordingCommand::PushClipPath(path) => {
+                    stack.push(Pushed::Clip);
+                    sink.push_clip_path(path);
+                }
+                RecordingCommand::PopClipPath => {
+                    if stack.last() == Some(&Pushed::Clip) {
+                        stack.pop();
+                        sink.pop_clip_path();
+                    }
+                }
+                RecordingCommand::PushLayer {
+                    clip_path,
+                    blend_mode,
+                    opacity,
+                } => {
+                    stack.push(Pushed::Layer);
+                    sink.push_layer(clip_path.as_deref(), *blend_mode, *opacity);
+                }
+                RecordingCommand::PopLayer => {
+                    if stack.last() == Some(&Pushed::Layer) {
+                        stack.pop();
+                        sink.pop_layer();
+                    }
+                }
+            }
+        }
+
+        while let Some(pushed) = stack.pop() {
+            match pushed {
+                Pushed::Clip => sink.pop_clip_path(),
+                Pushed::Layer => sink.pop_layer(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::palette::css::RED;
+    use crate::kurbo::Shape;
+    use alloc::vec;
+
+    #[derive(Default)]
+    struct Log {
+        calls: Vec<&'static str>,
+        transforms: Vec<Affine>,
+    }
+
+    impl PatternSink for Log {
+        fn set_transform(&mut self, transform: Affine) {
+            self.calls.push("transform");
+            self.transforms.push(transform);
+        }
+        fn set_paint(&mut self, _: PaintType) {
+            self.calls.push("paint");
+        }
+        fn set_paint_transform(&mut self, _: Affine) {
+            self.calls.push("paint_transform");
+        }
+        fn set_fill_rule(&mut self, _: Fill) {
+            self.calls.push("fill_rule");
+        }
+        fn set_stroke(&mut self, _: Stroke) {
+            self.calls.push("stroke");
+        }
+        fn fill_path(&mut self, _: &BezPath) {
+            self.calls.push("fill_path");
+        }
+        fn stroke_path(&mut self, _: &BezPath) {
+            self.calls.push("stroke_path");
+        }
+        fn fill_rect(&mut self, _: &Rect) {
+            self.calls.push("fill_rect");
+        }
+        fn push_clip_path(&mut self, _: &BezPath) {
+            self.calls.push("push_clip");
+        }
+        fn pop_clip_path(&mut self) {
+            self.calls.push("pop_clip");
+        }
+    }
+
+    impl RecordingSink for Log {
+        fn stroke_rect(&mut self, _: &Rect) {
+            self.calls.push("stroke_rect");
+        }
+        fn push_layer(&mut self, _: Option<&BezPath>, _: Option<BlendMode>, _: Option<f32>) {
+            self.calls.push("push_layer");
+        }
+        fn pop_layer(&mut self) {
+            self.calls.push("pop_layer");
+        }
+    }
+
+    #[test]
+    fn replay_prepends_transform() {
+        let mut recording = Recording::new();
+        recording.set_transform(Affine::scale(2.0));
+        recording.fill_rect(&Rect::new(0.0, 0.0, 1.0, 1.0));
+
+        let mut log = Log::default();
+        recording.replay(&mut log, Affine::translate((10.0, 0.0)));
+
+        assert_eq!(
+            log.transforms,
+            vec![
+                Affine::translate((10.0, 0.0)),
+                Affine::translate((10.0, 0.0)) * Affine::scale(2.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn replay_balances_clips_and_layers() {
+        let clip = Rect::new(0.0, 0.0, 2.0, 2.0).to_path(0.1);
+        let mut recording = Recording::new();
+        recording.set_paint(RED);
+        recording.push_clip_path(&clip);
+        recording.push_layer(None, None, Some(0.5));
+        // Doesn't match the layer on top of the stack.
+        recording.pop_clip_path();
+        recording.fill_rect(&Rect::new(0.0, 0.0, 4.0, 4.0));
+        recording.pop_layer();
+        recording.pop_layer();
+        recording.push_layer(Some(&clip), None, None);
+
+        let mut log = Log::default();
+        recording.replay(&mut log, Affine::IDENTITY);
+
+        let ops: Vec<_> = log
+            .calls
+            .iter()
+            .copied()
+            .filter(|c| c.starts_with("push") || c.starts_with("pop"))
+            .collect();
+        assert_eq!(
+            ops,
+            vec![
+                "push_clip",
+                "push_layer",
+                "pop_layer",
+                "push_layer",
+                "pop_layer",
+                "pop_clip"
+            ]
+        );
+    }
+
+    #[test]
+    fn extend_shares_paths() {
+        let mut a = Recording::new();
+        a.fill_path(&Rect::new(0.0, 0.0, 1.0, 1.0).to_path(0.1));
+        let mut b = Recording::new();
+        b.extend(&a);
+        b.extend(&a);
+
+        assert_eq!(b.len(), 2);
+        let (RecordingCommand::FillPath(p1), RecordingCommand::FillPath(p2)) =
+            (&a.commands()[0], &b.commands()[1])
+        else {
+            panic!("expected fill commands");
+        };
+        assert!(Arc::ptr_eq(p1, p2));
+    }
+}
@@ -12,6 +12,7 @@
 //! execute in the correct order. Each node represents either:
 //! - The root (backdrop) layer that forms the base for compositing
 //! - A filtered layer that renders geometry, applies effects, and blends the result
+//! - A backdrop layer that applies effects to the content of the root layer drawn before it
 //!
 //! # Key Features
 //!
@@ -148,7 +149,10 @@ impl RenderGraph {
         }
 
         // Track if we have any filters to avoid scanning nodes later
-        if matches!(kind, RenderNodeKind::FilterLayer { .. }) {
+        if matches!(
+            kind,
+            RenderNodeKind::FilterLayer { .. } | RenderNodeKind::BackdropLayer { .. }
+        ) {
             self.has_filters = true;
         }
 
@@ -286,7 +290,8 @@ impl RenderNode {
     pub fn is_empty(&self) -> bool {
         match &self.kind {
             RenderNodeKind::RootLayer { wtile_bbox, .. } => wtile_bbox.is_empty(),
-            RenderNodeKind::FilterLayer { wtile_bbox, .. } => wtile_bbox.is_empty(),
+            RenderNodeKind::FilterLayer { wtile_bbox, .. }
+            | RenderNodeKind::BackdropLayer { wtile_bbox, .. } => wtile_bbox.is_empty(),
         }
     }
 }
@@ -335,6 +340,28 @@ pub enum RenderNodeKind {
         /// Used to scale filter parameters based on the current scale/zoom level.
         transform: Affine,
     },
+    /// A layer that applies filter effects to its backdrop.
+    ///
+    /// Instead of geometry of its own, the layer renders the commands of its parent layer that
+    /// precede it and applies the filter to them. Like for [`RenderNodeKind::FilterLayer`],
+    /// the output is composited into the parent layer where the layer was pushed.
+    ///
+    /// The parent layer is the innermost isolated layer (a layer with a non-default blend
+    /// mode, an opacity, a mask or a filter) that the layer was pushed in, or the root layer.
+    /// Clip layers in between are part of the backdrop, without their clip path applied.
+    BackdropLayer {
+        /// ID of this backdrop layer.
+        layer_id: LayerId,
+        /// The filter effect to apply to the backdrop.
+        filter: Filter,
+        /// Bounding box in wide tile coordinates of the backdrop that is rendered.
+        wtile_bbox: WideTilesBbox,
+        /// Transform that was active when the layer was created.
+        transform: Affine,
+        /// Whether the parent layer is the root layer, in which case the backdrop includes
+        /// the background of the wide tiles.
+        in_root_layer: bool,
+    },
 }
 
 /// Unique identifier for a layer in the rendering system.
@@ -3,6 +3,7 @@
 
 //! Shared render state.
 
+use crate::encode::GradientHints;
 use crate::kurbo::{Affine, Cap, Join, Stroke};
 use crate::paint::{PaintType, Tint};
 use crate::peniko::color::palette::css::BLACK;
@@ -26,6 +27,8 @@ pub struct RenderState {
     pub blend_mode: BlendMode,
     /// The tint for image painting.
     pub tint: Option<Tint>,
+    /// The interpolation hints for gradient painting.
+    pub gradient_hints: GradientHints,
 }
 
 impl Default for RenderState {
@@ -44,6 +47,7 @@ impl Default for RenderState {
             fill_rule: Fill::NonZero,
             blend_mode: BlendMode::new(Mix::Normal, Compose::SrcOver),
             tint: None,
+            gradient_hints: GradientHints::default(),
         }
     }
 }
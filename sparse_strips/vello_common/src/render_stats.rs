@@ -0,0 +1,36 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Statistics about the work done to render a frame.
+
+use core::ops::AddAssign;
+
+/// Counters for the work done by the renderer in a frame, for example to enforce
+/// performance budgets.
+///
+/// The counters start at zero when the scene or render context is reset, and are accumulated
+/// while the frame is drawn and rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RenderStats {
+    /// The number of paths that were flattened and turned into strips.
+    pub paths: u32,
+    /// The number of strips that were generated.
+    pub strips: u64,
+    /// The number of bytes of alpha masks that were generated for the strips.
+    ///
+    /// For GPU renderers, this is the amount of alpha data uploaded to the GPU.
+    pub alpha_bytes: u64,
+    /// The number of draw calls issued to the GPU.
+    ///
+    /// This is always zero for CPU renderers.
+    pub draw_calls: u32,
+}
+
+impl AddAssign for RenderStats {
+    fn add_assign(&mut self, other: Self) {
+        self.paths += other.paths;
+        self.strips += other.strips;
+        self.alpha_bytes += other.alpha_bytes;
+        self.draw_calls += other.draw_calls;
+    }
+}
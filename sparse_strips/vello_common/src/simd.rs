@@ -74,3 +74,108 @@ pub fn element_wise_splat<S: Simd>(simd: S, input: f32x4<S>) -> f32x16<S> {
         simd.combine_f32x4(f32x4::splat(simd, input[2]), f32x4::splat(simd, input[3])),
     )
 }
+
+/// The order of the bytes within the lanes of SIMD vectors with elements wider than a byte.
+///
+/// Converting between bytes and wider elements by reinterpreting vectors as raw bytes depends
+/// on where the least significant byte of each element is stored. The conversion functions take
+/// the byte order as a parameter, so that the paths for both byte orders can be tested on any
+/// target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+    /// The least significant byte comes first, like on `x86_64` and `aarch64`.
+    LittleEndian,
+    /// The most significant byte comes first, like on `s390x` and older PowerPC targets.
+    BigEndian,
+}
+
+impl ByteOrder {
+    /// The byte order of the target.
+    pub const NATIVE: Self = if cfg!(target_endian = "big") {
+        Self::BigEndian
+    } else {
+        Self::LittleEndian
+    };
+}
+
+/// Zero-extend the bytes of `val` into 16 `u32`s with the given byte order, returned as the raw
+/// bytes of four vectors of four `u32`s each.
+#[inline(always)]
+pub fn widen_u8_to_u32_bytes<S: Simd>(val: u8x16<S>, order: ByteOrder) -> [u8x16<S>; 4] {
+    let simd = val.simd;
+    let zeroes = u8x16::splat(simd, 0);
+    // Zipping with zeroes puts each byte in front of, or behind, a zero byte, depending on
+    // where the least significant byte goes.
+    let zip_low = |v| match order {
+        ByteOrder::LittleEndian => simd.zip_low_u8x16(v, zeroes),
+        ByteOrder::BigEndian => simd.zip_low_u8x16(zeroes, v),
+    };
+    let zip_high = |v| match order {
+        ByteOrder::LittleEndian => simd.zip_high_u8x16(v, zeroes),
+        ByteOrder::BigEndian => simd.zip_high_u8x16(zeroes, v),
+    };
+
+    let low = zip_low(val);
+    let high = zip_high(val);
+    [zip_low(low), zip_high(low), zip_low(high), zip_high(high)]
+}
+
+/// Truncate 16 `u32`s with the given byte order, given as raw bytes, to their least significant
+/// bytes.
+#[inline(always)]
+pub fn narrow_u32_bytes_to_u8<S: Simd>(bytes: u8x64<S>, order: ByteOrder) -> u8x16<S> {
+    let simd = bytes.simd;
+    // Unzipping twice keeps every fourth byte, starting at the least significant one.
+    let unzip = |a, b| match order {
+        ByteOrder::LittleEndian => simd.unzip_low_u8x16(a, b),
+        ByteOrder::BigEndian => simd.unzip_high_u8x16(a, b),
+    };
+
+    let (x8_1, x8_2) = simd.split_u8x64(bytes);
+    let (p1, p2) = simd.split_u8x32(x8_1);
+    let (p3, p4) = simd.split_u8x32(x8_2);
+    unzip(unzip(p1, p2), unzip(p3, p4))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn widening_puts_bytes_in_the_least_significant_position() {
+        let simd = Fallback::new();
+        let bytes: [u8; 16] = core::array::from_fn(|i| i as u8 + 1);
+        let val = u8x16::from_slice(simd, &bytes);
+
+        let little = widen_u8_to_u32_bytes(val, ByteOrder::LittleEndian);
+        let big = widen_u8_to_u32_bytes(val, ByteOrder::BigEndian);
+        for (i, &b) in bytes.iter().enumerate() {
+            let (vector, lane) = (i / 4, i % 4);
+            assert_eq!(little[vector].as_slice()[lane * 4..][..4], [b, 0, 0, 0]);
+            assert_eq!(big[vector].as_slice()[lane * 4..][..4], [0, 0, 0, b]);
+        }
+    }
+
+    #[test]
+    fn narrowing_keeps_the_least_significant_bytes() {
+        let simd = Fallback::new();
+        let values: [u32; 16] = core::array::from_fn(|i| 0x0a0b_0c00 + i as u32);
+        let little: [u8; 64] = bytemuck::cast(values.map(u32::to_le_bytes));
+        let big: [u8; 64] = bytemuck::cast(values.map(u32::to_be_bytes));
+        let expected: [u8; 16] = core::array::from_fn(|i| i as u8);
+
+        let narrow = |bytes: &[u8; 64], order| {
+            *narrow_u32_bytes_to_u8(u8x64::from_slice(simd, bytes), order)
+        };
+        assert_eq!(narrow(&little, ByteOrder::LittleEndian), expected);
+        assert_eq!(narrow(&big, ByteOrder::BigEndian), expected);
+    }
+
+    #[test]
+    fn native_byte_order_matches_the_target() {
+        assert_eq!(
+            ByteOrder::NATIVE == ByteOrder::LittleEndian,
+            1_u32.to_ne_bytes()[0] == 1
+        );
+    }
+}
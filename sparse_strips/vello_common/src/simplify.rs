@@ -0,0 +1,261 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Path simplification and levels of detail.
+//!
+//! Detailed geometry, like a coastline with hundreds of thousands of points, costs the same to
+//! flatten and rasterize no matter how small it ends up on screen. When zoomed out, most of
+//! those points fall within a fraction of a pixel of each other and don't change the result.
+//!
+//! [`simplify`] reduces a path to a polyline that stays within a tolerance of the original,
+//! using the Douglas-Peucker algorithm. [`LodPath`] precomputes a series of such simplified
+//! paths with increasing tolerances, and [`LodPath::select`] picks the coarsest one that is
+//! still indistinguishable from the original under a given transform.
+
+use alloc::vec::Vec;
+
+use crate::flatten::TOL;
+use crate::kurbo::{self, Affine, BezPath, PathEl, Point, Shape};
+use crate::util::extract_scales;
+
+/// The factor between the tolerances of consecutive levels of a [`LodPath`].
+const LEVEL_FACTOR: f64 = 4.0;
+
+/// Simplify `path` into a polyline that deviates from it by at most `tolerance`, and append
+/// it to `out`.
+///
+/// Curves are flattened first, so the result only contains lines. Every subpath keeps its
+/// first and last point, and closed subpaths stay closed.
+pub fn simplify(path: impl IntoIterator<Item = PathEl>, tolerance: f64, out: &mut BezPath) {
+    // Split the tolerance between flattening and simplification.
+    let tolerance = 0.5 * tolerance;
+    let mut points = Vec::new();
+    let mut keep = Vec::new();
+    let mut stack = Vec::new();
+
+    let mut flush = |points: &mut Vec<Point>, closed: bool, out: &mut BezPath| {
+        if points.is_empty() {
+            return;
+        }
+        douglas_peucker(points, tolerance, &mut keep, &mut stack);
+        out.move_to(points[0]);
+        for (point, _) in points.iter().zip(&keep).skip(1).filter(|(_, keep)| **keep) {
+            out.line_to(*point);
+        }
+        if closed {
+            out.close_path();
+        }
+        points.clear();
+    };
+
+    kurbo::flatten(path, tolerance, |el| match el {
+        PathEl::MoveTo(p) => {
+            flush(&mut points, false, out);
+            points.push(p);
+        }
+        PathEl::LineTo(p) => points.push(p),
+        PathEl::ClosePath => {
+            if let Some(&first) = points.first() {
+                points.push(first);
+            }
+            flush(&mut points, true, out);
+        }
+        // `flatten` only emits move, line and close elements.
+        PathEl::QuadTo(..) | PathEl::CurveTo(..) => unreachable!(),
+    });
+    flush(&mut points, false, out);
+}
+
+/// Mark the points of a polyline that are kept by the Douglas-Peucker algorithm.
+fn douglas_peucker(
+    points: &[Point],
+    tolerance: f64,
+    keep: &mut Vec<bool>,
+    stack: &mut Vec<(usize, usize)>,
+) {
+    keep.clear();
+    keep.resize(points.len(), false);
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    // Use an explicit stack, since paths can have far more points than the call stack allows
+    // for recursion.
+    stack.push((0, points.len() - 1));
+    while let Some((start, end)) = stack.pop() {
+        let (a, b) = (points[start], points[end]);
+        let mut max_distance = tolerance;
+        let mut split = None;
+        for (i, p) in points.iter().enumerate().take(end).skip(start + 1) {
+            let distance = segment_distance(*p, a, b);
+            if distance > max_distance {
+                max_distance = distance;
+                split = Some(i);
+            }
+        }
+
+        if let Some(split) = split {
+            keep[split] = true;
+            stack.push((start, split));
+            stack.push((split, end));
+        }
+    }
+}
+
+/// Return the distance from `p` to the line segment from `a` to `b`.
+fn segment_distance(p: Point, a: Point, b: Point) -> f64 {
+    let ab = b - a;
+    let length_squared = ab.hypot2();
+    let t = if length_squared > 0.0 {
+        ((p - a).dot(ab) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    (p - (a + t * ab)).hypot()
+}
+
+/// A path together with simplified versions of it for rendering at smaller scales.
+#[derive(Debug, Clone)]
+pub struct LodPath {
+    path: BezPath,
+    /// Simplified paths, ordered by increasing tolerance.
+    levels: Vec<(f64, BezPath)>,
+}
+
+impl LodPath {
+    /// Create the levels of detail of `path`.
+    ///
+    /// The finest level is simplified with `tolerance`, and each further level with four times
+    /// the tolerance of the previous one, until the path is simplified to a few points. Levels
+    /// that aren't smaller than the previous one are left out.
+    pub fn new(path: BezPath, tolerance: f64) -> Self {
+        let bounds = path.bounding_box();
+        let extent = bounds.width().max(bounds.height());
+        let mut levels: Vec<(f64, BezPath)> = Vec::new();
+
+        let mut tolerance = tolerance;
+        if tolerance > 0.0 && extent.is_finite() {
+            let mut size = path.elements().len();
+            while tolerance < extent {
+                let mut simplified = BezPath::new();
+                simplify(&path, tolerance, &mut simplified);
+                if simplified.elements().len() < size {
+                    size = simplified.elements().len();
+                    levels.push((tolerance, simplified));
+                }
+                tolerance *= LEVEL_FACTOR;
+            }
+        }
+
+        Self { path, levels }
+    }
+
+    /// The original path.
+    pub fn path(&self) -> &BezPath {
+        &self.path
+    }
+
+    /// The simplified paths and their tolerances, ordered by increasing tolerance.
+    pub fn levels(&self) -> impl Iterator<Item = (f64, &BezPath)> {
+        self.levels
+            .iter()
+            .map(|(tolerance, path)| (*tolerance, path))
+    }
+
+    /// Return the coarsest version of the path that stays within the flattening tolerance of
+    /// the renderers when drawn with `transform`.
+    pub fn select(&self, transform: &Affine) -> &BezPath {
+        let (scale, _) = extract_scales(transform);
+        self.select_tolerance(TOL / f64::from(scale))
+    }
+
+    /// Return the coarsest version of the path that deviates from it by at most `tolerance`.
+    pub fn select_tolerance(&self, tolerance: f64) -> &BezPath {
+        self.levels
+            .iter()
+            .rev()
+            .find(|(level, _)| *level <= tolerance)
+            .map_or(&self.path, |(_, path)| path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LodPath, segment_distance, simplify};
+    use crate::kurbo::{Affine, BezPath, Circle, PathEl, Point, Shape};
+
+    fn wiggly_line() -> BezPath {
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        for i in 1..=1000 {
+            let x = f64::from(i) * 0.1;
+            path.line_to((x, (x * 3.0).sin() * 0.05 + (x * 0.1).sin() * 10.0));
+        }
+        path
+    }
+
+    #[test]
+    fn simplified_paths_stay_within_tolerance() {
+        let path = wiggly_line();
+        let points: alloc::vec::Vec<_> = path
+            .elements()
+            .iter()
+            .filter_map(|el| el.end_point())
+            .collect();
+
+        for tolerance in [0.01, 0.2, 1.0] {
+            let mut simplified = BezPath::new();
+            simplify(&path, tolerance, &mut simplified);
+            assert!(simplified.elements().len() < path.elements().len());
+            assert_eq!(simplified.elements()[0], PathEl::MoveTo(Point::ZERO));
+            assert_eq!(simplified.elements().last(), path.elements().last());
+
+            let lines: alloc::vec::Vec<_> = simplified.segments().collect();
+            for p in &points {
+                let distance = lines
+                    .iter()
+                    .map(|seg| {
+                        let line = seg.as_line().unwrap();
+                        segment_distance(*p, line.p0, line.p1)
+                    })
+                    .fold(f64::INFINITY, f64::min);
+                assert!(distance <= tolerance, "{p:?} is {distance} away");
+            }
+        }
+    }
+
+    #[test]
+    fn closed_subpaths_stay_closed() {
+        let mut path = Circle::new((10.0, 10.0), 5.0).to_path(0.01);
+        path.extend(Circle::new((30.0, 10.0), 5.0).to_path(0.01));
+        let mut simplified = BezPath::new();
+        simplify(&path, 0.5, &mut simplified);
+
+        let closes = |path: &BezPath| {
+            path.elements()
+                .iter()
+                .filter(|el| **el == PathEl::ClosePath)
+                .count()
+        };
+        assert_eq!(closes(&simplified), 2);
+        assert!((simplified.area() - path.area()).abs() < 0.05 * path.area());
+    }
+
+    #[test]
+    fn levels_are_selected_by_scale() {
+        let lod = LodPath::new(wiggly_line(), 0.01);
+        let levels: alloc::vec::Vec<_> = lod.levels().collect();
+        assert!(levels.len() >= 3);
+        for pair in levels.windows(2) {
+            assert!(pair[0].0 < pair[1].0);
+            assert!(pair[0].1.elements().len() > pair[1].1.elements().len());
+        }
+
+        // Zoomed in, the original path is used.
+        assert!(core::ptr::eq(lod.select(&Affine::scale(100.0)), lod.path()));
+        let at_1x = lod.select(&Affine::IDENTITY);
+        let at_tenth = lod.select(&Affine::scale(0.1).then_rotate(1.0));
+        assert!(at_1x.elements().len() < lod.path().elements().len());
+        assert!(at_tenth.elements().len() < at_1x.elements().len());
+    }
+}
@@ -0,0 +1,329 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Visualizing the strips generated for a scene.
+//!
+//! The cost of rendering a scene with the sparse strips renderers depends less on the number
+//! of paths than on the strips generated for them: every anti-aliased tile of a strip is
+//! rasterized with its own alpha mask, while the sparse fills between strips are cheap. A
+//! [`StripDebugger`] records the strips of every path drawn into a scene and turns them into
+//! an overlay of colored rectangles, which makes it easy to spot where a scene produces
+//! excessive work, for example because of paths with many tiny segments or heavy overdraw.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::color::{AlphaColor, Srgb};
+use crate::kurbo::Rect;
+use crate::strip::Strip;
+use crate::tile::Tile;
+
+/// What a [`StripDebugger`] visualizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StripDebugView {
+    /// Color each strip differently, with the sparse fills between strips in gray.
+    Strips,
+    /// Color each tile by the number of anti-aliased strip tiles generated for it.
+    Tiles,
+    /// Color each tile by the number of times it is covered by strip tiles or sparse fills,
+    /// which shows the overdraw of the scene.
+    Coverage,
+}
+
+/// Statistics about the strips recorded by a [`StripDebugger`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StripDebugStats {
+    /// The number of paths whose strips were recorded.
+    pub paths: u32,
+    /// The number of strips, excluding the sentinel strips at the end of each path.
+    pub strips: u32,
+    /// The number of anti-aliased tiles, which each have their own alpha mask.
+    pub alpha_tiles: u64,
+    /// The number of tiles covered by sparse fills between strips.
+    pub fill_tiles: u64,
+}
+
+/// A horizontal span of tiles in a row of strips.
+#[derive(Debug, Clone, Copy)]
+struct Span {
+    /// The index of the first tile of the span.
+    x0: u16,
+    /// The index of the tile after the span.
+    x1: u16,
+    /// The row of the span, in strip units.
+    y: u16,
+    /// Whether the span is a sparse fill instead of a strip.
+    is_fill: bool,
+}
+
+/// The colors of the heat maps, from the lowest to the highest count.
+const HEAT_COLORS: [[u8; 3]; 6] = [
+    [49, 54, 149],
+    [69, 117, 180],
+    [116, 173, 209],
+    [254, 224, 144],
+    [244, 109, 67],
+    [215, 48, 39],
+];
+
+/// The colors that strips cycle through.
+const STRIP_COLORS: [[u8; 3]; 6] = [
+    [228, 26, 28],
+    [55, 126, 184],
+    [77, 175, 74],
+    [152, 78, 163],
+    [255, 127, 0],
+    [166, 86, 40],
+];
+
+/// The color of sparse fills between strips.
+const FILL_COLOR: [u8; 3] = [128, 128, 128];
+
+/// The opacity of the overlay, so that the scene stays recognizable beneath it.
+const OVERLAY_ALPHA: f32 = 0.6;
+
+/// Records the strips generated for a scene and visualizes them.
+#[derive(Debug, Clone)]
+pub struct StripDebugger {
+    view: StripDebugView,
+    width_tiles: u16,
+    height_tiles: u16,
+    /// The number of anti-aliased strip tiles for each tile, in row-major order.
+    alpha_counts: Vec<u32>,
+    /// The number of sparse fills covering each tile, in row-major order.
+    fill_counts: Vec<u32>,
+    /// The spans of the strips and fills, only recorded for [`StripDebugView::Strips`].
+    spans: Vec<Span>,
+    stats: StripDebugStats,
+}
+
+impl StripDebugger {
+    /// Create a debugger for a scene with the given width and height in pixels.
+    pub fn new(width: u16, height: u16, view: StripDebugView) -> Self {
+        let width_tiles = width.div_ceil(Tile::WIDTH);
+        let height_tiles = height.div_ceil(Tile::HEIGHT);
+        let tile_count = usize::from(width_tiles) * usize::from(height_tiles);
+
+        Self {
+            view,
+            width_tiles,
+            height_tiles,
+            alpha_counts: vec![0; tile_count],
+            fill_counts: vec![0; tile_count],
+            spans: Vec::new(),
+            stats: StripDebugStats::default(),
+        }
+    }
+
+    /// The view that is visualized.
+    pub fn view(&self) -> StripDebugView {
+        self.view
+    }
+
+    /// Statistics about the strips recorded since the last clear.
+    pub fn stats(&self) -> StripDebugStats {
+        self.stats
+    }
+
+    /// Forget all recorded strips.
+    pub fn clear(&mut self) {
+        self.alpha_counts.fill(0);
+        self.fill_counts.fill(0);
+        self.spans.clear();
+        self.stats = StripDebugStats::default();
+    }
+
+    /// Record the strips generated for a single path, which end with a sentinel strip.
+    pub fn record(&mut self, strips: &[Strip]) {
+        if strips.len() < 2 {
+            return;
+        }
+
+        self.stats.paths += 1;
+        self.stats.strips += u32::try_from(strips.len() - 1).unwrap();
+        for pair in strips.windows(2) {
+            let (strip, next_strip) = (&pair[0], &pair[1]);
+            let y = strip.strip_y();
+            if y >= self.height_tiles {
+                continue;
+            }
+
+            // Like in coarse rasterization, the width of a strip follows from the number of
+            // alpha columns until the next strip.
+            let columns =
+                next_strip.alpha_idx().saturating_sub(strip.alpha_idx()) / u32::from(Tile::HEIGHT);
+            let x0 = strip.x / Tile::WIDTH;
+            let x1 = u16::try_from(u32::from(strip.x) + columns)
+                .unwrap_or(u16::MAX)
+                .div_ceil(Tile::WIDTH);
+            self.add_span(x0, x1, y, false);
+
+            if next_strip.fill_gap() && next_strip.strip_y() == y {
+                self.add_span(x1, next_strip.x / Tile::WIDTH, y, true);
+            }
+        }
+    }
+
+    fn add_span(&mut self, x0: u16, x1: u16, y: u16, is_fill: bool) {
+        let x1 = x1.min(self.width_tiles);
+        if x0 >= x1 {
+            return;
+        }
+
+        let row = usize::from(y) * usize::from(self.width_tiles);
+        let counts = if is_fill {
+            self.stats.fill_tiles += u64::from(x1 - x0);
+            &mut self.fill_counts
+        } else {
+            self.stats.alpha_tiles += u64::from(x1 - x0);
+            &mut self.alpha_counts
+        };
+        for count in &mut counts[row + usize::from(x0)..row + usize::from(x1)] {
+            *count = count.saturating_add(1);
+        }
+
+        if self.view == StripDebugView::Strips {
+            self.spans.push(Span { x0, x1, y, is_fill });
+        }
+    }
+
+    /// Return the rectangles of the overlay that visualizes the recorded strips, in pixels,
+    /// with the colors they should be filled with.
+    ///
+    /// Adjacent tiles of the heat maps with the same color are merged into a single
+    /// rectangle.
+    pub fn overlay(&self) -> Vec<(Rect, AlphaColor<Srgb>)> {
+        let tile_rect = |x0: u16, x1: u16, y: u16| {
+            let [x0, x1, y] = [x0, x1, y].map(u32::from);
+            let (tile_width, tile_height) = (u32::from(Tile::WIDTH), u32::from(Tile::HEIGHT));
+            Rect::new(
+                f64::from(x0 * tile_width),
+                f64::from(y * tile_height),
+                f64::from(x1 * tile_width),
+                f64::from((y + 1) * tile_height),
+            )
+        };
+
+        let counts = match self.view {
+            StripDebugView::Strips => {
+                let mut strip_idx = 0;
+                return self
+                    .spans
+                    .iter()
+                    .map(|span| {
+                        let color = if span.is_fill {
+                            FILL_COLOR
+                        } else {
+                            strip_idx += 1;
+                            STRIP_COLORS[(strip_idx - 1) % STRIP_COLORS.len()]
+                        };
+                        (tile_rect(span.x0, span.x1, span.y), overlay_color(color))
+                    })
+                    .collect();
+            }
+            StripDebugView::Tiles => self.alpha_counts.clone(),
+            StripDebugView::Coverage => self
+                .alpha_counts
+                .iter()
+                .zip(&self.fill_counts)
+                .map(|(alpha, fill)| alpha.saturating_add(*fill))
+                .collect(),
+        };
+
+        let mut rects = Vec::new();
+        let row_len = usize::from(self.width_tiles).max(1);
+        for (y, row) in (0..self.height_tiles).zip(counts.chunks_exact(row_len)) {
+            let mut x0 = 0;
+            while x0 < self.width_tiles {
+                let level = heat_level(row[usize::from(x0)]);
+                let mut x1 = x0 + 1;
+                while x1 < self.width_tiles && heat_level(row[usize::from(x1)]) == level {
+                    x1 += 1;
+                }
+                if let Some(level) = level {
+                    rects.push((tile_rect(x0, x1, y), overlay_color(HEAT_COLORS[level])));
+                }
+                x0 = x1;
+            }
+        }
+
+        rects
+    }
+}
+
+/// Return the index of the heat map color for a count, or `None` for tiles that aren't
+/// covered at all.
+///
+/// The levels grow exponentially, so that the colors still tell apart tiles that are
+/// covered dozens of times.
+fn heat_level(count: u32) -> Option<usize> {
+    let level = count.checked_ilog2()? as usize;
+    Some(level.min(HEAT_COLORS.len() - 1))
+}
+
+fn overlay_color([r, g, b]: [u8; 3]) -> AlphaColor<Srgb> {
+    AlphaColor::from_rgb8(r, g, b).with_alpha(OVERLAY_ALPHA)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::{StripDebugView, StripDebugger};
+    use crate::kurbo::Rect;
+    use crate::strip::Strip;
+    use crate::tile::Tile;
+
+    /// Two strips of a single tile in the first row, with a sparse fill of two tiles between
+    /// them.
+    fn strips() -> [Strip; 3] {
+        let alphas_per_tile = u32::from(Tile::WIDTH * Tile::HEIGHT);
+        [
+            Strip::new(0, 0, 0, false),
+            Strip::new(12, 0, alphas_per_tile, true),
+            Strip::new(u16::MAX, 0, 2 * alphas_per_tile, false),
+        ]
+    }
+
+    #[test]
+    fn stats_count_strips_and_tiles() {
+        let mut debugger = StripDebugger::new(32, 8, StripDebugView::Strips);
+        debugger.record(&strips());
+        debugger.record(&strips());
+
+        let stats = debugger.stats();
+        assert_eq!(stats.paths, 2);
+        assert_eq!(stats.strips, 4);
+        assert_eq!(stats.alpha_tiles, 4);
+        assert_eq!(stats.fill_tiles, 4);
+
+        debugger.clear();
+        assert_eq!(debugger.stats().paths, 0);
+    }
+
+    #[test]
+    fn strips_view_has_a_rect_per_span() {
+        let mut debugger = StripDebugger::new(32, 8, StripDebugView::Strips);
+        debugger.record(&strips());
+
+        let rects: Vec<Rect> = debugger.overlay().iter().map(|(rect, _)| *rect).collect();
+        assert_eq!(
+            rects,
+            [
+                Rect::new(0.0, 0.0, 4.0, 4.0),
+                Rect::new(4.0, 0.0, 12.0, 4.0),
+                Rect::new(12.0, 0.0, 16.0, 4.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn coverage_view_merges_tiles_with_the_same_level() {
+        let mut debugger = StripDebugger::new(32, 8, StripDebugView::Coverage);
+        debugger.record(&strips());
+
+        let overlay = debugger.overlay();
+        assert_eq!(overlay.len(), 1);
+        assert_eq!(overlay[0].0, Rect::new(0.0, 0.0, 16.0, 4.0));
+    }
+}
@@ -5,15 +5,53 @@
 
 use crate::clip::{PathDataRef, intersect};
 use crate::fearless_simd::Level;
-use crate::flatten::{FlattenCtx, Line};
+use crate::flatten::{FlattenCtx, Line, Point};
+use crate::fragment::Fragment;
 use crate::geometry::RectU16;
 use crate::kurbo::{Affine, PathEl, Rect, Stroke};
 use crate::peniko::Fill;
+use crate::render_stats::RenderStats;
 use crate::strip::Strip;
 use crate::tile::Tiles;
 use crate::{flatten, rect, strip};
 use alloc::vec::Vec;
 use peniko::kurbo::StrokeCtx;
+#[cfg(not(feature = "std"))]
+use peniko::kurbo::common::FloatFuncs as _;
+
+/// The coverage threshold used for pixel-snapped paths if no aliasing threshold is set, so that
+/// pixels which are at least half covered are painted.
+const SNAP_THRESHOLD: u8 = 128;
+
+/// How the edges of a path are rasterized.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Aliasing {
+    /// If set, a pixel is fully painted if its coverage is at least the threshold (between 0
+    /// and 255), and not painted at all otherwise. If `None`, edges are anti-aliased.
+    pub threshold: Option<u8>,
+    /// Whether to snap the edges of the path to the pixel grid.
+    ///
+    /// Each vertex of the flattened path is moved to the nearest pixel corner, so that
+    /// axis-aligned edges fall exactly onto pixel boundaries. Snapping implies that
+    /// anti-aliasing is disabled; without a threshold, pixels that are at least half covered
+    /// are painted.
+    pub snap_to_pixels: bool,
+}
+
+impl Aliasing {
+    /// Whether edges are anti-aliased, which is the default.
+    pub fn is_anti_aliased(self) -> bool {
+        self.threshold.is_none() && !self.snap_to_pixels
+    }
+
+    /// Return the coverage threshold that is applied when generating strips.
+    fn effective_threshold(self) -> Option<u8> {
+        match (self.threshold, self.snap_to_pixels) {
+            (None, true) => Some(SNAP_THRESHOLD),
+            (threshold, _) => threshold,
+        }
+    }
+}
 
 /// A storage for storing strip-related data.
 #[derive(Debug, Default, PartialEq, Eq)]
@@ -74,6 +112,26 @@ impl StripStorage {
         self.strips.extend(&other.strips);
         self.alphas.extend(&other.alphas);
     }
+    /// Add the strips of a single path that were generated into a separate storage, as if
+    /// they had been generated into this storage.
+    ///
+    /// Like the strip generator, this respects the generation mode of this storage. The alpha
+    /// indices of the strips are shifted to point to their alphas in this storage.
+    pub fn push_path(&mut self, path: &Self) {
+        match self.generation_mode {
+            GenerationMode::Replace => self.strips.clear(),
+            GenerationMode::Append => {}
+            GenerationMode::ReplaceAfter(n) => self.strips.truncate(n),
+        }
+
+        let alpha_offset = u32::try_from(self.alphas.len()).unwrap();
+        self.strips.extend(path.strips.iter().map(|strip| {
+            let mut strip = *strip;
+            strip.set_alpha_idx(strip.alpha_idx() + alpha_offset);
+            strip
+        }));
+        self.alphas.extend(&path.alphas);
+    }
 }
 
 /// An object for easily generating strips for a filled/stroked path.
@@ -87,6 +145,7 @@ pub struct StripGenerator {
     tiles: Tiles,
     width: u16,
     height: u16,
+    stats: RenderStats,
 }
 
 impl StripGenerator {
@@ -101,9 +160,16 @@ impl StripGenerator {
             temp_storage: StripStorage::default(),
             width,
             height,
+            stats: RenderStats::default(),
         }
     }
 
+    /// Get the SIMD level of this strip generator.
+    #[inline(always)]
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
     /// Get this strip generator's viewport width.
     #[inline(always)]
     pub fn width(&self) -> u16 {
@@ -116,13 +182,33 @@ impl StripGenerator {
         self.height
     }
 
+    /// Set the tolerance for flattening the curves of paths, in device pixels.
+    ///
+    /// See [`FlattenCtx::set_tolerance`].
+    #[inline(always)]
+    pub fn set_tolerance(&mut self, tolerance: f64) {
+        self.flatten_ctx.set_tolerance(tolerance);
+    }
+
+    /// Get the tolerance for flattening the curves of paths, in device pixels.
+    #[inline(always)]
+    pub fn tolerance(&self) -> f64 {
+        self.flatten_ctx.tolerance()
+    }
+
+    /// Get the number of paths, strips and alpha bytes generated since the last reset.
+    #[inline(always)]
+    pub fn stats(&self) -> RenderStats {
+        self.stats
+    }
+
     /// Generate the strips for a filled path.
     pub fn generate_filled_path(
         &mut self,
         path: impl IntoIterator<Item = PathEl>,
         fill_rule: Fill,
         transform: Affine,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
         strip_storage: &mut StripStorage,
         clip_path: Option<PathDataRef<'_>>,
     ) {
@@ -138,7 +224,7 @@ impl StripGenerator {
             cull_bbox,
         );
 
-        self.generate_with_clip(aliasing_threshold, strip_storage, fill_rule, clip_path);
+        self.generate_with_clip(aliasing, strip_storage, fill_rule, clip_path);
     }
 
     /// Generate the strips for a stroked path.
@@ -147,7 +233,7 @@ impl StripGenerator {
         path: impl IntoIterator<Item = PathEl>,
         stroke: &Stroke,
         transform: Affine,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
         strip_storage: &mut StripStorage,
         clip_path: Option<PathDataRef<'_>>,
     ) {
@@ -164,16 +250,35 @@ impl StripGenerator {
             &mut self.stroke_ctx,
             cull_bbox,
         );
-        self.generate_with_clip(aliasing_threshold, strip_storage, Fill::NonZero, clip_path);
+        self.generate_with_clip(aliasing, strip_storage, Fill::NonZero, clip_path);
+    }
+
+    /// Generate the strips for a [`Fragment`] drawn with the given transform.
+    pub fn generate_fragment(
+        &mut self,
+        fragment: &Fragment,
+        transform: Affine,
+        aliasing: Aliasing,
+        strip_storage: &mut StripStorage,
+        clip_path: Option<PathDataRef<'_>>,
+    ) {
+        fragment.transform_lines(transform, self.width, self.height, &mut self.line_buf);
+        self.generate_with_clip(aliasing, strip_storage, fragment.fill_rule(), clip_path);
     }
 
     fn generate_with_clip(
         &mut self,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
         strip_storage: &mut StripStorage,
         fill_rule: Fill,
         clip_path: Option<PathDataRef<'_>>,
     ) {
+        if aliasing.snap_to_pixels {
+            snap_lines(&mut self.line_buf);
+        }
+        let aliasing_threshold = aliasing.effective_threshold();
+        self.stats.paths += 1;
+
         self.tiles
             .make_tiles_analytic_aa(self.level, &self.line_buf, self.width, self.height);
 
@@ -187,6 +292,7 @@ impl StripGenerator {
             &mut self.temp_storage,
             strip_storage,
             clip_path,
+            &mut self.stats,
             |strips, alphas| {
                 strip::render(
                     level,
@@ -232,6 +338,7 @@ impl StripGenerator {
             &mut self.temp_storage,
             strip_storage,
             clip_path,
+            &mut self.stats,
             |strips, alphas| {
                 rect::render(level, clamped, strips, alphas);
             },
@@ -243,19 +350,33 @@ impl StripGenerator {
         self.line_buf.clear();
         self.tiles.reset();
         self.temp_storage.clear();
+        self.stats = RenderStats::default();
     }
 }
 
+/// Move the end points of all lines to the nearest pixel corner, and drop lines that collapse
+/// into a point.
+fn snap_lines(lines: &mut Vec<Line>) {
+    let snap = |p: Point| Point::new((p.x + 0.5).floor(), (p.y + 0.5).floor());
+    lines.retain_mut(|line| {
+        line.p0 = snap(line.p0);
+        line.p1 = snap(line.p1);
+        line.p0 != line.p1
+    });
+}
+
 /// Render strips via `render_fn` with optional clip intersection.
 ///
 /// When `clip_path` is `Some`, strips are rendered into `temp_storage` first, then
 /// intersected with the clip mask into `strip_storage`. Otherwise strips are rendered
-/// directly into `strip_storage`.
+/// directly into `strip_storage`. The strips and alphas added to `strip_storage` are counted
+/// in `stats`.
 fn render_with_clip(
     level: Level,
     temp_storage: &mut StripStorage,
     strip_storage: &mut StripStorage,
     clip_path: Option<PathDataRef<'_>>,
+    stats: &mut RenderStats,
     render_fn: impl FnOnce(&mut Vec<Strip>, &mut Vec<u8>),
 ) {
     match strip_storage.generation_mode {
@@ -263,6 +384,7 @@ fn render_with_clip(
         GenerationMode::Append => {}
         GenerationMode::ReplaceAfter(n) => strip_storage.strips.truncate(n),
     }
+    let (strips_before, alphas_before) = (strip_storage.strips.len(), strip_storage.alphas.len());
 
     if let Some(clip_path) = clip_path {
         temp_storage.clear();
@@ -278,6 +400,9 @@ fn render_with_clip(
     } else {
         render_fn(&mut strip_storage.strips, &mut strip_storage.alphas);
     }
+
+    stats.strips += (strip_storage.strips.len() - strips_before) as u64;
+    stats.alpha_bytes += (strip_storage.alphas.len() - alphas_before) as u64;
 }
 
 #[cfg(test)]
@@ -287,7 +412,100 @@ mod tests {
     use crate::fearless_simd::Level;
     use crate::kurbo::{Affine, Rect, Shape};
     use crate::peniko::Fill;
-    use crate::strip_generator::{StripGenerator, StripStorage};
+    use crate::render_stats::RenderStats;
+    use crate::strip_generator::{Aliasing, GenerationMode, StripGenerator, StripStorage};
+
+    #[test]
+    fn push_path_matches_direct_generation() {
+        let mut generator = StripGenerator::new(100, 100, Level::baseline());
+        let paths = [
+            Rect::new(10.5, 10.5, 40.0, 30.0),
+            Rect::new(20.0, 50.25, 90.0, 70.0),
+        ];
+        let mut direct = StripStorage::new(GenerationMode::Append);
+        let mut pushed = StripStorage::new(GenerationMode::Append);
+        for rect in paths {
+            let mut path_storage = StripStorage::default();
+            for storage in [&mut direct, &mut path_storage] {
+                generator.generate_filled_path(
+                    rect.to_path(0.1),
+                    Fill::NonZero,
+                    Affine::IDENTITY,
+                    Aliasing::default(),
+                    storage,
+                    None,
+                );
+            }
+            pushed.push_path(&path_storage);
+        }
+
+        assert_eq!(pushed, direct);
+    }
+
+    #[test]
+    fn stats_count_generated_paths_strips_and_alphas() {
+        let mut generator = StripGenerator::new(100, 100, Level::baseline());
+        let mut storage = StripStorage::new(GenerationMode::Append);
+        for rect in [
+            Rect::new(10.5, 10.5, 40.0, 30.0),
+            Rect::new(0.0, 0.0, 8.0, 8.0),
+        ] {
+            generator.generate_filled_path(
+                rect.to_path(0.1),
+                Fill::NonZero,
+                Affine::IDENTITY,
+                Aliasing::default(),
+                &mut storage,
+                None,
+            );
+        }
+
+        let stats = generator.stats();
+        assert_eq!(stats.paths, 2);
+        assert_eq!(stats.strips, storage.strips.len() as u64);
+        assert_eq!(stats.alpha_bytes, storage.alphas.len() as u64);
+        assert_eq!(stats.draw_calls, 0);
+
+        generator.reset();
+        assert_eq!(generator.stats(), RenderStats::default());
+    }
+
+    #[test]
+    fn coarser_tolerances_flatten_curves_into_fewer_lines() {
+        use crate::kurbo::{Circle, Stroke};
+
+        let circle = Circle::new((50.0, 50.0), 40.0).to_path(0.01);
+        let lines = |tolerance: f64, stroked: bool| {
+            let mut generator = StripGenerator::new(100, 100, Level::baseline());
+            generator.set_tolerance(tolerance);
+            let mut storage = StripStorage::default();
+            if stroked {
+                generator.generate_stroked_path(
+                    circle.iter(),
+                    &Stroke::new(4.0),
+                    Affine::IDENTITY,
+                    Aliasing::default(),
+                    &mut storage,
+                    None,
+                );
+            } else {
+                generator.generate_filled_path(
+                    circle.iter(),
+                    Fill::NonZero,
+                    Affine::IDENTITY,
+                    Aliasing::default(),
+                    &mut storage,
+                    None,
+                );
+            }
+            generator.line_buf.len()
+        };
+
+        for stroked in [false, true] {
+            assert!(lines(1.0, stroked) < lines(0.25, stroked));
+            assert!(lines(0.25, stroked) < lines(0.1, stroked));
+        }
+    }
 
     #[test]
     fn reset() {
@@ -299,7 +517,7 @@ mod tests {
             rect.to_path(0.1),
             Fill::NonZero,
             Affine::IDENTITY,
-            None,
+            Aliasing::default(),
             &mut storage,
             None,
         );
@@ -314,6 +532,31 @@ mod tests {
         assert!(storage.is_empty());
     }
 
+    #[test]
+    fn pixel_snapping_rounds_to_pixel_edges() {
+        let mut generator = StripGenerator::new(100, 100, Level::baseline());
+        let mut snapped = StripStorage::default();
+        let mut aligned = StripStorage::default();
+        let aliasing = Aliasing {
+            threshold: None,
+            snap_to_pixels: true,
+        };
+
+        generator.generate_filled_path(
+            Rect::new(10.4, 3.6, 29.5, 20.2).to_path(0.1),
+            Fill::NonZero,
+            Affine::IDENTITY,
+            aliasing,
+            &mut snapped,
+            None,
+        );
+        generator.reset();
+        generator.generate_filled_rect_fast(&Rect::new(10.0, 4.0, 30.0, 20.0), &mut aligned, None);
+
+        assert_eq!(snapped.strips, aligned.strips);
+        assert_eq!(snapped.alphas, aligned.alphas);
+    }
+
     /// Assert that `generate_filled_rect_fast` produces the same strips as the
     /// path-based pipeline for the given rectangle.
     fn assert_rect_fast_eq_path(rect: Rect, test_name: &str) {
@@ -325,7 +568,7 @@ mod tests {
             rect.to_path(0.1),
             Fill::NonZero,
             Affine::IDENTITY,
-            None,
+            Aliasing::default(),
             &mut storage_path,
             None,
         );
@@ -0,0 +1,474 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Caching of rendered document tiles across zoom levels.
+//!
+//! Document viewers (PDF readers, e-book viewers and the like) show large, mostly static
+//! pages at arbitrary zoom factors. Re-rendering every visible page on each frame is wasteful,
+//! so a [`DocumentTileCache`] splits pages into fixed-size tiles that are rendered once per
+//! zoom bucket and reused while scrolling.
+//!
+//! Zoom factors are grouped into power-of-two buckets, and tiles are always rendered at the
+//! scale of the bucket, which is the smallest power of two that is not smaller than the zoom.
+//! The viewer then draws the tiles scaled down by a factor between 0.5 and 1, which keeps
+//! them sharp while avoiding re-rendering for every small change of the zoom.
+//!
+//! The cache is independent of the renderer. Each [`TileRequest`] describes the size of the
+//! tile and the transform from page coordinates into it, so the tile can be rendered with a
+//! `vello_cpu` render context or a `vello_hybrid` scene of that size alike.
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::ops::Range;
+use hashbrown::HashMap;
+
+use crate::kurbo::{Affine, Rect, Size};
+#[cfg(not(feature = "std"))]
+use peniko::kurbo::common::FloatFuncs as _;
+
+/// The key of a tile in a [`DocumentTileCache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TileKey {
+    /// The index of the page the tile belongs to.
+    pub page: usize,
+    /// The zoom bucket the tile was rendered for, see [`DocumentTileCache::level`].
+    pub level: i32,
+    /// The column of the tile within the page.
+    pub col: u32,
+    /// The row of the tile within the page.
+    pub row: u32,
+}
+
+/// A tile that needs to be rendered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileRequest {
+    /// The key under which the rendered tile is cached.
+    pub key: TileKey,
+    /// The transform from page coordinates into the pixel coordinates of the tile.
+    pub transform: Affine,
+    /// The width of the tile in pixels.
+    ///
+    /// Tiles at the right edge of a page are cut off at the page boundary, so they can be
+    /// narrower than the tile size of the cache.
+    pub width: u16,
+    /// The height of the tile in pixels.
+    pub height: u16,
+    /// The area of the page covered by the tile, in page coordinates.
+    pub bounds: Rect,
+}
+
+#[derive(Debug)]
+struct CachedTile<T> {
+    tile: T,
+    last_used: u64,
+}
+
+/// A cache of rendered page tiles for document viewers.
+///
+/// Tiles are cached for the current zoom bucket only; changing to a different bucket with
+/// [`DocumentTileCache::set_zoom`] drops all tiles and pending prefetches. Once more than
+/// `max_tiles` tiles are cached, the least recently used ones are evicted.
+///
+/// `T` is the rendered tile, for example an `Arc<Pixmap>` or a GPU texture handle.
+#[derive(Debug)]
+pub struct DocumentTileCache<T> {
+    entries: HashMap<TileKey, CachedTile<T>>,
+    prefetch_queue: VecDeque<TileRequest>,
+    tile_size: u16,
+    max_tiles: usize,
+    level: i32,
+    epoch: u64,
+}
+
+impl<T: Clone> DocumentTileCache<T> {
+    /// Create a new cache with square tiles of `tile_size` pixels that holds at most
+    /// `max_tiles` tiles.
+    ///
+    /// The zoom starts out at 1.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tile_size` is zero.
+    pub fn new(tile_size: u16, max_tiles: usize) -> Self {
+        assert!(tile_size > 0, "tile size must be positive");
+
+        Self {
+            entries: HashMap::new(),
+            prefetch_queue: VecDeque::new(),
+            tile_size,
+            max_tiles,
+            level: 0,
+            epoch: 0,
+        }
+    }
+
+    /// Return the size of the tiles in pixels.
+    pub fn tile_size(&self) -> u16 {
+        self.tile_size
+    }
+
+    /// Return the current zoom bucket.
+    ///
+    /// This is the base-2 logarithm of [`DocumentTileCache::scale`].
+    pub fn level(&self) -> i32 {
+        self.level
+    }
+
+    /// Return the scale at which tiles of the current zoom bucket are rendered.
+    pub fn scale(&self) -> f64 {
+        level_scale(self.level)
+    }
+
+    /// Set the zoom factor of the viewer.
+    ///
+    /// If the zoom falls into a different bucket than before, all cached tiles and pending
+    /// prefetches are dropped and `true` is returned. Non-positive and non-finite zoom
+    /// factors are ignored.
+    pub fn set_zoom(&mut self, zoom: f64) -> bool {
+        if !(zoom.is_finite() && zoom > 0.0) {
+            return false;
+        }
+
+        let level = zoom_level(zoom);
+        if level == self.level {
+            return false;
+        }
+
+        self.level = level;
+        self.entries.clear();
+        self.prefetch_queue.clear();
+
+        true
+    }
+
+    /// Return the tiles of a page of size `page_size` that intersect `visible`.
+    ///
+    /// Both `page_size` and `visible` are in page coordinates, so `visible` is the part of the
+    /// viewport that overlaps the page, mapped back into the page's coordinate system.
+    pub fn tiles(&self, page: usize, page_size: Size, visible: Rect) -> Vec<TileRequest> {
+        let page_rect = page_size.to_rect();
+        let visible = visible.intersect(page_rect);
+        if visible.is_zero_area() {
+            return Vec::new();
+        }
+
+        let scale = self.scale();
+        let tile_size = f64::from(self.tile_size);
+        let page_px = (page_size.width * scale, page_size.height * scale);
+        let cols = tile_range(visible.x0, visible.x1, scale, tile_size, page_px.0);
+        let rows = tile_range(visible.y0, visible.y1, scale, tile_size, page_px.1);
+
+        let mut tiles = Vec::with_capacity(cols.len() * rows.len());
+        for row in rows {
+            for col in cols.clone() {
+                let x0 = f64::from(col) * tile_size;
+                let y0 = f64::from(row) * tile_size;
+                let x1 = (x0 + tile_size).min(page_px.0.ceil());
+                let y1 = (y0 + tile_size).min(page_px.1.ceil());
+
+                tiles.push(TileRequest {
+                    key: TileKey {
+                        page,
+                        level: self.level,
+                        col,
+                        row,
+                    },
+                    transform: Affine::translate((-x0, -y0)) * Affine::scale(scale),
+                    width: (x1 - x0) as u16,
+                    height: (y1 - y0) as u16,
+                    bounds: Rect::new(x0 / scale, y0 / scale, x1 / scale, y1 / scale)
+                        .intersect(page_rect),
+                });
+            }
+        }
+
+        tiles
+    }
+
+    /// Return the cached tile with the given key, if there is one.
+    pub fn get(&mut self, key: &TileKey) -> Option<T> {
+        self.epoch += 1;
+        let entry = self.entries.get_mut(key)?;
+        entry.last_used = self.epoch;
+
+        Some(entry.tile.clone())
+    }
+
+    /// Return the requested tile, rendering it if it isn't cached yet.
+    ///
+    /// `render` is only called if the tile needs to be rendered.
+    pub fn get_or_render(
+        &mut self,
+        request: &TileRequest,
+        render: impl FnOnce(&TileRequest) -> T,
+    ) -> T {
+        if let Some(tile) = self.get(&request.key) {
+            return tile;
+        }
+
+        let tile = render(request);
+        self.insert(request.key, tile.clone());
+        self.trim(self.max_tiles);
+
+        tile
+    }
+
+    /// Queue all tiles of a page for prefetching.
+    ///
+    /// Queued tiles are rendered by [`DocumentTileCache::prefetch_step`]. Tiles that are
+    /// already cached or queued are skipped.
+    pub fn queue_page(&mut self, page: usize, page_size: Size) {
+        for request in self.tiles(page, page_size, page_size.to_rect()) {
+            if !self.entries.contains_key(&request.key)
+                && !self.prefetch_queue.iter().any(|r| r.key == request.key)
+            {
+                self.prefetch_queue.push_back(request);
+            }
+        }
+    }
+
+    /// Queue the pages directly before and after the `visible` pages for prefetching.
+    ///
+    /// `pages` holds the sizes of all pages of the document.
+    pub fn queue_adjacent_pages(&mut self, pages: &[Size], visible: Range<usize>) {
+        if let Some(next) = pages.get(visible.end) {
+            self.queue_page(visible.end, *next);
+        }
+        if let Some(prev) = visible.start.checked_sub(1) {
+            self.queue_page(prev, pages[prev]);
+        }
+    }
+
+    /// Render the next queued tile, and return whether more tiles are queued.
+    ///
+    /// This is meant to be called while the viewer is idle, for example once per idle
+    /// callback. Prefetching never evicts cached tiles: once the cache is full, the
+    /// remaining queue is dropped.
+    pub fn prefetch_step(&mut self, render: impl FnOnce(&TileRequest) -> T) -> bool {
+        if self.entries.len() >= self.max_tiles {
+            self.prefetch_queue.clear();
+            return false;
+        }
+
+        if let Some(request) = self.prefetch_queue.pop_front()
+            && !self.entries.contains_key(&request.key)
+        {
+            let tile = render(&request);
+            self.insert(request.key, tile);
+        }
+
+        !self.prefetch_queue.is_empty()
+    }
+
+    /// Return the number of tiles that are queued for prefetching.
+    pub fn pending_prefetches(&self) -> usize {
+        self.prefetch_queue.len()
+    }
+
+    /// Remove all cached and queued tiles of the given page, for example because its content
+    /// changed.
+    ///
+    /// Returns whether any tiles were removed.
+    pub fn invalidate_page(&mut self, page: usize) -> bool {
+        let len = self.entries.len() + self.prefetch_queue.len();
+        self.entries.retain(|key, _| key.page != page);
+        self.prefetch_queue
+            .retain(|request| request.key.page != page);
+
+        self.entries.len() + self.prefetch_queue.len() != len
+    }
+
+    /// Remove the cached and queued tiles of the given page that intersect `area`, which is
+    /// in page coordinates.
+    ///
+    /// Returns whether any tiles were removed.
+    pub fn invalidate_area(&mut self, page: usize, area: Rect) -> bool {
+        let len = self.entries.len() + self.prefetch_queue.len();
+        let tile_size = f64::from(self.tile_size) / self.scale();
+        let overlaps = |key: &TileKey| {
+            let x0 = f64::from(key.col) * tile_size;
+            let y0 = f64::from(key.row) * tile_size;
+            key.page == page
+                && x0 < area.x1
+                && x0 + tile_size > area.x0
+                && y0 < area.y1
+                && y0 + tile_size > area.y0
+        };
+        self.entries.retain(|key, _| !overlaps(key));
+        self.prefetch_queue
+            .retain(|request| !overlaps(&request.key));
+
+        self.entries.len() + self.prefetch_queue.len() != len
+    }
+
+    /// Evict the least recently used tiles until at most `max_tiles` remain.
+    pub fn trim(&mut self, max_tiles: usize) {
+        while self.entries.len() > max_tiles {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| *key)
+                .unwrap();
+            self.entries.remove(&oldest);
+        }
+    }
+
+    /// Return the number of cached tiles.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove all cached and queued tiles.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.prefetch_queue.clear();
+    }
+
+    fn insert(&mut self, key: TileKey, tile: T) {
+        self.epoch += 1;
+        self.entries.insert(
+            key,
+            CachedTile {
+                tile,
+                last_used: self.epoch,
+            },
+        );
+    }
+}
+
+/// Return the zoom bucket of `zoom`, which is the smallest level whose scale is not smaller
+/// than `zoom`.
+fn zoom_level(zoom: f64) -> i32 {
+    // Allow for a bit of imprecision, so that zooming to exactly 2 doesn't jump to 4.
+    (zoom.log2() - 1e-9).ceil() as i32
+}
+
+fn level_scale(level: i32) -> f64 {
+    2_f64.powi(level)
+}
+
+/// Return the range of tiles along one axis that overlap `start..end` in page coordinates.
+fn tile_range(start: f64, end: f64, scale: f64, tile_size: f64, page_px: f64) -> Range<u32> {
+    let last = (page_px / tile_size).ceil();
+    let first_tile = (start * scale / tile_size).floor().clamp(0.0, last);
+    let end_tile = (end * scale / tile_size).ceil().clamp(0.0, last);
+
+    first_tile as u32..end_tile as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: Size = Size::new(100.0, 150.0);
+
+    #[test]
+    fn zoom_buckets_round_up_to_powers_of_two() {
+        assert_eq!(zoom_level(1.0), 0);
+        assert_eq!(zoom_level(1.3), 1);
+        assert_eq!(zoom_level(2.0), 1);
+        assert_eq!(zoom_level(0.3), -1);
+
+        let mut cache = DocumentTileCache::<u32>::new(64, 16);
+        assert!(!cache.set_zoom(1.0));
+        assert!(cache.set_zoom(1.5));
+        assert_eq!(cache.scale(), 2.0);
+        assert!(!cache.set_zoom(1.8));
+        assert!(!cache.set_zoom(f64::NAN));
+    }
+
+    #[test]
+    fn tiles_cover_visible_part_of_page() {
+        let mut cache = DocumentTileCache::<u32>::new(64, 16);
+        cache.set_zoom(2.0);
+
+        // At scale 2, the page is 200x300 pixels, so it has 4x5 tiles.
+        let all = cache.tiles(0, PAGE, PAGE.to_rect());
+        assert_eq!(all.len(), 20);
+        let last = all.last().unwrap();
+        assert_eq!((last.key.col, last.key.row), (3, 4));
+        assert_eq!((last.width, last.height), (8, 44));
+        assert_eq!(last.bounds, Rect::new(96.0, 128.0, 100.0, 150.0));
+        assert_eq!(
+            last.transform * last.bounds.origin(),
+            crate::kurbo::Point::ZERO
+        );
+
+        let visible = cache.tiles(0, PAGE, Rect::new(40.0, -10.0, 60.0, 40.0));
+        let keys: Vec<_> = visible.iter().map(|r| (r.key.col, r.key.row)).collect();
+        assert_eq!(keys, [(1, 0), (1, 1)]);
+
+        assert!(
+            cache
+                .tiles(0, PAGE, Rect::new(200.0, 0.0, 300.0, 10.0))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn zoom_bucket_change_invalidates_tiles() {
+        let mut cache = DocumentTileCache::new(64, 16);
+        for request in cache.tiles(0, PAGE, PAGE.to_rect()) {
+            cache.get_or_render(&request, |_| 1);
+        }
+        assert_eq!(cache.len(), 6);
+
+        let request = cache.tiles(0, PAGE, PAGE.to_rect())[0];
+        assert_eq!(
+            cache.get_or_render(&request, |_| panic!("tile should be cached")),
+            1
+        );
+
+        cache.set_zoom(0.9);
+        assert_eq!(cache.len(), 6);
+        cache.set_zoom(3.0);
+        assert!(cache.is_empty());
+        assert!(cache.get(&request.key).is_none());
+    }
+
+    #[test]
+    fn prefetch_renders_adjacent_pages_until_full() {
+        let pages = [PAGE; 4];
+        let mut cache = DocumentTileCache::new(64, 8);
+
+        cache.queue_adjacent_pages(&pages, 1..2);
+        assert_eq!(cache.pending_prefetches(), 12);
+        cache.queue_page(2, PAGE);
+        assert_eq!(cache.pending_prefetches(), 12);
+
+        let mut rendered = Vec::new();
+        while cache.prefetch_step(|request| {
+            rendered.push(request.key.page);
+            request.key.page
+        }) {}
+
+        assert_eq!(rendered, [2, 2, 2, 2, 2, 2, 0, 0]);
+        assert_eq!(cache.len(), 8);
+        assert_eq!(cache.pending_prefetches(), 0);
+
+        assert!(cache.invalidate_page(2));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn invalidate_area_only_removes_overlapping_tiles() {
+        let mut cache = DocumentTileCache::new(64, 32);
+        cache.set_zoom(2.0);
+        for request in cache.tiles(0, PAGE, PAGE.to_rect()) {
+            cache.get_or_render(&request, |_| 1);
+        }
+        assert_eq!(cache.len(), 20);
+
+        // At scale 2, tiles cover 32x32 units of the page.
+        assert!(cache.invalidate_area(0, Rect::new(40.0, 10.0, 70.0, 20.0)));
+        assert_eq!(cache.len(), 18);
+        assert!(!cache.invalidate_area(1, PAGE.to_rect()));
+        assert!(!cache.invalidate_area(0, Rect::new(40.0, 10.0, 60.0, 20.0)));
+    }
+}
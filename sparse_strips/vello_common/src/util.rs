@@ -4,6 +4,7 @@
 //! Utility functions.
 
 use crate::math::FloatExt;
+use crate::simd::{ByteOrder, narrow_u32_bytes_to_u8};
 use fearless_simd::{
     Bytes, Simd, SimdBase, SimdFloat, f32x16, u8x16, u8x32, u16x16, u16x32, u32x16,
 };
@@ -19,16 +20,8 @@ use peniko::kurbo::common::FloatFuncs as _;
 // https://github.com/linebender/fearless_simd/blob/3f4489389940b7c3c6ee1847a2d007a22494eeff/fearless_simd/src/generated/simd_types.rs#L1623
 #[inline(always)]
 pub fn f32_to_u8<S: Simd>(val: f32x16<S>) -> u8x16<S> {
-    let simd = val.simd;
     let converted = val.to_int::<u32x16<S>>().to_bytes();
-
-    let (x8_1, x8_2) = simd.split_u8x64(converted);
-    let (p1, p2) = simd.split_u8x32(x8_1);
-    let (p3, p4) = simd.split_u8x32(x8_2);
-
-    let uzp1 = simd.unzip_low_u8x16(p1, p2);
-    let uzp2 = simd.unzip_low_u8x16(p3, p4);
-    simd.unzip_low_u8x16(uzp1, uzp2)
+    narrow_u32_bytes_to_u8(converted, ByteOrder::NATIVE)
 }
 
 /// A trait for implementing a fast approximal division by 255 for integers.
@@ -0,0 +1,302 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Strokes whose width varies along the path.
+//!
+//! A [`VariableWidthStroke`] is a polyline where every point carries its own stroke width,
+//! for example the pressure samples of a stylus. It is expanded into a path that is filled
+//! with the non-zero fill rule, with round caps and joins.
+
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+use crate::kurbo::{Arc, BezPath, PathEl, Point, Vec2};
+#[cfg(not(feature = "std"))]
+use peniko::kurbo::common::FloatFuncs as _;
+
+/// A point of a [`VariableWidthStroke`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WidthSample {
+    /// The position of the sample.
+    pub point: Point,
+    /// The width of the stroke at the sample.
+    pub width: f64,
+}
+
+/// A stroke with a width for every point.
+///
+/// Like a [`BezPath`], the stroke consists of subpaths which are started with
+/// [`move_to`](Self::move_to). Between two samples, the outline of the stroke interpolates
+/// linearly between their widths.
+#[derive(Clone, Debug, Default)]
+pub struct VariableWidthStroke {
+    samples: Vec<WidthSample>,
+    /// The index of the first sample of each subpath.
+    subpaths: Vec<usize>,
+}
+
+impl VariableWidthStroke {
+    /// Create an empty stroke.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a stroke following `path`, with the width given by a profile function.
+    ///
+    /// The path is flattened with the given tolerance, and `profile` is called with the
+    /// fraction of the length of the whole path (in the range `[0, 1]`) at every point to get
+    /// the width at that point.
+    pub fn from_profile(
+        path: impl IntoIterator<Item = PathEl>,
+        tolerance: f64,
+        mut profile: impl FnMut(f64) -> f64,
+    ) -> Self {
+        let mut flattened = Vec::new();
+        crate::kurbo::flatten(path, tolerance, |el| flattened.push(el));
+
+        let mut total = 0.0;
+        let mut start = Point::ZERO;
+        let mut last = Point::ZERO;
+        for el in &flattened {
+            match *el {
+                PathEl::MoveTo(p) => start = p,
+                PathEl::LineTo(p) => total += (p - last).hypot(),
+                PathEl::ClosePath => total += (start - last).hypot(),
+                _ => unreachable!("flattened paths only consist of lines"),
+            }
+            last = el.end_point().unwrap_or(start);
+        }
+
+        let mut stroke = Self::new();
+        let mut length = 0.0;
+        let t = |length: f64| if total > 0.0 { length / total } else { 0.0 };
+        for el in flattened {
+            match el {
+                PathEl::MoveTo(p) => {
+                    stroke.move_to(p, profile(t(length)));
+                    start = p;
+                }
+                PathEl::LineTo(p) => {
+                    length += (p - last).hypot();
+                    stroke.line_to(p, profile(t(length)));
+                }
+                PathEl::ClosePath => {
+                    length += (start - last).hypot();
+                    stroke.line_to(start, profile(t(length)));
+                }
+                _ => unreachable!("flattened paths only consist of lines"),
+            }
+            last = el.end_point().unwrap_or(start);
+        }
+
+        stroke
+    }
+
+    /// Start a new subpath at `point`.
+    pub fn move_to(&mut self, point: impl Into<Point>, width: f64) {
+        self.subpaths.push(self.samples.len());
+        self.samples.push(WidthSample {
+            point: point.into(),
+            width,
+        });
+    }
+
+    /// Add a sample to the current subpath.
+    ///
+    /// If there is no current subpath, this starts a new one.
+    pub fn line_to(&mut self, point: impl Into<Point>, width: f64) {
+        if self.subpaths.is_empty() {
+            self.move_to(point, width);
+        } else {
+            self.samples.push(WidthSample {
+                point: point.into(),
+                width,
+            });
+        }
+    }
+
+    /// Remove all samples.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.subpaths.clear();
+    }
+
+    /// Whether the stroke has no samples.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// The samples of all subpaths.
+    pub fn samples(&self) -> &[WidthSample] {
+        &self.samples
+    }
+
+    /// Expand the stroke into a path to be filled with the non-zero fill rule, replacing the
+    /// contents of `out`.
+    ///
+    /// The outline is the union of the outlines of all segments, each of which is the convex
+    /// hull of the discs around its two samples. The round caps and joins are approximated
+    /// with the given tolerance.
+    pub fn expand(&self, tolerance: f64, out: &mut BezPath) {
+        out.truncate(0);
+
+        for (i, &start) in self.subpaths.iter().enumerate() {
+            let end = self
+                .subpaths
+                .get(i + 1)
+                .copied()
+                .unwrap_or(self.samples.len());
+            let samples = &self.samples[start..end];
+
+            if let [sample] = samples {
+                push_disc(out, sample.point, radius(sample), tolerance);
+            }
+            for pair in samples.windows(2) {
+                push_segment(out, &pair[0], &pair[1], tolerance);
+            }
+        }
+    }
+}
+
+fn radius(sample: &WidthSample) -> f64 {
+    if sample.width.is_finite() {
+        0.5 * sample.width.max(0.0)
+    } else {
+        0.0
+    }
+}
+
+/// Append the convex hull of the discs around two samples.
+///
+/// All outlines are oriented the same way, so that they add up with the non-zero fill rule.
+fn push_segment(out: &mut BezPath, s0: &WidthSample, s1: &WidthSample, tolerance: f64) {
+    let (r0, r1) = (radius(s0), radius(s1));
+    let d = s1.point - s0.point;
+    let len = d.hypot();
+    if !len.is_finite() {
+        return;
+    }
+    if len <= (r0 - r1).abs() {
+        // One disc contains the other one.
+        if r0 >= r1 {
+            push_disc(out, s0.point, r0, tolerance);
+        } else {
+            push_disc(out, s1.point, r1, tolerance);
+        }
+        return;
+    }
+
+    // The outer tangents touch the discs at the angles `±phi` relative to the direction of the
+    // segment.
+    let dir = d.atan2();
+    let sin = (r0 - r1) / len;
+    let phi = Vec2::new(sin, (1.0 - sin * sin).max(0.0).sqrt()).atan2();
+
+    out.move_to(s0.point + r0 * Vec2::from_angle(dir + phi));
+    out.line_to(s1.point + r1 * Vec2::from_angle(dir + phi));
+    push_arc(out, s1.point, r1, dir + phi, -2.0 * phi, tolerance);
+    out.line_to(s0.point + r0 * Vec2::from_angle(dir - phi));
+    push_arc(out, s0.point, r0, dir - phi, -2.0 * (PI - phi), tolerance);
+    out.close_path();
+}
+
+fn push_disc(out: &mut BezPath, center: Point, radius: f64, tolerance: f64) {
+    if radius > 0.0 {
+        out.move_to(center + Vec2::new(radius, 0.0));
+        push_arc(out, center, radius, 0.0, -2.0 * PI, tolerance);
+        out.close_path();
+    }
+}
+
+fn push_arc(
+    out: &mut BezPath,
+    center: Point,
+    radius: f64,
+    start_angle: f64,
+    sweep_angle: f64,
+    tolerance: f64,
+) {
+    if radius > 0.0 {
+        let arc = Arc::new(center, (radius, radius), start_angle, sweep_angle, 0.0);
+        out.extend(arc.append_iter(tolerance));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kurbo::{Line, Shape};
+
+    fn expand(stroke: &VariableWidthStroke) -> BezPath {
+        let mut out = BezPath::new();
+        stroke.expand(0.01, &mut out);
+        out
+    }
+
+    #[test]
+    fn segments_are_capsules() {
+        let mut stroke = VariableWidthStroke::new();
+        stroke.move_to((0.0, 0.0), 2.0);
+        stroke.line_to((10.0, 0.0), 2.0);
+
+        let area = expand(&stroke).area().abs();
+        assert!((area - (20.0 + PI)).abs() < 0.01, "{area}");
+    }
+
+    #[test]
+    fn outlines_share_the_orientation() {
+        let mut stroke = VariableWidthStroke::new();
+        stroke.move_to((0.0, 0.0), 2.0);
+        stroke.line_to((10.0, 0.0), 6.0);
+        stroke.line_to((10.0, 10.0), 0.0);
+        stroke.move_to((-20.0, 0.0), 4.0);
+        stroke.line_to((-20.0, 1.0), 8.0);
+
+        let path = expand(&stroke);
+        let mut subpath = BezPath::new();
+        let mut signs = Vec::new();
+        for el in path.elements() {
+            if let PathEl::MoveTo(_) = el
+                && !subpath.is_empty()
+            {
+                signs.push(subpath.area().signum());
+                subpath.truncate(0);
+            }
+            subpath.push(*el);
+        }
+        signs.push(subpath.area().signum());
+
+        // Three segments, one of which is a single disc since it contains the other sample.
+        assert_eq!(signs.len(), 3);
+        assert!(signs.iter().all(|sign| *sign == signs[0]));
+    }
+
+    #[test]
+    fn single_samples_are_dots() {
+        let mut stroke = VariableWidthStroke::new();
+        stroke.line_to((5.0, 5.0), 4.0);
+        assert_eq!(stroke.samples().len(), 1);
+
+        let area = expand(&stroke).area().abs();
+        assert!((area - 4.0 * PI).abs() < 0.01, "{area}");
+    }
+
+    #[test]
+    fn profile_follows_the_length() {
+        let path = Line::new((0.0, 0.0), (10.0, 0.0)).path_elements(0.1);
+        let stroke = VariableWidthStroke::from_profile(path, 0.1, |t| 1.0 + t);
+        assert_eq!(
+            stroke.samples(),
+            [
+                WidthSample {
+                    point: Point::new(0.0, 0.0),
+                    width: 1.0
+                },
+                WidthSample {
+                    point: Point::new(10.0, 0.0),
+                    width: 2.0
+                },
+            ]
+        );
+    }
+}
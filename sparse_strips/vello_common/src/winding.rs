@@ -0,0 +1,216 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Winding number visualization for debugging fills.
+//!
+//! The renderers only ever see the coverage of a path after the fill rule has been applied,
+//! which makes it hard to tell why a self-intersecting path fills unexpectedly.
+//! [`winding_numbers`] computes the raw winding number at the center of every pixel instead,
+//! and [`render_winding_numbers`] turns them into a false-color image, with clockwise
+//! windings in warm colors and counter-clockwise windings in cool colors.
+
+use alloc::vec::Vec;
+
+use crate::flatten::TOL;
+use crate::kurbo::{self, Affine, PathEl, Point};
+use crate::peniko::color::PremulRgba8;
+use crate::pixmap::Pixmap;
+#[cfg(not(feature = "std"))]
+use peniko::kurbo::common::FloatFuncs as _;
+
+/// Colors for the winding numbers 1, 2, 3 and 4 or more.
+const POSITIVE_COLORS: [[u8; 3]; 4] = [
+    [230, 80, 60],
+    [245, 160, 40],
+    [250, 230, 70],
+    [255, 255, 255],
+];
+
+/// Colors for the winding numbers -1, -2, -3 and -4 or less.
+const NEGATIVE_COLORS: [[u8; 3]; 4] = [
+    [60, 110, 230],
+    [40, 190, 220],
+    [90, 220, 130],
+    [200, 255, 200],
+];
+
+/// Compute the winding number of `path` at the center of each pixel of a `width` by `height`
+/// area, after applying `transform`.
+///
+/// The results are written to `out` in row-major order. Subpaths are closed implicitly, like
+/// when filling. In the y-down coordinate system of the renderers, clockwise subpaths have a
+/// positive and counter-clockwise subpaths a negative winding number.
+pub fn winding_numbers(
+    path: impl IntoIterator<Item = PathEl>,
+    transform: Affine,
+    width: u16,
+    height: u16,
+    out: &mut Vec<i32>,
+) {
+    let width = usize::from(width);
+    let height = usize::from(height);
+    out.clear();
+    // Each row has an extra column for crossings right of the area.
+    out.resize((width + 1) * height, 0);
+
+    let mut add_line = |p0: Point, p1: Point| {
+        if p0.y == p1.y {
+            return;
+        }
+        let (top, bottom, delta) = if p0.y < p1.y {
+            (p0, p1, -1)
+        } else {
+            (p1, p0, 1)
+        };
+
+        // The rows whose pixel centers lie in `[top.y, bottom.y)`.
+        let start = (top.y - 0.5).ceil().max(0.0) as usize;
+        let end = ((bottom.y - 0.5).ceil().max(0.0) as usize).min(height);
+        let slope = (bottom.x - top.x) / (bottom.y - top.y);
+        for row in start..end {
+            let y = row as f64 + 0.5;
+            let x = top.x + (y - top.y) * slope;
+            // The first column whose pixel center lies right of the crossing.
+            let column = ((x - 0.5).ceil().max(0.0) as usize).min(width);
+            out[row * (width + 1) + column] += delta;
+        }
+    };
+
+    let mut start = Point::ZERO;
+    let mut last = Point::ZERO;
+    kurbo::flatten(path.into_iter().map(|el| transform * el), TOL, |el| {
+        match el {
+            PathEl::MoveTo(p) => {
+                add_line(last, start);
+                start = p;
+                last = p;
+            }
+            PathEl::LineTo(p) => {
+                add_line(last, p);
+                last = p;
+            }
+            PathEl::ClosePath => {
+                add_line(last, start);
+                last = start;
+            }
+            // `flatten` only emits move, line and close elements.
+            PathEl::QuadTo(..) | PathEl::CurveTo(..) => unreachable!(),
+        }
+    });
+    add_line(last, start);
+
+    // Accumulate the crossings along each row and drop the extra column.
+    let mut idx = 0;
+    for row in 0..height {
+        let mut winding = 0;
+        for column in 0..width {
+            winding += out[row * (width + 1) + column];
+            out[idx] = winding;
+            idx += 1;
+        }
+    }
+    out.truncate(width * height);
+}
+
+/// Return the false color used for a winding number.
+///
+/// A winding number of zero is transparent.
+pub fn winding_color(winding: i32) -> PremulRgba8 {
+    let colors = if winding > 0 {
+        &POSITIVE_COLORS
+    } else {
+        &NEGATIVE_COLORS
+    };
+    match winding.unsigned_abs() {
+        0 => PremulRgba8::from_u32(0),
+        n => {
+            let [r, g, b] = colors[(n as usize).min(colors.len()) - 1];
+            PremulRgba8 { r, g, b, a: 255 }
+        }
+    }
+}
+
+/// Draw the winding numbers of `path` into `pixmap` as a false-color image.
+///
+/// See [`winding_numbers`] and [`winding_color`].
+pub fn render_winding_numbers(
+    path: impl IntoIterator<Item = PathEl>,
+    transform: Affine,
+    pixmap: &mut Pixmap,
+) {
+    let mut windings = Vec::new();
+    winding_numbers(
+        path,
+        transform,
+        pixmap.width(),
+        pixmap.height(),
+        &mut windings,
+    );
+    for (pixel, winding) in pixmap.data_mut().iter_mut().zip(windings) {
+        *pixel = winding_color(winding);
+    }
+    pixmap.set_may_have_transparency(true);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_winding_numbers, winding_color, winding_numbers};
+    use crate::kurbo::{Affine, BezPath, Circle, Rect, Shape};
+    use crate::pixmap::Pixmap;
+    use alloc::vec::Vec;
+
+    fn windings(path: &BezPath) -> Vec<i32> {
+        let mut out = Vec::new();
+        winding_numbers(path, Affine::IDENTITY, 10, 10, &mut out);
+        out
+    }
+
+    #[test]
+    fn overlapping_subpaths_add_up() {
+        let mut path = Rect::new(1.0, 1.0, 6.0, 6.0).to_path(0.1);
+        path.extend(Rect::new(4.0, 4.0, 9.0, 9.0).to_path(0.1));
+        let out = windings(&path);
+
+        assert_eq!(out[0], 0);
+        assert_eq!(out[2 * 10 + 2], 1);
+        assert_eq!(out[5 * 10 + 5], 2);
+        assert_eq!(out[8 * 10 + 8], 1);
+        assert_eq!(out[9 * 10 + 9], 0);
+    }
+
+    #[test]
+    fn reversed_subpaths_are_negative() {
+        let mut path = Circle::new((5.0, 5.0), 4.5).to_path(0.1);
+        path.extend(
+            Rect::new(3.0, 3.0, 7.0, 7.0)
+                .to_path(0.1)
+                .reverse_subpaths(),
+        );
+        let out = windings(&path);
+
+        assert_eq!(out[5 * 10 + 1], 1);
+        assert_eq!(out[5 * 10 + 5], 0);
+
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        path.line_to((0.0, 10.0));
+        path.line_to((10.0, 10.0));
+        // The path is closed implicitly.
+        assert!(windings(&path).iter().all(|w| *w <= 0));
+        assert_eq!(windings(&path)[9 * 10 + 1], -1);
+    }
+
+    #[test]
+    fn windings_are_drawn_in_false_colors() {
+        let mut path = Rect::new(0.0, 0.0, 4.0, 4.0).to_path(0.1);
+        path.extend(Rect::new(0.0, 0.0, 2.0, 2.0).to_path(0.1));
+        let mut pixmap = Pixmap::new(4, 4);
+        render_winding_numbers(&path, Affine::IDENTITY, &mut pixmap);
+
+        assert_eq!(pixmap.sample(0, 0), winding_color(2));
+        assert_eq!(pixmap.sample(3, 3), winding_color(1));
+        assert_ne!(winding_color(1), winding_color(-1));
+        assert_eq!(winding_color(0).a, 0);
+        assert_eq!(winding_color(7), winding_color(4));
+    }
+}
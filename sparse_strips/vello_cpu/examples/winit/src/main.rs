@@ -10,12 +10,12 @@
 
 #[cfg(not(target_arch = "wasm32"))]
 use std::env;
-use std::num::NonZeroU32;
 use std::rc::Rc;
 use std::time::Instant;
 use vello_common::kurbo::{Affine, Point};
 use vello_common::paint::ImageSource;
 use vello_common::pixmap::Pixmap;
+use vello_cpu::present::present_pixmap;
 use vello_cpu::{RenderContext, RenderSettings};
 use vello_example_scenes::image::ImageScene;
 use vello_example_scenes::{AnyScene, Capabilities, get_example_scenes};
@@ -203,13 +203,6 @@ impl ApplicationHandler for App {
                 let width = size.width.max(1);
                 let height = size.height.max(1);
 
-                surface
-                    .resize(
-                        NonZeroU32::new(width).unwrap(),
-                        NonZeroU32::new(height).unwrap(),
-                    )
-                    .unwrap();
-
                 self.pixmap.resize(width as u16, height as u16);
                 self.renderer = RenderContext::new_with(
                     width as u16,
@@ -462,18 +455,7 @@ impl ApplicationHandler for App {
                     &mut self.pixmap,
                 );
 
-                // Copy pixmap to window surface
-                let mut buffer = surface.buffer_mut().unwrap();
-                let pixmap_data = self.pixmap.data();
-
-                // Convert RGBA to BGRA/XRGB format expected by softbuffer
-                for (buffer_pixel, pixel) in buffer.iter_mut().zip(pixmap_data.iter()) {
-                    // softbuffer expects 0RGB format (little-endian: B, G, R, 0)
-                    // Our pixmap is premultiplied RGBA
-                    *buffer_pixel = u32::from_le_bytes([pixel.b, pixel.g, pixel.r, 0]);
-                }
-
-                buffer.present().unwrap();
+                present_pixmap(surface, &self.pixmap, None).unwrap();
 
                 // Request continuous redraw for FPS measurement
                 if self.rotating || self.shearing {
@@ -0,0 +1,155 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! A minimal EXR encoder.
+//!
+//! Writes single-part, uncompressed scanline images with 32-bit float RGBA channels. Pixels
+//! are stored premultiplied and in linear sRGB, as is conventional for EXR files.
+
+use vello_cpu::Pixmap;
+use vello_cpu::color::ColorSpaceTag;
+
+const MAGIC: [u8; 4] = [0x76, 0x2f, 0x31, 0x01];
+const VERSION: u32 = 2;
+// EXR stores channels sorted by name.
+const CHANNELS: [&str; 4] = ["A", "B", "G", "R"];
+const PIXEL_TYPE_FLOAT: i32 = 2;
+
+/// Encode `pixmap` as an EXR image.
+pub(crate) fn encode(pixmap: &Pixmap) -> Vec<u8> {
+    let width = i32::from(pixmap.width());
+    let height = i32::from(pixmap.height());
+    let mut out = Vec::new();
+
+    out.extend_from_slice(&MAGIC);
+    out.extend_from_slice(&VERSION.to_le_bytes());
+
+    let mut channels = Vec::new();
+    for name in CHANNELS {
+        channels.extend_from_slice(name.as_bytes());
+        channels.push(0);
+        channels.extend_from_slice(&PIXEL_TYPE_FLOAT.to_le_bytes());
+        // `pLinear` and three reserved bytes.
+        channels.extend_from_slice(&[0; 4]);
+        // x and y sampling.
+        channels.extend_from_slice(&1_i32.to_le_bytes());
+        channels.extend_from_slice(&1_i32.to_le_bytes());
+    }
+    channels.push(0);
+
+    let window = [0, 0, width - 1, height - 1]
+        .iter()
+        .flat_map(|v: &i32| v.to_le_bytes())
+        .collect::<Vec<_>>();
+
+    attribute(&mut out, "channels", "chlist", &channels);
+    // No compression.
+    attribute(&mut out, "compression", "compression", &[0]);
+    attribute(&mut out, "dataWindow", "box2i", &window);
+    attribute(&mut out, "displayWindow", "box2i", &window);
+    // Increasing y.
+    attribute(&mut out, "lineOrder", "lineOrder", &[0]);
+    attribute(
+        &mut out,
+        "pixelAspectRatio",
+        "float",
+        &1.0_f32.to_le_bytes(),
+    );
+    attribute(&mut out, "screenWindowCenter", "v2f", &[0; 8]);
+    attribute(
+        &mut out,
+        "screenWindowWidth",
+        "float",
+        &1.0_f32.to_le_bytes(),
+    );
+    out.push(0);
+
+    let row_size = usize::from(pixmap.width()) * CHANNELS.len() * 4;
+    // Each block starts with its y coordinate and the size of the pixel data.
+    let block_size = 8 + row_size;
+    let table_end = out.len() + usize::from(pixmap.height()) * 8;
+
+    for y in 0..usize::from(pixmap.height()) {
+        let offset = (table_end + y * block_size) as u64;
+        out.extend_from_slice(&offset.to_le_bytes());
+    }
+
+    let row_size_field = i32::try_from(row_size).expect("row size fits into an i32");
+    let linear = pixmap.data().iter().map(|pixel| {
+        let alpha = f32::from(pixel.a) / 255.0;
+        if pixel.a == 0 {
+            return [0.0; 4];
+        }
+        let unpremultiply = |component: u8| f32::from(component) / 255.0 / alpha;
+        let [r, g, b] = ColorSpaceTag::Srgb.convert(
+            ColorSpaceTag::LinearSrgb,
+            [
+                unpremultiply(pixel.r),
+                unpremultiply(pixel.g),
+                unpremultiply(pixel.b),
+            ],
+        );
+        // Same order as `CHANNELS`.
+        [alpha, b * alpha, g * alpha, r * alpha]
+    });
+    let linear = linear.collect::<Vec<_>>();
+
+    for (y, row) in (0..height).zip(linear.chunks(usize::from(pixmap.width()))) {
+        out.extend_from_slice(&y.to_le_bytes());
+        out.extend_from_slice(&row_size_field.to_le_bytes());
+
+        for channel in 0..CHANNELS.len() {
+            for pixel in row {
+                out.extend_from_slice(&pixel[channel].to_le_bytes());
+            }
+        }
+    }
+
+    out
+}
+
+fn attribute(out: &mut Vec<u8>, name: &str, ty: &str, value: &[u8]) {
+    out.extend_from_slice(name.as_bytes());
+    out.push(0);
+    out.extend_from_slice(ty.as_bytes());
+    out.push(0);
+    let size = i32::try_from(value.len()).expect("attribute size fits into an i32");
+    out.extend_from_slice(&size.to_le_bytes());
+    out.extend_from_slice(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vello_cpu::color::PremulRgba8;
+
+    #[test]
+    fn encodes_linear_premultiplied_scanlines() {
+        let pixel = PremulRgba8 {
+            r: 128,
+            g: 0,
+            b: 255,
+            a: 255,
+        };
+        let pixmap = Pixmap::from_parts(vec![pixel; 6], 3, 2);
+        let exr = encode(&pixmap);
+
+        assert_eq!(exr[..4], MAGIC);
+
+        // Each scanline holds 3 pixels with 4 float channels, preceded by 8 bytes.
+        let block_size = 8 + 3 * 4 * 4;
+        let first_block = exr.len() - 2 * block_size;
+        let offset = u64::from_le_bytes(exr[first_block - 16..first_block - 8].try_into().unwrap());
+        assert_eq!(offset, first_block as u64);
+
+        let float_at = |idx: usize| {
+            let start = first_block + 8 + idx * 4;
+            f32::from_le_bytes(exr[start..start + 4].try_into().unwrap())
+        };
+        // Alpha, followed by blue of the first pixel.
+        assert_eq!(float_at(0), 1.0);
+        assert_eq!(float_at(3), 1.0);
+        // sRGB 128 is roughly linear 0.216.
+        assert!((float_at(9) - 0.216).abs() < 0.001, "{}", float_at(9));
+    }
+}
@@ -0,0 +1,247 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Render an SVG file or a replay log to a PNG or EXR image using Vello CPU.
+//!
+//! This is both a debugging tool and a small reference for headless rendering with Vello CPU:
+//! create a [`RenderContext`], issue draw calls, flush, and render into a [`Pixmap`].
+//!
+//! ```text
+//! vello-render input.svg -o output.png --width 512
+//! vello-render bug-report.replay -o output.png
+//! ```
+//!
+//! SVG support is limited to what [`PicoSvg`] understands, which is fills and strokes of paths
+//! with solid colors, nested in groups with transforms.
+//!
+//! Files with a `.replay` extension are parsed as a [`ReplayLog`] and rendered at the size they
+//! were recorded at.
+
+mod exr;
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, ValueEnum};
+use vello_common::pico_svg::{Item, PicoSvg};
+use vello_cpu::color::{Srgb, parse_color};
+use vello_cpu::kurbo::{Affine, Rect, Size, Stroke};
+use vello_cpu::preview::fit_transform;
+use vello_cpu::replay::ReplayLog;
+use vello_cpu::{Level, Pixmap, RenderContext, RenderMode, RenderSettings, Resources};
+
+#[derive(Parser, Debug)]
+#[command(name = "vello-render", version, about)]
+struct Args {
+    /// The SVG file or replay log to render.
+    input: PathBuf,
+    /// The file to write the image to.
+    #[arg(short, long)]
+    output: PathBuf,
+    /// The output format. Inferred from the output file extension if not set.
+    #[arg(short, long)]
+    format: Option<Format>,
+    /// The width of the image in pixels. Defaults to the width of the SVG, or to the width that
+    /// preserves its aspect ratio if only `--height` is set.
+    #[arg(long)]
+    width: Option<u16>,
+    /// The height of the image in pixels. Defaults to the height of the SVG, or to the height
+    /// that preserves its aspect ratio if only `--width` is set.
+    #[arg(long)]
+    height: Option<u16>,
+    /// A CSS color to fill the background with before rendering the SVG.
+    #[arg(long)]
+    background: Option<String>,
+    /// Use the slower, more accurate f32 pipeline.
+    #[arg(long)]
+    high_quality: bool,
+    /// Only use the scalar fallback instead of SIMD, which can help with reproducing results
+    /// across machines.
+    #[arg(long)]
+    no_simd: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Png,
+    Exr,
+}
+
+impl Format {
+    fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "png" => Some(Self::Png),
+            "exr" => Some(Self::Exr),
+            _ => None,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("vello-render: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    let format = match args.format {
+        Some(format) => format,
+        None => Format::from_path(&args.output).ok_or_else(|| {
+            format!(
+                "can't infer the image format from `{}`, use `--format`",
+                args.output.display()
+            )
+        })?,
+    };
+    let background = args
+        .background
+        .as_deref()
+        .map(|color| parse_color(color).map(|c| c.to_alpha_color::<Srgb>()))
+        .transpose()?;
+
+    let input = std::fs::read_to_string(&args.input)?;
+    let is_replay = args
+        .input
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("replay"));
+
+    let settings = RenderSettings {
+        level: if args.no_simd {
+            Level::baseline()
+        } else {
+            Level::new()
+        },
+        num_threads: 0,
+        render_mode: if args.high_quality {
+            RenderMode::OptimizeQuality
+        } else {
+            RenderMode::OptimizeSpeed
+        },
+    };
+    let draw_background = |ctx: &mut RenderContext| {
+        if let Some(background) = background {
+            ctx.set_paint(background);
+            let (width, height) = (ctx.width(), ctx.height());
+            ctx.fill_rect(&Rect::new(0.0, 0.0, f64::from(width), f64::from(height)));
+        }
+    };
+
+    let mut ctx = if is_replay {
+        if args.width.is_some() || args.height.is_some() {
+            return Err("replay logs are always rendered at their recorded size".into());
+        }
+        let log = ReplayLog::parse(&input)?;
+        let mut ctx = RenderContext::new_with(log.width(), log.height(), settings);
+        draw_background(&mut ctx);
+        let inexact = log.replay(&mut ctx);
+        if inexact > 0 {
+            eprintln!(
+                "vello-render: {inexact} commands could not be replayed exactly, because their \
+                 payloads were only recorded as a hash"
+            );
+        }
+        ctx
+    } else {
+        let svg = PicoSvg::load(&input, 1.0)?;
+        let (width, height) = output_size(svg.size, args.width, args.height)?;
+        let mut ctx = RenderContext::new_with(width, height, settings);
+        draw_background(&mut ctx);
+        render_items(&mut ctx, &svg.items, fit_transform(svg.size, width, height));
+        ctx
+    };
+    ctx.flush();
+    let mut resources = Resources::new();
+
+    let mut pixmap = Pixmap::new(ctx.width(), ctx.height());
+    ctx.render_to_pixmap(&mut resources, &mut pixmap);
+
+    let data = match format {
+        Format::Png => pixmap.encode_png()?,
+        Format::Exr => exr::encode(&pixmap),
+    };
+    std::fs::write(&args.output, data)?;
+
+    Ok(())
+}
+
+/// Determine the size of the output image from the size of the SVG and the requested
+/// dimensions.
+fn output_size(
+    svg_size: Size,
+    width: Option<u16>,
+    height: Option<u16>,
+) -> Result<(u16, u16), Box<dyn Error>> {
+    let aspect = svg_size.width / svg_size.height;
+    let to_pixels = |v: f64| -> Result<u16, Box<dyn Error>> {
+        let v = v.round();
+        if v >= 1.0 && v <= f64::from(u16::MAX) {
+            #[expect(clippy::cast_possible_truncation, reason = "range was checked above")]
+            Ok(v as u16)
+        } else {
+            Err(format!("invalid image dimension {v}").into())
+        }
+    };
+
+    let size = match (width, height) {
+        (Some(width), Some(height)) => (width, height),
+        (Some(width), None) => (width, to_pixels(f64::from(width) / aspect)?),
+        (None, Some(height)) => (to_pixels(f64::from(height) * aspect)?, height),
+        (None, None) => (to_pixels(svg_size.width)?, to_pixels(svg_size.height)?),
+    };
+
+    if size.0 == 0 || size.1 == 0 {
+        return Err("image dimensions must be non-zero".into());
+    }
+
+    Ok(size)
+}
+
+fn render_items(ctx: &mut RenderContext, items: &[Item], transform: Affine) {
+    for item in items {
+        ctx.set_transform(transform);
+        match item {
+            Item::Fill(fill) => {
+                ctx.set_paint(fill.color);
+                ctx.fill_path(&fill.path);
+            }
+            Item::Stroke(stroke) => {
+                ctx.set_stroke(Stroke::new(stroke.width));
+                ctx.set_paint(stroke.color);
+                ctx.stroke_path(&stroke.path);
+            }
+            Item::Group(group) => {
+                render_items(ctx, &group.children, transform * group.affine);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_size_preserves_aspect_ratio() {
+        let svg_size = Size::new(200.0, 100.0);
+
+        assert_eq!(output_size(svg_size, None, None).unwrap(), (200, 100));
+        assert_eq!(output_size(svg_size, Some(50), None).unwrap(), (50, 25));
+        assert_eq!(output_size(svg_size, None, Some(50)).unwrap(), (100, 50));
+        assert_eq!(output_size(svg_size, Some(10), Some(10)).unwrap(), (10, 10));
+        assert!(output_size(svg_size, Some(0), None).is_err());
+        assert!(output_size(Size::ZERO, None, None).is_err());
+    }
+
+    #[test]
+    fn format_is_inferred_from_extension() {
+        assert_eq!(Format::from_path(Path::new("a.PNG")), Some(Format::Png));
+        assert_eq!(Format::from_path(Path::new("a/b.exr")), Some(Format::Exr));
+        assert_eq!(Format::from_path(Path::new("a.jpg")), None);
+    }
+}
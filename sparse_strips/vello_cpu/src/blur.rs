@@ -0,0 +1,383 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Gaussian and box blurs of pixmap regions.
+//!
+//! Without a GPU, shadows and blurred layers have to be blurred on the CPU. The blurs in this
+//! module work on a rectangular region of a [`Pixmap`] and are separable: the pixels are first
+//! blurred along their rows and then along their columns, with the four channels of each pixel
+//! processed together in one SIMD vector.
+//!
+//! ```rust
+//! use vello_cpu::blur::{EdgeMode, gaussian_blur};
+//! use vello_cpu::{Level, Pixmap, RectU16};
+//!
+//! let level = Level::try_detect().unwrap_or(Level::baseline());
+//! let mut pixmap = Pixmap::new(64, 64);
+//! // Blur the center of the pixmap, fading to transparent at the edges of the region.
+//! let region = RectU16::new(8, 8, 56, 56);
+//! gaussian_blur(&mut pixmap, region, 4.0, EdgeMode::None, level);
+//! ```
+
+use alloc::vec;
+use alloc::vec::Vec;
+use vello_common::fearless_simd::*;
+use vello_common::filter::gaussian_blur::compute_gaussian_kernel;
+use vello_common::geometry::RectU16;
+#[cfg(not(feature = "std"))]
+use vello_common::kurbo::common::FloatFuncs as _;
+use vello_common::peniko::color::PremulRgba8;
+use vello_common::pixmap::Pixmap;
+
+use crate::filter::gaussian_blur::extend;
+
+pub use vello_common::filter_effects::EdgeMode;
+
+/// The standard deviation from which on a Gaussian blur is approximated with three box blurs.
+///
+/// This is the threshold that the filter effects specification suggests for `feGaussianBlur`.
+const BOX_APPROXIMATION_THRESHOLD: f32 = 2.0;
+
+/// Blur the pixels in `region` of `pixmap` with a Gaussian blur.
+///
+/// Only the pixels inside `region`, clipped to the pixmap, are read and written. The pixels
+/// beyond the edges of the region are sampled according to `edge_mode`. Standard deviations of
+/// [two pixels or more](BOX_APPROXIMATION_THRESHOLD) are approximated with three successive
+/// box blurs, the same way as SVG filters do, so that the cost doesn't depend on the size of
+/// the blur.
+pub fn gaussian_blur(
+    pixmap: &mut Pixmap,
+    region: RectU16,
+    std_deviation: f32,
+    edge_mode: EdgeMode,
+    level: Level,
+) {
+    if std_deviation.is_nan() || std_deviation <= 0.0 {
+        return;
+    }
+
+    if std_deviation < BOX_APPROXIMATION_THRESHOLD {
+        let (kernel, kernel_size) = compute_gaussian_kernel(std_deviation);
+        let passes = [Pass::Kernel(&kernel[..usize::from(kernel_size)])];
+        blur(pixmap, region, &passes, edge_mode, level);
+        return;
+    }
+
+    // See <https://drafts.fxtf.org/filter-effects/#feGaussianBlurElement>.
+    let d = (std_deviation * 3.0 * (2.0 * core::f32::consts::PI).sqrt() / 4.0 + 0.5).floor();
+    let d = d as usize;
+    let half = d / 2;
+    let passes = if d % 2 == 1 {
+        [Pass::Box {
+            left: half,
+            right: half,
+        }; 3]
+    } else {
+        // Two boxes of an even size are centered between pixels, one on each side, and the
+        // third box is one pixel wider.
+        [
+            Pass::Box {
+                left: half,
+                right: half - 1,
+            },
+            Pass::Box {
+                left: half - 1,
+                right: half,
+            },
+            Pass::Box {
+                left: half,
+                right: half,
+            },
+        ]
+    };
+    blur(pixmap, region, &passes, edge_mode, level);
+}
+
+/// Blur the pixels in `region` of `pixmap` with a box blur.
+///
+/// Each pixel becomes the average of the square of `2 * radius + 1` pixels around it. Only the
+/// pixels inside `region`, clipped to the pixmap, are read and written, and the pixels beyond
+/// the edges of the region are sampled according to `edge_mode`.
+pub fn box_blur(
+    pixmap: &mut Pixmap,
+    region: RectU16,
+    radius: u16,
+    edge_mode: EdgeMode,
+    level: Level,
+) {
+    if radius == 0 {
+        return;
+    }
+
+    let radius = usize::from(radius);
+    let passes = [Pass::Box {
+        left: radius,
+        right: radius,
+    }];
+    blur(pixmap, region, &passes, edge_mode, level);
+}
+
+/// A one-dimensional blur that is applied along the rows and columns of a region.
+#[derive(Debug, Clone, Copy)]
+enum Pass<'a> {
+    /// A convolution with a kernel of odd length, centered on the pixel.
+    Kernel(&'a [f32]),
+    /// The average of the `left` pixels before, and the `right` pixels after the pixel,
+    /// including the pixel itself.
+    Box { left: usize, right: usize },
+}
+
+fn blur(
+    pixmap: &mut Pixmap,
+    region: RectU16,
+    passes: &[Pass<'_>],
+    edge_mode: EdgeMode,
+    level: Level,
+) {
+    let region = region.intersect(RectU16::new(0, 0, pixmap.width(), pixmap.height()));
+    if region.is_empty() {
+        return;
+    }
+
+    dispatch!(level, simd => blur_impl(simd, pixmap, region, passes, edge_mode));
+}
+
+fn blur_impl<S: Simd>(
+    s: S,
+    pixmap: &mut Pixmap,
+    region: RectU16,
+    passes: &[Pass<'_>],
+    edge_mode: EdgeMode,
+) {
+    let width = usize::from(region.width());
+    let height = usize::from(region.height());
+
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for y in region.y0..region.y1 {
+        for x in region.x0..region.x1 {
+            let p = pixmap.sample(x, y);
+            pixels.extend([p.r, p.g, p.b, p.a].map(f32::from));
+        }
+    }
+
+    let mut scratch = Scratch {
+        line: vec![0.0; width.max(height) * 4],
+        padded: Vec::new(),
+    };
+    // Rows, then columns.
+    for y in 0..height {
+        blur_line(
+            s,
+            &mut pixels,
+            y * width,
+            1,
+            width,
+            passes,
+            edge_mode,
+            &mut scratch,
+        );
+    }
+    for x in 0..width {
+        blur_line(
+            s,
+            &mut pixels,
+            x,
+            width,
+            height,
+            passes,
+            edge_mode,
+            &mut scratch,
+        );
+    }
+
+    for (i, p) in pixels.chunks_exact(4).enumerate() {
+        let [r, g, b, a] = [p[0], p[1], p[2], p[3]].map(|c| c.round().clamp(0.0, 255.0) as u8);
+        let x = region.x0 + (i % width) as u16;
+        let y = region.y0 + (i / width) as u16;
+        pixmap.set_pixel(x, y, PremulRgba8 { r, g, b, a });
+    }
+
+    if edge_mode == EdgeMode::None {
+        // Transparency is blurred in from beyond the edges.
+        pixmap.set_may_have_transparency(true);
+    }
+}
+
+/// Buffers that are reused across the lines of a blur.
+struct Scratch {
+    /// The pixels of the line being blurred.
+    line: Vec<f32>,
+    /// The pixels of the line, extended beyond its ends according to the edge mode.
+    padded: Vec<f32>,
+}
+
+/// Apply `passes` to the `len` pixels of `pixels` that start at pixel `start` and are `step`
+/// pixels apart.
+fn blur_line<S: Simd>(
+    s: S,
+    pixels: &mut [f32],
+    start: usize,
+    step: usize,
+    len: usize,
+    passes: &[Pass<'_>],
+    edge_mode: EdgeMode,
+    scratch: &mut Scratch,
+) {
+    let line = &mut scratch.line[..len * 4];
+    for (i, dst) in line.chunks_exact_mut(4).enumerate() {
+        dst.copy_from_slice(&pixels[(start + i * step) * 4..][..4]);
+    }
+
+    for pass in passes {
+        match *pass {
+            Pass::Kernel(kernel) => {
+                let radius = kernel.len() / 2;
+                pad(line, radius, radius, edge_mode, &mut scratch.padded);
+                for (x, dst) in line.chunks_exact_mut(4).enumerate() {
+                    let mut sum = f32x4::splat(s, 0.0);
+                    for (j, &weight) in kernel.iter().enumerate() {
+                        let p = f32x4::from_slice(s, &scratch.padded[(x + j) * 4..][..4]);
+                        sum = p.mul_add(f32x4::splat(s, weight), sum);
+                    }
+                    sum.store_slice(dst);
+                }
+            }
+            Pass::Box { left, right } => {
+                let window = left + right + 1;
+                pad(line, left, right, edge_mode, &mut scratch.padded);
+                let padded = &scratch.padded;
+                let inv_window = f32x4::splat(s, 1.0 / window as f32);
+                let mut sum = f32x4::splat(s, 0.0);
+                for p in padded[..window * 4].chunks_exact(4) {
+                    sum += f32x4::from_slice(s, p);
+                }
+                for (x, dst) in line.chunks_exact_mut(4).enumerate() {
+                    (sum * inv_window).store_slice(dst);
+                    // Slide the window by one pixel.
+                    if let Some(entering) = padded.get((x + window) * 4..(x + window + 1) * 4) {
+                        sum = sum + f32x4::from_slice(s, entering)
+                            - f32x4::from_slice(s, &padded[x * 4..][..4]);
+                    }
+                }
+            }
+        }
+    }
+
+    for (i, src) in line.chunks_exact(4).enumerate() {
+        pixels[(start + i * step) * 4..][..4].copy_from_slice(src);
+    }
+}
+
+/// Extend `line` by `before` pixels at its start and `after` pixels at its end.
+fn pad(line: &[f32], before: usize, after: usize, edge_mode: EdgeMode, padded: &mut Vec<f32>) {
+    let len = line.len() / 4;
+    padded.clear();
+    padded.reserve((len + before + after) * 4);
+
+    for i in 0..len + before + after {
+        #[expect(
+            clippy::cast_possible_wrap,
+            reason = "Lines are at most `u16::MAX` pixels long, and blurs are limited to that size as well."
+        )]
+        let coord = i as i32 - before as i32;
+        if edge_mode == EdgeMode::None && (coord < 0 || coord as usize >= len) {
+            padded.extend([0.0; 4]);
+        } else {
+            let idx = usize::from(extend(coord, len as u16, edge_mode));
+            padded.extend_from_slice(&line[idx * 4..][..4]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level() -> Level {
+        Level::try_detect().unwrap_or(Level::baseline())
+    }
+
+    fn opaque(v: u8) -> PremulRgba8 {
+        PremulRgba8 {
+            r: v,
+            g: v,
+            b: v,
+            a: 255,
+        }
+    }
+
+    #[test]
+    fn box_blur_averages_and_keeps_the_outside() {
+        let mut pixmap = Pixmap::new(8, 3);
+        for x in 0..8 {
+            for y in 0..3 {
+                pixmap.set_pixel(x, y, opaque(if x == 4 { 90 } else { 0 }));
+            }
+        }
+
+        box_blur(
+            &mut pixmap,
+            RectU16::new(2, 0, 7, 3),
+            1,
+            EdgeMode::Duplicate,
+            level(),
+        );
+
+        let row: Vec<_> = (0..8).map(|x| pixmap.sample(x, 1).r).collect();
+        assert_eq!(row, [0, 0, 0, 30, 30, 30, 0, 0]);
+        assert!(
+            (0..8).all(|x| (0..3).all(|y| pixmap.sample(x, y).a == 255)),
+            "duplicated edges keep the pixels opaque"
+        );
+    }
+
+    #[test]
+    fn edge_modes_control_what_is_blurred_in() {
+        let mut transparent = Pixmap::new(9, 9);
+        transparent.data_mut().fill(opaque(200));
+        let mut duplicated = transparent.clone();
+        let region = RectU16::new(0, 0, 9, 9);
+
+        box_blur(&mut transparent, region, 2, EdgeMode::None, level());
+        box_blur(&mut duplicated, region, 2, EdgeMode::Duplicate, level());
+
+        assert_eq!(transparent.sample(4, 4), opaque(200));
+        // 3 of the 5 pixels in each direction are inside the pixmap.
+        assert_eq!(transparent.sample(0, 0).a, 92);
+        assert!(duplicated.data().iter().all(|p| *p == opaque(200)));
+    }
+
+    #[test]
+    fn gaussian_blur_preserves_the_total() {
+        for std_deviation in [0.8, 5.0] {
+            let mut pixmap = Pixmap::new(64, 64);
+            for x in 27..37 {
+                for y in 27..37 {
+                    pixmap.set_pixel(x, y, opaque(255));
+                }
+            }
+
+            gaussian_blur(
+                &mut pixmap,
+                RectU16::new(0, 0, 64, 64),
+                std_deviation,
+                EdgeMode::None,
+                level(),
+            );
+
+            let total: u32 = pixmap.data().iter().map(|p| u32::from(p.a)).sum();
+            assert!(total.abs_diff(100 * 255) < 500, "{std_deviation}: {total}");
+            assert!(pixmap.sample(27, 31).a < 255, "the edges are blurred");
+            assert!(pixmap.sample(26, 31).a > 0, "the blur spreads outwards");
+            let alpha = |x, y| pixmap.sample(x, y).a;
+            assert!(
+                alpha(25, 31).abs_diff(alpha(38, 31)) <= 1,
+                "the blur is symmetric"
+            );
+            assert!(
+                alpha(31, 25).abs_diff(alpha(25, 31)) <= 1,
+                "the blur is symmetric"
+            );
+        }
+    }
+}
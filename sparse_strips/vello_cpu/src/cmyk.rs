@@ -0,0 +1,315 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Conversion of rendered pixmaps into CMYK for print.
+//!
+//! Scenes are rendered in RGB. [`pixmap_to_cmyk`] then separates the rendered [`Pixmap`] into
+//! cyan, magenta, yellow and black ink with 8 or 16 bits per channel, composited onto white
+//! paper. How colors are separated is up to the [`CmykTransform`], which can wrap an ICC
+//! transform of a color management library. Without one, [`GrayComponentReplacement`]
+//! generates black from the gray component of each color and limits the total ink.
+//!
+//! Layers that should overprint, instead of knocking out the inks below them, are rendered
+//! into pixmaps of their own and combined with the separated backdrop by
+//! [`overprint_pixmap`], which only replaces the inks that the layer actually uses.
+//!
+//! ```rust
+//! use vello_cpu::cmyk::{CmykFormat, GrayComponentReplacement, pixmap_to_cmyk};
+//! use vello_cpu::Pixmap;
+//!
+//! let pixmap = Pixmap::new(16, 16);
+//! let mut cmyk = vec![0; 16 * 16 * CmykFormat::Cmyk8.bytes_per_pixel()];
+//! let transform = GrayComponentReplacement::default();
+//! pixmap_to_cmyk(&pixmap, CmykFormat::Cmyk8, &transform, &mut cmyk);
+//! // The pixmap is transparent, so no ink is applied to the paper.
+//! assert!(cmyk.iter().all(|&ink| ink == 0));
+//! ```
+
+use alloc::borrow::Cow;
+use vello_common::peniko::color::{ColorSpaceTag, PremulRgba8};
+use vello_common::pixmap::Pixmap;
+
+/// The layout of the pixels of a CMYK buffer.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum CmykFormat {
+    /// Cyan, magenta, yellow and black ink, with one byte per channel.
+    #[default]
+    Cmyk8,
+    /// Cyan, magenta, yellow and black ink, with a native-endian `u16` per channel.
+    Cmyk16,
+}
+
+impl CmykFormat {
+    /// The number of bytes that each pixel takes up.
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            Self::Cmyk8 => 4,
+            Self::Cmyk16 => 8,
+        }
+    }
+
+    /// Store ink amounts in the range `0.0..=1.0` in `dst`.
+    fn encode(self, cmyk: [f32; 4], dst: &mut [u8]) {
+        match self {
+            Self::Cmyk8 => {
+                for (dst, ink) in dst.iter_mut().zip(cmyk) {
+                    *dst = (ink.clamp(0.0, 1.0) * 255.0 + 0.5) as u8;
+                }
+            }
+            Self::Cmyk16 => {
+                for (dst, ink) in dst.chunks_exact_mut(2).zip(cmyk) {
+                    let ink = (ink.clamp(0.0, 1.0) * 65535.0 + 0.5) as u16;
+                    dst.copy_from_slice(&ink.to_ne_bytes());
+                }
+            }
+        }
+    }
+
+    /// Load ink amounts in the range `0.0..=1.0` from `src`.
+    fn decode(self, src: &[u8]) -> [f32; 4] {
+        match self {
+            Self::Cmyk8 => core::array::from_fn(|i| f32::from(src[i]) / 255.0),
+            Self::Cmyk16 => core::array::from_fn(|i| {
+                f32::from(u16::from_ne_bytes([src[2 * i], src[2 * i + 1]])) / 65535.0
+            }),
+        }
+    }
+}
+
+/// A separation of opaque RGB colors into CMYK inks.
+///
+/// This is the hook for color management: implement it with the ICC profile of the output
+/// condition to get the separations that the printer expects. It is implemented for closures
+/// with the same signature as [`to_cmyk`](Self::to_cmyk).
+pub trait CmykTransform {
+    /// Separate an opaque color with sRGB components in the range `0.0..=1.0` into the
+    /// amounts of cyan, magenta, yellow and black ink, in the range `0.0..=1.0`.
+    fn to_cmyk(&self, rgb: [f32; 3]) -> [f32; 4];
+}
+
+impl<F: Fn([f32; 3]) -> [f32; 4]> CmykTransform for F {
+    fn to_cmyk(&self, rgb: [f32; 3]) -> [f32; 4] {
+        self(rgb)
+    }
+}
+
+/// A device CMYK separation, which replaces the gray component of colors with black ink.
+///
+/// Printing gray and dark colors with black instead of equal amounts of cyan, magenta and
+/// yellow keeps them neutral on press and uses less ink.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GrayComponentReplacement {
+    /// The fraction of the gray component that is printed with black ink, from `0.0`, which
+    /// only uses cyan, magenta and yellow, to `1.0`, which prints grays with black only.
+    pub amount: f32,
+    /// The maximum total amount of ink, where `4.0` would allow all four inks at full
+    /// strength. If a color needs more, its cyan, magenta and yellow are reduced.
+    pub ink_limit: f32,
+}
+
+impl Default for GrayComponentReplacement {
+    /// Full gray component replacement with an ink limit of 300%, as common for coated paper.
+    fn default() -> Self {
+        Self {
+            amount: 1.0,
+            ink_limit: 3.0,
+        }
+    }
+}
+
+impl CmykTransform for GrayComponentReplacement {
+    fn to_cmyk(&self, [r, g, b]: [f32; 3]) -> [f32; 4] {
+        let [c, m, y] = [1.0 - r, 1.0 - g, 1.0 - b];
+        let k = c.min(m).min(y) * self.amount.clamp(0.0, 1.0);
+        if k >= 1.0 {
+            return [0.0, 0.0, 0.0, 1.0];
+        }
+
+        let mut cmy = [c, m, y].map(|ink| (ink - k) / (1.0 - k));
+        let total = cmy.iter().sum::<f32>() + k;
+        if total > self.ink_limit {
+            let scale = ((self.ink_limit - k) / (total - k)).max(0.0);
+            cmy = cmy.map(|ink| ink * scale);
+        }
+        [cmy[0], cmy[1], cmy[2], k]
+    }
+}
+
+/// Separate `pixmap` into CMYK inks on white paper, stored in `dst` in the given `format`.
+///
+/// Translucent pixels are composited onto the paper before they are separated, and
+/// transparent pixels get no ink at all. Pixmaps in other color spaces than sRGB are
+/// converted to sRGB first.
+///
+/// # Panics
+///
+/// Panics if `dst` doesn't hold the same number of pixels as `pixmap`.
+pub fn pixmap_to_cmyk(
+    pixmap: &Pixmap,
+    format: CmykFormat,
+    transform: &impl CmykTransform,
+    dst: &mut [u8],
+) {
+    let bytes_per_pixel = format.bytes_per_pixel();
+    assert_eq!(
+        pixmap.data().len(),
+        dst.len() / bytes_per_pixel,
+        "the buffers don't hold the same number of pixels"
+    );
+
+    let pixmap = to_srgb(pixmap);
+    for (src, dst) in pixmap
+        .data()
+        .iter()
+        .zip(dst.chunks_exact_mut(bytes_per_pixel))
+    {
+        let cmyk = if src.a == 0 {
+            [0.0; 4]
+        } else {
+            transform.to_cmyk(on_paper(*src))
+        };
+        format.encode(cmyk, dst);
+    }
+}
+
+/// Overprint the layer in `pixmap` onto `backdrop`, a CMYK buffer in the given `format`.
+///
+/// Each pixel of the layer is separated with `transform` as if it was opaque. The inks that
+/// the separated color uses are then mixed with those of the backdrop according to the
+/// coverage of the pixel, while the inks that it doesn't use are left as they are, like with
+/// the nonzero overprint mode of PDF. A yellow layer over cyan text thus turns the text green
+/// instead of knocking it out.
+///
+/// # Panics
+///
+/// Panics if `backdrop` doesn't hold the same number of pixels as `pixmap`.
+pub fn overprint_pixmap(
+    backdrop: &mut [u8],
+    pixmap: &Pixmap,
+    format: CmykFormat,
+    transform: &impl CmykTransform,
+) {
+    let bytes_per_pixel = format.bytes_per_pixel();
+    assert_eq!(
+        pixmap.data().len(),
+        backdrop.len() / bytes_per_pixel,
+        "the buffers don't hold the same number of pixels"
+    );
+
+    let pixmap = to_srgb(pixmap);
+    for (src, dst) in pixmap
+        .data()
+        .iter()
+        .zip(backdrop.chunks_exact_mut(bytes_per_pixel))
+    {
+        if src.a == 0 {
+            continue;
+        }
+
+        let alpha = f32::from(src.a) / 255.0;
+        let color = unpremultiply(*src);
+        let inks = transform.to_cmyk(color);
+        let mut mixed = format.decode(dst);
+        for (backdrop, ink) in mixed.iter_mut().zip(inks) {
+            if ink > 0.0 {
+                *backdrop += (ink - *backdrop) * alpha;
+            }
+        }
+        format.encode(mixed, dst);
+    }
+}
+
+fn to_srgb(pixmap: &Pixmap) -> Cow<'_, Pixmap> {
+    if pixmap.color_space() == ColorSpaceTag::Srgb {
+        Cow::Borrowed(pixmap)
+    } else {
+        let mut pixmap = pixmap.clone();
+        pixmap.convert_color_space(ColorSpaceTag::Srgb);
+        Cow::Owned(pixmap)
+    }
+}
+
+/// The color of a premultiplied pixel composited onto white paper.
+fn on_paper(pixel: PremulRgba8) -> [f32; 3] {
+    let paper = 1.0 - f32::from(pixel.a) / 255.0;
+    [pixel.r, pixel.g, pixel.b].map(|c| (f32::from(c) / 255.0 + paper).min(1.0))
+}
+
+/// The color of a premultiplied pixel, which isn't transparent, with straight alpha.
+fn unpremultiply(pixel: PremulRgba8) -> [f32; 3] {
+    let alpha = f32::from(pixel.a);
+    [pixel.r, pixel.g, pixel.b].map(|c| (f32::from(c) / alpha).min(1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CmykFormat, CmykTransform, GrayComponentReplacement, overprint_pixmap, pixmap_to_cmyk,
+    };
+    use alloc::vec;
+    use vello_common::peniko::color::PremulRgba8;
+    use vello_common::pixmap::Pixmap;
+
+    fn pixmap(pixels: &[[u8; 4]]) -> Pixmap {
+        let data = pixels
+            .iter()
+            .map(|&[r, g, b, a]| PremulRgba8 { r, g, b, a })
+            .collect();
+        Pixmap::from_parts(data, pixels.len() as u16, 1)
+    }
+
+    #[test]
+    fn gray_component_replacement_prints_grays_with_black() {
+        let gcr = GrayComponentReplacement::default();
+        assert_eq!(gcr.to_cmyk([1.0, 1.0, 1.0]), [0.0, 0.0, 0.0, 0.0]);
+        assert_eq!(gcr.to_cmyk([0.0, 0.0, 0.0]), [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(gcr.to_cmyk([0.5, 0.5, 0.5]), [0.0, 0.0, 0.0, 0.5]);
+        assert_eq!(gcr.to_cmyk([1.0, 0.0, 0.0]), [0.0, 1.0, 1.0, 0.0]);
+
+        let no_gcr = GrayComponentReplacement {
+            amount: 0.0,
+            ink_limit: 2.4,
+        };
+        let [c, m, y, k] = no_gcr.to_cmyk([0.0, 0.0, 0.0]);
+        assert_eq!(k, 0.0);
+        assert!((c + m + y - 2.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn translucent_pixels_are_composited_onto_paper() {
+        let pixmap = pixmap(&[
+            [0, 0, 0, 0],
+            [0, 0, 0, 255],
+            [0, 0, 0, 128],
+            [0, 0, 128, 128],
+        ]);
+        let gcr = GrayComponentReplacement::default();
+
+        let mut cmyk8 = vec![0; 4 * 4];
+        pixmap_to_cmyk(&pixmap, CmykFormat::Cmyk8, &gcr, &mut cmyk8);
+        #[rustfmt::skip]
+        let expected = [
+            0, 0, 0, 0,
+            0, 0, 0, 255,
+            0, 0, 0, 128,
+            128, 128, 0, 0,
+        ];
+        assert_eq!(cmyk8, expected);
+
+        let mut cmyk16 = vec![0; 4 * 8];
+        pixmap_to_cmyk(&pixmap, CmykFormat::Cmyk16, &gcr, &mut cmyk16);
+        let black = u16::from_ne_bytes([cmyk16[14], cmyk16[15]]);
+        assert_eq!(black, 65535);
+    }
+
+    #[test]
+    fn overprinting_keeps_unused_inks() {
+        let transform = |[r, g, b]: [f32; 3]| [1.0 - r, 1.0 - g, 1.0 - b, 0.0];
+        // Cyan on the backdrop, overprinted with opaque and half-covered yellow.
+        let mut backdrop = vec![255, 0, 0, 0, 255, 0, 0, 0];
+        let layer = pixmap(&[[255, 255, 0, 255], [128, 128, 0, 128]]);
+
+        overprint_pixmap(&mut backdrop, &layer, CmykFormat::Cmyk8, &transform);
+        assert_eq!(backdrop, [255, 0, 255, 0, 255, 0, 128, 0]);
+    }
+}
@@ -5,18 +5,32 @@
 pub(crate) mod multi_threaded;
 pub(crate) mod single_threaded;
 
-use crate::RenderMode;
 use crate::kurbo::{Affine, BezPath, Rect, Stroke};
 use crate::peniko::{BlendMode, Fill};
+use crate::{PixelFormat, RenderMode};
 use core::fmt::Debug;
+use vello_common::accessibility::AccessTag;
+use vello_common::alpha_mode::AlphaMode;
 use vello_common::coarse::Wide;
 use vello_common::encode::EncodedPaint;
 use vello_common::filter_effects::Filter;
-use vello_common::mask::Mask;
+use vello_common::load_op::LoadOp;
+use vello_common::mask::{Mask, MaskKind};
 use vello_common::paint::{ImageResolver, Paint};
+use vello_common::quality::Quality;
+use vello_common::render_stats::RenderStats;
+use vello_common::strip_generator::Aliasing;
 
 pub(crate) trait Dispatcher: Debug + Send + Sync {
     fn wide(&self) -> &Wide;
+    /// Set the tag that is attached to the fill commands of subsequent paths.
+    fn set_access_tag(&mut self, tag: Option<AccessTag>);
+    /// Set whether colors are blended in linear light when rasterizing.
+    fn set_linear_compositing(&mut self, linear: bool);
+    /// Set whether colors are dithered when they are rounded to 8 bits when rasterizing.
+    fn set_dither(&mut self, dither: bool);
+    /// Set the quality preset for flattening subsequent paths and for applying filters.
+    fn set_quality(&mut self, quality: Quality);
     fn fill_path(
         &mut self,
         path: &BezPath,
@@ -24,7 +38,7 @@ pub(crate) trait Dispatcher: Debug + Send + Sync {
         transform: Affine,
         paint: Paint,
         blend_mode: BlendMode,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
         mask: Option<Mask>,
         encoded_paints: &[EncodedPaint],
     );
@@ -35,7 +49,7 @@ pub(crate) trait Dispatcher: Debug + Send + Sync {
         transform: Affine,
         paint: Paint,
         blend_mode: BlendMode,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
         mask: Option<Mask>,
         encoded_paints: &[EncodedPaint],
     );
@@ -53,7 +67,7 @@ pub(crate) trait Dispatcher: Debug + Send + Sync {
         path: &BezPath,
         fill_rule: Fill,
         transform: Affine,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
     );
     fn pop_clip_path(&mut self);
     fn push_layer(
@@ -63,13 +77,29 @@ pub(crate) trait Dispatcher: Debug + Send + Sync {
         clip_transform: Affine,
         blend_mode: BlendMode,
         opacity: f32,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
         mask: Option<Mask>,
         filter: Option<Filter>,
     );
+    fn push_mask_group(&mut self);
+    /// Apply `filter` to everything drawn so far, within the clip path.
+    fn push_backdrop_filter(
+        &mut self,
+        clip_path: &BezPath,
+        fill_rule: Fill,
+        transform: Affine,
+        aliasing: Aliasing,
+        filter: Filter,
+    );
+    fn end_mask(&mut self, kind: MaskKind);
     fn pop_layer(&mut self);
     fn reset(&mut self);
+    /// The paths, strips and alphas generated since the last reset, which are complete once
+    /// the dispatcher is flushed.
+    fn stats(&self) -> RenderStats;
     fn flush(&mut self, encoded_paints: &[EncodedPaint]);
+    /// Rasterize the scene into `buffer`, which holds `height` rows of pixels of the target,
+    /// starting at the row of wide tiles `first_row`.
     fn rasterize(
         &self,
         buffer: &mut [u8],
@@ -78,7 +108,15 @@ pub(crate) trait Dispatcher: Debug + Send + Sync {
         height: u16,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        load_op: LoadOp,
+        pixel_format: PixelFormat,
+        alpha_mode: AlphaMode,
+        stride: usize,
+        first_row: u16,
     );
+    /// The number of rows of wide tiles that are rasterized at once when rendering
+    /// progressively.
+    fn band_rows(&self) -> u16;
     fn composite_at_offset(
         &self,
         buffer: &mut [u8],
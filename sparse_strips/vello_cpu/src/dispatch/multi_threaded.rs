@@ -1,41 +1,50 @@
 // Copyright 2025 the Vello Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use crate::RenderMode;
 use crate::dispatch::Dispatcher;
 use crate::dispatch::multi_threaded::cost::{COST_THRESHOLD, estimate_render_task_cost};
 use crate::dispatch::multi_threaded::worker::Worker;
 use crate::fine::{Fine, FineKernel};
 use crate::kurbo::{Affine, BezPath, PathEl, Point, Rect, Stroke};
 use crate::peniko::{BlendMode, Fill};
-use crate::region::Regions;
+use crate::region::{Region, Regions};
+use crate::{PixelFormat, RenderMode};
 use alloc::boxed::Box;
 use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::fmt::{Debug, Formatter};
 use crossbeam_channel::TryRecvError;
-use rayon::{ThreadPool, ThreadPoolBuilder};
 use std::cell::RefCell;
 use std::ops::Range;
-use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
-use std::sync::{Barrier, Mutex};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
 use thread_local::ThreadLocal;
+use vello_common::accessibility::AccessTag;
+use vello_common::alpha_mode::AlphaMode;
 use vello_common::clip::ClipContext;
 use vello_common::coarse::{Cmd, MODE_CPU, Wide};
 use vello_common::encode::EncodedPaint;
+use vello_common::executor::Executor;
 use vello_common::fearless_simd::{Level, Simd, dispatch};
 use vello_common::filter_effects::Filter;
 use vello_common::geometry::RectU16;
-use vello_common::mask::Mask;
+use vello_common::load_op::LoadOp;
+use vello_common::mask::{Mask, MaskKind};
 use vello_common::paint::{ImageResolver, Paint};
+use vello_common::quality::Quality;
 use vello_common::render_graph::RenderGraph;
+use vello_common::render_stats::RenderStats;
 use vello_common::strip::Strip;
-use vello_common::strip_generator::StripGenerator;
+use vello_common::strip_generator::{Aliasing, StripGenerator};
 
 mod cost;
 mod worker;
 
+/// The smallest number of tile rows of a target for which rasterization is spread across the
+/// worker threads.
+const MIN_PARALLEL_TILE_ROWS: usize = 8;
+
 type RenderTaskSender = crossbeam_channel::Sender<RenderTask>;
 type CoarseTaskSender = ordered_channel::Sender<CoarseTask>;
 type CoarseTaskReceiver = ordered_channel::Receiver<CoarseTask>;
@@ -54,8 +63,8 @@ pub(crate) struct MultiThreadedDispatcher {
     /// The wide tile container.
     wide: Wide,
     clip_context: ClipContext,
-    /// The thread pool that is used for dispatching tasks.
-    thread_pool: ThreadPool,
+    /// The executor that runs the tasks of the worker threads.
+    executor: Arc<dyn Executor>,
     allocation_group: AllocationGroup,
     /// The cost of the current batch.
     batch_cost: f32,
@@ -68,14 +77,21 @@ pub(crate) struct MultiThreadedDispatcher {
     /// Contains one worker object for each thread.
     ///
     /// The workers will be initialized once when building the multi-threaded dispatcher via
-    /// `MultiThreadedDispatcher::new`.
-    workers: Arc<ThreadLocal<RefCell<Worker>>>,
+    /// `MultiThreadedDispatcher::new`. Each task spawned in `spawn_worker_task` locks an idle
+    /// worker if there is one, so the mutexes are rarely contended.
+    workers: Arc<Vec<Mutex<Worker>>>,
+    /// The ends of the channels that are handed to the tasks spawned in `spawn_worker_task`.
+    ///
+    /// Like `task_sender`, this is set in `init` and set back to `None` in `flush`.
+    worker_channels: Option<WorkerChannels>,
     /// The receiver for coarse command tasks, used to do coarse rasterization on the main thread.
     ///
     /// Similarly to `task_sender`, this value is set to `None` initially, and will only be set once
     /// we actually call the `init` method (either when creating the dispatcher for the first time, or
     /// when resetting it).
     coarse_task_receiver: Option<CoarseTaskReceiver>,
+    /// Disconnects once all tasks that were spawned in `spawn_worker_task` have finished.
+    finished_receiver: Option<crossbeam_channel::Receiver<()>>,
     /// The storage for alpha values.
     ///
     /// Similarly to the single-threaded dispatcher, we want to be able to reuse the allocation holding
@@ -97,8 +113,6 @@ pub(crate) struct MultiThreadedDispatcher {
     /// Since we are rendering the paths on different threads, we need to make sure that they
     /// come back in the right order. The `task_idx` is used to keep track of that order.
     task_idx: u32,
-    /// The number of threads active in the thread pool.
-    num_threads: u16,
     /// The strip generator for the main thread, used for clip path rasterization.
     strip_generator: StripGenerator,
     level: Level,
@@ -107,30 +121,50 @@ pub(crate) struct MultiThreadedDispatcher {
     allocations: Allocations,
     /// Render graph (unused in multi-threaded, only needed for API compatibility with Wide).
     render_graph: RenderGraph,
+    /// The access tag of subsequent paths.
+    ///
+    /// Coarse rasterization lags behind path registration, so the tag travels along with each
+    /// path and is only handed to `wide` right before its commands are generated.
+    access_tag: Option<AccessTag>,
+    /// Whether colors are blended in linear light.
+    linear_compositing: bool,
+    /// Whether colors are dithered when they are rounded to 8 bits.
+    dither: bool,
+    /// The tolerance for flattening the paths of the pending tasks.
+    tolerance: f64,
 }
 
 impl MultiThreadedDispatcher {
+    #[cfg(test)]
     pub(crate) fn new(width: u16, height: u16, num_threads: u16, level: Level) -> Self {
-        let wide = Wide::<MODE_CPU>::new(width, height);
-        let thread_pool = ThreadPoolBuilder::new()
-            .num_threads(num_threads as usize)
-            .build()
-            .unwrap();
-        let alpha_storage = MaybePresent::new(vec![vec![]; usize::from(num_threads)]);
-        let workers = Arc::new(ThreadLocal::new());
-
-        {
-            let thread_ids = Arc::new(AtomicU8::new(0));
-            let workers = workers.clone();
+        Self::with_executor(
+            width,
+            height,
+            vello_common::executor::thread_pool(num_threads),
+            level,
+        )
+    }
 
-            // Create all workers once in `new`, so that later on we can just call`.get().unwrap()`.
-            thread_pool.spawn_broadcast(move |_| {
-                let thread_id = thread_ids.fetch_add(1, Ordering::SeqCst);
-                let worker = Worker::new(width, height, thread_id, level);
+    pub(crate) fn with_executor(
+        width: u16,
+        height: u16,
+        executor: Arc<dyn Executor>,
+        level: Level,
+    ) -> Self {
+        let num_threads = executor.num_threads();
+        assert!(
+            (1..=usize::from(u8::MAX)).contains(&num_threads),
+            "the executor must have between 1 and 255 threads"
+        );
 
-                let _ = workers.get_or(|| RefCell::new(worker));
-            });
-        }
+        let wide = Wide::<MODE_CPU>::new(width, height);
+        let alpha_storage = MaybePresent::new(vec![vec![]; num_threads]);
+        // Create all workers once in `new`, so that later on each task can lock its own.
+        let workers = Arc::new(
+            (0..num_threads)
+                .map(|thread_id| Mutex::new(Worker::new(width, height, thread_id as u8, level)))
+                .collect::<Vec<_>>(),
+        );
 
         let task_idx = 0;
         let batch_cost = 0.0;
@@ -138,7 +172,7 @@ impl MultiThreadedDispatcher {
 
         let mut dispatcher = Self {
             wide,
-            thread_pool,
+            executor,
             allocations: Allocations::default(),
             allocation_group: AllocationGroup::default(),
             batch_cost,
@@ -147,12 +181,17 @@ impl MultiThreadedDispatcher {
             workers,
             clip_context: ClipContext::new(),
             task_sender: None,
+            worker_channels: None,
             coarse_task_receiver: None,
+            finished_receiver: None,
             strip_generator: StripGenerator::new(width, height, level),
             level,
             alpha_storage,
-            num_threads,
             render_graph: RenderGraph::new(),
+            access_tag: None,
+            linear_compositing: false,
+            dither: false,
+            tolerance: Quality::default().flatten_tolerance(),
         };
 
         dispatcher.init();
@@ -168,9 +207,14 @@ impl MultiThreadedDispatcher {
         height: u16,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        load_op: LoadOp,
+        pixel_format: PixelFormat,
+        alpha_mode: AlphaMode,
+        stride: usize,
+        first_row: u16,
     ) {
         use crate::fine::F32Kernel;
-        dispatch!(self.level, simd => self.rasterize_with::<_, F32Kernel>(simd, buffer, width, height, encoded_paints, image_resolver));
+        dispatch!(self.level, simd => self.rasterize_with::<_, F32Kernel>(simd, buffer, width, height, encoded_paints, image_resolver, load_op, pixel_format, alpha_mode, stride, first_row));
     }
 
     #[cfg(feature = "u8_pipeline")]
@@ -181,50 +225,79 @@ impl MultiThreadedDispatcher {
         height: u16,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        load_op: LoadOp,
+        pixel_format: PixelFormat,
+        alpha_mode: AlphaMode,
+        stride: usize,
+        first_row: u16,
     ) {
         use crate::fine::U8Kernel;
-        dispatch!(self.level, simd => self.rasterize_with::<_, U8Kernel>(simd, buffer, width, height, encoded_paints, image_resolver));
+        dispatch!(self.level, simd => self.rasterize_with::<_, U8Kernel>(simd, buffer, width, height, encoded_paints, image_resolver, load_op, pixel_format, alpha_mode, stride, first_row));
     }
 
     fn init(&mut self) {
         let (render_task_sender, render_task_receiver) = crossbeam_channel::unbounded();
         let (coarse_task_sender, coarse_task_receiver) = ordered_channel::unbounded();
-        let workers = self.workers.clone();
-        let alpha_storage = self.alpha_storage.clone();
 
         self.task_sender = Some(render_task_sender);
         self.coarse_task_receiver = Some(coarse_task_receiver);
 
-        // Spawn the loop for the worker threads.
-        self.thread_pool.spawn_broadcast(move |_| {
-            let render_task_receiver = render_task_receiver.clone();
-            let mut coarse_task_sender = coarse_task_sender.clone();
-            let worker = workers.get().unwrap();
-            let mut worker = worker.borrow_mut();
+        let (finished_sender, finished_receiver) = crossbeam_channel::bounded(0);
+        self.finished_receiver = Some(finished_receiver);
+
+        self.worker_channels = Some(WorkerChannels {
+            render_task_receiver,
+            coarse_task_sender,
+            finished_sender,
+        });
+    }
+
+    /// Spawn a task that renders the queued render tasks on a worker thread.
+    ///
+    /// Instead of waiting for more render tasks, the task returns as soon as the queue is empty,
+    /// so it never blocks a thread of the executor, which might be shared with other render
+    /// contexts. Since a task is spawned after each render task is queued, every render task
+    /// is picked up by one of them.
+    fn spawn_worker_task(&self) {
+        let workers = self.workers.clone();
+        let alpha_storage = self.alpha_storage.clone();
+        let WorkerChannels {
+            render_task_receiver,
+            mut coarse_task_sender,
+            finished_sender,
+        } = self.worker_channels.clone().unwrap();
+        let fallback_idx = self.task_idx as usize % workers.len();
+
+        self.executor.spawn(Box::new(move || {
+            // Prefer an idle worker. If all of them are busy, waiting for one is fine, since the
+            // task using it is running and will finish once the queue is empty.
+            let mut worker = workers
+                .iter()
+                .find_map(|worker| worker.try_lock().ok())
+                .unwrap_or_else(|| workers[fallback_idx].lock().unwrap());
             let thread_id = worker.thread_id();
 
             // Take out the allocation for alphas and store it in the worker.
             alpha_storage
                 .with_inner(|alphas| worker.init(std::mem::take(&mut alphas[thread_id as usize])));
 
-            while let Ok(task) = render_task_receiver.recv() {
+            while let Ok(task) = render_task_receiver.try_recv() {
                 worker.run_render_task(task, &mut coarse_task_sender);
             }
 
-            // If we reach this point, it means the `task_sender` has been dropped by the main thread
-            // and no more tasks are available (since we flushed).
-            // So we are done, and just need to place the alphas of the worker thread back into the
-            // vector.
-
+            // Place the alphas of the worker back into the vector, so that they are available
+            // for the next task using this worker, and for rasterization.
             alpha_storage.with_inner(|alphas| {
                 alphas[thread_id as usize] = worker.finalize();
             });
 
-            // Then, we drop the `coarse_task_sender`. Once all worker threads have
-            // dropped their `coarse_task_sender`, the main thread knows that all workers are done
+            // Then, we drop the `coarse_task_sender`. Once all tasks have dropped their
+            // `coarse_task_sender` after flushing, the main thread knows that all tasks are done
             // and all alphas have been placed, so it's safe to proceed.
             drop(coarse_task_sender);
-        });
+            drop(worker);
+            drop(finished_sender);
+        }));
     }
 
     fn register_task(&mut self, task: RenderTaskType) {
@@ -268,8 +341,10 @@ impl MultiThreadedDispatcher {
             idx: task_idx,
             clip_path,
             allocation_group,
+            tolerance: self.tolerance,
         };
         task_sender.send(task).unwrap();
+        self.spawn_worker_task();
         // TODO: Pass encoded_paints here to enable overdraw elimination for opaque indexed
         // paints. Currently we pass an empty slice, so indexed paints render correctly but miss
         // the FillHint::OpaqueImage optimization. The challenge is that encoded_paints is a
@@ -304,15 +379,19 @@ impl MultiThreadedDispatcher {
                                 blend_mode,
                                 thread_id,
                                 mask,
-                            } => self.wide.generate(
-                                &task.allocation_group.strips
-                                    [strip_range.start as usize..strip_range.end as usize],
-                                paint.clone(),
-                                blend_mode,
-                                thread_id,
-                                mask,
-                                encoded_paints,
-                            ),
+                                access_tag,
+                            } => {
+                                self.wide.set_access_tag(access_tag);
+                                self.wide.generate(
+                                    &task.allocation_group.strips
+                                        [strip_range.start as usize..strip_range.end as usize],
+                                    paint.clone(),
+                                    blend_mode,
+                                    thread_id,
+                                    mask,
+                                    encoded_paints,
+                                );
+                            }
                             CoarseTaskType::PushLayer {
                                 thread_id,
                                 clip_path,
@@ -339,6 +418,12 @@ impl MultiThreadedDispatcher {
                                     thread_id,
                                 );
                             }
+                            CoarseTaskType::PushMaskGroup => {
+                                self.wide.push_mask_group(0, &mut self.render_graph, 0);
+                            }
+                            CoarseTaskType::EndMask(kind) => {
+                                self.wide.end_mask(0, kind, &mut self.render_graph, 0);
+                            }
                             CoarseTaskType::PopLayer => self.wide.pop_layer(&mut self.render_graph),
                         }
                     }
@@ -366,41 +451,68 @@ impl MultiThreadedDispatcher {
         height: u16,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        load_op: LoadOp,
+        pixel_format: PixelFormat,
+        alpha_mode: AlphaMode,
+        stride: usize,
+        first_row: u16,
     ) {
-        let mut buffer = Regions::new(width, height, buffer);
+        let mut buffer = Regions::new_with_format(
+            width,
+            height,
+            stride,
+            first_row,
+            pixel_format,
+            alpha_mode,
+            buffer,
+        );
         let fines = ThreadLocal::new();
         let wide = &self.wide;
+        let linear = self.linear_compositing || pixel_format.is_linear();
+        let dither = self.dither;
         let alpha_slots = self.alpha_storage.take();
 
-        self.thread_pool.install(|| {
-            buffer.update_regions_par(|region| {
-                let x = region.x;
-                let y = region.y;
-
-                let mut fine = fines
-                    .get_or(|| RefCell::new(Fine::<S, F>::new(simd)))
-                    .borrow_mut();
-
-                let wtile = wide.get(x, y);
-                fine.set_coords(x, y);
-
-                fine.clear(wtile.bg);
-                for cmd in &wtile.cmds {
-                    let thread_idx = match cmd {
-                        Cmd::AlphaFill(a) => Some(wide.attrs.fill[a.attrs_idx as usize].thread_idx),
-                        Cmd::ClipStrip(a) => Some(wide.attrs.clip[a.attrs_idx as usize].thread_idx),
-                        _ => None,
-                    };
-
-                    let alphas = thread_idx
-                        .map(|i| alpha_slots[i as usize].as_slice())
-                        .unwrap_or(&[]);
-                    fine.run_cmd(cmd, alphas, encoded_paints, image_resolver, &wide.attrs);
-                }
+        let render_region = |region: &mut Region<'_>| {
+            let x = region.x;
+            let y = region.y;
+
+            let mut fine = fines
+                .get_or(|| {
+                    let mut fine = Fine::<S, F>::new(simd);
+                    fine.set_coverage_only(pixel_format.is_coverage_only());
+                    fine.set_linear(linear);
+                    fine.set_dither(dither);
+                    RefCell::new(fine)
+                })
+                .borrow_mut();
+
+            let wtile = wide.get(x, y);
+            fine.set_coords(x, y);
+
+            fine.load(region, wtile.bg, load_op);
+            for cmd in &wtile.cmds {
+                let thread_idx = match cmd {
+                    Cmd::AlphaFill(a) => Some(wide.attrs.fill[a.attrs_idx as usize].thread_idx),
+                    Cmd::ClipStrip(a) => Some(wide.attrs.clip[a.attrs_idx as usize].thread_idx),
+                    _ => None,
+                };
+
+                let alphas = thread_idx
+                    .map(|i| alpha_slots[i as usize].as_slice())
+                    .unwrap_or(&[]);
+                fine.run_cmd(cmd, alphas, encoded_paints, image_resolver, &wide.attrs);
+            }
 
-                fine.pack(region);
-            });
-        });
+            fine.pack(region);
+        };
+
+        // Rendering the tiles of small targets on the worker threads costs more in
+        // synchronization than it saves, so they are rendered on the calling thread instead.
+        if buffer.rows() < MIN_PARALLEL_TILE_ROWS {
+            buffer.update_regions(render_region);
+        } else {
+            buffer.update_regions_par(&*self.executor, render_region);
+        }
 
         // Don't forget to put back the alpha buffers, so that they can be re-used in
         // the next path rendering iteration!
@@ -413,6 +525,33 @@ impl Dispatcher for MultiThreadedDispatcher {
         &self.wide
     }
 
+    fn set_access_tag(&mut self, tag: Option<AccessTag>) {
+        self.access_tag = tag;
+    }
+
+    fn set_linear_compositing(&mut self, linear: bool) {
+        self.linear_compositing = linear;
+    }
+
+    fn set_dither(&mut self, dither: bool) {
+        self.dither = dither;
+    }
+
+    fn set_quality(&mut self, quality: Quality) {
+        let tolerance = quality.flatten_tolerance();
+        if tolerance == self.tolerance {
+            return;
+        }
+
+        // The tolerance is sent along with each batch of tasks, so the pending tasks must be
+        // sent before it changes.
+        if !self.allocation_group.render_tasks.is_empty() {
+            self.flush_tasks();
+        }
+        self.tolerance = tolerance;
+        self.strip_generator.set_tolerance(tolerance);
+    }
+
     fn fill_path(
         &mut self,
         path: &BezPath,
@@ -420,7 +559,7 @@ impl Dispatcher for MultiThreadedDispatcher {
         transform: Affine,
         paint: Paint,
         blend_mode: BlendMode,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
         mask: Option<Mask>,
         _encoded_paints: &[EncodedPaint],
     ) {
@@ -433,8 +572,9 @@ impl Dispatcher for MultiThreadedDispatcher {
             paint,
             fill_rule,
             blend_mode,
-            aliasing_threshold,
+            aliasing,
             mask,
+            access_tag: self.access_tag,
         });
     }
 
@@ -445,7 +585,7 @@ impl Dispatcher for MultiThreadedDispatcher {
         transform: Affine,
         paint: Paint,
         blend_mode: BlendMode,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
         mask: Option<Mask>,
         _encoded_paints: &[EncodedPaint],
     ) {
@@ -458,8 +598,9 @@ impl Dispatcher for MultiThreadedDispatcher {
             paint,
             stroke: stroke.clone(),
             blend_mode,
-            aliasing_threshold,
+            aliasing,
             mask,
+            access_tag: self.access_tag,
         });
     }
 
@@ -488,8 +629,9 @@ impl Dispatcher for MultiThreadedDispatcher {
             paint,
             fill_rule: Fill::NonZero,
             blend_mode,
-            aliasing_threshold: None,
+            aliasing: Aliasing::default(),
             mask,
+            access_tag: self.access_tag,
         });
     }
 
@@ -500,7 +642,7 @@ impl Dispatcher for MultiThreadedDispatcher {
         clip_transform: Affine,
         blend_mode: BlendMode,
         opacity: f32,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
         mask: Option<Mask>,
         filter: Option<Filter>,
     ) {
@@ -524,22 +666,53 @@ impl Dispatcher for MultiThreadedDispatcher {
             opacity,
             mask,
             fill_rule,
-            aliasing_threshold,
+            aliasing,
         });
     }
 
+    fn push_mask_group(&mut self) {
+        self.register_task(RenderTaskType::PushMaskGroup);
+    }
+
+    fn push_backdrop_filter(
+        &mut self,
+        _clip_path: &BezPath,
+        _fill_rule: Fill,
+        _transform: Affine,
+        _aliasing: Aliasing,
+        _filter: Filter,
+    ) {
+        // TODO: Implement filter support in multi-threaded dispatcher, see `push_layer`.
+        // Until then, the backdrop is left unfiltered instead of aborting the whole render.
+    }
+
+    fn end_mask(&mut self, kind: MaskKind) {
+        self.register_task(RenderTaskType::EndMask(kind));
+    }
+
     fn pop_layer(&mut self) {
         self.register_task(RenderTaskType::PopLayer);
     }
 
     fn reset(&mut self) {
         self.wide.reset();
+        self.access_tag = None;
         self.clip_context.reset();
         self.allocation_group.clear();
         self.batch_cost = 0.0;
         self.task_idx = 0;
         self.flushed = false;
         self.task_sender = None;
+        self.worker_channels = None;
+
+        // Wait for the spawned tasks to finish their remaining render tasks, including tasks
+        // that the executor hasn't started yet, so that they can't interfere with the next batch
+        // of render tasks.
+        if let Some(finished_receiver) = self.finished_receiver.take() {
+            // Nothing is ever sent, so this returns once all workers dropped their sender.
+            let _ = finished_receiver.recv();
+        }
+
         self.coarse_task_receiver = None;
         self.strip_generator.reset();
         self.alpha_storage.with_inner(|alphas| {
@@ -547,34 +720,35 @@ impl Dispatcher for MultiThreadedDispatcher {
                 alpha.clear();
             }
         });
-
-        let workers = self.workers.clone();
-        // + 1 since we also wait on the main thread.
-        let barrier = Arc::new(Barrier::new(usize::from(self.num_threads) + 1));
-        let t_barrier = barrier.clone();
-
-        self.thread_pool.spawn_broadcast(move |_| {
-            let worker = workers.get().unwrap();
-            let mut borrowed = worker.borrow_mut();
-            borrowed.reset();
-            t_barrier.wait();
-        });
-
-        barrier.wait();
+        for worker in self.workers.iter() {
+            worker.lock().unwrap().reset();
+        }
 
         self.init();
     }
 
+    fn stats(&self) -> RenderStats {
+        let mut stats = self.strip_generator.stats();
+        // While tasks can still be sent, the loops of the workers hold the locks of the
+        // workers, so their paths are only counted once the dispatcher is flushed.
+        if self.task_sender.is_none() {
+            for worker in self.workers.iter() {
+                stats += worker.lock().unwrap().stats();
+            }
+        }
+        stats
+    }
+
     fn flush(&mut self, encoded_paints: &[EncodedPaint]) {
         if self.flushed {
             return;
         }
 
         self.flush_tasks();
-        let sender = core::mem::take(&mut self.task_sender);
-        // Note that dropping the sender will signal to the workers that no more new paths
-        // can arrive.
-        drop(sender);
+        // Note that dropping the senders will signal to the main thread that no more new paths
+        // can arrive once all spawned tasks are done.
+        self.task_sender = None;
+        self.worker_channels = None;
         self.run_coarse(false, encoded_paints);
 
         self.flushed = true;
@@ -588,6 +762,11 @@ impl Dispatcher for MultiThreadedDispatcher {
         height: u16,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        load_op: LoadOp,
+        pixel_format: PixelFormat,
+        alpha_mode: AlphaMode,
+        stride: usize,
+        first_row: u16,
     ) {
         assert!(self.flushed, "attempted to rasterize before flushing");
 
@@ -595,27 +774,76 @@ impl Dispatcher for MultiThreadedDispatcher {
         #[cfg(all(feature = "u8_pipeline", not(feature = "f32_pipeline")))]
         {
             let _ = render_mode;
-            self.rasterize_u8(buffer, width, height, encoded_paints, image_resolver);
+            self.rasterize_u8(
+                buffer,
+                width,
+                height,
+                encoded_paints,
+                image_resolver,
+                load_op,
+                pixel_format,
+                alpha_mode,
+                stride,
+                first_row,
+            );
         }
         // Only f32 pipeline enabled
         #[cfg(all(feature = "f32_pipeline", not(feature = "u8_pipeline")))]
         {
             let _ = render_mode;
-            self.rasterize_f32(buffer, width, height, encoded_paints, image_resolver);
+            self.rasterize_f32(
+                buffer,
+                width,
+                height,
+                encoded_paints,
+                image_resolver,
+                load_op,
+                pixel_format,
+                alpha_mode,
+                stride,
+                first_row,
+            );
         }
 
         // Both pipelines enabled
         #[cfg(all(feature = "f32_pipeline", feature = "u8_pipeline"))]
         match render_mode {
             RenderMode::OptimizeSpeed => {
-                self.rasterize_u8(buffer, width, height, encoded_paints, image_resolver);
+                self.rasterize_u8(
+                    buffer,
+                    width,
+                    height,
+                    encoded_paints,
+                    image_resolver,
+                    load_op,
+                    pixel_format,
+                    alpha_mode,
+                    stride,
+                    first_row,
+                );
             }
             RenderMode::OptimizeQuality => {
-                self.rasterize_f32(buffer, width, height, encoded_paints, image_resolver);
+                self.rasterize_f32(
+                    buffer,
+                    width,
+                    height,
+                    encoded_paints,
+                    image_resolver,
+                    load_op,
+                    pixel_format,
+                    alpha_mode,
+                    stride,
+                    first_row,
+                );
             }
         }
     }
 
+    fn band_rows(&self) -> u16 {
+        // Fewer rows would be rendered on the calling thread.
+        MIN_PARALLEL_TILE_ROWS as u16
+    }
+
     fn composite_at_offset(
         &self,
         _buffer: &mut [u8],
@@ -638,7 +866,7 @@ impl Dispatcher for MultiThreadedDispatcher {
         path: &BezPath,
         fill_rule: Fill,
         transform: Affine,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
     ) {
         self.flush_tasks();
         self.clip_context.push_clip(
@@ -646,7 +874,7 @@ impl Dispatcher for MultiThreadedDispatcher {
             &mut self.strip_generator,
             fill_rule,
             transform,
-            aliasing_threshold,
+            aliasing,
         );
     }
 
@@ -764,6 +992,8 @@ pub(crate) struct RenderTask {
     pub(crate) idx: u32,
     pub(crate) clip_path: Option<OwnedClip>,
     pub(crate) allocation_group: AllocationGroup,
+    /// The tolerance for flattening the paths of the tasks.
+    pub(crate) tolerance: f64,
 }
 
 #[derive(Debug, Clone)]
@@ -774,8 +1004,9 @@ pub(crate) enum RenderTaskType {
         paint: Paint,
         fill_rule: Fill,
         blend_mode: BlendMode,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
         mask: Option<Mask>,
+        access_tag: Option<AccessTag>,
     },
     StrokePath {
         path_range: Range<u32>,
@@ -783,8 +1014,9 @@ pub(crate) enum RenderTaskType {
         paint: Paint,
         stroke: Stroke,
         blend_mode: BlendMode,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
         mask: Option<Mask>,
+        access_tag: Option<AccessTag>,
     },
     PushLayer {
         clip_path: Option<(Range<u32>, Affine)>,
@@ -792,8 +1024,10 @@ pub(crate) enum RenderTaskType {
         opacity: f32,
         mask: Option<Mask>,
         fill_rule: Fill,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
     },
+    PushMaskGroup,
+    EndMask(MaskKind),
     PopLayer,
 }
 
@@ -809,6 +1043,7 @@ pub(crate) enum CoarseTaskType {
         blend_mode: BlendMode,
         paint: Paint,
         mask: Option<Mask>,
+        access_tag: Option<AccessTag>,
     },
     PushLayer {
         thread_id: u8,
@@ -817,9 +1052,20 @@ pub(crate) enum CoarseTaskType {
         mask: Option<Mask>,
         opacity: f32,
     },
+    PushMaskGroup,
+    EndMask(MaskKind),
     PopLayer,
 }
 
+/// The ends of the channels that each task spawned by the dispatcher holds on to.
+#[derive(Clone)]
+struct WorkerChannels {
+    render_task_receiver: crossbeam_channel::Receiver<RenderTask>,
+    coarse_task_sender: CoarseTaskSender,
+    /// Never used to send anything, only dropped once the task is done.
+    finished_sender: crossbeam_channel::Sender<()>,
+}
+
 /// An object that might hold a certain value (behind a mutex), and panics if we attempt
 /// to access it when it's not initialized.
 #[derive(Clone)]
@@ -873,6 +1119,7 @@ mod tests {
     use crate::kurbo::{Affine, Rect, Shape};
     use crate::peniko::{BlendMode, Fill};
     use vello_common::paint::{Paint, PremulColor};
+    use vello_common::strip_generator::Aliasing;
 
     /// Ensure we don't cause a memory leak.
     #[test]
@@ -885,7 +1132,7 @@ mod tests {
                 Affine::IDENTITY,
                 Paint::Solid(PremulColor::from_alpha_color(BLUE)),
                 BlendMode::default(),
-                None,
+                Aliasing::default(),
                 None,
                 &[],
             );
@@ -40,7 +40,9 @@ pub(crate) fn estimate_render_task_cost(task: &RenderTaskType, paths: &[PathEl])
                     })
                     .unwrap_or(0.0)
         }
-        RenderTaskType::PopLayer => LAYER_COST,
+        RenderTaskType::PushMaskGroup | RenderTaskType::EndMask(_) | RenderTaskType::PopLayer => {
+            LAYER_COST
+        }
     }
 }
 
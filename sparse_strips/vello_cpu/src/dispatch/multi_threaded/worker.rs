@@ -7,6 +7,7 @@ use crate::dispatch::multi_threaded::{
 };
 use std::vec::Vec;
 use vello_common::clip::PathDataRef;
+use vello_common::render_stats::RenderStats;
 use vello_common::strip_generator::{GenerationMode, StripGenerator, StripStorage};
 
 #[derive(Debug)]
@@ -40,12 +41,17 @@ impl Worker {
         self.strip_generator.reset();
     }
 
+    pub(crate) fn stats(&self) -> RenderStats {
+        self.strip_generator.stats()
+    }
+
     pub(crate) fn run_render_task(
         &mut self,
         mut render_task: RenderTask,
         result_sender: &mut CoarseTaskSender,
     ) {
         let num_tasks = render_task.allocation_group.render_tasks.len();
+        self.strip_generator.set_tolerance(render_task.tolerance);
         self.strip_storage.strips.clear();
         self.strip_storage
             .set_generation_mode(GenerationMode::Append);
@@ -68,8 +74,9 @@ impl Worker {
                     paint,
                     fill_rule,
                     blend_mode,
-                    aliasing_threshold,
+                    aliasing,
                     mask,
+                    access_tag,
                 } => {
                     let start = self.strip_storage.strips.len() as u32;
                     let path = &render_task.allocation_group.path
@@ -79,7 +86,7 @@ impl Worker {
                         path.iter().copied(),
                         fill_rule,
                         transform,
-                        aliasing_threshold,
+                        aliasing,
                         &mut self.strip_storage,
                         path_clip,
                     );
@@ -91,6 +98,7 @@ impl Worker {
                         blend_mode,
                         paint,
                         mask,
+                        access_tag,
                     };
 
                     render_task
@@ -104,8 +112,9 @@ impl Worker {
                     paint,
                     blend_mode,
                     stroke,
-                    aliasing_threshold,
+                    aliasing,
                     mask,
+                    access_tag,
                 } => {
                     let start = self.strip_storage.strips.len() as u32;
                     let path = &render_task.allocation_group.path
@@ -115,7 +124,7 @@ impl Worker {
                         path.iter().copied(),
                         &stroke,
                         transform,
-                        aliasing_threshold,
+                        aliasing,
                         &mut self.strip_storage,
                         path_clip,
                     );
@@ -127,6 +136,7 @@ impl Worker {
                         blend_mode,
                         paint,
                         mask,
+                        access_tag,
                     };
 
                     render_task
@@ -140,7 +150,7 @@ impl Worker {
                     opacity,
                     mask,
                     fill_rule,
-                    aliasing_threshold,
+                    aliasing,
                 } => {
                     let clip = if let Some((path_range, transform)) = clip_path {
                         let start = self.strip_storage.strips.len() as u32;
@@ -151,7 +161,7 @@ impl Worker {
                             path.iter().copied(),
                             fill_rule,
                             transform,
-                            aliasing_threshold,
+                            aliasing,
                             &mut self.strip_storage,
                             path_clip,
                         );
@@ -176,6 +186,18 @@ impl Worker {
                         .coarse_tasks
                         .push(coarse_command);
                 }
+                RenderTaskType::PushMaskGroup => {
+                    render_task
+                        .allocation_group
+                        .coarse_tasks
+                        .push(CoarseTaskType::PushMaskGroup);
+                }
+                RenderTaskType::EndMask(kind) => {
+                    render_task
+                        .allocation_group
+                        .coarse_tasks
+                        .push(CoarseTaskType::EndMask(kind));
+                }
                 RenderTaskType::PopLayer => {
                     render_task
                         .allocation_group
@@ -1,24 +1,29 @@
 // Copyright 2025 the Vello Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use crate::RenderMode;
 use crate::dispatch::Dispatcher;
 use crate::fine::{Fine, FineKernel};
 use crate::kurbo::{Affine, BezPath, Rect, Stroke};
 use crate::layer_manager::LayerManager;
 use crate::peniko::{BlendMode, Fill};
 use crate::region::Regions;
+use crate::{PixelFormat, RenderMode};
+use vello_common::accessibility::AccessTag;
+use vello_common::alpha_mode::AlphaMode;
 use vello_common::clip::ClipContext;
 use vello_common::coarse::{Cmd, LayerKind, MODE_CPU, Wide, WideTilesBbox};
 use vello_common::color::palette::css::TRANSPARENT;
 use vello_common::encode::EncodedPaint;
 use vello_common::fearless_simd::{Level, Simd};
 use vello_common::filter_effects::Filter;
-use vello_common::mask::Mask;
+use vello_common::load_op::LoadOp;
+use vello_common::mask::{Mask, MaskKind};
 use vello_common::paint::{ImageResolver, Paint, PremulColor};
 use vello_common::pixmap::Pixmap;
+use vello_common::quality::Quality;
 use vello_common::render_graph::{RenderGraph, RenderNodeKind};
-use vello_common::strip_generator::{StripGenerator, StripStorage};
+use vello_common::render_stats::RenderStats;
+use vello_common::strip_generator::{Aliasing, StripGenerator, StripStorage};
 
 /// Single-threaded implementation of the rendering dispatcher.
 ///
@@ -42,6 +47,12 @@ pub(crate) struct SingleThreadedDispatcher {
     layer_id_next: u32,
     /// Dependency graph tracking layer relationships and filter effects.
     render_graph: RenderGraph,
+    /// Whether colors are blended in linear light.
+    linear_compositing: bool,
+    /// Whether colors are dithered when they are rounded to 8 bits.
+    dither: bool,
+    /// The quality preset for applying filters.
+    quality: Quality,
 }
 
 impl SingleThreadedDispatcher {
@@ -79,6 +90,9 @@ impl SingleThreadedDispatcher {
             level,
             layer_id_next: 0,
             render_graph,
+            linear_compositing: false,
+            dither: false,
+            quality: Quality::default(),
         }
     }
 
@@ -94,10 +108,15 @@ impl SingleThreadedDispatcher {
         height: u16,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        load_op: LoadOp,
+        pixel_format: PixelFormat,
+        alpha_mode: AlphaMode,
+        stride: usize,
+        first_row: u16,
     ) {
         use crate::fine::F32Kernel;
         use vello_common::fearless_simd::dispatch;
-        dispatch!(self.level, simd => self.rasterize_with::<_, F32Kernel>(simd, buffer, width, height, encoded_paints, image_resolver));
+        dispatch!(self.level, simd => self.rasterize_with::<_, F32Kernel>(simd, buffer, width, height, encoded_paints, image_resolver, load_op, pixel_format, alpha_mode, stride, first_row));
     }
 
     /// Rasterizes the scene using u8 precision (fast).
@@ -112,10 +131,15 @@ impl SingleThreadedDispatcher {
         height: u16,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        load_op: LoadOp,
+        pixel_format: PixelFormat,
+        alpha_mode: AlphaMode,
+        stride: usize,
+        first_row: u16,
     ) {
         use crate::fine::U8Kernel;
         use vello_common::fearless_simd::dispatch;
-        dispatch!(self.level, simd => self.rasterize_with::<_, U8Kernel>(simd, buffer, width, height, encoded_paints, image_resolver));
+        dispatch!(self.level, simd => self.rasterize_with::<_, U8Kernel>(simd, buffer, width, height, encoded_paints, image_resolver, load_op, pixel_format, alpha_mode, stride, first_row));
     }
 
     /// Core rasterization dispatcher that chooses between simple and filter-aware paths.
@@ -134,6 +158,11 @@ impl SingleThreadedDispatcher {
         height: u16,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        load_op: LoadOp,
+        pixel_format: PixelFormat,
+        alpha_mode: AlphaMode,
+        stride: usize,
+        first_row: u16,
     ) {
         let mut layer_manager = LayerManager::new();
 
@@ -146,6 +175,11 @@ impl SingleThreadedDispatcher {
                 height,
                 encoded_paints,
                 image_resolver,
+                load_op,
+                pixel_format,
+                alpha_mode,
+                stride,
+                first_row,
                 &mut layer_manager,
             );
         } else {
@@ -157,6 +191,11 @@ impl SingleThreadedDispatcher {
                 height,
                 encoded_paints,
                 image_resolver,
+                load_op,
+                pixel_format,
+                alpha_mode,
+                stride,
+                first_row,
             );
         }
     }
@@ -179,9 +218,17 @@ impl SingleThreadedDispatcher {
         height: u16,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        load_op: LoadOp,
+        pixel_format: PixelFormat,
+        alpha_mode: AlphaMode,
+        stride: usize,
+        first_row: u16,
         layer_manager: &mut LayerManager,
     ) {
         let mut fine = Fine::<S, F>::new(simd);
+        fine.set_coverage_only(pixel_format.is_coverage_only());
+        fine.set_linear(self.linear_compositing || pixel_format.is_linear());
+        fine.set_dither(self.dither);
 
         // Process nodes in dependency order (filtered layers before their consumers).
         for node_id in self.render_graph.execution_order() {
@@ -193,7 +240,24 @@ impl SingleThreadedDispatcher {
                     filter,
                     wtile_bbox,
                     transform,
+                }
+                | RenderNodeKind::BackdropLayer {
+                    layer_id,
+                    filter,
+                    wtile_bbox,
+                    transform,
+                    ..
                 } => {
+                    // The backdrop of a backdrop layer in the root layer includes the background
+                    // of the root layer.
+                    let is_backdrop = matches!(
+                        node.kind,
+                        RenderNodeKind::BackdropLayer {
+                            in_root_layer: true,
+                            ..
+                        }
+                    );
+
                     // Allocate intermediate buffer for this filtered layer.
                     let bbox_width = wtile_bbox.width_px();
                     let bbox_height = wtile_bbox.height_px();
@@ -208,18 +272,35 @@ impl SingleThreadedDispatcher {
                         // Convert region-local coords to global wtile coords.
                         let x = wtile_bbox.x0() + region.x;
                         let y = wtile_bbox.y0() + region.y;
-
+                        // TODO: Backdrops don't include the preserved contents of the target
+                        // with `LoadOp::Load`.
+                        let bg = self.wide.get(x, y).bg;
+                        let clear_color = if !is_backdrop {
+                            PremulColor::from_alpha_color(TRANSPARENT)
+                        } else if bg.is_opaque() {
+                            bg
+                        } else {
+                            load_op.clear_color().unwrap_or(bg)
+                        };
+
+                        fine.clear(clear_color);
                         self.process_layer_tile(
                             &mut fine,
                             x,
                             y,
                             *layer_id,
-                            PremulColor::from_alpha_color(TRANSPARENT),
                             layer_manager,
                             encoded_paints,
                             image_resolver,
                         );
 
+                        // The backdrop of a nested backdrop layer can end inside of clip layers
+                        // that are still open, which are composited without their clip.
+                        while fine.blend_buf.len() > 1 {
+                            fine.blend(BlendMode::default());
+                            fine.blend_buf.pop();
+                        }
+
                         debug_assert_eq!(
                             fine.blend_buf.len(),
                             1,
@@ -230,7 +311,7 @@ impl SingleThreadedDispatcher {
                     });
 
                     // Apply the filter effect to the completed layer.
-                    fine.filter_layer(&mut pixmap, filter, layer_manager, *transform);
+                    fine.filter_layer(&mut pixmap, filter, layer_manager, *transform, self.quality);
 
                     // Save the filtered pixmap to disk for debugging.
                     // #[cfg(all(debug_assertions, feature = "std", feature = "png"))]
@@ -244,16 +325,24 @@ impl SingleThreadedDispatcher {
                     wtile_bbox: _,
                 } => {
                     // Final composition directly to output buffer.
-                    let mut regions = Regions::new(width, height, buffer);
+                    let mut regions = Regions::new_with_format(
+                        width,
+                        height,
+                        stride,
+                        first_row,
+                        pixel_format,
+                        alpha_mode,
+                        buffer,
+                    );
                     regions.update_regions(|region| {
                         // Use the background color from the wide tile.
                         let bg = self.wide.get(region.x, region.y).bg;
+                        fine.load(region, bg, load_op);
                         self.process_layer_tile(
                             &mut fine,
                             region.x,
                             region.y,
                             *layer_id,
-                            bg,
                             layer_manager,
                             encoded_paints,
                             image_resolver,
@@ -280,11 +369,12 @@ impl SingleThreadedDispatcher {
     ///    content from the layer manager.
     /// 3. Skipping the filtered layer's internal commands (already rendered separately).
     ///
+    /// The blend buffer must already contain the initial color of the tile.
+    ///
     /// # Arguments
     /// * `fine` - The fine rasterizer instance.
     /// * `x`, `y` - Wide tile coordinates.
     /// * `layer_id` - The layer being processed.
-    /// * `clear_color` - Initial color for the tile.
     /// * `layer_manager` - Storage for filtered layer buffers.
     /// * `encoded_paints` - Paint definitions for the scene.
     /// * `image_resolver` - Resolver for looking up opaque image IDs.
@@ -294,14 +384,12 @@ impl SingleThreadedDispatcher {
         x: u16,
         y: u16,
         layer_id: u32,
-        clear_color: PremulColor,
         layer_manager: &mut LayerManager,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
     ) {
         let wtile = &self.wide.get(x, y);
         fine.set_coords(x, y);
-        fine.clear(clear_color);
 
         // Process all commands in this layer's render range.
         // It can happen that the layer has no associated ranges in this wide tile in
@@ -397,9 +485,25 @@ impl SingleThreadedDispatcher {
         height: u16,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        load_op: LoadOp,
+        pixel_format: PixelFormat,
+        alpha_mode: AlphaMode,
+        stride: usize,
+        first_row: u16,
     ) {
-        let mut regions = Regions::new(width, height, buffer);
+        let mut regions = Regions::new_with_format(
+            width,
+            height,
+            stride,
+            first_row,
+            pixel_format,
+            alpha_mode,
+            buffer,
+        );
         let mut fine = Fine::<S, F>::new(simd);
+        fine.set_coverage_only(pixel_format.is_coverage_only());
+        fine.set_linear(self.linear_compositing || pixel_format.is_linear());
+        fine.set_dither(self.dither);
 
         regions.update_regions(|region| {
             let x = region.x;
@@ -408,8 +512,8 @@ impl SingleThreadedDispatcher {
             let wtile = self.wide.get(x, y);
             fine.set_coords(x, y);
 
-            // Clear to background and process all commands in order.
-            fine.clear(wtile.bg);
+            // Initialize the tile according to the load op and process all commands in order.
+            fine.load(region, wtile.bg, load_op);
             for cmd in &wtile.cmds {
                 fine.run_cmd(
                     cmd,
@@ -498,6 +602,7 @@ impl SingleThreadedDispatcher {
             buffer,
         );
         let mut fine = Fine::<S, F>::new(simd);
+        fine.set_dither(self.dither);
 
         regions.update_regions(|region| {
             let x = region.x;
@@ -528,6 +633,24 @@ impl Dispatcher for SingleThreadedDispatcher {
         &self.wide
     }
 
+    fn set_access_tag(&mut self, tag: Option<AccessTag>) {
+        self.wide.set_access_tag(tag);
+    }
+
+    fn set_linear_compositing(&mut self, linear: bool) {
+        self.linear_compositing = linear;
+    }
+
+    fn set_dither(&mut self, dither: bool) {
+        self.dither = dither;
+    }
+
+    fn set_quality(&mut self, quality: Quality) {
+        self.quality = quality;
+        self.strip_generator
+            .set_tolerance(quality.flatten_tolerance());
+    }
+
     fn fill_path(
         &mut self,
         path: &BezPath,
@@ -535,7 +658,7 @@ impl Dispatcher for SingleThreadedDispatcher {
         transform: Affine,
         paint: Paint,
         blend_mode: BlendMode,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
         mask: Option<Mask>,
         encoded_paints: &[EncodedPaint],
     ) {
@@ -546,7 +669,7 @@ impl Dispatcher for SingleThreadedDispatcher {
             path,
             fill_rule,
             transform,
-            aliasing_threshold,
+            aliasing,
             &mut self.strip_storage,
             self.clip_context.get(),
         );
@@ -569,7 +692,7 @@ impl Dispatcher for SingleThreadedDispatcher {
         transform: Affine,
         paint: Paint,
         blend_mode: BlendMode,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
         mask: Option<Mask>,
         encoded_paints: &[EncodedPaint],
     ) {
@@ -580,7 +703,7 @@ impl Dispatcher for SingleThreadedDispatcher {
             path,
             stroke,
             transform,
-            aliasing_threshold,
+            aliasing,
             &mut self.strip_storage,
             self.clip_context.get(),
         );
@@ -631,7 +754,7 @@ impl Dispatcher for SingleThreadedDispatcher {
         transform: Affine,
         blend_mode: BlendMode,
         opacity: f32,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
         mask: Option<Mask>,
         filter: Option<Filter>,
     ) {
@@ -644,7 +767,7 @@ impl Dispatcher for SingleThreadedDispatcher {
                 c,
                 fill_rule,
                 transform,
-                aliasing_threshold,
+                aliasing,
                 &mut self.strip_storage,
                 self.clip_context.get(),
             );
@@ -668,11 +791,57 @@ impl Dispatcher for SingleThreadedDispatcher {
         );
     }
 
+    fn push_mask_group(&mut self) {
+        self.layer_id_next += 1;
+        self.wide
+            .push_mask_group(self.layer_id_next, &mut self.render_graph, 0);
+    }
+
+    fn push_backdrop_filter(
+        &mut self,
+        clip_path: &BezPath,
+        fill_rule: Fill,
+        transform: Affine,
+        aliasing: Aliasing,
+        filter: Filter,
+    ) {
+        self.layer_id_next += 1;
+
+        self.strip_generator.generate_filled_path(
+            clip_path,
+            fill_rule,
+            transform,
+            aliasing,
+            &mut self.strip_storage,
+            self.clip_context.get(),
+        );
+
+        self.wide.push_backdrop_filter(
+            self.layer_id_next,
+            self.strip_storage.strips.as_slice(),
+            filter,
+            transform,
+            &mut self.render_graph,
+            0,
+        );
+    }
+
+    fn end_mask(&mut self, kind: MaskKind) {
+        // The content of the mask group is drawn into its own layer.
+        self.layer_id_next += 1;
+        self.wide
+            .end_mask(self.layer_id_next, kind, &mut self.render_graph, 0);
+    }
+
     fn pop_layer(&mut self) {
         // Pop the current layer and update render graph.
         self.wide.pop_layer(&mut self.render_graph);
     }
 
+    fn stats(&self) -> RenderStats {
+        self.strip_generator.stats()
+    }
+
     fn reset(&mut self) {
         // Clear all rendering state to prepare for a new scene.
         self.wide.reset();
@@ -705,19 +874,46 @@ impl Dispatcher for SingleThreadedDispatcher {
         height: u16,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        load_op: LoadOp,
+        pixel_format: PixelFormat,
+        alpha_mode: AlphaMode,
+        stride: usize,
+        first_row: u16,
     ) {
         // If only the u8 pipeline is enabled, then use it
         #[cfg(all(feature = "u8_pipeline", not(feature = "f32_pipeline")))]
         {
             let _ = render_mode;
-            self.rasterize_u8(buffer, width, height, encoded_paints, image_resolver);
+            self.rasterize_u8(
+                buffer,
+                width,
+                height,
+                encoded_paints,
+                image_resolver,
+                load_op,
+                pixel_format,
+                alpha_mode,
+                stride,
+                first_row,
+            );
         }
 
         // If only the f32 pipeline is enabled, then use it
         #[cfg(all(feature = "f32_pipeline", not(feature = "u8_pipeline")))]
         {
             let _ = render_mode;
-            self.rasterize_f32(buffer, width, height, encoded_paints, image_resolver);
+            self.rasterize_f32(
+                buffer,
+                width,
+                height,
+                encoded_paints,
+                image_resolver,
+                load_op,
+                pixel_format,
+                alpha_mode,
+                stride,
+                first_row,
+            );
         }
 
         // If both pipelines are enabled, select precision based on render mode parameter.
@@ -725,11 +921,33 @@ impl Dispatcher for SingleThreadedDispatcher {
         match render_mode {
             RenderMode::OptimizeSpeed => {
                 // Use u8 precision for faster rendering.
-                self.rasterize_u8(buffer, width, height, encoded_paints, image_resolver);
+                self.rasterize_u8(
+                    buffer,
+                    width,
+                    height,
+                    encoded_paints,
+                    image_resolver,
+                    load_op,
+                    pixel_format,
+                    alpha_mode,
+                    stride,
+                    first_row,
+                );
             }
             RenderMode::OptimizeQuality => {
                 // Use f32 precision for higher quality.
-                self.rasterize_f32(buffer, width, height, encoded_paints, image_resolver);
+                self.rasterize_f32(
+                    buffer,
+                    width,
+                    height,
+                    encoded_paints,
+                    image_resolver,
+                    load_op,
+                    pixel_format,
+                    alpha_mode,
+                    stride,
+                    first_row,
+                );
             }
         }
 
@@ -744,10 +962,25 @@ impl Dispatcher for SingleThreadedDispatcher {
                 height,
                 encoded_paints,
                 image_resolver,
+                load_op,
+                pixel_format,
+                alpha_mode,
+                stride,
+                first_row,
             );
         }
     }
 
+    fn band_rows(&self) -> u16 {
+        // Filtered layers are rendered for the whole target at once, so rendering them again
+        // for each band would be wasteful.
+        if self.has_filters() {
+            self.wide.height_tiles()
+        } else {
+            1
+        }
+    }
+
     fn composite_at_offset(
         &self,
         buffer: &mut [u8],
@@ -836,6 +1069,7 @@ impl Dispatcher for SingleThreadedDispatcher {
                 render_mode,
                 encoded_paints,
                 image_resolver,
+                load_op,
             );
         }
     }
@@ -845,14 +1079,14 @@ impl Dispatcher for SingleThreadedDispatcher {
         path: &BezPath,
         fill_rule: Fill,
         transform: Affine,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
     ) {
         self.clip_context.push_clip(
             path,
             &mut self.strip_generator,
             fill_rule,
             transform,
-            aliasing_threshold,
+            aliasing,
         );
     }
 
@@ -903,7 +1137,7 @@ mod tests {
             Affine::IDENTITY,
             Paint::Solid(PremulColor::from_alpha_color(BLUE)),
             BlendMode::default(),
-            None,
+            Aliasing::default(),
             None,
             &[],
         );
@@ -0,0 +1,67 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! `feColorMatrix` filter primitive implementation.
+
+use vello_common::filter::color_matrix::ColorMatrix;
+use vello_common::peniko::color::PremulRgba8;
+use vello_common::pixmap::Pixmap;
+
+use super::FilterEffect;
+use crate::layer_manager::LayerManager;
+
+impl FilterEffect for ColorMatrix {
+    fn execute_lowp(&self, pixmap: &mut Pixmap, _: &mut LayerManager) {
+        apply_color_matrix(self, pixmap);
+    }
+
+    fn execute_highp(&self, pixmap: &mut Pixmap, _: &mut LayerManager) {
+        apply_color_matrix(self, pixmap);
+    }
+}
+
+fn apply_color_matrix(matrix: &ColorMatrix, pixmap: &mut Pixmap) {
+    for pixel in pixmap.data_mut() {
+        let src = [pixel.r, pixel.g, pixel.b, pixel.a].map(|c| f32::from(c) / 255.0);
+        let [r, g, b, a] = matrix.apply(src).map(|c| (c * 255.0 + 0.5) as u8);
+        *pixel = PremulRgba8 { r, g, b, a };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vello_common::filter_effects::matrices;
+
+    #[test]
+    fn color_matrix_operates_on_unpremultiplied_colors() {
+        let mut layer_manager = LayerManager::new();
+        let mut pixmap = Pixmap::new(2, 1);
+        // Half-transparent pure red, and a fully transparent pixel.
+        pixmap.set_pixel(
+            0,
+            0,
+            PremulRgba8 {
+                r: 128,
+                g: 0,
+                b: 0,
+                a: 128,
+            },
+        );
+
+        ColorMatrix::new(matrices::invert(1.0)).execute_lowp(&mut pixmap, &mut layer_manager);
+
+        // Red becomes cyan with the same alpha.
+        assert_eq!(
+            pixmap.sample(0, 0),
+            PremulRgba8 {
+                r: 0,
+                g: 128,
+                b: 128,
+                a: 128
+            }
+        );
+        // Transparent pixels stay transparent.
+        assert_eq!(pixmap.sample(1, 0), PremulRgba8::from_u32(0));
+    }
+}
@@ -446,7 +446,7 @@ where
 /// depending on the mode. For `EdgeMode::None`, the coordinate is guaranteed to be
 /// in-bounds (already checked by caller, which returns transparent black for out-of-bounds).
 #[inline(always)]
-fn extend(coord: i32, size: u16, edge_mode: EdgeMode) -> u16 {
+pub(crate) fn extend(coord: i32, size: u16, edge_mode: EdgeMode) -> u16 {
     match edge_mode {
         EdgeMode::Duplicate => {
             // Clamp to image bounds: pixels outside use nearest edge pixel
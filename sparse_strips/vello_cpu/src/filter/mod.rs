@@ -8,9 +8,10 @@
 //! Filters are applied to layers through the layer manager, which handles
 //! intermediate storage.
 
+mod color_matrix;
 mod drop_shadow;
 mod flood;
-mod gaussian_blur;
+pub(crate) mod gaussian_blur;
 mod offset;
 mod shift;
 
@@ -19,6 +20,7 @@ use vello_common::filter::PreparedFilter;
 use vello_common::filter_effects::Filter;
 use vello_common::kurbo::Affine;
 use vello_common::pixmap::Pixmap;
+use vello_common::quality::Quality;
 
 /// Trait for filter effects that can be applied to layers.
 ///
@@ -53,6 +55,7 @@ pub(crate) trait FilterEffect {
 /// * `pixmap` - The target pixmap containing rendering metadata
 /// * `layer_manager` - Manager for allocating and accessing intermediate layers
 /// * `transform` - The transformation matrix to extract scale from for filter parameters
+/// * `quality` - The quality preset that limits the number of taps of blurs
 ///
 /// # Limitations
 /// Currently only supports filter graphs with a single primitive.
@@ -62,8 +65,9 @@ pub(crate) fn filter_lowp(
     pixmap: &mut Pixmap,
     layer_manager: &mut LayerManager,
     transform: Affine,
+    quality: Quality,
 ) {
-    let prepared_filter = PreparedFilter::new(filter, &transform);
+    let prepared_filter = PreparedFilter::new(filter, &transform, quality);
 
     match prepared_filter {
         PreparedFilter::Flood(flood) => {
@@ -78,6 +82,9 @@ pub(crate) fn filter_lowp(
         PreparedFilter::DropShadow(drop_shadow) => {
             drop_shadow.execute_lowp(pixmap, layer_manager);
         }
+        PreparedFilter::ColorMatrix(color_matrix) => {
+            color_matrix.execute_lowp(pixmap, layer_manager);
+        }
     }
 }
 
@@ -91,6 +98,7 @@ pub(crate) fn filter_lowp(
 /// * `pixmap` - The target pixmap containing rendering metadata
 /// * `layer_manager` - Manager for allocating and accessing intermediate layers
 /// * `transform` - The transformation matrix to extract scale from for filter parameters
+/// * `quality` - The quality preset that limits the number of taps of blurs
 ///
 /// # Limitations
 /// Currently only supports filter graphs with a single primitive.
@@ -100,8 +108,9 @@ pub(crate) fn filter_highp(
     pixmap: &mut Pixmap,
     layer_manager: &mut LayerManager,
     transform: Affine,
+    quality: Quality,
 ) {
-    let prepared_filter = PreparedFilter::new(filter, &transform);
+    let prepared_filter = PreparedFilter::new(filter, &transform, quality);
 
     match prepared_filter {
         PreparedFilter::Flood(flood) => {
@@ -116,5 +125,8 @@ pub(crate) fn filter_highp(
         PreparedFilter::DropShadow(drop_shadow) => {
             drop_shadow.execute_highp(pixmap, layer_manager);
         }
+        PreparedFilter::ColorMatrix(color_matrix) => {
+            color_matrix.execute_highp(pixmap, layer_manager);
+        }
     }
 }
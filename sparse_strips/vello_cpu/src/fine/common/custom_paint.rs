@@ -0,0 +1,56 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Drawing custom paints.
+
+use crate::fine::Painter;
+use crate::kurbo::Point;
+use vello_common::custom_paint::EncodedCustomPaint;
+use vello_common::tile::Tile;
+
+/// A painter that evaluates a custom paint once per pixel.
+#[derive(Debug)]
+pub(crate) struct CustomPainter<'a> {
+    paint: &'a EncodedCustomPaint,
+    start_x: f64,
+    start_y: f64,
+}
+
+impl<'a> CustomPainter<'a> {
+    pub(crate) fn new(paint: &'a EncodedCustomPaint, start_x: f64, start_y: f64) -> Self {
+        Self {
+            paint,
+            start_x,
+            start_y,
+        }
+    }
+
+    fn colors(&self) -> impl Iterator<Item = [f32; 4]> + '_ {
+        let height = usize::from(Tile::HEIGHT);
+        (0..).map(move |i| {
+            let pos = Point::new(
+                self.start_x + (i / height) as f64,
+                self.start_y + (i % height) as f64,
+            );
+            self.paint.evaluate(pos)
+        })
+    }
+}
+
+impl Painter for CustomPainter<'_> {
+    fn paint_u8(&mut self, buf: &mut [u8]) {
+        for (pixel, color) in buf.chunks_exact_mut(4).zip(self.colors()) {
+            for (dest, c) in pixel.iter_mut().zip(color) {
+                *dest = (c.clamp(0.0, 1.0) * 255.0 + 0.5) as u8;
+            }
+        }
+    }
+
+    fn paint_f32(&mut self, buf: &mut [f32]) {
+        for (pixel, color) in buf.chunks_exact_mut(4).zip(self.colors()) {
+            for (dest, c) in pixel.iter_mut().zip(color) {
+                *dest = c.clamp(0.0, 1.0);
+            }
+        }
+    }
+}
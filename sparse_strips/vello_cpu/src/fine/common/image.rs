@@ -403,14 +403,11 @@ pub(crate) fn sample<S: Simd>(
 ) -> u8x16<S> {
     let idx = x_positions.to_int::<u32x4<S>>() + y_positions.to_int::<u32x4<S>>() * data.width_u32;
 
+    // The pixels are packed in native byte order, so that they come out as RGBA bytes again.
+    let pixel = |i: u32| u32::from_ne_bytes(data.pixmap.sample_idx(i).to_u8_array());
     u32x4::from_slice(
         simd,
-        &[
-            data.pixmap.sample_idx(idx[0]).to_u32(),
-            data.pixmap.sample_idx(idx[1]).to_u32(),
-            data.pixmap.sample_idx(idx[2]).to_u32(),
-            data.pixmap.sample_idx(idx[3]).to_u32(),
-        ],
+        &[pixel(idx[0]), pixel(idx[1]), pixel(idx[2]), pixel(idx[3])],
     )
     .to_bytes()
 }
@@ -22,9 +22,10 @@ use crate::region::Region;
 use vello_common::fearless_simd::*;
 use vello_common::filter_effects::Filter;
 use vello_common::kurbo::Affine;
-use vello_common::mask::Mask;
+use vello_common::mask::{Mask, luminance};
 use vello_common::paint::{PremulColor, Tint, TintMode};
 use vello_common::pixmap::Pixmap;
+use vello_common::quality::Quality;
 use vello_common::tile::Tile;
 
 pub(crate) mod blend;
@@ -112,8 +113,9 @@ impl<S: Simd> FineKernel<S> for F32Kernel {
         filter: &Filter,
         layer_manager: &mut LayerManager,
         transform: Affine,
+        quality: Quality,
     ) {
-        filter_highp(filter, pixmap, layer_manager, transform);
+        filter_highp(filter, pixmap, layer_manager, transform, quality);
     }
 
     /// Fills a buffer with a solid color using SIMD operations.
@@ -163,6 +165,18 @@ impl<S: Simd> FineKernel<S> for F32Kernel {
     }
 
     #[inline(always)]
+    fn luminance_to_alpha(simd: S, dest: &mut [Self::Numeric]) {
+        simd.vectorize(
+            #[inline(always)]
+            || {
+                for pixel in dest.chunks_exact_mut(COLOR_COMPONENTS) {
+                    let alpha = luminance(pixel[0], pixel[1], pixel[2]);
+                    pixel.copy_from_slice(&[0.0, 0.0, 0.0, alpha]);
+                }
+            },
+        );
+    }
+
     fn apply_tint(simd: S, dest: &mut [Self::Numeric], tint: &Tint) {
         let premul = tint.color.premultiply();
         let [r, g, b, a] = premul.components;
@@ -186,6 +200,32 @@ impl<S: Simd> FineKernel<S> for F32Kernel {
                         tinted.store_slice(chunk);
                     }
                 }
+                TintMode::InverseCoverage => {
+                    // A zero alpha factor leaves the alpha channel at one.
+                    let alpha_v = f32x16::block_splat(f32x4::from_slice(simd, &[a, a, a, 0.0]));
+                    let one = f32x16::splat(simd, 1.0);
+                    for chunk in dest.chunks_exact_mut(16) {
+                        let pixel = f32x16::from_slice(simd, chunk);
+                        let tinted = one - pixel * alpha_v;
+                        tinted.store_slice(chunk);
+                    }
+                }
+            },
+        );
+    }
+
+    fn cross_fade(simd: S, dest: &mut [Self::Numeric], src: &[Self::Numeric], t: f32) {
+        let t_v = f32x16::splat(simd, t);
+
+        simd.vectorize(
+            #[inline(always)]
+            || {
+                for (dest, src) in dest.chunks_exact_mut(16).zip(src.chunks_exact(16)) {
+                    let dest_v = f32x16::from_slice(simd, dest);
+                    let src_v = f32x16::from_slice(simd, src);
+                    let mixed = dest_v + (src_v - dest_v) * t_v;
+                    mixed.store_slice(dest);
+                }
             },
         );
     }
@@ -54,6 +54,13 @@ impl<S: Simd> Iterator for GradientPainter<'_, S> {
 
 impl<S: Simd> crate::fine::Painter for GradientPainter<'_, S> {
     fn paint_u8(&mut self, buf: &mut [u8]) {
+        if self.simd.level().is_fallback() {
+            let scale_factor = self.scale_factor[0];
+            let extend = self.gradient.extend;
+            super::scalar::paint_gradient(buf, &mut self.t_vals, self.lut, scale_factor, extend);
+            return;
+        }
+
         self.simd.vectorize(
             #[inline(always)]
             || {
@@ -12,6 +12,7 @@ pub(crate) mod blend;
 mod compose;
 mod gradient;
 mod image;
+mod scalar;
 
 use crate::filter::filter_lowp;
 use crate::fine::FineKernel;
@@ -29,9 +30,10 @@ use vello_common::encode::{EncodedGradient, EncodedImage};
 use vello_common::fearless_simd::*;
 use vello_common::filter_effects::Filter;
 use vello_common::kurbo::Affine;
-use vello_common::mask::Mask;
+use vello_common::mask::{Mask, luminance};
 use vello_common::paint::{PremulColor, Tint, TintMode};
 use vello_common::pixmap::Pixmap;
+use vello_common::quality::Quality;
 use vello_common::tile::Tile;
 use vello_common::util::Div255Ext;
 
@@ -101,8 +103,9 @@ impl<S: Simd> FineKernel<S> for U8Kernel {
         filter: &Filter,
         layer_manager: &mut LayerManager,
         transform: Affine,
+        quality: Quality,
     ) {
-        filter_lowp(filter, pixmap, layer_manager, transform);
+        filter_lowp(filter, pixmap, layer_manager, transform, quality);
     }
 
     /// Fills a buffer with a solid color using SIMD operations.
@@ -198,6 +201,20 @@ impl<S: Simd> FineKernel<S> for U8Kernel {
     }
 
     #[inline(always)]
+    fn luminance_to_alpha(simd: S, dest: &mut [Self::Numeric]) {
+        simd.vectorize(
+            #[inline(always)]
+            || {
+                for pixel in dest.chunks_exact_mut(COLOR_COMPONENTS) {
+                    let [r, g, b] = [pixel[0], pixel[1], pixel[2]].map(f32::from);
+                    // The weights sum up to one, so the result can't exceed 255.
+                    let alpha = (luminance(r, g, b) + 0.5) as u8;
+                    pixel.copy_from_slice(&[0, 0, 0, alpha]);
+                }
+            },
+        );
+    }
+
     fn apply_tint(simd: S, dest: &mut [Self::Numeric], tint: &Tint) {
         let premul = tint.color.premultiply();
         let [r, g, b, a] = premul.components;
@@ -223,6 +240,38 @@ impl<S: Simd> FineKernel<S> for U8Kernel {
                         tinted.store_slice(chunk);
                     }
                 }
+                TintMode::InverseCoverage => {
+                    // A zero alpha factor leaves the alpha channel at one.
+                    let a = to_u8(a);
+                    let alpha_v =
+                        u32x8::block_splat(u32x4::splat(simd, u32::from_ne_bytes([a, a, a, 0])))
+                            .to_bytes();
+                    let one = u8x32::splat(simd, 255);
+                    for chunk in dest.chunks_exact_mut(32) {
+                        let pixel = u8x32::from_slice(simd, chunk);
+                        let tinted = one - pixel.normalized_mul(alpha_v);
+                        tinted.store_slice(chunk);
+                    }
+                }
+            },
+        );
+    }
+
+    fn cross_fade(simd: S, dest: &mut [Self::Numeric], src: &[Self::Numeric], t: f32) {
+        let t = (t * 255.0 + 0.5) as u8;
+        let t_v = u16x32::splat(simd, u16::from(t));
+        let inv_t_v = u16x32::splat(simd, u16::from(255 - t));
+
+        simd.vectorize(
+            #[inline(always)]
+            || {
+                for (dest, src) in dest.chunks_exact_mut(32).zip(src.chunks_exact(32)) {
+                    let dest_v = simd.widen_u8x32(u8x32::from_slice(simd, dest));
+                    let src_v = simd.widen_u8x32(u8x32::from_slice(simd, src));
+                    // The weights sum up to 255, so the result can't exceed 255.
+                    let mixed = (dest_v * inv_t_v + src_v * t_v).div_255();
+                    simd.narrow_u16x32(mixed).store_slice(dest);
+                }
             },
         );
     }
@@ -230,7 +279,7 @@ impl<S: Simd> FineKernel<S> for U8Kernel {
     /// Composites a solid color onto a buffer using alpha blending.
     ///
     /// Dispatches to either the masked or unmasked implementation based on the
-    /// presence of per-pixel alpha masks.
+    /// presence of per-pixel alpha masks, using scalar kernels if no SIMD is available.
     #[inline(always)]
     fn alpha_composite_solid(
         simd: S,
@@ -238,7 +287,13 @@ impl<S: Simd> FineKernel<S> for U8Kernel {
         src: [Self::Numeric; 4],
         alphas: Option<&[u8]>,
     ) {
-        if let Some(alphas) = alphas {
+        if simd.level().is_fallback() {
+            if let Some(alphas) = alphas {
+                scalar::alpha_composite_solid_masked(dest, src, alphas);
+            } else {
+                scalar::alpha_composite_solid(dest, src);
+            }
+        } else if let Some(alphas) = alphas {
             alpha_fill::alpha_composite_solid(
                 simd,
                 dest,
@@ -261,6 +316,15 @@ impl<S: Simd> FineKernel<S> for U8Kernel {
         src: &[Self::Numeric],
         alphas: Option<&[u8]>,
     ) {
+        if simd.level().is_fallback() {
+            if let Some(alphas) = alphas {
+                scalar::alpha_composite_masked(dest, src, alphas);
+            } else {
+                scalar::alpha_composite(dest, src);
+            }
+            return;
+        }
+
         let src_iter = src.chunks_exact(32).map(|el| u8x32::from_slice(simd, el));
 
         if let Some(alphas) = alphas {
@@ -0,0 +1,244 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Scalar compositing and gradient kernels for targets without any of the supported SIMD levels.
+//!
+//! On such targets, the vector types of `fearless_simd` are emulated with arrays, and every
+//! operation on a `u8x32` goes through several layers of splitting and combining. The kernels
+//! here instead load whole pixels into a `u32` and process two color components at once in
+//! each half of it, skip pixels that don't change, and evaluate gradients one pixel at a time.
+//! They produce exactly the same results as the vectorized kernels, so that reference images
+//! rendered with [`Level::fallback`](vello_common::fearless_simd::Level::fallback) don't
+//! change.
+
+use crate::peniko;
+#[cfg(not(feature = "std"))]
+use vello_common::kurbo::common::FloatFuncs as _;
+
+/// The red and blue components of a pixel in native byte order, or green and alpha after a
+/// shift by 8 bits.
+const LOW_BYTES: u32 = 0x00ff_00ff;
+
+/// Composites a solid color onto `dest` with the "source over" operator.
+pub(super) fn alpha_composite_solid(dest: &mut [u8], src: [u8; 4]) {
+    let src_a = src[3];
+    let src = u32::from_ne_bytes(src);
+    if src == 0 {
+        return;
+    }
+
+    // Like the vectorized kernel, only process whole batches of 16 pixels.
+    let len = dest.len() - dest.len() % 64;
+    let inv_src_a = u32::from(255 - src_a);
+    for pixel in dest[..len].chunks_exact_mut(4) {
+        let bg = u32::from_ne_bytes(pixel.try_into().unwrap());
+        pixel.copy_from_slice(&add_bytes(scale(bg, inv_src_a), src).to_ne_bytes());
+    }
+}
+
+/// Composites a buffer of colors onto `dest` with the "source over" operator.
+pub(super) fn alpha_composite(dest: &mut [u8], src: &[u8]) {
+    for (pixel, src) in dest.chunks_exact_mut(4).zip(src.chunks_exact(4)) {
+        let src_a = src[3];
+        let src = u32::from_ne_bytes(src.try_into().unwrap());
+        match src_a {
+            0 if src == 0 => {}
+            255 => pixel.copy_from_slice(&src.to_ne_bytes()),
+            _ => {
+                let bg = u32::from_ne_bytes((&*pixel).try_into().unwrap());
+                let res = add_bytes(scale(bg, u32::from(255 - src_a)), src);
+                pixel.copy_from_slice(&res.to_ne_bytes());
+            }
+        }
+    }
+}
+
+/// Composites a solid color onto `dest` with the "source over" operator, with the coverage
+/// of each pixel given by `masks`.
+pub(super) fn alpha_composite_solid_masked(dest: &mut [u8], src: [u8; 4], masks: &[u8]) {
+    for (pixel, &mask) in dest.chunks_exact_mut(4).zip(masks) {
+        composite_masked(pixel, src, mask);
+    }
+}
+
+/// Composites a buffer of colors onto `dest` with the "source over" operator, with the
+/// coverage of each pixel given by `masks`.
+pub(super) fn alpha_composite_masked(dest: &mut [u8], src: &[u8], masks: &[u8]) {
+    for ((pixel, src), &mask) in dest.chunks_exact_mut(4).zip(src.chunks_exact(4)).zip(masks) {
+        composite_masked(pixel, src.try_into().unwrap(), mask);
+    }
+}
+
+/// Fills `buf` with the colors of a gradient at the positions in `t_vals`.
+pub(super) fn paint_gradient<'a>(
+    buf: &mut [u8],
+    t_vals: impl Iterator<Item = &'a [f32]>,
+    lut: &[[u8; 4]],
+    scale_factor: f32,
+    extend: peniko::Extend,
+) {
+    for (chunk, t_vals) in buf.chunks_exact_mut(64).zip(t_vals) {
+        for (pixel, &t) in chunk.chunks_exact_mut(4).zip(t_vals) {
+            let idx = (apply_extend(t, extend) * scale_factor) as u32;
+            pixel.copy_from_slice(&lut[idx as usize]);
+        }
+    }
+}
+
+/// The scalar version of [`apply_extend`](super::gradient::apply_extend).
+#[expect(
+    clippy::manual_clamp,
+    reason = "unlike `clamp`, this maps NaN to 0 instead of passing it on"
+)]
+#[inline(always)]
+fn apply_extend(t: f32, extend: peniko::Extend) -> f32 {
+    match extend {
+        peniko::Extend::Pad => t.max(0.0).min(1.0),
+        peniko::Extend::Repeat => {
+            // `fract` of the non-negative difference, written with `floor`, which is also
+            // available without `std`.
+            let t = t - t.floor();
+            t - t.floor()
+        }
+        peniko::Extend::Reflect => ((t - 1.0) - 2.0 * ((t - 1.0) * 0.5).floor() - 1.0)
+            .abs()
+            .max(0.0)
+            .min(1.0),
+    }
+}
+
+/// Formula: `result = src * mask + bg * (1 - src_alpha * mask)`, with the same wrapping
+/// 16-bit arithmetic as the vectorized kernel.
+#[inline(always)]
+fn composite_masked(pixel: &mut [u8], src: [u8; 4], mask: u8) {
+    if mask == 0 {
+        // Leaves the pixel unchanged.
+        return;
+    }
+    if mask == 255 && src[3] == 255 {
+        pixel.copy_from_slice(&src);
+        return;
+    }
+
+    let mask = u16::from(mask);
+    let inv_src_a_mask_a = 255 - div_255(u16::from(src[3]) * mask) as u8;
+    for (bg, src) in pixel.iter_mut().zip(src) {
+        let p1 = u16::from(*bg) * u16::from(inv_src_a_mask_a);
+        let p2 = u16::from(src) * mask;
+        *bg = div_255(p1.wrapping_add(p2)) as u8;
+    }
+}
+
+/// Multiplies each component of `pixel` by `factor / 255`, rounding like [`div_255`].
+#[inline(always)]
+fn scale(pixel: u32, factor: u32) -> u32 {
+    // Each product fits into the 16 bits of its half, even after adding 255.
+    let rb = (((pixel & LOW_BYTES) * factor + LOW_BYTES) >> 8) & LOW_BYTES;
+    let ga = ((((pixel >> 8) & LOW_BYTES) * factor + LOW_BYTES) >> 8) & LOW_BYTES;
+    rb | (ga << 8)
+}
+
+/// Adds the bytes of `a` and `b`, wrapping around like `u8x32` addition.
+#[inline(always)]
+fn add_bytes(a: u32, b: u32) -> u32 {
+    const LOW_BITS: u32 = 0x7f7f_7f7f;
+    ((a & LOW_BITS) + (b & LOW_BITS)) ^ ((a ^ b) & !LOW_BITS)
+}
+
+#[inline(always)]
+fn div_255(val: u16) -> u16 {
+    val.wrapping_add(255) >> 8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fine::lowp::{alpha_fill, fill, gradient};
+    use crate::peniko::Extend;
+    use alloc::vec::Vec;
+    use vello_common::fearless_simd::{Fallback, SimdBase, f32x16, u8x32};
+
+    fn pixels() -> Vec<u8> {
+        // A mix of opaque, transparent and translucent premultiplied pixels, as well as
+        // invalid ones whose color exceeds their alpha.
+        (0..64_u32)
+            .flat_map(|i| {
+                let a = (i * 37 % 256) as u8;
+                let c = |k: u32| ((i * k) % 256) as u8;
+                if i % 7 == 0 {
+                    [c(11), c(13), c(17), a]
+                } else {
+                    [c(11).min(a), c(13).min(a), c(17).min(a), a]
+                }
+            })
+            .collect()
+    }
+
+    fn masks() -> Vec<u8> {
+        (0..64_u32)
+            .map(|i| [0, 255, (i * 59 % 256) as u8][i as usize % 3])
+            .collect()
+    }
+
+    #[test]
+    fn scalar_kernels_match_the_vectorized_ones() {
+        let simd = Fallback::new();
+        let dest = pixels();
+        let mut src = pixels();
+        src.rotate_left(12);
+        let src_iter = || src.chunks_exact(32).map(|el| u8x32::from_slice(simd, el));
+        let masks = masks();
+        let mask_iter = || {
+            masks
+                .chunks_exact(8)
+                .map(|m| <[u8; 8]>::try_from(m).unwrap())
+        };
+
+        for color in [
+            [0, 0, 0, 0],
+            [10, 20, 30, 40],
+            [200, 100, 0, 255],
+            [1, 2, 3, 4],
+        ] {
+            let (mut expected, mut actual) = (dest.clone(), dest.clone());
+            fill::alpha_composite_solid(simd, &mut expected, color);
+            alpha_composite_solid(&mut actual, color);
+            assert_eq!(actual, expected);
+
+            let (mut expected, mut actual) = (dest.clone(), dest.clone());
+            alpha_fill::alpha_composite_solid(simd, &mut expected, color, mask_iter());
+            alpha_composite_solid_masked(&mut actual, color, &masks);
+            assert_eq!(actual, expected);
+        }
+
+        let (mut expected, mut actual) = (dest.clone(), dest.clone());
+        fill::alpha_composite(simd, &mut expected, src_iter());
+        alpha_composite(&mut actual, &src);
+        assert_eq!(actual, expected);
+
+        let (mut expected, mut actual) = (dest.clone(), dest.clone());
+        alpha_fill::alpha_composite(simd, &mut expected, src_iter(), mask_iter());
+        alpha_composite_masked(&mut actual, &src, &masks);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn scalar_extend_matches_the_vectorized_one() {
+        let simd = Fallback::new();
+        let t_vals = [
+            -2.5, -1.0, -0.75, -0.0, 0.0, 0.1, 0.5, 0.999, 1.0, 1.25, 2.0, 3.7, 1e8, -1e8, 1e-9,
+            0.3,
+        ];
+
+        for extend in [Extend::Pad, Extend::Repeat, Extend::Reflect] {
+            let expected = gradient::apply_extend(f32x16::from_slice(simd, &t_vals), extend);
+            for (&t, expected) in t_vals.iter().zip(*expected) {
+                assert_eq!(
+                    apply_extend(t, extend).to_bits(),
+                    expected.to_bits(),
+                    "{t} with {extend:?}"
+                );
+            }
+        }
+    }
+}
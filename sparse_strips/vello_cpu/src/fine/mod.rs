@@ -5,12 +5,16 @@
 //!
 //! This module implements the fine rasterization phase, which processes tiles at the pixel level.
 //! It supports both high-precision (f32) and low-precision (u8) rendering paths, along with
-//! various paint types including solid colors, gradients, images, and blurred rounded rectangles.
+//! various paint types including solid colors, gradients, images, blurred rounded rectangles and
+//! custom paints.
 
 mod common;
 mod highp;
 mod lowp;
 
+use crate::PixelFormat;
+use crate::color::palette;
+use crate::fine::common::custom_paint::CustomPainter;
 use crate::fine::common::gradient::linear::SimdLinearKind;
 use crate::fine::common::gradient::radial::SimdRadialKind;
 use crate::fine::common::gradient::sweep::SimdSweepKind;
@@ -19,6 +23,7 @@ use crate::fine::common::image::{FilteredImagePainter, NNImagePainter, PlainNNIm
 use crate::fine::common::rounded_blurred_rect::BlurredRoundedRectFiller;
 use crate::layer_manager::LayerManager;
 use crate::peniko::{BlendMode, ImageQuality};
+use crate::pixel_format;
 use crate::region::Region;
 use crate::util::EncodedImageExt;
 use alloc::vec;
@@ -35,10 +40,12 @@ use vello_common::fearless_simd::{
 };
 use vello_common::filter_effects::Filter;
 use vello_common::kurbo::Affine;
+use vello_common::load_op::LoadOp;
 use vello_common::mask::Mask;
-use vello_common::paint::{ImageResolver, ImageSource, Paint, PremulColor, Tint};
+use vello_common::paint::{ImageId, ImageResolver, ImageSource, Paint, PremulColor, Tint};
 use vello_common::pixmap::Pixmap;
-use vello_common::simd::Splat4thExt;
+use vello_common::quality::Quality;
+use vello_common::simd::{ByteOrder, Splat4thExt, widen_u8_to_u32_bytes};
 use vello_common::tile::Tile;
 use vello_common::util::f32_to_u8;
 
@@ -74,16 +81,84 @@ pub trait Numeric: Copy + Default + Clone + Debug + PartialEq + Send + Sync + 's
 
     /// The maximum opacity value for this numeric type (1.0 for f32, 255 for u8).
     const ONE: Self;
+
+    /// Whether values are more precise than the 8 bits per channel that they are packed into
+    /// (true for f32, false for u8).
+    const EXCEEDS_8_BITS: bool;
+
+    /// Convert the value to an f32 in the range `0.0..=1.0`.
+    fn to_f32(self) -> f32;
+
+    /// Convert an f32 in the range `0.0..=1.0` to this numeric type.
+    fn from_f32(value: f32) -> Self;
 }
 
 impl Numeric for f32 {
     const ZERO: Self = 0.0;
     const ONE: Self = 1.0;
+    const EXCEEDS_8_BITS: bool = true;
+
+    #[inline(always)]
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    #[inline(always)]
+    fn from_f32(value: f32) -> Self {
+        value
+    }
 }
 
 impl Numeric for u8 {
     const ZERO: Self = 0;
     const ONE: Self = 255;
+    const EXCEEDS_8_BITS: bool = false;
+
+    #[inline(always)]
+    fn to_f32(self) -> f32 {
+        f32::from(self) / 255.0
+    }
+
+    #[inline(always)]
+    fn from_f32(value: f32) -> Self {
+        (value * 255.0 + 0.5) as Self
+    }
+}
+
+/// Convert the premultiplied pixels in `buf` with `convert`, which works on f32 pixels.
+fn convert_pixels<N: Numeric>(buf: &mut [N], convert: impl Fn([f32; 4]) -> [f32; 4]) {
+    for pixel in buf.chunks_exact_mut(COLOR_COMPONENTS) {
+        let converted = convert([
+            pixel[0].to_f32(),
+            pixel[1].to_f32(),
+            pixel[2].to_f32(),
+            pixel[3].to_f32(),
+        ]);
+        for (c, value) in pixel.iter_mut().zip(converted) {
+            *c = N::from_f32(value);
+        }
+    }
+}
+
+/// Offset the premultiplied pixels in `buf`, which hold the wide tile at `(wide_x, wide_y)` in
+/// column-major order, by an ordered dither pattern, so that rounding them to 8 bits yields the
+/// original values on average.
+///
+/// The pattern spans less than an 8-bit step, and all channels of a pixel are offset by the same
+/// amount, so colors still don't exceed their alpha and opaque pixels stay opaque.
+fn dither_pixels<N: Numeric>(buf: &mut [N], wide_x: u16, wide_y: u16) {
+    let base_x = usize::from(wide_x) * usize::from(WideTile::WIDTH);
+    let base_y = usize::from(wide_y) * usize::from(Tile::HEIGHT);
+    for (i, pixel) in buf.chunks_exact_mut(COLOR_COMPONENTS).enumerate() {
+        let (x, y) = (i / usize::from(Tile::HEIGHT), i % usize::from(Tile::HEIGHT));
+        let threshold = pixel_format::threshold(true, base_x + x, base_y + y);
+        // Packing rounds to the nearest value, so shift the threshold of `0.5` to the one of
+        // the pattern.
+        let offset = (f32::from(threshold) / 255.0 - 0.5) / 255.0;
+        for c in pixel {
+            *c = N::from_f32(c.to_f32() + offset);
+        }
+    }
 }
 
 /// Trait for SIMD vector types that can convert between f32 and u8 representations.
@@ -134,27 +209,8 @@ impl<S: Simd> NumericVec<S> for u8x16<S> {
 #[inline(always)]
 pub(crate) fn u8_to_f32<S: Simd>(val: u8x16<S>) -> f32x16<S> {
     let simd = val.simd;
-    let zeroes = u8x16::splat(simd, 0);
-
-    let zip1 = simd.zip_high_u8x16(val, zeroes);
-    let zip2 = simd.zip_low_u8x16(val, zeroes);
-
-    let p1 = simd
-        .zip_low_u8x16(zip2, zeroes)
-        .bitcast::<u32x4<S>>()
-        .to_float::<f32x4<S>>();
-    let p2 = simd
-        .zip_high_u8x16(zip2, zeroes)
-        .bitcast::<u32x4<S>>()
-        .to_float::<f32x4<S>>();
-    let p3 = simd
-        .zip_low_u8x16(zip1, zeroes)
-        .bitcast::<u32x4<S>>()
-        .to_float::<f32x4<S>>();
-    let p4 = simd
-        .zip_high_u8x16(zip1, zeroes)
-        .bitcast::<u32x4<S>>()
-        .to_float::<f32x4<S>>();
+    let [p1, p2, p3, p4] = widen_u8_to_u32_bytes(val, ByteOrder::NATIVE)
+        .map(|bytes| bytes.bitcast::<u32x4<S>>().to_float::<f32x4<S>>());
 
     simd.combine_f32x8(simd.combine_f32x4(p1, p2), simd.combine_f32x4(p3, p4))
 }
@@ -248,12 +304,13 @@ pub trait FineKernel<S: Simd>: Send + Sync + 'static {
     ///
     /// The transform parameter is used to scale filter parameters based on the current
     /// transformation matrix (e.g., zoom level), ensuring filters look consistent
-    /// regardless of scale.
+    /// regardless of scale. The quality limits the number of taps of blurs.
     fn filter_layer(
         pixmap: &mut Pixmap,
         filter: &Filter,
         layer_manager: &mut LayerManager,
         transform: Affine,
+        quality: Quality,
     );
 
     /// Fill the target buffer with a solid color.
@@ -407,6 +464,18 @@ pub trait FineKernel<S: Simd>: Send + Sync + 'static {
     /// regressing the non-tinted fast path.
     fn apply_tint(simd: S, dest: &mut [Self::Numeric], tint: &Tint);
 
+    /// Mix `src` into an already-painted buffer, computing `dest * (1 - t) + src * t`.
+    ///
+    /// This is used to cross-fade between two images, with `src` holding the pixels of the
+    /// second image.
+    fn cross_fade(simd: S, dest: &mut [Self::Numeric], src: &[Self::Numeric], t: f32);
+
+    /// Replace each pixel in the buffer with transparent black whose alpha is the luminance
+    /// of the original pixel.
+    ///
+    /// This turns the contents of a luminance mask group into an alpha mask.
+    fn luminance_to_alpha(simd: S, dest: &mut [Self::Numeric]);
+
     /// Perform alpha compositing with a solid color over the target buffer.
     ///
     /// Blends a solid RGBA color over the existing contents using standard alpha compositing
@@ -463,6 +532,10 @@ pub struct Fine<S: Simd, T: FineKernel<S>> {
     /// Intermediate buffer used by painters to store generated pixel data before compositing.
     pub(crate) paint_buf: ScratchBuf<T::Numeric>,
 
+    /// Buffer for the pixels of the second image of a cross-fade, which are mixed into
+    /// `paint_buf` after painting the first image.
+    pub(crate) fade_buf: ScratchBuf<T::Numeric>,
+
     /// Buffer for storing gradient interpolation parameters (t values).
     ///
     /// Gradients pre-compute these values for efficiency before color lookup.
@@ -470,6 +543,16 @@ pub struct Fine<S: Simd, T: FineKernel<S>> {
 
     /// The SIMD context used for vectorized operations.
     pub(crate) simd: S,
+
+    /// The color that all fills are drawn with instead of their paint, if only the coverage
+    /// is rendered.
+    coverage_color: Option<PremulColor>,
+
+    /// Whether colors are composited in linear light instead of sRGB.
+    linear: bool,
+
+    /// Whether colors are dithered when they are rounded to 8 bits.
+    dither: bool,
 }
 
 impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
@@ -483,9 +566,38 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
             blend_buf: vec![[T::Numeric::ZERO; SCRATCH_BUF_SIZE]],
             f32_buf: vec![0.0; SCRATCH_BUF_SIZE / 4],
             paint_buf: [T::Numeric::ZERO; SCRATCH_BUF_SIZE],
+            fade_buf: [T::Numeric::ZERO; SCRATCH_BUF_SIZE],
+            coverage_color: None,
+            linear: false,
+            dither: false,
         }
     }
 
+    /// Set whether only the coverage is rendered.
+    ///
+    /// In that case, paints aren't evaluated and all fills are drawn with opaque white.
+    pub fn set_coverage_only(&mut self, coverage_only: bool) {
+        self.coverage_color =
+            coverage_only.then(|| PremulColor::from_alpha_color(palette::css::WHITE));
+    }
+
+    /// Set whether colors are composited in linear light instead of sRGB.
+    ///
+    /// In that case, paints are converted to linear light before they are composited, and
+    /// regions are expected to be in the [`Rgba32Float`](PixelFormat::Rgba32Float) format.
+    /// Regions in other formats, like the intermediate layers of filters, still store sRGB.
+    pub fn set_linear(&mut self, linear: bool) {
+        self.linear = linear;
+    }
+
+    /// Set whether colors are dithered when they are packed into regions with 8 bits per
+    /// channel.
+    ///
+    /// This only has an effect if the kernel composites with more than 8 bits per channel.
+    pub fn set_dither(&mut self, dither: bool) {
+        self.dither = dither;
+    }
+
     /// Set the coordinates of the wide tile currently being rendered.
     ///
     /// This is used by painters and other operations to compute absolute pixel positions.
@@ -497,7 +609,10 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
     ///
     /// This efficiently fills the entire buffer with the given premultiplied color.
     pub fn clear(&mut self, premul_color: PremulColor) {
-        let converted_color = T::extract_color(premul_color);
+        let mut converted_color = T::extract_color(premul_color);
+        if self.linear {
+            convert_pixels(&mut converted_color, pixel_format::srgb_to_linear);
+        }
         let blend_buf = self.blend_buf.last_mut().unwrap();
 
         T::copy_solid(self.simd, blend_buf, converted_color);
@@ -510,7 +625,30 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
     pub fn pack(&self, region: &mut Region<'_>) {
         let blend_buf = self.blend_buf.last().unwrap();
 
-        T::pack(self.simd, region, blend_buf);
+        if region.pixel_format == PixelFormat::Rgba32Float {
+            // Store the colors without rounding them to 8 bits.
+            region.write_f32(|x, y| {
+                let idx = COLOR_COMPONENTS * (usize::from(Tile::HEIGHT) * x + y);
+                core::array::from_fn(|i| blend_buf[idx + i].to_f32())
+            });
+        } else if self.linear || self.dithers() {
+            let mut srgb = *blend_buf;
+            if self.linear {
+                convert_pixels(&mut srgb, pixel_format::linear_to_srgb);
+            }
+            if self.dithers() {
+                dither_pixels(&mut srgb, region.x, region.y);
+            }
+            region.write_rgba(|region| T::pack(self.simd, region, &srgb));
+        } else {
+            region.write_rgba(|region| T::pack(self.simd, region, blend_buf));
+        }
+    }
+
+    /// Whether packing dithers colors, which is pointless for kernels that already composite
+    /// with 8 bits per channel.
+    fn dithers(&self) -> bool {
+        self.dither && T::Numeric::EXCEEDS_8_BITS
     }
 
     /// Reads the region contents back into the blend buffer.
@@ -521,7 +659,36 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
     pub fn unpack(&mut self, region: &mut Region<'_>) {
         let blend_buf = self.blend_buf.last_mut().unwrap();
 
-        T::unpack(self.simd, region, blend_buf);
+        if region.pixel_format == PixelFormat::Rgba32Float {
+            region.read_f32(|x, y, pixel| {
+                let idx = COLOR_COMPONENTS * (usize::from(Tile::HEIGHT) * x + y);
+                for (c, value) in blend_buf[idx..][..COLOR_COMPONENTS].iter_mut().zip(pixel) {
+                    *c = T::Numeric::from_f32(value);
+                }
+            });
+        } else {
+            region.read_rgba(|region| T::unpack(self.simd, region, blend_buf));
+            if self.linear {
+                convert_pixels(blend_buf, pixel_format::srgb_to_linear);
+            }
+        }
+    }
+
+    /// Initialize the current blend buffer of a tile of the render target.
+    ///
+    /// `bg` is the background of the wide tile, which is either transparent or, if an opaque
+    /// fill covered the whole tile, opaque. In the latter case it hides the previous contents
+    /// of the target, so the load op doesn't matter.
+    pub fn load(&mut self, region: &mut Region<'_>, bg: PremulColor, load_op: LoadOp) {
+        if bg.is_opaque() {
+            self.clear(bg);
+            return;
+        }
+
+        match load_op.clear_color() {
+            Some(color) => self.clear(color),
+            None => self.unpack(region),
+        }
     }
 
     /// Apply a filter to a layer.
@@ -533,8 +700,9 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
         filter: &Filter,
         layer_manager: &mut LayerManager,
         transform: Affine,
+        quality: Quality,
     ) {
-        T::filter_layer(pixmap, filter, layer_manager, transform);
+        T::filter_layer(pixmap, filter, layer_manager, transform, quality);
     }
 
     /// Execute a rendering command on the current tile.
@@ -652,6 +820,10 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
                     );
                 }
             }
+            Cmd::LuminanceToAlpha => {
+                let blend_buf = self.blend_buf.last_mut().unwrap();
+                T::luminance_to_alpha(self.simd, blend_buf);
+            }
             Cmd::PushZeroClip(_) | Cmd::PopZeroClip => {
                 // These commands are handled by the dispatcher and should not reach fine rasterization
                 unreachable!();
@@ -679,13 +851,26 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
         alphas: Option<&[u8]>,
         mask: Option<&Mask>,
     ) {
+        let linear = self.linear;
+        let coverage_paint;
+        let fill = match self.coverage_color {
+            Some(color) => {
+                coverage_paint = Paint::Solid(color);
+                &coverage_paint
+            }
+            None => fill,
+        };
+
         let blend_buf = &mut self.blend_buf.last_mut().unwrap()[x * TILE_HEIGHT_COMPONENTS..]
             [..TILE_HEIGHT_COMPONENTS * width];
         let default_blend = blend_mode == BlendMode::default();
 
         match fill {
             Paint::Solid(color) => {
-                let color = T::extract_color(*color);
+                let mut color = T::extract_color(*color);
+                if linear {
+                    convert_pixels(&mut color, pixel_format::srgb_to_linear);
+                }
 
                 // If color is completely opaque, we can just directly override
                 // the blend buffer.
@@ -734,14 +919,25 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
                 // we would have to repeatedly provide all arguments if we made it a function.
                 macro_rules! fill_complex_paint {
                     ($may_have_transparency:expr, $filler:expr) => {
-                        fill_complex_paint!($may_have_transparency, $filler, None::<&Tint>)
+                        fill_complex_paint!(
+                            $may_have_transparency,
+                            $filler,
+                            None::<&Tint>,
+                            None::<(&[T::Numeric], f32)>
+                        )
                     };
-                    ($may_have_transparency:expr, $filler:expr, $tint:expr) => {
+                    ($may_have_transparency:expr, $filler:expr, $tint:expr, $fade:expr) => {
                         if $may_have_transparency || alphas.is_some() {
                             T::apply_painter(self.simd, color_buf, $filler);
+                            if let Some((src, t)) = $fade {
+                                T::cross_fade(self.simd, color_buf, src, t);
+                            }
                             if let Some(t) = $tint {
                                 T::apply_tint(self.simd, color_buf, t);
                             }
+                            if linear {
+                                convert_pixels(color_buf, pixel_format::srgb_to_linear);
+                            }
 
                             if default_blend && mask.is_none() {
                                 T::alpha_composite_buffer(self.simd, blend_buf, color_buf, alphas);
@@ -763,9 +959,15 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
                             // Similarly to solid colors we can just override the previous values
                             // if all colors in the gradient are fully opaque.
                             T::apply_painter(self.simd, blend_buf, $filler);
+                            if let Some((src, t)) = $fade {
+                                T::cross_fade(self.simd, blend_buf, src, t);
+                            }
                             if let Some(t) = $tint {
                                 T::apply_tint(self.simd, blend_buf, t);
                             }
+                            if linear {
+                                convert_pixels(blend_buf, pixel_format::srgb_to_linear);
+                            }
                         }
                     };
                 }
@@ -843,15 +1045,55 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
                         }
                     }
                     EncodedPaint::Image(i) => {
-                        let pixmap = match &i.source {
+                        let resolve = |id: ImageId| {
+                            image_resolver
+                                .resolve(id)
+                                .unwrap_or_else(|| panic!("Image {:?} not found in registry", id))
+                        };
+                        let mut pixmap = match &i.source {
                             ImageSource::Pixmap(p) => p.clone(),
-                            ImageSource::OpaqueId { id, .. } => image_resolver
-                                .resolve(*id)
-                                .unwrap_or_else(|| panic!("Image {:?} not found in registry", id)),
+                            ImageSource::OpaqueId { id, .. } => resolve(*id),
+                            ImageSource::CrossFade(cross_fade) => resolve(cross_fade.from),
+                        };
+
+                        // Sample minified images from the closest mip level, if there is one.
+                        let mip = match &i.source {
+                            ImageSource::Pixmap(_) | ImageSource::CrossFade(_) => None,
+                            ImageSource::OpaqueId { id, .. } => {
+                                image_resolver.resolve_mip(*id, i.mip_lod())
+                            }
                         };
+                        let mip_paint;
+                        let mut i = i;
+                        if let Some(mip) = mip {
+                            mip_paint = i.for_mip_level(
+                                (pixmap.width(), pixmap.height()),
+                                (mip.width(), mip.height()),
+                            );
+                            i = &mip_paint;
+                            pixmap = mip;
+                        }
 
                         let tint = i.tint.as_ref();
 
+                        // Paint the image that is faded to first, so that it can be mixed into
+                        // the first image right after painting that one.
+                        let fade = match &i.source {
+                            ImageSource::CrossFade(cross_fade) => {
+                                let to = resolve(cross_fade.to);
+                                let to_paint = i.for_mip_level(
+                                    (pixmap.width(), pixmap.height()),
+                                    (to.width(), to.height()),
+                                );
+                                let fade_buf = &mut self.fade_buf[..TILE_HEIGHT_COMPONENTS * width];
+                                paint_image::<S, T>(
+                                    self.simd, fade_buf, &to_paint, &to, sampler_x, sampler_y,
+                                );
+                                Some((&*fade_buf, cross_fade.t))
+                            }
+                            _ => None,
+                        };
+
                         match (i.has_skew(), i.nearest_neighbor()) {
                             (false, false) => {
                                 // Axis-aligned with filtering - use optimized plain painters
@@ -861,7 +1103,8 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
                                         T::plain_medium_quality_image_painter(
                                             self.simd, i, &pixmap, sampler_x, sampler_y
                                         ),
-                                        tint
+                                        tint,
+                                        fade
                                     );
                                 } else {
                                     fill_complex_paint!(
@@ -869,7 +1112,8 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
                                         T::high_quality_image_painter(
                                             self.simd, i, &pixmap, sampler_x, sampler_y
                                         ),
-                                        tint
+                                        tint,
+                                        fade
                                     );
                                 }
                             }
@@ -881,7 +1125,8 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
                                         T::medium_quality_image_painter(
                                             self.simd, i, &pixmap, sampler_x, sampler_y
                                         ),
-                                        tint
+                                        tint,
+                                        fade
                                     );
                                 } else {
                                     fill_complex_paint!(
@@ -889,7 +1134,8 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
                                         T::high_quality_image_painter(
                                             self.simd, i, &pixmap, sampler_x, sampler_y
                                         ),
-                                        tint
+                                        tint,
+                                        fade
                                     );
                                 }
                             }
@@ -899,7 +1145,8 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
                                     T::plain_nn_image_painter(
                                         self.simd, i, &pixmap, sampler_x, sampler_y
                                     ),
-                                    tint
+                                    tint,
+                                    fade
                                 );
                             }
                             (true, true) => {
@@ -908,7 +1155,8 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
                                     T::nn_image_painter(
                                         self.simd, i, &pixmap, sampler_x, sampler_y
                                     ),
-                                    tint
+                                    tint,
+                                    fade
                                 );
                             }
                         }
@@ -916,6 +1164,12 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
                     EncodedPaint::ExternalTexture(_) => {
                         unimplemented!("External textures are not supported by `vello_cpu`")
                     }
+                    EncodedPaint::Custom(c) => {
+                        fill_complex_paint!(
+                            c.may_have_transparency,
+                            CustomPainter::new(c, sampler_x, sampler_y)
+                        );
+                    }
                 }
             }
         }
@@ -967,6 +1221,44 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
     }
 }
 
+/// Paint `image` into `dest`, selecting the painter the same way as for image paints.
+fn paint_image<S: Simd, T: FineKernel<S>>(
+    simd: S,
+    dest: &mut [T::Numeric],
+    image: &EncodedImage,
+    pixmap: &Pixmap,
+    start_x: f64,
+    start_y: f64,
+) {
+    match (image.has_skew(), image.nearest_neighbor()) {
+        (false, false) if image.sampler.quality == ImageQuality::Medium => T::apply_painter(
+            simd,
+            dest,
+            T::plain_medium_quality_image_painter(simd, image, pixmap, start_x, start_y),
+        ),
+        (true, false) if image.sampler.quality == ImageQuality::Medium => T::apply_painter(
+            simd,
+            dest,
+            T::medium_quality_image_painter(simd, image, pixmap, start_x, start_y),
+        ),
+        (_, false) => T::apply_painter(
+            simd,
+            dest,
+            T::high_quality_image_painter(simd, image, pixmap, start_x, start_y),
+        ),
+        (false, true) => T::apply_painter(
+            simd,
+            dest,
+            T::plain_nn_image_painter(simd, image, pixmap, start_x, start_y),
+        ),
+        (true, true) => T::apply_painter(
+            simd,
+            dest,
+            T::nn_image_painter(simd, image, pixmap, start_x, start_y),
+        ),
+    }
+}
+
 /// A trait for objects that can render pixel data into buffers.
 ///
 /// Painters abstract over different content sources (gradients, images, etc.) and can
@@ -68,7 +68,10 @@
 //! - `libm`: Use floating point implementations from [libm][].
 //! - `png`(enabled by default): Allow loading [`Pixmap`]s from PNG images.
 //!   Also required for rendering glyphs with an embedded PNG. Implies `std`.
-//! - `multithreading`: Enable multi-threaded rendering. Implies `std`.
+//! - `multithreading`: Enable multi-threaded rendering, which processes paths and rasterizes
+//!   the tiles of the target on a pool of [`num_threads`][RenderSettings::num_threads]
+//!   worker threads. Applications can also run this work on their own threads by passing an
+//!   [`Executor`] to [`RenderContext::new_with_executor`]. Implies `std`.
 //! - `text` (enabled by default): Enables glyph rendering ([`glyph_run`][RenderContext::glyph_run]).
 //! - `u8_pipeline` (enabled by default): Enable the u8 pipeline, for speed focused rendering using u8 math.
 //!   The `u8` pipeline will be used for [`OptimizeSpeed`][RenderMode::OptimizeSpeed], if both pipelines are enabled.
@@ -76,6 +79,11 @@
 //! - `f32_pipeline`: Enable the `f32` pipeline, which is slower but has more accurate
 //!   results. This is espectially useful for rendering test snapshots.
 //!   The `f32` pipeline will be used for [`OptimizeQuality`][RenderMode::OptimizeQuality], if both pipelines are enabled.
+//! - `softbuffer`: Enable the [`present`] module, which presents [`Pixmap`]s on windows through
+//!   [softbuffer](https://crates.io/crates/softbuffer). Implies `std`.
+//! - `cli`: Build the `vello-render` command-line tool, which renders SVG files and replay logs
+//!   to PNG or EXR images.
+//!   Implies `std`, `png` and `f32_pipeline`.
 //!
 //! At least one of `std` and `libm` is required; `std` overrides `libm`.
 //! At least one of `u8_pipeline` and `f32_pipeline` must be enabled.
@@ -103,6 +111,9 @@
 //! As can be seen, Vello CPU achieves compelling performance on both,
 //! aarch64 and x86 platforms. We also have SIMD optimizations for WASM SIMD,
 //! meaning that you can expect good performance there as well.
+//! Note that WASM has no runtime feature detection, so the SIMD128 code paths are only
+//! used if your application is compiled with `-Ctarget-feature=+simd128`, for example
+//! through `rustflags` in your `.cargo/config.toml`. Otherwise, scalar code is used.
 //!
 //! # Implementation
 //!
@@ -124,6 +135,13 @@
 // Targeting e.g. 32-bit means structs containing usize can give false positives for 64-bit.
 #![cfg_attr(target_pointer_width = "64", warn(clippy::trivially_copy_pass_by_ref))]
 // END LINEBENDER LINT SET
+#![cfg_attr(
+    feature = "cli",
+    allow(
+        unused_crate_dependencies,
+        reason = "`clap` is only used by the `vello-render` binary"
+    )
+)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![forbid(unsafe_code)]
 #![expect(
@@ -146,14 +164,30 @@ compile_error!("vello_cpu must have at least one of the u8 or f32 pipelines enab
 
 mod render;
 
+mod cancel;
 mod dispatch;
 mod filter;
+mod pattern;
+mod pixel_format;
+mod recording;
+mod shadow;
 #[cfg(feature = "text")]
 mod text;
 #[cfg(all(feature = "text", feature = "std", debug_assertions))]
 mod text_debug;
 mod util;
 
+pub mod blur;
+pub mod cmyk;
+pub mod map;
+#[cfg(feature = "softbuffer")]
+pub mod present;
+pub mod preview;
+pub mod replay;
+pub mod scroll;
+pub mod tile_cache;
+pub mod virtual_list;
+
 #[doc(hidden)]
 pub mod fine;
 #[doc(hidden)]
@@ -161,19 +195,47 @@ pub mod layer_manager;
 #[doc(hidden)]
 pub mod region;
 
+pub use cancel::{CancellationToken, Cancelled};
+pub use map::VectorMap;
+pub use pixel_format::{PixelFormat, rgba32_float_to_rgba8};
+pub use preview::PreviewCache;
 pub use render::{RenderContext, RenderSettings, Resources};
+pub use scroll::ScrollRenderer;
+pub use tile_cache::{DocumentTileCache, TileRenderer};
+pub use virtual_list::VirtualList;
 // Note: The first one is not something that should be
 // exposed, but is currently needed by vello_sparse_tests.
 #[cfg(feature = "text")]
 pub use glifo::Glyph;
 #[cfg(feature = "text")]
+pub use glifo::GlyphCacheConfig;
+#[cfg(feature = "text")]
 pub use text::{CpuGlyphRunBackend, GlyphRunBuilder};
+pub use vello_common::alpha_mode::AlphaMode;
+pub use vello_common::custom_paint::{CustomPaint, CustomPaintId, CustomPaintKind};
+#[cfg(feature = "std")]
+pub use vello_common::encode::GradientLutCache;
+#[cfg(feature = "multithreading")]
+pub use vello_common::executor::{Executor, Task};
 pub use vello_common::fearless_simd::Level;
-pub use vello_common::mask::Mask;
+pub use vello_common::geometry::RectU16;
+pub use vello_common::load_op::LoadOp;
+pub use vello_common::mask::{Mask, MaskKind};
 pub use vello_common::paint::{Image, ImageSource, Paint, PaintType};
+pub use vello_common::pattern::Pattern;
 pub use vello_common::pixmap::Pixmap;
+pub use vello_common::quality::Quality;
+pub use vello_common::recording::Recording;
+pub use vello_common::render_stats::RenderStats;
 pub use vello_common::{color, kurbo, peniko};
 
+// Scenes are drawn on worker threads and sent to the render thread.
+const _: () = {
+    const fn assert_send<T: Send>() {}
+    assert_send::<RenderContext>();
+    assert_send::<Resources>();
+};
+
 /// The selected rendering mode.
 /// For using [`RenderMode::OptimizeQuality`] you also need to enable `f32_pipeline` feature.
 #[derive(Copy, Clone, Debug, Default)]
@@ -0,0 +1,584 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Rendering of tiled vector maps.
+//!
+//! Map data is usually delivered as vector tiles: a grid of square tiles, each of which holds
+//! the lines, polygons and labels inside of it in its own coordinate system. A [`VectorMap`]
+//! keeps such tiles resident, converted into [`MapTile`] recordings that reference shared
+//! [`MapStyle`]s, and renders them through a [`DocumentTileCache`] so that panning only
+//! renders the newly exposed raster tiles. Labels are placed in screen space on top of the
+//! rendered tiles, with lower priority labels dropped where they would overlap.
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+#[cfg(not(feature = "std"))]
+use vello_common::kurbo::common::FloatFuncs as _;
+use vello_common::kurbo::{Affine, BezPath, Point, Rect, Size, Stroke};
+use vello_common::label::{Label, LabelPlacer};
+use vello_common::paint::{Image, ImageSource, PaintType};
+use vello_common::peniko::{Fill, ImageSampler};
+use vello_common::pixmap::Pixmap;
+
+use crate::tile_cache::{DocumentTileCache, TileRenderer};
+use crate::{RenderContext, RenderSettings, Resources};
+
+/// The maximum number of raster tiles a [`VectorMap`] keeps cached.
+const MAX_CACHED_TILES: usize = 256;
+
+/// The style of a map feature.
+#[derive(Clone, Debug, Default)]
+pub struct MapStyle {
+    /// The paint used to fill polygons.
+    pub fill: Option<PaintType>,
+    /// The stroke and paint used to draw lines and the outlines of polygons.
+    ///
+    /// The stroke width is in map units.
+    pub stroke: Option<(Stroke, PaintType)>,
+}
+
+impl MapStyle {
+    /// Create a style that fills polygons.
+    pub fn fill(paint: impl Into<PaintType>) -> Self {
+        Self {
+            fill: Some(paint.into()),
+            stroke: None,
+        }
+    }
+
+    /// Create a style that strokes lines and the outlines of polygons.
+    pub fn stroke(stroke: Stroke, paint: impl Into<PaintType>) -> Self {
+        Self {
+            fill: None,
+            stroke: Some((stroke, paint.into())),
+        }
+    }
+
+    /// Add a stroke to the style.
+    pub fn with_stroke(mut self, stroke: Stroke, paint: impl Into<PaintType>) -> Self {
+        self.stroke = Some((stroke, paint.into()));
+        self
+    }
+}
+
+/// A handle to a style registered with [`VectorMap::add_style`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StyleId(u32);
+
+/// A label of a map feature.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MapLabel {
+    /// The center of the label.
+    ///
+    /// When added to a [`MapTile`], this is in the coordinate system of the tile.
+    pub position: Point,
+    /// The size of the label on screen, in pixels.
+    pub size: Size,
+    /// The priority of the label. If labels overlap, the ones with a higher priority are
+    /// shown.
+    pub priority: i32,
+    /// An identifier that is passed back when the label is drawn, for example the index of
+    /// the label text.
+    pub id: u64,
+}
+
+#[derive(Clone, Debug)]
+enum FeatureKind {
+    Polygon,
+    Line,
+}
+
+#[derive(Clone, Debug)]
+struct Feature {
+    kind: FeatureKind,
+    path: BezPath,
+    style: StyleId,
+    /// The bounds of the path in map coordinates, not including the stroke.
+    bounds: Rect,
+}
+
+/// The contents of one vector tile.
+///
+/// Geometry is added in the coordinate system of the tile, which spans `0..extent` on both
+/// axes, and drawn in the order it was added. Geometry outside of the tile is drawn as well,
+/// so features that cross tile boundaries can be added to every tile they touch.
+#[derive(Clone, Debug)]
+pub struct MapTile {
+    extent: f64,
+    features: Vec<Feature>,
+    labels: Vec<MapLabel>,
+}
+
+impl MapTile {
+    /// Create an empty tile whose coordinates span `0..extent`.
+    ///
+    /// Vector tiles commonly use an extent of 4096.
+    pub fn new(extent: f64) -> Self {
+        Self {
+            extent,
+            features: Vec::new(),
+            labels: Vec::new(),
+        }
+    }
+
+    /// Add a polygon, which is filled with the non-zero fill rule and outlined with the
+    /// stroke of its style.
+    pub fn add_polygon(&mut self, path: BezPath, style: StyleId) {
+        self.push(FeatureKind::Polygon, path, style);
+    }
+
+    /// Add a line, which is drawn with the stroke of its style.
+    pub fn add_line(&mut self, path: BezPath, style: StyleId) {
+        self.push(FeatureKind::Line, path, style);
+    }
+
+    /// Add a label.
+    pub fn add_label(&mut self, label: MapLabel) {
+        self.labels.push(label);
+    }
+
+    /// Whether the tile has no features and labels.
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty() && self.labels.is_empty()
+    }
+
+    fn push(&mut self, kind: FeatureKind, path: BezPath, style: StyleId) {
+        self.features.push(Feature {
+            kind,
+            bounds: Rect::ZERO,
+            path,
+            style,
+        });
+    }
+
+    /// Move the tile from its own coordinate system into map coordinates.
+    fn place(&mut self, origin: Point, tile_size: f64) {
+        let scale = if self.extent > 0.0 {
+            tile_size / self.extent
+        } else {
+            1.0
+        };
+        let transform = Affine::translate(origin.to_vec2()) * Affine::scale(scale);
+
+        for feature in &mut self.features {
+            feature.path.apply_affine(transform);
+            feature.bounds = feature.path.control_box();
+        }
+        for label in &mut self.labels {
+            label.position = transform * label.position;
+        }
+    }
+
+    /// Return the area of the map the features of the tile can draw to.
+    fn bounds(&self, styles: &[MapStyle]) -> Option<Rect> {
+        self.features
+            .iter()
+            .map(|feature| {
+                let extent = stroke_extent(styles, feature.style);
+                feature.bounds.inflate(extent, extent)
+            })
+            .reduce(|a, b| a.union(b))
+    }
+}
+
+/// A view of a [`VectorMap`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MapView {
+    /// The point of the map at the top left corner of the viewport.
+    pub origin: Point,
+    /// The number of pixels per map unit.
+    pub zoom: f64,
+}
+
+impl Default for MapView {
+    fn default() -> Self {
+        Self {
+            origin: Point::ZERO,
+            zoom: 1.0,
+        }
+    }
+}
+
+impl MapView {
+    /// Return the transform from map coordinates into the viewport.
+    pub fn transform(&self) -> Affine {
+        Affine::scale(self.zoom) * Affine::translate(-self.origin.to_vec2())
+    }
+}
+
+/// A map made out of a grid of vector tiles.
+///
+/// Map coordinates are chosen such that every tile covers a square of `tile_size` map units,
+/// so at a zoom of 1 the tiles are rendered at their nominal pixel size.
+#[derive(Debug)]
+pub struct VectorMap {
+    columns: u32,
+    rows: u32,
+    tile_size: u16,
+    styles: Vec<MapStyle>,
+    tiles: HashMap<(u32, u32), MapTile>,
+    cache: DocumentTileCache<Arc<Pixmap>>,
+    renderer: TileRenderer,
+}
+
+impl VectorMap {
+    /// Create an empty map with a grid of `columns` by `rows` tiles of `tile_size` pixels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tile_size` is zero.
+    pub fn new(columns: u32, rows: u32, tile_size: u16) -> Self {
+        Self::new_with(columns, rows, tile_size, RenderSettings::default())
+    }
+
+    /// Create an empty map that renders tiles with specific settings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tile_size` is zero.
+    pub fn new_with(columns: u32, rows: u32, tile_size: u16, settings: RenderSettings) -> Self {
+        Self {
+            columns,
+            rows,
+            tile_size,
+            styles: Vec::new(),
+            tiles: HashMap::new(),
+            cache: DocumentTileCache::new(tile_size, MAX_CACHED_TILES),
+            renderer: TileRenderer::new_with(settings),
+        }
+    }
+
+    /// Return the size of the map in map units.
+    pub fn size(&self) -> Size {
+        let tile_size = f64::from(self.tile_size);
+        Size::new(
+            f64::from(self.columns) * tile_size,
+            f64::from(self.rows) * tile_size,
+        )
+    }
+
+    /// Register a style that can be used by the features of all tiles.
+    pub fn add_style(&mut self, style: MapStyle) -> StyleId {
+        let id = StyleId(self.styles.len() as u32);
+        self.styles.push(style);
+        id
+    }
+
+    /// Replace a style, which redraws all tiles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the style wasn't created by this map.
+    pub fn set_style(&mut self, id: StyleId, style: MapStyle) {
+        self.styles[id.0 as usize] = style;
+        self.cache.invalidate_page(0);
+    }
+
+    /// Set the contents of the tile at the given column and row.
+    ///
+    /// Tiles outside of the grid are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a feature of the tile uses a style that wasn't created by this map.
+    pub fn set_tile(&mut self, column: u32, row: u32, mut tile: MapTile) {
+        if column >= self.columns || row >= self.rows {
+            return;
+        }
+        assert!(
+            tile.features
+                .iter()
+                .all(|feature| (feature.style.0 as usize) < self.styles.len()),
+            "tile uses an unknown style"
+        );
+
+        let tile_size = f64::from(self.tile_size);
+        tile.place(
+            Point::new(f64::from(column) * tile_size, f64::from(row) * tile_size),
+            tile_size,
+        );
+        let new = tile.bounds(&self.styles);
+        let old = self
+            .tiles
+            .insert((column, row), tile)
+            .and_then(|old| old.bounds(&self.styles));
+        for dirty in [new, old].into_iter().flatten() {
+            self.cache.invalidate_area(0, dirty);
+        }
+    }
+
+    /// Remove the tile at the given column and row.
+    pub fn remove_tile(&mut self, column: u32, row: u32) {
+        if let Some(dirty) = self
+            .tiles
+            .remove(&(column, row))
+            .and_then(|old| old.bounds(&self.styles))
+        {
+            self.cache.invalidate_area(0, dirty);
+        }
+    }
+
+    /// Return the number of raster tiles that are currently cached.
+    pub fn cached_tiles(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Draw the part of the map seen through `view` into `ctx`, which covers the viewport.
+    ///
+    /// Visible raster tiles that aren't cached yet are rendered with `resources`. Afterwards,
+    /// `draw_label` is called for every label that is shown, with the bounds of the label in
+    /// the viewport and the transform of `ctx` reset to the identity.
+    pub fn render(
+        &mut self,
+        resources: &mut Resources,
+        ctx: &mut RenderContext,
+        view: &MapView,
+        mut draw_label: impl FnMut(&MapLabel, Rect, &mut RenderContext),
+    ) {
+        if view.zoom <= 0.0 || !view.zoom.is_finite() {
+            return;
+        }
+        self.cache.set_zoom(view.zoom);
+
+        let viewport = Rect::new(0.0, 0.0, f64::from(ctx.width()), f64::from(ctx.height()));
+        let visible = view.transform().inverse().transform_rect_bbox(viewport);
+        let map_size = self.size();
+        let (styles, tiles, renderer) = (&self.styles, &self.tiles, &mut self.renderer);
+        let tile_size = f64::from(self.tile_size);
+
+        for request in self.cache.tiles(0, map_size, visible) {
+            let pixmap = self.cache.get_or_render(&request, |request| {
+                renderer.render(resources, request, |ctx| {
+                    for tile in source_tiles(tiles, tile_size, request.bounds) {
+                        draw_tile(ctx, styles, tile, request.bounds);
+                    }
+                })
+            });
+
+            let size = Rect::new(
+                0.0,
+                0.0,
+                f64::from(pixmap.width()),
+                f64::from(pixmap.height()),
+            );
+            ctx.set_transform(view.transform() * request.transform.inverse());
+            ctx.set_paint(Image {
+                image: ImageSource::Pixmap(pixmap),
+                sampler: ImageSampler::default(),
+            });
+            ctx.fill_rect(&size);
+        }
+
+        ctx.reset_transform();
+        let sources = source_tiles(tiles, tile_size, visible);
+        for (label, bounds) in place_labels(&sources, view, viewport) {
+            draw_label(label, bounds, ctx);
+        }
+    }
+}
+
+/// Return the tiles that can draw into `visible`, in row-major order.
+fn source_tiles(
+    tiles: &HashMap<(u32, u32), MapTile>,
+    tile_size: f64,
+    visible: Rect,
+) -> Vec<&MapTile> {
+    // Features and labels can reach into neighboring tiles, so one more tile is included in
+    // every direction.
+    let range = |start: f64, end: f64| {
+        let first = (start / tile_size).floor() - 1.0;
+        let last = (end / tile_size).ceil() + 1.0;
+        first.clamp(0.0, f64::from(u32::MAX)) as u32..last.clamp(0.0, f64::from(u32::MAX)) as u32
+    };
+
+    let mut sources = Vec::new();
+    for row in range(visible.y0, visible.y1) {
+        for column in range(visible.x0, visible.x1) {
+            if let Some(tile) = tiles.get(&(column, row)) {
+                sources.push(tile);
+            }
+        }
+    }
+
+    sources
+}
+
+/// Return how far the stroke of a style reaches beyond the path.
+fn stroke_extent(styles: &[MapStyle], style: StyleId) -> f64 {
+    styles[style.0 as usize]
+        .stroke
+        .as_ref()
+        .map_or(0.0, |(stroke, _)| {
+            0.5 * stroke.width * stroke.miter_limit.max(1.0)
+        })
+}
+
+/// Draw the features of a tile that intersect `area`, in map coordinates.
+fn draw_tile(ctx: &mut RenderContext, styles: &[MapStyle], tile: &MapTile, area: Rect) {
+    for feature in &tile.features {
+        let style = &styles[feature.style.0 as usize];
+        let extent = stroke_extent(styles, feature.style);
+        let bounds = feature.bounds.inflate(extent, extent);
+        if !bounds.overlaps(area) {
+            continue;
+        }
+
+        if let (FeatureKind::Polygon, Some(fill)) = (&feature.kind, &style.fill) {
+            ctx.set_fill_rule(Fill::NonZero);
+            ctx.set_paint(fill.clone());
+            ctx.fill_path(&feature.path);
+        }
+        if let Some((stroke, paint)) = &style.stroke {
+            ctx.set_stroke(stroke.clone());
+            ctx.set_paint(paint.clone());
+            ctx.stroke_path(&feature.path);
+        }
+    }
+}
+
+/// Choose the labels to show, and return them with their bounds in the viewport.
+///
+/// See [`LabelPlacer`] for how overlapping labels are decluttered.
+fn place_labels<'a>(
+    tiles: &[&'a MapTile],
+    view: &MapView,
+    viewport: Rect,
+) -> Vec<(&'a MapLabel, Rect)> {
+    let transform = view.transform();
+    let map_labels: Vec<_> = tiles.iter().flat_map(|tile| &tile.labels).collect();
+    let labels: Vec<_> = map_labels
+        .iter()
+        .enumerate()
+        .map(|(index, label)| {
+            Label::centered(
+                index as u64,
+                transform * label.position,
+                label.size,
+                label.priority,
+            )
+        })
+        .collect();
+
+    LabelPlacer::new(viewport)
+        .place(&labels)
+        .into_iter()
+        .map(|placed| (map_labels[placed.id as usize], placed.bounds))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::palette::css::{BLUE, LIME, RED};
+    use vello_common::kurbo::Shape;
+
+    fn square(x: f64, y: f64, size: f64) -> BezPath {
+        Rect::new(x, y, x + size, y + size).to_path(0.1)
+    }
+
+    fn label(x: f64, y: f64, priority: i32, id: u64) -> MapLabel {
+        MapLabel {
+            position: Point::new(x, y),
+            size: Size::new(20.0, 10.0),
+            priority,
+            id,
+        }
+    }
+
+    fn render(map: &mut VectorMap, view: &MapView) -> (Pixmap, Vec<(u64, Rect)>) {
+        let mut resources = Resources::new();
+        let mut ctx = RenderContext::new(64, 64);
+        let mut labels = Vec::new();
+        map.render(&mut resources, &mut ctx, view, |label, bounds, _| {
+            labels.push((label.id, bounds));
+        });
+        ctx.flush();
+        let mut pixmap = Pixmap::new(64, 64);
+        ctx.render_to_pixmap(&mut resources, &mut pixmap);
+        (pixmap, labels)
+    }
+
+    #[test]
+    fn tiles_are_drawn_in_map_coordinates() {
+        let mut map = VectorMap::new(2, 2, 32);
+        let water = map.add_style(MapStyle::fill(BLUE));
+        let road = map.add_style(MapStyle::stroke(Stroke::new(4.0), RED));
+
+        let mut tile = MapTile::new(4096.0);
+        tile.add_polygon(square(0.0, 0.0, 4096.0), water);
+        map.set_tile(1, 0, tile);
+        let mut tile = MapTile::new(4096.0);
+        let mut line = BezPath::new();
+        line.move_to((0.0, 2048.0));
+        line.line_to((4096.0, 2048.0));
+        tile.add_line(line, road);
+        map.set_tile(0, 1, tile);
+
+        let (pixmap, _) = render(&mut map, &MapView::default());
+        assert_eq!(pixmap.sample(8, 8).a, 0);
+        assert_eq!(pixmap.sample(40, 8).b, 255);
+        assert_eq!(pixmap.sample(8, 48).r, 255);
+        assert_eq!(pixmap.sample(8, 56).a, 0);
+        assert_eq!(map.cached_tiles(), 4);
+
+        // At a zoom of 2, the top left quarter of the map fills the viewport.
+        let (pixmap, _) = render(
+            &mut map,
+            &MapView {
+                origin: Point::new(16.0, 0.0),
+                zoom: 2.0,
+            },
+        );
+        assert_eq!(pixmap.sample(8, 8).a, 0);
+        assert_eq!(pixmap.sample(40, 8).b, 255);
+    }
+
+    #[test]
+    fn replacing_a_tile_only_invalidates_its_area() {
+        let mut map = VectorMap::new(2, 2, 32);
+        let fill = map.add_style(MapStyle::fill(LIME));
+        for (column, row) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            let mut tile = MapTile::new(32.0);
+            tile.add_polygon(square(4.0, 4.0, 24.0), fill);
+            map.set_tile(column, row, tile);
+        }
+        render(&mut map, &MapView::default());
+        assert_eq!(map.cached_tiles(), 4);
+
+        map.set_tile(1, 1, MapTile::new(32.0));
+        assert_eq!(map.cached_tiles(), 3);
+        let (pixmap, _) = render(&mut map, &MapView::default());
+        assert_eq!(pixmap.sample(48, 48).a, 0);
+        assert_eq!(pixmap.sample(16, 48).g, 255);
+
+        map.set_style(fill, MapStyle::fill(RED));
+        assert_eq!(map.cached_tiles(), 0);
+    }
+
+    #[test]
+    fn overlapping_labels_are_decluttered() {
+        let mut map = VectorMap::new(2, 1, 32);
+        let mut tile = MapTile::new(32.0);
+        tile.add_label(label(10.0, 10.0, 0, 0));
+        tile.add_label(label(20.0, 12.0, 5, 1));
+        tile.add_label(label(10.0, 30.0, 0, 2));
+        map.set_tile(0, 0, tile);
+        let mut tile = MapTile::new(32.0);
+        // Touches the second label, but doesn't overlap it.
+        tile.add_label(label(8.0, 12.0, 0, 3));
+        // Outside of the viewport.
+        tile.add_label(label(8.0, 100.0, 9, 4));
+        map.set_tile(1, 0, tile);
+
+        let (_, labels) = render(&mut map, &MapView::default());
+        assert_eq!(
+            labels,
+            [
+                (1, Rect::new(10.0, 7.0, 30.0, 17.0)),
+                (2, Rect::new(0.0, 25.0, 20.0, 35.0)),
+                (3, Rect::new(30.0, 7.0, 50.0, 17.0)),
+            ]
+        );
+    }
+}
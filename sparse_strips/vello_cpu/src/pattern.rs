@@ -0,0 +1,91 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Pattern support for the CPU renderer.
+//!
+//! Patterns are evaluated by replaying their recorded commands into a small, single-threaded
+//! [`RenderContext`] the size of one pattern cell. The resulting pixmap is registered as an
+//! image so that it can be tiled with a repeating image paint.
+
+use crate::kurbo::{Affine, BezPath, Rect, Stroke, Vec2};
+use crate::peniko::Fill;
+use crate::{PaintType, Pixmap, RenderContext, RenderSettings, Resources};
+use alloc::sync::Arc;
+use vello_common::paint::ImageId;
+use vello_common::pattern::{Pattern, PatternSink};
+
+impl PatternSink for RenderContext {
+    #[inline]
+    fn set_transform(&mut self, transform: Affine) {
+        Self::set_transform(self, transform);
+    }
+
+    #[inline]
+    fn set_paint(&mut self, paint: PaintType) {
+        Self::set_paint(self, paint);
+    }
+
+    #[inline]
+    fn set_paint_transform(&mut self, paint_transform: Affine) {
+        Self::set_paint_transform(self, paint_transform);
+    }
+
+    #[inline]
+    fn set_fill_rule(&mut self, fill_rule: Fill) {
+        Self::set_fill_rule(self, fill_rule);
+    }
+
+    #[inline]
+    fn set_stroke(&mut self, stroke: Stroke) {
+        Self::set_stroke(self, stroke);
+    }
+
+    #[inline]
+    fn fill_path(&mut self, path: &BezPath) {
+        Self::fill_path(self, path);
+    }
+
+    #[inline]
+    fn stroke_path(&mut self, path: &BezPath) {
+        Self::stroke_path(self, path);
+    }
+
+    #[inline]
+    fn fill_rect(&mut self, rect: &Rect) {
+        Self::fill_rect(self, rect);
+    }
+
+    #[inline]
+    fn push_clip_path(&mut self, path: &BezPath) {
+        Self::push_clip_path(self, path);
+    }
+
+    #[inline]
+    fn pop_clip_path(&mut self) {
+        Self::pop_clip_path(self);
+    }
+}
+
+/// Pattern registry implementation.
+impl Resources {
+    /// Rasterize a single cell of `pattern` and register it as an image.
+    ///
+    /// The returned [`ImageId`] can be passed to [`Pattern::paint`] (via
+    /// `ImageSource::OpaqueId`) to tile the pattern. The image can be released again with
+    /// [`Resources::destroy_image`].
+    pub fn register_pattern(&mut self, pattern: &Pattern) -> ImageId {
+        let (width, height) = pattern.cell_size();
+        let settings = RenderSettings {
+            num_threads: 0,
+            ..RenderSettings::default()
+        };
+
+        let mut ctx = RenderContext::new_with(width, height, settings);
+        pattern.replay(&mut ctx, Vec2::ZERO);
+
+        let mut pixmap = Pixmap::new(width, height);
+        ctx.composite_to_pixmap_at_offset(self, &mut pixmap, 0, 0);
+
+        self.register_image(Arc::new(pixmap))
+    }
+}
@@ -0,0 +1,343 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The pixel formats of render targets, and conversions of RGBA pixels into them.
+
+use crate::color::{ColorSpace, Srgb};
+
+/// The layout of the pixels in a render target.
+///
+/// In all formats, the color channels are premultiplied with alpha.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// Red, green, blue, alpha, with one byte per channel.
+    #[default]
+    Rgba8,
+    /// Blue, green, red, alpha, with one byte per channel, as expected by many OS
+    /// compositors, for example for Windows DIBs or the `ARGB8888` format of Wayland on
+    /// little-endian machines.
+    Bgra8,
+    /// 16-bit little-endian pixels with 5 bits of red, 6 bits of green and 5 bits of blue,
+    /// from the most to the least significant bit, as used by many embedded displays.
+    ///
+    /// There is no alpha channel, so the pixels show the rendered image over black.
+    Rgb565 {
+        /// Whether to apply ordered dithering, which hides the banding of smooth gradients.
+        dither: bool,
+    },
+    /// 16-bit little-endian pixels with 1 bit of alpha, followed by 5 bits each of red,
+    /// green and blue.
+    ///
+    /// The alpha bit is set for pixels that are at least half opaque, which are stored with
+    /// their unpremultiplied color.
+    Argb1555 {
+        /// Whether to apply ordered dithering, which hides the banding of smooth gradients.
+        dither: bool,
+    },
+    /// 8-bit pixels with only the coverage of the scene, its alpha.
+    ///
+    /// Paints aren't evaluated when rendering to this format, every path is drawn as if it
+    /// was filled with opaque white. Blend modes, opacities and masks of layers still apply.
+    A8,
+    /// Red, green, blue, alpha, with a native-endian `f32` per channel, in linear light.
+    ///
+    /// When rendering to this format, colors are composited in linear light instead of
+    /// sRGB, and the results are stored without rounding them to 8 bits. Use
+    /// [`rgba32_float_to_rgba8`] to convert the results to 8-bit sRGB at the end.
+    Rgba32Float,
+}
+
+impl PixelFormat {
+    /// The number of bytes that each pixel takes up.
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            Self::Rgba8 | Self::Bgra8 => 4,
+            Self::Rgb565 { .. } | Self::Argb1555 { .. } => 2,
+            Self::A8 => 1,
+            Self::Rgba32Float => 16,
+        }
+    }
+
+    /// Whether colors are composited in linear light instead of sRGB.
+    pub(crate) fn is_linear(self) -> bool {
+        self == Self::Rgba32Float
+    }
+
+    /// Whether only the coverage of the scene is stored, so paints don't need to be evaluated.
+    pub(crate) fn is_coverage_only(self) -> bool {
+        self == Self::A8
+    }
+}
+
+/// A 4x4 Bayer matrix, with thresholds for quantizing a channel from 8 bits, scaled to `0..255`.
+const BAYER_THRESHOLDS: [[u16; 4]; 4] = {
+    const BAYER: [[u16; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+    let mut thresholds = [[0; 4]; 4];
+    let mut y = 0;
+    while y < 4 {
+        let mut x = 0;
+        while x < 4 {
+            thresholds[y][x] = (BAYER[y][x] * 2 + 1) * 255 / 32;
+            x += 1;
+        }
+        y += 1;
+    }
+    thresholds
+};
+
+/// The threshold for rounding a channel at the pixel `(x, y)` of the target.
+#[inline(always)]
+pub(crate) fn threshold(dither: bool, x: usize, y: usize) -> u16 {
+    if dither {
+        BAYER_THRESHOLDS[y % 4][x % 4]
+    } else {
+        127
+    }
+}
+
+/// Reduce an 8-bit channel to `max + 1` levels, rounding up if the fraction exceeds `threshold`.
+#[inline(always)]
+fn quantize(value: u8, max: u16, threshold: u16) -> u16 {
+    (u16::from(value) * max + threshold) / 255
+}
+
+/// Expand a channel with `max + 1` levels to 8 bits.
+#[inline(always)]
+fn expand(value: u16, max: u16) -> u8 {
+    ((value * 255 + max / 2) / max) as u8
+}
+
+/// Encode a premultiplied RGBA pixel as RGB565.
+#[inline(always)]
+pub(crate) fn encode_rgb565([r, g, b, _]: [u8; 4], threshold: u16) -> [u8; 2] {
+    let pixel = (quantize(r, 31, threshold) << 11)
+        | (quantize(g, 63, threshold) << 5)
+        | quantize(b, 31, threshold);
+    pixel.to_le_bytes()
+}
+
+/// Decode an RGB565 pixel into premultiplied RGBA.
+#[inline(always)]
+pub(crate) fn decode_rgb565(pixel: [u8; 2]) -> [u8; 4] {
+    let pixel = u16::from_le_bytes(pixel);
+    [
+        expand(pixel >> 11, 31),
+        expand((pixel >> 5) & 0x3f, 63),
+        expand(pixel & 0x1f, 31),
+        255,
+    ]
+}
+
+/// Encode a premultiplied RGBA pixel as ARGB1555.
+#[inline(always)]
+pub(crate) fn encode_argb1555(pixel: [u8; 4], threshold: u16) -> [u8; 2] {
+    if pixel[3] < 128 {
+        return [0; 2];
+    }
+
+    // The pixel is stored as opaque, so the color must not be darkened by its alpha.
+    let [r, g, b, _] = unpremultiply(pixel);
+    let pixel = (1 << 15)
+        | (quantize(r, 31, threshold) << 10)
+        | (quantize(g, 31, threshold) << 5)
+        | quantize(b, 31, threshold);
+    pixel.to_le_bytes()
+}
+
+/// Decode an ARGB1555 pixel into premultiplied RGBA.
+#[inline(always)]
+pub(crate) fn decode_argb1555(pixel: [u8; 2]) -> [u8; 4] {
+    let pixel = u16::from_le_bytes(pixel);
+    // A transparent pixel has to be black to be valid premultiplied RGBA.
+    if pixel >> 15 == 0 {
+        return [0; 4];
+    }
+
+    [
+        expand((pixel >> 10) & 0x1f, 31),
+        expand((pixel >> 5) & 0x1f, 31),
+        expand(pixel & 0x1f, 31),
+        255,
+    ]
+}
+
+/// Encode a premultiplied RGBA pixel as A8.
+#[inline(always)]
+pub(crate) fn encode_a8([_, _, _, a]: [u8; 4], _threshold: u16) -> [u8; 1] {
+    [a]
+}
+
+/// Decode an A8 pixel into premultiplied RGBA, as opaque white with the given alpha.
+#[inline(always)]
+pub(crate) fn decode_a8([a]: [u8; 1]) -> [u8; 4] {
+    [a; 4]
+}
+
+/// Convert a premultiplied RGBA pixel to straight alpha.
+#[inline(always)]
+pub(crate) fn unpremultiply([r, g, b, a]: [u8; 4]) -> [u8; 4] {
+    if a == 0 {
+        return [0; 4];
+    }
+
+    let alpha = u16::from(a);
+    let unpremultiply = |c: u8| ((u16::from(c) * 255 + alpha / 2) / alpha).min(255) as u8;
+    [unpremultiply(r), unpremultiply(g), unpremultiply(b), a]
+}
+
+/// Convert an RGBA pixel with straight alpha to premultiplied alpha.
+#[inline(always)]
+pub(crate) fn premultiply([r, g, b, a]: [u8; 4]) -> [u8; 4] {
+    let premultiply = |c: u8| ((u16::from(c) * u16::from(a) + 127) / 255) as u8;
+    [premultiply(r), premultiply(g), premultiply(b), a]
+}
+
+/// Convert a premultiplied pixel in linear light to premultiplied RGBA8 in sRGB.
+#[inline(always)]
+pub(crate) fn linear_to_rgba8(pixel: [f32; 4]) -> [u8; 4] {
+    linear_to_srgb(pixel).map(|c| (c * 255.0 + 0.5) as u8)
+}
+
+/// Convert a premultiplied RGBA8 pixel in sRGB to linear light.
+#[inline(always)]
+pub(crate) fn rgba8_to_linear(pixel: [u8; 4]) -> [f32; 4] {
+    srgb_to_linear(pixel.map(|c| f32::from(c) / 255.0))
+}
+
+/// Convert a premultiplied pixel in sRGB to linear light.
+#[inline(always)]
+pub(crate) fn srgb_to_linear([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    if a <= 0.0 {
+        return [0.0; 4];
+    }
+
+    let [r, g, b] = Srgb::to_linear_srgb([r / a, g / a, b / a]);
+    [r * a, g * a, b * a, a]
+}
+
+/// Convert a premultiplied pixel in linear light to sRGB.
+#[inline(always)]
+pub(crate) fn linear_to_srgb([r, g, b, a]: [f32; 4]) -> [f32; 4] {
+    if a <= 0.0 {
+        return [0.0; 4];
+    }
+
+    let [r, g, b] = Srgb::from_linear_srgb([r / a, g / a, b / a]);
+    [r * a, g * a, b * a, a]
+}
+
+/// Convert a buffer in the [`Rgba32Float`](PixelFormat::Rgba32Float) format into premultiplied
+/// RGBA8 in sRGB.
+///
+/// # Panics
+///
+/// Panics if the buffers don't hold the same number of pixels.
+pub fn rgba32_float_to_rgba8(src: &[u8], dst: &mut [u8]) {
+    assert_eq!(
+        src.len() / 16,
+        dst.len() / 4,
+        "the buffers don't hold the same number of pixels"
+    );
+
+    for (src, dst) in src.chunks_exact(16).zip(dst.chunks_exact_mut(4)) {
+        let mut pixel = [0.0; 4];
+        for (c, src) in pixel.iter_mut().zip(src.chunks_exact(4)) {
+            *c = f32::from_ne_bytes(src.try_into().unwrap());
+        }
+        dst.copy_from_slice(&linear_to_rgba8(pixel));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_argb1555, decode_rgb565, encode_argb1555, encode_rgb565, linear_to_rgba8,
+        premultiply, rgba8_to_linear, threshold, unpremultiply,
+    };
+
+    #[test]
+    fn rgb565_roundtrips_representable_colors() {
+        for pixel in [0_u16, 0xffff, 0xf800, 0x07e0, 0x001f, 0x1234] {
+            let rgba = decode_rgb565(pixel.to_le_bytes());
+            assert_eq!(
+                encode_rgb565(rgba, threshold(false, 0, 0)),
+                pixel.to_le_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn argb1555_roundtrips_representable_colors() {
+        for pixel in [0_u16, 0xffff, 0xfc00, 0x83e0, 0x801f, 0x9234] {
+            let rgba = decode_argb1555(pixel.to_le_bytes());
+            assert_eq!(
+                encode_argb1555(rgba, threshold(false, 0, 0)),
+                pixel.to_le_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn argb1555_stores_anti_aliased_pixels_with_straight_color() {
+        // An edge pixel of an orange shape, covering three quarters of the pixel.
+        let edge = premultiply([255, 128, 0, 192]);
+        let decoded = decode_argb1555(encode_argb1555(edge, threshold(false, 0, 0)));
+        assert_eq!(decoded[3], 255);
+        for (decoded, expected) in decoded.into_iter().zip([255_u8, 128, 0]) {
+            assert!(decoded.abs_diff(expected) <= 255 / 31 / 2 + 1);
+        }
+
+        // Pixels covering less than half of their area are dropped.
+        let edge = premultiply([255, 128, 0, 64]);
+        assert_eq!(
+            decode_argb1555(encode_argb1555(edge, threshold(false, 0, 0))),
+            [0; 4]
+        );
+    }
+
+    #[test]
+    fn dithering_averages_to_the_original_value() {
+        // 100 lies between two levels of a 5-bit channel, so the pattern mixes both.
+        let value = 100_u8;
+        let sum: u32 = (0..4)
+            .flat_map(|y| (0..4).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let pixel =
+                    u16::from_le_bytes(encode_rgb565([value, 0, 0, 255], threshold(true, x, y)));
+                u32::from(pixel >> 11)
+            })
+            .sum();
+
+        let average = sum as f32 / 16.0 * 255.0 / 31.0;
+        assert!((average - f32::from(value)).abs() < 255.0 / 31.0 / 16.0 + 0.5);
+    }
+
+    #[test]
+    fn straight_alpha_roundtrips_premultiplied_colors() {
+        for a in 0..=255_u8 {
+            for c in 0..=a {
+                let pixel = [c, a / 2, 0, a];
+                assert_eq!(premultiply(unpremultiply(pixel)), pixel);
+            }
+        }
+        assert_eq!(unpremultiply([0, 0, 0, 0]), [0; 4]);
+        assert_eq!(unpremultiply([64, 32, 0, 128]), [128, 64, 0, 128]);
+    }
+
+    #[test]
+    fn linear_light_roundtrips_rgba8() {
+        for pixel in [
+            [0, 0, 0, 0],
+            [255, 255, 255, 255],
+            [188, 128, 0, 255],
+            [64, 10, 0, 128],
+        ] {
+            assert_eq!(linear_to_rgba8(rgba8_to_linear(pixel)), pixel);
+        }
+
+        // Mid-gray in sRGB is darker in linear light.
+        let [red, ..] = rgba8_to_linear([128, 128, 128, 255]);
+        assert!((red - 0.216).abs() < 0.001, "{red}");
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Presentation of pixmaps on windows through [softbuffer].
+//!
+//! [`present_pixmap`] copies a rendered [`Pixmap`] into the buffer of a softbuffer
+//! [`Surface`], converting the pixels to the format that softbuffer expects, and presents
+//! it. If the parts of the pixmap that changed since the previous frame are known, for
+//! example from [`VirtualList::damage`](crate::VirtualList::damage), only those parts are
+//! copied and presented.
+
+use alloc::vec::Vec;
+use core::num::NonZeroU32;
+use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
+use softbuffer::{Rect, SoftBufferError, Surface};
+use vello_common::geometry::RectU16;
+use vello_common::peniko::color::PremulRgba8;
+use vello_common::pixmap::Pixmap;
+
+/// Present `pixmap` on the window of `surface`.
+///
+/// The surface is resized to the size of the pixmap. `damage` lists the parts of the pixmap
+/// that changed since the previously presented frame, or is `None` if all of it may have
+/// changed. Parts of the damage outside of the pixmap are ignored. If the buffer of the
+/// surface doesn't hold the previous frame, the whole pixmap is copied regardless of the
+/// damage.
+///
+/// Windows are opaque, so pixels that are not fully opaque are presented as if they were
+/// composited onto black. Nothing is presented for an empty pixmap.
+pub fn present_pixmap<D: HasDisplayHandle, W: HasWindowHandle>(
+    surface: &mut Surface<D, W>,
+    pixmap: &Pixmap,
+    damage: Option<&[RectU16]>,
+) -> Result<(), SoftBufferError> {
+    let (Some(width), Some(height)) = (
+        NonZeroU32::new(u32::from(pixmap.width())),
+        NonZeroU32::new(u32::from(pixmap.height())),
+    ) else {
+        return Ok(());
+    };
+    surface.resize(width, height)?;
+
+    let bounds = RectU16::new(0, 0, pixmap.width(), pixmap.height());
+    let damage: Option<Vec<RectU16>> = damage.map(|damage| {
+        damage
+            .iter()
+            .map(|rect| rect.intersect(bounds))
+            .filter(|rect| !rect.is_empty())
+            .collect()
+    });
+
+    let mut buffer = surface.buffer_mut()?;
+    // An age of 1 means that the buffer holds the previously presented frame, so only the
+    // damaged parts need to be updated.
+    match &damage {
+        Some(damage) if buffer.age() == 1 => {
+            for rect in damage {
+                copy_region(pixmap, &mut buffer, *rect);
+            }
+        }
+        _ => copy_region(pixmap, &mut buffer, bounds),
+    }
+
+    match damage {
+        Some(damage) => {
+            let rects: Vec<Rect> = damage
+                .iter()
+                .map(|rect| Rect {
+                    x: u32::from(rect.x0),
+                    y: u32::from(rect.y0),
+                    width: NonZeroU32::new(u32::from(rect.width())).unwrap(),
+                    height: NonZeroU32::new(u32::from(rect.height())).unwrap(),
+                })
+                .collect();
+            buffer.present_with_damage(&rects)
+        }
+        None => buffer.present(),
+    }
+}
+
+/// Copy `rect` of `pixmap` into `buffer`, which has the same size as the pixmap.
+fn copy_region(pixmap: &Pixmap, buffer: &mut [u32], rect: RectU16) {
+    let stride = usize::from(pixmap.width());
+    let columns = usize::from(rect.x0)..usize::from(rect.x1);
+
+    for y in usize::from(rect.y0)..usize::from(rect.y1) {
+        let row = y * stride;
+        let src = &pixmap.data()[row..][columns.clone()];
+        let dst = &mut buffer[row..][columns.clone()];
+        for (dst, src) in dst.iter_mut().zip(src) {
+            *dst = to_xrgb(*src);
+        }
+    }
+}
+
+/// Convert a pixel into the `0RGB` format of softbuffer, where the red component is stored in
+/// bits 16 to 23 of the native integer.
+fn to_xrgb(pixel: PremulRgba8) -> u32 {
+    (u32::from(pixel.r) << 16) | (u32::from(pixel.g) << 8) | u32::from(pixel.b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::copy_region;
+    use alloc::vec;
+    use vello_common::geometry::RectU16;
+    use vello_common::peniko::color::PremulRgba8;
+    use vello_common::pixmap::Pixmap;
+
+    #[test]
+    fn only_damaged_regions_are_copied() {
+        let pixel = PremulRgba8 {
+            r: 0x11,
+            g: 0x22,
+            b: 0x33,
+            a: 0xff,
+        };
+        let pixmap = Pixmap::from_parts(vec![pixel; 4 * 3], 4, 3);
+        let mut buffer = vec![0; 4 * 3];
+
+        copy_region(&pixmap, &mut buffer, RectU16::new(1, 1, 3, 3));
+
+        #[rustfmt::skip]
+        let expected = [
+            0, 0, 0, 0,
+            0, 0x112233, 0x112233, 0,
+            0, 0x112233, 0x112233, 0,
+        ];
+        assert_eq!(buffer, expected);
+    }
+}
@@ -0,0 +1,260 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Cached, scaled-down renders of scenes.
+//!
+//! Editor applications often show many small previews at once, for example in asset browsers
+//! or layer panels, while only a few of the underlying scenes change between frames. A
+//! [`PreviewCache`] renders each scene into a pixmap of the requested size and keeps the
+//! result around until the scene changes or the cache runs out of space.
+
+use alloc::sync::Arc;
+use hashbrown::HashMap;
+use vello_common::kurbo::{Affine, Size};
+use vello_common::pixmap::Pixmap;
+
+use crate::{RenderContext, RenderSettings, Resources};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct PreviewKey {
+    scene_hash: u64,
+    width: u16,
+    height: u16,
+}
+
+#[derive(Debug)]
+struct CachedPreview {
+    pixmap: Arc<Pixmap>,
+    last_used: u64,
+}
+
+/// A cache of scaled-down scene renders.
+///
+/// Scenes are identified by a hash that is provided by the caller, which should change
+/// whenever the content of the scene changes. Previews are keyed by that hash together with
+/// their size, so the same scene can be cached at several sizes at once.
+///
+/// Once more than `max_entries` previews are cached, the least recently used ones are
+/// evicted.
+#[derive(Debug)]
+pub struct PreviewCache {
+    entries: HashMap<PreviewKey, CachedPreview>,
+    max_entries: usize,
+    epoch: u64,
+    settings: RenderSettings,
+    // Reused between renders of the same size.
+    ctx: Option<RenderContext>,
+}
+
+impl PreviewCache {
+    /// Create a new cache that holds at most `max_entries` previews.
+    pub fn new(max_entries: usize) -> Self {
+        Self::new_with(max_entries, RenderSettings::default())
+    }
+
+    /// Create a new cache that renders previews with specific settings.
+    pub fn new_with(max_entries: usize, settings: RenderSettings) -> Self {
+        Self {
+            entries: HashMap::new(),
+            max_entries,
+            epoch: 0,
+            settings,
+            ctx: None,
+        }
+    }
+
+    /// Return the cached preview of the given scene and size, if there is one.
+    pub fn get(&mut self, scene_hash: u64, width: u16, height: u16) -> Option<Arc<Pixmap>> {
+        let key = PreviewKey {
+            scene_hash,
+            width,
+            height,
+        };
+        self.epoch += 1;
+        let entry = self.entries.get_mut(&key)?;
+        entry.last_used = self.epoch;
+
+        Some(entry.pixmap.clone())
+    }
+
+    /// Return the preview of the given scene and size, rendering it if it isn't cached yet.
+    ///
+    /// `scene_size` is the size of the scene's content in its own coordinate system. It is
+    /// scaled uniformly to fit into `width` x `height` and centered, see [`fit_transform`].
+    ///
+    /// `draw` is only called if the preview needs to be rendered. The render context passed
+    /// to it already has the fitting transform set, so draw calls that change the transform
+    /// should concatenate with [`RenderContext::transform`] instead of replacing it.
+    pub fn get_or_render(
+        &mut self,
+        resources: &mut Resources,
+        scene_hash: u64,
+        scene_size: Size,
+        width: u16,
+        height: u16,
+        draw: impl FnOnce(&mut RenderContext),
+    ) -> Arc<Pixmap> {
+        if let Some(pixmap) = self.get(scene_hash, width, height) {
+            return pixmap;
+        }
+
+        let pixmap = Arc::new(self.render(resources, scene_size, width, height, draw));
+        self.entries.insert(
+            PreviewKey {
+                scene_hash,
+                width,
+                height,
+            },
+            CachedPreview {
+                pixmap: pixmap.clone(),
+                last_used: self.epoch,
+            },
+        );
+        self.trim(self.max_entries);
+
+        pixmap
+    }
+
+    /// Remove all cached previews of the given scene, regardless of their size.
+    ///
+    /// Returns whether any previews were removed.
+    pub fn invalidate(&mut self, scene_hash: u64) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|key, _| key.scene_hash != scene_hash);
+
+        self.entries.len() != len
+    }
+
+    /// Evict the least recently used previews until at most `max_entries` remain.
+    pub fn trim(&mut self, max_entries: usize) {
+        while self.entries.len() > max_entries {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| *key)
+                .unwrap();
+            self.entries.remove(&oldest);
+        }
+    }
+
+    /// Return the number of cached previews.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove all cached previews.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn render(
+        &mut self,
+        resources: &mut Resources,
+        scene_size: Size,
+        width: u16,
+        height: u16,
+        draw: impl FnOnce(&mut RenderContext),
+    ) -> Pixmap {
+        let ctx = match &mut self.ctx {
+            Some(ctx) if ctx.width() == width && ctx.height() == height => {
+                ctx.reset();
+                ctx
+            }
+            ctx => ctx.insert(RenderContext::new_with(width, height, self.settings)),
+        };
+
+        ctx.set_transform(fit_transform(scene_size, width, height));
+        draw(ctx);
+        ctx.flush();
+
+        let mut pixmap = Pixmap::new(width, height);
+        ctx.render_to_pixmap(resources, &mut pixmap);
+
+        pixmap
+    }
+}
+
+/// Return the transform that scales content of size `scene_size` uniformly to fit into
+/// `width` x `height` and centers it.
+///
+/// Empty scenes are mapped with the identity transform.
+pub fn fit_transform(scene_size: Size, width: u16, height: u16) -> Affine {
+    if scene_size.is_zero_area() || !scene_size.is_finite() {
+        return Affine::IDENTITY;
+    }
+
+    let width = f64::from(width);
+    let height = f64::from(height);
+    let scale = (width / scene_size.width).min(height / scene_size.height);
+    let offset_x = (width - scene_size.width * scale) / 2.0;
+    let offset_y = (height - scene_size.height * scale) / 2.0;
+
+    Affine::translate((offset_x, offset_y)) * Affine::scale(scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::palette::css::REBECCA_PURPLE;
+    use vello_common::kurbo::{Point, Rect};
+
+    fn draw_square(ctx: &mut RenderContext) {
+        ctx.set_paint(REBECCA_PURPLE);
+        ctx.fill_rect(&Rect::new(0.0, 0.0, 100.0, 100.0));
+    }
+
+    #[test]
+    fn fit_transform_centers_content() {
+        let transform = fit_transform(Size::new(200.0, 100.0), 50, 50);
+
+        assert_eq!(transform * Point::ZERO, Point::new(0.0, 12.5));
+        assert_eq!(transform * Point::new(200.0, 100.0), Point::new(50.0, 37.5));
+        assert_eq!(fit_transform(Size::ZERO, 50, 50), Affine::IDENTITY);
+    }
+
+    #[test]
+    fn previews_are_cached_per_scene_and_size() {
+        let mut resources = Resources::new();
+        let mut cache = PreviewCache::new(8);
+        let size = Size::new(100.0, 100.0);
+
+        let first = cache.get_or_render(&mut resources, 1, size, 16, 16, draw_square);
+        let second = cache.get_or_render(&mut resources, 1, size, 16, 16, |_| {
+            panic!("preview should have been cached")
+        });
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(first.sample(8, 8).a, 255);
+
+        cache.get_or_render(&mut resources, 1, size, 8, 8, draw_square);
+        cache.get_or_render(&mut resources, 2, size, 16, 16, draw_square);
+        assert_eq!(cache.len(), 3);
+
+        assert!(cache.invalidate(1));
+        assert!(!cache.invalidate(1));
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get(2, 16, 16).is_some());
+    }
+
+    #[test]
+    fn least_recently_used_previews_are_evicted() {
+        let mut resources = Resources::new();
+        let mut cache = PreviewCache::new(2);
+        let size = Size::new(100.0, 100.0);
+
+        cache.get_or_render(&mut resources, 1, size, 4, 4, draw_square);
+        cache.get_or_render(&mut resources, 2, size, 4, 4, draw_square);
+        cache.get(1, 4, 4);
+        cache.get_or_render(&mut resources, 3, size, 4, 4, draw_square);
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(1, 4, 4).is_some());
+        assert!(cache.get(2, 4, 4).is_none());
+        assert!(cache.get(3, 4, 4).is_some());
+    }
+}
@@ -0,0 +1,44 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Recording support for the CPU renderer.
+
+use crate::RenderContext;
+use crate::kurbo::{Affine, BezPath, Rect};
+use crate::peniko::BlendMode;
+use vello_common::recording::{Recording, RecordingSink};
+
+impl RecordingSink for RenderContext {
+    #[inline]
+    fn stroke_rect(&mut self, rect: &Rect) {
+        Self::stroke_rect(self, rect);
+    }
+
+    #[inline]
+    fn push_layer(
+        &mut self,
+        clip_path: Option<&BezPath>,
+        blend_mode: Option<BlendMode>,
+        opacity: Option<f32>,
+    ) {
+        Self::push_layer(self, clip_path, blend_mode, opacity, None, None);
+    }
+
+    #[inline]
+    fn pop_layer(&mut self) {
+        Self::pop_layer(self);
+    }
+}
+
+impl RenderContext {
+    /// Draw the commands of `recording`, with `transform` applied on top of the recorded
+    /// transforms.
+    ///
+    /// The current render state is restored afterwards, and all clips and layers that the
+    /// recording pushes are popped again.
+    pub fn append_recording(&mut self, recording: &Recording, transform: Affine) {
+        let state = self.save_current_state();
+        recording.replay(self, transform);
+        self.restore_state(state);
+    }
+}
@@ -3,21 +3,66 @@
 
 //! Splitting a single mutable buffer into regions that can be accessed concurrently.
 
+use crate::PixelFormat;
 use crate::fine::COLOR_COMPONENTS;
+use crate::pixel_format;
+#[cfg(feature = "multithreading")]
+use alloc::boxed::Box;
 use alloc::vec::Vec;
+use vello_common::alpha_mode::AlphaMode;
 use vello_common::coarse::WideTile;
+#[cfg(feature = "multithreading")]
+use vello_common::executor::{Executor, Task};
 use vello_common::pixmap::Pixmap;
 use vello_common::tile::Tile;
 
 #[derive(Debug)]
 pub struct Regions<'a> {
     regions: Vec<Region<'a>>,
+    /// The number of regions in each row.
+    row_len: usize,
 }
 
 impl<'a> Regions<'a> {
     /// Creates regions from a buffer where the buffer dimensions match the render dimensions.
     pub fn new(width: u16, height: u16, buffer: &'a mut [u8]) -> Self {
-        Self::new_at_offset(width, height, 0, 0, width, height, buffer)
+        Self::new_with_format(
+            width,
+            height,
+            usize::from(width) * COLOR_COMPONENTS,
+            0,
+            PixelFormat::Rgba8,
+            AlphaMode::Premultiplied,
+            buffer,
+        )
+    }
+
+    /// Creates regions from a buffer whose pixels are in the given format and alpha mode,
+    /// with rows that are `stride` bytes apart.
+    ///
+    /// The buffer starts at the row of wide tiles `first_row` of the target.
+    pub fn new_with_format(
+        width: u16,
+        height: u16,
+        stride: usize,
+        first_row: u16,
+        pixel_format: PixelFormat,
+        alpha_mode: AlphaMode,
+        buffer: &'a mut [u8],
+    ) -> Self {
+        Self::from_buffer(
+            width,
+            height,
+            0,
+            0,
+            width,
+            height,
+            stride,
+            first_row,
+            pixel_format,
+            alpha_mode,
+            buffer,
+        )
     }
 
     /// Creates regions from a buffer at a specific offset.
@@ -41,8 +86,38 @@ impl<'a> Regions<'a> {
         dst_y: u16,
         dst_buffer_width: u16,
         dst_buffer_height: u16,
+        buffer: &'a mut [u8],
+    ) -> Self {
+        Self::from_buffer(
+            width,
+            height,
+            dst_x,
+            dst_y,
+            dst_buffer_width,
+            dst_buffer_height,
+            usize::from(dst_buffer_width) * COLOR_COMPONENTS,
+            0,
+            PixelFormat::Rgba8,
+            AlphaMode::Premultiplied,
+            buffer,
+        )
+    }
+
+    fn from_buffer(
+        width: u16,
+        height: u16,
+        dst_x: u16,
+        dst_y: u16,
+        dst_buffer_width: u16,
+        dst_buffer_height: u16,
+        row_stride: usize,
+        first_row: u16,
+        pixel_format: PixelFormat,
+        alpha_mode: AlphaMode,
         mut buffer: &'a mut [u8],
     ) -> Self {
+        let bytes_per_pixel = pixel_format.bytes_per_pixel();
+
         // Calculate effective render area (clamped to destination bounds)
         let effective_width = width.min(dst_buffer_width.saturating_sub(dst_x)) as usize;
         let effective_height = height.min(dst_buffer_height.saturating_sub(dst_y)) as usize;
@@ -50,6 +125,7 @@ impl<'a> Regions<'a> {
         if effective_width == 0 || effective_height == 0 {
             return Self {
                 regions: Vec::new(),
+                row_len: 0,
             };
         }
 
@@ -58,11 +134,10 @@ impl<'a> Regions<'a> {
 
         let mut regions = Vec::with_capacity(width_regions * height_regions);
 
-        let row_stride = dst_buffer_width as usize * COLOR_COMPONENTS;
-        let render_row_bytes = effective_width * COLOR_COMPONENTS;
+        let render_row_bytes = effective_width * bytes_per_pixel;
 
         // Calculate starting offset in the buffer
-        let start_offset = (dst_y as usize * row_stride) + (dst_x as usize * COLOR_COMPONENTS);
+        let start_offset = (dst_y as usize * row_stride) + (dst_x as usize * bytes_per_pixel);
         buffer = &mut buffer[start_offset..];
 
         let mut next_lines: [&'a mut [u8]; Tile::HEIGHT as usize] =
@@ -92,7 +167,7 @@ impl<'a> Regions<'a> {
 
                 let base_x = x * WideTile::WIDTH as usize;
                 let region_width_bytes =
-                    ((WideTile::WIDTH as usize).min(effective_width - base_x)) * COLOR_COMPONENTS;
+                    ((WideTile::WIDTH as usize).min(effective_width - base_x)) * bytes_per_pixel;
 
                 for h in 0..region_height {
                     let next = core::mem::take(&mut next_lines[h]);
@@ -101,27 +176,45 @@ impl<'a> Regions<'a> {
                     next_lines[h] = tail;
                 }
 
-                regions.push(Region::new(
+                let mut region = Region::new(
                     areas,
                     u16::try_from(x).unwrap(),
-                    u16::try_from(y).unwrap(),
-                    (region_width_bytes / COLOR_COMPONENTS) as u16,
+                    first_row + u16::try_from(y).unwrap(),
+                    (region_width_bytes / bytes_per_pixel) as u16,
                     region_height as u16,
-                ));
+                );
+                region.pixel_format = pixel_format;
+                region.alpha_mode = alpha_mode;
+                regions.push(region);
             }
         }
 
-        Self { regions }
+        Self {
+            regions,
+            row_len: width_regions,
+        }
+    }
+
+    /// The number of rows of regions.
+    pub fn rows(&self) -> usize {
+        self.regions.len().checked_div(self.row_len).unwrap_or(0)
     }
 
-    /// Apply the given function to each region. The functions will be applied
-    /// in parallel in the current threadpool.
+    /// Apply the given function to each region. The regions are independent of each other
+    /// and are processed in parallel by `executor`.
     #[cfg(feature = "multithreading")]
-    pub fn update_regions_par(&mut self, func: impl Fn(&mut Region<'_>) + Send + Sync) {
-        use rayon::iter::ParallelIterator;
-        use rayon::prelude::IntoParallelRefMutIterator;
-
-        self.regions.par_iter_mut().for_each(func);
+    pub fn update_regions_par(
+        &mut self,
+        executor: &dyn Executor,
+        func: impl Fn(&mut Region<'_>) + Send + Sync,
+    ) {
+        let func = &func;
+        let tasks = self
+            .regions
+            .iter_mut()
+            .map(|region| Box::new(move || func(region)) as Task<'_>)
+            .collect();
+        executor.join(tasks);
     }
 
     /// Apply the given function to each region.
@@ -130,6 +223,12 @@ impl<'a> Regions<'a> {
     }
 }
 
+/// The length of a row of the RGBA scratch buffer that regions are rendered into first, if
+/// their pixels have to be converted.
+const SCRATCH_ROW_LEN: usize = WideTile::WIDTH as usize * COLOR_COMPONENTS;
+/// The length of the RGBA scratch buffer for regions whose pixels have to be converted.
+const SCRATCH_LEN: usize = SCRATCH_ROW_LEN * Tile::HEIGHT as usize;
+
 /// A rectangular region containing the pixels from one wide tile.
 ///
 /// For wide tiles at the right/bottom edge, it might contain less pixels
@@ -143,6 +242,10 @@ pub struct Region<'a> {
     pub(crate) y: u16,
     pub width: u16,
     pub height: u16,
+    /// The pixel format of the buffer the region points into.
+    pub(crate) pixel_format: PixelFormat,
+    /// The alpha mode of the buffer the region points into.
+    pub(crate) alpha_mode: AlphaMode,
     areas: [&'a mut [u8]; Tile::HEIGHT as usize],
 }
 
@@ -160,6 +263,8 @@ impl<'a> Region<'a> {
             y,
             width,
             height,
+            pixel_format: PixelFormat::Rgba8,
+            alpha_mode: AlphaMode::Premultiplied,
         }
     }
 
@@ -233,6 +338,198 @@ impl<'a> Region<'a> {
         ))
     }
 
+    /// Write RGBA pixels into the region with `write`, converting them to the pixel format
+    /// and alpha mode of the region.
+    pub(crate) fn write_rgba(&mut self, write: impl FnOnce(&mut Region<'_>)) {
+        if self.alpha_mode == AlphaMode::Premultiplied {
+            match self.pixel_format {
+                PixelFormat::Rgba8 => return write(self),
+                PixelFormat::Bgra8 => {
+                    write(self);
+                    return self.swap_red_blue();
+                }
+                _ => {}
+            }
+        }
+
+        let mut scratch = [0; SCRATCH_LEN];
+        write(&mut self.scratch_region(&mut scratch));
+        if self.pixel_format == PixelFormat::Rgba32Float {
+            return self.write_f32(|x, y| {
+                let start = y * SCRATCH_ROW_LEN + x * COLOR_COMPONENTS;
+                pixel_format::rgba8_to_linear(scratch[start..][..4].try_into().unwrap())
+            });
+        }
+        if self.alpha_mode == AlphaMode::Straight {
+            for pixel in scratch.chunks_exact_mut(COLOR_COMPONENTS) {
+                let straight = pixel_format::unpremultiply(pixel.try_into().unwrap());
+                pixel.copy_from_slice(&straight);
+            }
+        }
+        match self.pixel_format {
+            PixelFormat::Rgba8 => self.encode(&scratch, false, |pixel, _| pixel),
+            PixelFormat::Bgra8 => self.encode(&scratch, false, |[r, g, b, a], _| [b, g, r, a]),
+            PixelFormat::Rgb565 { dither } => {
+                self.encode(&scratch, dither, pixel_format::encode_rgb565);
+            }
+            PixelFormat::Argb1555 { dither } => {
+                self.encode(&scratch, dither, pixel_format::encode_argb1555);
+            }
+            PixelFormat::A8 => self.encode(&scratch, false, pixel_format::encode_a8),
+            PixelFormat::Rgba32Float => unreachable!("float pixels are written above"),
+        }
+    }
+
+    /// Read the pixels of the region as premultiplied RGBA with `read`, converting them from
+    /// the pixel format and alpha mode of the region. The region is left unchanged.
+    pub(crate) fn read_rgba(&mut self, read: impl FnOnce(&mut Region<'_>)) {
+        if self.alpha_mode == AlphaMode::Premultiplied {
+            match self.pixel_format {
+                PixelFormat::Rgba8 => return read(self),
+                PixelFormat::Bgra8 => {
+                    // Swapping red and blue is its own inverse.
+                    self.swap_red_blue();
+                    read(self);
+                    return self.swap_red_blue();
+                }
+                _ => {}
+            }
+        }
+
+        let mut scratch = [0; SCRATCH_LEN];
+        if self.pixel_format == PixelFormat::Rgba32Float {
+            self.read_f32(|x, y, pixel| {
+                let start = y * SCRATCH_ROW_LEN + x * COLOR_COMPONENTS;
+                scratch[start..][..4].copy_from_slice(&pixel_format::linear_to_rgba8(pixel));
+            });
+            return read(&mut self.scratch_region(&mut scratch));
+        }
+        match self.pixel_format {
+            PixelFormat::Rgba8 => self.decode(&mut scratch, |pixel| pixel),
+            PixelFormat::Bgra8 => self.decode(&mut scratch, |[b, g, r, a]| [r, g, b, a]),
+            PixelFormat::Rgb565 { .. } => self.decode(&mut scratch, pixel_format::decode_rgb565),
+            PixelFormat::Argb1555 { .. } => {
+                self.decode(&mut scratch, pixel_format::decode_argb1555);
+            }
+            PixelFormat::A8 => self.decode(&mut scratch, pixel_format::decode_a8),
+            PixelFormat::Rgba32Float => unreachable!("float pixels are read above"),
+        }
+        if self.alpha_mode == AlphaMode::Straight {
+            for pixel in scratch.chunks_exact_mut(COLOR_COMPONENTS) {
+                let premultiplied = pixel_format::premultiply(pixel.try_into().unwrap());
+                pixel.copy_from_slice(&premultiplied);
+            }
+        }
+        read(&mut self.scratch_region(&mut scratch));
+    }
+
+    fn swap_red_blue(&mut self) {
+        for area in &mut self.areas {
+            for pixel in area.chunks_exact_mut(COLOR_COMPONENTS) {
+                pixel.swap(0, 2);
+            }
+        }
+    }
+
+    /// Create an RGBA region of the same size and position as this one, backed by `scratch`.
+    fn scratch_region<'s>(&self, scratch: &'s mut [u8; SCRATCH_LEN]) -> Region<'s> {
+        let row_bytes = usize::from(self.width) * COLOR_COMPONENTS;
+        let mut areas: [&mut [u8]; Tile::HEIGHT as usize] = [&mut [], &mut [], &mut [], &mut []];
+        for (area, row) in areas
+            .iter_mut()
+            .zip(scratch.chunks_exact_mut(SCRATCH_ROW_LEN))
+            .take(usize::from(self.height))
+        {
+            *area = &mut row[..row_bytes];
+        }
+
+        Region::new(areas, self.x, self.y, self.width, self.height)
+    }
+
+    /// Encode the RGBA pixels in `scratch` into the region, with `N` bytes per pixel.
+    fn encode<const N: usize>(
+        &mut self,
+        scratch: &[u8; SCRATCH_LEN],
+        dither: bool,
+        encode: impl Fn([u8; 4], u16) -> [u8; N],
+    ) {
+        let base_x = usize::from(self.x) * usize::from(WideTile::WIDTH);
+        let base_y = usize::from(self.y) * usize::from(Tile::HEIGHT);
+        for (y, (area, row)) in self
+            .areas
+            .iter_mut()
+            .zip(scratch.chunks_exact(SCRATCH_ROW_LEN))
+            .enumerate()
+        {
+            for (x, (dst, src)) in area
+                .chunks_exact_mut(N)
+                .zip(row.chunks_exact(COLOR_COMPONENTS))
+                .enumerate()
+            {
+                let threshold = pixel_format::threshold(dither, base_x + x, base_y + y);
+                dst.copy_from_slice(&encode(src.try_into().unwrap(), threshold));
+            }
+        }
+    }
+
+    /// Decode the pixels of the region, with `N` bytes per pixel, into RGBA in `scratch`.
+    fn decode<const N: usize>(
+        &self,
+        scratch: &mut [u8; SCRATCH_LEN],
+        decode: impl Fn([u8; N]) -> [u8; 4],
+    ) {
+        for (area, row) in self
+            .areas
+            .iter()
+            .zip(scratch.chunks_exact_mut(SCRATCH_ROW_LEN))
+        {
+            for (src, dst) in area
+                .chunks_exact(N)
+                .zip(row.chunks_exact_mut(COLOR_COMPONENTS))
+            {
+                dst.copy_from_slice(&decode(src.try_into().unwrap()));
+            }
+        }
+    }
+
+    /// Write premultiplied pixels in linear light, given by their position in the region, into
+    /// a region in the [`Rgba32Float`](PixelFormat::Rgba32Float) format, converting them to
+    /// its alpha mode.
+    pub(crate) fn write_f32(&mut self, pixel: impl Fn(usize, usize) -> [f32; 4]) {
+        let straight = self.alpha_mode == AlphaMode::Straight;
+        for (y, area) in self.areas.iter_mut().enumerate() {
+            for (x, dst) in area.chunks_exact_mut(16).enumerate() {
+                let mut pixel = pixel(x, y);
+                if straight && pixel[3] > 0.0 {
+                    let alpha = pixel[3];
+                    pixel[..3].iter_mut().for_each(|c| *c /= alpha);
+                }
+                for (dst, c) in dst.chunks_exact_mut(4).zip(pixel) {
+                    dst.copy_from_slice(&c.to_ne_bytes());
+                }
+            }
+        }
+    }
+
+    /// Read the pixels of a region in the [`Rgba32Float`](PixelFormat::Rgba32Float) format
+    /// as premultiplied pixels in linear light, together with their position in the region.
+    pub(crate) fn read_f32(&self, mut read: impl FnMut(usize, usize, [f32; 4])) {
+        let straight = self.alpha_mode == AlphaMode::Straight;
+        for (y, area) in self.areas.iter().enumerate() {
+            for (x, src) in area.chunks_exact(16).enumerate() {
+                let mut pixel = [0.0; 4];
+                for (c, src) in pixel.iter_mut().zip(src.chunks_exact(4)) {
+                    *c = f32::from_ne_bytes(src.try_into().unwrap());
+                }
+                if straight {
+                    let alpha = pixel[3];
+                    pixel[..3].iter_mut().for_each(|c| *c *= alpha);
+                }
+                read(x, y, pixel);
+            }
+        }
+    }
+
     pub(crate) fn row_mut(&mut self, y: u16) -> &mut [u8] {
         self.areas[usize::from(y)]
     }
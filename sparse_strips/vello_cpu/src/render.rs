@@ -3,34 +3,65 @@
 
 //! Basic render operations.
 
-use crate::RenderMode;
+use crate::cancel::{CancellationToken, Cancelled};
 use crate::dispatch::Dispatcher;
 #[cfg(feature = "text")]
 use crate::text::{GlyphAtlasResources, GlyphRunBuilder};
+use crate::{PixelFormat, RenderMode};
 #[cfg(feature = "text")]
-use glifo::GlyphPrepCache;
+use glifo::{GlyphCacheConfig, GlyphPrepCache};
 
 #[cfg(feature = "multithreading")]
 use crate::dispatch::multi_threaded::MultiThreadedDispatcher;
 use crate::dispatch::single_threaded::SingleThreadedDispatcher;
 use crate::kurbo::{PathEl, Point};
+use crate::replay::{Command, Payload, ReplayLog};
+use crate::shadow::ShadowCache;
 use alloc::boxed::Box;
 use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
+use core::ops::Range;
 use hashbrown::HashMap;
+use vello_common::accessibility::{AccessGeometry, AccessTag};
+use vello_common::alpha_mode::AlphaMode;
 use vello_common::blurred_rounded_rect::BlurredRoundedRectangle;
-use vello_common::encode::{EncodeExt, EncodedPaint};
+use vello_common::chart::Hairlines;
+use vello_common::compact_path::CompactPath;
+use vello_common::custom_paint::{
+    CustomPaint, CustomPaintId, CustomPaintKind, CustomPaintRegistry,
+};
+use vello_common::dash::MeasuredPath;
+#[cfg(feature = "std")]
+use vello_common::encode::GradientLutCache;
+use vello_common::encode::{EncodeExt, EncodedPaint, GradientHints, encode_gradient_interned};
+#[cfg(feature = "multithreading")]
+use vello_common::executor::Executor;
 use vello_common::fearless_simd::Level;
-use vello_common::filter_effects::Filter;
-use vello_common::kurbo::{Affine, BezPath, Rect, Stroke};
-use vello_common::mask::Mask;
-use vello_common::paint::{ImageId, ImageResolver, Paint, PaintType, Tint};
+use vello_common::filter_effects::{Filter, FilterFunction};
+use vello_common::flatten::{stroke_outline, stroke_tolerance_with};
+use vello_common::geometry::RectU16;
+use vello_common::gradient_bands::{GradientBands, stroke_bounds};
+use vello_common::gradient_stops::GradientStopArena;
+use vello_common::kurbo::{Affine, BezPath, Dashes, Rect, Shape, Stroke, Vec2};
+use vello_common::load_op::LoadOp;
+use vello_common::mask::{Mask, MaskKind};
+use vello_common::mipmap::{MipChain, nearest_level};
+use vello_common::paint::{
+    Image, ImageId, ImageResolver, ImageSource, Paint, PaintType, Tint, TintMode,
+};
 use vello_common::peniko::color::palette::css::BLACK;
-use vello_common::peniko::{BlendMode, Fill};
+use vello_common::peniko::{BlendMode, Fill, ImageSampler};
 use vello_common::pixmap::Pixmap;
+use vello_common::quality::Quality;
 use vello_common::render_state::RenderState;
+use vello_common::render_stats::RenderStats;
+use vello_common::simplify::LodPath;
+use vello_common::strip_generator::Aliasing;
+use vello_common::tile::Tile;
 use vello_common::util::is_axis_aligned;
+use vello_common::variable_width::VariableWidthStroke;
+use vello_common::winding::render_winding_numbers;
 
 #[cfg(feature = "text")]
 pub(crate) const DEFAULT_GLYPH_ATLAS_SIZE: u16 = 4096;
@@ -63,6 +94,8 @@ pub struct Resources {
     // Will be initialized lazily on first use.
     #[cfg(feature = "text")]
     pub(crate) glyph_resources: Option<GlyphAtlasResources>,
+    #[cfg(feature = "text")]
+    pub(crate) glyph_cache_config: GlyphCacheConfig,
 }
 
 impl Resources {
@@ -71,6 +104,14 @@ impl Resources {
         Self::default()
     }
 
+    /// Use the given configuration for the glyph atlas cache, for example to change the
+    /// number of subpixel positions that glyphs are cached at.
+    #[cfg(feature = "text")]
+    pub fn with_glyph_cache_config(mut self, config: GlyphCacheConfig) -> Self {
+        self.glyph_cache_config = config;
+        self
+    }
+
     pub(crate) fn before_render(&mut self) {
         #[cfg(feature = "text")]
         self.prepare_glyph_cache();
@@ -87,27 +128,57 @@ impl Resources {
 /// This is the main entry point for drawing operations. It maintains the current
 /// rendering state (transforms, paint, stroke, etc.) and dispatches drawing commands
 /// to the underlying rasterization engine.
+///
+/// Render contexts are [`Send`], so a scene can be drawn on one thread and rendered on
+/// another.
 #[derive(Debug)]
 pub struct RenderContext {
     /// Width of the render target in pixels.
     pub(crate) width: u16,
     /// Height of the render target in pixels.
     pub(crate) height: u16,
+    /// The region of the full target that the render target covers.
+    region: RectU16,
     /// The current rendering state.
     pub(crate) state: RenderState,
     /// The current mask in place.
     pub(crate) mask: Option<Mask>,
     /// Temporary path buffer to avoid repeated allocations.
     pub(crate) temp_path: BezPath,
-    /// Optional threshold for aliasing.
-    pub(crate) aliasing_threshold: Option<u8>,
+    /// How the edges of paths are rasterized.
+    pub(crate) aliasing: Aliasing,
     pub(crate) encoded_paints: Vec<EncodedPaint>,
     pub(crate) filter: Option<Filter>,
-    #[cfg_attr(
-        not(feature = "text"),
-        allow(dead_code, reason = "used when the `text` feature is enabled")
-    )]
+    /// The kinds of the mask groups whose mask content is currently being drawn.
+    mask_groups: Vec<MaskKind>,
     pub(crate) render_settings: RenderSettings,
+    /// How the render target is initialized before rendering.
+    load_op: LoadOp,
+    /// The pixel format of the buffers passed to `render_to_buffer`.
+    pixel_format: PixelFormat,
+    /// The alpha mode of the buffers passed to `render_to_buffer`.
+    alpha_mode: AlphaMode,
+    /// Whether colors are blended in linear light.
+    linear_compositing: bool,
+    /// Whether colors are dithered when they are rounded to 8 bits.
+    dither: bool,
+    /// The quality preset that subsequent paths, gradients and filters are rendered with.
+    quality: Quality,
+    /// A gradient lookup table cache shared with other render contexts or renderers.
+    #[cfg(feature = "std")]
+    pub(crate) gradient_lut_cache: Option<GradientLutCache>,
+    /// The log that calls are recorded into, if any.
+    replay_log: Option<ReplayLog>,
+    /// The tag that drawn primitives are recorded under, if any.
+    pub(crate) access_tag: Option<AccessTag>,
+    /// The geometry of the tagged primitives drawn since the last reset.
+    pub(crate) access_geometry: AccessGeometry,
+    /// The blurred silhouettes of recently drawn drop shadows.
+    shadow_cache: ShadowCache,
+    /// The custom paint kinds that can be drawn with [`RenderContext::fill_custom_paint`].
+    custom_paints: CustomPaintRegistry,
+    /// The interned color stops of the gradients drawn in recent frames.
+    gradient_stops: GradientStopArena,
     dispatcher: Box<dyn Dispatcher>,
 }
 
@@ -115,9 +186,18 @@ pub struct RenderContext {
 #[derive(Copy, Clone, Debug)]
 pub struct RenderSettings {
     /// The SIMD level that should be used for rendering operations.
+    ///
+    /// By default, the best level supported by the CPU is detected. On WASM, the SIMD128 level
+    /// can only be detected if the crate is compiled with `-Ctarget-feature=+simd128`.
     pub level: Level,
     /// The number of worker threads that should be used for rendering. Only has an effect
     /// if the `multithreading` feature is active.
+    ///
+    /// With worker threads, paths are processed in parallel, and the tiles of the target
+    /// are rasterized in parallel. Small targets with only a few rows of tiles are
+    /// still rasterized on the calling thread. On WebAssembly targets without the `atomics`
+    /// target feature, which can't spawn threads, rendering always happens on the calling
+    /// thread.
     pub num_threads: u16,
     /// Whether to prioritize speed or quality when rendering.
     ///
@@ -135,10 +215,10 @@ impl Default for RenderSettings {
         Self {
             level: Level::try_detect().unwrap_or(Level::baseline()),
             #[cfg(feature = "multithreading")]
+            // Targets that can't query their parallelism, like WebAssembly, render on the
+            // calling thread.
             num_threads: (std::thread::available_parallelism()
-                .unwrap()
-                .get()
-                .saturating_sub(1) as u16)
+                .map_or(0, |threads| threads.get().saturating_sub(1)) as u16)
                 .min(8),
             #[cfg(not(feature = "multithreading"))]
             num_threads: 0,
@@ -156,13 +236,15 @@ impl RenderContext {
     /// Create a new render context with specific settings.
     pub fn new_with(width: u16, height: u16, settings: RenderSettings) -> Self {
         #[cfg(feature = "multithreading")]
-        let dispatcher: Box<dyn Dispatcher> = if settings.num_threads == 0 {
+        let dispatcher: Box<dyn Dispatcher> = if settings.num_threads == 0
+            || cfg!(all(target_arch = "wasm32", not(target_feature = "atomics")))
+        {
             Box::new(SingleThreadedDispatcher::new(width, height, settings.level))
         } else {
-            Box::new(MultiThreadedDispatcher::new(
+            Box::new(MultiThreadedDispatcher::with_executor(
                 width,
                 height,
-                settings.num_threads,
+                vello_common::executor::thread_pool(settings.num_threads),
                 settings.level,
             ))
         };
@@ -171,88 +253,476 @@ impl RenderContext {
         let dispatcher: Box<dyn Dispatcher> =
             { Box::new(SingleThreadedDispatcher::new(width, height, settings.level)) };
 
+        Self::with_dispatcher(width, height, settings, dispatcher)
+    }
+
+    /// Create a new render context that runs its multi-threaded work on `executor`, instead of
+    /// on threads of its own.
+    ///
+    /// This lets applications that already manage a thread pool, for example a
+    /// [`rayon::ThreadPool`](https://docs.rs/rayon/latest/rayon/struct.ThreadPool.html),
+    /// control which threads render. The number of worker threads is taken from the
+    /// executor, so [`RenderSettings::num_threads`] is ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the executor doesn't have between 1 and 255 threads.
+    #[cfg(feature = "multithreading")]
+    pub fn new_with_executor(
+        width: u16,
+        height: u16,
+        settings: RenderSettings,
+        executor: Arc<dyn Executor>,
+    ) -> Self {
+        let dispatcher = Box::new(MultiThreadedDispatcher::with_executor(
+            width,
+            height,
+            executor,
+            settings.level,
+        ));
+
+        Self::with_dispatcher(width, height, settings, dispatcher)
+    }
+
+    /// Create a new render context that only renders `region` of a larger target.
+    ///
+    /// Draw calls use the coordinates of the full target, but everything outside of the
+    /// region is culled before flattening, so strip generation and rasterization only do
+    /// work for the region. This is useful for redrawing a damaged part of a large target,
+    /// see [`render_region_to_buffer`](Self::render_region_to_buffer).
+    ///
+    /// The render context has the size of the region, which also applies to masks: their
+    /// top-left pixel corresponds to the top-left corner of the region.
+    pub fn new_with_region(region: RectU16, settings: RenderSettings) -> Self {
+        let mut ctx = Self::new_with(region.width(), region.height(), settings);
+        ctx.region = region;
+        ctx
+    }
+
+    fn with_dispatcher(
+        width: u16,
+        height: u16,
+        settings: RenderSettings,
+        dispatcher: Box<dyn Dispatcher>,
+    ) -> Self {
         let encoded_paints = vec![];
         let temp_path = BezPath::new();
-        let aliasing_threshold = None;
+        let aliasing = Aliasing::default();
 
         Self {
             width,
             height,
+            region: RectU16::new(0, 0, width, height),
             dispatcher,
             state: RenderState::default(),
-            aliasing_threshold,
+            aliasing,
             render_settings: settings,
+            load_op: LoadOp::default(),
+            pixel_format: PixelFormat::default(),
+            alpha_mode: AlphaMode::default(),
+            linear_compositing: false,
+            dither: false,
+            quality: Quality::default(),
             mask: None,
             temp_path,
             encoded_paints,
             filter: None,
+            mask_groups: Vec::new(),
+            #[cfg(feature = "std")]
+            gradient_lut_cache: None,
+            replay_log: None,
+            access_tag: None,
+            access_geometry: AccessGeometry::new(),
+            shadow_cache: ShadowCache::default(),
+            custom_paints: CustomPaintRegistry::new(),
+            gradient_stops: GradientStopArena::new(),
+        }
+    }
+
+    /// Start recording calls into a [`ReplayLog`], replacing the log that is currently
+    /// being recorded, if any.
+    ///
+    /// The log starts with the current render state, so that it can be replayed into a new
+    /// render context. See the [`replay`](crate::replay) module for details.
+    pub fn start_replay_log(&mut self) {
+        let mut log = ReplayLog::new(self.width, self.height);
+        log.push_state(&self.state);
+        log.push(Command::SetAliasingThreshold(self.aliasing.threshold));
+        log.push(Command::SetPixelSnapping(self.aliasing.snap_to_pixels));
+        if let Some(mask) = &self.mask {
+            log.push(Command::set_mask(mask));
+        }
+        if let Some(filter) = &self.filter {
+            log.push(Command::set_filter_effect(filter));
+        }
+        self.replay_log = Some(log);
+    }
+
+    /// Stop recording calls and return the recorded log, if a log was being recorded.
+    pub fn take_replay_log(&mut self) -> Option<ReplayLog> {
+        self.replay_log.take()
+    }
+
+    /// Set the tag that the geometry of subsequently drawn primitives is recorded under, or
+    /// stop recording geometry with `None`.
+    ///
+    /// See the [`accessibility`](vello_common::accessibility) module for details.
+    pub fn set_access_tag(&mut self, tag: Option<AccessTag>) {
+        self.access_tag = tag;
+        self.dispatcher.set_access_tag(tag);
+    }
+
+    /// Get the tag that the geometry of drawn primitives is currently recorded under.
+    pub fn access_tag(&self) -> Option<AccessTag> {
+        self.access_tag
+    }
+
+    /// Get the geometry of the tagged primitives drawn since the last reset.
+    pub fn access_geometry(&self) -> &AccessGeometry {
+        &self.access_geometry
+    }
+
+    /// Take the geometry of the tagged primitives drawn since the last reset, leaving no
+    /// geometry behind.
+    pub fn take_access_geometry(&mut self) -> AccessGeometry {
+        core::mem::take(&mut self.access_geometry)
+    }
+
+    /// Get the tags of the primitives that contributed visible pixels, in ascending order.
+    ///
+    /// Unlike [`access_geometry`](Self::access_geometry), this excludes tagged primitives that
+    /// were clipped away entirely or completely covered by opaque content drawn on top of them.
+    /// The result is conservative: a primitive that is only partly covered, or hidden by a clip
+    /// or a layer at a finer granularity than a wide tile, is still reported.
+    ///
+    /// This is only up to date after [`flush`](Self::flush) and is cleared by
+    /// [`reset`](Self::reset).
+    pub fn visible_access_tags(&self) -> Vec<AccessTag> {
+        self.dispatcher.wide().visible_access_tags()
+    }
+
+    fn record_access_fill(&mut self, shape: &impl Shape) {
+        if let Some(tag) = self.access_tag {
+            self.access_geometry
+                .add_fill(tag, shape, self.state.transform);
+        }
+    }
+
+    fn record_access_stroke(&mut self, path: &BezPath) {
+        if let Some(tag) = self.access_tag {
+            self.access_geometry
+                .add_stroke(tag, path, &self.state.stroke, self.state.transform);
+        }
+    }
+
+    fn record(&mut self, command: impl FnOnce() -> Command) {
+        if let Some(log) = &mut self.replay_log {
+            log.push(command());
+        }
+    }
+
+    fn record_state(&mut self) {
+        if let Some(log) = &mut self.replay_log {
+            log.push_state(&self.state);
+        }
+    }
+
+    /// Record the current stroke after it was changed through [`Self::stroke_mut`].
+    #[cfg(feature = "text")]
+    pub(crate) fn record_stroke(&mut self) {
+        if let Some(log) = &mut self.replay_log {
+            log.push(Command::SetStroke(self.state.stroke.clone()));
         }
     }
 
     fn encode_current_paint(&mut self) -> Paint {
+        let transform = self.device_transform() * self.state.paint_transform;
         match self.state.paint.clone() {
             PaintType::Solid(s) => s.into(),
             PaintType::Gradient(g) => {
-                // TODO: Add caching?
-                g.encode_into(
+                let paint = encode_gradient_interned(
+                    &g,
+                    &self.state.gradient_hints,
+                    &mut self.gradient_stops,
                     &mut self.encoded_paints,
-                    self.state.transform * self.state.paint_transform,
-                    None,
-                )
+                    transform,
+                );
+
+                // Invalid gradients are encoded as a solid color, in which case the last
+                // encoded paint belongs to an earlier draw call.
+                if let (Paint::Indexed(_), Some(EncodedPaint::Gradient(encoded))) =
+                    (&paint, self.encoded_paints.last_mut())
+                {
+                    encoded.set_max_lut_size(self.quality.max_gradient_lut_size());
+                    #[cfg(feature = "std")]
+                    if let Some(cache) = &self.gradient_lut_cache {
+                        encoded.set_lut_cache(cache.clone());
+                    }
+                }
+
+                paint
             }
-            PaintType::Image(i) => i.encode_into(
-                &mut self.encoded_paints,
-                self.state.transform * self.state.paint_transform,
-                self.state.tint,
-            ),
+            PaintType::Image(i) => {
+                i.encode_into(&mut self.encoded_paints, transform, self.state.tint)
+            }
+        }
+    }
+
+    /// Decompose the current paint into solid bands if it is a gradient that only consists of
+    /// hard stops and can be rendered that way.
+    fn hard_stop_bands(&self, bounds: impl FnOnce() -> Rect) -> Option<GradientBands> {
+        let PaintType::Gradient(gradient) = &self.state.paint else {
+            return None;
+        };
+
+        // Bands are drawn on top of each other, which only works with plain source-over
+        // compositing.
+        if self.state.blend_mode != BlendMode::default() || self.mask.is_some() {
+            return None;
+        }
+
+        GradientBands::new(
+            gradient,
+            bounds(),
+            self.device_transform(),
+            self.state.paint_transform,
+        )
+    }
+
+    /// Draw `shape` with the colors of `bands`.
+    ///
+    /// The shape is pushed as a clip layer, so that its anti-aliased edges are only applied
+    /// once, and the opaque bands are filled inside of it.
+    fn draw_bands(&mut self, bands: &GradientBands, shape: &BezPath, fill_rule: Fill) {
+        let transform = self.device_transform();
+        self.dispatcher.push_layer(
+            Some(shape),
+            fill_rule,
+            transform,
+            BlendMode::default(),
+            1.0,
+            self.aliasing,
+            None,
+            None,
+        );
+
+        let regions = core::iter::once((bands.bounds(), Fill::NonZero, bands.base()));
+        let regions = regions.chain(
+            bands
+                .bands()
+                .iter()
+                .map(|band| (band.region(), Fill::EvenOdd, band.color())),
+        );
+        for (region, fill_rule, color) in regions {
+            self.dispatcher.fill_path(
+                region,
+                fill_rule,
+                transform,
+                color.into(),
+                BlendMode::default(),
+                self.aliasing,
+                None,
+                &self.encoded_paints,
+            );
         }
+
+        self.dispatcher.pop_layer();
+    }
+
+    /// Return the outline of `path` stroked with the current stroke, like it is filled when
+    /// the path is stroked.
+    fn stroke_outline(&self, path: &BezPath) -> BezPath {
+        let tolerance =
+            stroke_tolerance_with(self.device_transform(), self.quality.flatten_tolerance());
+        stroke_outline(path, &self.state.stroke, tolerance)
+    }
+
+    fn fill_path_with_paint(&mut self, path: &BezPath, paint: Paint) {
+        let transform = self.device_transform();
+        self.dispatcher.fill_path(
+            path,
+            self.state.fill_rule,
+            transform,
+            paint,
+            self.state.blend_mode,
+            self.aliasing,
+            self.mask.clone(),
+            &self.encoded_paints,
+        );
+    }
+
+    fn stroke_path_with_paint(&mut self, path: &BezPath, paint: Paint) {
+        let transform = self.device_transform();
+        self.dispatcher.stroke_path(
+            path,
+            &self.state.stroke,
+            transform,
+            paint,
+            self.state.blend_mode,
+            self.aliasing,
+            self.mask.clone(),
+            &self.encoded_paints,
+        );
     }
 
     /// Fill a path.
     pub fn fill_path(&mut self, path: &BezPath) {
+        self.record(|| Command::FillPath(Payload::path(path)));
+        self.record_access_fill(path);
         self.with_optional_filter(|ctx| {
+            if let Some(bands) = ctx.hard_stop_bands(|| path.bounding_box()) {
+                ctx.draw_bands(&bands, path, ctx.state.fill_rule);
+                return;
+            }
+
             let paint = ctx.encode_current_paint();
-            ctx.dispatcher.fill_path(
-                path,
-                ctx.state.fill_rule,
-                ctx.state.transform,
-                paint,
-                ctx.state.blend_mode,
-                ctx.aliasing_threshold,
-                ctx.mask.clone(),
-                &ctx.encoded_paints,
-            );
+            ctx.fill_path_with_paint(path, paint);
         });
     }
 
+    /// Draw the raw winding numbers of a path under the current transform as a false-color
+    /// image of the size of the render context, to debug why a path fills unexpectedly.
+    ///
+    /// Unlike [`fill_path`](Self::fill_path), this ignores the fill rule, paint, clips and
+    /// layers, and doesn't add anything to the scene. See
+    /// [`render_winding_numbers`](vello_common::winding::render_winding_numbers) for the
+    /// colors that are used.
+    pub fn debug_winding_numbers(&self, path: &BezPath) -> Pixmap {
+        let mut pixmap = Pixmap::new(self.width, self.height);
+        render_winding_numbers(path, self.device_transform(), &mut pixmap);
+        pixmap
+    }
+
     /// Stroke a path.
     pub fn stroke_path(&mut self, path: &BezPath) {
+        self.record(|| Command::StrokePath(Payload::path(path)));
+        self.record_access_stroke(path);
         self.with_optional_filter(|ctx| {
+            if let Some(bands) = ctx.hard_stop_bands(|| stroke_bounds(path, &ctx.state.stroke)) {
+                let outline = ctx.stroke_outline(path);
+                ctx.draw_bands(&bands, &outline, Fill::NonZero);
+                return;
+            }
+
             let paint = ctx.encode_current_paint();
-            ctx.dispatcher.stroke_path(
-                path,
-                &ctx.state.stroke,
-                ctx.state.transform,
-                paint,
-                ctx.state.blend_mode,
-                ctx.aliasing_threshold,
-                ctx.mask.clone(),
-                &ctx.encoded_paints,
-            );
+            ctx.stroke_path_with_paint(path, paint);
+        });
+    }
+
+    /// Stroke a measured path with the current paint and stroke settings.
+    ///
+    /// This renders the same as stroking the original path with
+    /// [`stroke_path`](Self::stroke_path), but the dash pattern of the stroke is applied
+    /// using the segment lengths cached in the [`MeasuredPath`], so that animating the
+    /// dash offset doesn't require measuring the whole path again.
+    pub fn stroke_measured_path(&mut self, path: &MeasuredPath) {
+        let stroke = self.state.stroke.clone();
+        let mut dashed = core::mem::take(&mut self.temp_path);
+        path.dash(stroke.dash_offset, &stroke.dash_pattern, &mut dashed);
+
+        // The path is already dashed, so stroke it without the dash pattern.
+        self.set_stroke(Stroke {
+            dash_pattern: Dashes::new(),
+            dash_offset: 0.0,
+            ..stroke.clone()
         });
+        self.stroke_path(&dashed);
+        self.set_stroke(stroke);
+        self.temp_path = dashed;
+    }
+
+    /// Fill pixel-snapped hairlines, such as the grid lines and ticks of a chart, with the
+    /// current paint.
+    ///
+    /// The lines are already in device space, so they are drawn independently of the current
+    /// transform, through the fast path for axis-aligned rectangles. The paint is still
+    /// positioned with the current transform.
+    pub fn fill_hairlines(&mut self, lines: &Hairlines) {
+        let transform = self.state.transform;
+        let paint_transform = self.state.paint_transform;
+        self.set_transform(Affine::IDENTITY);
+        self.set_paint_transform(transform * paint_transform);
+        for rect in lines.rects() {
+            self.fill_rect(rect);
+        }
+        self.set_paint_transform(paint_transform);
+        self.set_transform(transform);
+    }
+
+    /// Fill a path from a [`CompactPaths`](vello_common::compact_path::CompactPaths) set.
+    ///
+    /// The path is decoded into a scratch path that is reused between calls, so only the
+    /// compact set needs to stay resident.
+    pub fn fill_compact_path(&mut self, path: CompactPath<'_>) {
+        let mut decoded = core::mem::take(&mut self.temp_path);
+        path.decode_into(&mut decoded);
+        self.fill_path(&decoded);
+        self.temp_path = decoded;
+    }
+
+    /// Stroke a path from a [`CompactPaths`](vello_common::compact_path::CompactPaths) set.
+    ///
+    /// See [`fill_compact_path`](Self::fill_compact_path) for details.
+    pub fn stroke_compact_path(&mut self, path: CompactPath<'_>) {
+        let mut decoded = core::mem::take(&mut self.temp_path);
+        path.decode_into(&mut decoded);
+        self.stroke_path(&decoded);
+        self.temp_path = decoded;
+    }
+
+    /// Fill the level of detail of a path that suits the current transform.
+    ///
+    /// See the [`simplify`](vello_common::simplify) module for details.
+    pub fn fill_lod_path(&mut self, path: &LodPath) {
+        self.fill_path(path.select(&self.state.transform));
+    }
+
+    /// Stroke the level of detail of a path that suits the current transform.
+    ///
+    /// See the [`simplify`](vello_common::simplify) module for details.
+    pub fn stroke_lod_path(&mut self, path: &LodPath) {
+        self.stroke_path(path.select(&self.state.transform));
+    }
+
+    /// Stroke a path whose width varies along the path, with the current paint.
+    ///
+    /// The stroke is expanded with round caps and joins and filled with the non-zero fill
+    /// rule, independent of the current stroke settings and fill rule.
+    pub fn stroke_variable_width(&mut self, stroke: &VariableWidthStroke) {
+        let mut path = core::mem::take(&mut self.temp_path);
+        let tolerance =
+            stroke_tolerance_with(self.state.transform, self.quality.flatten_tolerance());
+        stroke.expand(tolerance, &mut path);
+
+        let fill_rule = self.state.fill_rule;
+        self.set_fill_rule(Fill::NonZero);
+        self.fill_path(&path);
+        self.set_fill_rule(fill_rule);
+        self.temp_path = path;
     }
 
     /// Fill a rectangle.
     pub fn fill_rect(&mut self, rect: &Rect) {
+        self.record(|| Command::FillRect(*rect));
+        self.record_access_fill(rect);
         self.with_optional_filter(|ctx| {
+            if let Some(bands) = ctx.hard_stop_bands(|| *rect) {
+                ctx.rect_to_temp_path(rect);
+                let path = core::mem::take(&mut ctx.temp_path);
+                ctx.draw_bands(&bands, &path, ctx.state.fill_rule);
+                ctx.temp_path = path;
+                return;
+            }
+
             let paint = ctx.encode_current_paint();
+            let transform = ctx.device_transform();
 
             // Fast path: Use optimized rect filling if we have no skew in the path transform
             // and anti-aliasing is enabled.
             // TODO: Maybe also support no anti-aliasing in the fast path
-            if is_axis_aligned(&ctx.state.transform) && ctx.aliasing_threshold.is_none() {
+            if is_axis_aligned(&transform) && ctx.aliasing.is_anti_aliased() {
                 // Transform the rect to screen coordinates.
-                let transformed_rect = ctx.state.transform.transform_rect_bbox(*rect);
+                let transformed_rect = transform.transform_rect_bbox(*rect);
                 ctx.dispatcher.fill_rect_fast(
                     &transformed_rect,
                     paint,
@@ -266,10 +736,10 @@ impl RenderContext {
                 ctx.dispatcher.fill_path(
                     &ctx.temp_path,
                     ctx.state.fill_rule,
-                    ctx.state.transform,
+                    transform,
                     paint,
                     ctx.state.blend_mode,
-                    ctx.aliasing_threshold,
+                    ctx.aliasing,
                     ctx.mask.clone(),
                     &ctx.encoded_paints,
                 );
@@ -279,16 +749,30 @@ impl RenderContext {
 
     /// Stroke a rectangle.
     pub fn stroke_rect(&mut self, rect: &Rect) {
+        self.record(|| Command::StrokeRect(*rect));
+        if self.access_tag.is_some() {
+            self.record_access_stroke(&rect.to_path(0.1));
+        }
         self.with_optional_filter(|ctx| {
             ctx.rect_to_temp_path(rect);
+
+            if let Some(bands) =
+                ctx.hard_stop_bands(|| stroke_bounds(&ctx.temp_path, &ctx.state.stroke))
+            {
+                let outline = ctx.stroke_outline(&ctx.temp_path);
+                ctx.draw_bands(&bands, &outline, Fill::NonZero);
+                return;
+            }
+
             let paint = ctx.encode_current_paint();
+            let transform = ctx.device_transform();
             ctx.dispatcher.stroke_path(
                 &ctx.temp_path,
                 &ctx.state.stroke,
-                ctx.state.transform,
+                transform,
                 paint,
                 ctx.state.blend_mode,
-                ctx.aliasing_threshold,
+                ctx.aliasing,
                 ctx.mask.clone(),
                 &ctx.encoded_paints,
             );
@@ -313,6 +797,7 @@ impl RenderContext {
     /// Note that this only works properly if the current paint is set to a solid color.
     /// If not, it will fall back to using black as the fill color.
     pub fn fill_blurred_rounded_rect(&mut self, rect: &Rect, radius: f32, std_dev: f32) {
+        self.record(|| Command::FillBlurredRoundedRect(*rect, radius, std_dev));
         let rect = rect.abs();
         let color = match self.state.paint {
             PaintType::Solid(s) => s,
@@ -333,23 +818,122 @@ impl RenderContext {
         // For performance reason we cut off the filter at some extent where the response is close to zero.
         let kernel_size = 2.5 * std_dev;
         let inflated_rect = rect.inflate(f64::from(kernel_size), f64::from(kernel_size));
-        let transform = self.state.transform * self.state.paint_transform;
+        let transform = self.device_transform();
+        self.record_access_fill(&inflated_rect);
 
         self.rect_to_temp_path(&inflated_rect);
 
-        let paint = blurred_rect.encode_into(&mut self.encoded_paints, transform, None);
+        let paint = blurred_rect.encode_into(
+            &mut self.encoded_paints,
+            transform * self.state.paint_transform,
+            None,
+        );
         self.dispatcher.fill_path(
             &self.temp_path,
             Fill::NonZero,
-            self.state.transform,
+            transform,
+            paint,
+            self.state.blend_mode,
+            self.aliasing,
+            self.mask.clone(),
+            &self.encoded_paints,
+        );
+    }
+
+    /// Fill the drop shadow of a path.
+    ///
+    /// The shadow is the silhouette of `path`, filled with the current fill rule, moved by
+    /// `offset` and blurred with a Gaussian blur of standard deviation `std_dev`, both in user
+    /// space. Like [`RenderContext::fill_blurred_rounded_rect`], it only uses solid paints;
+    /// non-solid paints fall back to black. The path itself isn't drawn.
+    ///
+    /// The blurred silhouettes of recently drawn shadows are cached, so drawing the shadow of
+    /// the same path again is cheap, as long as only the translation of the current transform
+    /// or `offset` changed.
+    pub fn fill_drop_shadow(&mut self, path: &BezPath, offset: Vec2, std_dev: f32) {
+        self.record(|| Command::FillDropShadow(Payload::path(path), offset, std_dev));
+        let color = match self.state.paint {
+            PaintType::Solid(s) => s,
+            _ => BLACK,
+        };
+
+        let [a, b, c, d, e, f] = self.device_transform().as_coeffs();
+        let linear = Affine::new([a, b, c, d, 0.0, 0.0]);
+        let Some(shadow) = self.shadow_cache.get_or_render(
+            path,
+            self.state.fill_rule,
+            linear,
+            std_dev,
+            self.render_settings,
+            self.quality,
+        ) else {
+            return;
+        };
+
+        let origin = Vec2::new(e, f) + (linear * offset.to_point()).to_vec2() + shadow.origin;
+        let transform = Affine::translate(origin);
+        let rect = Rect::new(
+            0.0,
+            0.0,
+            f64::from(shadow.pixmap.width()),
+            f64::from(shadow.pixmap.height()),
+        );
+        self.rect_to_temp_path(&rect);
+
+        let image = Image {
+            image: ImageSource::Pixmap(shadow.pixmap),
+            sampler: ImageSampler::default(),
+        };
+        let tint = Tint {
+            color,
+            mode: TintMode::AlphaMask,
+        };
+        let paint = image.encode_into(&mut self.encoded_paints, transform, Some(tint));
+        self.dispatcher.fill_path(
+            &self.temp_path,
+            Fill::NonZero,
+            transform,
             paint,
             self.state.blend_mode,
-            self.aliasing_threshold,
+            self.aliasing,
             self.mask.clone(),
             &self.encoded_paints,
         );
     }
 
+    /// Register a custom paint kind and return the identifier that [`CustomPaint`]s of that
+    /// kind refer to.
+    ///
+    /// Registered kinds are kept across [`RenderContext::reset`].
+    pub fn register_custom_paint(&mut self, kind: Arc<dyn CustomPaintKind>) -> CustomPaintId {
+        self.custom_paints.register(kind)
+    }
+
+    /// Fill a path with a custom paint, instead of the current paint.
+    ///
+    /// The paint is positioned with the current transform and paint transform. Paints of
+    /// kinds that weren't registered with [`RenderContext::register_custom_paint`] are
+    /// transparent.
+    pub fn fill_custom_paint(&mut self, path: &BezPath, paint: &CustomPaint) {
+        self.record(|| Command::fill_custom_paint(path, paint));
+        self.record_access_fill(path);
+        let transform = self.device_transform() * self.state.paint_transform;
+        let paint = paint.encode_into(&self.custom_paints, &mut self.encoded_paints, transform);
+        self.with_optional_filter(|ctx| ctx.fill_path_with_paint(path, paint.clone()));
+    }
+
+    /// Stroke a path with a custom paint and the current stroke settings, instead of the
+    /// current paint.
+    ///
+    /// The paint is positioned like in [`RenderContext::fill_custom_paint`].
+    pub fn stroke_custom_paint(&mut self, path: &BezPath, paint: &CustomPaint) {
+        self.record(|| Command::stroke_custom_paint(path, paint));
+        self.record_access_stroke(path);
+        let transform = self.device_transform() * self.state.paint_transform;
+        let paint = paint.encode_into(&self.custom_paints, &mut self.encoded_paints, transform);
+        self.with_optional_filter(|ctx| ctx.stroke_path_with_paint(path, paint.clone()));
+    }
+
     /// Creates a builder for drawing a run of glyphs that have the same attributes.
     #[cfg(feature = "text")]
     pub fn glyph_run<'a>(
@@ -380,6 +964,26 @@ impl RenderContext {
         opacity: Option<f32>,
         mask: Option<Mask>,
         filter: Option<Filter>,
+    ) {
+        self.record(|| {
+            Command::push_layer(
+                clip_path,
+                blend_mode,
+                opacity,
+                mask.as_ref(),
+                filter.as_ref(),
+            )
+        });
+        self.push_layer_unrecorded(clip_path, blend_mode, opacity, mask, filter);
+    }
+
+    fn push_layer_unrecorded(
+        &mut self,
+        clip_path: Option<&BezPath>,
+        blend_mode: Option<BlendMode>,
+        opacity: Option<f32>,
+        mask: Option<Mask>,
+        filter: Option<Filter>,
     ) {
         let mask = mask.and_then(|m| {
             if m.width() != self.width || m.height() != self.height {
@@ -391,14 +995,15 @@ impl RenderContext {
 
         let blend_mode = blend_mode.unwrap_or_default();
         let opacity = opacity.unwrap_or(1.0);
+        let transform = self.device_transform();
 
         self.dispatcher.push_layer(
             clip_path,
             self.state.fill_rule,
-            self.state.transform,
+            transform,
             blend_mode,
             opacity,
-            self.aliasing_threshold,
+            self.aliasing,
             mask,
             filter,
         );
@@ -418,6 +1023,12 @@ impl RenderContext {
     }
 
     /// Push a new opacity layer.
+    ///
+    /// Everything drawn until the matching [`RenderContext::pop_layer`] is composited into an
+    /// intermediate layer first, and `opacity` is applied to that layer as a whole. Unlike
+    /// giving each shape a translucent paint, overlapping shapes inside the group therefore don't
+    /// show through each other, which matches the `opacity` property of SVG groups. `opacity` is
+    /// clamped to the range `[0, 1]`.
     pub fn push_opacity_layer(&mut self, opacity: f32) {
         self.push_layer(None, None, Some(opacity), None, None);
     }
@@ -432,6 +1043,37 @@ impl RenderContext {
         self.push_layer(None, None, None, Some(mask), None);
     }
 
+    /// Push a new mask group.
+    ///
+    /// Everything drawn until the matching [`RenderContext::end_mask`] makes up the mask.
+    /// Everything drawn after that, until the matching [`RenderContext::pop_layer`], is the
+    /// content, which is composited into an intermediate layer and then modulated by the mask.
+    /// Depending on `kind`, either the alpha or the luminance of the mask is used for that,
+    /// which correspond to the `mask-type` values of SVG masks.
+    ///
+    /// Unlike [`RenderContext::push_mask_layer`], the mask is drawn with the regular drawing
+    /// operations and is therefore affected by the current transform.
+    pub fn push_mask_group(&mut self, kind: MaskKind) {
+        self.record(|| Command::PushMaskGroup(kind));
+        self.mask_groups.push(kind);
+        self.dispatcher.push_mask_group();
+    }
+
+    /// Finish drawing the mask of the last-pushed mask group and start drawing its content.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no mask group whose mask is being drawn, or if a layer that was
+    /// pushed after the mask group hasn't been popped yet.
+    pub fn end_mask(&mut self) {
+        self.record(|| Command::EndMask);
+        let kind = self
+            .mask_groups
+            .pop()
+            .expect("`end_mask` called without a matching `push_mask_group`");
+        self.dispatcher.end_mask(kind);
+    }
+
     /// Push a filter layer that affects all subsequent drawing operations.
     ///
     /// WARNING: Note that filters are currently incomplete and experimental. In
@@ -441,6 +1083,43 @@ impl RenderContext {
         self.push_layer(None, None, None, None, Some(filter));
     }
 
+    /// Push a layer that applies a Gaussian blur to all subsequent drawing operations.
+    ///
+    /// Like the CSS `blur()` filter function, `radius` is the standard deviation of the
+    /// Gaussian, so the blur visibly extends about three times as far. This is a shorthand for
+    /// [`RenderContext::push_filter_layer`] with [`FilterFunction::Blur`], so the same caveats
+    /// apply.
+    pub fn push_blur_layer(&mut self, radius: f32) {
+        self.push_filter_layer(Filter::from_function(FilterFunction::Blur { radius }));
+    }
+
+    /// Push a layer that is drawn on top of a blurred copy of everything drawn before it.
+    ///
+    /// This is the "frosted glass" effect of the CSS `backdrop-filter` property: the backdrop
+    /// within `clip` is blurred with the standard deviation `radius`, and all drawing
+    /// operations until the matching [`RenderContext::pop_layer`] are clipped to `clip` and
+    /// composited on top of it. The clip path is filled with the current fill rule and
+    /// transform, and the transform also scales the blur.
+    ///
+    /// The backdrop is the content of the innermost pushed layer with a blend mode, opacity,
+    /// mask or filter, or of the root layer if there is none, including content that lies
+    /// outside of `clip` but is within reach of the blur. Clip layers in between don't clip
+    /// the backdrop.
+    ///
+    /// When rendering with multiple threads, the backdrop is currently not blurred.
+    pub fn push_backdrop_blur_layer(&mut self, radius: f32, clip: &BezPath) {
+        self.record(|| Command::PushBackdropBlurLayer(radius, Payload::path(clip)));
+        let transform = self.device_transform();
+        self.dispatcher.push_backdrop_filter(
+            clip,
+            self.state.fill_rule,
+            transform,
+            self.aliasing,
+            Filter::from_function(FilterFunction::Blur { radius }),
+        );
+        self.push_layer_unrecorded(Some(clip), None, None, None, None);
+    }
+
     /// Set the aliasing threshold.
     ///
     /// If set to `None` (which is the recommended option in nearly all cases),
@@ -453,16 +1132,53 @@ impl RenderContext {
     /// Note that there is no performance benefit to disabling anti-aliasing and
     /// this functionality is simply provided for compatibility.
     pub fn set_aliasing_threshold(&mut self, aliasing_threshold: Option<u8>) {
-        self.aliasing_threshold = aliasing_threshold;
+        self.record(|| Command::SetAliasingThreshold(aliasing_threshold));
+        self.aliasing.threshold = aliasing_threshold;
+    }
+
+    /// Set whether the edges of paths are snapped to the pixel grid.
+    ///
+    /// When enabled, the vertices of paths are moved to the nearest pixel corner after
+    /// transforming and flattening them, and anti-aliasing is disabled. This keeps hairlines,
+    /// dividers and other axis-aligned shapes crisp without having to round their coordinates
+    /// beforehand. Pixels are painted if their coverage reaches the
+    /// [aliasing threshold](Self::set_aliasing_threshold), or if they are at least half covered
+    /// if no threshold is set.
+    ///
+    /// Pixel snapping is disabled by default.
+    pub fn set_pixel_snapping(&mut self, snap_to_pixels: bool) {
+        self.record(|| Command::SetPixelSnapping(snap_to_pixels));
+        self.aliasing.snap_to_pixels = snap_to_pixels;
+    }
+
+    /// Set the cache that gradient lookup tables are resolved through.
+    ///
+    /// By default, the lookup table of each gradient is generated separately during
+    /// rasterization. Sharing a [`GradientLutCache`] between several render contexts (or with
+    /// `vello_hybrid`) means that each gradient ramp is only computed once, and reused across
+    /// frames for as long as it stays in the cache.
+    #[cfg(feature = "std")]
+    pub fn set_gradient_lut_cache(&mut self, cache: Option<GradientLutCache>) {
+        self.gradient_lut_cache = cache;
+    }
+
+    /// Remove all blurred silhouettes that were cached by [`RenderContext::fill_drop_shadow`].
+    ///
+    /// Only a limited number of silhouettes is cached, so this is only needed to release their
+    /// memory early.
+    pub fn clear_shadow_cache(&mut self) {
+        self.shadow_cache.clear();
     }
 
     /// Pop the last-pushed layer.
     pub fn pop_layer(&mut self) {
+        self.record(|| Command::PopLayer);
         self.dispatcher.pop_layer();
     }
 
     /// Set the current stroke.
     pub fn set_stroke(&mut self, stroke: Stroke) {
+        self.record(|| Command::SetStroke(stroke.clone()));
         self.state.stroke = stroke;
     }
 
@@ -483,7 +1199,9 @@ impl RenderContext {
     /// resolved to the corresponding pixmap at rasterization time.
     /// Make sure to register images with [`Resources::register_image`] first.
     pub fn set_paint(&mut self, paint: impl Into<PaintType>) {
-        self.state.paint = paint.into();
+        let paint = paint.into();
+        self.record(|| Command::SetPaint(Payload::paint(&paint)));
+        self.state.paint = paint;
     }
 
     /// Get the current paint.
@@ -493,16 +1211,31 @@ impl RenderContext {
 
     /// Set the tint for subsequent image paint operations.
     pub fn set_tint(&mut self, tint: Option<Tint>) {
+        self.record(|| Command::SetTint(tint));
         self.state.tint = tint;
     }
 
     /// Clear the tint, so subsequent image paints are drawn without tinting.
     pub fn reset_tint(&mut self) {
+        self.record(|| Command::SetTint(None));
         self.state.tint = None;
     }
 
+    /// Set the interpolation hints for subsequent gradient paint operations.
+    ///
+    /// The hint at index `i` is the offset between the color stops `i` and `i + 1` at which
+    /// the color is halfway between the colors of the two stops, like the color hints of CSS
+    /// gradients. `None` entries keep the transition linear, and an empty slice removes all
+    /// hints. See [`GradientHints`] for details.
+    pub fn set_gradient_hints(&mut self, hints: &[Option<f32>]) {
+        let hints = GradientHints::new(hints);
+        self.record(|| Command::SetGradientHints(hints.clone()));
+        self.state.gradient_hints = hints;
+    }
+
     /// Set the blend mode that should be used when drawing objects.
     pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.record(|| Command::SetBlendMode(blend_mode));
         self.state.blend_mode = blend_mode;
     }
 
@@ -517,6 +1250,7 @@ impl RenderContext {
     /// is drawn in, i.e., the paint transform is applied after the global transform. This allows
     /// transforming the paint independently from the drawn geometry.
     pub fn set_paint_transform(&mut self, paint_transform: Affine) {
+        self.record(|| Command::SetPaintTransform(paint_transform));
         self.state.paint_transform = paint_transform;
     }
 
@@ -527,11 +1261,13 @@ impl RenderContext {
 
     /// Reset the current paint transform.
     pub fn reset_paint_transform(&mut self) {
+        self.record(|| Command::SetPaintTransform(Affine::IDENTITY));
         self.state.paint_transform = Affine::IDENTITY;
     }
 
     /// Set the current fill rule.
     pub fn set_fill_rule(&mut self, fill_rule: Fill) {
+        self.record(|| Command::SetFillRule(fill_rule));
         self.state.fill_rule = fill_rule;
     }
 
@@ -542,11 +1278,13 @@ impl RenderContext {
     /// See the explanation in the [masking](https://github.com/linebender/vello/tree/main/sparse_strips/masking/examples)
     /// example for how this method differs from `push_mask_layer`.
     pub fn set_mask(&mut self, mask: Mask) {
+        self.record(|| Command::set_mask(&mask));
         self.mask = Some(mask);
     }
 
     /// Reset the mask that is used for path-painting operations.
     pub fn reset_mask(&mut self) {
+        self.record(|| Command::ResetMask);
         self.mask = None;
     }
 
@@ -557,6 +1295,7 @@ impl RenderContext {
 
     /// Set the current transform.
     pub fn set_transform(&mut self, transform: Affine) {
+        self.record(|| Command::SetTransform(transform));
         self.state.transform = transform;
     }
 
@@ -567,6 +1306,7 @@ impl RenderContext {
 
     /// Reset the current transform.
     pub fn reset_transform(&mut self) {
+        self.record(|| Command::SetTransform(Affine::IDENTITY));
         self.state.transform = Affine::IDENTITY;
     }
 
@@ -575,20 +1315,27 @@ impl RenderContext {
     /// This sets a filter that will be applied to the next drawn element.
     /// To apply a filter to multiple elements, use `push_filter_layer` instead.
     pub fn set_filter_effect(&mut self, filter: Filter) {
+        self.record(|| Command::set_filter_effect(&filter));
         self.filter = Some(filter);
     }
 
     /// Reset the current filter effect.
     pub fn reset_filter_effect(&mut self) {
+        self.record(|| Command::ResetFilterEffect);
         self.filter = None;
     }
 
     /// Reset the render context.
     pub fn reset(&mut self) {
+        self.record(|| Command::Reset);
         self.dispatcher.reset();
         self.encoded_paints.clear();
+        self.gradient_stops.maintain();
         self.mask = None;
+        self.mask_groups.clear();
         self.state.reset();
+        self.access_tag = None;
+        self.access_geometry.clear();
     }
 
     /// Push a new clip path to the clip stack.
@@ -596,12 +1343,10 @@ impl RenderContext {
     /// See the explanation in the [clipping](https://github.com/linebender/vello/tree/main/sparse_strips/vello_cpu/examples)
     /// example for how this method differs from `push_clip_layer`.
     pub fn push_clip_path(&mut self, path: &BezPath) {
-        self.dispatcher.push_clip_path(
-            path,
-            self.state.fill_rule,
-            self.state.transform,
-            self.aliasing_threshold,
-        );
+        self.record(|| Command::PushClipPath(Payload::path(path)));
+        let transform = self.device_transform();
+        self.dispatcher
+            .push_clip_path(path, self.state.fill_rule, transform, self.aliasing);
     }
 
     /// Pop a clip path from the clip stack.
@@ -609,6 +1354,7 @@ impl RenderContext {
     /// Note that unlike `push_clip_layer`, it is permissible to have pending
     /// pushed clip paths before finishing the rendering operation.
     pub fn pop_clip_path(&mut self) {
+        self.record(|| Command::PopClipPath);
         self.dispatcher.pop_clip_path();
     }
 
@@ -618,30 +1364,427 @@ impl RenderContext {
     /// For multi-threaded rendering, you _have_ to call this before rasterizing, otherwise
     /// the program will panic.
     pub fn flush(&mut self) {
+        self.record(|| Command::Flush);
         self.dispatcher.flush(&self.encoded_paints);
     }
 
-    /// Render the current context into a buffer.
-    /// The buffer is expected to be in premultiplied RGBA8 format with length `width * height * 4`
-    pub fn render_to_buffer(
-        &self,
-        resources: &mut Resources,
-        buffer: &mut [u8],
-        width: u16,
-        height: u16,
-        render_mode: RenderMode,
-    ) {
-        // TODO: Maybe we should move those checks into the dispatcher.
-        let wide = self.dispatcher.wide();
-        assert!(!wide.has_layers(), "some layers haven't been popped yet");
-        assert_eq!(
-            buffer.len(),
-            (width as usize) * (height as usize) * 4,
-            "provided width ({}) and height ({}) do not match buffer size ({})",
-            width,
+    /// Set how the render target is initialized in [`render_to_buffer`](Self::render_to_buffer)
+    /// and [`render_to_pixmap`](Self::render_to_pixmap).
+    ///
+    /// By default, the target is cleared to transparent black. With [`LoadOp::Load`], the
+    /// existing contents of the target are preserved and the scene is composited on top of
+    /// them. Note that backdrop filters don't see the preserved contents.
+    ///
+    /// The load op is kept when the render context is [reset](Self::reset).
+    pub fn set_load_op(&mut self, load_op: LoadOp) {
+        self.load_op = load_op;
+    }
+
+    /// Return how the render target is initialized before rendering.
+    pub fn load_op(&self) -> LoadOp {
+        self.load_op
+    }
+
+    /// Set the pixel format of the buffers passed to [`render_to_buffer`](Self::render_to_buffer).
+    ///
+    /// By default, buffers are RGBA. With other formats, such as [`PixelFormat::Bgra8`] or
+    /// [`PixelFormat::Rgb565`], the pixels are converted as each tile is written while
+    /// rasterizing, so the buffer can be handed to a compositor or display that expects that
+    /// format without another pass over it. [`render_to_pixmap`](Self::render_to_pixmap)
+    /// always produces RGBA, since that is the format of a [`Pixmap`].
+    ///
+    /// [`PixelFormat::A8`] renders a coverage mask instead, skipping the evaluation of paints.
+    /// [`PixelFormat::Rgba32Float`] composites in linear light and stores the results as
+    /// floats. Render with [`RenderMode::OptimizeQuality`] to also avoid rounding to 8 bits
+    /// while compositing.
+    ///
+    /// The pixel format is kept when the render context is [reset](Self::reset).
+    pub fn set_pixel_format(&mut self, pixel_format: PixelFormat) {
+        self.pixel_format = pixel_format;
+    }
+
+    /// Return the pixel format of the buffers passed to
+    /// [`render_to_buffer`](Self::render_to_buffer).
+    pub fn pixel_format(&self) -> PixelFormat {
+        self.pixel_format
+    }
+
+    /// Set the alpha mode of the buffers passed to [`render_to_buffer`](Self::render_to_buffer).
+    ///
+    /// By default, buffers are premultiplied. With [`AlphaMode::Straight`], the pixels are
+    /// unpremultiplied as each tile is written, before they're converted to the
+    /// [pixel format](Self::set_pixel_format), and with [`LoadOp::Load`], the existing
+    /// contents of the buffer are expected to have straight alpha as well.
+    /// [`render_to_pixmap`](Self::render_to_pixmap) always produces premultiplied pixels,
+    /// since that is what a [`Pixmap`] holds.
+    ///
+    /// The alpha mode is kept when the render context is [reset](Self::reset).
+    pub fn set_alpha_mode(&mut self, alpha_mode: AlphaMode) {
+        self.alpha_mode = alpha_mode;
+    }
+
+    /// Return the alpha mode of the buffers passed to
+    /// [`render_to_buffer`](Self::render_to_buffer).
+    pub fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    /// Set whether colors are blended in linear light.
+    ///
+    /// By default, colors are blended as they are given, encoded in sRGB, which makes
+    /// anti-aliased edges and the transitions between translucent shapes look darker than they
+    /// should. With linear compositing, the colors of paints are decoded to linear before
+    /// blending, and the results are encoded to sRGB again when they are written to the
+    /// buffer. Render with [`RenderMode::OptimizeQuality`] to avoid the banding that storing
+    /// linear colors with 8 bits causes in dark gradients.
+    ///
+    /// [`PixelFormat::Rgba32Float`] always composites in linear light. Linear compositing
+    /// is kept when the render context is [reset](Self::reset).
+    pub fn set_linear_compositing(&mut self, linear: bool) {
+        self.linear_compositing = linear;
+        self.dispatcher.set_linear_compositing(linear);
+    }
+
+    /// Return whether colors are blended in linear light.
+    pub fn linear_compositing(&self) -> bool {
+        self.linear_compositing
+    }
+
+    /// Set whether colors are dithered when they are rounded to 8 bits per channel.
+    ///
+    /// With [`RenderMode::OptimizeQuality`] and the `f32_pipeline` feature, colors are
+    /// composited with more precision than the render target stores, and rounding them to the
+    /// nearest 8-bit value at the end causes visible bands in subtle gradients. With
+    /// dithering, an ordered 4x4 pattern decides whether each pixel is rounded up or down, so
+    /// that the colors are correct on average. The [`OptimizeSpeed`](RenderMode::OptimizeSpeed)
+    /// pipeline composites with 8 bits, so there is nothing to dither.
+    ///
+    /// This applies to [`render_to_pixmap`](Self::render_to_pixmap) as well as to
+    /// [`render_to_buffer`](Self::render_to_buffer) with 8-bit pixel formats. The 16-bit
+    /// formats, like [`PixelFormat::Rgb565`], have their own `dither` flag for reducing the
+    /// 8-bit colors further. Error diffusion isn't offered, because it would make every pixel
+    /// depend on the ones rendered before it. Dithering is kept when the render context is
+    /// [reset](Self::reset).
+    pub fn set_dither(&mut self, dither: bool) {
+        self.dither = dither;
+        self.dispatcher.set_dither(dither);
+    }
+
+    /// Return whether colors are dithered when they are rounded to 8 bits per channel.
+    pub fn dither(&self) -> bool {
+        self.dither
+    }
+
+    /// Return statistics about the paths drawn since the render context was last
+    /// [reset](Self::reset).
+    ///
+    /// The statistics are complete once the render context is [flushed](Self::flush), so
+    /// they are usually read after rendering a frame and before resetting for the next one.
+    /// [`RenderStats::draw_calls`] is always zero, since nothing is drawn on a GPU.
+    pub fn render_stats(&self) -> RenderStats {
+        self.dispatcher.stats()
+    }
+
+    /// Set the quality preset, which trades the accuracy of subsequently drawn content for
+    /// speed.
+    ///
+    /// The preset sets the tolerance for flattening curves, the size of the lookup tables of
+    /// gradients and the number of taps of blurs, and it sets the
+    /// [aliasing threshold](Self::set_aliasing_threshold), which can be overridden afterwards.
+    /// Use [`Quality::Fast`] for previews during interaction and [`Quality::High`] for final
+    /// output. `vello_hybrid` applies the same presets in the same way.
+    ///
+    /// The default is [`Quality::Balanced`]. The quality is kept when the render context is
+    /// [reset](Self::reset).
+    pub fn set_quality(&mut self, quality: Quality) {
+        self.quality = quality;
+        self.set_aliasing_threshold(quality.aliasing_threshold());
+        self.dispatcher.set_quality(quality);
+    }
+
+    /// Return the quality preset.
+    pub fn quality(&self) -> Quality {
+        self.quality
+    }
+
+    /// Render the current context into a buffer.
+    /// The buffer is expected to be in the [pixel format](Self::set_pixel_format) and
+    /// [alpha mode](Self::set_alpha_mode) of the context, which are premultiplied RGBA8 by
+    /// default, with a length of
+    /// `width * height * pixel_format.bytes_per_pixel()`.
+    ///
+    /// The buffer is initialized according to the [load op](Self::set_load_op) first.
+    pub fn render_to_buffer(
+        &self,
+        resources: &mut Resources,
+        buffer: &mut [u8],
+        width: u16,
+        height: u16,
+        render_mode: RenderMode,
+    ) {
+        let stride = (width as usize) * self.pixel_format.bytes_per_pixel();
+        assert_eq!(
+            buffer.len(),
+            stride * (height as usize),
+            "provided width ({}) and height ({}) do not match buffer size ({})",
+            width,
+            height,
+            buffer.len(),
+        );
+
+        self.render_to_buffer_as(
+            resources,
+            buffer,
+            width,
+            height,
+            stride,
+            render_mode,
+            self.pixel_format,
+            self.alpha_mode,
+        );
+    }
+
+    /// Render the current context into a buffer whose rows are `stride` bytes apart.
+    ///
+    /// This allows rendering directly into memory with padding at the end of each row, like
+    /// an existing framebuffer, instead of copying a tightly packed buffer into it. Apart
+    /// from that, this works like [`render_to_buffer`](Self::render_to_buffer). The padding
+    /// is left untouched, and the last row doesn't need to be padded, so the buffer must
+    /// have a length of at least `stride * (height - 1) + width * pixel_format.bytes_per_pixel()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `stride` is less than the length of a row, or the buffer is too short.
+    pub fn render_to_buffer_with_stride(
+        &self,
+        resources: &mut Resources,
+        buffer: &mut [u8],
+        width: u16,
+        height: u16,
+        stride: usize,
+        render_mode: RenderMode,
+    ) {
+        let row_len = (width as usize) * self.pixel_format.bytes_per_pixel();
+        assert!(
+            stride >= row_len,
+            "stride ({stride}) is less than the length of a row ({row_len})"
+        );
+        let min_len = (height as usize)
+            .checked_sub(1)
+            .map_or(0, |rows| stride * rows + row_len);
+        assert!(
+            buffer.len() >= min_len,
+            "buffer size ({}) is too small for width ({width}), height ({height}) and stride ({stride})",
+            buffer.len(),
+        );
+
+        self.render_to_buffer_as(
+            resources,
+            buffer,
+            width,
+            height,
+            stride,
+            render_mode,
+            self.pixel_format,
+            self.alpha_mode,
+        );
+    }
+
+    /// Render the [region](Self::region) of the current context into a buffer for the full
+    /// target.
+    ///
+    /// The buffer has the layout expected by [`render_to_buffer`](Self::render_to_buffer)
+    /// for a target of `width` × `height` pixels, but only the pixels inside of the region are
+    /// initialized and written, the rest of the buffer is left untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer size doesn't match `width` and `height`, or the region doesn't
+    /// fit into the target.
+    pub fn render_region_to_buffer(
+        &self,
+        resources: &mut Resources,
+        buffer: &mut [u8],
+        width: u16,
+        height: u16,
+        render_mode: RenderMode,
+    ) {
+        let bytes_per_pixel = self.pixel_format.bytes_per_pixel();
+        let stride = (width as usize) * bytes_per_pixel;
+        assert_eq!(
+            buffer.len(),
+            stride * (height as usize),
+            "provided width ({}) and height ({}) do not match buffer size ({})",
+            width,
+            height,
+            buffer.len(),
+        );
+        let region = self.region;
+        assert!(
+            region.x1 <= width && region.y1 <= height,
+            "region ({region:?}) doesn't fit into width ({width}) and height ({height})",
+        );
+        if self.width == 0 || self.height == 0 {
+            return;
+        }
+
+        let offset = (region.y0 as usize) * stride + (region.x0 as usize) * bytes_per_pixel;
+        self.render_to_buffer_as(
+            resources,
+            &mut buffer[offset..],
+            self.width,
+            self.height,
+            stride,
+            render_mode,
+            self.pixel_format,
+            self.alpha_mode,
+        );
+    }
+
+    /// Render the current context into a buffer band by band, until `cancellation` is
+    /// cancelled.
+    ///
+    /// This works like [`render_to_buffer`](Self::render_to_buffer), but the token is checked
+    /// before each band of rows of tiles is rasterized, so that runaway frames can be aborted
+    /// from another thread. After a band is complete, `on_band` is called with the rows of
+    /// pixels it covers and their contents in the buffer, for example to display a preview
+    /// before the frame is finished. Bands are delivered from top to bottom.
+    ///
+    /// Returns [`Cancelled`] if the render was aborted, in which case the rows below the last
+    /// delivered band are left as they were.
+    pub fn render_to_buffer_progressive(
+        &self,
+        resources: &mut Resources,
+        buffer: &mut [u8],
+        width: u16,
+        height: u16,
+        render_mode: RenderMode,
+        cancellation: &CancellationToken,
+        mut on_band: impl FnMut(Range<u16>, &[u8]),
+    ) -> Result<(), Cancelled> {
+        let stride = (width as usize) * self.pixel_format.bytes_per_pixel();
+        assert_eq!(
+            buffer.len(),
+            stride * (height as usize),
+            "provided width ({}) and height ({}) do not match buffer size ({})",
+            width,
             height,
             buffer.len(),
         );
+        let wide = self.dispatcher.wide();
+        assert!(!wide.has_layers(), "some layers haven't been popped yet");
+        if buffer.is_empty() {
+            return Ok(());
+        }
+
+        resources.before_render();
+
+        let band_rows = self.dispatcher.band_rows().max(1);
+        let band_height = usize::from(band_rows) * usize::from(Tile::HEIGHT);
+        let mut result = Ok(());
+        for (band_idx, band) in buffer.chunks_mut(band_height * stride).enumerate() {
+            if cancellation.is_cancelled() {
+                result = Err(Cancelled);
+                break;
+            }
+
+            let y = (band_idx * band_height) as u16;
+            let rows = (band.len() / stride) as u16;
+            self.dispatcher.rasterize(
+                band,
+                render_mode,
+                width,
+                rows,
+                &self.encoded_paints,
+                &resources.image_registry,
+                self.load_op,
+                self.pixel_format,
+                self.alpha_mode,
+                stride,
+                band_idx as u16 * band_rows,
+            );
+            on_band(y..y + rows, band);
+        }
+
+        resources.after_render();
+        result
+    }
+
+    /// Render the current context band by band into a reusable buffer that only holds
+    /// `band_height` rows, so that targets which are too large to keep in memory can be
+    /// streamed to an encoder.
+    ///
+    /// `band_height` is rounded up to a multiple of the tile height. After each band is
+    /// rasterized, `on_band` is called with the rows of pixels it covers and their contents,
+    /// in the [pixel format](Self::set_pixel_format) and [alpha mode](Self::set_alpha_mode) of
+    /// the context, without padding between rows. Bands are delivered from top to bottom, and
+    /// the last band may be shorter than the others.
+    ///
+    /// Since there is no existing target to draw over, [`LoadOp::Load`] draws each band over
+    /// transparent black.
+    pub fn render_in_bands(
+        &self,
+        resources: &mut Resources,
+        width: u16,
+        height: u16,
+        band_height: u16,
+        render_mode: RenderMode,
+        mut on_band: impl FnMut(Range<u16>, &[u8]),
+    ) {
+        let wide = self.dispatcher.wide();
+        assert!(!wide.has_layers(), "some layers haven't been popped yet");
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        resources.before_render();
+
+        let band_rows = band_height
+            .div_ceil(Tile::HEIGHT)
+            .clamp(1, u16::MAX / Tile::HEIGHT);
+        let band_height = band_rows * Tile::HEIGHT;
+        let stride = usize::from(width) * self.pixel_format.bytes_per_pixel();
+        let mut buffer = vec![0; usize::from(band_height.min(height)) * stride];
+        for (band_idx, y) in (0..height).step_by(usize::from(band_height)).enumerate() {
+            let rows = band_height.min(height - y);
+            let band = &mut buffer[..usize::from(rows) * stride];
+            if self.load_op == LoadOp::Load {
+                band.fill(0);
+            }
+
+            self.dispatcher.rasterize(
+                band,
+                render_mode,
+                width,
+                rows,
+                &self.encoded_paints,
+                &resources.image_registry,
+                self.load_op,
+                self.pixel_format,
+                self.alpha_mode,
+                stride,
+                band_idx as u16 * band_rows,
+            );
+            on_band(y..y + rows, band);
+        }
+
+        resources.after_render();
+    }
+
+    fn render_to_buffer_as(
+        &self,
+        resources: &mut Resources,
+        buffer: &mut [u8],
+        width: u16,
+        height: u16,
+        stride: usize,
+        render_mode: RenderMode,
+        pixel_format: PixelFormat,
+        alpha_mode: AlphaMode,
+    ) {
+        // TODO: Maybe we should move those checks into the dispatcher.
+        let wide = self.dispatcher.wide();
+        assert!(!wide.has_layers(), "some layers haven't been popped yet");
 
         resources.before_render();
 
@@ -652,6 +1795,11 @@ impl RenderContext {
             height,
             &self.encoded_paints,
             &resources.image_registry,
+            self.load_op,
+            pixel_format,
+            alpha_mode,
+            stride,
+            0,
         );
         // TODO: We need to figure something out here API-wise. At the moment, the user can
         // theoretically rasterize the same `RenderContext` multiple times without resetting in-between.
@@ -665,12 +1813,15 @@ impl RenderContext {
     pub fn render_to_pixmap(&self, resources: &mut Resources, pixmap: &mut Pixmap) {
         let width = pixmap.width();
         let height = pixmap.height();
-        self.render_to_buffer(
+        self.render_to_buffer_as(
             resources,
             pixmap.data_as_u8_slice_mut(),
             width,
             height,
+            usize::from(width) * PixelFormat::Rgba8.bytes_per_pixel(),
             self.render_settings.render_mode,
+            PixelFormat::Rgba8,
+            AlphaMode::Premultiplied,
         );
     }
 
@@ -724,20 +1875,36 @@ impl RenderContext {
         self.height
     }
 
+    /// Return the region of the full target that is rendered.
+    ///
+    /// This covers the whole render context, unless it was created with
+    /// [`new_with_region`](Self::new_with_region).
+    pub fn region(&self) -> RectU16 {
+        self.region
+    }
+
     /// Return the render settings used by the `RenderContext`.
     pub fn render_settings(&self) -> &RenderSettings {
         &self.render_settings
     }
 
+    /// The current transform, followed by the translation from the coordinates of the full
+    /// target to those of the region.
+    fn device_transform(&self) -> Affine {
+        Affine::translate((-f64::from(self.region.x0), -f64::from(self.region.y0)))
+            * self.state.transform
+    }
+
     /// Execute a drawing operation, optionally wrapping it in a filter layer.
     fn with_optional_filter<F>(&mut self, mut f: F)
     where
         F: FnMut(&mut Self),
     {
         if let Some(filter) = self.filter.clone() {
-            self.push_filter_layer(filter);
+            // Not recorded, since replaying the filter effect pushes the layer again.
+            self.push_layer_unrecorded(None, None, None, None, Some(filter));
             f(self);
-            self.pop_layer();
+            self.dispatcher.pop_layer();
         } else {
             f(self);
         }
@@ -745,7 +1912,10 @@ impl RenderContext {
 
     /// Take current rendering state and reset the existing state to its default.
     pub fn take_current_state(&mut self) -> RenderState {
-        core::mem::take(&mut self.state)
+        let state = core::mem::take(&mut self.state);
+        self.record_state();
+
+        state
     }
 
     /// Save a copy of the current rendering state.
@@ -756,16 +1926,30 @@ impl RenderContext {
     /// Restore rendering state.
     pub fn restore_state(&mut self, state: RenderState) {
         self.state = state;
+        self.record_state();
     }
 }
 
 /// Image registry implementation.
 impl Resources {
     /// Register a pixmap in the image registry and return its [`ImageId`].
+    ///
+    /// Pixmaps that are tagged with a color space other than sRGB (see
+    /// [`Pixmap::set_color_space`]) are converted to sRGB first.
     pub fn register_image(&mut self, pixmap: Arc<Pixmap>) -> ImageId {
         self.image_registry.register(pixmap)
     }
 
+    /// Register a pixmap together with its mip chain and return its [`ImageId`].
+    ///
+    /// Paints that draw the image at less than half its native size sample from the
+    /// closest downsampled level instead of the full-resolution image, which avoids the
+    /// shimmering caused by skipping over source pixels. Building the chain costs roughly a
+    /// third of the image size in additional memory.
+    pub fn register_image_mipmapped(&mut self, pixmap: Arc<Pixmap>) -> ImageId {
+        self.image_registry.register_mipmapped(pixmap)
+    }
+
     /// Remove an image from the registry.
     pub fn destroy_image(&mut self, id: ImageId) -> bool {
         self.image_registry.destroy(id)
@@ -788,6 +1972,7 @@ impl Resources {
 #[derive(Debug, Default)]
 pub(crate) struct ImageRegistry {
     images: HashMap<u32, Arc<Pixmap>>,
+    mip_chains: HashMap<u32, MipChain>,
     next_id: u32,
 }
 
@@ -800,10 +1985,18 @@ impl ImageRegistry {
         );
 
         self.next_id += 1;
-        self.images.insert(id, pixmap);
+        self.images.insert(id, pixmap.into_srgb());
         ImageId::new(id)
     }
 
+    fn register_mipmapped(&mut self, pixmap: Arc<Pixmap>) -> ImageId {
+        let pixmap = pixmap.into_srgb();
+        let chain = MipChain::new(pixmap.clone());
+        let id = self.register(pixmap);
+        self.mip_chains.insert(id.as_u32(), chain);
+        id
+    }
+
     #[cfg(feature = "text")]
     pub(crate) fn register_atlas_page(&mut self, page_index: u32, pixmap: Arc<Pixmap>) {
         self.images.insert(
@@ -813,6 +2006,7 @@ impl ImageRegistry {
     }
 
     pub(crate) fn destroy(&mut self, id: ImageId) -> bool {
+        self.mip_chains.remove(&id.as_u32());
         self.images.remove(&id.as_u32()).is_some()
     }
 
@@ -827,6 +2021,7 @@ impl ImageRegistry {
 
     fn clear(&mut self) {
         self.images.clear();
+        self.mip_chains.clear();
         self.next_id = 0;
     }
 }
@@ -835,6 +2030,13 @@ impl ImageResolver for ImageRegistry {
     fn resolve(&self, id: ImageId) -> Option<Arc<Pixmap>> {
         self.images.get(&id.as_u32()).cloned()
     }
+
+    fn resolve_mip(&self, id: ImageId, lod: f32) -> Option<Arc<Pixmap>> {
+        let chain = self.mip_chains.get(&id.as_u32())?;
+        let level = nearest_level(lod, chain.len());
+
+        (level > 0).then(|| chain.level(level).clone())
+    }
 }
 
 #[cfg(test)]
@@ -862,6 +2064,45 @@ mod tests {
         ctx.flush();
     }
 
+    #[test]
+    fn winding_numbers_use_the_current_transform() {
+        use vello_common::kurbo::Affine;
+        use vello_common::winding::winding_color;
+
+        let mut ctx = RenderContext::new(20, 20);
+        let mut path = Rect::new(0.0, 0.0, 4.0, 4.0).to_path(0.1);
+        path.extend(Rect::new(2.0, 2.0, 6.0, 6.0).to_path(0.1));
+        ctx.set_transform(Affine::translate((10.0, 10.0)));
+        let pixmap = ctx.debug_winding_numbers(&path);
+
+        assert_eq!(pixmap.sample(5, 5), winding_color(0));
+        assert_eq!(pixmap.sample(11, 11), winding_color(1));
+        assert_eq!(pixmap.sample(13, 13), winding_color(2));
+    }
+
+    #[test]
+    fn drop_shadows_are_cached_across_translations() {
+        use vello_common::kurbo::{Affine, Circle, Vec2};
+
+        let mut ctx = RenderContext::new(100, 100);
+        let path = Circle::new((20.0, 20.0), 10.0).to_path(0.1);
+
+        ctx.fill_drop_shadow(&path, Vec2::new(2.0, 2.0), 3.0);
+        ctx.set_transform(Affine::translate((30.5, 10.0)));
+        ctx.fill_drop_shadow(&path, Vec2::new(5.0, 0.0), 3.0);
+        assert_eq!(ctx.shadow_cache.len(), 1);
+
+        ctx.set_transform(Affine::scale(2.0));
+        ctx.fill_drop_shadow(&path, Vec2::new(2.0, 2.0), 3.0);
+        ctx.fill_drop_shadow(&path, Vec2::new(2.0, 2.0), 4.0);
+        assert_eq!(ctx.shadow_cache.len(), 3);
+
+        ctx.reset();
+        assert_eq!(ctx.shadow_cache.len(), 3);
+        ctx.clear_shadow_cache();
+        assert_eq!(ctx.shadow_cache.len(), 0);
+    }
+
     #[cfg(feature = "multithreading")]
     #[test]
     fn multithreaded_crash_after_reset() {
@@ -885,6 +2126,1035 @@ mod tests {
         ctx.render_to_pixmap(&mut resources, &mut pixmap);
     }
 
+    #[cfg(feature = "multithreading")]
+    #[test]
+    fn multithreaded_rows_match_single_threaded() {
+        use crate::color::palette::css::{BLUE, RED};
+        use crate::kurbo::Circle;
+        use crate::{RenderSettings, Resources};
+        use vello_common::pixmap::Pixmap;
+
+        let render = |num_threads| {
+            let settings = RenderSettings {
+                num_threads,
+                ..RenderSettings::default()
+            };
+            let mut ctx = RenderContext::new_with(300, 200, settings);
+            ctx.set_paint(RED);
+            ctx.fill_path(&Circle::new((150.0, 100.0), 90.0).to_path(0.1));
+            ctx.set_paint(BLUE.with_alpha(0.5));
+            ctx.fill_path(&Rect::new(20.5, 10.5, 280.0, 60.25).to_path(0.1));
+            ctx.flush();
+
+            let mut pixmap = Pixmap::new(300, 200);
+            ctx.render_to_pixmap(&mut Resources::new(), &mut pixmap);
+            pixmap
+        };
+
+        assert_eq!(render(0).data(), render(4).data());
+    }
+
+    #[cfg(feature = "multithreading")]
+    #[test]
+    fn custom_executor_matches_single_threaded() {
+        use crate::color::palette::css::{GREEN, RED};
+        use crate::kurbo::Circle;
+        use crate::{Executor, RenderSettings, Resources, Task};
+        use alloc::vec::Vec;
+        use vello_common::pixmap::Pixmap;
+
+        /// Spawns a thread for each background task and runs joined tasks in order.
+        #[derive(Debug)]
+        struct ThreadPerTask;
+
+        impl Executor for ThreadPerTask {
+            fn num_threads(&self) -> usize {
+                3
+            }
+
+            fn spawn(&self, task: Task<'static>) {
+                std::thread::spawn(task);
+            }
+
+            fn join(&self, tasks: Vec<Task<'_>>) {
+                tasks.into_iter().for_each(|task| task());
+            }
+        }
+
+        let draw = |ctx: &mut RenderContext| {
+            ctx.set_paint(GREEN);
+            ctx.fill_path(&Circle::new((120.0, 80.0), 70.0).to_path(0.1));
+            ctx.set_paint(RED.with_alpha(0.5));
+            ctx.fill_path(&Rect::new(10.5, 100.5, 230.0, 150.25).to_path(0.1));
+            ctx.flush();
+
+            let mut pixmap = Pixmap::new(240, 160);
+            ctx.render_to_pixmap(&mut Resources::new(), &mut pixmap);
+            pixmap
+        };
+
+        let mut single = RenderContext::new(240, 160);
+        let mut custom = RenderContext::new_with_executor(
+            240,
+            160,
+            RenderSettings::default(),
+            Arc::new(ThreadPerTask),
+        );
+        assert_eq!(draw(&mut single).data(), draw(&mut custom).data());
+
+        // The workers are spawned again after a reset.
+        custom.reset();
+        assert_eq!(draw(&mut single).data(), draw(&mut custom).data());
+    }
+
+    #[cfg(feature = "multithreading")]
+    #[test]
+    fn contexts_share_single_thread_executor() {
+        use crate::color::palette::css::{BLUE, RED};
+        use crate::kurbo::Circle;
+        use crate::{RenderSettings, Resources};
+        use vello_common::pixmap::Pixmap;
+
+        let executor = vello_common::executor::thread_pool(1);
+        let new_ctx = || {
+            RenderContext::new_with_executor(120, 80, RenderSettings::default(), executor.clone())
+        };
+        let render = |ctx: &mut RenderContext| {
+            ctx.flush();
+            let mut pixmap = Pixmap::new(120, 80);
+            ctx.render_to_pixmap(&mut Resources::new(), &mut pixmap);
+            pixmap
+        };
+
+        let mut expected = RenderContext::new(120, 80);
+        expected.set_paint(RED);
+        expected.fill_path(&Circle::new((60.0, 40.0), 30.0).to_path(0.1));
+        let expected = render(&mut expected);
+
+        // Both contexts have pending paths when the second one is flushed, so the tasks of the
+        // first one must not occupy the only thread of the executor.
+        let mut first = new_ctx();
+        let mut second = new_ctx();
+        first.set_paint(BLUE);
+        first.fill_rect(&Rect::new(10.0, 10.0, 50.0, 50.0));
+        second.set_paint(RED);
+        second.fill_path(&Circle::new((60.0, 40.0), 30.0).to_path(0.1));
+        assert_eq!(render(&mut second).data(), expected.data());
+
+        first.reset();
+        first.set_paint(RED);
+        first.fill_path(&Circle::new((60.0, 40.0), 30.0).to_path(0.1));
+        assert_eq!(render(&mut first).data(), expected.data());
+    }
+
+    #[test]
+    fn visible_access_tags_exclude_covered_primitives() {
+        use crate::RenderSettings;
+        use crate::color::palette::css::{BLUE, RED};
+        use vello_common::accessibility::AccessTag;
+
+        let thread_counts: &[u16] = if cfg!(feature = "multithreading") {
+            &[0, 2]
+        } else {
+            &[0]
+        };
+
+        for &num_threads in thread_counts {
+            let settings = RenderSettings {
+                num_threads,
+                ..RenderSettings::default()
+            };
+            let mut ctx = RenderContext::new_with(512, 8, settings);
+
+            ctx.set_paint(RED);
+            ctx.set_access_tag(Some(AccessTag(1)));
+            ctx.fill_rect(&Rect::new(300.0, 0.0, 310.0, 4.0));
+            ctx.set_access_tag(Some(AccessTag(2)));
+            ctx.fill_rect(&Rect::new(600.0, 0.0, 610.0, 4.0));
+            ctx.set_paint(BLUE);
+            ctx.set_access_tag(Some(AccessTag(3)));
+            ctx.fill_path(&Rect::new(-10.0, -10.0, 600.0, 20.0).to_path(0.1));
+            ctx.set_paint(RED.with_alpha(0.5));
+            ctx.set_access_tag(Some(AccessTag(4)));
+            ctx.fill_path(&Rect::new(320.5, 0.0, 330.0, 4.0).to_path(0.1));
+            ctx.set_access_tag(None);
+            ctx.fill_rect(&Rect::new(340.0, 0.0, 350.0, 4.0));
+            ctx.flush();
+
+            assert_eq!(ctx.visible_access_tags(), [AccessTag(3), AccessTag(4)]);
+            assert_eq!(ctx.access_geometry().nodes().len(), 4);
+
+            ctx.reset();
+            assert!(ctx.visible_access_tags().is_empty());
+        }
+    }
+
+    #[test]
+    fn bgra_buffers_match_rgba_buffers() {
+        use crate::color::palette::css::{BLUE, RED};
+        use crate::kurbo::Circle;
+        use crate::{LoadOp, PixelFormat, RenderMode, RenderSettings};
+        use alloc::vec;
+        use alloc::vec::Vec;
+
+        let thread_counts: &[u16] = if cfg!(feature = "multithreading") {
+            &[0, 2]
+        } else {
+            &[0]
+        };
+        // A target that isn't symmetric in red and blue, to check that loading it is swizzled too.
+        let background: Vec<u8> = [40, 80, 160, 255].repeat(300 * 20);
+        let swizzle = |buffer: &mut [u8]| buffer.chunks_exact_mut(4).for_each(|p| p.swap(0, 2));
+
+        for &num_threads in thread_counts {
+            let settings = RenderSettings {
+                num_threads,
+                ..RenderSettings::default()
+            };
+            let mut resources = crate::Resources::new();
+            let mut ctx = RenderContext::new_with(300, 20, settings);
+            ctx.set_paint(RED.with_alpha(0.75));
+            ctx.fill_path(&Circle::new((20.0, 10.0), 8.0).to_path(0.1));
+            ctx.set_paint(BLUE.with_alpha(0.5));
+            ctx.fill_path(&Rect::new(10.5, 2.5, 290.0, 15.25).to_path(0.1));
+            ctx.flush();
+
+            for load_op in [LoadOp::ClearTransparent, LoadOp::Load] {
+                ctx.set_load_op(load_op);
+                let mut render = |pixel_format, buffer: &mut [u8]| {
+                    ctx.set_pixel_format(pixel_format);
+                    ctx.render_to_buffer(
+                        &mut resources,
+                        buffer,
+                        300,
+                        20,
+                        RenderMode::OptimizeSpeed,
+                    );
+                };
+
+                let mut rgba = background.clone();
+                render(PixelFormat::Rgba8, &mut rgba);
+                let mut bgra = background.clone();
+                swizzle(&mut bgra);
+                render(PixelFormat::Bgra8, &mut bgra);
+                swizzle(&mut bgra);
+
+                assert_eq!(rgba, bgra, "num_threads: {num_threads}, {load_op:?}");
+                assert_ne!(rgba, vec![0; rgba.len()]);
+            }
+        }
+    }
+
+    #[test]
+    fn low_bit_depth_buffers_match_converted_rgba_buffers() {
+        use crate::color::palette::css::{BLUE, RED};
+        use crate::kurbo::Circle;
+        use crate::pixel_format::{
+            decode_argb1555, decode_rgb565, encode_argb1555, encode_rgb565, threshold,
+        };
+        use crate::{LoadOp, PixelFormat, RenderMode, RenderSettings};
+        use alloc::vec::Vec;
+
+        const WIDTH: u16 = 300;
+        const HEIGHT: u16 = 20;
+
+        type Encode = fn([u8; 4], u16) -> [u8; 2];
+        type Decode = fn([u8; 2]) -> [u8; 4];
+        let formats: [(PixelFormat, Encode, Decode, bool); 4] = [
+            (
+                PixelFormat::Rgb565 { dither: false },
+                encode_rgb565,
+                decode_rgb565,
+                false,
+            ),
+            (
+                PixelFormat::Rgb565 { dither: true },
+                encode_rgb565,
+                decode_rgb565,
+                true,
+            ),
+            (
+                PixelFormat::Argb1555 { dither: false },
+                encode_argb1555,
+                decode_argb1555,
+                false,
+            ),
+            (
+                PixelFormat::Argb1555 { dither: true },
+                encode_argb1555,
+                decode_argb1555,
+                true,
+            ),
+        ];
+        let thread_counts: &[u16] = if cfg!(feature = "multithreading") {
+            &[0, 2]
+        } else {
+            &[0]
+        };
+
+        for &num_threads in thread_counts {
+            let settings = RenderSettings {
+                num_threads,
+                ..RenderSettings::default()
+            };
+            let mut resources = crate::Resources::new();
+            let mut ctx = RenderContext::new_with(WIDTH, HEIGHT, settings);
+            ctx.set_paint(RED.with_alpha(0.75));
+            ctx.fill_path(&Circle::new((20.0, 10.0), 8.0).to_path(0.1));
+            ctx.set_paint(BLUE.with_alpha(0.5));
+            ctx.fill_path(&Rect::new(10.5, 2.5, 290.0, 15.25).to_path(0.1));
+            ctx.flush();
+
+            for (pixel_format, encode, decode, dither) in formats {
+                // A valid pixel of the format, to check that loading the target converts it.
+                let background: Vec<u8> =
+                    encode([40, 80, 160, 255], 127).repeat(usize::from(WIDTH * HEIGHT));
+
+                for load_op in [LoadOp::ClearTransparent, LoadOp::Load] {
+                    ctx.set_load_op(load_op);
+
+                    let mut rgba: Vec<u8> = background
+                        .chunks_exact(2)
+                        .flat_map(|pixel| decode(pixel.try_into().unwrap()))
+                        .collect();
+                    ctx.set_pixel_format(PixelFormat::Rgba8);
+                    ctx.render_to_buffer(
+                        &mut resources,
+                        &mut rgba,
+                        WIDTH,
+                        HEIGHT,
+                        RenderMode::OptimizeSpeed,
+                    );
+                    let expected: Vec<u8> = rgba
+                        .chunks_exact(4)
+                        .enumerate()
+                        .flat_map(|(i, pixel)| {
+                            let (x, y) = (i % usize::from(WIDTH), i / usize::from(WIDTH));
+                            encode(pixel.try_into().unwrap(), threshold(dither, x, y))
+                        })
+                        .collect();
+
+                    let mut converted = background.clone();
+                    ctx.set_pixel_format(pixel_format);
+                    ctx.render_to_buffer(
+                        &mut resources,
+                        &mut converted,
+                        WIDTH,
+                        HEIGHT,
+                        RenderMode::OptimizeSpeed,
+                    );
+
+                    assert_eq!(
+                        converted, expected,
+                        "num_threads: {num_threads}, {pixel_format:?}, {load_op:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn straight_alpha_buffers_match_unpremultiplied_buffers() {
+        use crate::color::palette::css::{BLUE, RED};
+        use crate::kurbo::Circle;
+        use crate::pixel_format::{premultiply, unpremultiply};
+        use crate::{AlphaMode, LoadOp, PixelFormat, RenderMode, RenderSettings};
+        use alloc::vec::Vec;
+
+        let thread_counts: &[u16] = if cfg!(feature = "multithreading") {
+            &[0, 2]
+        } else {
+            &[0]
+        };
+        let convert = |buffer: &mut [u8], convert: fn([u8; 4]) -> [u8; 4]| {
+            for pixel in buffer.chunks_exact_mut(4) {
+                let converted = convert(pixel.try_into().unwrap());
+                pixel.copy_from_slice(&converted);
+            }
+        };
+        // A translucent target with straight alpha, to check that loading it is premultiplied.
+        let straight_background: Vec<u8> = [200, 100, 50, 96].repeat(300 * 20);
+        let mut background = straight_background.clone();
+        convert(&mut background, premultiply);
+
+        for &num_threads in thread_counts {
+            let settings = RenderSettings {
+                num_threads,
+                ..RenderSettings::default()
+            };
+            let mut resources = crate::Resources::new();
+            let mut ctx = RenderContext::new_with(300, 20, settings);
+            ctx.set_paint(RED.with_alpha(0.75));
+            ctx.fill_path(&Circle::new((20.0, 10.0), 8.0).to_path(0.1));
+            ctx.set_paint(BLUE.with_alpha(0.3));
+            ctx.fill_path(&Rect::new(10.5, 2.5, 290.0, 15.25).to_path(0.1));
+            ctx.flush();
+
+            for load_op in [LoadOp::ClearTransparent, LoadOp::Load] {
+                for pixel_format in [PixelFormat::Rgba8, PixelFormat::Bgra8] {
+                    ctx.set_load_op(load_op);
+                    ctx.set_pixel_format(pixel_format);
+                    let mut render = |alpha_mode, buffer: &mut [u8]| {
+                        ctx.set_alpha_mode(alpha_mode);
+                        ctx.render_to_buffer(
+                            &mut resources,
+                            buffer,
+                            300,
+                            20,
+                            RenderMode::OptimizeSpeed,
+                        );
+                    };
+
+                    let mut expected = background.clone();
+                    render(AlphaMode::Premultiplied, &mut expected);
+                    convert(&mut expected, unpremultiply);
+                    let mut straight = straight_background.clone();
+                    render(AlphaMode::Straight, &mut straight);
+
+                    assert_eq!(
+                        straight, expected,
+                        "num_threads: {num_threads}, {pixel_format:?}, {load_op:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn strided_buffers_match_packed_buffers() {
+        use crate::color::palette::css::{BLUE, RED};
+        use crate::kurbo::Circle;
+        use crate::{PixelFormat, RenderMode, RenderSettings};
+        use alloc::vec;
+
+        let thread_counts: &[u16] = if cfg!(feature = "multithreading") {
+            &[0, 2]
+        } else {
+            &[0]
+        };
+
+        for &num_threads in thread_counts {
+            let settings = RenderSettings {
+                num_threads,
+                ..RenderSettings::default()
+            };
+            let mut resources = crate::Resources::new();
+            let mut ctx = RenderContext::new_with(300, 20, settings);
+            ctx.set_paint(RED.with_alpha(0.75));
+            ctx.fill_path(&Circle::new((20.0, 10.0), 8.0).to_path(0.1));
+            ctx.set_paint(BLUE.with_alpha(0.5));
+            ctx.fill_path(&Rect::new(10.5, 2.5, 299.5, 19.75).to_path(0.1));
+            ctx.flush();
+
+            for pixel_format in [PixelFormat::Rgba8, PixelFormat::Rgb565 { dither: true }] {
+                ctx.set_pixel_format(pixel_format);
+                let row_len = 300 * pixel_format.bytes_per_pixel();
+                let stride = row_len + 52;
+
+                let mut packed = vec![0; row_len * 20];
+                ctx.render_to_buffer(
+                    &mut resources,
+                    &mut packed,
+                    300,
+                    20,
+                    RenderMode::OptimizeSpeed,
+                );
+                // The last row isn't padded.
+                let mut strided = vec![0xab; stride * 19 + row_len];
+                ctx.render_to_buffer_with_stride(
+                    &mut resources,
+                    &mut strided,
+                    300,
+                    20,
+                    stride,
+                    RenderMode::OptimizeSpeed,
+                );
+
+                for (y, row) in packed.chunks_exact(row_len).enumerate() {
+                    let strided_row = &strided[y * stride..];
+                    assert_eq!(
+                        &strided_row[..row_len],
+                        row,
+                        "num_threads: {num_threads}, y: {y}"
+                    );
+                    if y < 19 {
+                        assert!(strided_row[row_len..stride].iter().all(|&b| b == 0xab));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn regions_match_the_full_render() {
+        use crate::color::palette::css::{BLUE, RED};
+        use crate::kurbo::{Affine, BezPath};
+        use crate::{RenderMode, RenderSettings};
+        use alloc::vec;
+        use vello_common::geometry::RectU16;
+
+        let draw = |ctx: &mut RenderContext| {
+            let mut triangle = BezPath::new();
+            triangle.move_to((4.0, 2.0));
+            triangle.line_to((290.5, 12.25));
+            triangle.line_to((30.0, 38.0));
+            triangle.close_path();
+            ctx.set_paint(RED.with_alpha(0.75));
+            ctx.fill_path(&triangle);
+            ctx.set_transform(Affine::translate((7.0, 3.0)));
+            ctx.set_paint(BLUE.with_alpha(0.5));
+            ctx.fill_rect(&Rect::new(10.5, 2.5, 250.5, 30.75));
+            ctx.flush();
+        };
+
+        let thread_counts: &[u16] = if cfg!(feature = "multithreading") {
+            &[0, 2]
+        } else {
+            &[0]
+        };
+
+        for &num_threads in thread_counts {
+            let settings = RenderSettings {
+                num_threads,
+                ..RenderSettings::default()
+            };
+            let mut resources = crate::Resources::new();
+            let mut full_ctx = RenderContext::new_with(300, 40, settings);
+            draw(&mut full_ctx);
+            let mut full = vec![0; 300 * 40 * 4];
+            full_ctx.render_to_buffer(
+                &mut resources,
+                &mut full,
+                300,
+                40,
+                RenderMode::OptimizeSpeed,
+            );
+
+            let region = RectU16::new(13, 5, 270, 22);
+            let mut ctx = RenderContext::new_with_region(region, settings);
+            draw(&mut ctx);
+            assert_eq!((ctx.width(), ctx.height()), (257, 17));
+            let mut partial = vec![0xab; 300 * 40 * 4];
+            ctx.render_region_to_buffer(
+                &mut resources,
+                &mut partial,
+                300,
+                40,
+                RenderMode::OptimizeSpeed,
+            );
+
+            for y in 0..40 {
+                for x in 0..300 {
+                    let i = (y * 300 + x) * 4;
+                    let inside = (13..270).contains(&x) && (5..22).contains(&y);
+                    if !inside {
+                        assert_eq!(
+                            partial[i..i + 4],
+                            [0xab; 4],
+                            "num_threads: {num_threads}, x: {x}, y: {y}"
+                        );
+                        continue;
+                    }
+                    // The geometry is translated by the origin of the region, which can round
+                    // the coverage of edge pixels differently.
+                    for (a, b) in partial[i..i + 4].iter().zip(&full[i..i + 4]) {
+                        assert!(
+                            a.abs_diff(*b) <= 1,
+                            "num_threads: {num_threads}, x: {x}, y: {y}: {:?} != {:?}",
+                            &partial[i..i + 4],
+                            &full[i..i + 4],
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn progressive_bands_match_the_full_render() {
+        use crate::color::palette::css::{BLUE, RED};
+        use crate::kurbo::Circle;
+        use crate::{CancellationToken, Cancelled, RenderMode, RenderSettings};
+        use alloc::vec;
+        use alloc::vec::Vec;
+
+        let thread_counts: &[u16] = if cfg!(feature = "multithreading") {
+            &[0, 2]
+        } else {
+            &[0]
+        };
+
+        for &num_threads in thread_counts {
+            let settings = RenderSettings {
+                num_threads,
+                ..RenderSettings::default()
+            };
+            let mut resources = crate::Resources::new();
+            let mut ctx = RenderContext::new_with(30, 70, settings);
+            ctx.set_paint(RED.with_alpha(0.75));
+            ctx.fill_path(&Circle::new((15.0, 35.0), 30.0).to_path(0.1));
+            ctx.set_paint(BLUE.with_alpha(0.5));
+            ctx.fill_rect(&Rect::new(2.5, 10.5, 27.5, 60.25));
+            ctx.flush();
+
+            let mut expected = vec![0; 30 * 70 * 4];
+            ctx.render_to_buffer(
+                &mut resources,
+                &mut expected,
+                30,
+                70,
+                RenderMode::OptimizeSpeed,
+            );
+
+            let mut buffer = vec![0; 30 * 70 * 4];
+            let mut bands = Vec::new();
+            let token = CancellationToken::new();
+            let result = ctx.render_to_buffer_progressive(
+                &mut resources,
+                &mut buffer,
+                30,
+                70,
+                RenderMode::OptimizeSpeed,
+                &token,
+                |rows, band| {
+                    let start = usize::from(rows.start) * 30 * 4;
+                    assert_eq!(band, &expected[start..start + band.len()]);
+                    bands.push(rows);
+                },
+            );
+            assert_eq!(result, Ok(()));
+            assert_eq!(buffer, expected, "num_threads: {num_threads}");
+            assert_eq!(bands.first().map(|rows| rows.start), Some(0));
+            assert_eq!(bands.last().map(|rows| rows.end), Some(70));
+            assert!(bands.windows(2).all(|w| w[0].end == w[1].start));
+
+            // Cancelling after the first band leaves the rest of the buffer untouched.
+            let mut buffer = vec![0xab; 30 * 70 * 4];
+            let mut delivered = 0;
+            let result = ctx.render_to_buffer_progressive(
+                &mut resources,
+                &mut buffer,
+                30,
+                70,
+                RenderMode::OptimizeSpeed,
+                &token,
+                |rows, _| {
+                    delivered = rows.end;
+                    token.cancel();
+                },
+            );
+            assert_eq!(result, Err(Cancelled));
+            assert!(delivered < 70);
+            let split = usize::from(delivered) * 30 * 4;
+            assert_eq!(buffer[..split], expected[..split]);
+            assert!(buffer[split..].iter().all(|&b| b == 0xab));
+            assert!(token.is_cancelled());
+        }
+    }
+
+    #[test]
+    fn bands_stitch_together_into_the_full_render() {
+        use crate::color::palette::css::{BLUE, RED};
+        use crate::kurbo::Circle;
+        use crate::{PixelFormat, RenderMode, RenderSettings};
+        use alloc::vec;
+        use alloc::vec::Vec;
+
+        let thread_counts: &[u16] = if cfg!(feature = "multithreading") {
+            &[0, 2]
+        } else {
+            &[0]
+        };
+
+        for &num_threads in thread_counts {
+            let settings = RenderSettings {
+                num_threads,
+                ..RenderSettings::default()
+            };
+            let mut resources = crate::Resources::new();
+            let mut ctx = RenderContext::new_with(30, 70, settings);
+            ctx.set_pixel_format(PixelFormat::Bgra8);
+            ctx.set_paint(RED.with_alpha(0.75));
+            ctx.fill_path(&Circle::new((15.0, 35.0), 30.0).to_path(0.1));
+            ctx.set_paint(BLUE.with_alpha(0.5));
+            ctx.fill_rect(&Rect::new(2.5, 10.5, 27.5, 60.25));
+            ctx.flush();
+
+            let mut expected = vec![0; 30 * 70 * 4];
+            ctx.render_to_buffer(
+                &mut resources,
+                &mut expected,
+                30,
+                70,
+                RenderMode::OptimizeSpeed,
+            );
+
+            // 10 rows are rounded up to 12, so the last band only has 10 rows.
+            let mut streamed = Vec::new();
+            let mut bands = Vec::new();
+            ctx.render_in_bands(
+                &mut resources,
+                30,
+                70,
+                10,
+                RenderMode::OptimizeSpeed,
+                |rows, band| {
+                    assert_eq!(band.len(), usize::from(rows.end - rows.start) * 30 * 4);
+                    streamed.extend_from_slice(band);
+                    bands.push(rows);
+                },
+            );
+            assert_eq!(streamed, expected, "num_threads: {num_threads}");
+            assert_eq!(bands.len(), 6);
+            assert_eq!(bands.first(), Some(&(0..12)));
+            assert_eq!(bands.last(), Some(&(60..70)));
+            assert!(bands.windows(2).all(|w| w[0].end == w[1].start));
+        }
+    }
+
+    #[test]
+    fn a8_buffers_contain_the_coverage() {
+        use crate::color::palette::css::{BLUE, RED, WHITE};
+        use crate::kurbo::Circle;
+        use crate::peniko::Gradient;
+        use crate::{PixelFormat, RenderMode, RenderSettings};
+        use alloc::vec;
+        use alloc::vec::Vec;
+
+        let thread_counts: &[u16] = if cfg!(feature = "multithreading") {
+            &[0, 2]
+        } else {
+            &[0]
+        };
+
+        for &num_threads in thread_counts {
+            let settings = RenderSettings {
+                num_threads,
+                ..RenderSettings::default()
+            };
+            let mut resources = crate::Resources::new();
+            let draw = |ctx: &mut RenderContext, coverage_paints: bool| {
+                let paint = |paint: crate::PaintType| {
+                    if coverage_paints { WHITE.into() } else { paint }
+                };
+                ctx.set_paint(paint(RED.with_alpha(0.25).into()));
+                ctx.fill_path(&Circle::new((20.0, 10.0), 8.3).to_path(0.1));
+                ctx.set_paint(paint(
+                    Gradient::new_linear((0.0, 0.0), (300.0, 0.0))
+                        .with_stops([RED, BLUE.with_alpha(0.5)])
+                        .into(),
+                ));
+                ctx.fill_path(&Rect::new(40.5, 2.5, 290.0, 15.25).to_path(0.1));
+                ctx.flush();
+            };
+
+            let mut coverage = RenderContext::new_with(300, 20, settings);
+            draw(&mut coverage, false);
+            coverage.set_pixel_format(PixelFormat::A8);
+            let mut a8 = vec![0; 300 * 20];
+            coverage.render_to_buffer(&mut resources, &mut a8, 300, 20, RenderMode::OptimizeSpeed);
+
+            let mut white = RenderContext::new_with(300, 20, settings);
+            draw(&mut white, true);
+            let mut rgba = vec![0; 300 * 20 * 4];
+            white.render_to_buffer(
+                &mut resources,
+                &mut rgba,
+                300,
+                20,
+                RenderMode::OptimizeSpeed,
+            );
+            let alphas: Vec<u8> = rgba.chunks_exact(4).map(|pixel| pixel[3]).collect();
+
+            assert_eq!(a8, alphas, "num_threads: {num_threads}");
+            assert_eq!(a8[10 * 300 + 20], 255);
+        }
+    }
+
+    #[test]
+    fn float_buffers_composite_in_linear_light() {
+        use crate::color::palette::css::{BLACK, WHITE};
+        use crate::{PixelFormat, RenderMode, RenderSettings, rgba32_float_to_rgba8};
+        use alloc::vec;
+
+        let thread_counts: &[u16] = if cfg!(feature = "multithreading") {
+            &[0, 2]
+        } else {
+            &[0]
+        };
+
+        for &num_threads in thread_counts {
+            let settings = RenderSettings {
+                num_threads,
+                ..RenderSettings::default()
+            };
+            let mut resources = crate::Resources::new();
+            let mut ctx = RenderContext::new_with(300, 20, settings);
+            ctx.set_pixel_format(PixelFormat::Rgba32Float);
+            ctx.set_paint(BLACK);
+            ctx.fill_rect(&Rect::new(0.0, 0.0, 300.0, 20.0));
+            ctx.set_paint(WHITE.with_alpha(0.5));
+            ctx.fill_rect(&Rect::new(0.0, 0.0, 300.0, 20.0));
+            ctx.flush();
+
+            let mut buffer = vec![0; 300 * 20 * 16];
+            ctx.render_to_buffer(
+                &mut resources,
+                &mut buffer,
+                300,
+                20,
+                RenderMode::OptimizeQuality,
+            );
+
+            for pixel in buffer.chunks_exact(16) {
+                let channel =
+                    |i: usize| f32::from_ne_bytes(pixel[i * 4..][..4].try_into().unwrap());
+                // Compositing in sRGB would result in about 0.22 in linear light.
+                assert!((channel(0) - 0.5).abs() < 0.01, "{}", channel(0));
+                assert_eq!(channel(3), 1.0);
+            }
+
+            let mut rgba = vec![0; 300 * 20 * 4];
+            rgba32_float_to_rgba8(&buffer, &mut rgba);
+            assert!(
+                rgba.chunks_exact(4)
+                    .all(|p| p[0].abs_diff(188) <= 1 && p[3] == 255)
+            );
+        }
+    }
+
+    #[test]
+    fn linear_compositing_blends_in_linear_light() {
+        use crate::color::palette::css::{BLACK, WHITE};
+        use crate::{RenderMode, RenderSettings};
+        use alloc::vec;
+
+        let thread_counts: &[u16] = if cfg!(feature = "multithreading") {
+            &[0, 2]
+        } else {
+            &[0]
+        };
+
+        for &num_threads in thread_counts {
+            let settings = RenderSettings {
+                num_threads,
+                ..RenderSettings::default()
+            };
+            let mut resources = crate::Resources::new();
+            let mut ctx = RenderContext::new_with(300, 20, settings);
+            ctx.set_linear_compositing(true);
+            ctx.set_paint(BLACK);
+            ctx.fill_rect(&Rect::new(0.0, 0.0, 300.0, 20.0));
+            ctx.set_paint(WHITE.with_alpha(0.5));
+            ctx.fill_rect(&Rect::new(0.0, 0.0, 150.0, 20.0));
+            ctx.flush();
+
+            let mut buffer = vec![0; 300 * 20 * 4];
+            ctx.render_to_buffer(&mut resources, &mut buffer, 300, 20, RenderMode::default());
+
+            for (i, pixel) in buffer.chunks_exact(4).enumerate() {
+                // Compositing in sRGB would result in 128.
+                let expected = if i % 300 < 150 { 188 } else { 0 };
+                assert!(pixel[0].abs_diff(expected) <= 2, "{pixel:?}");
+                assert_eq!(pixel[3], 255);
+            }
+
+            ctx.reset();
+            assert!(ctx.linear_compositing());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "f32_pipeline")]
+    fn dithering_preserves_colors_between_8_bit_values() {
+        use crate::color::{AlphaColor, Srgb};
+        use crate::{RenderMode, RenderSettings};
+        use alloc::vec;
+        use alloc::vec::Vec;
+
+        let thread_counts: &[u16] = if cfg!(feature = "multithreading") {
+            &[0, 2]
+        } else {
+            &[0]
+        };
+
+        for &num_threads in thread_counts {
+            let settings = RenderSettings {
+                num_threads,
+                ..RenderSettings::default()
+            };
+            let render = |dither: bool| {
+                let mut resources = crate::Resources::new();
+                let mut ctx = RenderContext::new_with(8, 8, settings);
+                ctx.set_dither(dither);
+                ctx.set_paint(AlphaColor::<Srgb>::new([100.25 / 255.0, 0.0, 1.0, 1.0]));
+                ctx.fill_rect(&Rect::new(0.0, 0.0, 8.0, 8.0));
+                ctx.flush();
+
+                let mut buffer = vec![0; 8 * 8 * 4];
+                let mode = RenderMode::OptimizeQuality;
+                ctx.render_to_buffer(&mut resources, &mut buffer, 8, 8, mode);
+                ctx.reset();
+                assert_eq!(ctx.dither(), dither);
+                buffer
+            };
+
+            let red = |buffer: &[u8]| buffer.chunks_exact(4).map(|p| p[0]).collect::<Vec<_>>();
+            assert!(red(&render(false)).iter().all(|&r| r == 100));
+
+            // A quarter of the pixels are rounded up, in every 4x4 block.
+            let dithered = render(true);
+            let red = red(&dithered);
+            assert!(red.iter().all(|&r| r == 100 || r == 101));
+            assert_eq!(red.iter().filter(|&&r| r == 101).count(), 16);
+            assert!(dithered.chunks_exact(4).all(|p| p[2] == 255 && p[3] == 255));
+        }
+    }
+
+    #[test]
+    fn render_stats_count_the_drawn_paths() {
+        use crate::color::palette::css::BLACK;
+        use crate::kurbo::{Circle, Stroke};
+        use crate::{RenderSettings, RenderStats};
+
+        let thread_counts: &[u16] = if cfg!(feature = "multithreading") {
+            &[0, 2]
+        } else {
+            &[0]
+        };
+
+        for &num_threads in thread_counts {
+            let settings = RenderSettings {
+                num_threads,
+                ..RenderSettings::default()
+            };
+            let mut ctx = RenderContext::new_with(40, 40, settings);
+            ctx.set_paint(BLACK);
+            let circle = Circle::new((20.0, 20.0), 15.3).to_path(0.1);
+            ctx.fill_path(&circle);
+            ctx.set_stroke(Stroke::new(2.0));
+            ctx.stroke_path(&circle);
+            ctx.flush();
+
+            let stats = ctx.render_stats();
+            assert_eq!(stats.paths, 2, "{num_threads} threads");
+            assert!(stats.strips > 0);
+            assert!(stats.alpha_bytes > 0);
+            assert_eq!(stats.draw_calls, 0);
+
+            ctx.reset();
+            assert_eq!(ctx.render_stats(), RenderStats::default());
+        }
+    }
+
+    #[test]
+    fn fast_quality_renders_aliased_edges() {
+        use crate::color::palette::css::BLACK;
+        use crate::kurbo::Circle;
+        use crate::{Quality, RenderMode};
+        use alloc::vec;
+
+        let render = |ctx: &mut RenderContext| {
+            let mut resources = crate::Resources::new();
+            ctx.set_paint(BLACK);
+            ctx.fill_path(&Circle::new((20.0, 20.0), 15.3).to_path(0.1));
+            ctx.flush();
+
+            let mut buffer = vec![0; 40 * 40 * 4];
+            ctx.render_to_buffer(&mut resources, &mut buffer, 40, 40, RenderMode::default());
+            buffer
+        };
+        let is_partially_covered = |buffer: &[u8]| {
+            buffer
+                .chunks_exact(4)
+                .any(|pixel| pixel[3] != 0 && pixel[3] != 255)
+        };
+
+        let mut ctx = RenderContext::new(40, 40);
+        assert!(is_partially_covered(&render(&mut ctx)));
+
+        ctx.reset();
+        ctx.set_quality(Quality::Fast);
+        assert!(!is_partially_covered(&render(&mut ctx)));
+
+        ctx.reset();
+        assert_eq!(ctx.quality(), Quality::Fast);
+    }
+
+    #[test]
+    fn load_op_initializes_the_target() {
+        use crate::RenderSettings;
+        use crate::color::PremulRgba8;
+        use crate::color::palette::css::{BLUE, LIME};
+        use crate::{LoadOp, Pixmap};
+        use alloc::vec;
+        use vello_common::filter_effects::Filter;
+
+        let thread_counts: &[u16] = if cfg!(feature = "multithreading") {
+            &[0, 2]
+        } else {
+            &[0]
+        };
+        let red = PremulRgba8 {
+            r: 255,
+            g: 0,
+            b: 0,
+            a: 255,
+        };
+
+        for &num_threads in thread_counts {
+            // The multi-threaded dispatcher doesn't support filter layers.
+            for with_filter in [false, num_threads == 0] {
+                let settings = RenderSettings {
+                    num_threads,
+                    ..RenderSettings::default()
+                };
+                let mut resources = crate::Resources::new();
+                let mut ctx = RenderContext::new_with(300, 8, settings);
+                if with_filter {
+                    ctx.push_filter_layer(Filter::brightness(1.0));
+                }
+                ctx.set_paint(BLUE.with_alpha(0.5));
+                ctx.fill_rect(&Rect::new(0.0, 0.0, 4.0, 4.0));
+                // Covers the whole second wide tile with an opaque color.
+                ctx.set_paint(BLUE);
+                ctx.fill_path(&Rect::new(256.0, 0.0, 300.0, 8.0).to_path(0.1));
+                if with_filter {
+                    ctx.pop_layer();
+                }
+                ctx.flush();
+
+                let render = |ctx: &mut RenderContext, resources: &mut crate::Resources, op| {
+                    let mut pixmap = Pixmap::from_parts(vec![red; 300 * 8], 300, 8);
+                    ctx.set_load_op(op);
+                    ctx.render_to_pixmap(resources, &mut pixmap);
+                    (
+                        pixmap.sample(2, 2),
+                        pixmap.sample(10, 2),
+                        pixmap.sample(280, 2),
+                    )
+                };
+
+                let (inside, outside, opaque) =
+                    render(&mut ctx, &mut resources, LoadOp::ClearTransparent);
+                assert_eq!((inside.b, inside.a), (128, 128));
+                assert_eq!(outside.a, 0);
+                assert_eq!((opaque.b, opaque.a), (255, 255));
+
+                let (inside, outside, opaque) =
+                    render(&mut ctx, &mut resources, LoadOp::Clear(LIME));
+                assert_eq!((inside.g, inside.b, inside.a), (127, 128, 255));
+                assert_eq!((outside.g, outside.a), (255, 255));
+                assert_eq!((opaque.b, opaque.a), (255, 255));
+
+                let (inside, outside, opaque) = render(&mut ctx, &mut resources, LoadOp::Load);
+                assert_eq!((inside.r, inside.b, inside.a), (127, 128, 255));
+                assert_eq!(outside, red);
+                assert_eq!((opaque.r, opaque.b, opaque.a), (0, 255, 255));
+            }
+        }
+    }
+
     #[cfg(feature = "text")]
     #[test]
     fn glyph_atlas_resources_are_lazy() {
@@ -914,4 +3184,113 @@ mod tests {
 
         assert!(resources.glyph_resources.is_some());
     }
+
+    #[test]
+    fn hard_stop_gradients_keep_anti_aliased_edges() {
+        use crate::Resources;
+        use crate::color::DynamicColor;
+        use crate::color::palette::css::{BLACK, BLUE, GREEN, RED};
+        use crate::kurbo::{Affine, Circle, Ellipse, Point, Stroke};
+        use crate::peniko::{ColorStop, ColorStops, Gradient, SweepGradientPosition};
+        use vello_common::paint::PaintType;
+        use vello_common::pixmap::Pixmap;
+
+        let third = 1.0 / 3.0;
+        let stops = [
+            (0.0, RED),
+            (third, RED),
+            (third, GREEN),
+            (2.0 * third, GREEN),
+            (2.0 * third, BLUE),
+            (1.0, BLUE),
+        ]
+        .map(|(offset, color)| ColorStop {
+            offset,
+            color: DynamicColor::from_alpha_color(color),
+        });
+        let gradient = Gradient {
+            kind: SweepGradientPosition {
+                center: Point::new(30.0, 20.0),
+                start_angle: 0.0,
+                end_angle: core::f32::consts::TAU,
+            }
+            .into(),
+            stops: ColorStops(stops.as_slice().into()),
+            ..Default::default()
+        };
+
+        let render = |paint: PaintType| {
+            let mut ctx = RenderContext::new(60, 40);
+            ctx.set_transform(Affine::rotate_about(0.5, Point::new(30.0, 20.0)));
+            ctx.set_paint(paint);
+            ctx.fill_path(&Ellipse::new((18.0, 20.0), (10.0, 6.0), 0.0).to_path(0.1));
+            ctx.set_stroke(Stroke::new(2.5));
+            ctx.stroke_path(&Circle::new((43.0, 20.0), 9.3).to_path(0.1));
+            ctx.flush();
+
+            let mut pixmap = Pixmap::new(60, 40);
+            ctx.render_to_pixmap(&mut Resources::new(), &mut pixmap);
+            pixmap
+        };
+
+        // All bands are opaque, so the alpha of each pixel only depends on the coverage of
+        // the shape, like for a solid color, no matter how many bands cover the pixel.
+        let banded = render(gradient.into());
+        let solid = render(BLACK.into());
+        assert!(solid.data().iter().any(|p| p.a > 0 && p.a < 255));
+        for (banded, solid) in banded.data().iter().zip(solid.data()) {
+            assert!(banded.a.abs_diff(solid.a) <= 1, "{banded:?} != {solid:?}");
+        }
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn access_geometry_of_tagged_primitives() {
+        use vello_common::accessibility::AccessTag;
+        use vello_common::kurbo::Affine;
+
+        const ROBOTO_FONT: &[u8] =
+            include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+
+        let font = FontData::new(Blob::new(Arc::new(ROBOTO_FONT)), 0);
+        let glyphs = [(1, 10.0), (2, 20.0), (3, 30.0)].map(|(id, x)| Glyph { id, x, y: 0.0 });
+
+        let mut resources = crate::Resources::new();
+        let mut ctx = RenderContext::new(100, 100);
+
+        ctx.fill_rect(&Rect::new(0.0, 0.0, 5.0, 5.0));
+        ctx.set_access_tag(Some(AccessTag(1)));
+        ctx.set_transform(Affine::translate((10.0, 20.0)));
+        ctx.fill_rect(&Rect::new(0.0, 0.0, 10.0, 10.0));
+        ctx.stroke_rect(&Rect::new(20.0, 0.0, 30.0, 10.0));
+        ctx.set_access_tag(Some(AccessTag(2)));
+        ctx.set_transform(Affine::translate((0.0, 50.0)));
+        ctx.glyph_run(&mut resources, &font)
+            .font_size(20.0)
+            .fill_glyphs(glyphs.into_iter());
+        ctx.set_access_tag(None);
+        ctx.fill_rect(&Rect::new(0.0, 0.0, 100.0, 100.0));
+
+        let geometry = ctx.access_geometry();
+        assert_eq!(geometry.nodes().len(), 2);
+
+        let node = geometry.get(AccessTag(1)).unwrap();
+        let bounds = node.bounds();
+        assert_eq!(bounds.x0, 10.0);
+        assert!(bounds.contains_rect(Rect::new(10.0, 19.5, 40.5, 30.5)));
+        assert!(node.glyph_quads().is_empty());
+
+        let node = geometry.get(AccessTag(2)).unwrap();
+        let quads = node.glyph_quads();
+        assert_eq!(quads.len(), 3);
+        for (quad, glyph) in quads.iter().zip(&glyphs) {
+            assert_eq!(quad[0].x, f64::from(glyph.x));
+            assert!(quad[0].y < 50.0 && quad[3].y > 50.0);
+        }
+        assert_eq!(node.bounds().x0, 10.0);
+
+        ctx.reset();
+        assert!(ctx.access_geometry().is_empty());
+        assert_eq!(ctx.access_tag(), None);
+    }
 }
@@ -0,0 +1,1270 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Deterministic replay logs of render context calls.
+//!
+//! A [`ReplayLog`] records the sequence of calls made on a [`RenderContext`] in a compact,
+//! line-based text format, so that a rendering bug can be reproduced without access to the
+//! application that triggered it. Recording is started with
+//! [`RenderContext::start_replay_log`], and the finished log is retrieved with
+//! [`RenderContext::take_replay_log`]. Its [`Display`](fmt::Display) implementation produces
+//! the text that can be attached to a bug report, which is turned back into a log with
+//! [`ReplayLog::parse`] and drawn into a new render context with [`ReplayLog::replay`].
+//!
+//! Small payloads like transforms, strokes, solid colors and gradients are recorded in full.
+//! Large payloads are only recorded as a hash, which identifies them without bloating the
+//! log:
+//!
+//! - image paints, including glyphs that are drawn from the glyph atlas,
+//! - custom paints,
+//! - masks and filters,
+//! - paths with more than [`MAX_INLINE_PATH_ELEMENTS`] elements.
+//!
+//! Commands with hashed payloads can't be replayed exactly. Hashed paths aren't drawn, hashed
+//! paints are replaced with solid magenta, and layers and clip paths are pushed without their
+//! hashed parts so that the layer stack stays balanced.
+//!
+//! ```
+//! use vello_cpu::RenderContext;
+//! use vello_cpu::color::palette::css::REBECCA_PURPLE;
+//! use vello_cpu::kurbo::Rect;
+//! use vello_cpu::replay::ReplayLog;
+//!
+//! let mut ctx = RenderContext::new(100, 100);
+//! ctx.start_replay_log();
+//! ctx.set_paint(REBECCA_PURPLE);
+//! ctx.fill_rect(&Rect::new(10.0, 10.0, 90.0, 90.0));
+//! let log = ctx.take_replay_log().unwrap();
+//!
+//! // The text can be stored and parsed again later.
+//! let log = ReplayLog::parse(&log.to_string()).unwrap();
+//! let mut replayed = RenderContext::new(log.width(), log.height());
+//! assert_eq!(log.replay(&mut replayed), 0);
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+use core::str::SplitAsciiWhitespace;
+
+use bytemuck::Contiguous;
+use vello_common::color::palette::css::MAGENTA;
+use vello_common::color::{AlphaColor, ColorSpaceTag, DynamicColor, HueDirection, Srgb};
+use vello_common::custom_paint::CustomPaint;
+use vello_common::encode::GradientHints;
+use vello_common::filter_effects::Filter;
+use vello_common::kurbo::{Affine, BezPath, Cap, Join, PathEl, Point, Rect, Shape, Stroke, Vec2};
+use vello_common::mask::{Mask, MaskKind};
+use vello_common::paint::{Image, ImageSource, PaintType, Tint, TintMode};
+use vello_common::peniko::{
+    BlendMode, ColorStop, Compose, Extend, Fill, Gradient, GradientKind, InterpolationAlphaSpace,
+    LinearGradientPosition, Mix, RadialGradientPosition, SweepGradientPosition,
+};
+use vello_common::render_state::RenderState;
+
+use crate::RenderContext;
+
+/// Paths with more elements than this are only recorded as a hash.
+pub const MAX_INLINE_PATH_ELEMENTS: usize = 4096;
+
+const HEADER: &str = "vello_cpu-replay 1";
+
+/// A recorded sequence of render context calls.
+///
+/// See the [module documentation](self) for details.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplayLog {
+    width: u16,
+    height: u16,
+    commands: Vec<Command>,
+}
+
+impl ReplayLog {
+    /// Create an empty log for a render context of the given size.
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Return the width of the render context the log was recorded from.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Return the height of the render context the log was recorded from.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Return the number of recorded commands.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Return whether no commands have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Parse a log from the text produced by its [`Display`](fmt::Display) implementation.
+    pub fn parse(text: &str) -> Result<Self, ReplayParseError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| (idx + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+
+        let error = |line, message| ReplayParseError { line, message };
+        let (line, header) = lines.next().ok_or(error(1, "missing header"))?;
+        let size = header
+            .strip_prefix(HEADER)
+            .ok_or(error(line, "invalid header"))?;
+        let mut tokens = Tokens::new(size);
+        let width = tokens.parse().map_err(|message| error(line, message))?;
+        let height = tokens.parse().map_err(|message| error(line, message))?;
+        tokens.finish().map_err(|message| error(line, message))?;
+
+        let mut log = Self::new(width, height);
+        for (line, text) in lines {
+            let command = Command::parse(text).map_err(|message| error(line, message))?;
+            log.commands.push(command);
+        }
+
+        Ok(log)
+    }
+
+    /// Issue the recorded commands on `ctx`.
+    ///
+    /// Returns the number of commands that could not be replayed exactly, because some of
+    /// their payloads were only recorded as a hash.
+    pub fn replay(&self, ctx: &mut RenderContext) -> usize {
+        self.commands
+            .iter()
+            .filter(|command| !command.replay(ctx))
+            .count()
+    }
+
+    pub(crate) fn push(&mut self, command: Command) {
+        self.commands.push(command);
+    }
+
+    /// Record all parts of the render state.
+    pub(crate) fn push_state(&mut self, state: &RenderState) {
+        self.commands.extend([
+            Command::SetPaint(Payload::paint(&state.paint)),
+            Command::SetPaintTransform(state.paint_transform),
+            Command::SetStroke(state.stroke.clone()),
+            Command::SetTransform(state.transform),
+            Command::SetFillRule(state.fill_rule),
+            Command::SetBlendMode(state.blend_mode),
+            Command::SetTint(state.tint),
+            Command::SetGradientHints(state.gradient_hints.clone()),
+        ]);
+    }
+}
+
+impl fmt::Display for ReplayLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{HEADER} {} {}", self.width, self.height)?;
+        for command in &self.commands {
+            writeln!(f, "{command}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An error that occurred while parsing a [`ReplayLog`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayParseError {
+    /// The 1-based line the error occurred on.
+    pub line: usize,
+    /// A description of the error.
+    pub message: &'static str,
+}
+
+impl fmt::Display for ReplayParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl core::error::Error for ReplayParseError {}
+
+/// A payload that is either recorded in full, or only as a hash.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Payload<T> {
+    Inline(T),
+    Hashed(u64),
+}
+
+impl Payload<BezPath> {
+    pub(crate) fn path(path: &BezPath) -> Self {
+        let elements = path.elements();
+        if elements.len() <= MAX_INLINE_PATH_ELEMENTS {
+            return Self::Inline(path.clone());
+        }
+
+        let mut hasher = Hasher::new();
+        for el in elements {
+            hasher.write(&[el_command(el) as u8]);
+            let (points, count) = el_points(el);
+            for point in &points[..count] {
+                hasher.write(&point.x.to_le_bytes());
+                hasher.write(&point.y.to_le_bytes());
+            }
+        }
+        Self::Hashed(hasher.finish())
+    }
+}
+
+/// A paint that is recorded in full.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum RecordedPaint {
+    Solid(AlphaColor<Srgb>),
+    Gradient(Gradient),
+}
+
+impl Payload<RecordedPaint> {
+    pub(crate) fn paint(paint: &PaintType) -> Self {
+        match paint {
+            PaintType::Solid(color) => Self::Inline(RecordedPaint::Solid(*color)),
+            PaintType::Gradient(gradient) => {
+                Self::Inline(RecordedPaint::Gradient(gradient.clone()))
+            }
+            PaintType::Image(image) => Self::Hashed(hash_image(image)),
+        }
+    }
+}
+
+/// A single recorded call.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Command {
+    FillPath(Payload<BezPath>),
+    StrokePath(Payload<BezPath>),
+    FillRect(Rect),
+    StrokeRect(Rect),
+    FillBlurredRoundedRect(Rect, f32, f32),
+    FillDropShadow(Payload<BezPath>, Vec2, f32),
+    FillCustomPaint(Payload<BezPath>, u64),
+    StrokeCustomPaint(Payload<BezPath>, u64),
+    PushLayer {
+        clip: Option<Payload<BezPath>>,
+        blend_mode: Option<BlendMode>,
+        opacity: Option<f32>,
+        mask: Option<u64>,
+        filter: Option<u64>,
+    },
+    PushBackdropBlurLayer(f32, Payload<BezPath>),
+    PushMaskGroup(MaskKind),
+    EndMask,
+    PopLayer,
+    PushClipPath(Payload<BezPath>),
+    PopClipPath,
+    SetStroke(Stroke),
+    SetPaint(Payload<RecordedPaint>),
+    SetTint(Option<Tint>),
+    SetGradientHints(GradientHints),
+    SetBlendMode(BlendMode),
+    SetPaintTransform(Affine),
+    SetFillRule(Fill),
+    SetMask(u64),
+    ResetMask,
+    SetTransform(Affine),
+    SetAliasingThreshold(Option<u8>),
+    SetPixelSnapping(bool),
+    SetFilterEffect(u64),
+    ResetFilterEffect,
+    Reset,
+    Flush,
+}
+
+impl Command {
+    pub(crate) fn push_layer(
+        clip: Option<&BezPath>,
+        blend_mode: Option<BlendMode>,
+        opacity: Option<f32>,
+        mask: Option<&Mask>,
+        filter: Option<&Filter>,
+    ) -> Self {
+        Self::PushLayer {
+            clip: clip.map(Payload::path),
+            blend_mode,
+            opacity,
+            mask: mask.map(hash_mask),
+            filter: filter.map(hash_debug),
+        }
+    }
+
+    pub(crate) fn fill_custom_paint(path: &BezPath, paint: &CustomPaint) -> Self {
+        Self::FillCustomPaint(Payload::path(path), hash_custom_paint(paint))
+    }
+
+    pub(crate) fn stroke_custom_paint(path: &BezPath, paint: &CustomPaint) -> Self {
+        Self::StrokeCustomPaint(Payload::path(path), hash_custom_paint(paint))
+    }
+
+    pub(crate) fn set_mask(mask: &Mask) -> Self {
+        Self::SetMask(hash_mask(mask))
+    }
+
+    pub(crate) fn set_filter_effect(filter: &Filter) -> Self {
+        Self::SetFilterEffect(hash_debug(filter))
+    }
+
+    /// Issue the command on `ctx`, returning whether it was replayed exactly.
+    fn replay(&self, ctx: &mut RenderContext) -> bool {
+        match self {
+            Self::FillPath(Payload::Inline(path)) => ctx.fill_path(path),
+            Self::StrokePath(Payload::Inline(path)) => ctx.stroke_path(path),
+            Self::FillRect(rect) => ctx.fill_rect(rect),
+            Self::StrokeRect(rect) => ctx.stroke_rect(rect),
+            Self::FillBlurredRoundedRect(rect, radius, std_dev) => {
+                ctx.fill_blurred_rounded_rect(rect, *radius, *std_dev);
+            }
+            Self::FillDropShadow(Payload::Inline(path), offset, std_dev) => {
+                ctx.fill_drop_shadow(path, *offset, *std_dev);
+            }
+            Self::PushLayer {
+                clip,
+                blend_mode,
+                opacity,
+                mask,
+                filter,
+            } => {
+                let clip_path = match clip {
+                    Some(Payload::Inline(path)) => Some(path),
+                    _ => None,
+                };
+                ctx.push_layer(clip_path, *blend_mode, *opacity, None, None);
+
+                return !matches!(clip, Some(Payload::Hashed(_)))
+                    && mask.is_none()
+                    && filter.is_none();
+            }
+            Self::PushBackdropBlurLayer(radius, Payload::Inline(path)) => {
+                ctx.push_backdrop_blur_layer(*radius, path);
+            }
+            Self::PushBackdropBlurLayer(_, Payload::Hashed(_)) => {
+                // Keep pushes and pops balanced by clipping to the whole render target.
+                clip_to_viewport(ctx, RenderContext::push_clip_layer);
+
+                return false;
+            }
+            Self::PushMaskGroup(kind) => ctx.push_mask_group(*kind),
+            Self::EndMask => ctx.end_mask(),
+            Self::PopLayer => ctx.pop_layer(),
+            Self::PushClipPath(Payload::Inline(path)) => ctx.push_clip_path(path),
+            Self::PushClipPath(Payload::Hashed(_)) => {
+                // Keep pushes and pops balanced by clipping to the whole render target.
+                clip_to_viewport(ctx, RenderContext::push_clip_path);
+
+                return false;
+            }
+            Self::PopClipPath => ctx.pop_clip_path(),
+            Self::SetStroke(stroke) => ctx.set_stroke(stroke.clone()),
+            Self::SetPaint(Payload::Inline(RecordedPaint::Solid(color))) => ctx.set_paint(*color),
+            Self::SetPaint(Payload::Inline(RecordedPaint::Gradient(gradient))) => {
+                ctx.set_paint(gradient.clone());
+            }
+            Self::SetPaint(Payload::Hashed(_)) => {
+                ctx.set_paint(MAGENTA);
+                return false;
+            }
+            Self::SetTint(tint) => ctx.set_tint(*tint),
+            Self::SetGradientHints(hints) => ctx.set_gradient_hints(hints.as_slice()),
+            Self::SetBlendMode(blend_mode) => ctx.set_blend_mode(*blend_mode),
+            Self::SetPaintTransform(transform) => ctx.set_paint_transform(*transform),
+            Self::SetFillRule(fill_rule) => ctx.set_fill_rule(*fill_rule),
+            Self::ResetMask => ctx.reset_mask(),
+            Self::SetTransform(transform) => ctx.set_transform(*transform),
+            Self::SetAliasingThreshold(threshold) => ctx.set_aliasing_threshold(*threshold),
+            Self::SetPixelSnapping(snap) => ctx.set_pixel_snapping(*snap),
+            Self::ResetFilterEffect => ctx.reset_filter_effect(),
+            Self::Reset => ctx.reset(),
+            Self::Flush => ctx.flush(),
+            Self::FillPath(Payload::Hashed(_))
+            | Self::StrokePath(Payload::Hashed(_))
+            | Self::FillDropShadow(Payload::Hashed(_), ..)
+            | Self::FillCustomPaint(..)
+            | Self::StrokeCustomPaint(..)
+            | Self::SetMask(_)
+            | Self::SetFilterEffect(_) => return false,
+        }
+
+        true
+    }
+
+    fn parse(line: &str) -> Result<Self, &'static str> {
+        let mut tokens = Tokens::new(line);
+        let command = match tokens.next()? {
+            "fill_path" => Self::FillPath(tokens.path()?),
+            "stroke_path" => Self::StrokePath(tokens.path()?),
+            "fill_rect" => Self::FillRect(tokens.rect()?),
+            "stroke_rect" => Self::StrokeRect(tokens.rect()?),
+            "fill_blurred_rounded_rect" => {
+                Self::FillBlurredRoundedRect(tokens.rect()?, tokens.parse()?, tokens.parse()?)
+            }
+            "fill_drop_shadow" => {
+                Self::FillDropShadow(tokens.path()?, tokens.point()?.to_vec2(), tokens.parse()?)
+            }
+            "fill_custom_paint" => Self::FillCustomPaint(tokens.path()?, tokens.hash()?),
+            "stroke_custom_paint" => Self::StrokeCustomPaint(tokens.path()?, tokens.hash()?),
+            "push_layer" => Self::PushLayer {
+                clip: tokens.optional(Tokens::path)?,
+                blend_mode: tokens.optional(Tokens::blend_mode)?,
+                opacity: tokens.optional(Tokens::parse)?,
+                mask: tokens.optional(Tokens::hash)?,
+                filter: tokens.optional(Tokens::hash)?,
+            },
+            "push_backdrop_blur_layer" => {
+                Self::PushBackdropBlurLayer(tokens.parse()?, tokens.path()?)
+            }
+            "push_mask_group" => Self::PushMaskGroup(match tokens.next()? {
+                "alpha" => MaskKind::Alpha,
+                "luminance" => MaskKind::Luminance,
+                _ => return Err("invalid mask kind"),
+            }),
+            "end_mask" => Self::EndMask,
+            "pop_layer" => Self::PopLayer,
+            "push_clip_path" => Self::PushClipPath(tokens.path()?),
+            "pop_clip_path" => Self::PopClipPath,
+            "set_stroke" => Self::SetStroke(tokens.stroke()?),
+            "set_paint" => Self::SetPaint(tokens.paint()?),
+            "set_tint" => Self::SetTint(tokens.optional(Tokens::tint)?),
+            "set_gradient_hints" => Self::SetGradientHints(tokens.gradient_hints()?),
+            "set_blend_mode" => Self::SetBlendMode(tokens.blend_mode()?),
+            "set_paint_transform" => Self::SetPaintTransform(tokens.affine()?),
+            "set_fill_rule" => Self::SetFillRule(match tokens.next()? {
+                "nonzero" => Fill::NonZero,
+                "evenodd" => Fill::EvenOdd,
+                _ => return Err("invalid fill rule"),
+            }),
+            "set_mask" => Self::SetMask(tokens.hash()?),
+            "reset_mask" => Self::ResetMask,
+            "set_transform" => Self::SetTransform(tokens.affine()?),
+            "set_aliasing_threshold" => Self::SetAliasingThreshold(tokens.optional(Tokens::parse)?),
+            "set_pixel_snapping" => Self::SetPixelSnapping(tokens.parse()?),
+            "set_filter_effect" => Self::SetFilterEffect(tokens.hash()?),
+            "reset_filter_effect" => Self::ResetFilterEffect,
+            "reset" => Self::Reset,
+            "flush" => Self::Flush,
+            _ => return Err("unknown command"),
+        };
+        tokens.finish()?;
+
+        Ok(command)
+    }
+}
+
+/// Push a clip of the whole render target with `push`.
+fn clip_to_viewport(ctx: &mut RenderContext, push: fn(&mut RenderContext, &BezPath)) {
+    let transform = *ctx.transform();
+    let viewport = Rect::new(0.0, 0.0, f64::from(ctx.width()), f64::from(ctx.height()));
+    ctx.set_transform(Affine::IDENTITY);
+    push(ctx, &viewport.to_path(0.1));
+    ctx.set_transform(transform);
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FillPath(path) => write!(f, "fill_path {}", DisplayPath(path)),
+            Self::StrokePath(path) => write!(f, "stroke_path {}", DisplayPath(path)),
+            Self::FillRect(rect) => write!(f, "fill_rect {}", DisplayRect(rect)),
+            Self::StrokeRect(rect) => write!(f, "stroke_rect {}", DisplayRect(rect)),
+            Self::FillBlurredRoundedRect(rect, radius, std_dev) => write!(
+                f,
+                "fill_blurred_rounded_rect {} {radius} {std_dev}",
+                DisplayRect(rect)
+            ),
+            Self::FillDropShadow(path, offset, std_dev) => write!(
+                f,
+                "fill_drop_shadow {} {} {} {std_dev}",
+                DisplayPath(path),
+                offset.x,
+                offset.y
+            ),
+            Self::FillCustomPaint(path, hash) => {
+                write!(f, "fill_custom_paint {} ", DisplayPath(path))?;
+                write_hash(f, *hash)
+            }
+            Self::StrokeCustomPaint(path, hash) => {
+                write!(f, "stroke_custom_paint {} ", DisplayPath(path))?;
+                write_hash(f, *hash)
+            }
+            Self::PushLayer {
+                clip,
+                blend_mode,
+                opacity,
+                mask,
+                filter,
+            } => {
+                f.write_str("push_layer ")?;
+                write_optional(f, clip.as_ref(), |f, path| {
+                    write!(f, "{}", DisplayPath(path))
+                })?;
+                f.write_char(' ')?;
+                write_optional(f, *blend_mode, write_blend_mode)?;
+                f.write_char(' ')?;
+                write_optional(f, *opacity, |f, opacity| write!(f, "{opacity}"))?;
+                f.write_char(' ')?;
+                write_optional(f, *mask, write_hash)?;
+                f.write_char(' ')?;
+                write_optional(f, *filter, write_hash)
+            }
+            Self::PushBackdropBlurLayer(radius, path) => {
+                write!(f, "push_backdrop_blur_layer {radius} {}", DisplayPath(path))
+            }
+            Self::PushMaskGroup(kind) => match kind {
+                MaskKind::Alpha => f.write_str("push_mask_group alpha"),
+                MaskKind::Luminance => f.write_str("push_mask_group luminance"),
+            },
+            Self::EndMask => f.write_str("end_mask"),
+            Self::PopLayer => f.write_str("pop_layer"),
+            Self::PushClipPath(path) => write!(f, "push_clip_path {}", DisplayPath(path)),
+            Self::PopClipPath => f.write_str("pop_clip_path"),
+            Self::SetStroke(stroke) => {
+                let join = match stroke.join {
+                    Join::Bevel => "bevel",
+                    Join::Miter => "miter",
+                    Join::Round => "round",
+                };
+                write!(
+                    f,
+                    "set_stroke {} {join} {} {} {} {}",
+                    stroke.width,
+                    stroke.miter_limit,
+                    cap_name(stroke.start_cap),
+                    cap_name(stroke.end_cap),
+                    stroke.dash_offset,
+                )?;
+                for dash in &stroke.dash_pattern {
+                    write!(f, " {dash}")?;
+                }
+
+                Ok(())
+            }
+            Self::SetPaint(paint) => {
+                f.write_str("set_paint ")?;
+                match paint {
+                    Payload::Inline(RecordedPaint::Solid(color)) => {
+                        write!(f, "solid {}", DisplayColor(color))
+                    }
+                    Payload::Inline(RecordedPaint::Gradient(gradient)) => {
+                        write_gradient(f, gradient)
+                    }
+                    Payload::Hashed(hash) => write_hash(f, *hash),
+                }
+            }
+            Self::SetTint(tint) => {
+                f.write_str("set_tint ")?;
+                write_optional(f, *tint, |f, tint| {
+                    let mode = match tint.mode {
+                        TintMode::AlphaMask => "alpha_mask",
+                        TintMode::Multiply => "multiply",
+                        TintMode::InverseCoverage => "inverse_coverage",
+                    };
+                    write!(f, "{} {mode}", DisplayColor(&tint.color))
+                })
+            }
+            Self::SetGradientHints(hints) => {
+                f.write_str("set_gradient_hints")?;
+                for hint in hints.as_slice() {
+                    f.write_str(" ")?;
+                    write_optional(f, *hint, |f, hint| write!(f, "{hint}"))?;
+                }
+                Ok(())
+            }
+            Self::SetBlendMode(blend_mode) => {
+                f.write_str("set_blend_mode ")?;
+                write_blend_mode(f, *blend_mode)
+            }
+            Self::SetPaintTransform(transform) => {
+                write!(f, "set_paint_transform {}", DisplayAffine(transform))
+            }
+            Self::SetFillRule(fill_rule) => match fill_rule {
+                Fill::NonZero => f.write_str("set_fill_rule nonzero"),
+                Fill::EvenOdd => f.write_str("set_fill_rule evenodd"),
+            },
+            Self::SetMask(hash) => {
+                f.write_str("set_mask ")?;
+                write_hash(f, *hash)
+            }
+            Self::ResetMask => f.write_str("reset_mask"),
+            Self::SetTransform(transform) => {
+                write!(f, "set_transform {}", DisplayAffine(transform))
+            }
+            Self::SetAliasingThreshold(threshold) => {
+                f.write_str("set_aliasing_threshold ")?;
+                write_optional(f, *threshold, |f, threshold| write!(f, "{threshold}"))
+            }
+            Self::SetPixelSnapping(snap) => write!(f, "set_pixel_snapping {snap}"),
+            Self::SetFilterEffect(hash) => {
+                f.write_str("set_filter_effect ")?;
+                write_hash(f, *hash)
+            }
+            Self::ResetFilterEffect => f.write_str("reset_filter_effect"),
+            Self::Reset => f.write_str("reset"),
+            Self::Flush => f.write_str("flush"),
+        }
+    }
+}
+
+fn write_optional<T>(
+    f: &mut fmt::Formatter<'_>,
+    value: Option<T>,
+    write: impl FnOnce(&mut fmt::Formatter<'_>, T) -> fmt::Result,
+) -> fmt::Result {
+    match value {
+        Some(value) => write(f, value),
+        None => f.write_char('-'),
+    }
+}
+
+fn write_hash(f: &mut fmt::Formatter<'_>, hash: u64) -> fmt::Result {
+    write!(f, "#{hash:016x}")
+}
+
+fn write_blend_mode(f: &mut fmt::Formatter<'_>, blend_mode: BlendMode) -> fmt::Result {
+    write!(
+        f,
+        "{},{}",
+        blend_mode.mix.into_integer(),
+        blend_mode.compose.into_integer()
+    )
+}
+
+fn write_gradient(f: &mut fmt::Formatter<'_>, gradient: &Gradient) -> fmt::Result {
+    match &gradient.kind {
+        GradientKind::Linear(LinearGradientPosition { start, end }) => {
+            write!(f, "linear {} {} {} {}", start.x, start.y, end.x, end.y)?;
+        }
+        GradientKind::Radial(RadialGradientPosition {
+            start_center,
+            start_radius,
+            end_center,
+            end_radius,
+        }) => write!(
+            f,
+            "radial {} {} {start_radius} {} {} {end_radius}",
+            start_center.x, start_center.y, end_center.x, end_center.y
+        )?,
+        GradientKind::Sweep(SweepGradientPosition {
+            center,
+            start_angle,
+            end_angle,
+        }) => write!(
+            f,
+            "sweep {} {} {start_angle} {end_angle}",
+            center.x, center.y
+        )?,
+    }
+
+    let alpha_space = match gradient.interpolation_alpha_space {
+        InterpolationAlphaSpace::Premultiplied => "premultiplied",
+        InterpolationAlphaSpace::Unpremultiplied => "unpremultiplied",
+    };
+    write!(
+        f,
+        " {} {} {} {alpha_space}",
+        gradient.extend.into_integer(),
+        gradient.interpolation_cs.into_integer(),
+        gradient.hue_direction.into_integer(),
+    )?;
+    for stop in gradient.stops.iter() {
+        let [c0, c1, c2, c3] = stop.color.components;
+        write!(
+            f,
+            " {} {} {c0} {c1} {c2} {c3}",
+            stop.offset,
+            stop.color.cs.into_integer()
+        )?;
+    }
+
+    Ok(())
+}
+
+fn cap_name(cap: Cap) -> &'static str {
+    match cap {
+        Cap::Butt => "butt",
+        Cap::Square => "square",
+        Cap::Round => "round",
+    }
+}
+
+fn parse_cap(name: &str) -> Result<Cap, &'static str> {
+    match name {
+        "butt" => Ok(Cap::Butt),
+        "square" => Ok(Cap::Square),
+        "round" => Ok(Cap::Round),
+        _ => Err("invalid cap"),
+    }
+}
+
+struct DisplayRect<'a>(&'a Rect);
+
+impl fmt::Display for DisplayRect<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Rect { x0, y0, x1, y1 } = self.0;
+        write!(f, "{x0} {y0} {x1} {y1}")
+    }
+}
+
+struct DisplayAffine<'a>(&'a Affine);
+
+impl fmt::Display for DisplayAffine<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.0.as_coeffs();
+        write!(f, "{a} {b} {c} {d} {e} {g}")
+    }
+}
+
+struct DisplayColor<'a>(&'a AlphaColor<Srgb>);
+
+impl fmt::Display for DisplayColor<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [r, g, b, a] = self.0.components;
+        write!(f, "{r} {g} {b} {a}")
+    }
+}
+
+/// Paths are written as a single token, with each element starting with an SVG-like command
+/// letter followed by comma-separated coordinates, for example `M0,0L10,0L10,10Z`.
+struct DisplayPath<'a>(&'a Payload<BezPath>);
+
+impl fmt::Display for DisplayPath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = match self.0 {
+            Payload::Inline(path) => path,
+            Payload::Hashed(hash) => return write_hash(f, *hash),
+        };
+
+        if path.elements().is_empty() {
+            return f.write_char('_');
+        }
+
+        for el in path.elements() {
+            f.write_char(el_command(el))?;
+            let (points, count) = el_points(el);
+            for (idx, point) in points[..count].iter().enumerate() {
+                if idx > 0 {
+                    f.write_char(',')?;
+                }
+                write!(f, "{},{}", point.x, point.y)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn el_command(el: &PathEl) -> char {
+    match el {
+        PathEl::MoveTo(_) => 'M',
+        PathEl::LineTo(_) => 'L',
+        PathEl::QuadTo(..) => 'Q',
+        PathEl::CurveTo(..) => 'C',
+        PathEl::ClosePath => 'Z',
+    }
+}
+
+/// Return the points of a path element, padded to three points, along with their number.
+fn el_points(el: &PathEl) -> ([Point; 3], usize) {
+    match *el {
+        PathEl::MoveTo(p) | PathEl::LineTo(p) => ([p, Point::ZERO, Point::ZERO], 1),
+        PathEl::QuadTo(p0, p1) => ([p0, p1, Point::ZERO], 2),
+        PathEl::CurveTo(p0, p1, p2) => ([p0, p1, p2], 3),
+        PathEl::ClosePath => ([Point::ZERO; 3], 0),
+    }
+}
+
+struct Tokens<'a>(SplitAsciiWhitespace<'a>);
+
+impl<'a> Tokens<'a> {
+    fn new(text: &'a str) -> Self {
+        Self(text.split_ascii_whitespace())
+    }
+
+    fn next(&mut self) -> Result<&'a str, &'static str> {
+        self.0.next().ok_or("unexpected end of line")
+    }
+
+    fn finish(mut self) -> Result<(), &'static str> {
+        match self.0.next() {
+            Some(_) => Err("unexpected trailing data"),
+            None => Ok(()),
+        }
+    }
+
+    fn parse<T: core::str::FromStr>(&mut self) -> Result<T, &'static str> {
+        self.next()?.parse().map_err(|_| "invalid number")
+    }
+
+    fn optional<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, &'static str>,
+    ) -> Result<Option<T>, &'static str> {
+        let mut peek = Self(self.0.clone());
+        if peek.next()? == "-" {
+            self.0 = peek.0;
+            return Ok(None);
+        }
+
+        parse(self).map(Some)
+    }
+
+    fn hash(&mut self) -> Result<u64, &'static str> {
+        parse_hash(self.next()?)
+    }
+
+    fn rect(&mut self) -> Result<Rect, &'static str> {
+        Ok(Rect::new(
+            self.parse()?,
+            self.parse()?,
+            self.parse()?,
+            self.parse()?,
+        ))
+    }
+
+    fn affine(&mut self) -> Result<Affine, &'static str> {
+        Ok(Affine::new([
+            self.parse()?,
+            self.parse()?,
+            self.parse()?,
+            self.parse()?,
+            self.parse()?,
+            self.parse()?,
+        ]))
+    }
+
+    fn point(&mut self) -> Result<Point, &'static str> {
+        Ok(Point::new(self.parse()?, self.parse()?))
+    }
+
+    fn color(&mut self) -> Result<AlphaColor<Srgb>, &'static str> {
+        Ok(AlphaColor::new([
+            self.parse()?,
+            self.parse()?,
+            self.parse()?,
+            self.parse()?,
+        ]))
+    }
+
+    fn enum_value<T: Contiguous<Int = u8>>(&mut self) -> Result<T, &'static str> {
+        T::from_integer(self.parse()?).ok_or("invalid enum value")
+    }
+
+    fn blend_mode(&mut self) -> Result<BlendMode, &'static str> {
+        let (mix, compose) = self.next()?.split_once(',').ok_or("invalid blend mode")?;
+        let mix = mix.parse().ok().and_then(Mix::from_integer);
+        let compose = compose.parse().ok().and_then(Compose::from_integer);
+
+        match (mix, compose) {
+            (Some(mix), Some(compose)) => Ok(BlendMode::new(mix, compose)),
+            _ => Err("invalid blend mode"),
+        }
+    }
+
+    fn path(&mut self) -> Result<Payload<BezPath>, &'static str> {
+        let token = self.next()?;
+        if token.starts_with('#') {
+            return parse_hash(token).map(Payload::Hashed);
+        }
+        if token == "_" {
+            return Ok(Payload::Inline(BezPath::new()));
+        }
+
+        let mut path = BezPath::new();
+        let mut rest = token;
+        while let Some(command) = rest.chars().next() {
+            let args = &rest[1..];
+            let end = args.find(['M', 'L', 'Q', 'C', 'Z']).unwrap_or(args.len());
+            let (args, next) = args.split_at(end);
+            rest = next;
+
+            let mut coords = [0.0; 6];
+            let mut count = 0;
+            for arg in args.split(',').filter(|arg| !arg.is_empty()) {
+                *coords.get_mut(count).ok_or("invalid path")? =
+                    arg.parse().map_err(|_| "invalid path")?;
+                count += 1;
+            }
+            let point = |idx: usize| Point::new(coords[2 * idx], coords[2 * idx + 1]);
+
+            let el = match (command, count) {
+                ('M', 2) => PathEl::MoveTo(point(0)),
+                ('L', 2) => PathEl::LineTo(point(0)),
+                ('Q', 4) => PathEl::QuadTo(point(0), point(1)),
+                ('C', 6) => PathEl::CurveTo(point(0), point(1), point(2)),
+                ('Z', 0) => PathEl::ClosePath,
+                _ => return Err("invalid path"),
+            };
+            path.push(el);
+        }
+
+        Ok(Payload::Inline(path))
+    }
+
+    fn stroke(&mut self) -> Result<Stroke, &'static str> {
+        let width = self.parse()?;
+        let join = match self.next()? {
+            "bevel" => Join::Bevel,
+            "miter" => Join::Miter,
+            "round" => Join::Round,
+            _ => return Err("invalid join"),
+        };
+        let miter_limit = self.parse()?;
+        let start_cap = parse_cap(self.next()?)?;
+        let end_cap = parse_cap(self.next()?)?;
+        let dash_offset = self.parse()?;
+        let mut dashes = Vec::<f64>::new();
+        for dash in self.0.by_ref() {
+            dashes.push(dash.parse().map_err(|_| "invalid number")?);
+        }
+
+        Ok(Stroke::new(width)
+            .with_join(join)
+            .with_miter_limit(miter_limit)
+            .with_start_cap(start_cap)
+            .with_end_cap(end_cap)
+            .with_dashes(dash_offset, dashes))
+    }
+
+    fn tint(&mut self) -> Result<Tint, &'static str> {
+        let color = self.color()?;
+        let mode = match self.next()? {
+            "alpha_mask" => TintMode::AlphaMask,
+            "multiply" => TintMode::Multiply,
+            "inverse_coverage" => TintMode::InverseCoverage,
+            _ => return Err("invalid tint mode"),
+        };
+
+        Ok(Tint { color, mode })
+    }
+
+    fn gradient_hints(&mut self) -> Result<GradientHints, &'static str> {
+        let mut hints = Vec::new();
+        for token in self.0.by_ref() {
+            hints.push(match token {
+                "-" => None,
+                _ => Some(token.parse().map_err(|_| "invalid number")?),
+            });
+        }
+
+        Ok(GradientHints::new(&hints))
+    }
+
+    fn paint(&mut self) -> Result<Payload<RecordedPaint>, &'static str> {
+        let kind = match self.next()? {
+            "solid" => return Ok(Payload::Inline(RecordedPaint::Solid(self.color()?))),
+            "linear" => GradientKind::Linear(LinearGradientPosition {
+                start: self.point()?,
+                end: self.point()?,
+            }),
+            "radial" => GradientKind::Radial(RadialGradientPosition {
+                start_center: self.point()?,
+                start_radius: self.parse()?,
+                end_center: self.point()?,
+                end_radius: self.parse()?,
+            }),
+            "sweep" => GradientKind::Sweep(SweepGradientPosition {
+                center: self.point()?,
+                start_angle: self.parse()?,
+                end_angle: self.parse()?,
+            }),
+            hash => return parse_hash(hash).map(Payload::Hashed),
+        };
+
+        let extend: Extend = self.enum_value()?;
+        let interpolation_cs: ColorSpaceTag = self.enum_value()?;
+        let hue_direction: HueDirection = self.enum_value()?;
+        let interpolation_alpha_space = match self.next()? {
+            "premultiplied" => InterpolationAlphaSpace::Premultiplied,
+            "unpremultiplied" => InterpolationAlphaSpace::Unpremultiplied,
+            _ => return Err("invalid alpha space"),
+        };
+
+        let mut stops = Vec::new();
+        while let Some(offset) = self.0.next() {
+            let offset = offset.parse().map_err(|_| "invalid number")?;
+            let cs: ColorSpaceTag = self.enum_value()?;
+            let components = [self.parse()?, self.parse()?, self.parse()?, self.parse()?];
+            let mut color = DynamicColor::from_alpha_color(AlphaColor::<Srgb>::TRANSPARENT);
+            color.cs = cs;
+            color.components = components;
+            stops.push(ColorStop { offset, color });
+        }
+
+        Ok(Payload::Inline(RecordedPaint::Gradient(Gradient {
+            kind,
+            extend,
+            interpolation_cs,
+            hue_direction,
+            interpolation_alpha_space,
+            stops: stops.as_slice().into(),
+        })))
+    }
+}
+
+fn parse_hash(token: &str) -> Result<u64, &'static str> {
+    token
+        .strip_prefix('#')
+        .and_then(|hash| u64::from_str_radix(hash, 16).ok())
+        .ok_or("invalid hash")
+}
+
+/// The 64-bit FNV-1a hash, which is stable across platforms and program runs.
+struct Hasher(u64);
+
+impl Hasher {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Write for Hasher {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write(s.as_bytes());
+        Ok(())
+    }
+}
+
+fn hash_debug(value: &impl fmt::Debug) -> u64 {
+    let mut hasher = Hasher::new();
+    let _ = write!(hasher, "{value:?}");
+    hasher.finish()
+}
+
+fn hash_image(image: &Image) -> u64 {
+    let mut hasher = Hasher::new();
+    match &image.image {
+        ImageSource::Pixmap(pixmap) => {
+            hasher.write(&pixmap.width().to_le_bytes());
+            hasher.write(&pixmap.height().to_le_bytes());
+            hasher.write(pixmap.data_as_u8_slice());
+        }
+        ImageSource::OpaqueId { id, .. } => {
+            let _ = write!(hasher, "{id:?}");
+        }
+        ImageSource::CrossFade(cross_fade) => {
+            let _ = write!(hasher, "{cross_fade:?}");
+        }
+    }
+    let _ = write!(hasher, "{:?}", image.sampler);
+
+    hasher.finish()
+}
+
+fn hash_custom_paint(paint: &CustomPaint) -> u64 {
+    let mut hasher = Hasher::new();
+    hasher.write(&paint.kind.as_u32().to_le_bytes());
+    for param in paint.params.iter() {
+        hasher.write(&param.to_le_bytes());
+    }
+    hasher.finish()
+}
+
+fn hash_mask(mask: &Mask) -> u64 {
+    let mut hasher = Hasher::new();
+    hasher.write(&mask.width().to_le_bytes());
+    hasher.write(&mask.height().to_le_bytes());
+    for y in 0..mask.height() {
+        for x in 0..mask.width() {
+            hasher.write(&[mask.sample(x, y)]);
+        }
+    }
+
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::palette::css::{BLUE, REBECCA_PURPLE, RED};
+    use crate::{Pixmap, Resources};
+    use alloc::string::ToString;
+    use alloc::sync::Arc;
+    use vello_common::kurbo::{Circle, Dashes};
+    use vello_common::peniko::ImageSampler;
+
+    fn draw(ctx: &mut RenderContext) {
+        ctx.set_paint(REBECCA_PURPLE);
+        ctx.fill_rect(&Rect::new(2.0, 2.0, 30.0, 30.0));
+        ctx.set_transform(Affine::rotate_about(0.3, Point::new(16.0, 16.0)));
+        ctx.push_clip_layer(&Circle::new((16.0, 16.0), 12.0).to_path(0.1));
+        ctx.set_paint(Gradient::new_linear((0.0, 0.0), (32.0, 0.0)).with_stops([RED, BLUE]));
+        ctx.fill_path(&Circle::new((10.0, 10.0), 8.0).to_path(0.1));
+        ctx.set_stroke(Stroke::new(2.0).with_dashes(1.0, [3.0, 2.0]));
+        ctx.stroke_rect(&Rect::new(6.0, 6.0, 26.0, 26.0));
+        ctx.pop_layer();
+        ctx.set_blend_mode(BlendMode::new(Mix::Multiply, Compose::SrcOver));
+        ctx.set_paint(RED.with_alpha(0.5));
+        ctx.fill_blurred_rounded_rect(&Rect::new(8.0, 8.0, 24.0, 24.0), 4.0, 2.0);
+        ctx.fill_drop_shadow(
+            &Circle::new((12.0, 12.0), 6.0).to_path(0.1),
+            Vec2::new(2.0, 3.0),
+            1.5,
+        );
+        ctx.flush();
+    }
+
+    fn render(ctx: &RenderContext) -> Pixmap {
+        let mut pixmap = Pixmap::new(ctx.width(), ctx.height());
+        ctx.render_to_pixmap(&mut Resources::new(), &mut pixmap);
+        pixmap
+    }
+
+    #[test]
+    fn replay_matches_original() {
+        let mut ctx = RenderContext::new(32, 32);
+        ctx.start_replay_log();
+        draw(&mut ctx);
+        let log = ctx.take_replay_log().unwrap();
+
+        let parsed = ReplayLog::parse(&log.to_string()).unwrap();
+        assert_eq!(parsed, log);
+
+        let mut replayed = RenderContext::new(parsed.width(), parsed.height());
+        assert_eq!(parsed.replay(&mut replayed), 0);
+        assert_eq!(render(&replayed).data(), render(&ctx).data());
+    }
+
+    #[test]
+    fn commands_round_trip() {
+        let gradient = Gradient {
+            kind: RadialGradientPosition {
+                start_center: Point::new(1.5, 2.0),
+                start_radius: 0.25,
+                end_center: Point::new(-3.0, 4.0),
+                end_radius: 10.0,
+            }
+            .into(),
+            extend: Extend::Reflect,
+            interpolation_cs: ColorSpaceTag::Oklch,
+            hue_direction: HueDirection::Longer,
+            interpolation_alpha_space: InterpolationAlphaSpace::Unpremultiplied,
+            stops: Gradient::default()
+                .with_stops([(0.0, RED), (0.3, BLUE)])
+                .stops,
+        };
+        let mut path = BezPath::new();
+        path.move_to((0.1, 0.2));
+        path.quad_to((1.0, 2.0), (3.0, 4.0));
+        path.curve_to((5.0, 6.0), (7.0, 8.0), (-9.5, 1e-9));
+        path.close_path();
+
+        let commands = [
+            Command::FillPath(Payload::Inline(path.clone())),
+            Command::StrokePath(Payload::Inline(BezPath::new())),
+            Command::FillDropShadow(Payload::Hashed(1), Vec2::new(-1.5, 2.0), 0.25),
+            Command::FillCustomPaint(Payload::Hashed(2), 0xfeed),
+            Command::StrokeCustomPaint(Payload::Inline(BezPath::new()), 0xbeef),
+            Command::PushClipPath(Payload::Hashed(0x0123_4567_89ab_cdef)),
+            Command::PushLayer {
+                clip: Some(Payload::Inline(path)),
+                blend_mode: Some(BlendMode::new(Mix::Screen, Compose::Xor)),
+                opacity: Some(0.5),
+                mask: Some(7),
+                filter: None,
+            },
+            Command::PushLayer {
+                clip: None,
+                blend_mode: None,
+                opacity: None,
+                mask: None,
+                filter: Some(u64::MAX),
+            },
+            Command::PushBackdropBlurLayer(2.5, Payload::Hashed(3)),
+            Command::PushMaskGroup(MaskKind::Alpha),
+            Command::PushMaskGroup(MaskKind::Luminance),
+            Command::EndMask,
+            Command::SetStroke(
+                Stroke::new(3.5)
+                    .with_join(Join::Round)
+                    .with_start_cap(Cap::Square)
+                    .with_end_cap(Cap::Round)
+                    .with_dashes(0.5, Dashes::from_slice(&[1.0, 2.0, 3.0])),
+            ),
+            Command::SetPaint(Payload::Inline(RecordedPaint::Gradient(gradient))),
+            Command::SetPaint(Payload::Hashed(42)),
+            Command::SetTint(Some(Tint {
+                color: RED,
+                mode: TintMode::Multiply,
+            })),
+            Command::SetTint(None),
+            Command::SetGradientHints(GradientHints::new(&[Some(0.25), None, Some(0.9)])),
+            Command::SetGradientHints(GradientHints::default()),
+            Command::SetFillRule(Fill::EvenOdd),
+            Command::SetAliasingThreshold(Some(128)),
+            Command::SetAliasingThreshold(None),
+            Command::SetPixelSnapping(true),
+            Command::SetTransform(Affine::new([1.0, 0.5, -0.5, 1.0, 10.0, f64::MAX])),
+        ];
+
+        for command in commands {
+            let text = command.to_string();
+            assert_eq!(Command::parse(&text), Ok(command), "{text}");
+        }
+    }
+
+    #[test]
+    fn large_payloads_are_hashed() {
+        let mut ctx = RenderContext::new(16, 16);
+        let mut path = BezPath::new();
+        path.move_to((0.0, 0.0));
+        for i in 0..MAX_INLINE_PATH_ELEMENTS {
+            path.line_to((i as f64 % 16.0, 16.0));
+        }
+        let image = Image {
+            image: ImageSource::Pixmap(Arc::new(Pixmap::new(4, 4))),
+            sampler: ImageSampler::default(),
+        };
+
+        ctx.start_replay_log();
+        ctx.push_clip_path(&path);
+        ctx.set_paint(image);
+        ctx.fill_rect(&Rect::new(0.0, 0.0, 16.0, 16.0));
+        ctx.pop_clip_path();
+        let text = ctx.take_replay_log().unwrap().to_string();
+
+        assert!(text.len() < 2048, "{text}");
+        let log = ReplayLog::parse(&text).unwrap();
+        assert_eq!(log.replay(&mut RenderContext::new(16, 16)), 2);
+    }
+
+    #[test]
+    fn parse_errors_report_lines() {
+        let error = ReplayLog::parse("vello_cpu-replay 1 4 4\n\nflush\nfill_rect 0 0 1\n");
+        assert_eq!(
+            error,
+            Err(ReplayParseError {
+                line: 4,
+                message: "unexpected end of line"
+            })
+        );
+        assert_eq!(
+            ReplayLog::parse("something else").unwrap_err().message,
+            "invalid header"
+        );
+        assert_eq!(
+            ReplayLog::parse("vello_cpu-replay 1 4 4\nfill_path M0,0X1\n")
+                .unwrap_err()
+                .message,
+            "invalid path"
+        );
+    }
+}
@@ -0,0 +1,317 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Rendering of scrolled scenes.
+//!
+//! When a document is scrolled, most of the pixels of a frame are the same as in the previous
+//! frame, just at another position. A [`ScrollRenderer`] detects when the transform of the
+//! scene only differs from the previous frame by a translation by whole pixels, moves the
+//! previous frame accordingly, and only renders the strips along the edges of the viewport
+//! that were scrolled into view.
+
+use alloc::vec::Vec;
+use vello_common::geometry::RectU16;
+use vello_common::kurbo::Affine;
+#[cfg(not(feature = "std"))]
+use vello_common::kurbo::common::FloatFuncs as _;
+use vello_common::peniko::color::PremulRgba8;
+use vello_common::pixmap::Pixmap;
+
+use crate::{RenderContext, RenderSettings, Resources};
+
+/// The largest difference from a whole pixel that a translation can have to still be treated
+/// as one, to allow for the rounding errors of accumulated scroll offsets.
+const TRANSLATION_TOLERANCE: f64 = 1e-6;
+
+/// Renders a scene into a viewport, reusing the previous frame when the scene is scrolled.
+///
+/// The scene is drawn by the caller with the transform passed to [`render`](Self::render),
+/// which is already set on the render context. If only its translation changed since the
+/// previous frame, by whole pixels, the previous frame is shifted and only the exposed parts
+/// of the viewport are drawn again. Otherwise, and after
+/// [`invalidate`](Self::invalidate), the whole viewport is drawn.
+///
+/// After each [`render`](Self::render), [`damage`](Self::damage) holds the parts of the
+/// viewport that changed, which can be used to only present those parts.
+#[derive(Debug)]
+pub struct ScrollRenderer {
+    width: u16,
+    height: u16,
+    frame: Pixmap,
+    /// The transform the frame was rendered with, or `None` if it needs to be redrawn.
+    frame_transform: Option<Affine>,
+    damage: Vec<RectU16>,
+    settings: RenderSettings,
+    ctx: Option<RenderContext>,
+}
+
+impl ScrollRenderer {
+    /// Create a new scroll renderer with a viewport of the given size.
+    pub fn new(width: u16, height: u16) -> Self {
+        Self::new_with(width, height, RenderSettings::default())
+    }
+
+    /// Create a new scroll renderer that renders with specific settings.
+    pub fn new_with(width: u16, height: u16, settings: RenderSettings) -> Self {
+        Self {
+            width,
+            height,
+            frame: Pixmap::new(width, height),
+            frame_transform: None,
+            damage: Vec::new(),
+            settings,
+            ctx: None,
+        }
+    }
+
+    /// Resize the viewport, which redraws it in the next frame.
+    pub fn set_viewport_size(&mut self, width: u16, height: u16) {
+        if (width, height) != (self.width, self.height) {
+            self.width = width;
+            self.height = height;
+            self.frame = Pixmap::new(width, height);
+            self.frame_transform = None;
+        }
+    }
+
+    /// Mark the scene as changed, so that the whole viewport is drawn in the next frame.
+    pub fn invalidate(&mut self) {
+        self.frame_transform = None;
+    }
+
+    /// Update the viewport for the scene at `transform` and return it.
+    ///
+    /// `draw` is called once for each part of the viewport that needs to be drawn, with a
+    /// render context of the size of that part. The transform of the render context maps the
+    /// scene to that part, so draw calls that change the transform should concatenate with
+    /// [`RenderContext::transform`] instead of replacing it.
+    pub fn render(
+        &mut self,
+        resources: &mut Resources,
+        transform: Affine,
+        mut draw: impl FnMut(&mut RenderContext),
+    ) -> &Pixmap {
+        self.damage.clear();
+
+        match self
+            .frame_transform
+            .and_then(|previous| self.scroll_delta(previous, transform))
+        {
+            Some((0, 0)) => {}
+            Some((dx, dy)) => {
+                self.shift_frame(dx, dy);
+                for rect in self.exposed(dx, dy) {
+                    self.patch(resources, rect, transform, &mut draw);
+                }
+            }
+            None => {
+                let viewport = RectU16::new(0, 0, self.width, self.height);
+                self.patch(resources, viewport, transform, &mut draw);
+            }
+        }
+        self.frame_transform = Some(transform);
+
+        &self.frame
+    }
+
+    /// Return the parts of the viewport that were updated by the last call to
+    /// [`render`](Self::render).
+    pub fn damage(&self) -> &[RectU16] {
+        &self.damage
+    }
+
+    /// Return the viewport as of the last call to [`render`](Self::render).
+    pub fn frame(&self) -> &Pixmap {
+        &self.frame
+    }
+
+    /// Return by how many pixels the scene moved from `previous` to `transform`, if it moved
+    /// by whole pixels and some of the previous frame is still visible.
+    fn scroll_delta(&self, previous: Affine, transform: Affine) -> Option<(i32, i32)> {
+        let [a0, b0, c0, d0, e0, f0] = previous.as_coeffs();
+        let [a, b, c, d, e, f] = transform.as_coeffs();
+        if [a, b, c, d] != [a0, b0, c0, d0] {
+            return None;
+        }
+
+        let (dx, dy) = (e - e0, f - f0);
+        let (rounded_x, rounded_y) = (dx.round(), dy.round());
+        let whole_pixels = (dx - rounded_x).abs() <= TRANSLATION_TOLERANCE
+            && (dy - rounded_y).abs() <= TRANSLATION_TOLERANCE;
+        let overlaps =
+            rounded_x.abs() < f64::from(self.width) && rounded_y.abs() < f64::from(self.height);
+
+        (whole_pixels && overlaps).then_some((rounded_x as i32, rounded_y as i32))
+    }
+
+    /// Move the contents of the frame by `(dx, dy)` pixels.
+    fn shift_frame(&mut self, dx: i32, dy: i32) {
+        let width = i32::from(self.width);
+        let height = i32::from(self.height);
+        let columns = (width - dx.abs()) as usize;
+        let (src_x, dst_x) = if dx >= 0 { (0, dx) } else { (-dx, 0) };
+        let data = self.frame.data_mut();
+
+        let copy_row = |data: &mut [PremulRgba8], dst_y: i32| {
+            let src = ((dst_y - dy) * width + src_x) as usize;
+            let dst = (dst_y * width + dst_x) as usize;
+            data.copy_within(src..src + columns, dst);
+        };
+        // Copy the rows in an order that doesn't overwrite rows that still need to be moved.
+        let rows = dy.max(0)..height + dy.min(0);
+        if dy > 0 {
+            rows.rev().for_each(|y| copy_row(data, y));
+        } else {
+            rows.for_each(|y| copy_row(data, y));
+        }
+    }
+
+    /// Return the parts of the viewport that were scrolled into view by `(dx, dy)` pixels.
+    fn exposed(&self, dx: i32, dy: i32) -> Vec<RectU16> {
+        let (width, height) = (self.width, self.height);
+        let (dx_len, dy_len) = (dx.unsigned_abs() as u16, dy.unsigned_abs() as u16);
+        let mut exposed = Vec::new();
+
+        // The exposed columns span the whole height, the exposed rows only the rest of the
+        // width.
+        let (x0, x1) = match dx {
+            0 => (0, width),
+            1.. => {
+                exposed.push(RectU16::new(0, 0, dx_len, height));
+                (dx_len, width)
+            }
+            _ => {
+                exposed.push(RectU16::new(width - dx_len, 0, width, height));
+                (0, width - dx_len)
+            }
+        };
+        match dy {
+            0 => {}
+            1.. => exposed.push(RectU16::new(x0, 0, x1, dy_len)),
+            _ => exposed.push(RectU16::new(x0, height - dy_len, x1, height)),
+        }
+
+        exposed
+    }
+
+    /// Draw the scene into a part of the viewport.
+    fn patch(
+        &mut self,
+        resources: &mut Resources,
+        rect: RectU16,
+        transform: Affine,
+        draw: &mut impl FnMut(&mut RenderContext),
+    ) {
+        if rect.is_empty() {
+            return;
+        }
+        self.damage.push(rect);
+
+        let (width, height) = (rect.width(), rect.height());
+        let ctx = match &mut self.ctx {
+            Some(ctx) if ctx.width() == width && ctx.height() == height => {
+                ctx.reset();
+                ctx
+            }
+            ctx => ctx.insert(RenderContext::new_with(width, height, self.settings)),
+        };
+        let offset = Affine::translate((-f64::from(rect.x0), -f64::from(rect.y0)));
+        ctx.set_transform(offset * transform);
+        draw(ctx);
+        ctx.flush();
+
+        let mut pixmap = Pixmap::new(width, height);
+        ctx.render_to_pixmap(resources, &mut pixmap);
+
+        let stride = usize::from(self.width);
+        let columns = usize::from(rect.x0)..usize::from(rect.x1);
+        for (y, src) in pixmap.data().chunks_exact(usize::from(width)).enumerate() {
+            let row = (usize::from(rect.y0) + y) * stride;
+            self.frame.data_mut()[row..][columns.clone()].copy_from_slice(src);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::palette::css::{BLUE, RED};
+    use vello_common::kurbo::{Circle, Rect, Shape};
+
+    /// Draw a scene with anti-aliased edges, whose content is larger than the viewport.
+    fn draw(ctx: &mut RenderContext) {
+        ctx.set_paint(RED);
+        ctx.fill_rect(&Rect::new(2.25, 3.5, 60.75, 41.25));
+        ctx.set_paint(BLUE.with_alpha(0.5));
+        ctx.fill_path(&Circle::new((30.5, 20.25), 17.0).to_path(0.1));
+    }
+
+    fn render_full(width: u16, height: u16, transform: Affine) -> Pixmap {
+        let mut resources = Resources::new();
+        ScrollRenderer::new(width, height)
+            .render(&mut resources, transform, draw)
+            .clone()
+    }
+
+    #[test]
+    fn scrolling_by_whole_pixels_only_renders_exposed_parts() {
+        let mut resources = Resources::new();
+        let mut renderer = ScrollRenderer::new(40, 30);
+        renderer.render(&mut resources, Affine::IDENTITY, draw);
+        assert_eq!(renderer.damage(), [RectU16::new(0, 0, 40, 30)]);
+
+        let mut offset = (0.0, 0.0);
+        for (dx, dy, exposed) in [
+            (0.0, -5.0, [RectU16::new(0, 25, 40, 30)].as_slice()),
+            (
+                -3.0,
+                4.0,
+                &[RectU16::new(37, 0, 40, 30), RectU16::new(0, 0, 37, 4)],
+            ),
+            (7.0, 0.0, &[RectU16::new(0, 0, 7, 30)]),
+            (0.0, 0.0, &[]),
+        ] {
+            offset = (offset.0 + dx, offset.1 + dy);
+            let transform = Affine::translate(offset);
+            let frame = renderer.render(&mut resources, transform, draw);
+
+            // The same edges can be a rounding step apart when they are rendered at different
+            // positions in a render context.
+            let expected = render_full(40, 30, transform);
+            for (actual, expected) in frame.data().iter().zip(expected.data()) {
+                let (actual, expected) = (actual.to_u8_array(), expected.to_u8_array());
+                assert!(
+                    actual.iter().zip(expected).all(|(a, e)| a.abs_diff(e) <= 1),
+                    "{actual:?} != {expected:?} at offset {offset:?}"
+                );
+            }
+            assert_eq!(renderer.damage(), exposed, "offset {offset:?}");
+        }
+    }
+
+    #[test]
+    fn other_transforms_render_everything() {
+        let mut resources = Resources::new();
+        let mut renderer = ScrollRenderer::new(40, 30);
+        renderer.render(&mut resources, Affine::IDENTITY, draw);
+
+        let full = [RectU16::new(0, 0, 40, 30)];
+        // Not a whole pixel, scrolled out of the viewport, and not just translated.
+        for transform in [
+            Affine::translate((0.0, 2.5)),
+            Affine::translate((0.0, 32.5)),
+            Affine::translate((0.0, 32.5)).then_scale(1.5),
+        ] {
+            renderer.render(&mut resources, transform, draw);
+            assert_eq!(renderer.damage(), full, "{transform:?}");
+        }
+
+        renderer.invalidate();
+        renderer.render(
+            &mut resources,
+            Affine::translate((0.0, 32.5)).then_scale(1.5),
+            draw,
+        );
+        assert_eq!(renderer.damage(), full);
+    }
+}
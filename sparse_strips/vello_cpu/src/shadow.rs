@@ -0,0 +1,245 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Cached drop shadows of arbitrary paths.
+//!
+//! [`RenderContext::fill_drop_shadow`] rasterizes the silhouette of a path into a pixmap of
+//! its own, blurs it with the same Gaussian blur that is used for filter layers and draws the
+//! result as an image that is tinted with the shadow color. Since only the translation of a
+//! shadow usually changes between frames, the blurred silhouettes are cached, keyed by the
+//! path, the fill rule, the standard deviation, the linear part of the transform and the
+//! quality preset.
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+use vello_common::color::palette::css::WHITE;
+use vello_common::filter::PreparedFilter;
+use vello_common::filter_effects::{EdgeMode, Filter, FilterPrimitive};
+use vello_common::kurbo::{Affine, BezPath, PathEl, Shape, Vec2};
+use vello_common::peniko::Fill;
+#[cfg(not(feature = "std"))]
+use vello_common::peniko::kurbo::common::FloatFuncs as _;
+use vello_common::pixmap::Pixmap;
+use vello_common::quality::Quality;
+
+use crate::filter::gaussian_blur::apply_blur;
+use crate::{RenderContext, RenderSettings, Resources};
+
+/// The number of blurred silhouettes that are kept in the cache.
+const MAX_CACHED_SHADOWS: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ShadowKey {
+    /// The commands and coordinates of the path elements, bit by bit.
+    path: Vec<u64>,
+    fill_rule: u8,
+    /// The linear part of the transform, bit by bit.
+    linear: [u64; 4],
+    std_dev: u32,
+    quality: Quality,
+}
+
+impl ShadowKey {
+    fn new(
+        path: &BezPath,
+        fill_rule: Fill,
+        linear: Affine,
+        std_dev: f32,
+        quality: Quality,
+    ) -> Self {
+        let mut bits = Vec::with_capacity(path.elements().len() * 3);
+        for el in path.elements() {
+            let points: &[_] = match el {
+                PathEl::MoveTo(p0) => {
+                    bits.push(0);
+                    &[*p0]
+                }
+                PathEl::LineTo(p0) => {
+                    bits.push(1);
+                    &[*p0]
+                }
+                PathEl::QuadTo(p0, p1) => {
+                    bits.push(2);
+                    &[*p0, *p1]
+                }
+                PathEl::CurveTo(p0, p1, p2) => {
+                    bits.push(3);
+                    &[*p0, *p1, *p2]
+                }
+                PathEl::ClosePath => {
+                    bits.push(4);
+                    &[]
+                }
+            };
+            for point in points {
+                bits.extend([point.x.to_bits(), point.y.to_bits()]);
+            }
+        }
+
+        let [a, b, c, d, _, _] = linear.as_coeffs();
+
+        Self {
+            path: bits,
+            fill_rule: fill_rule as u8,
+            linear: [a, b, c, d].map(f64::to_bits),
+            std_dev: std_dev.to_bits(),
+            quality,
+        }
+    }
+}
+
+/// A blurred silhouette of a path.
+#[derive(Debug, Clone)]
+pub(crate) struct Shadow {
+    /// The blurred silhouette, as white with the coverage in the alpha channel.
+    pub(crate) pixmap: Arc<Pixmap>,
+    /// The position of the top-left corner of the pixmap, relative to the origin of the path
+    /// in device space.
+    pub(crate) origin: Vec2,
+}
+
+#[derive(Debug)]
+struct CachedShadow {
+    shadow: Shadow,
+    last_used: u64,
+}
+
+/// A cache of blurred path silhouettes.
+#[derive(Debug, Default)]
+pub(crate) struct ShadowCache {
+    entries: HashMap<ShadowKey, CachedShadow>,
+    epoch: u64,
+}
+
+impl ShadowCache {
+    /// Return the blurred silhouette of `path`, rendering it if it isn't cached yet.
+    ///
+    /// `linear` is the transform of the path without its translation, and `std_dev` is the
+    /// standard deviation of the blur in user space. Returns `None` if the silhouette doesn't
+    /// fit into a pixmap.
+    pub(crate) fn get_or_render(
+        &mut self,
+        path: &BezPath,
+        fill_rule: Fill,
+        linear: Affine,
+        std_dev: f32,
+        settings: RenderSettings,
+        quality: Quality,
+    ) -> Option<Shadow> {
+        let key = ShadowKey::new(path, fill_rule, linear, std_dev, quality);
+        self.epoch += 1;
+
+        if let Some(entry) = self.entries.get_mut(&key) {
+            entry.last_used = self.epoch;
+            return Some(entry.shadow.clone());
+        }
+
+        let shadow = render(path, fill_rule, linear, std_dev, settings, quality)?;
+        self.entries.insert(
+            key,
+            CachedShadow {
+                shadow: shadow.clone(),
+                last_used: self.epoch,
+            },
+        );
+        self.trim(MAX_CACHED_SHADOWS);
+
+        Some(shadow)
+    }
+
+    /// Return the number of cached silhouettes.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Remove all cached silhouettes.
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Evict the least recently used silhouettes until at most `max_entries` remain.
+    fn trim(&mut self, max_entries: usize) {
+        while self.entries.len() > max_entries {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+                .unwrap();
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "the size of the silhouette is checked to fit into u16 before casting"
+)]
+fn render(
+    path: &BezPath,
+    fill_rule: Fill,
+    linear: Affine,
+    std_dev: f32,
+    settings: RenderSettings,
+    quality: Quality,
+) -> Option<Shadow> {
+    // Preparing the blur as a filter scales the standard deviation into device space, just
+    // like for filter layers.
+    let filter = Filter::from_primitive(FilterPrimitive::GaussianBlur {
+        std_deviation: std_dev,
+        edge_mode: EdgeMode::None,
+    });
+    let PreparedFilter::GaussianBlur(blur) = PreparedFilter::new(&filter, &linear, quality) else {
+        unreachable!("a Gaussian blur is always prepared as one");
+    };
+
+    // Leave enough room around the silhouette for the blur to fade out.
+    let padding = f64::from((3.0 * blur.std_deviation.max(0.0)).ceil()) + 1.0;
+    let bounds = linear.transform_rect_bbox(path.bounding_box());
+    let x0 = (bounds.x0 - padding).floor();
+    let y0 = (bounds.y0 - padding).floor();
+    let width = (bounds.x1 + padding).ceil() - x0;
+    let height = (bounds.y1 + padding).ceil() - y0;
+
+    let max_size = f64::from(u16::MAX);
+    if !(width > 0.0 && width <= max_size && height > 0.0 && height <= max_size) {
+        return None;
+    }
+    let (width, height) = (width as u16, height as u16);
+
+    let mut ctx = RenderContext::new_with(
+        width,
+        height,
+        RenderSettings {
+            num_threads: 0,
+            ..settings
+        },
+    );
+    ctx.set_quality(quality);
+    ctx.set_paint(WHITE);
+    ctx.set_fill_rule(fill_rule);
+    ctx.set_transform(Affine::translate((-x0, -y0)) * linear);
+    ctx.fill_path(path);
+    ctx.flush();
+
+    let mut pixmap = Pixmap::new(width, height);
+    ctx.render_to_pixmap(&mut Resources::new(), &mut pixmap);
+
+    if blur.std_deviation > 0.0 {
+        let mut scratch = Pixmap::new(width, height);
+        apply_blur(
+            &mut pixmap,
+            &mut scratch,
+            blur.n_decimations,
+            &blur.kernel[..usize::from(blur.kernel_size)],
+            blur.edge_mode,
+        );
+    }
+
+    Some(Shadow {
+        pixmap: Arc::new(pixmap),
+        origin: Vec2::new(x0, y0),
+    })
+}
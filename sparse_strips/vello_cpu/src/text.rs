@@ -128,7 +128,7 @@ impl Resources {
                 DEFAULT_GLYPH_ATLAS_SIZE,
                 level,
                 render_mode,
-                GlyphCacheConfig::default(),
+                self.glyph_cache_config.clone(),
             ));
         }
     }
@@ -244,6 +244,30 @@ impl<'a> CpuGlyphRunBackend<'a> {
         );
         render(&mut glyph_run, self.ctx);
     }
+
+    /// Record the quads of the glyphs if an access tag is set, and draw them without recording
+    /// the primitives they are made of.
+    fn render_tagged_glyphs<Glyphs>(
+        self,
+        run: glifo::GlyphRun<'a>,
+        glyphs: Glyphs,
+        render: impl FnOnce(&mut glifo::GlyphRunRenderer<'a, 'a, Glyphs>, &mut RenderContext),
+    ) where
+        Glyphs: Iterator<Item = Glyph> + Clone,
+    {
+        let Some(tag) = self.ctx.access_tag.take() else {
+            self.render_glyphs(run, glyphs, render);
+            return;
+        };
+
+        self.ctx
+            .access_geometry
+            .add_glyph_quads(tag, run.glyph_quads(glyphs.clone()));
+        self.render_glyphs(run, glyphs, |glyph_run, ctx| {
+            render(glyph_run, ctx);
+            ctx.access_tag = Some(tag);
+        });
+    }
 }
 
 impl<'a> GlyphRunBackend<'a> for CpuGlyphRunBackend<'a> {
@@ -256,19 +280,21 @@ impl<'a> GlyphRunBackend<'a> for CpuGlyphRunBackend<'a> {
     where
         Glyphs: Iterator<Item = Glyph> + Clone,
     {
-        self.render_glyphs(run, glyphs, |glyph_run, ctx| glyph_run.fill_glyphs(ctx));
+        self.render_tagged_glyphs(run, glyphs, |glyph_run, ctx| glyph_run.fill_glyphs(ctx));
     }
 
     fn stroke_glyphs<Glyphs>(self, run: glifo::GlyphRun<'a>, glyphs: Glyphs)
     where
         Glyphs: Iterator<Item = Glyph> + Clone,
     {
-        self.render_glyphs(run, glyphs, |glyph_run, ctx| {
+        self.render_tagged_glyphs(run, glyphs, |glyph_run, ctx| {
             let stroke_adjustment = glyph_run.stroke_adjustment();
             let original_width = ctx.stroke().width;
             ctx.stroke_mut().width *= stroke_adjustment;
+            ctx.record_stroke();
             glyph_run.stroke_glyphs(ctx);
             ctx.stroke_mut().width = original_width;
+            ctx.record_stroke();
         });
     }
 
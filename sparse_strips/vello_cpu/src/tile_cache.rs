@@ -0,0 +1,110 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Rendering of document tiles.
+//!
+//! See [`DocumentTileCache`] for how pages are split into tiles and cached across zoom
+//! levels. A [`TileRenderer`] renders the tiles requested by the cache into pixmaps.
+
+use alloc::sync::Arc;
+use vello_common::pixmap::Pixmap;
+
+use crate::{RenderContext, RenderSettings, Resources};
+
+pub use vello_common::tile_cache::{DocumentTileCache, TileKey, TileRequest};
+
+/// Renders the tiles of a [`DocumentTileCache`] into pixmaps.
+///
+/// The render context is reused between tiles of the same size, which is the case for all
+/// tiles except those at the right and bottom edges of pages.
+#[derive(Debug)]
+pub struct TileRenderer {
+    settings: RenderSettings,
+    ctx: Option<RenderContext>,
+}
+
+impl Default for TileRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TileRenderer {
+    /// Create a new tile renderer.
+    pub fn new() -> Self {
+        Self::new_with(RenderSettings::default())
+    }
+
+    /// Create a new tile renderer with specific settings.
+    pub fn new_with(settings: RenderSettings) -> Self {
+        Self {
+            settings,
+            ctx: None,
+        }
+    }
+
+    /// Render the requested tile.
+    ///
+    /// `draw` should draw the whole page in page coordinates. The render context passed to it
+    /// already has the transform of the tile set, so draw calls that change the transform
+    /// should concatenate with [`RenderContext::transform`] instead of replacing it.
+    pub fn render(
+        &mut self,
+        resources: &mut Resources,
+        request: &TileRequest,
+        draw: impl FnOnce(&mut RenderContext),
+    ) -> Arc<Pixmap> {
+        let ctx = match &mut self.ctx {
+            Some(ctx) if ctx.width() == request.width && ctx.height() == request.height => {
+                ctx.reset();
+                ctx
+            }
+            ctx => ctx.insert(RenderContext::new_with(
+                request.width,
+                request.height,
+                self.settings,
+            )),
+        };
+
+        ctx.set_transform(request.transform);
+        draw(ctx);
+        ctx.flush();
+
+        let mut pixmap = Pixmap::new(request.width, request.height);
+        ctx.render_to_pixmap(resources, &mut pixmap);
+
+        Arc::new(pixmap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::palette::css::REBECCA_PURPLE;
+    use vello_common::kurbo::{Rect, Size};
+
+    #[test]
+    fn tiles_render_their_part_of_the_page() {
+        let mut resources = Resources::new();
+        let mut renderer = TileRenderer::new();
+        let mut cache = DocumentTileCache::new(16, 8);
+        let page = Size::new(20.0, 10.0);
+        cache.set_zoom(2.0);
+
+        let draw = |ctx: &mut RenderContext| {
+            ctx.set_paint(REBECCA_PURPLE);
+            ctx.fill_rect(&Rect::new(10.0, 0.0, 20.0, 10.0));
+        };
+        let tiles = cache.tiles(0, page, page.to_rect());
+        assert_eq!(tiles.len(), 6);
+
+        let first = cache.get_or_render(&tiles[0], |r| renderer.render(&mut resources, r, draw));
+        let second = cache.get_or_render(&tiles[1], |r| renderer.render(&mut resources, r, draw));
+        let last = cache.get_or_render(&tiles[5], |r| renderer.render(&mut resources, r, draw));
+
+        assert_eq!(first.sample(8, 8).a, 0);
+        assert_eq!(second.sample(8, 8).a, 255);
+        assert_eq!((last.width(), last.height()), (8, 4));
+        assert_eq!(last.sample(4, 2).a, 255);
+    }
+}
@@ -0,0 +1,446 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Rendering of long, scrollable lists.
+//!
+//! Lists in user interfaces often have far more rows than fit into the viewport, and most
+//! frames only scroll the list or change a few rows. A [`VirtualList`] only renders the rows
+//! that are visible, caches each rendered row as a pixmap, and updates the viewport
+//! incrementally: when scrolling, the previous frame is shifted and only the newly exposed
+//! band is patched from the row cache.
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::ops::Range;
+use hashbrown::HashMap;
+use vello_common::geometry::RectU16;
+use vello_common::peniko::color::PremulRgba8;
+use vello_common::pixmap::Pixmap;
+
+use crate::{RenderContext, RenderSettings, Resources};
+
+/// A list of rows with a fixed height, rendered through a viewport.
+///
+/// Rows are drawn by the caller in their own coordinate system, which spans the width of the
+/// viewport and the height of a row. Rendered rows are cached until they are invalidated with
+/// [`invalidate_row`](Self::invalidate_row) or scroll further than
+/// [`overscan`](Self::set_overscan) rows out of the viewport.
+///
+/// After each [`render`](Self::render), [`damage`](Self::damage) holds the parts of the
+/// viewport that changed, which can be used to only present those parts.
+#[derive(Debug)]
+pub struct VirtualList {
+    width: u16,
+    height: u16,
+    row_height: u16,
+    row_count: usize,
+    scroll_offset: u32,
+    overscan: usize,
+    rows: HashMap<usize, Arc<Pixmap>>,
+    /// Rows that changed since the last frame.
+    invalid_rows: Vec<usize>,
+    frame: Pixmap,
+    /// The scroll offset the frame was rendered at, or `None` if it needs to be redrawn.
+    frame_scroll_offset: Option<u32>,
+    damage: Vec<RectU16>,
+    settings: RenderSettings,
+    ctx: Option<RenderContext>,
+}
+
+impl VirtualList {
+    /// Create a new list with a viewport of the given size.
+    pub fn new(width: u16, height: u16, row_height: u16, row_count: usize) -> Self {
+        Self::new_with(
+            width,
+            height,
+            row_height,
+            row_count,
+            RenderSettings::default(),
+        )
+    }
+
+    /// Create a new list that renders rows with specific settings.
+    pub fn new_with(
+        width: u16,
+        height: u16,
+        row_height: u16,
+        row_count: usize,
+        settings: RenderSettings,
+    ) -> Self {
+        Self {
+            width,
+            height,
+            row_height: row_height.max(1),
+            row_count,
+            scroll_offset: 0,
+            overscan: 2,
+            rows: HashMap::new(),
+            invalid_rows: Vec::new(),
+            frame: Pixmap::new(width, height),
+            frame_scroll_offset: None,
+            damage: Vec::new(),
+            settings,
+            ctx: None,
+        }
+    }
+
+    /// Resize the viewport.
+    ///
+    /// Changing the width invalidates all cached rows.
+    pub fn set_viewport_size(&mut self, width: u16, height: u16) {
+        if width != self.width {
+            self.rows.clear();
+        }
+        if (width, height) != (self.width, self.height) {
+            self.width = width;
+            self.height = height;
+            self.frame = Pixmap::new(width, height);
+            self.frame_scroll_offset = None;
+            self.scroll_to(self.scroll_offset);
+        }
+    }
+
+    /// Set the number of rows.
+    ///
+    /// Rows that were added or removed are redrawn in the next frame. If the rows were
+    /// inserted or removed anywhere else than at the end, the shifted rows need to be
+    /// invalidated as well.
+    pub fn set_row_count(&mut self, row_count: usize) {
+        let changed = self.row_count.min(row_count)..self.row_count.max(row_count);
+        self.row_count = row_count;
+        self.rows.retain(|index, _| *index < row_count);
+        let visible = self.visible_rows();
+        self.invalid_rows
+            .extend(changed.filter(|row| visible.contains(row)));
+        self.scroll_to(self.scroll_offset);
+    }
+
+    /// Return the number of rows.
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// Set how many rows outside of the viewport are kept in the cache, in each direction.
+    ///
+    /// The default is 2.
+    pub fn set_overscan(&mut self, rows: usize) {
+        self.overscan = rows;
+    }
+
+    /// Scroll so that the viewport starts at `offset` pixels into the list.
+    ///
+    /// The offset is clamped to [`max_scroll_offset`](Self::max_scroll_offset).
+    pub fn scroll_to(&mut self, offset: u32) {
+        self.scroll_offset = offset.min(self.max_scroll_offset());
+    }
+
+    /// Scroll by `delta` pixels, where positive values scroll down.
+    pub fn scroll_by(&mut self, delta: i64) {
+        let offset = i64::from(self.scroll_offset).saturating_add(delta);
+        self.scroll_to(offset.clamp(0, i64::from(u32::MAX)) as u32);
+    }
+
+    /// Return the current scroll offset in pixels.
+    pub fn scroll_offset(&self) -> u32 {
+        self.scroll_offset
+    }
+
+    /// Return the largest scroll offset, at which the last row is at the bottom of the
+    /// viewport.
+    pub fn max_scroll_offset(&self) -> u32 {
+        let content_height = self.row_count as u64 * u64::from(self.row_height);
+        content_height
+            .saturating_sub(u64::from(self.height))
+            .try_into()
+            .unwrap_or(u32::MAX)
+    }
+
+    /// Return the rows that are at least partially visible.
+    pub fn visible_rows(&self) -> Range<usize> {
+        let row_height = u64::from(self.row_height);
+        let start = u64::from(self.scroll_offset) / row_height;
+        let end = (u64::from(self.scroll_offset) + u64::from(self.height)).div_ceil(row_height);
+
+        start as usize..(end as usize).min(self.row_count)
+    }
+
+    /// Mark a row as changed, so that it is drawn again when it is visible.
+    pub fn invalidate_row(&mut self, index: usize) {
+        self.rows.remove(&index);
+        if self.is_visible(index) {
+            self.invalid_rows.push(index);
+        }
+    }
+
+    /// Mark all rows as changed.
+    pub fn invalidate_all(&mut self) {
+        self.rows.clear();
+        self.frame_scroll_offset = None;
+    }
+
+    /// Return the number of cached rows.
+    pub fn cached_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Update the viewport and return it.
+    ///
+    /// `draw_row` is called with the index of each visible row that isn't cached yet. The
+    /// render context passed to it has the size of a row.
+    pub fn render(
+        &mut self,
+        resources: &mut Resources,
+        mut draw_row: impl FnMut(usize, &mut RenderContext),
+    ) -> &Pixmap {
+        self.damage.clear();
+        let height = u32::from(self.height);
+
+        match self.frame_scroll_offset {
+            Some(previous) if previous.abs_diff(self.scroll_offset) < height => {
+                let exposed = self.shift_frame(previous);
+                if !exposed.is_empty() {
+                    self.patch(resources, exposed, &mut draw_row);
+                }
+                let mut invalid_rows = core::mem::take(&mut self.invalid_rows);
+                invalid_rows.sort_unstable();
+                invalid_rows.dedup();
+                for row in invalid_rows.drain(..) {
+                    if let Some(band) = self.row_band(row) {
+                        self.patch(resources, band, &mut draw_row);
+                    }
+                }
+                self.invalid_rows = invalid_rows;
+            }
+            _ => {
+                self.invalid_rows.clear();
+                self.patch(resources, 0..self.height, &mut draw_row);
+            }
+        }
+        self.frame_scroll_offset = Some(self.scroll_offset);
+
+        let visible = self.visible_rows();
+        let keep =
+            visible.start.saturating_sub(self.overscan)..visible.end.saturating_add(self.overscan);
+        self.rows.retain(|index, _| keep.contains(index));
+
+        &self.frame
+    }
+
+    /// Return the parts of the viewport that were updated by the last call to
+    /// [`render`](Self::render).
+    pub fn damage(&self) -> &[RectU16] {
+        &self.damage
+    }
+
+    /// Return the viewport as of the last call to [`render`](Self::render).
+    pub fn frame(&self) -> &Pixmap {
+        &self.frame
+    }
+
+    fn is_visible(&self, row: usize) -> bool {
+        self.visible_rows().contains(&row)
+    }
+
+    /// Return the band of the viewport covered by a row, if it is visible.
+    fn row_band(&self, row: usize) -> Option<Range<u16>> {
+        let top = row as i64 * i64::from(self.row_height) - i64::from(self.scroll_offset);
+        let start = top.clamp(0, i64::from(self.height));
+        let end = (top + i64::from(self.row_height)).clamp(0, i64::from(self.height));
+
+        (start < end).then_some(start as u16..end as u16)
+    }
+
+    /// Move the contents of the frame from `previous` to the current scroll offset, and
+    /// return the band that needs to be redrawn.
+    fn shift_frame(&mut self, previous: u32) -> Range<u16> {
+        let width = usize::from(self.width);
+        let height = self.height;
+        let data = self.frame.data_mut();
+
+        if self.scroll_offset >= previous {
+            let delta = (self.scroll_offset - previous) as u16;
+            data.copy_within(usize::from(delta) * width.., 0);
+            height - delta..height
+        } else {
+            let delta = (previous - self.scroll_offset) as u16;
+            data.copy_within(
+                ..usize::from(height - delta) * width,
+                usize::from(delta) * width,
+            );
+            0..delta
+        }
+    }
+
+    /// Redraw a band of the viewport from the row cache.
+    fn patch(
+        &mut self,
+        resources: &mut Resources,
+        band: Range<u16>,
+        draw_row: &mut impl FnMut(usize, &mut RenderContext),
+    ) {
+        self.damage
+            .push(RectU16::new(0, band.start, self.width, band.end));
+
+        let width = usize::from(self.width);
+        let row_height = u64::from(self.row_height);
+        let mut y = band.start;
+        while y < band.end {
+            let content_y = u64::from(self.scroll_offset) + u64::from(y);
+            let row = (content_y / row_height) as usize;
+            let row_y = (content_y % row_height) as usize;
+            let lines = (row_height as usize - row_y).min(usize::from(band.end - y));
+            let dst = usize::from(y) * width..(usize::from(y) + lines) * width;
+
+            if row < self.row_count {
+                let pixmap = self.row(resources, row, draw_row);
+                let src = row_y * width..(row_y + lines) * width;
+                self.frame.data_mut()[dst].copy_from_slice(&pixmap.data()[src]);
+            } else {
+                self.frame.data_mut()[dst].fill(PremulRgba8::from_u32(0));
+            }
+            y += lines as u16;
+        }
+    }
+
+    fn row(
+        &mut self,
+        resources: &mut Resources,
+        index: usize,
+        draw_row: &mut impl FnMut(usize, &mut RenderContext),
+    ) -> Arc<Pixmap> {
+        if let Some(pixmap) = self.rows.get(&index) {
+            return pixmap.clone();
+        }
+
+        let (width, height) = (self.width, self.row_height);
+        let ctx = match &mut self.ctx {
+            Some(ctx) if ctx.width() == width && ctx.height() == height => {
+                ctx.reset();
+                ctx
+            }
+            ctx => ctx.insert(RenderContext::new_with(width, height, self.settings)),
+        };
+        draw_row(index, ctx);
+        ctx.flush();
+
+        let mut pixmap = Pixmap::new(width, height);
+        ctx.render_to_pixmap(resources, &mut pixmap);
+        let pixmap = Arc::new(pixmap);
+        self.rows.insert(index, pixmap.clone());
+
+        pixmap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::palette::css::{BLUE, RED};
+    use vello_common::kurbo::Rect;
+
+    /// Draw a row whose color encodes its index.
+    fn draw_row(index: usize, ctx: &mut RenderContext) {
+        let color = if index.is_multiple_of(2) { RED } else { BLUE };
+        ctx.set_paint(color.with_alpha((index + 1) as f32 / 32.0));
+        ctx.fill_rect(&Rect::new(0.0, 0.0, 4.0, 10.0));
+    }
+
+    fn render_full(list: &VirtualList, resources: &mut Resources) -> Pixmap {
+        let mut fresh = VirtualList::new(list.width, list.height, list.row_height, list.row_count);
+        fresh.scroll_to(list.scroll_offset());
+        fresh.render(resources, draw_row).clone()
+    }
+
+    #[test]
+    fn only_visible_rows_are_rendered() {
+        let mut resources = Resources::new();
+        let mut list = VirtualList::new(4, 25, 10, 1000);
+        let mut drawn = Vec::new();
+        list.render(&mut resources, |index, ctx| {
+            drawn.push(index);
+            draw_row(index, ctx);
+        });
+
+        assert_eq!(list.visible_rows(), 0..3);
+        assert_eq!(drawn, [0, 1, 2]);
+        assert_eq!(list.damage(), [RectU16::new(0, 0, 4, 25)]);
+        // The third row is only partially visible.
+        assert_ne!(list.frame().sample(0, 24).a, 0);
+    }
+
+    #[test]
+    fn scrolling_shifts_and_patches_the_frame() {
+        let mut resources = Resources::new();
+        let mut list = VirtualList::new(4, 25, 10, 8);
+        list.render(&mut resources, draw_row);
+
+        for (offset, exposed) in [(7, 18..25), (3, 0..4), (20, 8..25), (100, 0..25)] {
+            let mut drawn = Vec::new();
+            list.scroll_to(offset);
+            list.render(&mut resources, |index, ctx| {
+                drawn.push(index);
+                draw_row(index, ctx);
+            });
+
+            assert_eq!(list.scroll_offset(), offset.min(55));
+            assert_eq!(
+                list.damage(),
+                [RectU16::new(0, exposed.start, 4, exposed.end)]
+            );
+            assert_eq!(
+                list.frame().data(),
+                render_full(&list, &mut resources).data(),
+                "scroll offset {offset}"
+            );
+            // Rows that were rendered before are taken from the cache.
+            assert!(!drawn.contains(&1), "{drawn:?}");
+        }
+    }
+
+    #[test]
+    fn invalidated_rows_are_redrawn() {
+        let mut resources = Resources::new();
+        let mut list = VirtualList::new(4, 25, 10, 8);
+        list.scroll_to(5);
+        list.render(&mut resources, draw_row);
+
+        list.invalidate_row(0);
+        list.invalidate_row(7);
+        let frame = list.render(&mut resources, |_, ctx| {
+            ctx.set_paint(RED);
+            ctx.fill_rect(&Rect::new(0.0, 0.0, 4.0, 10.0));
+        });
+        assert_eq!(
+            frame.sample(0, 0),
+            PremulRgba8 {
+                r: 255,
+                g: 0,
+                b: 0,
+                a: 255
+            }
+        );
+        assert_eq!(list.damage(), [RectU16::new(0, 0, 4, 5)]);
+    }
+
+    #[test]
+    fn rows_out_of_the_overscan_are_evicted() {
+        let mut resources = Resources::new();
+        let mut list = VirtualList::new(4, 20, 10, 100);
+        list.set_overscan(1);
+        list.render(&mut resources, draw_row);
+        list.scroll_by(10);
+        list.render(&mut resources, draw_row);
+        assert_eq!(list.cached_rows(), 3);
+
+        list.scroll_by(500);
+        list.render(&mut resources, draw_row);
+        assert_eq!(list.visible_rows(), 51..53);
+        assert_eq!(list.cached_rows(), 2);
+
+        list.set_row_count(10);
+        assert_eq!(list.scroll_offset(), 80);
+        assert_eq!(list.cached_rows(), 0);
+        list.render(&mut resources, draw_row);
+        assert_eq!(list.visible_rows(), 8..10);
+    }
+}
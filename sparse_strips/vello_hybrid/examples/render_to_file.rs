@@ -9,8 +9,7 @@
 use std::io::BufWriter;
 use vello_common::kurbo::{Affine, Stroke};
 use vello_common::pico_svg::{Item, PicoSvg};
-use vello_common::pixmap::Pixmap;
-use vello_hybrid::{DimensionConstraints, Resources, Scene};
+use vello_hybrid::{DimensionConstraints, Readback, Resources, Scene};
 
 /// Main entry point for the headless rendering example.
 /// Takes two command line arguments:
@@ -80,6 +79,7 @@ async fn run() {
             format: texture.format(),
             width: width.into(),
             height: height.into(),
+            output_color: vello_hybrid::OutputColorConfig::default(),
         },
     );
     let mut resources = Resources::new();
@@ -104,61 +104,19 @@ async fn run() {
         )
         .unwrap();
 
-    // Create a buffer to copy the texture data
-    let bytes_per_row = (u32::from(width) * 4).next_multiple_of(256);
-    let texture_copy_buffer = device.create_buffer(&wgpu::BufferDescriptor {
-        label: Some("Output Buffer"),
-        size: u64::from(bytes_per_row) * u64::from(height),
-        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
-        mapped_at_creation: false,
-    });
-
-    encoder.copy_texture_to_buffer(
-        wgpu::TexelCopyTextureInfo {
-            texture: &texture,
-            mip_level: 0,
-            origin: wgpu::Origin3d::ZERO,
-            aspect: wgpu::TextureAspect::All,
-        },
-        wgpu::TexelCopyBufferInfo {
-            buffer: &texture_copy_buffer,
-            layout: wgpu::TexelCopyBufferLayout {
-                offset: 0,
-                bytes_per_row: Some(bytes_per_row),
-                rows_per_image: None,
-            },
-        },
-        wgpu::Extent3d {
-            width: width.into(),
-            height: height.into(),
-            depth_or_array_layers: 1,
-        },
-    );
+    // Copy the texture into a buffer, and read it back once the GPU is done.
+    let readback = Readback::new(&device, &mut encoder, &texture);
     queue.submit([encoder.finish()]);
 
-    // Map the buffer for reading
-    texture_copy_buffer
-        .slice(..)
-        .map_async(wgpu::MapMode::Read, move |result| {
-            if result.is_err() {
-                panic!("Failed to map texture for reading");
-            }
-        });
+    let (sender, receiver) = std::sync::mpsc::channel();
+    readback.read(move |image| sender.send(image).unwrap());
     device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
-
-    // Read back the pixel data
-    let mut img_data = Vec::with_capacity(usize::from(width) * usize::from(height));
-    for row in texture_copy_buffer
-        .slice(..)
-        .get_mapped_range()
-        .chunks_exact(bytes_per_row as usize)
-    {
-        img_data.extend_from_slice(bytemuck::cast_slice(&row[0..usize::from(width) * 4]));
-    }
-    texture_copy_buffer.unmap();
-
-    // Create the pixmap from the image data
-    let pixmap = Pixmap::from_parts(img_data, width, height);
+    let pixmap = receiver
+        .recv()
+        .unwrap()
+        .expect("Failed to map texture for reading")
+        .to_pixmap()
+        .unwrap();
 
     // Write the pixmap to a file
     let file = std::fs::File::create(output_filename).unwrap();
@@ -0,0 +1,162 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Demonstrates falling back from WebGPU to WebGL2 to the CPU in the browser.
+
+#![allow(
+    clippy::cast_possible_truncation,
+    reason = "truncation has no appreciable impact in this demo"
+)]
+#![cfg(target_arch = "wasm32")]
+
+mod renderer;
+
+pub use renderer::{Tier, WebRenderer};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use vello_common::kurbo::Affine;
+use vello_example_scenes::ExampleScene;
+use vello_example_scenes::clip::ClipScene;
+use wasm_bindgen::prelude::*;
+use web_sys::{Event, HtmlCanvasElement, KeyboardEvent};
+
+/// State that handles scene rendering and interactions
+struct AppState {
+    renderer: WebRenderer,
+    scene: ClipScene,
+    need_render: bool,
+}
+
+impl AppState {
+    fn render(&mut self) {
+        if !self.need_render {
+            return;
+        }
+
+        self.renderer.render(&mut self.scene, Affine::IDENTITY);
+        self.need_render = false;
+    }
+
+    fn resize(&mut self, width: u16, height: u16) {
+        self.renderer.resize(width, height);
+        self.need_render = true;
+    }
+
+    fn handle_key(&mut self, key: &str) {
+        if self.scene.handle_key(key) {
+            self.need_render = true;
+        }
+    }
+}
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = requestAnimationFrame)]
+    fn request_animation_frame(f: &Closure<dyn FnMut()>);
+}
+
+/// Read the most capable tier to try from the `tier` URL query parameter.
+fn requested_tier() -> Tier {
+    let search = web_sys::window().unwrap().location().search().unwrap();
+    let tier = web_sys::UrlSearchParams::new_with_str(&search)
+        .ok()
+        .and_then(|params| params.get("tier"));
+
+    match tier {
+        Some(name) => Tier::from_name(&name).unwrap_or_else(|| {
+            log::warn!("Unknown tier `{name}`, expected `webgpu`, `webgl2` or `cpu`");
+            Tier::WebGpu
+        }),
+        None => Tier::WebGpu,
+    }
+}
+
+/// Creates a `HTMLCanvasElement` of the given dimensions, picks the most capable renderer for
+/// it and renders an example scene, showing which tier was selected.
+pub async fn run_interactive(canvas_width: u16, canvas_height: u16) {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let canvas = document
+        .create_element("canvas")
+        .unwrap()
+        .dyn_into::<HtmlCanvasElement>()
+        .unwrap();
+    canvas.set_width(canvas_width as u32);
+    canvas.set_height(canvas_height as u32);
+    canvas.style().set_property("width", "100%").unwrap();
+    canvas.style().set_property("height", "100%").unwrap();
+
+    let body = document.body().unwrap();
+    // Apply background color so white text can be seen.
+    body.style()
+        .set_property("background-color", "#111")
+        .unwrap();
+    body.append_child(&canvas).unwrap();
+
+    let renderer = WebRenderer::new_with(canvas, requested_tier().and_fallbacks()).await;
+
+    let label = document.create_element("div").unwrap();
+    label.set_text_content(Some(&format!("Tier: {}", renderer.tier())));
+    label
+        .set_attribute(
+            "style",
+            "position: fixed; top: 10px; left: 10px; padding: 4px 8px; \
+             background: rgba(0, 0, 0, 0.7); color: white; font-family: monospace;",
+        )
+        .unwrap();
+    body.append_child(&label).unwrap();
+
+    let app_state = Rc::new(RefCell::new(AppState {
+        renderer,
+        scene: ClipScene::new(),
+        need_render: true,
+    }));
+
+    // Set up animation frame loop
+    {
+        let f = Rc::new(RefCell::new(None));
+        let g = f.clone();
+        let app_state = app_state.clone();
+
+        *g.borrow_mut() = Some(Closure::wrap(Box::new(move || {
+            app_state.borrow_mut().render();
+            request_animation_frame(f.borrow().as_ref().unwrap());
+        }) as Box<dyn FnMut()>));
+
+        request_animation_frame(g.borrow().as_ref().unwrap());
+    }
+
+    // Set up window resize event handler
+    {
+        let app_state = app_state.clone();
+        let closure = Closure::wrap(Box::new(move |_: Event| {
+            let window = web_sys::window().unwrap();
+            let dpr = window.device_pixel_ratio();
+
+            let width = window.inner_width().unwrap().as_f64().unwrap() as u16 * dpr as u16;
+            let height = window.inner_height().unwrap().as_f64().unwrap() as u16 * dpr as u16;
+
+            app_state.borrow_mut().resize(width, height);
+        }) as Box<dyn FnMut(_)>);
+
+        let window = web_sys::window().unwrap();
+        window
+            .add_event_listener_with_callback("resize", closure.as_ref().unchecked_ref())
+            .unwrap();
+        closure.forget();
+    }
+
+    // Forward key presses to the scene
+    {
+        let app_state = app_state.clone();
+        let closure = Closure::wrap(Box::new(move |event: KeyboardEvent| {
+            app_state.borrow_mut().handle_key(&event.key());
+        }) as Box<dyn FnMut(_)>);
+
+        let window = web_sys::window().unwrap();
+        window
+            .add_event_listener_with_callback("keydown", closure.as_ref().unchecked_ref())
+            .unwrap();
+        closure.forget();
+    }
+}
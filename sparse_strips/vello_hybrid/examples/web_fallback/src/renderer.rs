@@ -0,0 +1,398 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! A renderer that uses the most capable backend the browser supports.
+
+use std::fmt;
+
+use vello_common::fearless_simd::Level;
+use vello_common::kurbo::Affine;
+use vello_example_scenes::ExampleScene;
+use vello_hybrid::{
+    AtlasConfig, OutputColorConfig, RenderSettings, RenderSize, RenderTargetConfig, Scene,
+};
+use wasm_bindgen::{Clamped, JsCast};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, ImageData};
+use wgpu::CurrentSurfaceTexture;
+
+/// The rendering backend selected by a [`WebRenderer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tier {
+    /// Vello Hybrid using the browser's WebGPU implementation through `wgpu`.
+    WebGpu,
+    /// Vello Hybrid using its dedicated WebGL2 renderer.
+    WebGl2,
+    /// Vello CPU, copying every frame into a 2D canvas context.
+    Cpu,
+}
+
+impl Tier {
+    /// All tiers, from the most to the least capable.
+    pub const ALL: [Self; 3] = [Self::WebGpu, Self::WebGl2, Self::Cpu];
+
+    /// The name of the tier, as accepted by [`Tier::from_name`].
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::WebGpu => "webgpu",
+            Self::WebGl2 => "webgl2",
+            Self::Cpu => "cpu",
+        }
+    }
+
+    /// Parse a tier from its name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|tier| tier.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Return this tier followed by all less capable ones.
+    pub fn and_fallbacks(self) -> &'static [Self] {
+        let start = Self::ALL.iter().position(|tier| *tier == self).unwrap();
+        &Self::ALL[start..]
+    }
+}
+
+impl fmt::Display for Tier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Renders example scenes into a canvas, using the most capable backend that is available.
+///
+/// The backend is chosen once, when the renderer is created, and can be queried with
+/// [`WebRenderer::tier`]. Everything else works the same regardless of the selected tier.
+pub struct WebRenderer {
+    canvas: HtmlCanvasElement,
+    backend: Backend,
+}
+
+impl fmt::Debug for WebRenderer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebRenderer")
+            .field("tier", &self.tier())
+            .finish_non_exhaustive()
+    }
+}
+
+enum Backend {
+    WebGpu(WebGpuBackend),
+    WebGl2(WebGl2Backend),
+    Cpu(CpuBackend),
+}
+
+impl WebRenderer {
+    /// Create a renderer for `canvas`, trying WebGPU first, then WebGL2 and finally the CPU.
+    pub async fn new(canvas: HtmlCanvasElement) -> Self {
+        Self::new_with(canvas, &Tier::ALL).await
+    }
+
+    /// Create a renderer for `canvas`, trying the given tiers in order.
+    ///
+    /// Availability is probed without touching `canvas`, since a canvas can't switch to a
+    /// different kind of context once one has been created. If none of the given tiers are
+    /// available, the CPU tier is used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `canvas` already has a context that doesn't match the selected tier.
+    pub async fn new_with(canvas: HtmlCanvasElement, tiers: &[Tier]) -> Self {
+        let mut backend = None;
+
+        for tier in tiers {
+            backend = match tier {
+                Tier::WebGpu => WebGpuBackend::new(&canvas).await.map(Backend::WebGpu),
+                Tier::WebGl2 => WebGl2Backend::new(&canvas).map(Backend::WebGl2),
+                Tier::Cpu => Some(Backend::Cpu(CpuBackend::new(&canvas))),
+            };
+            if backend.is_some() {
+                break;
+            }
+            log::info!("The {tier} tier is not available");
+        }
+
+        let backend = backend.unwrap_or_else(|| Backend::Cpu(CpuBackend::new(&canvas)));
+        let renderer = Self { canvas, backend };
+        log::info!("Rendering with the {} tier", renderer.tier());
+
+        renderer
+    }
+
+    /// The tier that was selected when creating the renderer.
+    pub fn tier(&self) -> Tier {
+        match self.backend {
+            Backend::WebGpu(_) => Tier::WebGpu,
+            Backend::WebGl2(_) => Tier::WebGl2,
+            Backend::Cpu(_) => Tier::Cpu,
+        }
+    }
+
+    /// The canvas that is rendered into.
+    pub fn canvas(&self) -> &HtmlCanvasElement {
+        &self.canvas
+    }
+
+    /// Resize the canvas and all render targets.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.canvas.set_width(u32::from(width));
+        self.canvas.set_height(u32::from(height));
+
+        match &mut self.backend {
+            Backend::WebGpu(backend) => backend.resize(width, height),
+            Backend::WebGl2(backend) => backend.scene = Scene::new(width, height),
+            Backend::Cpu(backend) => backend.resize(width, height),
+        }
+    }
+
+    /// Render `example` into the canvas.
+    pub fn render<S: ExampleScene>(&mut self, example: &mut S, transform: Affine) {
+        match &mut self.backend {
+            Backend::WebGpu(backend) => backend.render(example, transform),
+            Backend::WebGl2(backend) => backend.render(example, transform),
+            Backend::Cpu(backend) => backend.render(example, transform),
+        }
+    }
+}
+
+struct WebGpuBackend {
+    renderer: vello_hybrid::Renderer,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    surface: wgpu::Surface<'static>,
+    scene: Scene,
+    resources: vello_hybrid::Resources,
+}
+
+impl WebGpuBackend {
+    const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+    async fn new(canvas: &HtmlCanvasElement) -> Option<Self> {
+        if !wgpu::util::is_browser_webgpu_supported().await {
+            return None;
+        }
+
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::BROWSER_WEBGPU,
+            ..wgpu::InstanceDescriptor::new_without_display_handle()
+        });
+        // Request the device before creating the surface, so that the canvas is left untouched
+        // if WebGPU turns out to be unusable.
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions::default())
+            .await
+            .ok()?;
+        // Downlevel adapters can only do what WebGL2 can, so leave them to the dedicated
+        // WebGL2 renderer, which is smaller and faster on such devices.
+        if vello_hybrid::Renderer::is_downlevel(&adapter) {
+            log::info!("The WebGPU adapter only has downlevel capabilities");
+            return None;
+        }
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor {
+                label: None,
+                required_features: wgpu::Features::empty(),
+                required_limits: vello_hybrid::Renderer::required_limits(&adapter),
+                ..Default::default()
+            })
+            .await
+            .ok()?;
+        let surface = instance
+            .create_surface(wgpu::SurfaceTarget::Canvas(canvas.clone()))
+            .ok()?;
+
+        let width = canvas.width();
+        let height = canvas.height();
+        let max_texture_dimension_2d = device.limits().max_texture_dimension_2d;
+        let renderer = vello_hybrid::Renderer::new_with(
+            &device,
+            &RenderTargetConfig {
+                format: Self::FORMAT,
+                width,
+                height,
+                output_color: OutputColorConfig::default(),
+            },
+            RenderSettings {
+                level: Level::try_detect().unwrap_or(Level::baseline()),
+                atlas_config: AtlasConfig {
+                    atlas_size: (max_texture_dimension_2d, max_texture_dimension_2d),
+                    ..AtlasConfig::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        let mut backend = Self {
+            renderer,
+            device,
+            queue,
+            surface,
+            scene: Scene::new(1, 1),
+            resources: vello_hybrid::Resources::new(),
+        };
+        backend.resize(width as u16, height as u16);
+
+        Some(backend)
+    }
+
+    fn resize(&mut self, width: u16, height: u16) {
+        self.surface.configure(
+            &self.device,
+            &wgpu::SurfaceConfiguration {
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                format: Self::FORMAT,
+                width: u32::from(width),
+                height: u32::from(height),
+                present_mode: wgpu::PresentMode::Fifo,
+                alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+                desired_maximum_frame_latency: 2,
+                view_formats: vec![],
+            },
+        );
+        self.scene = Scene::new(width, height);
+    }
+
+    fn render<S: ExampleScene>(&mut self, example: &mut S, transform: Affine) {
+        self.scene.reset();
+        example.render(&mut self.scene, &mut self.resources, transform);
+
+        let surface_texture = match self.surface.get_current_texture() {
+            CurrentSurfaceTexture::Success(surface_texture) => surface_texture,
+            CurrentSurfaceTexture::Occluded
+            | CurrentSurfaceTexture::Timeout
+            | CurrentSurfaceTexture::Outdated
+            | CurrentSurfaceTexture::Suboptimal(_) => {
+                return;
+            }
+            CurrentSurfaceTexture::Lost => panic!("Surface was lost"),
+            CurrentSurfaceTexture::Validation => {
+                panic!("Validation error getting surface")
+            }
+        };
+        let surface_texture_view = surface_texture
+            .texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+
+        self.renderer
+            .render(
+                &self.scene,
+                &mut self.resources,
+                &self.device,
+                &self.queue,
+                &mut encoder,
+                &render_size(&self.scene),
+                &surface_texture_view,
+                &vello_hybrid::TextureBindings::new(),
+            )
+            .unwrap();
+
+        self.queue.submit([encoder.finish()]);
+        surface_texture.present();
+    }
+}
+
+struct WebGl2Backend {
+    renderer: vello_hybrid::WebGlRenderer,
+    scene: Scene,
+    resources: vello_hybrid::Resources,
+}
+
+impl WebGl2Backend {
+    fn new(canvas: &HtmlCanvasElement) -> Option<Self> {
+        // Probe with a throwaway canvas, as the renderer panics if WebGL2 is unavailable.
+        let probe = web_sys::window()?
+            .document()?
+            .create_element("canvas")
+            .ok()?
+            .dyn_into::<HtmlCanvasElement>()
+            .ok()?;
+        probe.get_context("webgl2").ok()??;
+
+        Some(Self {
+            renderer: vello_hybrid::WebGlRenderer::new(canvas),
+            scene: Scene::new(canvas.width() as u16, canvas.height() as u16),
+            resources: vello_hybrid::Resources::new(),
+        })
+    }
+
+    fn render<S: ExampleScene>(&mut self, example: &mut S, transform: Affine) {
+        self.scene.reset();
+        example.render(&mut self.scene, &mut self.resources, transform);
+
+        self.renderer
+            .render(&self.scene, &mut self.resources, &render_size(&self.scene))
+            .unwrap();
+    }
+}
+
+struct CpuBackend {
+    ctx: vello_cpu::RenderContext,
+    resources: vello_cpu::Resources,
+    pixmap: vello_cpu::Pixmap,
+    context_2d: CanvasRenderingContext2d,
+}
+
+impl CpuBackend {
+    fn new(canvas: &HtmlCanvasElement) -> Self {
+        let context_2d = canvas
+            .get_context("2d")
+            .ok()
+            .flatten()
+            .expect("canvas should not have a non-2D context")
+            .dyn_into::<CanvasRenderingContext2d>()
+            .unwrap();
+        let width = canvas.width() as u16;
+        let height = canvas.height() as u16;
+
+        Self {
+            ctx: Self::render_context(width, height),
+            resources: vello_cpu::Resources::new(),
+            pixmap: vello_cpu::Pixmap::new(width, height),
+            context_2d,
+        }
+    }
+
+    fn render_context(width: u16, height: u16) -> vello_cpu::RenderContext {
+        vello_cpu::RenderContext::new_with(
+            width,
+            height,
+            vello_cpu::RenderSettings {
+                num_threads: 0,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn resize(&mut self, width: u16, height: u16) {
+        self.ctx = Self::render_context(width, height);
+        self.pixmap.resize(width, height);
+    }
+
+    fn render<S: ExampleScene>(&mut self, example: &mut S, transform: Affine) {
+        self.ctx.reset();
+        example.render(&mut self.ctx, &mut self.resources, transform);
+        self.ctx.flush();
+        self.ctx
+            .render_to_pixmap(&mut self.resources, &mut self.pixmap);
+
+        let image_data = ImageData::new_with_u8_clamped_array_and_sh(
+            Clamped(self.pixmap.data_as_u8_slice()),
+            u32::from(self.pixmap.width()),
+            u32::from(self.pixmap.height()),
+        )
+        .expect("Failed to create ImageData");
+        self.context_2d
+            .put_image_data(&image_data, 0., 0.)
+            .expect("Failed to put image data");
+    }
+}
+
+fn render_size(scene: &Scene) -> RenderSize {
+    RenderSize {
+        width: u32::from(scene.width()),
+        height: u32::from(scene.height()),
+    }
+}
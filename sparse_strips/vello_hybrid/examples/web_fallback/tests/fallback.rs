@@ -0,0 +1,57 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Tests whether each tier of the fallback example can be selected and renders without panicking.
+#![cfg(target_arch = "wasm32")]
+
+mod wasm {
+    use vello_common::kurbo::Affine;
+    use vello_example_scenes::clip::ClipScene;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::*;
+    use web_fallback::{Tier, WebRenderer};
+    use web_sys::HtmlCanvasElement;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn canvas() -> HtmlCanvasElement {
+        let canvas = web_sys::window()
+            .unwrap()
+            .document()
+            .unwrap()
+            .create_element("canvas")
+            .unwrap()
+            .dyn_into::<HtmlCanvasElement>()
+            .unwrap();
+        canvas.set_width(100);
+        canvas.set_height(100);
+        canvas
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_renders_with_best_tier() {
+        let _ = console_log::init_with_level(log::Level::Debug);
+
+        let mut renderer = WebRenderer::new(canvas()).await;
+        renderer.render(&mut ClipScene::new(), Affine::IDENTITY);
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_falls_back_to_cpu() {
+        let mut renderer = WebRenderer::new_with(canvas(), &[]).await;
+        assert_eq!(renderer.tier(), Tier::Cpu);
+
+        renderer.resize(50, 80);
+        renderer.render(&mut ClipScene::new(), Affine::IDENTITY);
+        assert_eq!(renderer.canvas().width(), 50);
+    }
+
+    #[wasm_bindgen_test]
+    fn test_tier_names_round_trip() {
+        for tier in Tier::ALL {
+            assert_eq!(Tier::from_name(tier.name()), Some(tier));
+        }
+        assert_eq!(Tier::from_name("WebGL2"), Some(Tier::WebGl2));
+        assert_eq!(Tier::WebGl2.and_fallbacks(), [Tier::WebGl2, Tier::Cpu]);
+    }
+}
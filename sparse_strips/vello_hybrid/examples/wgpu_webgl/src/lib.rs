@@ -17,7 +17,9 @@ use vello_common::{
     paint::{ImageId, ImageSource},
 };
 use vello_example_scenes::{AnyScene, image::ImageScene};
-use vello_hybrid::{AtlasConfig, Pixmap, RenderSettings, RenderTargetConfig, Renderer, Scene};
+use vello_hybrid::{
+    AtlasConfig, OutputColorConfig, Pixmap, RenderSettings, RenderTargetConfig, Renderer, Scene,
+};
 use wasm_bindgen::prelude::*;
 use web_sys::{Event, HtmlCanvasElement, KeyboardEvent, MouseEvent, WheelEvent};
 use wgpu::{
@@ -64,11 +66,7 @@ impl RendererWrapper {
             .request_device(&wgpu::DeviceDescriptor {
                 label: None,
                 required_features: wgpu::Features::empty(),
-                required_limits: wgpu::Limits {
-                    max_texture_dimension_2d: adapter.limits().max_texture_dimension_2d,
-                    max_buffer_size: adapter.limits().max_buffer_size,
-                    ..wgpu::Limits::downlevel_webgl2_defaults()
-                },
+                required_limits: Renderer::required_limits(&adapter),
                 ..Default::default()
             })
             .await
@@ -95,6 +93,7 @@ impl RendererWrapper {
                 format: surface_format,
                 width,
                 height,
+                output_color: OutputColorConfig::default(),
             },
             RenderSettings {
                 level: Level::try_detect().unwrap_or(Level::baseline()),
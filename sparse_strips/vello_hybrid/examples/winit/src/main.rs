@@ -44,6 +44,8 @@ struct App<'s> {
     fps_update_time: Instant,
     accumulated_frame_time: f64,
     accumulated_render_time: f64,
+    #[cfg(feature = "hot_reload")]
+    shader_watcher: vello_hybrid::ShaderWatcher,
 }
 
 fn main() {
@@ -112,6 +114,14 @@ fn main() {
         fps_update_time: now,
         accumulated_frame_time: 0.0,
         accumulated_render_time: 0.0,
+        #[cfg(feature = "hot_reload")]
+        shader_watcher: {
+            let watcher = vello_hybrid::ShaderWatcher::bundled();
+            match env::var_os("VELLO_SHADER_PARAMETERS") {
+                Some(path) => watcher.with_parameters(path),
+                None => watcher,
+            }
+        },
     };
 
     let event_loop = EventLoop::new().unwrap();
@@ -357,6 +367,9 @@ impl ApplicationHandler for App<'_> {
                 self.scene.set_transform(self.transform);
                 self.scenes[self.current_scene].render(&mut self.scene, self.transform);
 
+                #[cfg(feature = "hot_reload")]
+                reload_shaders(&mut self.shader_watcher, &mut self.renderers, &self.context);
+
                 let device_handle = &self.context.devices[surface.dev_id];
                 let render_size = RenderSize {
                     width: surface.config.width,
@@ -527,3 +540,28 @@ impl App<'_> {
         texture
     }
 }
+
+/// Rebuild the pipelines of all renderers if the shaders changed on disk.
+#[cfg(feature = "hot_reload")]
+fn reload_shaders(
+    watcher: &mut vello_hybrid::ShaderWatcher,
+    renderers: &mut [Option<Renderer>],
+    context: &RenderContext,
+) {
+    let Some(shaders) = watcher.poll() else {
+        return;
+    };
+    let result = shaders.and_then(|shaders| {
+        for (renderer, device_handle) in renderers.iter_mut().zip(&context.devices) {
+            if let Some(renderer) = renderer {
+                renderer.reload_shaders(&device_handle.device, &shaders)?;
+            }
+        }
+        Ok(())
+    });
+
+    match result {
+        Ok(()) => println!("Reloaded shaders from {}", watcher.dir().display()),
+        Err(e) => eprintln!("Failed to reload shaders: {e}"),
+    }
+}
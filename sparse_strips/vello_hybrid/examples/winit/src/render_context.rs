@@ -8,10 +8,10 @@
 
 use std::sync::Arc;
 
-use vello_hybrid::{RenderTargetConfig, Renderer};
+use vello_hybrid::{OutputColorConfig, RenderTargetConfig, Renderer};
 use wgpu::{
-    Adapter, Device, Features, Instance, Limits, Queue, Surface, SurfaceConfiguration,
-    SurfaceTarget, TextureFormat,
+    Adapter, Device, Features, Instance, Queue, Surface, SurfaceConfiguration, SurfaceTarget,
+    TextureFormat,
 };
 use winit::{event_loop::ActiveEventLoop, window::Window};
 
@@ -42,6 +42,7 @@ pub(crate) fn create_vello_renderer(
             format: surface.config.format,
             width: surface.config.width,
             height: surface.config.height,
+            output_color: OutputColorConfig::default(),
         },
     )
 }
@@ -188,13 +189,11 @@ impl RenderContext {
             wgpu::util::initialize_adapter_from_env_or_default(&self.instance, compatible_surface)
                 .await
                 .ok()?;
-        let limits = Limits::default();
-
         let (device, queue) = adapter
             .request_device(&wgpu::DeviceDescriptor {
                 label: None,
                 required_features: Features::empty(),
-                required_limits: limits,
+                required_limits: Renderer::required_limits(&adapter),
                 ..Default::default()
             })
             .await
@@ -32,6 +32,7 @@ use hashbrown::HashMap;
 use vello_common::coarse::{WideTile, WideTilesBbox};
 use vello_common::encode::{EncodedImage, EncodedPaint};
 use vello_common::filter::PreparedFilter;
+use vello_common::filter::color_matrix::ColorMatrix;
 use vello_common::filter::drop_shadow::DropShadow;
 use vello_common::filter::flood::Flood;
 use vello_common::filter::gaussian_blur::{DecimationSizer, GaussianBlur, MAX_KERNEL_SIZE};
@@ -40,6 +41,7 @@ use vello_common::filter_effects::EdgeMode;
 use vello_common::kurbo::{Affine, Vec2};
 use vello_common::paint::{ImageId, ImageSource};
 use vello_common::peniko::{ImageQuality, ImageSampler};
+use vello_common::quality::Quality;
 use vello_common::render_graph::{LayerId, RenderGraph, RenderNodeKind};
 use vello_common::tile::Tile;
 
@@ -61,7 +63,7 @@ const FILTER_ATLAS_PADDING: u16 = MAX_KERNEL_SIZE as u16 / 2;
 
 // Since we store in RGBA32 texture.
 const BYTES_PER_TEXEL: usize = 16;
-const FILTER_SIZE_BYTES: usize = 48;
+const FILTER_SIZE_BYTES: usize = 96;
 const FILTER_SIZE_U32: usize = FILTER_SIZE_BYTES / 4;
 
 const _: () = assert!(
@@ -84,12 +86,17 @@ const _: () = assert!(
     size_of::<GpuGaussianBlur>() == FILTER_SIZE_BYTES,
     "memory size of filters need to match"
 );
+const _: () = assert!(
+    size_of::<GpuColorMatrix>() == FILTER_SIZE_BYTES,
+    "memory size of filters need to match"
+);
 
 pub(crate) mod filter_type {
     pub(crate) const OFFSET: u32 = 0;
     pub(crate) const FLOOD: u32 = 1;
     pub(crate) const GAUSSIAN_BLUR: u32 = 2;
     pub(crate) const DROP_SHADOW: u32 = 3;
+    pub(crate) const COLOR_MATRIX: u32 = 4;
 }
 
 pub(crate) mod edge_mode {
@@ -109,6 +116,7 @@ pub(crate) mod pass_kind {
     pub(crate) const BLUR_V: u32 = 5;
     pub(crate) const UPSCALE: u32 = 6;
     pub(crate) const COMPOSITE_DROP_SHADOW: u32 = 7;
+    pub(crate) const COLOR_MATRIX: u32 = 8;
 }
 
 pub(crate) fn edge_mode_to_gpu(mode: EdgeMode) -> u32 {
@@ -220,7 +228,7 @@ pub(crate) struct GpuOffset {
     pub header: u32,
     pub dx: f32,
     pub dy: f32,
-    pub _padding: [u32; 9],
+    pub _padding: [u32; 21],
 }
 
 impl From<&Offset> for GpuOffset {
@@ -229,7 +237,7 @@ impl From<&Offset> for GpuOffset {
             header: pack_header(filter_type::OFFSET),
             dx: offset.dx,
             dy: offset.dy,
-            _padding: [0; 9],
+            _padding: [0; 21],
         }
     }
 }
@@ -239,7 +247,7 @@ impl From<&Offset> for GpuOffset {
 pub(crate) struct GpuFlood {
     pub header: u32,
     pub color: u32,
-    pub _padding: [u32; 10],
+    pub _padding: [u32; 22],
 }
 
 impl From<&Flood> for GpuFlood {
@@ -247,7 +255,7 @@ impl From<&Flood> for GpuFlood {
         Self {
             header: pack_header(filter_type::FLOOD),
             color: flood.color.premultiply().to_rgba8().to_u32(),
-            _padding: [0; 10],
+            _padding: [0; 22],
         }
     }
 }
@@ -259,8 +267,8 @@ pub(crate) struct GpuGaussianBlur {
     pub center_weight: f32,
     pub linear_weights: [f32; MAX_TAPS_PER_SIDE],
     pub linear_offsets: [f32; MAX_TAPS_PER_SIDE],
-    // Needed since drop shadow has a bigger footprint.
-    pub _padding: [u32; 4],
+    // Needed since the color matrix has a bigger footprint.
+    pub _padding: [u32; 16],
 }
 
 impl From<&GaussianBlur> for GpuGaussianBlur {
@@ -284,7 +292,7 @@ impl From<&GaussianBlur> for GpuGaussianBlur {
             center_weight: lk.center_weight,
             linear_weights: lk.weights,
             linear_offsets: lk.offsets,
-            _padding: [0; 4],
+            _padding: [0; 16],
         }
     }
 }
@@ -299,7 +307,7 @@ pub(crate) struct GpuDropShadow {
     pub dx: f32,
     pub dy: f32,
     pub color: u32,
-    pub _padding: [u32; 1],
+    pub _padding: [u32; 13],
 }
 
 impl From<&DropShadow> for GpuDropShadow {
@@ -322,7 +330,29 @@ impl From<&DropShadow> for GpuDropShadow {
             dx: shadow.dx,
             dy: shadow.dy,
             color: shadow.color.premultiply().to_rgba8().to_u32(),
-            _padding: [0; 1],
+            _padding: [0; 13],
+        }
+    }
+}
+
+#[repr(C, align(16))]
+#[derive(Debug, Clone, Copy, PartialEq, Zeroable, Pod)]
+pub(crate) struct GpuColorMatrix {
+    pub header: u32,
+    pub _padding: [u32; 3],
+    // Stored column by column, so that each column (and the offsets) occupies one texel.
+    pub columns: [[f32; 4]; 5],
+}
+
+impl From<&ColorMatrix> for GpuColorMatrix {
+    fn from(color_matrix: &ColorMatrix) -> Self {
+        let m = &color_matrix.matrix;
+        let columns = core::array::from_fn(|col| [m[col], m[5 + col], m[10 + col], m[15 + col]]);
+
+        Self {
+            header: pack_header(filter_type::COLOR_MATRIX),
+            _padding: [0; 3],
+            columns,
         }
     }
 }
@@ -364,6 +394,7 @@ impl CastToFilterData for GpuOffset {}
 impl CastToFilterData for GpuFlood {}
 impl CastToFilterData for GpuGaussianBlur {}
 impl CastToFilterData for GpuDropShadow {}
+impl CastToFilterData for GpuColorMatrix {}
 
 impl<T: CastToFilterData> From<T> for GpuFilterData {
     fn from(filter: T) -> Self {
@@ -378,6 +409,7 @@ impl From<&PreparedFilter> for GpuFilterData {
             PreparedFilter::Flood(f) => GpuFlood::from(f).into(),
             PreparedFilter::GaussianBlur(f) => GpuGaussianBlur::from(f).into(),
             PreparedFilter::DropShadow(f) => GpuDropShadow::from(f).into(),
+            PreparedFilter::ColorMatrix(f) => GpuColorMatrix::from(f).into(),
         }
     }
 }
@@ -745,6 +777,7 @@ impl FilterContext {
         render_graph: &RenderGraph,
         dest_cache: &mut ImageCache,
         encoded_paints: &mut Vec<EncodedPaint>,
+        quality: Quality,
     ) -> Result<(), AtlasError> {
         if !render_graph.has_filters() {
             return Ok(());
@@ -765,12 +798,19 @@ impl FilterContext {
                 filter,
                 transform,
                 wtile_bbox,
+            }
+            | RenderNodeKind::BackdropLayer {
+                layer_id,
+                filter,
+                transform,
+                wtile_bbox,
+                ..
             } = &node.kind
             {
                 let width = wtile_bbox.width_px() as u32;
                 let height = wtile_bbox.height_px() as u32;
 
-                let instantiated = PreparedFilter::new(filter, transform);
+                let instantiated = PreparedFilter::new(filter, transform, quality);
                 let gpu_filter = GpuFilterData::from(&instantiated);
                 let is_multi_pass = gpu_filter.is_multi_pass();
 
@@ -945,6 +985,7 @@ impl FilterContext {
             let pass = match filter_type {
                 filter_type::OFFSET => pass_kind::OFFSET,
                 filter_type::FLOOD => pass_kind::FLOOD,
+                filter_type::COLOR_MATRIX => pass_kind::COLOR_MATRIX,
                 // The above are the only single-pass filters currently implemented.
                 _ => unimplemented!(),
             };
@@ -955,7 +996,7 @@ impl FilterContext {
                     dest: IntRect::new(dest_image.offsets(), dest_image.size()),
                     dest_atlas_size: IntSize(main_atlas_size),
                     filter_data_offset,
-                    // Note that these two passes don't sample the original atlas, so we
+                    // Note that these passes don't sample the original atlas, so we
                     // can pass anything here.
                     original: IntRect::new([0, 0], dest_image.size()),
                     pass_kind: pass,
@@ -1114,6 +1155,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_color_matrix_round_trip() {
+        let gpu = GpuColorMatrix::from(&ColorMatrix::new(
+            vello_common::filter_effects::matrices::SEPIA,
+        ));
+        // The first column holds the red coefficients of each output channel.
+        assert_eq!(gpu.columns[0], [0.393, 0.349, 0.272, 0.0]);
+        check_round_trip(gpu, filter_type::COLOR_MATRIX);
+    }
+
     fn check_linear_kernel(kernel: &[f32; MAX_KERNEL_SIZE], size: u8, expected_taps: u8) {
         let lk = LinearKernel::new(kernel, size);
         assert_eq!(lk.n_taps, expected_taps);
@@ -5,7 +5,7 @@
 
 use alloc::vec::Vec;
 use hashbrown::HashMap;
-use vello_common::encode::{EncodedGradient, GradientCacheKey};
+use vello_common::encode::{EncodedGradient, GradientCacheKey, GradientLutCache};
 use vello_common::fearless_simd::{Level, Simd, dispatch};
 use vello_common::peniko::color::cache_key::CacheKey;
 
@@ -28,6 +28,8 @@ pub(crate) struct GradientRampCache {
     retained_count: u32,
     /// SIMD level used for gradient LUT generation.
     level: Level,
+    /// Optional cache shared with other renderers that LUTs are resolved through.
+    lut_cache: Option<GradientLutCache>,
     /// Scratch space for maintaining the cache.
     scratch: ScratchSpace,
 }
@@ -63,10 +65,16 @@ impl GradientRampCache {
             has_changed: false,
             retained_count,
             level,
+            lut_cache: None,
             scratch: ScratchSpace::default(),
         }
     }
 
+    /// Set the shared cache that new gradient LUTs are resolved through.
+    pub(crate) fn set_lut_cache(&mut self, lut_cache: Option<GradientLutCache>) {
+        self.lut_cache = lut_cache;
+    }
+
     /// Get or generate a gradient ramp, returning its offset in the packed luts.
     #[allow(
         clippy::cast_possible_truncation,
@@ -83,7 +91,7 @@ impl GradientRampCache {
 
         // Generate new gradient LUT.
         let lut_start = self.luts.len() as u32 / BYTES_PER_TEXEL;
-        let width = dispatch!(self.level, simd => generate_gradient_lut_impl(simd, gradient, &mut self.luts))
+        let width = dispatch!(self.level, simd => generate_gradient_lut_impl(simd, gradient, self.lut_cache.as_ref(), &mut self.luts))
             as u32;
         let cached_ramp = CachedRamp { width, lut_start };
         self.has_changed = true;
@@ -276,9 +284,17 @@ pub(crate) struct CachedRamp {
 fn generate_gradient_lut_impl<S: Simd>(
     simd: S,
     gradient: &EncodedGradient,
+    lut_cache: Option<&GradientLutCache>,
     output: &mut Vec<u8>,
 ) -> usize {
-    let lut = gradient.u8_lut(simd);
+    let shared;
+    let lut = match lut_cache {
+        Some(lut_cache) => {
+            shared = lut_cache.u8_lut(simd, gradient);
+            &*shared
+        }
+        None => gradient.u8_lut(simd),
+    };
     let bytes: &[u8] = bytemuck::cast_slice(lut.lut());
     output.reserve(bytes.len());
     output.extend_from_slice(bytes);
@@ -358,6 +374,22 @@ mod tests {
         assert!(!cache.has_changed());
     }
 
+    #[test]
+    fn test_shared_lut_cache() {
+        let shared = GradientLutCache::new();
+        let mut first = GradientRampCache::new(5, Level::baseline());
+        let mut second = GradientRampCache::new(5, Level::baseline());
+        first.set_lut_cache(Some(shared.clone()));
+        second.set_lut_cache(Some(shared.clone()));
+
+        insert_entries(&mut first, 3);
+        insert_entries(&mut second, 3);
+
+        // Both ramp caches resolved their LUTs through the same shared entries.
+        assert_eq!(shared.len(), 3);
+        assert_eq!(first.luts, second.luts);
+    }
+
     #[test]
     fn test_unique_entry_creation() {
         let mut cache = GradientRampCache::new(5, Level::baseline());
@@ -25,6 +25,11 @@
 //! - `wgpu` (enabled by default): Enables the GPU rendering backend via wgpu and includes the required sparse shaders.
 //! - `wgpu_default` (enabled by default): Enables wgpu with its default hardware backends (such as Vulkan, Metal, and DX12).
 //! - `text` (enabled by default): Enables glyph rendering ([`Scene::glyph_run`]).
+//! - `hot_reload`: Enables rebuilding the renderer's pipelines from shaders and tuning parameters on disk
+//!   while the application is running ([`Renderer::reload_shaders`]). This is meant for development.
+//! - `multithreading`: Enables generating the strips of batches of paths on multiple threads
+//!   ([`Scene::fill_paths`], [`Scene::stroke_paths`]), either on a thread pool of the scene or
+//!   on an [`Executor`] passed to [`Scene::new_with_executor`].
 //! - `webgl`: Enables the WebGL rendering backend for browser support, using GLSL shaders for compatibility.
 //!
 //! If you need to customize the set of enabled wgpu features, disable this crate's default features then enable its `wgpu` feature.
@@ -37,43 +42,88 @@
 //!
 //! - `Scene`: Manages the render context and path processing on the CPU
 //! - `Renderer` or `WebGlRenderer`: Handles GPU resource management and executes draw operations
+//! - [`backend::BackendRenderer`]: Lets other GPU backends, like OpenGL through `glow`, reuse all
+//!   CPU-side work by implementing [`backend::GpuBackend`]
 //! - `Scheduler`: Manages and schedules draw operations on the renderer.
 //!
 //! See the individual module documentation for more details on usage and implementation.
+//!
+//! # Threads
+//!
+//! `Scene` and `Resources` are [`Send`], so scenes can be built on worker threads and sent to
+//! the thread that renders them. A scene keeps its encoded paints and strips in `RefCell`s,
+//! which renderers borrow while rendering it, so a scene isn't `Sync` and can't be shared
+//! between threads by reference.
+//!
+//! The renderers own the GPU resources of a device or a WebGL context and must stay on the
+//! render thread. `WebGlRenderer` isn't `Send`, because WebGL contexts are tied to the thread
+//! that created them. `Renderer` is `Send` if the types of wgpu are, which is not the case on
+//! the web by default.
 
 #![no_std]
 
 extern crate alloc;
+#[cfg(feature = "hot_reload")]
+extern crate std;
 
 pub(crate) mod filter;
 mod gradient_cache;
+mod pattern;
+mod recording;
 mod render;
 mod resources;
 mod sampling;
 mod scene;
-#[cfg(any(all(target_arch = "wasm32", feature = "webgl"), feature = "wgpu"))]
 mod schedule;
 #[cfg(feature = "text")]
 mod text;
 
 pub mod util;
 
+#[cfg(feature = "text")]
+pub use glifo::GlyphCacheConfig;
+pub use render::backend;
 #[cfg(feature = "wgpu")]
-pub use render::{AtlasWriter, RenderTargetConfig, Renderer, TextureBindings};
-pub use render::{Config, GpuStrip, RenderSize};
+pub use render::{
+    AtlasWriter, FrameProfile, ProfileStage, Readback, ReadbackImage, RenderTargetConfig, Renderer,
+    SurfaceTarget, TextureBindings, Viewport,
+};
+pub use render::{Config, DynamicRange, GpuStrip, OutputColorConfig, RenderSize, WorkingSpace};
+#[cfg(feature = "hot_reload")]
+pub use render::{HotReloadError, ShaderSet, ShaderWatcher, parse_parameters};
 #[cfg(all(target_arch = "wasm32", feature = "webgl", feature = "probe"))]
 pub use render::{Probe, ProbeResult};
 #[cfg(all(target_arch = "wasm32", feature = "webgl"))]
 pub use render::{WebGlAtlasWriter, WebGlRenderer, WebGlTextureWithDimensions};
 pub use resources::Resources;
-pub use sampling::SampleRect;
+pub use sampling::{SampleRect, TexturePaint};
 pub use scene::{RenderSettings, Scene, SceneConstraints};
 #[cfg(feature = "text")]
 pub use text::{GlyphRunBuilder, HybridGlyphRunBackend};
 pub use util::DimensionConstraints;
 pub use vello_common::TextureId;
+pub use vello_common::alpha_mode::AlphaMode;
+pub use vello_common::custom_paint::{CustomPaint, CustomPaintId, CustomPaintKind};
+pub use vello_common::encode::GradientLutCache;
+#[cfg(feature = "multithreading")]
+pub use vello_common::executor::{Executor, Task};
+pub use vello_common::fragment::{Fragment, FragmentInstance};
+pub use vello_common::load_op::LoadOp;
+pub use vello_common::mask::MaskKind;
 pub use vello_common::multi_atlas::{AllocationStrategy, AtlasConfig, AtlasId};
+pub use vello_common::pattern::Pattern;
 pub use vello_common::pixmap::Pixmap;
+pub use vello_common::quality::Quality;
+pub use vello_common::recording::Recording;
+pub use vello_common::render_stats::RenderStats;
+pub use vello_common::strip_debug::{StripDebugStats, StripDebugView, StripDebugger};
+
+// Scenes are built on worker threads and sent to the render thread, see the crate documentation.
+const _: () = {
+    const fn assert_send<T: Send>() {}
+    assert_send::<Scene>();
+    assert_send::<Resources>();
+};
 
 use thiserror::Error;
 
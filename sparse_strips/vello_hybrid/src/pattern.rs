@@ -0,0 +1,142 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Pattern support for the hybrid renderer.
+//!
+//! A pattern cell is rasterized once into its own region of the image atlas by replaying
+//! the recorded commands into an atlas-sized [`Scene`]. Afterwards, the region behaves like
+//! any other uploaded image and can be tiled with a repeating image paint.
+//!
+//! Cells that are painted with images can't be rendered into the atlas directly, since the
+//! atlas would have to be sampled while it is being rendered into. Those cells are instead
+//! rendered into a separate texture the size of the cell, which is then copied into the
+//! allocated region.
+
+use crate::Scene;
+#[cfg(any(all(target_arch = "wasm32", feature = "webgl"), feature = "wgpu"))]
+use crate::render::common::IMAGE_PADDING;
+#[cfg(any(all(target_arch = "wasm32", feature = "webgl"), feature = "wgpu"))]
+use crate::{RenderError, Resources};
+#[cfg(any(all(target_arch = "wasm32", feature = "webgl"), feature = "wgpu"))]
+use vello_common::kurbo::Vec2;
+use vello_common::kurbo::{Affine, BezPath, Rect, Stroke};
+#[cfg(any(all(target_arch = "wasm32", feature = "webgl"), feature = "wgpu"))]
+use vello_common::multi_atlas::{AtlasConfig, AtlasId};
+#[cfg(any(all(target_arch = "wasm32", feature = "webgl"), feature = "wgpu"))]
+use vello_common::paint::ImageId;
+use vello_common::paint::PaintType;
+#[cfg(any(all(target_arch = "wasm32", feature = "webgl"), feature = "wgpu"))]
+use vello_common::pattern::Pattern;
+use vello_common::pattern::PatternSink;
+use vello_common::peniko::Fill;
+
+impl PatternSink for Scene {
+    #[inline]
+    fn set_transform(&mut self, transform: Affine) {
+        Self::set_transform(self, transform);
+    }
+
+    #[inline]
+    fn set_paint(&mut self, paint: PaintType) {
+        Self::set_paint(self, paint);
+    }
+
+    #[inline]
+    fn set_paint_transform(&mut self, paint_transform: Affine) {
+        Self::set_paint_transform(self, paint_transform);
+    }
+
+    #[inline]
+    fn set_fill_rule(&mut self, fill_rule: Fill) {
+        Self::set_fill_rule(self, fill_rule);
+    }
+
+    #[inline]
+    fn set_stroke(&mut self, stroke: Stroke) {
+        Self::set_stroke(self, stroke);
+    }
+
+    #[inline]
+    fn fill_path(&mut self, path: &BezPath) {
+        Self::fill_path(self, path);
+    }
+
+    #[inline]
+    fn stroke_path(&mut self, path: &BezPath) {
+        Self::stroke_path(self, path);
+    }
+
+    #[inline]
+    fn fill_rect(&mut self, rect: &Rect) {
+        Self::fill_rect(self, rect);
+    }
+
+    #[inline]
+    fn push_clip_path(&mut self, path: &BezPath) {
+        Self::push_clip_path(self, path);
+    }
+
+    #[inline]
+    fn pop_clip_path(&mut self) {
+        Self::pop_clip_path(self);
+    }
+}
+
+/// An allocated atlas region together with the scene that rasterizes a pattern cell into it.
+#[cfg(any(all(target_arch = "wasm32", feature = "webgl"), feature = "wgpu"))]
+#[derive(Debug)]
+pub(crate) struct PatternUpload {
+    pub(crate) image_id: ImageId,
+    /// Whether the scene samples images, in which case it's sized to the cell instead of the
+    /// atlas and has to be rendered into a separate texture first.
+    pub(crate) samples_atlas: bool,
+    pub(crate) atlas_id: AtlasId,
+    pub(crate) atlas_count: u32,
+    pub(crate) atlas_config: AtlasConfig,
+    pub(crate) scene: Scene,
+}
+
+#[cfg(any(all(target_arch = "wasm32", feature = "webgl"), feature = "wgpu"))]
+impl Resources {
+    /// Allocate an atlas region for a single cell of `pattern` and record the scene that
+    /// draws the cell.
+    pub(crate) fn prepare_pattern_upload(
+        &mut self,
+        pattern: &Pattern,
+    ) -> Result<PatternUpload, RenderError> {
+        let (width, height) = pattern.cell_size();
+        let image_id =
+            self.image_cache
+                .allocate(u32::from(width), u32::from(height), IMAGE_PADDING)?;
+        let resource = self.image_cache.get(image_id).unwrap();
+        let atlas_config = *self.image_cache.atlas_manager().config();
+
+        let samples_atlas = pattern.uses_images();
+        let scene = if samples_atlas {
+            let mut scene = Scene::new(width, height);
+            pattern.replay(&mut scene, Vec2::ZERO);
+            scene
+        } else {
+            #[expect(
+                clippy::cast_possible_truncation,
+                reason = "atlas dimensions are configured to fit in u16"
+            )]
+            let mut scene = Scene::new(
+                atlas_config.atlas_size.0 as u16,
+                atlas_config.atlas_size.1 as u16,
+            );
+            let origin = Vec2::new(f64::from(resource.offset[0]), f64::from(resource.offset[1]));
+            pattern.replay(&mut scene, origin);
+            scene
+        };
+
+        Ok(PatternUpload {
+            image_id,
+            samples_atlas,
+            atlas_id: resource.atlas_id,
+            atlas_count: u32::try_from(self.image_cache.atlas_count()).unwrap(),
+            atlas_config,
+            scene,
+        })
+    }
+}
@@ -0,0 +1,1149 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! An interface for GPU backends that aren't built into this crate.
+//!
+//! The built-in renderers drive `wgpu` and WebGL2. Applications that embed into a graphics
+//! context that neither of them can use, like an existing OpenGL context of a UI toolkit
+//! driven through `glow`, can implement [`GpuBackend`] for it and render with a
+//! [`BackendRenderer`]. The renderer does all the work on the CPU that the built-in renderers
+//! do: it schedules the strips of a scene into passes, splits its filter effects into passes,
+//! encodes the paints and maintains the gradient ramps. The backend only uploads the
+//! resulting data and issues the draws.
+//!
+//! The data is laid out the way the shaders of `vello_sparse_shaders` expect it, so a backend
+//! can use them as they are. The GLSL ES 3.0 versions that its `glsl` feature generates are
+//! the ones the WebGL2 renderer uses, which makes that renderer a reference for how the data
+//! is bound.
+
+#![expect(
+    clippy::cast_possible_truncation,
+    reason = "Atlas counts and the sizes of GPU data are bounded by the texture limits of the backend"
+)]
+
+use alloc::vec::Vec;
+
+use crate::filter::{FilterContext, FilterPassState, FilterPassTarget};
+use crate::gradient_cache::GradientRampCache;
+use crate::render::common::{
+    GpuEncodedPaint, IMAGE_PADDING, OUTPUT_TRANSFER_NONE, encode_external_texture_paint,
+    encode_gpu_paints, linear_texture_extent, normalize_atlas_config,
+};
+use crate::render::{Config, GpuStrip, RenderSize};
+use crate::schedule::{
+    ExternalTextureRun, LoadOp, RendererBackend, RootRenderTarget, Scheduler, SchedulerState,
+    StripPassRenderTarget,
+};
+use crate::{RenderError, RenderSettings, Resources, Scene};
+use vello_common::TextureId;
+use vello_common::coarse::WideTile;
+use vello_common::encode::{EncodedPaint, GradientLutCache, MAX_GRADIENT_LUT_SIZE};
+use vello_common::image_cache::ImageCache;
+use vello_common::load_op::LoadOp as TargetLoadOp;
+#[cfg(feature = "text")]
+use vello_common::multi_atlas::{AtlasConfig, AtlasId};
+use vello_common::paint::ImageId;
+use vello_common::pixmap::Pixmap;
+use vello_common::render_graph::LayerId;
+use vello_common::tile::Tile;
+
+/// The limits and conventions of a [`GpuBackend`].
+#[derive(Clone, Copy, Debug)]
+pub struct BackendConfig {
+    /// The largest width and height of the 2D textures the backend creates, like
+    /// `GL_MAX_TEXTURE_SIZE`. This has to be a power of two.
+    ///
+    /// The alpha, encoded paints and gradient textures are this wide, and the slot textures
+    /// are this high.
+    pub max_texture_dimension_2d: u32,
+    /// The largest number of layers of texture arrays, like `GL_MAX_ARRAY_TEXTURE_LAYERS`.
+    ///
+    /// This limits the layers of the atlas, and of the alpha, encoded paints and gradient
+    /// textures once their data doesn't fit into a single layer.
+    pub max_texture_array_layers: u32,
+    /// Whether the origin of the render target is in its bottom left corner, like the default
+    /// framebuffer of OpenGL.
+    pub y_up: bool,
+}
+
+/// The data that the draws of a scene read, see [`GpuBackend::upload`].
+///
+/// The alpha, encoded paints and gradient textures are texture arrays, whose rows are indexed
+/// linearly: once a layer has [`max_texture_dimension_2d`](BackendConfig::max_texture_dimension_2d)
+/// rows, the next row starts a new layer. The data of each texture is padded to whole rows of
+/// `max_texture_dimension_2d` texels, and to whole layers if it needs more than one. So a
+/// texture with `rows` rows of data has `rows.min(max_texture_dimension_2d)` rows in each of
+/// its `rows.div_ceil(max_texture_dimension_2d)` layers.
+#[derive(Debug)]
+pub struct FrameData<'a> {
+    /// The size of the render target.
+    pub render_size: RenderSize,
+    /// The configuration of draws into the render target or an atlas layer.
+    pub view_config: Config,
+    /// The configuration of draws into the slot textures.
+    pub slot_config: Config,
+    /// The configuration of draws into a filter atlas, see [`StripTarget::FilterAtlas`].
+    pub filter_config: Config,
+    /// The premultiplied color to clear the render target to before drawing, if any.
+    pub clear_color: Option<[f32; 4]>,
+    /// Whether the render target stays opaque, in which case blended draws into it don't need
+    /// to write the alpha channel.
+    pub opaque_target: bool,
+    /// The coverage of the strips, for an `RGBA32UI` texture.
+    pub alphas: &'a [u8],
+    /// The encoded paints, for an `RGBA32UI` texture.
+    pub encoded_paints: &'a [u8],
+    /// The gradient ramps, for an `RGBA8` texture, if they changed since the previous upload.
+    pub gradient_ramps: Option<&'a [u8]>,
+    /// The parameters of the filter effects, for an `RGBA32UI` texture with a single layer,
+    /// which is empty if the scene has none.
+    pub filter_data: &'a [u8],
+}
+
+/// The target of a [`StripPass`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StripTarget {
+    /// The render target, drawn with [`FrameData::view_config`].
+    View,
+    /// A layer of the image atlas, drawn with [`FrameData::view_config`].
+    ///
+    /// Glyphs are rendered into the atlas this way. The atlas can't be sampled while it's
+    /// rendered to, so the backend has to bind a placeholder instead.
+    AtlasLayer(u32),
+    /// One of the two slot textures, drawn with [`FrameData::slot_config`].
+    ///
+    /// Draws into a slot texture read clips from the other one, while draws into any other
+    /// target read them from the second one.
+    Slot(usize),
+    /// A region of one of the filter atlases, drawn like [`StripTarget::AtlasLayer`].
+    ///
+    /// The content of a layer with a filter effect is drawn this way, before
+    /// [`GpuBackend::draw_filter_pass`] applies the effect. The draws use
+    /// [`FrameData::filter_config`] with `strip_offset` as its `strip_offset_x` and
+    /// `strip_offset_y`, and are limited to the `[x, y, width, height]` of `scissor`.
+    FilterAtlas {
+        /// The index of the filter atlas.
+        atlas: u32,
+        /// The offset of the strips within the filter atlas.
+        strip_offset: [i32; 2],
+        /// The region that the strips are drawn to.
+        scissor: [u32; 4],
+    },
+}
+
+/// A batch of strips to draw, see [`GpuBackend::draw_strips`].
+#[derive(Debug)]
+pub struct StripPass<'a> {
+    /// Where the strips are drawn to.
+    pub target: StripTarget,
+    /// Whether the target should be cleared to transparent before drawing.
+    pub clear: bool,
+    /// Opaque strips in front-to-back order, which only passes into [`StripTarget::View`] have.
+    ///
+    /// They are drawn first, with the depth test (`LEQUAL`) and depth writes enabled and
+    /// blending disabled.
+    pub opaque_strips: &'a [GpuStrip],
+    /// Strips in back-to-front order, drawn with premultiplied alpha blending.
+    ///
+    /// In passes into [`StripTarget::View`], they are drawn with the depth test enabled, but
+    /// without depth writes. Other passes don't use the depth buffer.
+    pub alpha_strips: &'a [GpuStrip],
+    /// The runs of alpha strips that sample an external texture, in order.
+    ///
+    /// If there are any, the first one starts at the first alpha strip, and each strip is drawn
+    /// with the texture of its run bound as the `external_texture` of the shader.
+    pub texture_runs: &'a [TextureRun],
+}
+
+/// A run of alpha strips that sample the same external texture, see [`StripPass::texture_runs`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TextureRun {
+    /// The texture that the strips of the run sample.
+    pub texture_id: TextureId,
+    /// The index of the first alpha strip of the run. The run ends where the next one starts,
+    /// or with the last alpha strip.
+    pub strips_start: usize,
+}
+
+/// Where a [`FilterPass`] writes to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterTarget {
+    /// One of the filter atlases, holding intermediate results of the effect.
+    FilterAtlas(u32),
+    /// A layer of the image atlas, where the filtered layer is composited from.
+    AtlasLayer(u32),
+}
+
+/// A pass of a filter effect, see [`GpuBackend::draw_filter_pass`].
+#[derive(Debug)]
+pub struct FilterPass<'a> {
+    /// The filter atlas that the pass reads.
+    pub input_atlas: u32,
+    /// The filter atlas with the unfiltered content of the layer, for the passes that need it.
+    pub original_atlas: Option<u32>,
+    /// Where the pass writes to.
+    pub target: FilterTarget,
+    /// The `[x, y, width, height]` region of the target that the pass is limited to.
+    pub scissor: [u32; 4],
+    /// The single instance of the pass, laid out as the instance data of the `filters` shader.
+    pub instance: &'a [u8],
+}
+
+/// A GPU backend that a [`BackendRenderer`] renders with.
+///
+/// See the [module documentation](self) for details.
+pub trait GpuBackend {
+    /// Make sure that the atlas texture array has at least `count` layers, keeping the content
+    /// of the existing ones.
+    fn ensure_atlas_layers(&mut self, count: u32);
+
+    /// Write `pixmap` into `atlas_layer` of the atlas, with its top left corner at `offset`.
+    fn write_to_atlas(&mut self, atlas_layer: u32, offset: [u32; 2], pixmap: &Pixmap);
+
+    /// Clear a region of `atlas_layer` of the atlas to transparent.
+    fn clear_atlas_region(&mut self, atlas_layer: u32, offset: [u32; 2], width: u32, height: u32);
+
+    /// Make sure that there are at least `count` filter atlases.
+    ///
+    /// Filter atlases are `RGBA8` textures of the size of the image atlas layers, which hold
+    /// the content of layers with filter effects while they are applied. Their content doesn't
+    /// need to be kept.
+    fn ensure_filter_atlases(&mut self, count: u32);
+
+    /// Clear all filter atlases to transparent.
+    fn clear_filter_atlases(&mut self);
+
+    /// Whether `texture_id` refers to a texture that the backend can bind for
+    /// [`StripPass::texture_runs`].
+    ///
+    /// Scenes that draw other textures fail with [`RenderError::MissingTextureBinding`].
+    fn has_texture(&self, texture_id: TextureId) -> bool;
+
+    /// Upload the data that the following passes read, and clear the render target if
+    /// requested.
+    ///
+    /// The depth buffer of the render target should be cleared to 1.0 before the first pass
+    /// into it.
+    fn upload(&mut self, frame: &FrameData<'_>);
+
+    /// Clear `slots` of one of the two slot textures to transparent.
+    ///
+    /// Each slot is a [`WideTile::WIDTH`] by [`Tile::HEIGHT`] region, and slot `i` starts at
+    /// row `i * Tile::HEIGHT`.
+    fn clear_slots(&mut self, slot_texture: usize, slots: &[u32]);
+
+    /// Draw a batch of strips, with 4 vertices per instance.
+    fn draw_strips(&mut self, pass: &StripPass<'_>);
+
+    /// Draw a pass of a filter effect, with 4 vertices and a single instance.
+    ///
+    /// The pass samples its input atlas with a linear sampler and reads the filter data
+    /// uploaded with [`FrameData::filter_data`]. Its output is written without blending.
+    fn draw_filter_pass(&mut self, pass: &FilterPass<'_>);
+}
+
+/// Renders scenes with a [`GpuBackend`].
+///
+/// See the [module documentation](self) for details.
+#[derive(Debug)]
+pub struct BackendRenderer {
+    config: BackendConfig,
+    /// Scheduler for scheduling draws.
+    scheduler: Scheduler,
+    /// The state used by the scheduler.
+    scheduler_state: SchedulerState,
+    /// Encoded paints for storing encoded paints.
+    encoded_paints: Vec<GpuEncodedPaint>,
+    /// Stores the index (offset) of the encoded paints in the encoded paints texture.
+    paint_idxs: Vec<u32>,
+    /// Gradient cache for storing gradient ramps.
+    gradient_cache: GradientRampCache,
+    /// Filter context for the filter effects of the current scene.
+    filter_context: FilterContext,
+    /// The passes of the filter effect that is applied next.
+    filter_pass_state: FilterPassState,
+    /// The encoded paints, serialized for uploading.
+    encoded_paints_data: Vec<u8>,
+    /// The filter data, serialized for uploading.
+    filter_data: Vec<u8>,
+    /// The external texture runs of the current pass.
+    texture_runs: Vec<TextureRun>,
+    #[cfg(feature = "text")]
+    dummy_image_cache: Option<ImageCache>,
+}
+
+impl BackendRenderer {
+    /// Create a renderer for a backend with the given configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the maximum texture dimension isn't a power of two.
+    pub fn new(config: BackendConfig, settings: RenderSettings) -> Self {
+        assert!(
+            config.max_texture_dimension_2d.is_power_of_two(),
+            "The maximum texture dimension must be a power of two"
+        );
+
+        let mut settings = settings;
+        let max_texture_dimension_2d = config.max_texture_dimension_2d;
+        normalize_atlas_config(
+            &mut settings.atlas_config,
+            max_texture_dimension_2d,
+            config.max_texture_array_layers,
+            1,
+        );
+        let total_slots = (max_texture_dimension_2d / u32::from(Tile::HEIGHT)) as usize;
+        // Estimate the maximum number of gradient cache entries based on the max texture dimension
+        // and the maximum gradient LUT size - worst case scenario.
+        let max_gradient_cache_size =
+            max_texture_dimension_2d * max_texture_dimension_2d / MAX_GRADIENT_LUT_SIZE as u32;
+
+        Self {
+            config,
+            scheduler: Scheduler::new(total_slots),
+            scheduler_state: SchedulerState::default(),
+            encoded_paints: Vec::new(),
+            paint_idxs: Vec::new(),
+            gradient_cache: GradientRampCache::new(max_gradient_cache_size, settings.level),
+            filter_context: FilterContext::new(settings.atlas_config),
+            filter_pass_state: FilterPassState::default(),
+            encoded_paints_data: Vec::new(),
+            filter_data: Vec::new(),
+            texture_runs: Vec::new(),
+            #[cfg(feature = "text")]
+            dummy_image_cache: Some(ImageCache::new_dummy()),
+        }
+    }
+
+    /// Set the cache that gradient ramps are resolved through.
+    ///
+    /// Sharing a [`GradientLutCache`] with other renderers means that each gradient ramp is
+    /// only computed once.
+    pub fn set_gradient_lut_cache(&mut self, cache: Option<GradientLutCache>) {
+        self.gradient_cache.set_lut_cache(cache);
+    }
+
+    /// Render `scene` into the render target of `backend`.
+    ///
+    /// Returns [`RenderError::MissingTextureBinding`] if the scene draws a texture that
+    /// `backend` doesn't have.
+    pub fn render<B: GpuBackend>(
+        &mut self,
+        scene: &Scene,
+        resources: &mut Resources,
+        render_size: &RenderSize,
+        backend: &mut B,
+    ) -> Result<(), RenderError> {
+        #[cfg(feature = "text")]
+        {
+            let mut target = (&mut *self, &mut *backend);
+            resources.before_render(
+                &mut target,
+                |(renderer, backend), glyph_renderer, atlas_count, atlas_config, atlas_id| {
+                    renderer
+                        .render_to_atlas(
+                            glyph_renderer,
+                            atlas_count,
+                            atlas_config,
+                            atlas_id,
+                            &mut **backend,
+                        )
+                        .expect("Failed to render glyphs to atlas");
+                },
+                |(_, backend), image_cache, upload, dst_x, dst_y| {
+                    let resource = image_cache.get(upload.image_id).unwrap();
+                    backend.ensure_atlas_layers(image_cache.atlas_count() as u32);
+                    backend.write_to_atlas(
+                        resource.atlas_id.as_u32(),
+                        [dst_x, dst_y],
+                        &upload.pixmap,
+                    );
+                },
+            );
+        }
+
+        let mut encoded_paints = scene.encoded_paints.borrow_mut();
+        let scene_paint_count = encoded_paints.len();
+        self.prepare_filters(
+            scene,
+            &mut resources.image_cache,
+            &mut encoded_paints,
+            backend,
+        )?;
+
+        backend.ensure_atlas_layers(resources.image_cache.atlas_count() as u32);
+        let result = self.render_scene(
+            scene,
+            &resources.image_cache,
+            &encoded_paints,
+            render_size,
+            scene.target_load_op(),
+            RootRenderTarget::UserSurface,
+            backend,
+        );
+        encoded_paints.truncate(scene_paint_count);
+        drop(encoded_paints);
+
+        #[cfg(feature = "text")]
+        resources.after_render(backend, |backend, rect| {
+            let padding = u32::from(glifo::GLYPH_PADDING);
+            backend.clear_atlas_region(
+                rect.page_index,
+                [
+                    u32::from(rect.x).saturating_sub(padding),
+                    u32::from(rect.y).saturating_sub(padding),
+                ],
+                u32::from(rect.width) + padding * 2,
+                u32::from(rect.height) + padding * 2,
+            );
+        });
+
+        result
+    }
+
+    /// Render a glyph scene into an atlas layer.
+    #[cfg(feature = "text")]
+    fn render_to_atlas<B: GpuBackend>(
+        &mut self,
+        scene: &Scene,
+        atlas_count: u32,
+        atlas_config: AtlasConfig,
+        atlas_id: AtlasId,
+        backend: &mut B,
+    ) -> Result<(), RenderError> {
+        backend.ensure_atlas_layers(atlas_count);
+
+        let (width, height) = atlas_config.atlas_size;
+        let dummy_image_cache = self
+            .dummy_image_cache
+            .take()
+            .expect("dummy image cache must exist");
+        let result = self.render_scene(
+            scene,
+            &dummy_image_cache,
+            &scene.encoded_paints.borrow(),
+            &RenderSize { width, height },
+            TargetLoadOp::Load,
+            RootRenderTarget::AtlasLayer,
+            &mut AtlasLayerBackend {
+                backend,
+                atlas_layer: atlas_id.as_u32(),
+            },
+        );
+        self.dummy_image_cache = Some(dummy_image_cache);
+
+        result
+    }
+
+    /// Upload an image into the atlas and return its `ImageId`.
+    pub fn upload_image<B: GpuBackend>(
+        &mut self,
+        resources: &mut Resources,
+        pixmap: &Pixmap,
+        backend: &mut B,
+    ) -> ImageId {
+        let image_cache = &mut resources.image_cache;
+        let image_id = image_cache
+            .allocate(
+                u32::from(pixmap.width()),
+                u32::from(pixmap.height()),
+                IMAGE_PADDING,
+            )
+            .unwrap();
+        let resource = image_cache.get(image_id).unwrap();
+        backend.ensure_atlas_layers(image_cache.atlas_count() as u32);
+        backend.write_to_atlas(
+            resource.atlas_id.as_u32(),
+            [u32::from(resource.offset[0]), u32::from(resource.offset[1])],
+            pixmap,
+        );
+
+        image_id
+    }
+
+    /// Remove an image from the atlas and clear the region it occupied.
+    pub fn destroy_image<B: GpuBackend>(
+        &mut self,
+        resources: &mut Resources,
+        image_id: ImageId,
+        backend: &mut B,
+    ) {
+        let Some(image_resource) = resources.image_cache.deallocate(image_id) else {
+            return;
+        };
+        let mip_levels = image_resource.mip_levels().to_vec();
+        let mip_resources = mip_levels
+            .into_iter()
+            .filter_map(|id| resources.image_cache.deallocate(id));
+
+        for image_resource in core::iter::once(image_resource).chain(mip_resources) {
+            let padding = u32::from(image_resource.padding);
+            backend.clear_atlas_region(
+                image_resource.atlas_id.as_u32(),
+                [
+                    u32::from(image_resource.offset[0]) - padding,
+                    u32::from(image_resource.offset[1]) - padding,
+                ],
+                u32::from(image_resource.width) + padding * 2,
+                u32::from(image_resource.height) + padding * 2,
+            );
+        }
+    }
+
+    /// Allocate the intermediate textures of the filter effects of `scene`.
+    fn prepare_filters<B: GpuBackend>(
+        &mut self,
+        scene: &Scene,
+        image_cache: &mut ImageCache,
+        encoded_paints: &mut Vec<EncodedPaint>,
+        backend: &mut B,
+    ) -> Result<(), RenderError> {
+        // The filter atlases still hold the content of the previous scene.
+        if !self.filter_context.filter_textures.is_empty() {
+            backend.clear_filter_atlases();
+        }
+
+        self.filter_context
+            .deallocate_all_and_clear_context(image_cache);
+        self.filter_context.prepare(
+            &scene.render_graph,
+            image_cache,
+            encoded_paints,
+            scene.quality(),
+        )?;
+        backend.ensure_filter_atlases(self.filter_context.image_cache.atlas_count() as u32);
+
+        Ok(())
+    }
+
+    /// Encode and upload the data of `scene`, then run the scheduler against `backend`.
+    fn render_scene<B: GpuBackend>(
+        &mut self,
+        scene: &Scene,
+        image_cache: &ImageCache,
+        encoded_paints: &[EncodedPaint],
+        render_size: &RenderSize,
+        load_op: TargetLoadOp,
+        root_output_target: RootRenderTarget,
+        backend: &mut B,
+    ) -> Result<(), RenderError> {
+        encode_gpu_paints(
+            encoded_paints,
+            image_cache,
+            &mut self.gradient_cache,
+            &mut self.encoded_paints,
+            &mut self.paint_idxs,
+            |texture| {
+                if !backend.has_texture(texture.texture_id) {
+                    return Err(RenderError::MissingTextureBinding(texture.texture_id));
+                }
+                Ok(Some(encode_external_texture_paint(texture)))
+            },
+        )?;
+
+        let width = self.config.max_texture_dimension_2d;
+        // Each row has `width` texels of 16 bytes.
+        let row_size = width as usize * 16;
+        let paint_texels = *self.paint_idxs.last().unwrap() as usize;
+        self.encoded_paints_data.clear();
+        self.encoded_paints_data
+            .resize(self.padded_len(paint_texels * 16, row_size), 0);
+        GpuEncodedPaint::serialize_to_buffer(&self.encoded_paints, &mut self.encoded_paints_data);
+
+        self.filter_data.clear();
+        if !self.filter_context.is_empty() {
+            let filter_rows = self
+                .filter_context
+                .required_filter_data_height(width)
+                .unwrap();
+            self.filter_data.resize(filter_rows as usize * row_size, 0);
+            self.filter_context
+                .serialize_to_buffer(&mut self.filter_data);
+        }
+
+        let gradient_ramps_len = self.gradient_cache.luts_size();
+        let mut gradient_ramps = (self.gradient_cache.has_changed()
+            && !self.gradient_cache.is_empty())
+        .then(|| self.gradient_cache.take_luts());
+        if let Some(luts) = &mut gradient_ramps {
+            // Each row has `width` texels of 4 bytes.
+            luts.resize(self.padded_len(luts.len(), width as usize * 4), 0);
+        }
+
+        // TODO: For the time being, we upload the entire alpha buffer as one big chunk. As a future
+        // refinement, we could have a bounded alpha buffer, and break draws when the alpha
+        // buffer fills.
+        let mut strip_storage = scene.strip_storage.borrow_mut();
+        let alphas = &mut strip_storage.alphas;
+        let alphas_len = alphas.len();
+        // Temporarily pad the alphas to whole rows and layers before uploading.
+        alphas.resize(self.padded_len(alphas_len, row_size), 0);
+
+        let is_user_surface = matches!(root_output_target, RootRenderTarget::UserSurface);
+        let filter_atlas_size = self
+            .filter_context
+            .image_cache
+            .atlas_manager()
+            .config()
+            .atlas_size;
+        backend.upload(&FrameData {
+            render_size: render_size.clone(),
+            view_config: self.draw_config(render_size, is_user_surface && self.config.y_up),
+            slot_config: self.draw_config(
+                &RenderSize {
+                    width: u32::from(WideTile::WIDTH),
+                    height: width,
+                },
+                false,
+            ),
+            filter_config: self.draw_config(
+                &RenderSize {
+                    width: filter_atlas_size.0,
+                    height: filter_atlas_size.1,
+                },
+                false,
+            ),
+            clear_color: load_op
+                .clear_color()
+                .map(|color| color.as_premul_f32().components),
+            opaque_target: is_user_surface && scene.has_opaque_result(),
+            alphas: alphas.as_slice(),
+            encoded_paints: &self.encoded_paints_data,
+            gradient_ramps: gradient_ramps.as_deref(),
+            filter_data: &self.filter_data,
+        });
+
+        alphas.truncate(alphas_len);
+        drop(strip_storage);
+        if let Some(mut luts) = gradient_ramps {
+            luts.truncate(gradient_ramps_len);
+            self.gradient_cache.restore_luts(luts);
+            self.gradient_cache.mark_synced();
+        }
+
+        let result = self.scheduler.do_scene(
+            &mut self.scheduler_state,
+            &mut BackendContext {
+                backend,
+                image_cache,
+                filter_context: &self.filter_context,
+                filter_pass_state: &mut self.filter_pass_state,
+                texture_runs: &mut self.texture_runs,
+            },
+            scene,
+            root_output_target,
+            &self.paint_idxs,
+            &self.filter_context,
+            encoded_paints,
+        );
+        self.gradient_cache.maintain();
+
+        result
+    }
+
+    /// The length that `len` bytes of data for a linear texture with rows of `row_size` bytes
+    /// are padded to, see [`FrameData`].
+    fn padded_len(&self, len: usize, row_size: usize) -> usize {
+        if len == 0 {
+            return 0;
+        }
+
+        let rows = u32::try_from(len.div_ceil(row_size)).unwrap();
+        let (height, layers) = linear_texture_extent(
+            rows,
+            self.config.max_texture_dimension_2d,
+            self.config.max_texture_array_layers,
+            1,
+        );
+        (height * layers) as usize * row_size
+    }
+
+    /// The configuration of draws into a target of `size`.
+    fn draw_config(&self, size: &RenderSize, negate_ndc: bool) -> Config {
+        let width_bits = self.config.max_texture_dimension_2d.trailing_zeros();
+        Config {
+            width: size.width,
+            height: size.height,
+            strip_height: u32::from(Tile::HEIGHT),
+            alphas_tex_width_bits: width_bits,
+            encoded_paints_tex_width_bits: width_bits,
+            strip_offset_x: 0,
+            strip_offset_y: 0,
+            negate_ndc: u32::from(negate_ndc),
+            output_transfer: OUTPUT_TRANSFER_NONE,
+            output_scale: 1.0,
+            decode_paints: 0,
+            _padding: 0,
+        }
+    }
+}
+
+/// Redirects the passes into the root target to an atlas layer.
+#[cfg(feature = "text")]
+struct AtlasLayerBackend<'a, B> {
+    backend: &'a mut B,
+    atlas_layer: u32,
+}
+
+#[cfg(feature = "text")]
+impl<B: GpuBackend> GpuBackend for AtlasLayerBackend<'_, B> {
+    fn ensure_atlas_layers(&mut self, count: u32) {
+        self.backend.ensure_atlas_layers(count);
+    }
+
+    fn write_to_atlas(&mut self, atlas_layer: u32, offset: [u32; 2], pixmap: &Pixmap) {
+        self.backend.write_to_atlas(atlas_layer, offset, pixmap);
+    }
+
+    fn clear_atlas_region(&mut self, atlas_layer: u32, offset: [u32; 2], width: u32, height: u32) {
+        self.backend
+            .clear_atlas_region(atlas_layer, offset, width, height);
+    }
+
+    fn ensure_filter_atlases(&mut self, count: u32) {
+        self.backend.ensure_filter_atlases(count);
+    }
+
+    fn clear_filter_atlases(&mut self) {
+        self.backend.clear_filter_atlases();
+    }
+
+    fn has_texture(&self, texture_id: TextureId) -> bool {
+        self.backend.has_texture(texture_id)
+    }
+
+    fn upload(&mut self, frame: &FrameData<'_>) {
+        self.backend.upload(frame);
+    }
+
+    fn clear_slots(&mut self, slot_texture: usize, slots: &[u32]) {
+        self.backend.clear_slots(slot_texture, slots);
+    }
+
+    fn draw_strips(&mut self, pass: &StripPass<'_>) {
+        let target = match pass.target {
+            StripTarget::View => StripTarget::AtlasLayer(self.atlas_layer),
+            target => target,
+        };
+        self.backend.draw_strips(&StripPass { target, ..*pass });
+    }
+
+    fn draw_filter_pass(&mut self, pass: &FilterPass<'_>) {
+        self.backend.draw_filter_pass(pass);
+    }
+}
+
+/// Adapts a [`GpuBackend`] to the scheduler.
+struct BackendContext<'a, B> {
+    backend: &'a mut B,
+    image_cache: &'a ImageCache,
+    filter_context: &'a FilterContext,
+    filter_pass_state: &'a mut FilterPassState,
+    texture_runs: &'a mut Vec<TextureRun>,
+}
+
+impl<B: GpuBackend> RendererBackend for BackendContext<'_, B> {
+    fn clear_slots(&mut self, texture_index: usize, slots: &[u32]) {
+        if !slots.is_empty() {
+            self.backend.clear_slots(texture_index, slots);
+        }
+    }
+
+    fn render_strips(
+        &mut self,
+        opaque_strips: &[GpuStrip],
+        alpha_strips: &[GpuStrip],
+        external_texture_runs: &[ExternalTextureRun],
+        target: StripPassRenderTarget,
+        load_op: LoadOp,
+    ) {
+        let target = match target {
+            StripPassRenderTarget::Root(_) => StripTarget::View,
+            StripPassRenderTarget::SlotTexture(ix) => StripTarget::Slot(usize::from(ix)),
+            StripPassRenderTarget::FilterLayer(layer_id) => {
+                let filter_textures = &self.filter_context.filter_textures[&layer_id];
+                let resource = self
+                    .filter_context
+                    .image_cache
+                    .get(filter_textures.initial_image_id)
+                    .unwrap();
+                let bbox = &filter_textures.bbox;
+                // The intermediate texture lives at an offset within its atlas, while the
+                // filter layer doesn't start at (0, 0).
+                StripTarget::FilterAtlas {
+                    atlas: resource.atlas_id.as_u32(),
+                    strip_offset: [
+                        i32::from(resource.offset[0])
+                            - i32::from(bbox.x0()) * i32::from(WideTile::WIDTH),
+                        i32::from(resource.offset[1])
+                            - i32::from(bbox.y0()) * i32::from(Tile::HEIGHT),
+                    ],
+                    scissor: [
+                        u32::from(resource.offset[0]),
+                        u32::from(resource.offset[1]),
+                        u32::from(resource.width),
+                        u32::from(resource.height),
+                    ],
+                }
+            }
+        };
+        let clear = load_op == LoadOp::Clear;
+        if opaque_strips.is_empty() && alpha_strips.is_empty() && !clear {
+            return;
+        }
+        self.texture_runs.clear();
+        self.texture_runs
+            .extend(external_texture_runs.iter().map(|run| TextureRun {
+                texture_id: run.texture_id,
+                strips_start: run.strips_start,
+            }));
+        self.backend.draw_strips(&StripPass {
+            target,
+            clear,
+            opaque_strips,
+            alpha_strips,
+            texture_runs: self.texture_runs,
+        });
+    }
+
+    fn apply_filter(&mut self, layer_id: LayerId) {
+        let filter_atlas_size = self
+            .filter_context
+            .image_cache
+            .atlas_manager()
+            .config()
+            .atlas_size;
+        let image_atlas_size = self.image_cache.atlas_manager().config().atlas_size;
+        self.filter_context.build_filter_passes(
+            self.filter_pass_state,
+            &layer_id,
+            self.image_cache,
+            |_| [filter_atlas_size.0, filter_atlas_size.1],
+            || [image_atlas_size.0, image_atlas_size.1],
+        );
+
+        let passes = self.filter_pass_state.filter_passes();
+        let instances = self.filter_pass_state.instances();
+        for (pass, instance) in passes.iter().zip(instances) {
+            let (target, target_size) = match pass.output {
+                FilterPassTarget::FilterAtlas(idx) => {
+                    (FilterTarget::FilterAtlas(idx), filter_atlas_size)
+                }
+                FilterPassTarget::MainAtlas(idx) => {
+                    (FilterTarget::AtlasLayer(idx), image_atlas_size)
+                }
+            };
+            self.backend.draw_filter_pass(&FilterPass {
+                input_atlas: pass.input_atlas_idx,
+                original_atlas: pass.original_atlas_idx,
+                target,
+                scissor: instance.scissor_rect([target_size.0, target_size.1]),
+                instance: bytemuck::bytes_of(instance),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BackendConfig, BackendRenderer, FilterPass, FilterTarget, FrameData, GpuBackend, StripPass,
+        StripTarget, TextureRun,
+    };
+    use crate::render::RenderSize;
+    use crate::{RenderError, RenderSettings, Resources, SampleRect, Scene};
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use vello_common::TextureId;
+    use vello_common::color::palette::css::{BLUE, RED};
+    use vello_common::filter_effects::{Filter, FilterFunction};
+    use vello_common::geometry::RectU16;
+    use vello_common::kurbo::{Affine, Circle, Rect, Shape};
+    use vello_common::peniko::{BlendMode, Compose, Gradient, ImageQuality, Mix};
+    use vello_common::pixmap::Pixmap;
+
+    const MAX_TEXTURE_DIMENSION_2D: u32 = 512;
+
+    #[derive(Debug)]
+    enum Event {
+        Upload {
+            alphas: usize,
+            encoded_paints: usize,
+            gradient_ramps: Option<usize>,
+            filter_data: usize,
+        },
+        ClearSlots(usize),
+        Draw {
+            target: StripTarget,
+            clear: bool,
+            strips: usize,
+            texture_runs: Vec<TextureRun>,
+        },
+        FilterPass(FilterTarget),
+    }
+
+    /// Records the calls of the renderer instead of drawing anything.
+    #[derive(Debug, Default)]
+    struct RecordingBackend {
+        events: Vec<Event>,
+        alphas: Vec<u8>,
+        textures: Vec<TextureId>,
+        filter_atlases: u32,
+    }
+
+    impl GpuBackend for RecordingBackend {
+        fn ensure_atlas_layers(&mut self, _count: u32) {}
+
+        fn write_to_atlas(&mut self, _atlas_layer: u32, _offset: [u32; 2], _pixmap: &Pixmap) {}
+
+        fn clear_atlas_region(
+            &mut self,
+            _atlas_layer: u32,
+            _offset: [u32; 2],
+            _width: u32,
+            _height: u32,
+        ) {
+        }
+
+        fn ensure_filter_atlases(&mut self, count: u32) {
+            self.filter_atlases = self.filter_atlases.max(count);
+        }
+
+        fn clear_filter_atlases(&mut self) {}
+
+        fn has_texture(&self, texture_id: TextureId) -> bool {
+            self.textures.contains(&texture_id)
+        }
+
+        fn upload(&mut self, frame: &FrameData<'_>) {
+            self.alphas = frame.alphas.to_vec();
+            self.events.push(Event::Upload {
+                alphas: frame.alphas.len(),
+                encoded_paints: frame.encoded_paints.len(),
+                gradient_ramps: frame.gradient_ramps.map(<[u8]>::len),
+                filter_data: frame.filter_data.len(),
+            });
+        }
+
+        fn clear_slots(&mut self, slot_texture: usize, slots: &[u32]) {
+            assert!(!slots.is_empty(), "empty clears should be skipped");
+            self.events.push(Event::ClearSlots(slot_texture));
+        }
+
+        fn draw_strips(&mut self, pass: &StripPass<'_>) {
+            self.events.push(Event::Draw {
+                target: pass.target,
+                clear: pass.clear,
+                strips: pass.opaque_strips.len() + pass.alpha_strips.len(),
+                texture_runs: pass.texture_runs.to_vec(),
+            });
+        }
+
+        fn draw_filter_pass(&mut self, pass: &FilterPass<'_>) {
+            assert!(
+                pass.input_atlas < self.filter_atlases,
+                "filter passes must read existing filter atlases"
+            );
+            self.events.push(Event::FilterPass(pass.target));
+        }
+    }
+
+    fn renderer(max_texture_array_layers: u32) -> BackendRenderer {
+        BackendRenderer::new(
+            BackendConfig {
+                max_texture_dimension_2d: MAX_TEXTURE_DIMENSION_2D,
+                max_texture_array_layers,
+                y_up: false,
+            },
+            RenderSettings::default(),
+        )
+    }
+
+    fn render(scene: &Scene, backend: &mut RecordingBackend) -> Result<(), RenderError> {
+        renderer(16).render(
+            scene,
+            &mut Resources::new(),
+            &RenderSize {
+                width: scene.width().into(),
+                height: scene.height().into(),
+            },
+            backend,
+        )
+    }
+
+    #[test]
+    fn renders_blend_layer_through_slots() {
+        let mut scene = Scene::new(100, 100);
+        scene.set_paint(Gradient::new_linear((0.0, 0.0), (100.0, 0.0)).with_stops([RED, BLUE]));
+        scene.fill_path(&Circle::new((50.0, 50.0), 40.0).to_path(0.1));
+        scene.push_blend_layer(BlendMode::new(Mix::Multiply, Compose::SrcOver));
+        scene.set_paint(BLUE);
+        scene.fill_rect(&Rect::new(20.0, 20.0, 80.0, 80.0));
+        scene.pop_layer();
+
+        let mut backend = RecordingBackend::default();
+        render(&scene, &mut backend).unwrap();
+
+        // Everything is uploaded once, before the first pass, padded to whole rows.
+        let Some(Event::Upload {
+            alphas,
+            encoded_paints,
+            gradient_ramps: Some(gradient_ramps),
+            filter_data: 0,
+        }) = backend.events.first()
+        else {
+            panic!(
+                "the data and gradient ramp must be uploaded first, got {:?}",
+                backend.events
+            );
+        };
+        let row_size = MAX_TEXTURE_DIMENSION_2D as usize * 16;
+        let scene_alphas = &scene.strip_storage.borrow().alphas;
+        assert_eq!(*alphas, scene_alphas.len().next_multiple_of(row_size));
+        assert_eq!(
+            &backend.alphas[..scene_alphas.len()],
+            scene_alphas.as_slice()
+        );
+        assert!(*encoded_paints > 0 && encoded_paints % row_size == 0);
+        assert!(
+            *gradient_ramps > 0 && gradient_ramps % (MAX_TEXTURE_DIMENSION_2D as usize * 4) == 0
+        );
+
+        let mut targets = Vec::new();
+        for event in &backend.events[1..] {
+            match event {
+                Event::Upload { .. } => panic!("the data must only be uploaded once"),
+                Event::ClearSlots(slot_texture) => assert!(*slot_texture < 2),
+                Event::Draw {
+                    target,
+                    clear,
+                    strips,
+                    ..
+                } => {
+                    assert!(*strips > 0 || *clear, "empty passes should be skipped");
+                    targets.push(*target);
+                }
+                Event::FilterPass(_) => panic!("the scene has no filter effects"),
+            }
+        }
+        // Blending into the root reads it back, so it is drawn into the slot textures and
+        // composited into the view last.
+        assert!(
+            targets
+                .iter()
+                .all(|target| matches!(target, StripTarget::View | StripTarget::Slot(0 | 1)))
+        );
+        assert!(
+            targets
+                .iter()
+                .any(|target| matches!(target, StripTarget::Slot(_)))
+        );
+        assert_eq!(targets.last(), Some(&StripTarget::View));
+    }
+
+    #[test]
+    fn uploads_are_padded_to_rows_and_layers() {
+        let renderer = renderer(4);
+        let row_size = MAX_TEXTURE_DIMENSION_2D as usize * 16;
+        let layer_size = MAX_TEXTURE_DIMENSION_2D as usize * row_size;
+
+        assert_eq!(renderer.padded_len(0, row_size), 0);
+        assert_eq!(renderer.padded_len(1, row_size), row_size);
+        assert_eq!(renderer.padded_len(row_size + 1, row_size), 2 * row_size);
+        assert_eq!(renderer.padded_len(layer_size, row_size), layer_size);
+        assert_eq!(
+            renderer.padded_len(layer_size + 1, row_size),
+            2 * layer_size
+        );
+    }
+
+    #[test]
+    fn applies_filter_effects() {
+        let mut scene = Scene::new(100, 100);
+        scene.set_filter_effect(Filter::from_function(FilterFunction::Blur { radius: 2.0 }));
+        scene.set_paint(RED);
+        scene.fill_rect(&Rect::new(20.0, 20.0, 80.0, 80.0));
+
+        let mut backend = RecordingBackend::default();
+        render(&scene, &mut backend).unwrap();
+
+        let Some(Event::Upload { filter_data, .. }) = backend.events.first() else {
+            panic!("the data must be uploaded first, got {:?}", backend.events);
+        };
+        assert!(*filter_data > 0 && filter_data % (MAX_TEXTURE_DIMENSION_2D as usize * 16) == 0);
+
+        // The content of the layer is drawn into a filter atlas, which the passes of the blur
+        // read, until the last one writes the result into the image atlas.
+        let first_pass = backend
+            .events
+            .iter()
+            .position(|event| matches!(event, Event::FilterPass(_)))
+            .expect("the blur must be applied");
+        assert!(backend.events[..first_pass].iter().any(|event| matches!(
+            event,
+            Event::Draw {
+                target: StripTarget::FilterAtlas { .. },
+                ..
+            }
+        )));
+        let passes: Vec<_> = backend
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                Event::FilterPass(target) => Some(*target),
+                _ => None,
+            })
+            .collect();
+        assert!(passes.len() > 1);
+        assert!(matches!(passes.last(), Some(FilterTarget::AtlasLayer(_))));
+        assert!(matches!(
+            backend.events.last(),
+            Some(Event::Draw {
+                target: StripTarget::View,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn draws_external_textures() {
+        let mut scene = Scene::new(100, 100);
+        scene.draw_texture_rects(
+            TextureId(7),
+            ImageQuality::Low,
+            [SampleRect {
+                source_region: RectU16::new(0, 0, 10, 10),
+                transform: Affine::translate((20.0, 20.0)),
+            }],
+        );
+
+        let mut backend = RecordingBackend::default();
+        assert!(matches!(
+            render(&scene, &mut backend),
+            Err(RenderError::MissingTextureBinding(TextureId(7)))
+        ));
+
+        let mut backend = RecordingBackend {
+            textures: vec![TextureId(7)],
+            ..Default::default()
+        };
+        render(&scene, &mut backend).unwrap();
+        let runs: Vec<_> = backend
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Draw { texture_runs, .. } if !texture_runs.is_empty() => {
+                    Some(texture_runs.as_slice())
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            runs,
+            [[TextureRun {
+                texture_id: TextureId(7),
+                strips_start: 0,
+            }]]
+        );
+    }
+}
@@ -8,8 +8,30 @@
     reason = "GPU paint structures have small, fixed sizes that fit in u32"
 )]
 
+use crate::RenderError;
+use crate::gradient_cache::GradientRampCache;
+#[cfg(feature = "wgpu")]
+use alloc::string::String;
+#[cfg(feature = "wgpu")]
+use alloc::sync::Arc;
+use alloc::vec::Vec;
 use bytemuck::{Pod, Zeroable};
+#[cfg(feature = "wgpu")]
+use core::fmt::Write as _;
+use vello_common::alpha_mode::AlphaMode;
+#[cfg(feature = "wgpu")]
+use vello_common::custom_paint::CustomPaintKind;
+use vello_common::custom_paint::EncodedCustomPaint;
+use vello_common::encode::{
+    EncodedBlurredRoundedRectangle, EncodedExternalTexture, EncodedGradient, EncodedImage,
+    EncodedKind, EncodedPaint, RadialKind,
+};
+use vello_common::image_cache::{ImageCache, ImageResource};
+use vello_common::kurbo::Affine;
+use vello_common::mipmap::nearest_level;
 use vello_common::multi_atlas::AtlasConfig;
+use vello_common::paint::ImageSource;
+use vello_common::peniko::{self, ImageQuality};
 
 // GPU paint structure sizes in texels (1 texel = 16 bytes for RGBA32Uint texture format).
 pub(crate) const GPU_ENCODED_IMAGE_SIZE_TEXELS: u32 = (size_of::<GpuEncodedImage>() / 16) as u32;
@@ -43,9 +65,41 @@ pub(crate) fn normalize_atlas_config(
         .clamp(min_initial_atlas_count, config.max_atlases);
 }
 
+/// Return the height and number of layers of the texture array that stores `rows` rows of a
+/// linear texture, like the alpha, encoded paints and gradient textures.
+///
+/// The rows of these textures are indexed linearly, so rows that don't fit into one layer of
+/// `max_texture_dimension_2d` rows continue in the next layer. This way, the amount of data
+/// isn't limited by the largest 2D texture of the device.
+pub(crate) fn linear_texture_extent(
+    rows: u32,
+    max_texture_dimension_2d: u32,
+    max_texture_array_layers: u32,
+    min_layers: u32,
+) -> (u32, u32) {
+    if rows <= max_texture_dimension_2d {
+        return (rows.max(1), min_layers);
+    }
+
+    let layers = rows.div_ceil(max_texture_dimension_2d);
+    assert!(
+        layers <= max_texture_array_layers,
+        "{rows} texture rows exceed the device limits of {max_texture_array_layers} layers \
+        of {max_texture_dimension_2d} rows"
+    );
+    (max_texture_dimension_2d, layers.max(min_layers))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::normalize_atlas_config;
+    #[cfg(feature = "wgpu")]
+    use super::{DynamicRange, OUTPUT_TRANSFER_NONE, OutputColorConfig, compose_custom_paints};
+    use super::{GpuCustomPaint, linear_texture_extent, normalize_atlas_config};
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+    use vello_common::custom_paint::{CustomPaint, CustomPaintKind, CustomPaintRegistry};
+    use vello_common::encode::EncodedPaint;
+    use vello_common::kurbo::{Affine, Point};
     use vello_common::multi_atlas::AtlasConfig;
 
     #[test]
@@ -64,6 +118,60 @@ mod tests {
         assert_eq!(config.atlas_size, (4096, 2048));
     }
 
+    #[test]
+    fn linear_texture_extent_splits_rows_into_layers() {
+        assert_eq!(linear_texture_extent(0, 4096, 256, 1), (1, 1));
+        assert_eq!(linear_texture_extent(100, 4096, 256, 2), (100, 2));
+        assert_eq!(linear_texture_extent(4096, 4096, 256, 1), (4096, 1));
+        assert_eq!(linear_texture_extent(4097, 4096, 256, 1), (4096, 2));
+        assert_eq!(linear_texture_extent(3 * 4096, 4096, 256, 1), (4096, 3));
+    }
+
+    #[test]
+    #[cfg(feature = "wgpu")]
+    fn coverage_only_output_writes_alpha_to_all_channels() {
+        let config = OutputColorConfig {
+            coverage_only: true,
+            dynamic_range: DynamicRange::SdrToneMapped,
+            ..OutputColorConfig::default()
+        };
+
+        let transfer = config.output_transfer();
+        assert_eq!(transfer.apply([0.1, 0.2, 0.05, 0.25]), [0.25; 4]);
+        assert_eq!(transfer.apply([0.0; 4]), [0.0; 4]);
+    }
+
+    #[test]
+    #[cfg(feature = "wgpu")]
+    fn linear_compositing_roundtrips_through_the_blend_space() {
+        let config = OutputColorConfig {
+            linear_compositing: true,
+            ..OutputColorConfig::default()
+        };
+        assert!(config.decodes_paints());
+
+        let color = [0.25, 0.1, 0.4, 0.5];
+        let blended = config.to_blend_space(color);
+        assert!(blended[0] < color[0]);
+        assert_eq!(blended[3], color[3]);
+        let output = config.output_transfer().apply(blended);
+        for (a, b) in output.iter().zip(color) {
+            assert!((a - b).abs() < 1e-5, "{output:?} != {color:?}");
+        }
+
+        let srgb_target = OutputColorConfig {
+            srgb_target: true,
+            ..config
+        };
+        assert_eq!(srgb_target.output_transfer().flags, OUTPUT_TRANSFER_NONE);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceed the device limits")]
+    fn linear_texture_extent_panics_beyond_layer_limit() {
+        linear_texture_extent(5 * 4096, 4096, 4, 1);
+    }
+
     #[test]
     fn normalize_atlas_config_enforces_minimum_initial_count() {
         let mut config = AtlasConfig {
@@ -79,6 +187,57 @@ mod tests {
         assert_eq!(config.max_atlases, 2);
         assert_eq!(config.atlas_size, (1, 1));
     }
+
+    #[derive(Debug)]
+    struct Flat;
+
+    impl CustomPaintKind for Flat {
+        fn evaluate(&self, params: &[f32], _: Point) -> [f32; 4] {
+            [params[0]; 4]
+        }
+
+        fn wgsl(&self) -> &str {
+            "return vec4(custom_paint_param(params, 0u));"
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "wgpu")]
+    fn custom_paints_replace_the_placeholder() {
+        let shader =
+            "fn a() {}\n// CUSTOM_PAINTS_START\nfn custom_paint() {}\n// CUSTOM_PAINTS_END\n";
+        let kinds: [Arc<dyn CustomPaintKind>; 2] = [Arc::new(Flat), Arc::new(Flat)];
+
+        let composed = compose_custom_paints(shader, &kinds).unwrap();
+        assert!(composed.starts_with("fn a() {}\n"));
+        assert!(!composed.contains("fn custom_paint() {}"));
+        assert!(composed.contains("fn custom_paint_1(pos: vec2<f32>, params: u32)"));
+        assert!(composed.contains("case 1u: { return custom_paint_1(pos, params); }"));
+        assert!(composed.ends_with("// CUSTOM_PAINTS_END\n"));
+
+        assert!(compose_custom_paints("fn a() {}", &kinds).is_none());
+    }
+
+    #[test]
+    fn custom_paint_parameters_are_packed_after_the_transform() {
+        let mut registry = CustomPaintRegistry::new();
+        let flat = registry.register(Arc::new(Flat));
+        let mut paints = Vec::new();
+        CustomPaint::new(flat, [0.5; 5]).encode_into(
+            &registry,
+            &mut paints,
+            Affine::translate((2.0, 0.0)),
+        );
+        let EncodedPaint::Custom(paint) = &paints[0] else {
+            panic!("expected a custom paint");
+        };
+
+        let gpu = GpuCustomPaint::new(paint);
+        assert_eq!(gpu.size_texels(), 4);
+        assert_eq!(gpu.texels[0][..2], [flat.as_u32(), 5]);
+        assert_eq!(f32::from_bits(gpu.texels[1][2]), -2.0);
+        assert_eq!(gpu.texels[3], [0.5_f32.to_bits(), 0, 0, 0]);
+    }
 }
 
 /// Dimensions of the rendering target.
@@ -90,6 +249,300 @@ pub struct RenderSize {
     pub height: u32,
 }
 
+/// The color space that the colors of a scene are given, and blended, in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WorkingSpace {
+    /// Colors are sRGB-encoded, the same as in `vello_cpu`.
+    #[default]
+    Srgb,
+    /// Colors are linear.
+    Linear,
+}
+
+/// The dynamic range of the render target, and how colors are encoded for it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DynamicRange {
+    /// A standard dynamic range target. Color components above 1.0 are clipped.
+    #[default]
+    Sdr,
+    /// A standard dynamic range target, where color components above 0.8 are smoothly
+    /// compressed so that extended-range colors approach 1.0 instead of being clipped.
+    SdrToneMapped,
+    /// An extended-range linear target in the scRGB color space, like an `Rgba16Float`
+    /// swapchain, where 1.0 corresponds to 80 nits.
+    ScRgb {
+        /// The brightness of SDR white (a color component of 1.0) in nits.
+        sdr_white_nits: f32,
+    },
+    /// An HDR10 target, like an `Rgb10a2Unorm` swapchain, with BT.2020 primaries and the
+    /// perceptual quantizer (PQ) transfer function.
+    Pq {
+        /// The brightness of SDR white (a color component of 1.0) in nits.
+        sdr_white_nits: f32,
+    },
+}
+
+/// How the renderer writes colors to the render target.
+///
+/// Depending on the format of the render target, the GPU stores the values that the shaders
+/// write as they are (e.g. `Bgra8Unorm`), or encodes them from linear to sRGB first (e.g.
+/// `Bgra8UnormSrgb`). The renderer converts the colors from the working space so that the
+/// target ends up with the intended colors in both cases.
+///
+/// Solid colors can have components above 1.0, which are preserved in intermediate layers
+/// if the render target has a floating point format, and shown brighter than SDR white on
+/// [HDR targets](DynamicRange). Gradients and images are limited to `[0, 1]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OutputColorConfig {
+    /// The color space that the colors of scenes are given, and blended, in.
+    pub working_space: WorkingSpace,
+    /// Whether the GPU encodes the colors written to the render target to sRGB.
+    ///
+    /// This is the case for sRGB formats like `Bgra8UnormSrgb`, unless the target is written
+    /// through a view with a non-sRGB format. HDR targets are never sRGB-encoded, so this is
+    /// ignored for them.
+    pub srgb_target: bool,
+    /// The dynamic range of the render target.
+    pub dynamic_range: DynamicRange,
+    /// Whether to write only the coverage of the scene, its alpha, to all channels of the
+    /// render target, for rendering masks into single-channel targets like `R8Unorm`.
+    ///
+    /// Paints drawn directly to the render target aren't evaluated then, which is faster.
+    /// Paints drawn inside layers still are, since their colors are blended. The other
+    /// options are ignored.
+    pub coverage_only: bool,
+    /// Whether the colors written to the render target are premultiplied with their alpha.
+    ///
+    /// With [`AlphaMode::Straight`], the scene is rendered into an intermediate texture of
+    /// the size of the target, which is unpremultiplied into the target by a final pass. Like
+    /// with MSAA, rendering with [`LoadOp::Load`](crate::LoadOp::Load) then continues from
+    /// what the renderer rendered at the same size before, rather than from the current
+    /// content of the target.
+    pub alpha_mode: AlphaMode,
+    /// Whether to blend in linear light, even though colors are given in sRGB.
+    ///
+    /// Blending sRGB-encoded colors makes anti-aliased edges and the transitions between
+    /// translucent shapes look darker than they should. With this option, the colors of
+    /// paints are decoded to linear before they are blended, and the result is encoded for the
+    /// render target at the end. This has no effect if the working space is already
+    /// [linear](WorkingSpace::Linear).
+    ///
+    /// Intermediate layers then store linear colors with 8 bits per channel, which can show
+    /// banding in dark gradients.
+    pub linear_compositing: bool,
+}
+
+/// Colors are written to the render target unchanged.
+pub(crate) const OUTPUT_TRANSFER_NONE: u32 = 0;
+/// Colors are decoded from sRGB to linear.
+#[cfg(feature = "wgpu")]
+const OUTPUT_TRANSFER_DECODE_SRGB: u32 = 1 << 0;
+/// Linear colors are tone mapped into `[0, 1]`.
+#[cfg(feature = "wgpu")]
+const OUTPUT_TRANSFER_TONE_MAP: u32 = 1 << 1;
+/// Linear colors are converted from BT.709 to BT.2020 primaries.
+#[cfg(feature = "wgpu")]
+const OUTPUT_TRANSFER_BT2020: u32 = 1 << 2;
+/// Linear colors are multiplied with [`Config::output_scale`].
+#[cfg(feature = "wgpu")]
+const OUTPUT_TRANSFER_SCALE: u32 = 1 << 3;
+/// Linear colors are encoded with the PQ transfer function.
+#[cfg(feature = "wgpu")]
+const OUTPUT_TRANSFER_ENCODE_PQ: u32 = 1 << 4;
+/// Linear colors are encoded to sRGB.
+#[cfg(feature = "wgpu")]
+const OUTPUT_TRANSFER_ENCODE_SRGB: u32 = 1 << 5;
+/// Only the coverage is written, to all channels.
+#[cfg(feature = "wgpu")]
+const OUTPUT_TRANSFER_COVERAGE: u32 = 1 << 6;
+
+/// The luminance in nits that a linear scRGB value of 1.0 corresponds to.
+#[cfg(feature = "wgpu")]
+const SCRGB_WHITE_NITS: f32 = 80.0;
+/// The luminance in nits that a PQ-encoded value of 1.0 corresponds to.
+#[cfg(feature = "wgpu")]
+const PQ_MAX_NITS: f32 = 10000.0;
+
+/// The steps of converting colors for the render target, as applied in the shaders.
+#[cfg(feature = "wgpu")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct OutputTransfer {
+    /// A combination of the `OUTPUT_TRANSFER_*` flags.
+    pub(crate) flags: u32,
+    /// The factor that linear colors are multiplied with for [`OUTPUT_TRANSFER_SCALE`].
+    pub(crate) scale: f32,
+}
+
+#[cfg(feature = "wgpu")]
+impl OutputTransfer {
+    /// Write colors unchanged.
+    pub(crate) const NONE: Self = Self {
+        flags: OUTPUT_TRANSFER_NONE,
+        scale: 1.0,
+    };
+
+    /// Apply the conversion to a premultiplied color, like the shaders do.
+    pub(crate) fn apply(self, premul: [f32; 4]) -> [f32; 4] {
+        let [r, g, b, a] = premul;
+        if self.flags == OUTPUT_TRANSFER_NONE || a == 0.0 {
+            return premul;
+        }
+        if self.flags & OUTPUT_TRANSFER_COVERAGE != 0 {
+            return [a; 4];
+        }
+
+        let mut rgb = [r, g, b].map(|c| (c / a).max(0.0));
+        if self.flags & OUTPUT_TRANSFER_DECODE_SRGB != 0 {
+            rgb = rgb.map(srgb_to_linear);
+        }
+        if self.flags & OUTPUT_TRANSFER_TONE_MAP != 0 {
+            rgb = tone_map(rgb);
+        }
+        if self.flags & OUTPUT_TRANSFER_BT2020 != 0 {
+            rgb = bt709_to_bt2020(rgb);
+        }
+        if self.flags & OUTPUT_TRANSFER_SCALE != 0 {
+            rgb = rgb.map(|c| c * self.scale);
+        }
+        if self.flags & OUTPUT_TRANSFER_ENCODE_PQ != 0 {
+            rgb = rgb.map(pq_encode);
+        }
+        if self.flags & OUTPUT_TRANSFER_ENCODE_SRGB != 0 {
+            rgb = rgb.map(linear_to_srgb);
+        }
+
+        let [r, g, b] = rgb.map(|c| c * a);
+        [r, g, b, a]
+    }
+}
+
+#[cfg(feature = "wgpu")]
+impl OutputColorConfig {
+    /// Whether scenes have to be rendered into a premultiplied texture first, which is
+    /// unpremultiplied into the render target.
+    pub(crate) fn needs_premultiplied_target(self) -> bool {
+        self.alpha_mode == AlphaMode::Straight && !self.coverage_only
+    }
+
+    /// The conversion that has to be applied to colors written to the render target.
+    pub(crate) fn output_transfer(self) -> OutputTransfer {
+        if self.coverage_only {
+            return OutputTransfer {
+                flags: OUTPUT_TRANSFER_COVERAGE,
+                scale: 1.0,
+            };
+        }
+
+        let decode = match self.blend_space() {
+            WorkingSpace::Srgb => OUTPUT_TRANSFER_DECODE_SRGB,
+            WorkingSpace::Linear => OUTPUT_TRANSFER_NONE,
+        };
+        let encode = if self.srgb_target {
+            OUTPUT_TRANSFER_NONE
+        } else {
+            OUTPUT_TRANSFER_ENCODE_SRGB
+        };
+        let (flags, scale) = match self.dynamic_range {
+            // Only convert if the working space doesn't match the target.
+            DynamicRange::Sdr => match (self.blend_space(), self.srgb_target) {
+                (WorkingSpace::Srgb, true) => (OUTPUT_TRANSFER_DECODE_SRGB, 1.0),
+                (WorkingSpace::Linear, false) => (OUTPUT_TRANSFER_ENCODE_SRGB, 1.0),
+                _ => (OUTPUT_TRANSFER_NONE, 1.0),
+            },
+            DynamicRange::SdrToneMapped => (decode | OUTPUT_TRANSFER_TONE_MAP | encode, 1.0),
+            DynamicRange::ScRgb { sdr_white_nits } => (
+                decode | OUTPUT_TRANSFER_SCALE,
+                sdr_white_nits / SCRGB_WHITE_NITS,
+            ),
+            DynamicRange::Pq { sdr_white_nits } => (
+                decode | OUTPUT_TRANSFER_BT2020 | OUTPUT_TRANSFER_SCALE | OUTPUT_TRANSFER_ENCODE_PQ,
+                sdr_white_nits / PQ_MAX_NITS,
+            ),
+        };
+
+        OutputTransfer { flags, scale }
+    }
+
+    /// Whether the shaders decode the colors of paints from sRGB to linear before blending.
+    pub(crate) fn decodes_paints(self) -> bool {
+        self.linear_compositing && self.working_space == WorkingSpace::Srgb && !self.coverage_only
+    }
+
+    /// Convert a premultiplied color of the scene to the space that colors are blended in.
+    pub(crate) fn to_blend_space(self, premul: [f32; 4]) -> [f32; 4] {
+        if !self.decodes_paints() {
+            return premul;
+        }
+        OutputTransfer {
+            flags: OUTPUT_TRANSFER_DECODE_SRGB,
+            scale: 1.0,
+        }
+        .apply(premul)
+    }
+
+    /// The color space that colors are blended in.
+    fn blend_space(self) -> WorkingSpace {
+        if self.linear_compositing {
+            WorkingSpace::Linear
+        } else {
+            self.working_space
+        }
+    }
+}
+
+#[cfg(feature = "wgpu")]
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[cfg(feature = "wgpu")]
+fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Compress the color components above 0.8 so that they approach 1.0, keeping the hue.
+#[cfg(feature = "wgpu")]
+fn tone_map(rgb: [f32; 3]) -> [f32; 3] {
+    const KNEE: f32 = 0.8;
+    let max = rgb[0].max(rgb[1]).max(rgb[2]);
+    if max <= KNEE {
+        return rgb;
+    }
+    let excess = max - KNEE;
+    let mapped = KNEE + (1.0 - KNEE) * excess / (excess + 1.0 - KNEE);
+    rgb.map(|c| c * mapped / max)
+}
+
+#[cfg(feature = "wgpu")]
+fn bt709_to_bt2020([r, g, b]: [f32; 3]) -> [f32; 3] {
+    [
+        0.627_404 * r + 0.329_283 * g + 0.043_313 * b,
+        0.069_097 * r + 0.919_540 * g + 0.011_362 * b,
+        0.016_391 * r + 0.088_013 * g + 0.895_595 * b,
+    ]
+}
+
+/// Encode a linear value, where 1.0 corresponds to 10000 nits, with the PQ transfer function
+/// of SMPTE ST 2084.
+#[cfg(feature = "wgpu")]
+fn pq_encode(c: f32) -> f32 {
+    const M1: f32 = 0.159_301_76;
+    const M2: f32 = 78.843_75;
+    const C1: f32 = 0.835_937_5;
+    const C2: f32 = 18.851_563;
+    const C3: f32 = 18.6875;
+    let p = c.clamp(0.0, 1.0).powf(M1);
+    ((C1 + C2 * p) / (1.0 + C3 * p)).powf(M2)
+}
+
 /// Configuration for the GPU renderer.
 #[repr(C, align(16))]
 #[derive(Debug, Copy, Clone, Pod, Zeroable)]
@@ -129,6 +582,21 @@ pub struct Config {
     /// and just apply the second negation manually in case we render to the final output surface
     /// in the WebGL backend.
     pub negate_ndc: u32,
+    /// The steps of converting the colors written to the render target, as a combination of
+    /// bit flags: decoding from sRGB (1), tone mapping (2), converting to BT.2020 primaries (4),
+    /// multiplying with `output_scale` (8), encoding with PQ (16), encoding to sRGB (32) and
+    /// writing only the coverage (64).
+    ///
+    /// This is only non-zero when rendering to the final output surface, see
+    /// [`OutputColorConfig`].
+    pub output_transfer: u32,
+    /// The factor that linear colors are multiplied with, if enabled by `output_transfer`.
+    pub output_scale: f32,
+    /// Whether the colors of paints are decoded from sRGB to linear before they are blended,
+    /// see [`OutputColorConfig::linear_compositing`].
+    pub decode_paints: u32,
+    /// Padding to a multiple of 16 bytes.
+    pub _padding: u32,
 }
 
 /// A GPU strip instance for rendering.
@@ -171,6 +639,8 @@ pub(crate) enum GpuEncodedPaint {
     SweepGradient(GpuSweepGradient),
     /// An encoded blurred rounded rectangle.
     BlurredRoundedRect(GpuBlurredRoundedRect),
+    /// An encoded custom paint.
+    Custom(GpuCustomPaint),
 }
 
 impl GpuEncodedPaint {
@@ -183,6 +653,7 @@ impl GpuEncodedPaint {
             Self::RadialGradient(paint) => bytemuck::bytes_of(paint),
             Self::SweepGradient(paint) => bytemuck::bytes_of(paint),
             Self::BlurredRoundedRect(paint) => bytemuck::bytes_of(paint),
+            Self::Custom(paint) => bytemuck::cast_slice(&paint.texels),
         }
     }
 
@@ -223,6 +694,22 @@ pub(crate) struct GpuEncodedImage {
     pub tint_mode: u32,
     /// Number of transparent padding pixels around the image in the atlas.
     pub image_padding: u32,
+    /// Atlas index of the next smaller mip level.
+    pub mip_atlas_index: u32,
+    /// Packed width and height of the next smaller mip level.
+    pub mip_size: u32,
+    /// The offset of the next smaller mip level in the atlas texture in pixels.
+    pub mip_offset: u32,
+    /// Weight with which the next smaller mip level is blended in. Zero disables blending.
+    pub mip_blend: f32,
+    /// Atlas index of the image that is cross-faded to.
+    pub fade_atlas_index: u32,
+    /// Packed width and height of the image that is cross-faded to.
+    pub fade_size: u32,
+    /// The offset of the image that is cross-faded to in the atlas texture in pixels.
+    pub fade_offset: u32,
+    /// Weight with which the image that is cross-faded to is blended in. Zero disables it.
+    pub fade_t: f32,
 }
 
 /// GPU encoded blurred rounded rectangle data.
@@ -311,8 +798,336 @@ pub(crate) struct GpuSweepGradient {
     pub _padding: [u32; 2],
 }
 
+/// GPU encoded custom paint data.
+///
+/// The first texel holds the kind and the parameter count, followed by the linear part of the
+/// transform. The second texel holds the translation, and the parameters follow from the third
+/// texel on, four per texel.
+#[derive(Debug)]
+#[allow(dead_code, reason = "Clippy fails when --no-default-features")]
+pub(crate) struct GpuCustomPaint {
+    pub texels: Vec<[u32; 4]>,
+}
+
+impl GpuCustomPaint {
+    pub(crate) fn new(paint: &EncodedCustomPaint) -> Self {
+        let [a, b, c, d, tx, ty] = paint.transform.as_coeffs().map(|x| (x as f32).to_bits());
+        let mut texels = Vec::with_capacity(2 + paint.params.len().div_ceil(4));
+        texels.push([paint.id.as_u32(), paint.params.len() as u32, a, b]);
+        texels.push([c, d, tx, ty]);
+        for chunk in paint.params.chunks(4) {
+            let mut texel = [0; 4];
+            for (dst, param) in texel.iter_mut().zip(chunk) {
+                *dst = param.to_bits();
+            }
+            texels.push(texel);
+        }
+
+        Self { texels }
+    }
+
+    /// Return the size of the paint in texels.
+    pub(crate) fn size_texels(&self) -> u32 {
+        self.texels.len() as u32
+    }
+}
+
+/// Marks the start of the custom paint functions in `render_strips.wgsl`.
+#[cfg(feature = "wgpu")]
+const CUSTOM_PAINTS_START: &str = "// CUSTOM_PAINTS_START";
+/// Marks the end of the custom paint functions in `render_strips.wgsl`.
+#[cfg(feature = "wgpu")]
+const CUSTOM_PAINTS_END: &str = "// CUSTOM_PAINTS_END";
+
+/// Replace the placeholder `custom_paint` function of the strip shader with one that
+/// dispatches to the WGSL of the given custom paint kinds, in the order of their identifiers.
+///
+/// Returns `None` if the shader doesn't contain the placeholder.
+#[cfg(feature = "wgpu")]
+pub(crate) fn compose_custom_paints(
+    render_strips: &str,
+    kinds: &[Arc<dyn CustomPaintKind>],
+) -> Option<String> {
+    let start = render_strips.find(CUSTOM_PAINTS_START)?;
+    let end = render_strips[start..].find(CUSTOM_PAINTS_END)? + start;
+
+    let mut wgsl = String::from(&render_strips[..start]);
+    for (id, kind) in kinds.iter().enumerate() {
+        let _ = writeln!(
+            wgsl,
+            "fn custom_paint_{id}(pos: vec2<f32>, params: u32) -> vec4<f32> {{\n{}\n}}\n",
+            kind.wgsl()
+        );
+    }
+    wgsl.push_str(
+        "fn custom_paint(kind: u32, pos: vec2<f32>, params: u32) -> vec4<f32> {\n    switch kind {\n",
+    );
+    for id in 0..kinds.len() {
+        let _ = writeln!(
+            wgsl,
+            "        case {id}u: {{ return custom_paint_{id}(pos, params); }}"
+        );
+    }
+    wgsl.push_str("        default: { return vec4<f32>(0.0); }\n    }\n}\n");
+    wgsl.push_str(&render_strips[end..]);
+
+    Some(wgsl)
+}
+
 // Constants for packing extend_mode and texture_width.
+/// Placeholder value for uninitialized GPU encoded paints.
+const GPU_PAINT_PLACEHOLDER: GpuEncodedPaint = GpuEncodedPaint::LinearGradient(GpuLinearGradient {
+    texture_width_and_extend_mode: 0,
+    gradient_start: 0,
+    transform: [0.0; 6],
+});
+
+/// Encode `encoded_paints` for the GPU.
+///
+/// This writes the GPU representation of each paint into `gpu_paints` and its offset in the
+/// encoded paints texture, in texels, into `paint_idxs`, which gets one more entry with the
+/// total number of texels. Gradient ramps are resolved through `gradient_cache`.
+///
+/// External textures are bound differently by each backend, so they are encoded by
+/// `encode_external_texture`. It can return `None` to keep the placeholder of the paint.
+pub(crate) fn encode_gpu_paints(
+    encoded_paints: &[EncodedPaint],
+    image_cache: &ImageCache,
+    gradient_cache: &mut GradientRampCache,
+    gpu_paints: &mut Vec<GpuEncodedPaint>,
+    paint_idxs: &mut Vec<u32>,
+    mut encode_external_texture: impl FnMut(
+        &EncodedExternalTexture,
+    ) -> Result<Option<GpuEncodedPaint>, RenderError>,
+) -> Result<(), RenderError> {
+    gpu_paints.resize_with(encoded_paints.len(), || GPU_PAINT_PLACEHOLDER);
+    paint_idxs.resize(encoded_paints.len() + 1, 0);
+
+    let mut current_idx = 0;
+    for (encoded_paint_idx, paint) in encoded_paints.iter().enumerate() {
+        paint_idxs[encoded_paint_idx] = current_idx;
+        match paint {
+            EncodedPaint::Image(img) => {
+                if let Some((image_resource, fade)) = resolve_image_source(&img.source, image_cache)
+                {
+                    gpu_paints[encoded_paint_idx] =
+                        encode_image_paint(img, image_resource, fade, image_cache);
+                    current_idx += GPU_ENCODED_IMAGE_SIZE_TEXELS;
+                }
+            }
+            EncodedPaint::ExternalTexture(texture) => {
+                if let Some(gpu_paint) = encode_external_texture(texture)? {
+                    gpu_paints[encoded_paint_idx] = gpu_paint;
+                }
+                current_idx += GPU_ENCODED_IMAGE_SIZE_TEXELS;
+            }
+            EncodedPaint::Gradient(gradient) => {
+                let (gradient_start, gradient_width) = gradient_cache.get_or_create_ramp(gradient);
+                let gradient_paint =
+                    encode_gradient_paint(gradient, gradient_width, gradient_start);
+                let gradient_size_texels = match &gradient_paint {
+                    GpuEncodedPaint::LinearGradient(_) => GPU_LINEAR_GRADIENT_SIZE_TEXELS,
+                    GpuEncodedPaint::RadialGradient(_) => GPU_RADIAL_GRADIENT_SIZE_TEXELS,
+                    GpuEncodedPaint::SweepGradient(_) => GPU_SWEEP_GRADIENT_SIZE_TEXELS,
+                    _ => unreachable!("encode_gradient_paint only returns gradient types"),
+                };
+                gpu_paints[encoded_paint_idx] = gradient_paint;
+                current_idx += gradient_size_texels;
+            }
+            EncodedPaint::BlurredRoundedRect(blurred_rect) => {
+                gpu_paints[encoded_paint_idx] = encode_blurred_rounded_rect_paint(blurred_rect);
+                current_idx += GPU_BLURRED_ROUNDED_RECT_SIZE_TEXELS;
+            }
+            EncodedPaint::Custom(custom) => {
+                // Backends whose shaders are compiled ahead of time draw custom paints with the
+                // placeholder function, which makes them transparent.
+                let custom = GpuCustomPaint::new(custom);
+                current_idx += custom.size_texels();
+                gpu_paints[encoded_paint_idx] = GpuEncodedPaint::Custom(custom);
+            }
+        }
+    }
+    paint_idxs[encoded_paints.len()] = current_idx;
+    Ok(())
+}
+
+/// Encode an image paint that samples an external texture.
+pub(crate) fn encode_external_texture_paint(image: &EncodedExternalTexture) -> GpuEncodedPaint {
+    let transform = image.transform.as_coeffs().map(|x| x as f32);
+    let region = image.source_region;
+    let image_size = pack_image_size(region.width(), region.height());
+    let image_offset = pack_image_offset(region.x0, region.y0);
+    let image_params = pack_image_params(
+        image.sampler.quality as u32,
+        image.sampler.x_extend as u32,
+        image.sampler.y_extend as u32,
+        0,
+    ) | EXTERNAL_IMAGE_SOURCE_FLAG;
+    let (tint, tint_mode) = pack_tint(image.tint);
+
+    GpuEncodedPaint::Image(GpuEncodedImage {
+        image_params,
+        image_size,
+        image_offset,
+        transform,
+        tint,
+        tint_mode,
+        image_padding: 0,
+        mip_atlas_index: 0,
+        mip_size: 0,
+        mip_offset: 0,
+        mip_blend: 0.0,
+        fade_atlas_index: 0,
+        fade_size: 0,
+        fade_offset: 0,
+        fade_t: 0.0,
+    })
+}
+
+/// Encode an image paint that samples `image_resource`, and optionally fades to another image.
+pub(crate) fn encode_image_paint(
+    image: &EncodedImage,
+    image_resource: &ImageResource,
+    fade: Option<(&ImageResource, f32)>,
+    image_cache: &ImageCache,
+) -> GpuEncodedPaint {
+    let mips = select_mip_levels(image, image_resource, image_cache);
+    let image_resource = mips.level;
+    let transform = mips.transform.as_coeffs().map(|x| x as f32);
+    let image_size = pack_image_size(image_resource.width, image_resource.height);
+    let image_offset = pack_image_offset(image_resource.offset[0], image_resource.offset[1]);
+    let image_params = pack_image_params(
+        image.sampler.quality as u32,
+        image.sampler.x_extend as u32,
+        image.sampler.y_extend as u32,
+        image_resource.atlas_id.as_u32(),
+    );
+    let (tint, tint_mode) = pack_tint(image.tint);
+    let (mip_atlas_index, mip_size, mip_offset, mip_blend) = match mips.next {
+        Some((next, blend)) => (
+            next.atlas_id.as_u32(),
+            pack_image_size(next.width, next.height),
+            pack_image_offset(next.offset[0], next.offset[1]),
+            blend,
+        ),
+        None => (0, 0, 0, 0.0),
+    };
+    let (fade_atlas_index, fade_size, fade_offset, fade_t) = pack_cross_fade(fade);
+
+    GpuEncodedPaint::Image(GpuEncodedImage {
+        image_params,
+        image_size,
+        image_offset,
+        transform,
+        tint,
+        tint_mode,
+        image_padding: image_resource.padding as u32,
+        mip_atlas_index,
+        mip_size,
+        mip_offset,
+        mip_blend,
+        fade_atlas_index,
+        fade_size,
+        fade_offset,
+        fade_t,
+    })
+}
+
+/// Encode a gradient paint whose ramp starts at `gradient_start` in the gradient texture.
+pub(crate) fn encode_gradient_paint(
+    gradient: &EncodedGradient,
+    gradient_width: u32,
+    gradient_start: u32,
+) -> GpuEncodedPaint {
+    let transform = gradient.transform.as_coeffs().map(|x| x as f32);
+    let extend_mode = match gradient.extend {
+        peniko::Extend::Pad => 0,
+        peniko::Extend::Repeat => 1,
+        peniko::Extend::Reflect => 2,
+    };
+    let texture_width_and_extend_mode =
+        pack_texture_width_and_extend_mode(gradient_width, extend_mode);
+
+    match &gradient.kind {
+        EncodedKind::Linear(_) => GpuEncodedPaint::LinearGradient(GpuLinearGradient {
+            texture_width_and_extend_mode,
+            gradient_start,
+            transform,
+        }),
+        EncodedKind::Radial(radial) => {
+            let (kind, bias, scale, fp0, fp1, fr1, f_focal_x, f_is_swapped, scaled_r0_squared) =
+                match radial {
+                    RadialKind::Radial { bias, scale } => {
+                        (0, *bias, *scale, 0.0, 0.0, 0.0, 0.0, 0, 0.0)
+                    }
+                    RadialKind::Strip { scaled_r0_squared } => {
+                        (1, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0, *scaled_r0_squared)
+                    }
+                    RadialKind::Focal {
+                        focal_data,
+                        fp0,
+                        fp1,
+                    } => (
+                        2,
+                        *fp0,
+                        *fp1,
+                        *fp0,
+                        *fp1,
+                        focal_data.fr1,
+                        focal_data.f_focal_x,
+                        focal_data.f_is_swapped as u32,
+                        0.0,
+                    ),
+                };
+            GpuEncodedPaint::RadialGradient(GpuRadialGradient {
+                texture_width_and_extend_mode,
+                gradient_start,
+                transform,
+                kind_and_f_is_swapped: pack_radial_kind_and_swapped(kind, f_is_swapped),
+                bias,
+                scale,
+                fp0,
+                fp1,
+                fr1,
+                f_focal_x,
+                scaled_r0_squared,
+            })
+        }
+        EncodedKind::Sweep(sweep) => GpuEncodedPaint::SweepGradient(GpuSweepGradient {
+            texture_width_and_extend_mode,
+            gradient_start,
+            transform,
+            start_angle: sweep.start_angle,
+            inv_angle_delta: sweep.inv_angle_delta,
+            _padding: [0, 0],
+        }),
+    }
+}
+
+/// Encode a blurred rounded rectangle paint.
+pub(crate) fn encode_blurred_rounded_rect_paint(
+    rect: &EncodedBlurredRoundedRectangle,
+) -> GpuEncodedPaint {
+    GpuEncodedPaint::BlurredRoundedRect(GpuBlurredRoundedRect {
+        transform: rect.transform.as_coeffs().map(|x| x as f32),
+        color: rect.color.as_premul_rgba8().to_u32(),
+        _padding0: 0,
+        params0: [
+            rect.exponent,
+            rect.recip_exponent,
+            rect.scale,
+            rect.std_dev_inv,
+        ],
+        params1: [rect.min_edge, rect.w, rect.h, rect.r1],
+        size: [rect.width, rect.height],
+        _padding1: [0, 0],
+    })
+}
+
 const EXTEND_MODE_MASK: u32 = 1 << 30;
+
+/// Marks images that are sampled from the external texture instead of the atlas.
+const EXTERNAL_IMAGE_SOURCE_FLAG: u32 = 1 << 14;
 const TEXTURE_WIDTH_MASK: u32 = !EXTEND_MODE_MASK;
 
 /// Pack `extend_mode` and `texture_width` into a single u32.
@@ -328,6 +1143,99 @@ pub(crate) fn pack_texture_width_and_extend_mode(texture_width: u32, extend_mode
     (extend_mode << 30) | (texture_width & TEXTURE_WIDTH_MASK)
 }
 
+/// The mip levels of an atlas image that an image paint samples from.
+#[derive(Debug)]
+pub(crate) struct MipSelection<'a> {
+    /// The level to sample from.
+    pub level: &'a ImageResource,
+    /// The paint transform, adjusted to the size of `level`.
+    pub transform: Affine,
+    /// The next smaller level and the weight with which it is blended in.
+    pub next: Option<(&'a ImageResource, f32)>,
+}
+
+/// Select the mip levels that `image` should be sampled from.
+///
+/// Images without a mip chain and magnified images always sample `base`. Otherwise, low
+/// quality paints sample the closest level, while filtered paints blend between the two
+/// levels surrounding the level of detail (trilinear filtering).
+pub(crate) fn select_mip_levels<'a>(
+    image: &EncodedImage,
+    base: &'a ImageResource,
+    image_cache: &'a ImageCache,
+) -> MipSelection<'a> {
+    let levels = base.mip_levels();
+    let lod = image.mip_lod();
+
+    if levels.is_empty() || lod.is_nan() || lod <= 0.0 {
+        return MipSelection {
+            level: base,
+            transform: image.transform,
+            next: None,
+        };
+    }
+
+    let resolve = |index: usize| match index {
+        0 => base,
+        _ => image_cache
+            .get(levels[index - 1])
+            .expect("mip levels must stay allocated together with their image"),
+    };
+    let max_level = levels.len();
+
+    let (index, next) = if image.sampler.quality == ImageQuality::Low {
+        (nearest_level(lod, max_level + 1), None)
+    } else {
+        let index = (lod as usize).min(max_level);
+        let blend = lod - index as f32;
+        let next = (index < max_level && blend > 0.0).then(|| (resolve(index + 1), blend));
+        (index, next)
+    };
+
+    let level = resolve(index);
+    let transform = Affine::scale_non_uniform(
+        f64::from(level.width) / f64::from(base.width),
+        f64::from(level.height) / f64::from(base.height),
+    ) * image.transform;
+
+    MipSelection {
+        level,
+        transform,
+        next,
+    }
+}
+
+/// Look up the atlas images that an image paint with `source` samples from.
+///
+/// Returns the image itself and, for cross-fades, the image that is faded to together with
+/// its weight. Returns `None` if any of the images isn't registered in `image_cache`.
+pub(crate) fn resolve_image_source<'a>(
+    source: &ImageSource,
+    image_cache: &'a ImageCache,
+) -> Option<(&'a ImageResource, Option<(&'a ImageResource, f32)>)> {
+    match source {
+        ImageSource::OpaqueId { id, .. } => Some((image_cache.get(*id)?, None)),
+        ImageSource::CrossFade(cross_fade) => Some((
+            image_cache.get(cross_fade.from)?,
+            Some((image_cache.get(cross_fade.to)?, cross_fade.t)),
+        )),
+        ImageSource::Pixmap(_) => None,
+    }
+}
+
+/// Pack the image that is cross-faded to into the `fade_*` fields of [`GpuEncodedImage`].
+pub(crate) fn pack_cross_fade(fade: Option<(&ImageResource, f32)>) -> (u32, u32, u32, f32) {
+    match fade {
+        Some((to, t)) => (
+            to.atlas_id.as_u32(),
+            pack_image_size(to.width, to.height),
+            pack_image_offset(to.offset[0], to.offset[1]),
+            t,
+        ),
+        None => (0, 0, 0, 0.0),
+    }
+}
+
 /// Pack radial gradient `kind` and `f_is_swapped` into a single u32.
 /// `kind`: 0=Radial, 1=Strip, 2=Focal (stored in bits 0-1)
 /// `f_is_swapped`: 0=false, 1=true (stored in bit 2)
@@ -0,0 +1,395 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Reloading shaders and tuning parameters while the application is running.
+//!
+//! This is a development facility for iterating on the renderer's WGSL shaders: a
+//! [`ShaderWatcher`] polls the shader sources and an optional parameter file for changes,
+//! and [`Renderer::reload_shaders`](crate::Renderer::reload_shaders) rebuilds the render
+//! pipelines from them, keeping all other GPU resources like the image atlases.
+//!
+//! Tuning parameters are passed to the shaders as pipeline-overridable constants, see
+//! [`parse_parameters`].
+
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use thiserror::Error;
+
+use super::wgpu::{ShaderSource, ShaderSources};
+
+/// Errors that can occur while reloading shaders.
+#[derive(Error, Debug)]
+pub enum HotReloadError {
+    /// A shader or parameter file couldn't be read.
+    #[error("failed to read `{}`: {source}", path.display())]
+    Io {
+        /// The file that couldn't be read.
+        path: PathBuf,
+        /// The underlying error.
+        source: io::Error,
+    },
+    /// A line of the parameter file couldn't be parsed.
+    #[error("invalid parameter on line {line}: {message}")]
+    Parameter {
+        /// The line number, starting at 1.
+        line: usize,
+        /// A description of the problem.
+        message: String,
+    },
+    /// A parameter doesn't match any `override` declaration in the shaders.
+    #[error("parameter `{0}` doesn't match any override in the shaders")]
+    UnknownParameter(String),
+    /// A shader failed to parse or validate.
+    #[error("invalid shader `{name}`: {message}")]
+    Shader {
+        /// The file name of the shader.
+        name: &'static str,
+        /// The diagnostics emitted for the shader.
+        message: String,
+    },
+    /// The device rejected the rebuilt pipelines.
+    #[error("failed to build pipelines: {0}")]
+    Pipeline(String),
+}
+
+/// The shaders that make up the renderer, with the entry points that the pipelines use.
+const SHADERS: [(&str, &[&str]); 3] = [
+    ("render_strips.wgsl", &["vs_main", "fs_main"]),
+    (
+        "clear_slots.wgsl",
+        &["vs_main", "vs_main_fullscreen", "fs_main"],
+    ),
+    ("filters.wgsl", &["vs_main", "fs_main"]),
+];
+
+/// A set of shader sources and tuning parameters that pipelines can be built from.
+#[derive(Debug, Clone)]
+pub struct ShaderSet {
+    /// The WGSL sources, in the same order as [`SHADERS`].
+    sources: [String; 3],
+    parameters: Vec<(String, f64)>,
+}
+
+impl ShaderSet {
+    /// Return the shaders that were bundled at build time, without any parameters.
+    pub fn builtin() -> Self {
+        Self {
+            sources: [
+                vello_sparse_shaders::wgsl::RENDER_STRIPS.to_owned(),
+                vello_sparse_shaders::wgsl::CLEAR_SLOTS.to_owned(),
+                vello_sparse_shaders::wgsl::FILTERS.to_owned(),
+            ],
+            parameters: Vec::new(),
+        }
+    }
+
+    /// Load the shaders from a directory that is laid out like the `shaders` directory of
+    /// `vello_sparse_shaders`.
+    pub fn load(dir: &Path) -> Result<Self, HotReloadError> {
+        let mut sources = [String::new(), String::new(), String::new()];
+        for (source, (name, _)) in sources.iter_mut().zip(SHADERS) {
+            *source = read(&dir.join(name))?;
+        }
+
+        Ok(Self {
+            sources,
+            parameters: Vec::new(),
+        })
+    }
+
+    /// Set the tuning parameters, replacing any existing ones.
+    pub fn set_parameters(&mut self, parameters: Vec<(String, f64)>) {
+        self.parameters = parameters;
+    }
+
+    /// Return the tuning parameters.
+    pub fn parameters(&self) -> &[(String, f64)] {
+        &self.parameters
+    }
+
+    /// Parse and validate all shaders, and work out which parameters apply to which shader.
+    ///
+    /// Every parameter has to match an `override` declaration in at least one shader, and
+    /// every `override` declaration without a default value has to be set by a parameter.
+    pub(crate) fn validate(&self) -> Result<[Vec<(&str, f64)>; 3], HotReloadError> {
+        let mut constants: [Vec<(&str, f64)>; 3] = Default::default();
+        let mut used = alloc::vec![false; self.parameters.len()];
+
+        for ((source, (name, entry_points)), constants) in
+            self.sources.iter().zip(SHADERS).zip(&mut constants)
+        {
+            let error = |message| HotReloadError::Shader { name, message };
+            let module = naga::front::wgsl::parse_str(source)
+                .map_err(|e| error(e.emit_to_string_with_path(source, name)))?;
+            naga::valid::Validator::new(
+                naga::valid::ValidationFlags::all(),
+                naga::valid::Capabilities::all(),
+            )
+            .validate(&module)
+            .map_err(|e| error(e.emit_to_string_with_path(source, name)))?;
+
+            for entry_point in entry_points {
+                if !module.entry_points.iter().any(|e| e.name == *entry_point) {
+                    return Err(error(format!("missing entry point `{entry_point}`")));
+                }
+            }
+
+            for (_, o) in module.overrides.iter() {
+                let id = o.id.map(|id| id.to_string());
+                let keys = [o.name.as_deref(), id.as_deref()];
+                let parameter = self
+                    .parameters
+                    .iter()
+                    .position(|(key, _)| keys.contains(&Some(key.as_str())));
+
+                match parameter {
+                    Some(idx) => {
+                        used[idx] = true;
+                        let (key, value) = &self.parameters[idx];
+                        constants.push((key.as_str(), *value));
+                    }
+                    None if o.init.is_none() => {
+                        let name = o.name.clone().or(id).unwrap_or_default();
+                        return Err(error(format!("no value for override `{name}`")));
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        if let Some(idx) = used.iter().position(|used| !used) {
+            return Err(HotReloadError::UnknownParameter(
+                self.parameters[idx].0.clone(),
+            ));
+        }
+
+        Ok(constants)
+    }
+
+    /// Return the sources for building pipelines, given the constants from
+    /// [`validate`](Self::validate).
+    pub(crate) fn sources<'a>(
+        &'a self,
+        constants: &'a [Vec<(&'a str, f64)>; 3],
+    ) -> ShaderSources<'a> {
+        let source = |idx: usize| ShaderSource {
+            wgsl: &self.sources[idx],
+            constants: &constants[idx],
+        };
+
+        ShaderSources {
+            render_strips: source(0),
+            clear_slots: source(1),
+            filters: source(2),
+        }
+    }
+}
+
+/// Parse the contents of a parameter file.
+///
+/// The file contains one `name = value` assignment per line, where `name` refers to an
+/// `override` declaration in one of the shaders and `value` is a number or a boolean. Lines
+/// starting with `#` are ignored.
+///
+/// ```text
+/// # Values for `override` declarations in the shaders.
+/// debug_tint = true
+/// gamma = 2.2
+/// ```
+pub fn parse_parameters(source: &str) -> Result<Vec<(String, f64)>, HotReloadError> {
+    let mut parameters = Vec::new();
+
+    for (idx, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let error = |message: &str| HotReloadError::Parameter {
+            line: idx + 1,
+            message: message.to_owned(),
+        };
+        let (name, value) = line
+            .split_once('=')
+            .ok_or_else(|| error("expected `name = value`"))?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(error("missing parameter name"));
+        }
+        let value = match value.trim() {
+            "true" => 1.0,
+            "false" => 0.0,
+            value => value
+                .parse::<f64>()
+                .map_err(|_| error("expected a number or a boolean"))?,
+        };
+
+        parameters.push((name.to_owned(), value));
+    }
+
+    Ok(parameters)
+}
+
+/// Watches shader sources and a parameter file for changes.
+///
+/// The watcher polls the modification times of the files, so [`poll`](Self::poll) is cheap
+/// enough to call once per frame.
+#[derive(Debug)]
+pub struct ShaderWatcher {
+    dir: PathBuf,
+    parameters: Option<PathBuf>,
+    modified: Vec<Option<SystemTime>>,
+}
+
+impl ShaderWatcher {
+    /// Watch the shaders in the given directory, which is laid out like the `shaders`
+    /// directory of `vello_sparse_shaders`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let mut watcher = Self {
+            dir: dir.into(),
+            parameters: None,
+            modified: Vec::new(),
+        };
+        watcher.modified = watcher.modification_times();
+        watcher
+    }
+
+    /// Watch the shader sources of the `vello_sparse_shaders` crate this renderer was built
+    /// with.
+    ///
+    /// This is most useful when working on a checkout of Vello.
+    pub fn bundled() -> Self {
+        Self::new(vello_sparse_shaders::SHADER_DIR)
+    }
+
+    /// Also watch a parameter file, see [`parse_parameters`] for its format.
+    pub fn with_parameters(mut self, path: impl Into<PathBuf>) -> Self {
+        self.parameters = Some(path.into());
+        self.modified = self.modification_times();
+        self
+    }
+
+    /// Return the directory that contains the shaders.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Reload the shaders and parameters, regardless of whether they changed.
+    pub fn load(&self) -> Result<ShaderSet, HotReloadError> {
+        let mut shaders = ShaderSet::load(&self.dir)?;
+        if let Some(path) = &self.parameters {
+            shaders.set_parameters(parse_parameters(&read(path)?)?);
+        }
+
+        Ok(shaders)
+    }
+
+    /// Check whether any of the watched files changed since the last call, and reload them
+    /// if so.
+    ///
+    /// Returns `None` if nothing changed.
+    pub fn poll(&mut self) -> Option<Result<ShaderSet, HotReloadError>> {
+        let modified = self.modification_times();
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+
+        Some(self.load())
+    }
+
+    fn modification_times(&self) -> Vec<Option<SystemTime>> {
+        let shaders = SHADERS.iter().map(|(name, _)| self.dir.join(name));
+
+        shaders
+            .chain(self.parameters.clone())
+            .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+            .collect()
+    }
+}
+
+fn read(path: &Path) -> Result<String, HotReloadError> {
+    std::fs::read_to_string(path).map_err(|source| HotReloadError::Io {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_shaders_are_valid() {
+        let shaders = ShaderSet::builtin();
+        let constants = shaders.validate().unwrap();
+
+        assert!(constants.iter().all(Vec::is_empty));
+    }
+
+    #[test]
+    fn parameters_are_parsed() {
+        let parameters = parse_parameters("# Comment\n\nscale = 0.5\n debug=true\n").unwrap();
+
+        assert_eq!(
+            parameters,
+            [("scale".to_owned(), 0.5), ("debug".to_owned(), 1.0)]
+        );
+        assert!(matches!(
+            parse_parameters("scale 0.5"),
+            Err(HotReloadError::Parameter { line: 1, .. })
+        ));
+        assert!(matches!(
+            parse_parameters("a = 1\nb = yes"),
+            Err(HotReloadError::Parameter { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn parameters_are_matched_to_overrides() {
+        let mut shaders = ShaderSet::builtin();
+        shaders.sources[1].push_str("\noverride clear_scale: f32;\n");
+
+        assert!(matches!(
+            shaders.validate(),
+            Err(HotReloadError::Shader {
+                name: "clear_slots.wgsl",
+                ..
+            })
+        ));
+
+        shaders.set_parameters(alloc::vec![("clear_scale".to_owned(), 2.0)]);
+        let constants = shaders.validate().unwrap();
+        assert!(constants[0].is_empty());
+        assert_eq!(constants[1], [("clear_scale", 2.0)]);
+
+        shaders.set_parameters(alloc::vec![
+            ("clear_scale".to_owned(), 2.0),
+            ("unknown".to_owned(), 2.0),
+        ]);
+        assert!(matches!(
+            shaders.validate(),
+            Err(HotReloadError::UnknownParameter(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_shaders_are_rejected() {
+        let mut shaders = ShaderSet::builtin();
+        shaders.sources[0] = shaders.sources[0].replace("fn fs_main", "fn fs_main_renamed");
+
+        assert!(matches!(
+            shaders.validate(),
+            Err(HotReloadError::Shader {
+                name: "render_strips.wgsl",
+                ..
+            })
+        ));
+    }
+}
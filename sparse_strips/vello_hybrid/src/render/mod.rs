@@ -7,20 +7,39 @@
 //!
 //! - `wgpu` contains the default renderer backend, leveraging `wgpu`.
 //! - `webgl` contains a WebGL2 backend specifically for `wasm32` if the `webgl` feature is active.
+//! - `backend` contains the interface for implementing other backends outside of this crate.
 
+pub mod backend;
 pub(crate) mod common;
+#[cfg(feature = "hot_reload")]
+mod hot_reload;
 #[cfg(feature = "probe")]
 mod probe;
+#[cfg(feature = "wgpu")]
+mod profiling;
+#[cfg(feature = "wgpu")]
+mod readback;
+#[cfg(feature = "wgpu")]
+mod upload;
 #[cfg(all(target_arch = "wasm32", feature = "webgl"))]
 mod webgl;
 #[cfg(feature = "wgpu")]
 mod wgpu;
 
-pub use common::{Config, GpuStrip, RenderSize};
+pub use common::{Config, DynamicRange, GpuStrip, OutputColorConfig, RenderSize, WorkingSpace};
 
+#[cfg(feature = "hot_reload")]
+pub use hot_reload::{HotReloadError, ShaderSet, ShaderWatcher, parse_parameters};
+
+#[cfg(feature = "wgpu")]
+pub use profiling::{FrameProfile, ProfileStage};
+#[cfg(feature = "wgpu")]
+pub use readback::{Readback, ReadbackImage};
 #[cfg(all(target_arch = "wasm32", feature = "webgl", feature = "probe"))]
 pub use vello_common::probe::{Probe, ProbeResult};
 #[cfg(all(target_arch = "wasm32", feature = "webgl"))]
 pub use webgl::{WebGlAtlasWriter, WebGlRenderer, WebGlTextureWithDimensions};
 #[cfg(feature = "wgpu")]
-pub use wgpu::{AtlasWriter, RenderTargetConfig, Renderer, TextureBindings};
+pub use wgpu::{
+    AtlasWriter, RenderTargetConfig, Renderer, SurfaceTarget, TextureBindings, Viewport,
+};
@@ -0,0 +1,271 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! GPU timestamp queries around the render passes of the wgpu renderer.
+//!
+//! When profiling is enabled with [`Renderer::set_profiling`](crate::Renderer::set_profiling),
+//! every render pass of a frame writes a timestamp at its start and end. The timestamps are
+//! resolved into a buffer at the end of the frame and read back asynchronously once the GPU
+//! has finished the frame, see [`Renderer::frame_profiles`](crate::Renderer::frame_profiles).
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU8, Ordering};
+use core::time::Duration;
+use wgpu::{Buffer, CommandEncoder, Device, QuerySet, Queue, RenderPassTimestampWrites};
+
+/// The maximum number of render passes that are timed per frame.
+///
+/// Later passes of a frame are counted in [`FrameProfile::untimed_passes`].
+const MAX_TIMED_PASSES: u32 = 256;
+
+/// The stages of a frame that GPU time is attributed to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ProfileStage {
+    /// Clearing the render target, the slot textures and the filter atlases.
+    Clear,
+    /// Drawing strips into the slot textures and filter layers, which hold the contents of
+    /// layers until they are composited.
+    Layers,
+    /// Drawing strips into the render target, including compositing layers into it.
+    Target,
+    /// Applying filter effects.
+    Filters,
+}
+
+/// The GPU durations of the render passes of a frame, grouped by [`ProfileStage`].
+///
+/// Uploads of strips, alphas and paints are written with the queue before the frame's
+/// command buffer runs, so they aren't part of any stage.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FrameProfile {
+    /// Time spent clearing textures.
+    pub clear: Duration,
+    /// Time spent drawing into slot textures and filter layers.
+    pub layers: Duration,
+    /// Time spent drawing into the render target.
+    pub target: Duration,
+    /// Time spent applying filter effects.
+    pub filters: Duration,
+    /// The number of render passes that were timed.
+    pub timed_passes: u32,
+    /// The number of render passes that weren't timed, because the frame had more passes
+    /// than can be timed.
+    pub untimed_passes: u32,
+}
+
+impl FrameProfile {
+    /// Return the time spent in the given stage.
+    pub fn stage(&self, stage: ProfileStage) -> Duration {
+        match stage {
+            ProfileStage::Clear => self.clear,
+            ProfileStage::Layers => self.layers,
+            ProfileStage::Target => self.target,
+            ProfileStage::Filters => self.filters,
+        }
+    }
+
+    /// Return the time spent in all stages.
+    pub fn total(&self) -> Duration {
+        self.clear + self.layers + self.target + self.filters
+    }
+
+    fn stage_mut(&mut self, stage: ProfileStage) -> &mut Duration {
+        match stage {
+            ProfileStage::Clear => &mut self.clear,
+            ProfileStage::Layers => &mut self.layers,
+            ProfileStage::Target => &mut self.target,
+            ProfileStage::Filters => &mut self.filters,
+        }
+    }
+}
+
+/// The state of the read back of a frame's timestamps.
+const READBACK_UNMAPPED: u8 = 0;
+const READBACK_PENDING: u8 = 1;
+const READBACK_MAPPED: u8 = 2;
+const READBACK_FAILED: u8 = 3;
+
+/// A frame whose timestamps were resolved, but not read back yet.
+#[derive(Debug)]
+struct PendingFrame {
+    /// The buffer the timestamps were copied into.
+    buffer: Buffer,
+    /// The stage of each timed pass.
+    stages: Vec<ProfileStage>,
+    untimed_passes: u32,
+    /// One of the `READBACK_*` states.
+    state: Arc<AtomicU8>,
+}
+
+/// Records the timestamps of the render passes of frames.
+#[derive(Debug)]
+pub(crate) struct GpuProfiler {
+    query_set: QuerySet,
+    resolve_buffer: Buffer,
+    /// Nanoseconds per timestamp tick.
+    timestamp_period: f64,
+    /// The stages of the passes timed in the current frame.
+    stages: Vec<ProfileStage>,
+    untimed_passes: u32,
+    /// Whether passes are currently being timed.
+    in_frame: bool,
+    pending: Vec<PendingFrame>,
+    /// Read back buffers of frames that were already read.
+    spare_buffers: Vec<Buffer>,
+}
+
+impl GpuProfiler {
+    /// Create a profiler, or return `None` if `device` doesn't support timestamp queries.
+    pub(crate) fn new(device: &Device, queue: &Queue) -> Option<Self> {
+        if !device.features().contains(wgpu::Features::TIMESTAMP_QUERY) {
+            return None;
+        }
+
+        let query_set = device.create_query_set(&wgpu::QuerySetDescriptor {
+            label: Some("Frame Profile Query Set"),
+            ty: wgpu::QueryType::Timestamp,
+            count: MAX_TIMED_PASSES * 2,
+        });
+        let resolve_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Frame Profile Resolve Buffer"),
+            size: Self::buffer_size(),
+            usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+
+        Some(Self {
+            query_set,
+            resolve_buffer,
+            timestamp_period: f64::from(queue.get_timestamp_period()),
+            stages: Vec::new(),
+            untimed_passes: 0,
+            in_frame: false,
+            pending: Vec::new(),
+            spare_buffers: Vec::new(),
+        })
+    }
+
+    fn buffer_size() -> u64 {
+        u64::from(MAX_TIMED_PASSES) * 2 * u64::from(wgpu::QUERY_SIZE)
+    }
+
+    /// Start timing the passes of a new frame.
+    pub(crate) fn begin_frame(&mut self) {
+        self.stages.clear();
+        self.untimed_passes = 0;
+        self.in_frame = true;
+    }
+
+    /// Return the timestamp writes for the next render pass, which belongs to `stage`.
+    ///
+    /// Returns `None` outside of frames and once the frame has used up all queries.
+    pub(crate) fn timestamp_writes(
+        &mut self,
+        stage: ProfileStage,
+    ) -> Option<RenderPassTimestampWrites<'_>> {
+        if !self.in_frame {
+            return None;
+        }
+        let pass = u32::try_from(self.stages.len()).unwrap();
+        if pass == MAX_TIMED_PASSES {
+            self.untimed_passes += 1;
+            return None;
+        }
+
+        self.stages.push(stage);
+        Some(RenderPassTimestampWrites {
+            query_set: &self.query_set,
+            beginning_of_pass_write_index: Some(pass * 2),
+            end_of_pass_write_index: Some(pass * 2 + 1),
+        })
+    }
+
+    /// Resolve the timestamps of the current frame into a buffer that is read back later.
+    pub(crate) fn end_frame(&mut self, device: &Device, encoder: &mut CommandEncoder) {
+        self.in_frame = false;
+        if self.stages.is_empty() {
+            return;
+        }
+
+        let query_count = u32::try_from(self.stages.len()).unwrap() * 2;
+        let size = u64::from(query_count) * u64::from(wgpu::QUERY_SIZE);
+        let buffer = self.spare_buffers.pop().unwrap_or_else(|| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Frame Profile Read Buffer"),
+                size: Self::buffer_size(),
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        });
+        encoder.resolve_query_set(&self.query_set, 0..query_count, &self.resolve_buffer, 0);
+        encoder.copy_buffer_to_buffer(&self.resolve_buffer, 0, &buffer, 0, size);
+
+        self.pending.push(PendingFrame {
+            buffer,
+            stages: core::mem::take(&mut self.stages),
+            untimed_passes: self.untimed_passes,
+            state: Arc::new(AtomicU8::new(READBACK_UNMAPPED)),
+        });
+    }
+
+    /// Start reading back all resolved frames and return the profiles of the frames that
+    /// are done, in the order they were rendered.
+    pub(crate) fn collect(&mut self) -> Vec<FrameProfile> {
+        for frame in &self.pending {
+            if frame.state.load(Ordering::Acquire) != READBACK_UNMAPPED {
+                continue;
+            }
+            frame.state.store(READBACK_PENDING, Ordering::Release);
+            let state = frame.state.clone();
+            frame
+                .buffer
+                .slice(..)
+                .map_async(wgpu::MapMode::Read, move |result| {
+                    let state_after = if result.is_ok() {
+                        READBACK_MAPPED
+                    } else {
+                        READBACK_FAILED
+                    };
+                    state.store(state_after, Ordering::Release);
+                });
+        }
+
+        // Frames complete in order, so stop at the first one that isn't done yet.
+        let done = self
+            .pending
+            .iter()
+            .take_while(|frame| frame.state.load(Ordering::Acquire) >= READBACK_MAPPED)
+            .count();
+        let mut profiles = Vec::with_capacity(done);
+        for frame in self.pending.drain(..done) {
+            if frame.state.load(Ordering::Acquire) == READBACK_FAILED {
+                continue;
+            }
+
+            let mut profile = FrameProfile {
+                timed_passes: u32::try_from(frame.stages.len()).unwrap(),
+                untimed_passes: frame.untimed_passes,
+                ..FrameProfile::default()
+            };
+            {
+                let data = frame.buffer.slice(..).get_mapped_range();
+                let timestamps: &[u64] = bytemuck::cast_slice(&data);
+                for (stage, pass) in frame.stages.iter().zip(timestamps.chunks_exact(2)) {
+                    let ticks = pass[1].saturating_sub(pass[0]);
+                    #[expect(
+                        clippy::cast_possible_truncation,
+                        reason = "pass durations are far below the range of u64 nanoseconds"
+                    )]
+                    let nanos = (ticks as f64 * self.timestamp_period) as u64;
+                    *profile.stage_mut(*stage) += Duration::from_nanos(nanos);
+                }
+            }
+            frame.buffer.unmap();
+            self.spare_buffers.push(frame.buffer);
+            profiles.push(profile);
+        }
+
+        profiles
+    }
+}
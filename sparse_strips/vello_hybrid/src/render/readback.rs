@@ -0,0 +1,172 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Non-blocking copies of rendered textures into CPU memory.
+
+use alloc::vec::Vec;
+use vello_common::peniko::color::PremulRgba8;
+use vello_common::pixmap::Pixmap;
+use wgpu::{Buffer, BufferAsyncError, CommandEncoder, Device, Texture, TextureFormat};
+
+/// A copy of a texture into CPU memory that completes once the GPU is done with it.
+///
+/// This is meant for taking screenshots or generating thumbnails of rendered frames without
+/// blocking the render loop on the device:
+///
+/// 1. Render into a texture with [`TextureUsages::COPY_SRC`](wgpu::TextureUsages::COPY_SRC)
+///    and create the readback with the same command encoder, using [`Readback::new`].
+/// 2. Submit the encoder.
+/// 3. Call [`Readback::read`] with a callback that receives the pixels.
+///
+/// The callback is invoked by wgpu when the copy has finished and the device is polled, for
+/// example by polling it with [`PollType::Poll`](wgpu::PollType::Poll) once per frame. On the
+/// web, the browser invokes it on its own.
+#[derive(Debug)]
+pub struct Readback {
+    buffer: Buffer,
+    width: u32,
+    height: u32,
+    format: TextureFormat,
+    /// The number of bytes per row in the buffer, which is padded to the copy alignment.
+    padded_bytes_per_row: u32,
+}
+
+/// The pixels of a texture, read back with a [`Readback`].
+#[derive(Clone, Debug)]
+pub struct ReadbackImage {
+    /// The width of the image.
+    pub width: u32,
+    /// The height of the image.
+    pub height: u32,
+    /// The format of the texture the image was read from.
+    pub format: TextureFormat,
+    /// The rows of the image, without padding.
+    pub data: Vec<u8>,
+}
+
+impl ReadbackImage {
+    /// Convert the image into a [`Pixmap`].
+    ///
+    /// Returns `None` if the image isn't in one of the RGBA8 or BGRA8 formats, or is too
+    /// large for a pixmap. Like the textures it was rendered into, the pixmap holds
+    /// premultiplied colors.
+    pub fn to_pixmap(&self) -> Option<Pixmap> {
+        let swap_red_blue = match self.format {
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => false,
+            TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => true,
+            _ => return None,
+        };
+        let width = u16::try_from(self.width).ok()?;
+        let height = u16::try_from(self.height).ok()?;
+
+        let pixels = self
+            .data
+            .chunks_exact(4)
+            .map(|pixel| {
+                let [r, g, b, a] = [pixel[0], pixel[1], pixel[2], pixel[3]];
+                let (r, b) = if swap_red_blue { (b, r) } else { (r, b) };
+                PremulRgba8 { r, g, b, a }
+            })
+            .collect();
+
+        Some(Pixmap::from_parts(pixels, width, height))
+    }
+}
+
+impl Readback {
+    /// Record a copy of the first layer of `texture` into `encoder`.
+    ///
+    /// The texture needs [`TextureUsages::COPY_SRC`](wgpu::TextureUsages::COPY_SRC), and must
+    /// have an uncompressed color format.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the format of `texture` has no fixed size per texel.
+    pub fn new(device: &Device, encoder: &mut CommandEncoder, texture: &Texture) -> Self {
+        let format = texture.format();
+        let bytes_per_pixel = format
+            .block_copy_size(None)
+            .expect("the texture must have a color format with a fixed texel size");
+        let (width, height) = (texture.width(), texture.height());
+        let padded_bytes_per_row =
+            (width * bytes_per_pixel).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback Buffer"),
+            size: u64::from(padded_bytes_per_row) * u64::from(height),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        Self {
+            buffer,
+            width,
+            height,
+            format,
+            padded_bytes_per_row,
+        }
+    }
+
+    /// Read the copied pixels and pass them to `callback` once the copy has finished.
+    ///
+    /// This must only be called after the encoder that the readback was created with has
+    /// been submitted. It doesn't block; see the [type documentation](Self) for when
+    /// `callback` is invoked.
+    pub fn read(
+        self,
+        callback: impl FnOnce(Result<ReadbackImage, BufferAsyncError>) + wgpu::WasmNotSend + 'static,
+    ) {
+        let buffer = self.buffer.clone();
+        buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                callback(result.map(|()| self.take_image()));
+            });
+    }
+
+    /// Copy the rows out of the mapped buffer, dropping the padding.
+    fn take_image(self) -> ReadbackImage {
+        let bytes_per_row = (self.width
+            * self
+                .format
+                .block_copy_size(None)
+                .expect("checked when the readback was created"))
+            as usize;
+        let mut data = Vec::with_capacity(bytes_per_row * self.height as usize);
+        {
+            let mapped = self.buffer.slice(..).get_mapped_range();
+            for row in mapped.chunks_exact(self.padded_bytes_per_row as usize) {
+                data.extend_from_slice(&row[..bytes_per_row]);
+            }
+        }
+        self.buffer.unmap();
+
+        ReadbackImage {
+            width: self.width,
+            height: self.height,
+            format: self.format,
+            data,
+        }
+    }
+}
@@ -0,0 +1,212 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Recycled staging buffers for the per-frame uploads of the wgpu renderer.
+//!
+//! Strips, slot indices, filter instances, alphas, encoded paints and gradient LUTs are
+//! written into mapped staging buffers and copied to their destination with the command
+//! encoder of the frame. Unlike writes through the queue, the copies are ordered with the
+//! render passes, so a single destination buffer can be reused by all passes of a frame.
+//!
+//! Staging buffers are unmapped at the end of each render and mapped again once the command
+//! encoder of the render was submitted and the GPU is done with them, so that later frames can
+//! reuse them instead of allocating new ones. Until then, the encoder can be used to record
+//! more renders, which write into other staging buffers.
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicU8, Ordering};
+use wgpu::{Buffer, CommandEncoder, Device, Queue, Texture};
+
+/// The smallest size of a staging buffer.
+const MIN_CHUNK_SIZE: u64 = 1 << 16;
+/// The alignment of the data in staging buffers, which satisfies the alignment requirements
+/// of all copies.
+const ALIGNMENT: u64 = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64;
+
+/// The state of a staging buffer that is being mapped again.
+const MAP_PENDING: u8 = 0;
+const MAP_DONE: u8 = 1;
+const MAP_FAILED: u8 = 2;
+
+/// A staging buffer and the part of it that is already used.
+#[derive(Debug)]
+struct Chunk {
+    buffer: Buffer,
+    used: u64,
+}
+
+impl Chunk {
+    fn size(&self) -> u64 {
+        self.buffer.size()
+    }
+}
+
+/// Hands out space in mapped staging buffers and recycles them across frames.
+#[derive(Debug, Default)]
+pub(crate) struct UploadBelt {
+    /// Chunks that are mapped and written in the current render.
+    active: Vec<Chunk>,
+    /// Chunks that are mapped again once their encoder is submitted, with the state set by
+    /// the map callback.
+    recalled: Vec<(Chunk, Arc<AtomicU8>)>,
+    /// Chunks that are mapped and unused.
+    free: Vec<Chunk>,
+}
+
+impl UploadBelt {
+    /// Copy the concatenation of `parts` to the start of `target`.
+    pub(crate) fn copy_to_buffer(
+        &mut self,
+        device: &Device,
+        encoder: &mut CommandEncoder,
+        target: &Buffer,
+        parts: &[&[u8]],
+    ) {
+        let size = parts.iter().map(|part| part.len() as u64).sum();
+        if size == 0 {
+            return;
+        }
+
+        let (buffer, offset) = self.write(device, size, parts);
+        encoder.copy_buffer_to_buffer(&buffer, offset, target, 0, size);
+    }
+
+    /// Copy `data` into all layers of `texture`.
+    ///
+    /// `data` must have `bytes_per_row` bytes for each row of each layer of the texture. Rows that don't
+    /// have the alignment of buffer to texture copies are written through the queue instead.
+    pub(crate) fn copy_to_texture(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        texture: &Texture,
+        data: &[u8],
+        bytes_per_row: u32,
+    ) {
+        let destination = wgpu::TexelCopyTextureInfo {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        };
+        let layout = wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(bytes_per_row),
+            rows_per_image: Some(texture.height()),
+        };
+        let size = wgpu::Extent3d {
+            width: texture.width(),
+            height: texture.height(),
+            depth_or_array_layers: texture.depth_or_array_layers(),
+        };
+
+        if u64::from(bytes_per_row) % ALIGNMENT != 0 {
+            queue.write_texture(destination, data, layout, size);
+            return;
+        }
+
+        let (buffer, offset) = self.write(device, data.len() as u64, &[data]);
+        encoder.copy_buffer_to_texture(
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout { offset, ..layout },
+            },
+            destination,
+            size,
+        );
+    }
+
+    /// Write `parts` into a mapped staging buffer and return the buffer and the offset of
+    /// the data in it.
+    fn write(&mut self, device: &Device, size: u64, parts: &[&[u8]]) -> (Buffer, u64) {
+        let aligned_size = size.next_multiple_of(ALIGNMENT);
+        let chunk_idx = match self
+            .active
+            .iter()
+            .position(|chunk| chunk.size() - chunk.used >= aligned_size)
+        {
+            Some(idx) => idx,
+            None => {
+                let chunk = self.take_free_chunk(aligned_size).unwrap_or_else(|| Chunk {
+                    buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                        label: Some("Upload Staging Buffer"),
+                        size: aligned_size.max(MIN_CHUNK_SIZE).next_power_of_two(),
+                        usage: wgpu::BufferUsages::MAP_WRITE | wgpu::BufferUsages::COPY_SRC,
+                        mapped_at_creation: true,
+                    }),
+                    used: 0,
+                });
+                self.active.push(chunk);
+                self.active.len() - 1
+            }
+        };
+
+        let chunk = &mut self.active[chunk_idx];
+        let offset = chunk.used;
+        chunk.used += aligned_size;
+        {
+            let mut view = chunk
+                .buffer
+                .slice(offset..offset + aligned_size)
+                .get_mapped_range_mut();
+            let mut start = 0;
+            for part in parts {
+                view.slice(start..start + part.len()).copy_from_slice(part);
+                start += part.len();
+            }
+        }
+
+        (chunk.buffer.clone(), offset)
+    }
+
+    /// Take the smallest free chunk that can hold `size` bytes.
+    fn take_free_chunk(&mut self, size: u64) -> Option<Chunk> {
+        let idx = self
+            .free
+            .iter()
+            .enumerate()
+            .filter(|(_, chunk)| chunk.size() >= size)
+            .min_by_key(|(_, chunk)| chunk.size())
+            .map(|(idx, _)| idx)?;
+
+        Some(self.free.swap_remove(idx))
+    }
+
+    /// Unmap the chunks written in the current render, so that the commands of `encoder` can
+    /// be submitted, and map them again once they were.
+    ///
+    /// If `encoder` is dropped without being submitted, its chunks are never reused.
+    pub(crate) fn finish(&mut self, encoder: &CommandEncoder) {
+        for chunk in self.active.drain(..) {
+            chunk.buffer.unmap();
+            let state = Arc::new(AtomicU8::new(MAP_PENDING));
+            let callback_state = state.clone();
+            encoder.map_buffer_on_submit(&chunk.buffer, wgpu::MapMode::Write, .., move |result| {
+                let done = if result.is_ok() { MAP_DONE } else { MAP_FAILED };
+                callback_state.store(done, Ordering::Release);
+            });
+            self.recalled.push((chunk, state));
+        }
+    }
+
+    /// Make the chunks whose mapping completed available for writing.
+    pub(crate) fn recall(&mut self) {
+        let mut idx = 0;
+        while idx < self.recalled.len() {
+            match self.recalled[idx].1.load(Ordering::Acquire) {
+                MAP_PENDING => idx += 1,
+                MAP_DONE => {
+                    let (mut chunk, _) = self.recalled.swap_remove(idx);
+                    chunk.used = 0;
+                    self.free.push(chunk);
+                }
+                // The chunk is dropped, and a new one is created when needed.
+                _ => {
+                    self.recalled.swap_remove(idx);
+                }
+            }
+        }
+    }
+}
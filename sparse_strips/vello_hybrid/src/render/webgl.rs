@@ -25,15 +25,12 @@ use crate::{
     GpuStrip, RenderError, RenderSettings, RenderSize, Resources,
     filter::{FilterContext, FilterInstanceData, FilterPassState, FilterPassTarget},
     gradient_cache::GradientRampCache,
+    pattern::PatternUpload,
     render::{
         Config,
         common::{
-            GPU_BLURRED_ROUNDED_RECT_SIZE_TEXELS, GPU_ENCODED_IMAGE_SIZE_TEXELS,
-            GPU_LINEAR_GRADIENT_SIZE_TEXELS, GPU_RADIAL_GRADIENT_SIZE_TEXELS,
-            GPU_SWEEP_GRADIENT_SIZE_TEXELS, GpuBlurredRoundedRect, GpuEncodedImage,
-            GpuEncodedPaint, GpuLinearGradient, GpuRadialGradient, GpuSweepGradient,
-            normalize_atlas_config, pack_image_offset, pack_image_params, pack_image_size,
-            pack_radial_kind_and_swapped, pack_texture_width_and_extend_mode, pack_tint,
+            GpuEncodedPaint, OUTPUT_TRANSFER_NONE, encode_gpu_paints, linear_texture_extent,
+            normalize_atlas_config,
         },
     },
     scene::Scene,
@@ -48,21 +45,24 @@ use bytemuck::{Pod, Zeroable};
 use core::fmt::Debug;
 #[cfg(feature = "text")]
 use glifo::{GLYPH_PADDING, PendingClearRect};
-use vello_common::image_cache::{ImageCache, ImageResource};
+use vello_common::encode::GradientLutCache;
+use vello_common::image_cache::ImageCache;
+use vello_common::load_op::LoadOp as TargetLoadOp;
+use vello_common::mipmap::MipChain;
 #[cfg(feature = "probe")]
 use vello_common::multi_atlas::AllocationStrategy;
 use vello_common::multi_atlas::{AtlasConfig, AtlasId};
 #[cfg(feature = "probe")]
+use vello_common::paint::ImageSource;
+use vello_common::peniko::color::ColorSpaceTag;
+#[cfg(feature = "probe")]
 use vello_common::probe::Probe;
 use vello_common::render_graph::LayerId;
+use vello_common::render_stats::RenderStats;
 use vello_common::{
     coarse::WideTile,
-    encode::{
-        EncodedBlurredRoundedRectangle, EncodedGradient, EncodedKind, EncodedPaint,
-        MAX_GRADIENT_LUT_SIZE, RadialKind,
-    },
-    paint::{ImageId, ImageSource},
-    peniko::{self},
+    encode::{EncodedPaint, MAX_GRADIENT_LUT_SIZE},
+    paint::{ImageId, PremulColor},
     pixmap::Pixmap,
     tile::Tile,
 };
@@ -73,13 +73,6 @@ use web_sys::{
     WebGlTexture, WebGlUniformLocation, WebGlVertexArrayObject,
 };
 
-/// Placeholder value for uninitialized GPU encoded paints.
-const GPU_PAINT_PLACEHOLDER: GpuEncodedPaint = GpuEncodedPaint::LinearGradient(GpuLinearGradient {
-    texture_width_and_extend_mode: 0,
-    gradient_start: 0,
-    transform: [0.0; 6],
-});
-
 /// Query the WebGL context for the max texture size.
 fn get_max_texture_dimension_2d(gl: &WebGl2RenderingContext) -> u32 {
     gl.get_parameter(WebGl2RenderingContext::MAX_TEXTURE_SIZE)
@@ -117,6 +110,8 @@ pub struct WebGlRenderer {
     /// State used for constructing filter passes.
     filter_pass_state: FilterPassState,
     dummy_image_cache: Option<ImageCache>,
+    /// Statistics about the last rendered scene.
+    stats: RenderStats,
 }
 
 impl WebGlRenderer {
@@ -225,6 +220,7 @@ impl WebGlRenderer {
             filter_context,
             filter_pass_state: FilterPassState::default(),
             dummy_image_cache: Some(ImageCache::new_dummy()),
+            stats: RenderStats::default(),
         }
     }
 
@@ -245,6 +241,7 @@ impl WebGlRenderer {
             *render_size,
             "Render size must match drawing buffer size"
         );
+        self.programs.frame_stats = RenderStats::default();
 
         #[cfg(feature = "text")]
         {
@@ -270,7 +267,7 @@ impl WebGlRenderer {
             scene,
             &mut resources.image_cache,
             render_size,
-            true,
+            scene.target_load_op(),
             RootRenderTarget::UserSurface,
         )?;
 
@@ -280,6 +277,11 @@ impl WebGlRenderer {
                 clear_atlas_region(renderer, rect);
             });
         }
+        self.stats = RenderStats {
+            alpha_bytes: self.programs.frame_stats.alpha_bytes,
+            draw_calls: self.programs.frame_stats.draw_calls,
+            ..scene.render_stats()
+        };
 
         Ok(())
     }
@@ -353,7 +355,7 @@ impl WebGlRenderer {
             scene,
             &mut dummy_image_cache,
             &atlas_render_size,
-            false,
+            TargetLoadOp::Load,
             RootRenderTarget::AtlasLayer,
         );
         self.dummy_image_cache = Some(dummy_image_cache);
@@ -465,7 +467,7 @@ impl WebGlRenderer {
             &scene,
             &mut probe_image_cache,
             &render_size,
-            true,
+            TargetLoadOp::ClearTransparent,
             RootRenderTarget::AtlasLayer,
         );
         self.programs.resources.view_framebuffer_override = previous_view_framebuffer;
@@ -494,16 +496,16 @@ impl WebGlRenderer {
     /// Shared render pipeline: prepares GPU resources, runs the scheduler, and
     /// maintains caches.
     ///
-    /// When `clear` is true the view framebuffer is cleared to transparent black
-    /// before drawing. This must happen *after* `prepare` (which may create/resize
-    /// the framebuffer attachment). Atlas renders skip the clear so previously
-    /// rendered atlas content is preserved.
+    /// The view framebuffer is cleared according to `load_op` before drawing. This must
+    /// happen *after* `prepare` (which may create/resize the framebuffer attachment).
+    /// Atlas renders use [`TargetLoadOp::Load`] so previously rendered atlas content is
+    /// preserved.
     fn render_scene(
         &mut self,
         scene: &Scene,
         image_cache: &mut ImageCache,
         render_size: &RenderSize,
-        clear: bool,
+        load_op: TargetLoadOp,
         root_output_target: RootRenderTarget,
     ) -> Result<(), RenderError> {
         if !self.filter_context.filter_textures.is_empty() {
@@ -516,10 +518,14 @@ impl WebGlRenderer {
         let mut encoded_paints = scene.encoded_paints.borrow_mut();
         let original_scene_paint_count = encoded_paints.len();
 
-        self.filter_context
-            .prepare(&scene.render_graph, image_cache, &mut encoded_paints)?;
+        self.filter_context.prepare(
+            &scene.render_graph,
+            image_cache,
+            &mut encoded_paints,
+            scene.quality(),
+        )?;
 
-        self.prepare_gpu_encoded_paints(&encoded_paints, image_cache);
+        self.prepare_gpu_encoded_paints(&encoded_paints, image_cache)?;
 
         self.programs
             .maybe_resize_atlas_texture_array(&self.gl, image_cache.atlas_count() as u32);
@@ -541,10 +547,13 @@ impl WebGlRenderer {
             &self.filter_context,
         );
 
-        if clear {
-            self.programs.clear_view_framebuffer(&self.gl);
+        if let Some(color) = load_op.clear_color() {
+            self.programs.clear_view_framebuffer(&self.gl, color);
         }
         self.programs.resources.depth_cleared_this_frame = false;
+        self.programs.resources.opaque_target =
+            matches!(root_output_target, RootRenderTarget::UserSurface)
+                && scene.has_opaque_result();
         let mut ctx = WebGlRendererContext {
             programs: &mut self.programs,
             gl: &self.gl,
@@ -591,6 +600,24 @@ impl WebGlRenderer {
         &self.gl
     }
 
+    /// Return statistics about the scene that was rendered last.
+    ///
+    /// Besides the paths and strips counted by [`Scene::render_stats`], this includes the
+    /// alpha bytes that were uploaded and the draw calls that were issued for the frame,
+    /// including those for rendering glyphs into the atlas.
+    pub fn render_stats(&self) -> RenderStats {
+        self.stats
+    }
+
+    /// Set the cache that gradient ramps are resolved through.
+    ///
+    /// Sharing a [`GradientLutCache`] with other renderers (for example a `vello_cpu` render
+    /// context that exports thumbnails of the same content) means that each gradient ramp is
+    /// only computed once. Ramps that are already resident on the GPU are not affected.
+    pub fn set_gradient_lut_cache(&mut self, cache: Option<GradientLutCache>) {
+        self.gradient_cache.set_lut_cache(cache);
+    }
+
     /// Upload image to cache and atlas in one step. Returns the `ImageId`.
     ///
     /// This is the WebGL analogue of the wgpu Renderer's `upload_image` method.
@@ -603,6 +630,113 @@ impl WebGlRenderer {
         self.upload_image_with(&mut resources.image_cache, writer, IMAGE_PADDING)
     }
 
+    /// Upload an image together with its mip chain and return its `ImageId`.
+    ///
+    /// This is the WebGL analogue of the wgpu Renderer's `upload_image_mipmapped` method.
+    pub fn upload_image_mipmapped(
+        &mut self,
+        resources: &mut Resources,
+        pixmap: Arc<Pixmap>,
+    ) -> ImageId {
+        let chain = MipChain::new(pixmap);
+        let mut ids = chain
+            .levels()
+            .iter()
+            .map(|level| self.upload_image_with(&mut resources.image_cache, level, IMAGE_PADDING));
+        let image_id = ids.next().unwrap();
+        let levels = ids.collect();
+        resources.image_cache.set_mip_levels(image_id, levels);
+
+        image_id
+    }
+
+    /// Rasterize a single cell of `pattern` into the atlas and return its `ImageId`.
+    ///
+    /// This is the WebGL analogue of the wgpu Renderer's `upload_pattern` method.
+    pub fn upload_pattern(
+        &mut self,
+        resources: &mut Resources,
+        pattern: &vello_common::pattern::Pattern,
+    ) -> Result<ImageId, RenderError> {
+        let upload = resources.prepare_pattern_upload(pattern)?;
+        let result = if upload.samples_atlas {
+            self.render_pattern_via_texture(resources, &upload)
+        } else {
+            self.render_to_atlas(
+                &upload.scene,
+                upload.atlas_count,
+                upload.atlas_config,
+                upload.atlas_id,
+            )
+        };
+        if let Err(e) = result {
+            resources.image_cache.deallocate(upload.image_id);
+            return Err(e);
+        }
+
+        Ok(upload.image_id)
+    }
+
+    /// Render a pattern cell that samples the atlas into a separate texture, and copy the
+    /// result into the cell's atlas region.
+    ///
+    /// This is the WebGL analogue of the wgpu Renderer's `render_pattern_via_texture` method.
+    fn render_pattern_via_texture(
+        &mut self,
+        resources: &mut Resources,
+        upload: &PatternUpload,
+    ) -> Result<(), RenderError> {
+        self.programs
+            .maybe_resize_atlas_texture_array(&self.gl, upload.atlas_count);
+
+        let render_size = RenderSize {
+            width: u32::from(upload.scene.width()),
+            height: u32::from(upload.scene.height()),
+        };
+        let texture = create_texture(&self.gl);
+        self.gl
+            .tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_array_buffer_view(
+                WebGl2RenderingContext::TEXTURE_2D,
+                0,
+                WebGl2RenderingContext::RGBA8 as i32,
+                render_size.width as i32,
+                render_size.height as i32,
+                0,
+                WebGl2RenderingContext::RGBA,
+                WebGl2RenderingContext::UNSIGNED_BYTE,
+                None,
+            )
+            .unwrap();
+        let framebuffer = create_framebuffer_for_texture(&self.gl, &texture);
+
+        let previous_view_framebuffer = self
+            .programs
+            .resources
+            .view_framebuffer_override
+            .replace(framebuffer.clone());
+        let result = self.render_scene(
+            &upload.scene,
+            &mut resources.image_cache,
+            &render_size,
+            TargetLoadOp::ClearTransparent,
+            RootRenderTarget::AtlasLayer,
+        );
+        self.programs.resources.view_framebuffer_override = previous_view_framebuffer;
+        self.gl.delete_framebuffer(Some(&framebuffer));
+
+        let texture = WebGlTextureWithDimensions {
+            texture,
+            width: render_size.width,
+            height: render_size.height,
+        };
+        if result.is_ok() {
+            self.write_to_atlas(&resources.image_cache, upload.image_id, &texture, None);
+        }
+        self.gl.delete_texture(Some(&texture.texture));
+
+        result
+    }
+
     pub(crate) fn upload_image_with<T: WebGlAtlasWriter>(
         &mut self,
         image_cache: &mut ImageCache,
@@ -652,7 +786,15 @@ impl WebGlRenderer {
 
     /// Destroy an image from the cache and clear the allocated slot in the atlas.
     pub fn destroy_image(&mut self, resources: &mut Resources, image_id: ImageId) {
-        if let Some(image_resource) = resources.image_cache.deallocate(image_id) {
+        let Some(image_resource) = resources.image_cache.deallocate(image_id) else {
+            return;
+        };
+        let mip_levels = image_resource.mip_levels().to_vec();
+        let mip_resources = mip_levels
+            .into_iter()
+            .filter_map(|id| resources.image_cache.deallocate(id));
+
+        for image_resource in core::iter::once(image_resource).chain(mip_resources) {
             let padding = image_resource.padding as u32;
             self.clear_atlas_region(
                 image_resource.atlas_id,
@@ -719,167 +861,19 @@ impl WebGlRenderer {
         &mut self,
         encoded_paints: &[EncodedPaint],
         image_cache: &ImageCache,
-    ) {
-        self.encoded_paints
-            .resize_with(encoded_paints.len(), || GPU_PAINT_PLACEHOLDER);
-        self.paint_idxs.resize(encoded_paints.len() + 1, 0);
-
-        let mut current_idx = 0;
-        for (encoded_paint_idx, paint) in encoded_paints.iter().enumerate() {
-            self.paint_idxs[encoded_paint_idx] = current_idx;
-            match paint {
-                EncodedPaint::Image(img) => {
-                    if let ImageSource::OpaqueId { id: image_id, .. } = img.source {
-                        let image_resource: Option<&ImageResource> = image_cache.get(image_id);
-                        if let Some(image_resource) = image_resource {
-                            let gpu_image = self.encode_image_paint(img, image_resource);
-                            self.encoded_paints[encoded_paint_idx] = gpu_image;
-                            current_idx += GPU_ENCODED_IMAGE_SIZE_TEXELS;
-                        }
-                    }
-                }
-                EncodedPaint::Gradient(gradient) => {
-                    let (gradient_start, gradient_width) =
-                        self.gradient_cache.get_or_create_ramp(gradient);
-                    let gpu_gradient =
-                        self.encode_gradient_paint(gradient, gradient_width, gradient_start);
-                    let gradient_size_texels = match &gpu_gradient {
-                        GpuEncodedPaint::LinearGradient(_) => GPU_LINEAR_GRADIENT_SIZE_TEXELS,
-                        GpuEncodedPaint::RadialGradient(_) => GPU_RADIAL_GRADIENT_SIZE_TEXELS,
-                        GpuEncodedPaint::SweepGradient(_) => GPU_SWEEP_GRADIENT_SIZE_TEXELS,
-                        _ => unreachable!("encode_gradient_for_gpu only returns gradient types"),
-                    };
-                    self.encoded_paints[encoded_paint_idx] = gpu_gradient;
-                    current_idx += gradient_size_texels;
-                }
-                EncodedPaint::ExternalTexture(_external_texture) => {
-                    // TODO: External textures are not yet supported.
-                    log::warn!("External textures are not yet supported in the WebGL backend");
-                    current_idx += GPU_ENCODED_IMAGE_SIZE_TEXELS;
-                }
-                EncodedPaint::BlurredRoundedRect(blurred_rect) => {
-                    self.encoded_paints[encoded_paint_idx] =
-                        Self::encode_blurred_rounded_rect_paint(blurred_rect);
-                    current_idx += GPU_BLURRED_ROUNDED_RECT_SIZE_TEXELS;
-                }
-            }
-        }
-        self.paint_idxs[encoded_paints.len()] = current_idx;
-    }
-
-    fn encode_image_paint(
-        &self,
-        image: &vello_common::encode::EncodedImage,
-        image_resource: &ImageResource,
-    ) -> GpuEncodedPaint {
-        let transform = image.transform.as_coeffs().map(|x| x as f32);
-        let image_size = pack_image_size(image_resource.width, image_resource.height);
-        let image_offset = pack_image_offset(image_resource.offset[0], image_resource.offset[1]);
-        let image_params = pack_image_params(
-            image.sampler.quality as u32,
-            image.sampler.x_extend as u32,
-            image.sampler.y_extend as u32,
-            image_resource.atlas_id.as_u32(),
-        );
-        let (tint, tint_mode) = pack_tint(image.tint);
-
-        GpuEncodedPaint::Image(GpuEncodedImage {
-            image_params,
-            image_size,
-            image_offset,
-            transform,
-            tint,
-            tint_mode,
-            image_padding: image_resource.padding as u32,
-        })
-    }
-
-    fn encode_gradient_paint(
-        &self,
-        gradient: &EncodedGradient,
-        gradient_width: u32,
-        gradient_start: u32,
-    ) -> GpuEncodedPaint {
-        let transform = gradient.transform.as_coeffs().map(|x| x as f32);
-        let extend_mode = match gradient.extend {
-            peniko::Extend::Pad => 0,
-            peniko::Extend::Repeat => 1,
-            peniko::Extend::Reflect => 2,
-        };
-        let texture_width_and_extend_mode =
-            pack_texture_width_and_extend_mode(gradient_width, extend_mode);
-
-        match &gradient.kind {
-            EncodedKind::Linear(_) => GpuEncodedPaint::LinearGradient(GpuLinearGradient {
-                texture_width_and_extend_mode,
-                gradient_start,
-                transform,
-            }),
-            EncodedKind::Radial(radial) => {
-                let (kind, bias, scale, fp0, fp1, fr1, f_focal_x, f_is_swapped, scaled_r0_squared) =
-                    match radial {
-                        RadialKind::Radial { bias, scale } => {
-                            (0, *bias, *scale, 0.0, 0.0, 0.0, 0.0, 0, 0.0)
-                        }
-                        RadialKind::Strip { scaled_r0_squared } => {
-                            (1, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0, *scaled_r0_squared)
-                        }
-                        RadialKind::Focal {
-                            focal_data,
-                            fp0,
-                            fp1,
-                        } => (
-                            2,
-                            *fp0,
-                            *fp1,
-                            *fp0,
-                            *fp1,
-                            focal_data.fr1,
-                            focal_data.f_focal_x,
-                            focal_data.f_is_swapped as u32,
-                            0.0,
-                        ),
-                    };
-                GpuEncodedPaint::RadialGradient(GpuRadialGradient {
-                    texture_width_and_extend_mode,
-                    gradient_start,
-                    transform,
-                    kind_and_f_is_swapped: pack_radial_kind_and_swapped(kind, f_is_swapped),
-                    bias,
-                    scale,
-                    fp0,
-                    fp1,
-                    fr1,
-                    f_focal_x,
-                    scaled_r0_squared,
-                })
-            }
-            EncodedKind::Sweep(sweep) => GpuEncodedPaint::SweepGradient(GpuSweepGradient {
-                texture_width_and_extend_mode,
-                gradient_start,
-                transform,
-                start_angle: sweep.start_angle,
-                inv_angle_delta: sweep.inv_angle_delta,
-                _padding: [0, 0],
-            }),
-        }
-    }
-
-    fn encode_blurred_rounded_rect_paint(rect: &EncodedBlurredRoundedRectangle) -> GpuEncodedPaint {
-        GpuEncodedPaint::BlurredRoundedRect(GpuBlurredRoundedRect {
-            transform: rect.transform.as_coeffs().map(|x| x as f32),
-            color: rect.color.as_premul_rgba8().to_u32(),
-            _padding0: 0,
-            params0: [
-                rect.exponent,
-                rect.recip_exponent,
-                rect.scale,
-                rect.std_dev_inv,
-            ],
-            params1: [rect.min_edge, rect.w, rect.h, rect.r1],
-            size: [rect.width, rect.height],
-            _padding1: [0, 0],
-        })
+    ) -> Result<(), RenderError> {
+        encode_gpu_paints(
+            encoded_paints,
+            image_cache,
+            &mut self.gradient_cache,
+            &mut self.encoded_paints,
+            &mut self.paint_idxs,
+            |_external_texture| {
+                // TODO: External textures are not yet supported.
+                log::warn!("External textures are not yet supported in the WebGL backend");
+                Ok(None)
+            },
+        )
     }
 }
 
@@ -922,6 +916,8 @@ struct WebGlPrograms {
     encoded_paints_data: Vec<u8>,
     /// Scratch buffer for staging filter data texture data.
     filter_data: Vec<u8>,
+    /// The alpha bytes uploaded and the draw calls issued for the current frame.
+    frame_stats: RenderStats,
 }
 
 #[derive(Debug)]
@@ -970,20 +966,26 @@ struct WebGlResources {
     strips_buffer: WebGlBuffer,
     /// Texture for alpha values (used by both view and slot rendering).
     alphas_texture: WebGlTexture,
-    /// Height of alpha texture.
+    /// Height of each layer of the alpha texture.
     alpha_texture_height: u32,
+    /// Number of layers of the alpha texture.
+    alpha_texture_layers: u32,
     /// Texture array for atlas data (multiple atlases supported)
     atlas_texture_array: WebGlTextureArray,
     /// Encoded paints texture for image metadata.
     encoded_paints_texture: WebGlTexture,
-    /// Height of encoded paints texture.
+    /// Height of each layer of the encoded paints texture.
     encoded_paints_texture_height: u32,
+    /// Number of layers of the encoded paints texture.
+    encoded_paints_texture_layers: u32,
     /// Gradient texture for gradient ramp data.
     gradient_texture: WebGlTexture,
     /// Placeholder texture bound to the strip shader's `external_texture` sampler.
     placeholder_external_texture: WebGlTexture,
-    /// Height of gradient texture.
+    /// Height of each layer of the gradient texture.
     gradient_texture_height: u32,
+    /// Number of layers of the gradient texture.
+    gradient_texture_layers: u32,
 
     /// Config buffer for rendering wide tile commands into the view texture.
     view_config_buffer: WebGlBuffer,
@@ -1000,6 +1002,9 @@ struct WebGlResources {
     view_framebuffer_override: Option<WebGlFramebuffer>,
     /// Whether the depth buffer has been cleared this frame.
     depth_cleared_this_frame: bool,
+    /// Whether the user surface is guaranteed to stay opaque this frame, see
+    /// [`Scene::has_opaque_result`].
+    opaque_target: bool,
     /// Pre-allocated JS array for `invalidateFramebuffer` calls.
     depth_attachment_array: js_sys::Array,
 
@@ -1011,6 +1016,8 @@ struct WebGlResources {
     /// Cached result from querying `WebGl2RenderingContext::MAX_TEXTURE_SIZE` which is a blocking
     /// WebGL call.
     max_texture_dimension_2d: u32,
+    /// Cached result from querying `WebGl2RenderingContext::MAX_ARRAY_TEXTURE_LAYERS`.
+    max_texture_array_layers: u32,
 
     /// Placeholder 1x1 atlas texture array, used during `render_to_atlas` to avoid
     /// binding the real atlas texture while it is also the render target.
@@ -1112,6 +1119,7 @@ impl WebGlPrograms {
             negate_ndc: false,
             encoded_paints_data,
             filter_data: Vec::new(),
+            frame_stats: RenderStats::default(),
         }
     }
 
@@ -1313,20 +1321,23 @@ impl WebGlPrograms {
 
     /// Update the alpha texture size if needed.
     fn maybe_resize_alphas_tex(&mut self, max_texture_dimension_2d: u32, alphas_len: usize) {
-        let required_alpha_height = (alphas_len as u32)
+        let required_alpha_rows = (alphas_len as u32)
             // There are 16 1-byte alpha values per texel.
             .div_ceil(max_texture_dimension_2d << 4);
 
-        let current_alpha_height = self.resources.alpha_texture_height;
-        if required_alpha_height > current_alpha_height {
-            // We need to resize the alpha texture to fit the new alpha data.
-            assert!(
-                required_alpha_height <= max_texture_dimension_2d,
-                "Alpha texture height exceeds max texture dimensions"
+        let current_alpha_rows =
+            self.resources.alpha_texture_height * self.resources.alpha_texture_layers;
+        if required_alpha_rows > current_alpha_rows {
+            // We need to resize the alpha texture to fit the new alpha data. Track the new size.
+            (
+                self.resources.alpha_texture_height,
+                self.resources.alpha_texture_layers,
+            ) = linear_texture_extent(
+                required_alpha_rows,
+                max_texture_dimension_2d,
+                self.resources.max_texture_array_layers,
+                1,
             );
-
-            // Track the new height.
-            self.resources.alpha_texture_height = required_alpha_height;
         }
     }
 
@@ -1337,19 +1348,22 @@ impl WebGlPrograms {
         paint_idxs: &[u32],
     ) {
         let required_texels = paint_idxs.last().unwrap();
-        let required_encoded_paints_height = required_texels.div_ceil(max_texture_dimension_2d);
-        let current_encoded_paints_height = self.resources.encoded_paints_texture_height;
-        if required_encoded_paints_height > current_encoded_paints_height {
-            assert!(
-                required_encoded_paints_height <= max_texture_dimension_2d,
-                "Encoded paints texture height exceeds max texture dimensions"
+        let required_encoded_paints_rows = required_texels.div_ceil(max_texture_dimension_2d);
+        let current_encoded_paints_rows = self.resources.encoded_paints_texture_height
+            * self.resources.encoded_paints_texture_layers;
+        if required_encoded_paints_rows > current_encoded_paints_rows {
+            let (height, layers) = linear_texture_extent(
+                required_encoded_paints_rows,
+                max_texture_dimension_2d,
+                self.resources.max_texture_array_layers,
+                1,
             );
 
-            let required_encoded_paints_size =
-                (max_texture_dimension_2d * required_encoded_paints_height) << 4;
+            let required_encoded_paints_size = (max_texture_dimension_2d * height * layers) << 4;
             self.encoded_paints_data
                 .resize(required_encoded_paints_size as usize, 0);
-            self.resources.encoded_paints_texture_height = required_encoded_paints_height;
+            self.resources.encoded_paints_texture_height = height;
+            self.resources.encoded_paints_texture_layers = layers;
         }
     }
 
@@ -1366,17 +1380,21 @@ impl WebGlPrograms {
 
         let gradient_data_size = gradient_cache.luts_size();
         // Each texel is RGBA8, so 4 bytes per texel
-        let required_gradient_height =
+        let required_gradient_rows =
             (gradient_data_size as u32).div_ceil(max_texture_dimension_2d * 4);
 
-        let current_gradient_height = self.resources.gradient_texture_height;
-        if required_gradient_height > current_gradient_height {
-            assert!(
-                required_gradient_height <= max_texture_dimension_2d,
-                "Gradient texture height exceeds max texture dimensions"
+        let current_gradient_rows =
+            self.resources.gradient_texture_height * self.resources.gradient_texture_layers;
+        if required_gradient_rows > current_gradient_rows {
+            (
+                self.resources.gradient_texture_height,
+                self.resources.gradient_texture_layers,
+            ) = linear_texture_extent(
+                required_gradient_rows,
+                max_texture_dimension_2d,
+                self.resources.max_texture_array_layers,
+                1,
             );
-
-            self.resources.gradient_texture_height = required_gradient_height;
         }
     }
 
@@ -1405,6 +1423,10 @@ impl WebGlPrograms {
                     strip_offset_x: 0,
                     strip_offset_y: 0,
                     negate_ndc: u32::from(negate_ndc),
+                    output_transfer: OUTPUT_TRANSFER_NONE,
+                    output_scale: 1.0,
+                    decode_paints: 0,
+                    _padding: 0,
                 };
 
                 gl.bind_buffer(
@@ -1432,6 +1454,10 @@ impl WebGlPrograms {
                     strip_offset_y: 0,
                     // Always use y-down when rendering to slots.
                     negate_ndc: 0,
+                    output_transfer: OUTPUT_TRANSFER_NONE,
+                    output_scale: 1.0,
+                    decode_paints: 0,
+                    _padding: 0,
                 };
 
                 gl.bind_buffer(
@@ -1481,24 +1507,30 @@ impl WebGlPrograms {
 
         let alpha_texture_width = self.resources.max_texture_dimension_2d;
         let alpha_texture_height = self.resources.alpha_texture_height;
-        let total_size = alpha_texture_width as usize * alpha_texture_height as usize * 16;
+        let alpha_texture_layers = self.resources.alpha_texture_layers;
+        let total_size = alpha_texture_width as usize
+            * alpha_texture_height as usize
+            * alpha_texture_layers as usize
+            * 16;
 
         let original_len = alphas.len();
+        self.frame_stats.alpha_bytes += original_len as u64;
 
         // Temporarily pad the length of the alphas to the texture size before uploading.
         alphas.resize(total_size, 0);
 
         gl.active_texture(WebGl2RenderingContext::TEXTURE0);
         gl.bind_texture(
-            WebGl2RenderingContext::TEXTURE_2D,
+            WebGl2RenderingContext::TEXTURE_2D_ARRAY,
             Some(&self.resources.alphas_texture),
         );
 
-        upload_data_to_rgba32_texture(
+        upload_data_to_rgba32_texture_array(
             gl,
             bytemuck::cast_slice::<u8, u32>(alphas),
             alpha_texture_width,
             alpha_texture_height,
+            alpha_texture_layers,
         );
 
         // Truncate back to the original size.
@@ -1514,20 +1546,22 @@ impl WebGlPrograms {
         if !encoded_paints.is_empty() {
             let encoded_paints_texture_width = self.resources.max_texture_dimension_2d;
             let encoded_paints_texture_height = self.resources.encoded_paints_texture_height;
+            let encoded_paints_texture_layers = self.resources.encoded_paints_texture_layers;
 
             GpuEncodedPaint::serialize_to_buffer(encoded_paints, &mut self.encoded_paints_data);
 
             gl.active_texture(WebGl2RenderingContext::TEXTURE0);
             gl.bind_texture(
-                WebGl2RenderingContext::TEXTURE_2D,
+                WebGl2RenderingContext::TEXTURE_2D_ARRAY,
                 Some(&self.resources.encoded_paints_texture),
             );
 
-            upload_data_to_rgba32_texture(
+            upload_data_to_rgba32_texture_array(
                 gl,
                 bytemuck::cast_slice::<u8, u32>(&self.encoded_paints_data),
                 encoded_paints_texture_width,
                 encoded_paints_texture_height,
+                encoded_paints_texture_layers,
             );
         }
     }
@@ -1544,7 +1578,10 @@ impl WebGlPrograms {
 
         let gradient_texture_width = self.resources.max_texture_dimension_2d;
         let gradient_texture_height = self.resources.gradient_texture_height;
-        let total_capacity = (gradient_texture_width * gradient_texture_height * 4) as usize;
+        let gradient_texture_layers = self.resources.gradient_texture_layers;
+        let total_capacity =
+            (gradient_texture_width * gradient_texture_height * gradient_texture_layers * 4)
+                as usize;
 
         // Take ownership of the luts to avoid copying, then resize for texture padding.
         let mut luts = gradient_cache.take_luts();
@@ -1553,16 +1590,17 @@ impl WebGlPrograms {
 
         gl.active_texture(WebGl2RenderingContext::TEXTURE0);
         gl.bind_texture(
-            WebGl2RenderingContext::TEXTURE_2D,
+            WebGl2RenderingContext::TEXTURE_2D_ARRAY,
             Some(&self.resources.gradient_texture),
         );
 
-        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
-            WebGl2RenderingContext::TEXTURE_2D,
+        gl.tex_image_3d_with_opt_u8_array(
+            WebGl2RenderingContext::TEXTURE_2D_ARRAY,
             0,
             WebGl2RenderingContext::RGBA8 as i32,
             gradient_texture_width as i32,
             gradient_texture_height as i32,
+            gradient_texture_layers as i32,
             0,
             WebGl2RenderingContext::RGBA,
             WebGl2RenderingContext::UNSIGNED_BYTE,
@@ -1575,14 +1613,15 @@ impl WebGlPrograms {
         gradient_cache.restore_luts(luts);
     }
 
-    /// Clear the view framebuffer.
+    /// Clear the view framebuffer to a premultiplied color.
     // TODO: Investigate adding tests for the clear_view behavior.
-    fn clear_view_framebuffer(&mut self, gl: &WebGl2RenderingContext) {
+    fn clear_view_framebuffer(&mut self, gl: &WebGl2RenderingContext, color: PremulColor) {
         gl.bind_framebuffer(
             WebGl2RenderingContext::FRAMEBUFFER,
             self.resources.view_framebuffer_override.as_ref(),
         );
-        gl.clear_color(0.0, 0.0, 0.0, 0.0);
+        let [r, g, b, a] = color.as_premul_f32().components;
+        gl.clear_color(r, g, b, a);
         gl.clear(WebGl2RenderingContext::COLOR_BUFFER_BIT);
     }
 
@@ -2157,7 +2196,7 @@ fn create_webgl_resources(
     let clear_config_buffer = gl.create_buffer().unwrap();
 
     // Create and configure alpha texture.
-    let alphas_texture = create_texture(gl);
+    let alphas_texture = create_texture_array(gl);
 
     let AtlasConfig {
         atlas_size: (atlas_width, atlas_height),
@@ -2172,10 +2211,10 @@ fn create_webgl_resources(
     let stub_atlas_texture_array = create_atlas_texture_array(gl, 1, 1, 1);
 
     // Create and configure encoded paints texture.
-    let encoded_paints_texture = create_texture(gl);
+    let encoded_paints_texture = create_texture_array(gl);
 
     // Create and configure gradient texture.
-    let gradient_texture = create_texture(gl);
+    let gradient_texture = create_texture_array(gl);
     let placeholder_external_texture = create_placeholder_texture(gl);
 
     // Create slot textures and framebuffers.
@@ -2204,12 +2243,15 @@ fn create_webgl_resources(
         strips_buffer,
         alphas_texture,
         alpha_texture_height: 0,
+        alpha_texture_layers: 0,
         atlas_texture_array,
         encoded_paints_texture,
         encoded_paints_texture_height: 0,
+        encoded_paints_texture_layers: 0,
         gradient_texture,
         placeholder_external_texture,
         gradient_texture_height: 0,
+        gradient_texture_layers: 0,
         view_config_buffer,
         slot_config_buffer,
         clear_slot_indices_buffer,
@@ -2219,11 +2261,13 @@ fn create_webgl_resources(
         slot_framebuffers,
         view_framebuffer_override: None,
         depth_cleared_this_frame: false,
+        opaque_target: false,
         // Note: we use DEPTH (not DEPTH_ATTACHMENT) because we render to the default
         // framebuffer. If we ever support non-default framebuffers, this must change
         // to DEPTH_ATTACHMENT.
         depth_attachment_array: js_sys::Array::of1(&WebGl2RenderingContext::DEPTH.into()),
         max_texture_dimension_2d,
+        max_texture_array_layers: get_max_texture_array_layers(gl),
         stub_atlas_texture_array,
         atlas_render_framebuffer: None,
         filter_main_atlas_framebuffer: None,
@@ -2430,6 +2474,10 @@ impl WebGlRendererContext<'_> {
                     strip_offset_x,
                     strip_offset_y,
                     negate_ndc: 0,
+                    output_transfer: OUTPUT_TRANSFER_NONE,
+                    output_scale: 1.0,
+                    decode_paints: 0,
+                    _padding: 0,
                 };
                 let buf = &self.programs.resources.filter_config_buffer;
                 self.gl
@@ -2532,7 +2580,7 @@ impl WebGlRendererContext<'_> {
         // Bind textures.
         self.gl.active_texture(WebGl2RenderingContext::TEXTURE0);
         self.gl.bind_texture(
-            WebGl2RenderingContext::TEXTURE_2D,
+            WebGl2RenderingContext::TEXTURE_2D_ARRAY,
             Some(&self.programs.resources.alphas_texture),
         );
         self.gl
@@ -2562,7 +2610,7 @@ impl WebGlRendererContext<'_> {
         // Bind encoded paints texture for image metadata
         self.gl.active_texture(WebGl2RenderingContext::TEXTURE3);
         self.gl.bind_texture(
-            WebGl2RenderingContext::TEXTURE_2D,
+            WebGl2RenderingContext::TEXTURE_2D_ARRAY,
             Some(&self.programs.resources.encoded_paints_texture),
         );
         self.gl.uniform1i(
@@ -2577,7 +2625,7 @@ impl WebGlRendererContext<'_> {
         // Bind gradient texture for gradient rendering
         self.gl.active_texture(WebGl2RenderingContext::TEXTURE4);
         self.gl.bind_texture(
-            WebGl2RenderingContext::TEXTURE_2D,
+            WebGl2RenderingContext::TEXTURE_2D_ARRAY,
             Some(&self.programs.resources.gradient_texture),
         );
         self.gl
@@ -2627,9 +2675,11 @@ impl WebGlRendererContext<'_> {
                     4,
                     opaque_count,
                 );
+                self.programs.frame_stats.draw_calls += 1;
             }
 
-            // Alpha pass: back-to-front, depth test ON, depth write OFF, blend ON.
+            // Alpha pass: back-to-front, depth test ON, depth write OFF, blend ON. The alpha
+            // channel of a target that stays opaque is already 1, so it isn't written.
             if alpha_count > 0 {
                 // Rebind attribute pointers with offset to start at the alpha portion
                 // of the buffer.
@@ -2646,12 +2696,16 @@ impl WebGlRendererContext<'_> {
 
                 self.gl.depth_mask(false);
                 self.gl.enable(WebGl2RenderingContext::BLEND);
+                if self.programs.resources.opaque_target {
+                    self.gl.color_mask(true, true, true, false);
+                }
                 self.gl.draw_arrays_instanced(
                     WebGl2RenderingContext::TRIANGLE_STRIP,
                     0,
                     4,
                     alpha_count,
                 );
+                self.programs.frame_stats.draw_calls += 1;
 
                 // Restore attribute offsets to base for subsequent passes.
                 for i in 0..STRIP_ATTR_COUNT {
@@ -2668,6 +2722,7 @@ impl WebGlRendererContext<'_> {
             // Restore state.
             self.gl.disable(WebGl2RenderingContext::DEPTH_TEST);
             self.gl.depth_mask(true);
+            self.gl.color_mask(true, true, true, true);
             self.gl.enable(WebGl2RenderingContext::BLEND);
         } else {
             // Slot texture / intermediate: single draw with blending, no depth.
@@ -2677,6 +2732,7 @@ impl WebGlRendererContext<'_> {
                 4,
                 opaque_count + alpha_count,
             );
+            self.programs.frame_stats.draw_calls += 1;
         }
 
         // Clean up.
@@ -2738,6 +2794,7 @@ impl WebGlRendererContext<'_> {
             4,
             slot_indices.len() as i32,
         );
+        self.programs.frame_stats.draw_calls += 1;
 
         self.gl.enable(WebGl2RenderingContext::BLEND);
 
@@ -2873,6 +2930,7 @@ impl RendererBackend for WebGlRendererContext<'_> {
 
             self.gl
                 .draw_arrays_instanced(WebGl2RenderingContext::TRIANGLE_STRIP, 0, 4, 1);
+            self.programs.frame_stats.draw_calls += 1;
         }
         self.gl.bind_vertex_array(None);
         self.gl.disable(WebGl2RenderingContext::SCISSOR_TEST);
@@ -2923,6 +2981,17 @@ impl WebGlAtlasWriter for Pixmap {
         width: u32,
         height: u32,
     ) {
+        // The atlas stores sRGB data, so convert images that are tagged otherwise.
+        let converted;
+        let pixmap = if self.color_space() == ColorSpaceTag::Srgb {
+            self
+        } else {
+            let mut pixmap = self.clone();
+            pixmap.convert_color_space(ColorSpaceTag::Srgb);
+            converted = pixmap;
+            &converted
+        };
+
         // Bind the atlas texture array
         gl.active_texture(WebGl2RenderingContext::TEXTURE0);
         gl.bind_texture(
@@ -2931,7 +3000,7 @@ impl WebGlAtlasWriter for Pixmap {
         );
 
         // Convert pixmap data to the format expected by WebGL
-        let rgba_data = self.data_as_u8_slice();
+        let rgba_data = pixmap.data_as_u8_slice();
 
         // Upload the image data to the specific layer and region of the atlas texture array
         gl.tex_sub_image_3d_with_opt_u8_array(
@@ -3139,17 +3208,18 @@ fn copy_to_texture_array_layer(
 }
 
 // Upload the data to the currently bound texture assuming a RGBA32UI format.
-fn upload_data_to_rgba32_texture(
+fn upload_data_to_rgba32_texture_array(
     gl: &WebGl2RenderingContext,
     data: &[u32],
     texture_width: u32,
     texture_height: u32,
+    texture_layers: u32,
 ) {
     // Safety: This calling `Uint32Array::view` is unsafe because it provides a view into
     // WASM linear memory, and any additional allocations might invalidate that view.
     // In our case, this is not an issue because we only use this view once for uploading
     // data to the GPU below, and no allocations happen between that.
-    // The `tex_image_2d` method is synchronous in the sense that once it returns, it is guaranteed
+    // The `tex_image_3d` method is synchronous in the sense that once it returns, it is guaranteed
     // that all necessary data has already been read, so any allocations that happen
     // after this block don't affect this anymore.
     //
@@ -3165,12 +3235,13 @@ fn upload_data_to_rgba32_texture(
     // >> pointer you gave it, as OpenGL has already read as much as it wants.
     let packed_array = unsafe { js_sys::Uint32Array::view(data) };
 
-    gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_array_buffer_view(
-        WebGl2RenderingContext::TEXTURE_2D,
+    gl.tex_image_3d_with_opt_array_buffer_view(
+        WebGl2RenderingContext::TEXTURE_2D_ARRAY,
         0,
         WebGl2RenderingContext::RGBA32UI as i32,
         texture_width as i32,
         texture_height as i32,
+        texture_layers as i32,
         0,
         WebGl2RenderingContext::RGBA_INTEGER,
         WebGl2RenderingContext::UNSIGNED_INT,
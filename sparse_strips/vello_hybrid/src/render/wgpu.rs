@@ -23,16 +23,16 @@ use crate::{
     GpuStrip, RenderError, RenderSettings, RenderSize, Resources,
     filter::{FilterContext, FilterInstanceData, FilterPassState, FilterPassTarget},
     gradient_cache::GradientRampCache,
+    pattern::PatternUpload,
     render::{
         Config,
         common::{
-            GPU_BLURRED_ROUNDED_RECT_SIZE_TEXELS, GPU_ENCODED_IMAGE_SIZE_TEXELS,
-            GPU_LINEAR_GRADIENT_SIZE_TEXELS, GPU_RADIAL_GRADIENT_SIZE_TEXELS,
-            GPU_SWEEP_GRADIENT_SIZE_TEXELS, GpuBlurredRoundedRect, GpuEncodedImage,
-            GpuEncodedPaint, GpuLinearGradient, GpuRadialGradient, GpuSweepGradient,
-            normalize_atlas_config, pack_image_offset, pack_image_params, pack_image_size,
-            pack_radial_kind_and_swapped, pack_texture_width_and_extend_mode, pack_tint,
+            GpuEncodedPaint, OutputColorConfig, OutputTransfer, compose_custom_paints,
+            encode_external_texture_paint, encode_gpu_paints, linear_texture_extent,
+            normalize_atlas_config,
         },
+        profiling::{FrameProfile, GpuProfiler, ProfileStage},
+        upload::UploadBelt,
     },
     scene::Scene,
     schedule::{
@@ -47,37 +47,30 @@ use core::{fmt::Debug, num::NonZeroU64};
 #[cfg(feature = "text")]
 use glifo::PendingClearRect;
 use hashbrown::{HashMap, hash_map::Entry};
-use vello_common::image_cache::{ImageCache, ImageResource};
+use vello_common::alpha_mode::AlphaMode;
+use vello_common::encode::GradientLutCache;
+use vello_common::image_cache::ImageCache;
+use vello_common::load_op::LoadOp as TargetLoadOp;
+use vello_common::mipmap::MipChain;
 use vello_common::multi_atlas::{AtlasConfig, AtlasError, AtlasId};
+use vello_common::peniko::color::ColorSpaceTag;
 use vello_common::render_graph::LayerId;
+use vello_common::render_stats::RenderStats;
 use vello_common::{
     TextureId,
     coarse::WideTile,
-    encode::{
-        EncodedBlurredRoundedRectangle, EncodedExternalTexture, EncodedGradient, EncodedKind,
-        EncodedPaint, MAX_GRADIENT_LUT_SIZE, RadialKind,
-    },
-    paint::ImageSource,
-    peniko,
+    custom_paint::{CustomPaintKind, CustomPaintRegistry},
+    encode::{EncodedPaint, MAX_GRADIENT_LUT_SIZE},
     pixmap::Pixmap,
     tile::Tile,
 };
 use wgpu::{
-    BindGroup, BindGroupLayout, BlendState, Buffer, ColorTargetState, ColorWrites, CommandEncoder,
-    Device, Extent3d, PipelineCompilationOptions, Queue, RenderPassColorAttachment,
-    RenderPassDescriptor, RenderPipeline, Sampler, Texture, TextureView, TextureViewDescriptor,
-    util::DeviceExt,
+    Adapter, BindGroup, BindGroupLayout, BlendState, Buffer, ColorTargetState, ColorWrites,
+    CommandEncoder, Device, Extent3d, Limits, PipelineCompilationOptions, Queue,
+    RenderPassColorAttachment, RenderPassDescriptor, RenderPassTimestampWrites, RenderPipeline,
+    Sampler, Texture, TextureView, TextureViewDescriptor, util::DeviceExt,
 };
 
-/// Placeholder value for uninitialized GPU encoded paints.
-const GPU_PAINT_PLACEHOLDER: GpuEncodedPaint = GpuEncodedPaint::LinearGradient(GpuLinearGradient {
-    texture_width_and_extend_mode: 0,
-    gradient_start: 0,
-    transform: [0.0; 6],
-});
-
-const EXTERNAL_IMAGE_SOURCE_FLAG: u32 = 1 << 14;
-
 /// Options for the renderer
 #[derive(Debug)]
 pub struct RenderTargetConfig {
@@ -87,6 +80,36 @@ pub struct RenderTargetConfig {
     pub width: u32,
     /// Height of the rendering target
     pub height: u32,
+    /// How colors are written to the rendering target.
+    ///
+    /// This has to be configured to match the format of the target, or of the view that is
+    /// rendered to: otherwise, colors look too bright or too dark when rendering to a format
+    /// that is sRGB-encoded, like `Bgra8UnormSrgb`.
+    pub output_color: OutputColorConfig,
+}
+
+/// A surface that [`Renderer::render_to_surfaces`] renders a scene to.
+#[derive(Debug)]
+pub struct SurfaceTarget<'a> {
+    /// The scene to render.
+    pub scene: &'a Scene,
+    /// The view to render into, which must have the format of the renderer's render target.
+    pub view: &'a TextureView,
+    /// The size of `view`, which should match the size of `scene`.
+    pub render_size: RenderSize,
+}
+
+/// A rectangle of a render target that [`Renderer::render_to_viewport`] renders a scene into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    /// The x coordinate of the left edge of the rectangle, in pixels.
+    pub x: u32,
+    /// The y coordinate of the top edge of the rectangle, in pixels.
+    pub y: u32,
+    /// The width of the rectangle, which should match the width of the scene.
+    pub width: u32,
+    /// The height of the rectangle, which should match the height of the scene.
+    pub height: u32,
 }
 
 /// Runtime bindings for [externally owned textures](`TextureId`) sampled by texture-rect draws.
@@ -142,6 +165,107 @@ impl TextureBindings {
     }
 }
 
+/// The first [`TextureId`] handed out by [`Renderer::register_texture`].
+///
+/// Ids passed to [`TextureBindings`] should stay below this value to avoid clashes.
+const REGISTERED_TEXTURE_ID_BASE: u64 = 1 << 63;
+
+/// The number of renders a registered texture is kept for after the last render that
+/// referenced it.
+const RELEASE_AFTER_UNUSED_RENDERS: u64 = 3;
+
+#[derive(Debug)]
+struct RegisteredTexture {
+    view: TextureView,
+    /// The epoch of the last render that referenced this texture.
+    last_used: u64,
+    /// Whether the texture is kept until it is unregistered, regardless of its use.
+    pinned: bool,
+}
+
+/// Texture views registered with a [`Renderer`], released once renders stop referencing them.
+#[derive(Debug)]
+struct RegisteredTextures {
+    textures: HashMap<TextureId, RegisteredTexture>,
+    next_id: u64,
+    /// Incremented after every render.
+    epoch: u64,
+}
+
+impl Default for RegisteredTextures {
+    fn default() -> Self {
+        Self {
+            textures: HashMap::new(),
+            next_id: REGISTERED_TEXTURE_ID_BASE,
+            epoch: 0,
+        }
+    }
+}
+
+impl RegisteredTextures {
+    fn register(&mut self, view: TextureView, pinned: bool) -> TextureId {
+        let texture_id = TextureId(self.next_id);
+        self.next_id += 1;
+        self.textures.insert(
+            texture_id,
+            RegisteredTexture {
+                view,
+                last_used: self.epoch,
+                pinned,
+            },
+        );
+        texture_id
+    }
+
+    /// Merge the registered textures referenced by `encoded_paints` into `texture_bindings`,
+    /// then release the textures that weren't referenced by recent renders.
+    fn bind_and_release(
+        &mut self,
+        encoded_paints: &[EncodedPaint],
+        texture_bindings: &TextureBindings,
+    ) -> Option<TextureBindings> {
+        let merged = self.bind(encoded_paints, texture_bindings);
+        self.release_unused();
+
+        merged
+    }
+
+    /// Release all unpinned textures that were neither referenced nor registered by the last
+    /// [`RELEASE_AFTER_UNUSED_RENDERS`] renders.
+    fn release_unused(&mut self) {
+        let epoch = self.epoch;
+        self.textures.retain(|_, registered| {
+            registered.pinned || epoch - registered.last_used < RELEASE_AFTER_UNUSED_RENDERS
+        });
+        self.epoch += 1;
+    }
+
+    /// Merge the registered textures referenced by `encoded_paints` into `texture_bindings`
+    /// and mark them as used, without releasing any textures.
+    fn bind(
+        &mut self,
+        encoded_paints: &[EncodedPaint],
+        texture_bindings: &TextureBindings,
+    ) -> Option<TextureBindings> {
+        if self.textures.is_empty() {
+            return None;
+        }
+
+        let mut merged = texture_bindings.clone();
+        for paint in encoded_paints {
+            let EncodedPaint::ExternalTexture(texture) = paint else {
+                continue;
+            };
+            if let Some(registered) = self.textures.get_mut(&texture.texture_id) {
+                registered.last_used = self.epoch;
+                merged.insert(texture.texture_id, registered.view.clone());
+            }
+        }
+
+        Some(merged)
+    }
+}
+
 /// Vello Hybrid's Renderer.
 #[derive(Debug)]
 pub struct Renderer {
@@ -162,11 +286,44 @@ pub struct Renderer {
     /// State used for constructing filter passes.
     filter_pass_state: FilterPassState,
     dummy_image_cache: Option<ImageCache>,
+    /// External textures registered through [`Renderer::register_texture`].
+    registered_textures: RegisteredTextures,
+    /// Statistics about the last rendered scene.
+    stats: RenderStats,
     #[cfg(feature = "text")]
     atlas_clear_scratch: Vec<u8>,
 }
 
 impl Renderer {
+    /// Return whether `adapter` only offers downlevel capabilities, like WebGL2 through the GL
+    /// backend of wgpu.
+    ///
+    /// The renderer doesn't need compute shaders or storage buffers, so it runs on such
+    /// adapters with the same scene API. In the browser, the dedicated `WebGlRenderer` of the
+    /// `webgl` feature is a smaller alternative.
+    pub fn is_downlevel(adapter: &Adapter) -> bool {
+        !adapter.get_downlevel_capabilities().is_webgpu_compliant()
+    }
+
+    /// Return the limits to request when creating a device from `adapter` for the renderer.
+    ///
+    /// These are the WebGL2 defaults for [downlevel](Renderer::is_downlevel) adapters and the
+    /// downlevel WebGPU defaults otherwise, raised to the largest texture dimension and buffer
+    /// size of the adapter, so that atlases can be as large as possible.
+    pub fn required_limits(adapter: &Adapter) -> Limits {
+        let defaults = if Self::is_downlevel(adapter) {
+            Limits::downlevel_webgl2_defaults()
+        } else {
+            Limits::downlevel_defaults()
+        };
+        let limits = adapter.limits();
+        Limits {
+            max_texture_dimension_2d: limits.max_texture_dimension_2d,
+            max_buffer_size: limits.max_buffer_size,
+            ..defaults
+        }
+    }
+
     /// Creates a new renderer.
     pub fn new(device: &Device, render_target_config: &RenderTargetConfig) -> Self {
         Self::new_with(device, render_target_config, RenderSettings::default())
@@ -227,11 +384,138 @@ impl Renderer {
             filter_context,
             filter_pass_state: FilterPassState::default(),
             dummy_image_cache: Some(ImageCache::new_dummy()),
+            registered_textures: RegisteredTextures::default(),
+            stats: RenderStats::default(),
             #[cfg(feature = "text")]
             atlas_clear_scratch: Vec::new(),
         }
     }
 
+    /// Register an externally owned texture view and return a [`TextureId`] for it.
+    ///
+    /// The returned id can be used with [`Scene::set_texture_paint`] or
+    /// [`Scene::draw_texture_rects`] without passing the view through [`TextureBindings`]. The
+    /// view has the same requirements as described in [`TextureBindings::insert`].
+    ///
+    /// The renderer keeps the view alive for as long as scenes keep referencing it: a
+    /// registered texture is released once it wasn't used by the scenes of the last
+    /// few calls to [`render`](Self::render) or [`render_to_surfaces`](Self::render_to_surfaces),
+    /// so skipping it for a frame doesn't release it. Once released, the id is no longer valid
+    /// and rendering a scene that uses it returns [`RenderError::MissingTextureBinding`].
+    pub fn register_texture(&mut self, view: TextureView) -> TextureId {
+        self.registered_textures.register(view, false)
+    }
+
+    /// Release a texture registered with [`register_texture`](Self::register_texture) before
+    /// it would otherwise be released, or one rendered with
+    /// [`render_to_texture`](Self::render_to_texture).
+    ///
+    /// This returns the view if the texture was still registered.
+    pub fn unregister_texture(&mut self, texture_id: TextureId) -> Option<TextureView> {
+        self.registered_textures
+            .textures
+            .remove(&texture_id)
+            .map(|registered| registered.view)
+    }
+
+    /// Return whether `texture_id` refers to a texture that is currently registered with
+    /// this renderer.
+    pub fn is_texture_registered(&self, texture_id: TextureId) -> bool {
+        self.registered_textures.textures.contains_key(&texture_id)
+    }
+
+    /// Return the paint-source bind group that is kept for the external texture `texture_id`.
+    ///
+    /// This is only meant for testing that bind groups are reused across frames.
+    #[doc(hidden)]
+    pub fn external_paint_source_bind_group(&self, texture_id: TextureId) -> Option<&BindGroup> {
+        self.programs
+            .resources
+            .external_paint_source_bind_groups
+            .get(&texture_id)
+            .map(|(_, bind_group)| bind_group)
+    }
+
+    /// Set the cache that gradient ramps are resolved through.
+    ///
+    /// Sharing a [`GradientLutCache`] with other renderers (for example a `vello_cpu` render
+    /// context that exports thumbnails of the same content) means that each gradient ramp is
+    /// only computed once. Ramps that are already resident on the GPU are not affected.
+    pub fn set_gradient_lut_cache(&mut self, cache: Option<GradientLutCache>) {
+        self.gradient_cache.set_lut_cache(cache);
+    }
+
+    /// Enable or disable GPU profiling of rendered frames.
+    ///
+    /// While profiling is enabled, the render passes of every frame rendered with
+    /// [`render`](Self::render), [`render_to_surfaces`](Self::render_to_surfaces) or
+    /// [`render_to_texture`](Self::render_to_texture) are timed with timestamp queries, and
+    /// the results can be read with [`frame_profiles`](Self::frame_profiles). This requires
+    /// the device to have been created with [`wgpu::Features::TIMESTAMP_QUERY`]; returns
+    /// whether profiling is enabled afterwards.
+    pub fn set_profiling(&mut self, device: &Device, queue: &Queue, enabled: bool) -> bool {
+        if !enabled {
+            self.programs.profiler = None;
+        } else if self.programs.profiler.is_none() {
+            self.programs.profiler = GpuProfiler::new(device, queue);
+        }
+
+        self.programs.profiler.is_some()
+    }
+
+    /// Return the profiles of the frames whose GPU work has finished since the last call, in
+    /// the order they were rendered.
+    ///
+    /// Profiles are read back asynchronously: this must only be called after the command
+    /// encoders of the profiled frames have been submitted, and a frame's profile is
+    /// returned by a later call once the device has been polled after the GPU finished the
+    /// frame. Frames whose read back fails are skipped.
+    pub fn frame_profiles(&mut self) -> Vec<FrameProfile> {
+        self.programs
+            .profiler
+            .as_mut()
+            .map(GpuProfiler::collect)
+            .unwrap_or_default()
+    }
+
+    /// Return statistics about the scene that was rendered last.
+    ///
+    /// Besides the paths and strips counted by [`Scene::render_stats`], this includes the
+    /// alpha bytes that were uploaded and the draw calls that were issued for the frame,
+    /// including those for rendering glyphs into the atlas.
+    pub fn render_stats(&self) -> RenderStats {
+        self.stats
+    }
+
+    /// Rebuild the render pipelines from the given shaders and tuning parameters.
+    ///
+    /// All other GPU resources, including uploaded images, are kept. The shaders are
+    /// validated before any pipelines are replaced, so the renderer keeps its current
+    /// pipelines if the new ones contain errors.
+    ///
+    /// Pipeline errors that are only detected by the device are reported immediately on
+    /// native backends. On the web, they are reported asynchronously to the device's error
+    /// handler instead.
+    #[cfg(feature = "hot_reload")]
+    pub fn reload_shaders(
+        &mut self,
+        device: &Device,
+        shaders: &crate::ShaderSet,
+    ) -> Result<(), crate::HotReloadError> {
+        let constants = shaders.validate()?;
+        let sources = shaders.sources(&constants);
+
+        let scope = device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let pipelines = self.programs.build_pipelines(device, &sources);
+        if let Some(Some(error)) = poll_once(scope.pop()) {
+            return Err(crate::HotReloadError::Pipeline(alloc::format!("{error}")));
+        }
+
+        self.programs.pipelines = pipelines;
+        self.programs.reloaded_shaders = Some(shaders.clone());
+        Ok(())
+    }
+
     fn prepare_filter_textures(
         &mut self,
         scene: &Scene,
@@ -243,6 +527,11 @@ impl Renderer {
         // TODO: Maybe we can do the clear implicitly when using the textures for the first time.
         if !self.filter_context.filter_textures.is_empty() {
             for view in &self.programs.resources.filter_atlas.views {
+                let timestamp_writes = self
+                    .programs
+                    .profiler
+                    .as_mut()
+                    .and_then(|profiler| profiler.timestamp_writes(ProfileStage::Clear));
                 let _pass = encoder.begin_render_pass(&RenderPassDescriptor {
                     label: Some("Clear Filter Atlas Texture"),
                     color_attachments: &[Some(RenderPassColorAttachment {
@@ -256,7 +545,7 @@ impl Renderer {
                     })],
                     depth_stencil_attachment: None,
                     occlusion_query_set: None,
-                    timestamp_writes: None,
+                    timestamp_writes,
                     multiview_mask: None,
                 });
             }
@@ -265,8 +554,12 @@ impl Renderer {
         self.filter_context
             .deallocate_all_and_clear_context(image_cache);
 
-        self.filter_context
-            .prepare(&scene.render_graph, image_cache, encoded_paints)?;
+        self.filter_context.prepare(
+            &scene.render_graph,
+            image_cache,
+            encoded_paints,
+            scene.quality(),
+        )?;
 
         Programs::maybe_resize_atlas_texture_array(
             device,
@@ -292,6 +585,12 @@ impl Renderer {
     /// requirements on the bound texture views.
     ///
     /// To render without any texture bindings, you can pass an empty [`TextureBindings`].
+    /// Textures registered with [`register_texture`](Self::register_texture) don't need to be
+    /// passed here; registered textures that recent scenes didn't reference are released.
+    ///
+    /// The data of the scene is uploaded through staging buffers that are recycled by later
+    /// renders once `encoder` was submitted and the GPU is done with it. Several renders can be
+    /// recorded into the same encoder.
     pub fn render(
         &mut self,
         scene: &Scene,
@@ -303,6 +602,206 @@ impl Renderer {
         view: &TextureView,
         texture_bindings: &TextureBindings,
     ) -> Result<(), RenderError> {
+        let merged_bindings = self
+            .registered_textures
+            .bind_and_release(&scene.encoded_paints.borrow(), texture_bindings);
+        let texture_bindings = merged_bindings.as_ref().unwrap_or(texture_bindings);
+        self.render_with_bindings(
+            scene,
+            resources,
+            device,
+            queue,
+            encoder,
+            render_size,
+            view,
+            texture_bindings,
+        )
+    }
+
+    /// Render `scene` into the `viewport` rectangle of `view`, for example to embed it in a
+    /// larger user interface.
+    ///
+    /// This works like [`render`](Self::render), with `render_size` being the size of the
+    /// whole `view`. The scene is drawn with its origin at the top left corner of `viewport`,
+    /// which should have the size of the scene, and nothing outside of `viewport` is drawn
+    /// or cleared. Since strips are only generated within the bounds of the scene, geometry
+    /// outside of the viewport doesn't cost any rendering time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `viewport` doesn't lie within `render_size`.
+    pub fn render_to_viewport(
+        &mut self,
+        scene: &Scene,
+        resources: &mut Resources,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        render_size: &RenderSize,
+        viewport: &Viewport,
+        view: &TextureView,
+        texture_bindings: &TextureBindings,
+    ) -> Result<(), RenderError> {
+        assert!(
+            viewport.x.saturating_add(viewport.width) <= render_size.width
+                && viewport.y.saturating_add(viewport.height) <= render_size.height,
+            "the viewport must lie within the render target"
+        );
+
+        self.programs.viewport = Some(*viewport);
+        let result = self.render(
+            scene,
+            resources,
+            device,
+            queue,
+            encoder,
+            render_size,
+            view,
+            texture_bindings,
+        );
+        self.programs.viewport = None;
+
+        result
+    }
+
+    /// Render a scene to each of `targets`, for example to the surfaces of multiple windows.
+    ///
+    /// The targets can have different sizes, and share the pipelines, the gradient cache and
+    /// the atlases of the renderer, so a single renderer is enough for all windows of an
+    /// application. All targets must have the format of the renderer's render target.
+    ///
+    /// Each target is rendered with its own command encoder, which is submitted immediately,
+    /// since renders share staged GPU resources. Registered textures are released once all
+    /// targets are rendered, if recent scenes didn't use them.
+    pub fn render_to_surfaces(
+        &mut self,
+        targets: &[SurfaceTarget<'_>],
+        resources: &mut Resources,
+        device: &Device,
+        queue: &Queue,
+        texture_bindings: &TextureBindings,
+    ) -> Result<(), RenderError> {
+        // Keep the attachments of every size around, so alternating between the targets
+        // doesn't reallocate them.
+        self.programs.spare_attachment_limit = targets.len().saturating_sub(1);
+        let result = targets.iter().try_for_each(|target| {
+            let merged_bindings = self
+                .registered_textures
+                .bind(&target.scene.encoded_paints.borrow(), texture_bindings);
+            let texture_bindings = merged_bindings.as_ref().unwrap_or(texture_bindings);
+
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Render to Surface Encoder"),
+            });
+            self.render_with_bindings(
+                target.scene,
+                resources,
+                device,
+                queue,
+                &mut encoder,
+                &target.render_size,
+                target.view,
+                texture_bindings,
+            )?;
+            queue.submit(Some(encoder.finish()));
+            Ok(())
+        });
+        self.programs.spare_attachment_limit = 0;
+        self.programs
+            .spare_attachments
+            .retain(|(size, ..)| targets.iter().any(|target| target.render_size == *size));
+        self.registered_textures.release_unused();
+
+        result
+    }
+
+    /// Render `scene` into a new texture and register it with the renderer.
+    ///
+    /// This allows caching expensive groups of draws, or applying effects to them: the
+    /// returned [`TextureId`] can be used as a paint with [`Scene::set_texture_paint`] or
+    /// [`Scene::draw_texture_rects`] in the scenes that are rendered afterwards. The texture
+    /// has the size of `render_size`, which should match the size of `scene`, and the format
+    /// of the renderer's render target.
+    ///
+    /// Unlike a texture registered with [`register_texture`](Self::register_texture), the
+    /// rendered texture isn't released when scenes stop referencing it, so it can be cached
+    /// for any number of frames. It is kept until it is released with
+    /// [`unregister_texture`](Self::unregister_texture). This doesn't release any registered
+    /// textures either, so that sub-scenes can be rendered before the scene that uses them.
+    ///
+    /// Like rendering into the atlas, this uses its own command encoder and submits it
+    /// immediately, since it shares staged GPU resources with other renders.
+    pub fn render_to_texture(
+        &mut self,
+        scene: &Scene,
+        resources: &mut Resources,
+        device: &Device,
+        queue: &Queue,
+        render_size: &RenderSize,
+        texture_bindings: &TextureBindings,
+    ) -> Result<TextureId, RenderError> {
+        let merged_bindings = self
+            .registered_textures
+            .bind(&scene.encoded_paints.borrow(), texture_bindings);
+        let texture_bindings = merged_bindings.as_ref().unwrap_or(texture_bindings);
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen Render Texture"),
+            size: Extent3d {
+                width: render_size.width,
+                height: render_size.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.programs.render_target_format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&TextureViewDescriptor::default());
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Render to Texture Encoder"),
+        });
+        // The texture is drawn as a paint later, which has to be premultiplied.
+        let alpha_mode = core::mem::replace(
+            &mut self.programs.output_color.alpha_mode,
+            AlphaMode::Premultiplied,
+        );
+        let result = self.render_with_bindings(
+            scene,
+            resources,
+            device,
+            queue,
+            &mut encoder,
+            render_size,
+            &view,
+            texture_bindings,
+        );
+        self.programs.output_color.alpha_mode = alpha_mode;
+        result?;
+        queue.submit(Some(encoder.finish()));
+
+        Ok(self.registered_textures.register(view, true))
+    }
+
+    /// Render `scene` with the registered textures already merged into `texture_bindings`.
+    fn render_with_bindings(
+        &mut self,
+        scene: &Scene,
+        resources: &mut Resources,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        render_size: &RenderSize,
+        view: &TextureView,
+        texture_bindings: &TextureBindings,
+    ) -> Result<(), RenderError> {
+        self.programs.frame_stats = RenderStats::default();
+        self.programs
+            .sync_custom_paints(device, &scene.custom_paints);
+
         #[cfg(feature = "text")]
         {
             resources.before_render(
@@ -334,6 +833,9 @@ impl Renderer {
             );
         }
 
+        if let Some(profiler) = &mut self.programs.profiler {
+            profiler.begin_frame();
+        }
         let mut encoded_paints = scene.encoded_paints.borrow_mut();
         let scene_paint_count = encoded_paints.len();
 
@@ -354,16 +856,26 @@ impl Renderer {
             view,
             &resources.image_cache,
             &encoded_paints,
-            true,
+            scene.target_load_op(),
             RootRenderTarget::UserSurface,
             texture_bindings,
         );
+        self.programs
+            .release_external_paint_source_bind_groups(texture_bindings);
+        if let Some(profiler) = &mut self.programs.profiler {
+            profiler.end_frame(device, encoder);
+        }
 
         encoded_paints.truncate(scene_paint_count);
         #[cfg(feature = "text")]
         resources.after_render(self, |renderer, rect| {
             clear_atlas_region(queue, renderer, rect);
         });
+        self.stats = RenderStats {
+            alpha_bytes: self.programs.frame_stats.alpha_bytes,
+            draw_calls: self.programs.frame_stats.draw_calls,
+            ..scene.render_stats()
+        };
         result
     }
 
@@ -452,7 +964,7 @@ impl Renderer {
             &layer_view,
             &dummy_image_cache,
             &encoded_paints,
-            false,
+            TargetLoadOp::Load,
             RootRenderTarget::AtlasLayer,
             texture_bindings,
         );
@@ -474,8 +986,8 @@ impl Renderer {
     /// Shared render pipeline: prepares GPU resources, runs the scheduler against
     /// the provided `view` at `render_size`, and maintains caches.
     ///
-    /// When `clear` is true the render target is cleared to transparent black
-    /// before drawing (normal frame rendering).
+    /// The render target is cleared according to `load_op` before drawing. Atlas renders
+    /// use [`TargetLoadOp::Load`] so that previously rendered atlas content is preserved.
     fn render_scene(
         &mut self,
         scene: &Scene,
@@ -486,33 +998,85 @@ impl Renderer {
         view: &TextureView,
         image_cache: &ImageCache,
         encoded_paints: &[EncodedPaint],
-        clear: bool,
+        load_op: TargetLoadOp,
         root_output_target: RootRenderTarget,
         texture_bindings: &TextureBindings,
     ) -> Result<(), RenderError> {
+        self.programs.upload_belt.recall();
         self.programs.depth_cleared_this_frame = false;
+        self.programs.opaque_target = matches!(root_output_target, RootRenderTarget::UserSurface)
+            && scene.has_opaque_result();
         self.prepare_gpu_encoded_paints(encoded_paints, image_cache, texture_bindings)?;
         // TODO: For the time being, we upload the entire alpha buffer as one big chunk. As a future
         // refinement, we could have a bounded alpha buffer, and break draws when the alpha
         // buffer fills.
+        let is_user_surface = matches!(root_output_target, RootRenderTarget::UserSurface);
+        let output_transfer = if is_user_surface {
+            self.programs.output_color.output_transfer()
+        } else {
+            OutputTransfer::NONE
+        };
+        let viewport = self.programs.viewport.filter(|_| is_user_surface);
+        let config_size = match viewport {
+            Some(viewport) => RenderSize {
+                width: viewport.width,
+                height: viewport.height,
+            },
+            None => render_size.clone(),
+        };
         self.programs.prepare(
             device,
             queue,
+            encoder,
             &mut self.gradient_cache,
             &self.encoded_paints,
             &mut scene.strip_storage.borrow_mut().alphas,
             render_size,
+            &config_size,
+            output_transfer,
             &self.paint_idxs,
             &self.filter_context,
         );
 
-        if clear {
-            Self::clear_view(encoder, view);
+        // With straight alpha, the scene is rendered into a premultiplied texture, which is
+        // unpremultiplied into the target at the end.
+        let premultiplied = self.programs.attachments.premultiplied.clone().filter(|_| {
+            is_user_surface && self.programs.output_color.alpha_mode == AlphaMode::Straight
+        });
+        let target_view = view;
+        let view = premultiplied.as_ref().map_or(view, |target| &target.view);
+
+        if let Some(color) = load_op.clear_color() {
+            let mut premul = self
+                .programs
+                .output_color
+                .to_blend_space(color.as_premul_f32().components);
+            if is_user_surface {
+                premul = output_transfer.apply(premul);
+            }
+            let timestamp_writes = self
+                .programs
+                .profiler
+                .as_mut()
+                .and_then(|profiler| profiler.timestamp_writes(ProfileStage::Clear));
+            match viewport {
+                Some(viewport) => {
+                    Self::clear_viewport(
+                        encoder,
+                        view,
+                        &self.programs.pipelines.viewport_clear,
+                        &viewport,
+                        premul,
+                        timestamp_writes,
+                    );
+                    self.programs.frame_stats.draw_calls += 1;
+                }
+                None => Self::clear_view(encoder, view, premul, timestamp_writes),
+            }
         }
         let mut ctx = RendererContext {
             programs: &mut self.programs,
             device,
-            queue,
             encoder,
             view,
             image_cache,
@@ -521,7 +1085,7 @@ impl Renderer {
             texture_bindings,
             external_paint_source_bind_groups: HashMap::new(),
         };
-        self.scheduler.do_scene(
+        let result = self.scheduler.do_scene(
             &mut self.scheduler_state,
             &mut ctx,
             scene,
@@ -529,59 +1093,302 @@ impl Renderer {
             &self.paint_idxs,
             &self.filter_context,
             encoded_paints,
-        )?;
+        );
+        // The staging buffers must be unmapped before the commands are submitted, even if
+        // scheduling failed.
+        self.programs.upload_belt.finish(encoder);
+        result?;
+        if let Some(premultiplied) = &premultiplied {
+            let timestamp_writes = self
+                .programs
+                .profiler
+                .as_mut()
+                .and_then(|profiler| profiler.timestamp_writes(ProfileStage::Target));
+            Self::unpremultiply_view(
+                encoder,
+                target_view,
+                &self.programs.pipelines.unpremultiply,
+                premultiplied,
+                viewport.as_ref(),
+                timestamp_writes,
+            );
+            self.programs.frame_stats.draw_calls += 1;
+        }
         self.gradient_cache.maintain();
 
         Ok(())
     }
 
-    /// Clear the view to transparent black.
+    /// Clear the view to a premultiplied color.
     // TODO: Investigate adding tests for the clear_view behavior.
-    fn clear_view(encoder: &mut CommandEncoder, view: &TextureView) {
+    fn clear_view(
+        encoder: &mut CommandEncoder,
+        view: &TextureView,
+        premul: [f32; 4],
+        timestamp_writes: Option<RenderPassTimestampWrites<'_>>,
+    ) {
+        let [r, g, b, a] = premul.map(f64::from);
         encoder.begin_render_pass(&RenderPassDescriptor {
             label: Some("Clear View"),
             color_attachments: &[Some(RenderPassColorAttachment {
                 view,
                 resolve_target: None,
                 ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    load: wgpu::LoadOp::Clear(wgpu::Color { r, g, b, a }),
                     store: wgpu::StoreOp::Store,
                 },
                 depth_slice: None,
             })],
             depth_stencil_attachment: None,
             occlusion_query_set: None,
-            timestamp_writes: None,
+            timestamp_writes,
             multiview_mask: None,
         });
     }
 
-    /// Upload image to cache and atlas in one step. Returns the `ImageId`.
-    ///
-    /// It's used when an image is not already in the cache.
+    /// Clear the `viewport` rectangle of the view to a premultiplied color.
     ///
-    /// This is a convenience method that:
-    /// 1. Reserves space in the image cache
-    /// 2. Writes the image data directly to the atlas
-    /// 3. Returns the `ImageId` for use in rendering
-    pub fn upload_image<T: AtlasWriter>(
-        &mut self,
-        resources: &mut Resources,
-        device: &Device,
-        queue: &Queue,
+    /// Load operations always clear the whole view, so the rectangle is cleared by a
+    /// scissored draw whose output is replaced with the color through the blend constant.
+    fn clear_viewport(
         encoder: &mut CommandEncoder,
-        writer: &T,
-    ) -> vello_common::paint::ImageId {
-        self.upload_image_with(
-            &mut resources.image_cache,
-            device,
-            queue,
-            encoder,
-            writer,
+        view: &TextureView,
+        pipeline: &RenderPipeline,
+        viewport: &Viewport,
+        premul: [f32; 4],
+        timestamp_writes: Option<RenderPassTimestampWrites<'_>>,
+    ) {
+        let [r, g, b, a] = premul.map(f64::from);
+        let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("Clear Viewport"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes,
+            multiview_mask: None,
+        });
+        render_pass.set_scissor_rect(viewport.x, viewport.y, viewport.width, viewport.height);
+        render_pass.set_blend_constant(wgpu::Color { r, g, b, a });
+        render_pass.set_pipeline(pipeline);
+        render_pass.draw(0..4, 0..1);
+    }
+
+    /// Copy the premultiplied colors of `premultiplied` into the view with straight alpha.
+    ///
+    /// If a `viewport` is given, the rest of the view is left unchanged.
+    fn unpremultiply_view(
+        encoder: &mut CommandEncoder,
+        view: &TextureView,
+        pipeline: &RenderPipeline,
+        premultiplied: &PremultipliedTarget,
+        viewport: Option<&Viewport>,
+        timestamp_writes: Option<RenderPassTimestampWrites<'_>>,
+    ) {
+        let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
+            label: Some("Unpremultiply View"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+                depth_slice: None,
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes,
+            multiview_mask: None,
+        });
+        if let Some(viewport) = viewport {
+            render_pass.set_scissor_rect(viewport.x, viewport.y, viewport.width, viewport.height);
+        }
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(1, &premultiplied.bind_group, &[]);
+        render_pass.draw(0..4, 0..1);
+    }
+
+    /// Upload image to cache and atlas in one step. Returns the `ImageId`.
+    ///
+    /// It's used when an image is not already in the cache.
+    ///
+    /// This is a convenience method that:
+    /// 1. Reserves space in the image cache
+    /// 2. Writes the image data directly to the atlas
+    /// 3. Returns the `ImageId` for use in rendering
+    pub fn upload_image<T: AtlasWriter>(
+        &mut self,
+        resources: &mut Resources,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        writer: &T,
+    ) -> vello_common::paint::ImageId {
+        self.upload_image_with(
+            &mut resources.image_cache,
+            device,
+            queue,
+            encoder,
+            writer,
             IMAGE_PADDING,
         )
     }
 
+    /// Upload an image together with its mip chain and return its `ImageId`.
+    ///
+    /// The downsampled levels are generated on the CPU and stored in the atlas next to the
+    /// image. Paints that draw the image at less than its native size then sample from the
+    /// levels that match the covered area, blending between two levels for filtered image
+    /// qualities (trilinear filtering). This avoids the shimmering of heavily minified
+    /// images, at the cost of roughly a third of the image size in additional atlas space.
+    ///
+    /// The levels are released together with the image in
+    /// [`destroy_image`](Self::destroy_image).
+    pub fn upload_image_mipmapped(
+        &mut self,
+        resources: &mut Resources,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        pixmap: Arc<Pixmap>,
+    ) -> vello_common::paint::ImageId {
+        let chain = MipChain::new(pixmap);
+        let mut ids = chain.levels().iter().map(|level| {
+            self.upload_image_with(
+                &mut resources.image_cache,
+                device,
+                queue,
+                encoder,
+                level,
+                IMAGE_PADDING,
+            )
+        });
+        let image_id = ids.next().unwrap();
+        let levels = ids.collect();
+        resources.image_cache.set_mip_levels(image_id, levels);
+
+        image_id
+    }
+
+    /// Rasterize a single cell of `pattern` into the atlas and return its `ImageId`.
+    ///
+    /// The cell is rendered once on the GPU, after which it can be tiled by passing the
+    /// returned id to [`Pattern::paint`](vello_common::pattern::Pattern::paint). Like
+    /// [`render_to_atlas`](Self::render_to_atlas), this submits its own command encoder.
+    /// The region can be released again with [`destroy_image`](Self::destroy_image).
+    pub fn upload_pattern(
+        &mut self,
+        resources: &mut Resources,
+        device: &Device,
+        queue: &Queue,
+        pattern: &vello_common::pattern::Pattern,
+    ) -> Result<vello_common::paint::ImageId, RenderError> {
+        let upload = resources.prepare_pattern_upload(pattern)?;
+        let result = if upload.samples_atlas {
+            self.render_pattern_via_texture(resources, device, queue, &upload)
+        } else {
+            self.render_to_atlas(
+                &upload.scene,
+                upload.atlas_count,
+                upload.atlas_config,
+                device,
+                queue,
+                upload.atlas_id,
+                &TextureBindings::new(),
+            )
+        };
+        if let Err(e) = result {
+            resources.image_cache.deallocate(upload.image_id);
+            return Err(e);
+        }
+
+        Ok(upload.image_id)
+    }
+
+    /// Render a pattern cell that samples the atlas into a separate texture, and copy the
+    /// result into the cell's atlas region.
+    ///
+    /// Rendering straight into the atlas isn't possible in this case, since the atlas can't
+    /// be bound as a render target and as a texture in the same pass.
+    fn render_pattern_via_texture(
+        &mut self,
+        resources: &Resources,
+        device: &Device,
+        queue: &Queue,
+        upload: &PatternUpload,
+    ) -> Result<(), RenderError> {
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Render Pattern Encoder"),
+        });
+
+        Programs::maybe_resize_atlas_texture_array(
+            device,
+            &mut encoder,
+            &mut self.programs.resources,
+            &self.programs.atlas_bind_group_layout,
+            upload.atlas_count,
+        );
+
+        let render_size = RenderSize {
+            width: u32::from(upload.scene.width()),
+            height: u32::from(upload.scene.height()),
+        };
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Pattern Cell Texture"),
+            size: Extent3d {
+                width: render_size.width,
+                height: render_size.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&TextureViewDescriptor::default());
+
+        let encoded_paints = upload.scene.encoded_paints.borrow();
+        let result = self.render_scene(
+            &upload.scene,
+            device,
+            queue,
+            &mut encoder,
+            &render_size,
+            &view,
+            &resources.image_cache,
+            &encoded_paints,
+            TargetLoadOp::ClearTransparent,
+            RootRenderTarget::AtlasLayer,
+            &TextureBindings::new(),
+        );
+        if result.is_ok() {
+            self.write_to_atlas(
+                &resources.image_cache,
+                device,
+                queue,
+                &mut encoder,
+                upload.image_id,
+                &texture,
+                None,
+            );
+        }
+
+        queue.submit(Some(encoder.finish()));
+        texture.destroy();
+
+        result
+    }
+
     pub(crate) fn upload_image_with<T: AtlasWriter>(
         &mut self,
         image_cache: &mut ImageCache,
@@ -651,7 +1458,15 @@ impl Renderer {
         encoder: &mut CommandEncoder,
         image_id: vello_common::paint::ImageId,
     ) {
-        if let Some(image_resource) = resources.image_cache.deallocate(image_id) {
+        let Some(image_resource) = resources.image_cache.deallocate(image_id) else {
+            return;
+        };
+        let mip_levels = image_resource.mip_levels().to_vec();
+        let mip_resources = mip_levels
+            .into_iter()
+            .filter_map(|id| resources.image_cache.deallocate(id));
+
+        for image_resource in core::iter::once(image_resource).chain(mip_resources) {
             let padding = image_resource.padding as u32;
 
             self.clear_atlas_region(
@@ -728,9 +1543,10 @@ impl Renderer {
         // Set scissor rectangle to limit clearing to specific region
         render_pass.set_scissor_rect(offset[0], offset[1], width, height);
         // Use atlas clear pipeline to render transparent pixels
-        render_pass.set_pipeline(&self.programs.atlas_clear_pipeline);
+        render_pass.set_pipeline(&self.programs.pipelines.atlas_clear);
         // Draw fullscreen quad
         render_pass.draw(0..4, 0..1);
+        self.programs.frame_stats.draw_calls += 1;
     }
 
     fn prepare_gpu_encoded_paints(
@@ -739,270 +1555,567 @@ impl Renderer {
         image_cache: &ImageCache,
         texture_bindings: &TextureBindings,
     ) -> Result<(), RenderError> {
-        self.encoded_paints
-            .resize_with(encoded_paints.len(), || GPU_PAINT_PLACEHOLDER);
-        self.paint_idxs.resize(encoded_paints.len() + 1, 0);
-
-        let mut current_idx = 0;
-        for (encoded_paint_idx, paint) in encoded_paints.iter().enumerate() {
-            self.paint_idxs[encoded_paint_idx] = current_idx;
-            match paint {
-                EncodedPaint::Image(img) => {
-                    if let ImageSource::OpaqueId { id: image_id, .. } = img.source {
-                        let image_resource: Option<&ImageResource> = image_cache.get(image_id);
-                        if let Some(image_resource) = image_resource {
-                            let image_paint = self.encode_image_paint(img, image_resource);
-                            self.encoded_paints[encoded_paint_idx] = image_paint;
-                            current_idx += GPU_ENCODED_IMAGE_SIZE_TEXELS;
-                        }
-                    }
-                }
-                EncodedPaint::ExternalTexture(img) => {
-                    if texture_bindings.get(img.texture_id).is_none() {
-                        return Err(RenderError::MissingTextureBinding(img.texture_id));
-                    }
-                    let image_paint = self.encode_external_texture_paint(img);
-                    self.encoded_paints[encoded_paint_idx] = image_paint;
-                    current_idx += GPU_ENCODED_IMAGE_SIZE_TEXELS;
-                }
-                EncodedPaint::Gradient(gradient) => {
-                    let (gradient_start, gradient_width) =
-                        self.gradient_cache.get_or_create_ramp(gradient);
-                    let gradient_paint: GpuEncodedPaint =
-                        self.encode_gradient_paint(gradient, gradient_width, gradient_start);
-                    let gradient_size_texels = match &gradient_paint {
-                        GpuEncodedPaint::LinearGradient(_) => GPU_LINEAR_GRADIENT_SIZE_TEXELS,
-                        GpuEncodedPaint::RadialGradient(_) => GPU_RADIAL_GRADIENT_SIZE_TEXELS,
-                        GpuEncodedPaint::SweepGradient(_) => GPU_SWEEP_GRADIENT_SIZE_TEXELS,
-                        _ => unreachable!("encode_gradient_for_gpu only returns gradient types"),
-                    };
-                    self.encoded_paints[encoded_paint_idx] = gradient_paint;
-                    current_idx += gradient_size_texels;
-                }
-                EncodedPaint::BlurredRoundedRect(blurred_rect) => {
-                    self.encoded_paints[encoded_paint_idx] =
-                        Self::encode_blurred_rounded_rect_paint(blurred_rect);
-                    current_idx += GPU_BLURRED_ROUNDED_RECT_SIZE_TEXELS;
+        encode_gpu_paints(
+            encoded_paints,
+            image_cache,
+            &mut self.gradient_cache,
+            &mut self.encoded_paints,
+            &mut self.paint_idxs,
+            |texture| {
+                if texture_bindings.get(texture.texture_id).is_none() {
+                    return Err(RenderError::MissingTextureBinding(texture.texture_id));
                 }
-            }
-        }
-        self.paint_idxs[encoded_paints.len()] = current_idx;
-        Ok(())
+                Ok(Some(encode_external_texture_paint(texture)))
+            },
+        )
     }
+}
 
-    fn encode_image_paint(
-        &self,
-        image: &vello_common::encode::EncodedImage,
-        image_resource: &ImageResource,
-    ) -> GpuEncodedPaint {
-        let transform = image.transform.as_coeffs().map(|x| x as f32);
-        let image_size = pack_image_size(image_resource.width, image_resource.height);
-        let image_offset = pack_image_offset(image_resource.offset[0], image_resource.offset[1]);
-        let image_params = pack_image_params(
-            image.sampler.quality as u32,
-            image.sampler.x_extend as u32,
-            image.sampler.y_extend as u32,
-            image_resource.atlas_id.as_u32(),
-        );
-        let (tint, tint_mode) = pack_tint(image.tint);
-
-        GpuEncodedPaint::Image(GpuEncodedImage {
-            image_params,
-            image_size,
-            image_offset,
-            transform,
-            tint,
-            tint_mode,
-            image_padding: image_resource.padding as u32,
-        })
+#[cfg(feature = "text")]
+fn clear_atlas_region(queue: &Queue, renderer: &mut Renderer, rect: &PendingClearRect) {
+    // TODO: Can we optimize this more?
+    let byte_count = rect.width as usize * rect.height as usize * 4;
+    renderer.atlas_clear_scratch.resize(byte_count, 0);
+    queue.write_texture(
+        wgpu::TexelCopyTextureInfo {
+            texture: renderer.atlas_texture(),
+            mip_level: 0,
+            origin: wgpu::Origin3d {
+                x: rect.x as u32,
+                y: rect.y as u32,
+                z: rect.page_index,
+            },
+            aspect: wgpu::TextureAspect::All,
+        },
+        &renderer.atlas_clear_scratch[..byte_count],
+        wgpu::TexelCopyBufferLayout {
+            offset: 0,
+            bytes_per_row: Some(rect.width as u32 * 4),
+            rows_per_image: None,
+        },
+        Extent3d {
+            width: rect.width as u32,
+            height: rect.height as u32,
+            depth_or_array_layers: 1,
+        },
+    );
+}
+
+/// Defines the GPU resources and pipelines for rendering.
+#[derive(Debug)]
+struct Programs {
+    /// Render pipelines, which are built from the shaders.
+    pipelines: Pipelines,
+    /// Layouts of the render pipelines, for rebuilding them.
+    pipeline_layouts: PipelineLayouts,
+    /// Format of the render target, for rebuilding the pipelines.
+    render_target_format: wgpu::TextureFormat,
+    /// The custom paint kinds that the pipelines were built with.
+    custom_paint_kinds: Vec<Arc<dyn CustomPaintKind>>,
+    /// The shaders that were loaded with [`Renderer::reload_shaders`], if any.
+    #[cfg(feature = "hot_reload")]
+    reloaded_shaders: Option<crate::ShaderSet>,
+    /// How colors are written to the Output target.
+    output_color: OutputColorConfig,
+    /// The output transfer that the view config buffer was last written with.
+    output_transfer: OutputTransfer,
+    /// The attachments for rendering to the Output target at `render_size`.
+    attachments: TargetAttachments,
+    /// Attachments of other sizes, kept while rendering to multiple surfaces.
+    spare_attachments: Vec<(RenderSize, TargetAttachments)>,
+    /// The maximum number of spare attachments to keep when the render size changes.
+    spare_attachment_limit: usize,
+    /// The rectangle of the Output target that is rendered into, if it isn't the whole
+    /// target.
+    viewport: Option<Viewport>,
+    /// Whether the depth buffer has been cleared this frame.
+    depth_cleared_this_frame: bool,
+    /// Whether the user surface is guaranteed to stay opaque this frame, see
+    /// [`Scene::has_opaque_result`].
+    opaque_target: bool,
+    /// Records the GPU time of render passes, if profiling is enabled.
+    profiler: Option<GpuProfiler>,
+    /// Staging buffers for the uploads of each render.
+    upload_belt: UploadBelt,
+    /// Bind group layout for strip draws
+    strip_bind_group_layout: BindGroupLayout,
+    /// Bind group layout for encoded paints
+    encoded_paints_bind_group_layout: BindGroupLayout,
+    /// Bind group layout for gradient texture
+    gradient_bind_group_layout: BindGroupLayout,
+    /// Bind group layout for atlas textures
+    atlas_bind_group_layout: BindGroupLayout,
+    /// Bind group layout for filter data texture.
+    filter_bind_group_layout: BindGroupLayout,
+    /// Bind group layouts for filter input.
+    filter_input_bind_group_layouts: [BindGroupLayout; 2],
+    /// GPU resources for rendering (created during prepare)
+    resources: GpuResources,
+    /// Dimensions of the rendering target
+    render_size: RenderSize,
+    /// Dimensions that the view config buffer was last written with, which are those of the
+    /// viewport when rendering to one.
+    config_size: RenderSize,
+    /// Scratch buffer for staging encoded paints texture data.
+    encoded_paints_data: Vec<u8>,
+    /// Scratch buffer for staging filter data texture data.
+    filter_data: Vec<u8>,
+    /// The alpha bytes uploaded and the draw calls issued for the current frame.
+    frame_stats: RenderStats,
+}
+
+/// The size-dependent attachments used when rendering to the Output target.
+#[derive(Debug)]
+struct TargetAttachments {
+    /// View of the depth texture for early-z rejection.
+    depth_view: TextureView,
+    /// The texture that the scene is rendered into before it's unpremultiplied into the
+    /// Output target, if the target has straight alpha.
+    premultiplied: Option<PremultipliedTarget>,
+}
+
+/// A texture that holds the premultiplied colors of the Output target.
+#[derive(Debug, Clone)]
+struct PremultipliedTarget {
+    view: TextureView,
+    /// The bind group for reading the texture when unpremultiplying it.
+    bind_group: BindGroup,
+}
+
+impl TargetAttachments {
+    /// Create the attachments, including a premultiplied texture to be read through
+    /// `premultiplied_layout` if it's given.
+    fn new(
+        device: &Device,
+        format: wgpu::TextureFormat,
+        render_size: &RenderSize,
+        premultiplied_layout: Option<&BindGroupLayout>,
+    ) -> Self {
+        let size = Extent3d {
+            width: render_size.width.max(1),
+            height: render_size.height.max(1),
+            depth_or_array_layers: 1,
+        };
+        let depth_view = device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: Some("Depth Texture"),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: wgpu::TextureFormat::Depth24Plus,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            })
+            .create_view(&TextureViewDescriptor::default());
+        let premultiplied = premultiplied_layout.map(|layout| {
+            let view = device
+                .create_texture(&wgpu::TextureDescriptor {
+                    label: Some("Premultiplied Color Texture"),
+                    size,
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                        | wgpu::TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                })
+                .create_view(&TextureViewDescriptor::default());
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Premultiplied Color Bind Group"),
+                layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                }],
+            });
+            PremultipliedTarget { view, bind_group }
+        });
+
+        Self {
+            depth_view,
+            premultiplied,
+        }
     }
+}
 
-    fn encode_external_texture_paint(&self, image: &EncodedExternalTexture) -> GpuEncodedPaint {
-        let transform = image.transform.as_coeffs().map(|x| x as f32);
-        let region = image.source_region;
-        let image_size = pack_image_size(region.width(), region.height());
-        let image_offset = pack_image_offset(region.x0, region.y0);
-        let image_params = pack_image_params(
-            image.sampler.quality as u32,
-            image.sampler.x_extend as u32,
-            image.sampler.y_extend as u32,
-            0,
-        ) | EXTERNAL_IMAGE_SOURCE_FLAG;
-        let (tint, tint_mode) = pack_tint(image.tint);
-
-        GpuEncodedPaint::Image(GpuEncodedImage {
-            image_params,
-            image_size,
-            image_offset,
-            transform,
-            tint,
-            tint_mode,
-            image_padding: 0,
-        })
+/// The WGSL source of a shader, together with values for its pipeline-overridable constants.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ShaderSource<'a> {
+    pub(crate) wgsl: &'a str,
+    pub(crate) constants: &'a [(&'a str, f64)],
+}
+
+impl ShaderSource<'_> {
+    fn compilation_options(&self) -> PipelineCompilationOptions<'_> {
+        PipelineCompilationOptions {
+            constants: self.constants,
+            ..Default::default()
+        }
     }
+}
 
-    fn encode_gradient_paint(
-        &self,
-        gradient: &EncodedGradient,
-        gradient_width: u32,
-        gradient_start: u32,
-    ) -> GpuEncodedPaint {
-        let transform = gradient.transform.as_coeffs().map(|x| x as f32);
-        let extend_mode = match gradient.extend {
-            peniko::Extend::Pad => 0,
-            peniko::Extend::Repeat => 1,
-            peniko::Extend::Reflect => 2,
+/// The shaders that the render pipelines are built from.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ShaderSources<'a> {
+    pub(crate) render_strips: ShaderSource<'a>,
+    pub(crate) clear_slots: ShaderSource<'a>,
+    pub(crate) filters: ShaderSource<'a>,
+}
+
+impl ShaderSources<'static> {
+    /// The shaders that were bundled at build time.
+    const BUILTIN: Self = Self {
+        render_strips: ShaderSource {
+            wgsl: vello_sparse_shaders::wgsl::RENDER_STRIPS,
+            constants: &[],
+        },
+        clear_slots: ShaderSource {
+            wgsl: vello_sparse_shaders::wgsl::CLEAR_SLOTS,
+            constants: &[],
+        },
+        filters: ShaderSource {
+            wgsl: vello_sparse_shaders::wgsl::FILTERS,
+            constants: &[],
+        },
+    };
+}
+
+/// Pipeline layouts, which stay the same when the pipelines are rebuilt.
+#[derive(Debug)]
+struct PipelineLayouts {
+    strip: wgpu::PipelineLayout,
+    clear: wgpu::PipelineLayout,
+    atlas_clear: wgpu::PipelineLayout,
+    filter: wgpu::PipelineLayout,
+    unpremultiply: wgpu::PipelineLayout,
+}
+
+/// All render pipelines of the renderer.
+#[derive(Debug)]
+struct Pipelines {
+    /// Pipelines for rendering strips to slot textures (depth test OFF, depth write OFF, blending ON).
+    /// The first pipeline should be used for color attachments in the native pixel format,
+    /// the second for color attachments in RGBA8.
+    slot_strip: [RenderPipeline; 2],
+    /// Alpha pipelines for rendering strips to Output targets (depth test ON, depth write OFF, blending ON).
+    alpha_strip: [RenderPipeline; 2],
+    /// Alpha pipelines for rendering strips to Output targets that stay opaque. The same as
+    /// `alpha_strip`, but the alpha channel isn't written, since it's already 1.
+    opaque_target_alpha_strip: [RenderPipeline; 2],
+    /// Opaque pipelines for rendering strips to Output targets (depth test ON, depth write ON, blending OFF).
+    opaque_strip: [RenderPipeline; 2],
+    /// Pipeline for applying filter effects.
+    filter: RenderPipeline,
+    /// Pipeline for clearing slots in slot textures.
+    clear: RenderPipeline,
+    /// Pipeline for clearing atlas regions.
+    atlas_clear: RenderPipeline,
+    /// Pipeline for clearing the viewport of the render target to the blend constant.
+    viewport_clear: RenderPipeline,
+    /// Pipeline for unpremultiplying the colors of the render target.
+    unpremultiply: RenderPipeline,
+}
+
+impl Pipelines {
+    fn new(
+        device: &Device,
+        layouts: &PipelineLayouts,
+        render_target_format: wgpu::TextureFormat,
+        shaders: &ShaderSources<'_>,
+    ) -> Self {
+        let strip_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Strip Shader"),
+            source: wgpu::ShaderSource::Wgsl(shaders.render_strips.wgsl.into()),
+        });
+
+        let clear_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Clear Slots Shader"),
+            source: wgpu::ShaderSource::Wgsl(shaders.clear_slots.wgsl.into()),
+        });
+
+        let depth_format = wgpu::TextureFormat::Depth24Plus;
+        let strip_formats = [render_target_format, wgpu::TextureFormat::Rgba8Unorm];
+
+        let strip_vertex_state = wgpu::VertexBufferLayout {
+            array_stride: size_of::<GpuStrip>() as u64,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &GpuStrip::vertex_attributes(),
         };
-        let texture_width_and_extend_mode =
-            pack_texture_width_and_extend_mode(gradient_width, extend_mode);
-
-        match &gradient.kind {
-            EncodedKind::Linear(_) => GpuEncodedPaint::LinearGradient(GpuLinearGradient {
-                texture_width_and_extend_mode,
-                gradient_start,
-                transform,
-            }),
-            EncodedKind::Radial(radial) => {
-                let (kind, bias, scale, fp0, fp1, fr1, f_focal_x, f_is_swapped, scaled_r0_squared) =
-                    match radial {
-                        RadialKind::Radial { bias, scale } => {
-                            (0, *bias, *scale, 0.0, 0.0, 0.0, 0.0, 0, 0.0)
-                        }
-                        RadialKind::Strip { scaled_r0_squared } => {
-                            (1, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0, *scaled_r0_squared)
-                        }
-                        RadialKind::Focal {
-                            focal_data,
-                            fp0,
-                            fp1,
-                        } => (
-                            2,
-                            *fp0,
-                            *fp1,
-                            *fp0,
-                            *fp1,
-                            focal_data.fr1,
-                            focal_data.f_focal_x,
-                            focal_data.f_is_swapped as u32,
-                            0.0,
-                        ),
-                    };
-                GpuEncodedPaint::RadialGradient(GpuRadialGradient {
-                    texture_width_and_extend_mode,
-                    gradient_start,
-                    transform,
-                    kind_and_f_is_swapped: pack_radial_kind_and_swapped(kind, f_is_swapped),
-                    bias,
-                    scale,
-                    fp0,
-                    fp1,
-                    fr1,
-                    f_focal_x,
-                    scaled_r0_squared,
+
+        let create_strip_pipelines = |label,
+                                      blend,
+                                      write_mask,
+                                      depth_stencil: Option<wgpu::DepthStencilState>|
+         -> [RenderPipeline; 2] {
+            core::array::from_fn(|i| {
+                device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some(label),
+                    layout: Some(&layouts.strip),
+                    vertex: wgpu::VertexState {
+                        module: &strip_shader,
+                        entry_point: Some("vs_main"),
+                        buffers: core::slice::from_ref(&strip_vertex_state),
+                        compilation_options: shaders.render_strips.compilation_options(),
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &strip_shader,
+                        entry_point: Some("fs_main"),
+                        targets: &[Some(ColorTargetState {
+                            format: strip_formats[i],
+                            blend,
+                            write_mask,
+                        })],
+                        compilation_options: shaders.render_strips.compilation_options(),
+                    }),
+                    primitive: wgpu::PrimitiveState {
+                        topology: wgpu::PrimitiveTopology::TriangleStrip,
+                        ..Default::default()
+                    },
+                    depth_stencil: depth_stencil.clone(),
+                    multisample: wgpu::MultisampleState::default(),
+                    multiview_mask: None,
+                    cache: None,
                 })
-            }
-            EncodedKind::Sweep(sweep) => GpuEncodedPaint::SweepGradient(GpuSweepGradient {
-                texture_width_and_extend_mode,
-                gradient_start,
-                transform,
-                start_angle: sweep.start_angle,
-                inv_angle_delta: sweep.inv_angle_delta,
-                _padding: [0, 0],
+            })
+        };
+
+        let depth_stencil = |depth_write_enabled| wgpu::DepthStencilState {
+            format: depth_format,
+            depth_write_enabled: Some(depth_write_enabled),
+            depth_compare: Some(wgpu::CompareFunction::LessEqual),
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        };
+
+        // Slot pipelines: depth test OFF, depth write OFF, blending ON.
+        let slot_strip_pipelines = create_strip_pipelines(
+            "Strip Slot Pipeline",
+            Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+            ColorWrites::ALL,
+            None,
+        );
+        // Alpha pipelines: depth test ON (LessEqual), depth write OFF, blending ON.
+        let alpha_strip_pipelines = create_strip_pipelines(
+            "Strip Alpha Pipeline",
+            Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+            ColorWrites::ALL,
+            Some(depth_stencil(false)),
+        );
+        // Opaque target alpha pipelines: like the alpha pipelines, but without alpha writes.
+        let opaque_target_alpha_strip_pipelines = create_strip_pipelines(
+            "Strip Opaque Target Alpha Pipeline",
+            Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+            ColorWrites::COLOR,
+            Some(depth_stencil(false)),
+        );
+        // Opaque pipelines: depth test ON (LessEqual), depth write ON, blending OFF.
+        let opaque_strip_pipelines = create_strip_pipelines(
+            "Strip Opaque Pipeline",
+            None,
+            ColorWrites::ALL,
+            Some(depth_stencil(true)),
+        );
+
+        let clear_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Clear Slots Pipeline"),
+            layout: Some(&layouts.clear),
+            vertex: wgpu::VertexState {
+                module: &clear_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: size_of::<u32>() as u64,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &[wgpu::VertexAttribute {
+                        format: wgpu::VertexFormat::Uint32,
+                        offset: 0,
+                        shader_location: 0,
+                    }],
+                }],
+                compilation_options: shaders.clear_slots.compilation_options(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &clear_shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(ColorTargetState {
+                    format: render_target_format,
+                    // No blending needed for clearing
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: shaders.clear_slots.compilation_options(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview_mask: None,
+            cache: None,
+        });
+
+        let atlas_clear_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Atlas Clear Pipeline"),
+            layout: Some(&layouts.atlas_clear),
+            vertex: wgpu::VertexState {
+                module: &clear_shader,
+                // Use a different vertex shader entry point
+                entry_point: Some("vs_main_fullscreen"),
+                buffers: &[],
+                compilation_options: shaders.clear_slots.compilation_options(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &clear_shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(ColorTargetState {
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: shaders.clear_slots.compilation_options(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview_mask: None,
+            cache: None,
+        });
+
+        let viewport_clear_pipeline =
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("Viewport Clear Pipeline"),
+                layout: Some(&layouts.atlas_clear),
+                vertex: wgpu::VertexState {
+                    module: &clear_shader,
+                    entry_point: Some("vs_main_fullscreen"),
+                    buffers: &[],
+                    compilation_options: shaders.clear_slots.compilation_options(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &clear_shader,
+                    entry_point: Some("fs_main_blend_constant"),
+                    targets: &[Some(ColorTargetState {
+                        format: render_target_format,
+                        blend: Some(BlendState {
+                            color: wgpu::BlendComponent {
+                                src_factor: wgpu::BlendFactor::Constant,
+                                dst_factor: wgpu::BlendFactor::Zero,
+                                operation: wgpu::BlendOperation::Add,
+                            },
+                            alpha: wgpu::BlendComponent {
+                                src_factor: wgpu::BlendFactor::Constant,
+                                dst_factor: wgpu::BlendFactor::Zero,
+                                operation: wgpu::BlendOperation::Add,
+                            },
+                        }),
+                        write_mask: ColorWrites::ALL,
+                    })],
+                    compilation_options: shaders.clear_slots.compilation_options(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleStrip,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview_mask: None,
+                cache: None,
+            });
+
+        let unpremultiply_pipeline =
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("Unpremultiply Pipeline"),
+                layout: Some(&layouts.unpremultiply),
+                vertex: wgpu::VertexState {
+                    module: &clear_shader,
+                    entry_point: Some("vs_main_fullscreen"),
+                    buffers: &[],
+                    compilation_options: shaders.clear_slots.compilation_options(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &clear_shader,
+                    entry_point: Some("fs_main_unpremultiply"),
+                    targets: &[Some(ColorTargetState {
+                        format: render_target_format,
+                        blend: None,
+                        write_mask: ColorWrites::ALL,
+                    })],
+                    compilation_options: shaders.clear_slots.compilation_options(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleStrip,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview_mask: None,
+                cache: None,
+            });
+
+        let filter_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Filter Shader"),
+            source: wgpu::ShaderSource::Wgsl(shaders.filters.wgsl.into()),
+        });
+        let filter_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Filter Pipeline"),
+            layout: Some(&layouts.filter),
+            vertex: wgpu::VertexState {
+                module: &filter_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: size_of::<FilterInstanceData>() as u64,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &wgpu::vertex_attr_array![
+                        0 => Uint32x2,
+                        1 => Uint32x2,
+                        2 => Uint32x2,
+                        3 => Uint32x2,
+                        4 => Uint32x2,
+                        5 => Uint32,
+                        6 => Uint32x2,
+                        7 => Uint32x2,
+                        8 => Uint32,
+                    ],
+                }],
+                compilation_options: shaders.filters.compilation_options(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &filter_shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(ColorTargetState {
+                    format: wgpu::TextureFormat::Rgba8Unorm,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: shaders.filters.compilation_options(),
             }),
-        }
-    }
-
-    fn encode_blurred_rounded_rect_paint(rect: &EncodedBlurredRoundedRectangle) -> GpuEncodedPaint {
-        GpuEncodedPaint::BlurredRoundedRect(GpuBlurredRoundedRect {
-            transform: rect.transform.as_coeffs().map(|x| x as f32),
-            color: rect.color.as_premul_rgba8().to_u32(),
-            _padding0: 0,
-            params0: [
-                rect.exponent,
-                rect.recip_exponent,
-                rect.scale,
-                rect.std_dev_inv,
-            ],
-            params1: [rect.min_edge, rect.w, rect.h, rect.r1],
-            size: [rect.width, rect.height],
-            _padding1: [0, 0],
-        })
-    }
-}
-
-#[cfg(feature = "text")]
-fn clear_atlas_region(queue: &Queue, renderer: &mut Renderer, rect: &PendingClearRect) {
-    // TODO: Can we optimize this more?
-    let byte_count = rect.width as usize * rect.height as usize * 4;
-    renderer.atlas_clear_scratch.resize(byte_count, 0);
-    queue.write_texture(
-        wgpu::TexelCopyTextureInfo {
-            texture: renderer.atlas_texture(),
-            mip_level: 0,
-            origin: wgpu::Origin3d {
-                x: rect.x as u32,
-                y: rect.y as u32,
-                z: rect.page_index,
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleStrip,
+                ..Default::default()
             },
-            aspect: wgpu::TextureAspect::All,
-        },
-        &renderer.atlas_clear_scratch[..byte_count],
-        wgpu::TexelCopyBufferLayout {
-            offset: 0,
-            bytes_per_row: Some(rect.width as u32 * 4),
-            rows_per_image: None,
-        },
-        Extent3d {
-            width: rect.width as u32,
-            height: rect.height as u32,
-            depth_or_array_layers: 1,
-        },
-    );
-}
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            cache: None,
+            multiview_mask: None,
+        });
 
-/// Defines the GPU resources and pipelines for rendering.
-#[derive(Debug)]
-struct Programs {
-    /// Pipelines for rendering strips to slot textures (depth test OFF, depth write OFF, blending ON).
-    /// The first pipeline should be used for color attachments in the native pixel format,
-    /// the second for color attachments in RGBA8.
-    slot_strip_pipelines: [RenderPipeline; 2],
-    /// Alpha pipelines for rendering strips to Output targets (depth test ON, depth write OFF, blending ON).
-    alpha_strip_pipelines: [RenderPipeline; 2],
-    /// Opaque pipelines for rendering strips to Output targets (depth test ON, depth write ON, blending OFF).
-    opaque_strip_pipelines: [RenderPipeline; 2],
-    /// Depth texture for early-z rejection on the Output target.
-    depth_texture: Texture,
-    /// View for the depth texture.
-    depth_texture_view: TextureView,
-    /// Whether the depth buffer has been cleared this frame.
-    depth_cleared_this_frame: bool,
-    /// Bind group layout for strip draws
-    strip_bind_group_layout: BindGroupLayout,
-    /// Bind group layout for encoded paints
-    encoded_paints_bind_group_layout: BindGroupLayout,
-    /// Bind group layout for gradient texture
-    gradient_bind_group_layout: BindGroupLayout,
-    /// Bind group layout for atlas textures
-    atlas_bind_group_layout: BindGroupLayout,
-    /// Bind group layout for filter data texture.
-    filter_bind_group_layout: BindGroupLayout,
-    /// Pipeline for applying filter effects.
-    filter_pipeline: RenderPipeline,
-    /// Bind group layouts for filter input.
-    filter_input_bind_group_layouts: [BindGroupLayout; 2],
-    /// Pipeline for clearing slots in slot textures.
-    clear_pipeline: RenderPipeline,
-    /// Pipeline for clearing atlas regions.
-    atlas_clear_pipeline: RenderPipeline,
-    /// GPU resources for rendering (created during prepare)
-    resources: GpuResources,
-    /// Dimensions of the rendering target
-    render_size: RenderSize,
-    /// Scratch buffer for staging encoded paints texture data.
-    encoded_paints_data: Vec<u8>,
-    /// Scratch buffer for staging filter data texture data.
-    filter_data: Vec<u8>,
+        Self {
+            slot_strip: slot_strip_pipelines,
+            alpha_strip: alpha_strip_pipelines,
+            opaque_target_alpha_strip: opaque_target_alpha_strip_pipelines,
+            opaque_strip: opaque_strip_pipelines,
+            filter: filter_pipeline,
+            clear: clear_pipeline,
+            atlas_clear: atlas_clear_pipeline,
+            viewport_clear: viewport_clear_pipeline,
+            unpremultiply: unpremultiply_pipeline,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -1098,6 +2211,11 @@ struct GpuResources {
     atlas_bind_group: BindGroup,
     /// Transparent 1x1 placeholder texture in case no external texture is bound by the user.
     placeholder_external_texture_view: TextureView,
+    /// Paint-source bind groups for external textures, with the view each one was created for.
+    ///
+    /// These are kept across frames and only recreated when a texture is bound to a different
+    /// view or the atlas texture array is reallocated.
+    external_paint_source_bind_groups: HashMap<TextureId, (TextureView, BindGroup)>,
     /// Filter atlas textures and their associated views/bind groups.
     /// Lazily allocated: stays empty until the first scene with filters.
     filter_atlas: FilterAtlasState,
@@ -1136,6 +2254,15 @@ struct GpuResources {
     stub_atlas_bind_group: BindGroup,
 }
 
+/// The smallest number of layers of the alpha, encoded paints and gradient textures.
+///
+/// See the comment in `Renderer::new_with`: the wgpu GLES backend treats texture arrays with a
+/// single layer as 2D textures.
+#[cfg(target_arch = "wasm32")]
+const MIN_LINEAR_TEXTURE_LAYERS: u32 = 2;
+#[cfg(not(target_arch = "wasm32"))]
+const MIN_LINEAR_TEXTURE_LAYERS: u32 = 1;
+
 const SIZE_OF_CONFIG: NonZeroU64 = NonZeroU64::new(size_of::<Config>() as u64).unwrap();
 
 /// Config for the clear slots pipeline
@@ -1167,6 +2294,60 @@ impl GpuStrip {
 }
 
 impl Programs {
+    /// Build the render pipelines from `sources`, with the custom paint kinds that the
+    /// current pipelines were built with.
+    fn build_pipelines(&self, device: &Device, sources: &ShaderSources<'_>) -> Pipelines {
+        let composed = if self.custom_paint_kinds.is_empty() {
+            None
+        } else {
+            let composed =
+                compose_custom_paints(sources.render_strips.wgsl, &self.custom_paint_kinds);
+            if composed.is_none() {
+                log::warn!("the strip shader has no placeholder for custom paints");
+            }
+            composed
+        };
+
+        let mut sources = *sources;
+        if let Some(wgsl) = &composed {
+            sources.render_strips.wgsl = wgsl;
+        }
+        Pipelines::new(
+            device,
+            &self.pipeline_layouts,
+            self.render_target_format,
+            &sources,
+        )
+    }
+
+    /// Rebuild the render pipelines if `registry` contains custom paint kinds that they
+    /// weren't built with.
+    ///
+    /// Registries whose kinds are a prefix of the current ones agree on their identifiers,
+    /// so they can keep using the current pipelines.
+    fn sync_custom_paints(&mut self, device: &Device, registry: &CustomPaintRegistry) {
+        let compatible = registry.len() <= self.custom_paint_kinds.len()
+            && registry
+                .kinds()
+                .zip(&self.custom_paint_kinds)
+                .all(|((_, kind), current)| Arc::ptr_eq(kind, current));
+        if compatible {
+            return;
+        }
+
+        self.custom_paint_kinds = registry.kinds().map(|(_, kind)| kind.clone()).collect();
+
+        #[cfg(feature = "hot_reload")]
+        if let Some(shaders) = &self.reloaded_shaders
+            && let Ok(constants) = shaders.validate()
+        {
+            self.pipelines = self.build_pipelines(device, &shaders.sources(&constants));
+            return;
+        }
+
+        self.pipelines = self.build_pipelines(device, &ShaderSources::BUILTIN);
+    }
+
     fn new(
         device: &Device,
         image_cache: &ImageCache,
@@ -1183,7 +2364,7 @@ impl Programs {
                         visibility: wgpu::ShaderStages::FRAGMENT,
                         ty: wgpu::BindingType::Texture {
                             sample_type: wgpu::TextureSampleType::Uint,
-                            view_dimension: wgpu::TextureViewDimension::D2,
+                            view_dimension: wgpu::TextureViewDimension::D2Array,
                             multisampled: false,
                         },
                         count: None,
@@ -1246,7 +2427,7 @@ impl Programs {
                     visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Texture {
                         sample_type: wgpu::TextureSampleType::Uint,
-                        view_dimension: wgpu::TextureViewDimension::D2,
+                        view_dimension: wgpu::TextureViewDimension::D2Array,
                         multisampled: false,
                     },
                     count: None,
@@ -1261,7 +2442,7 @@ impl Programs {
                     visibility: wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Texture {
                         sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                        view_dimension: wgpu::TextureViewDimension::D2,
+                        view_dimension: wgpu::TextureViewDimension::D2Array,
                         multisampled: false,
                     },
                     count: None,
@@ -1284,176 +2465,32 @@ impl Programs {
                 }],
             });
 
-        let strip_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Strip Shader"),
-            source: wgpu::ShaderSource::Wgsl(vello_sparse_shaders::wgsl::RENDER_STRIPS.into()),
-        });
-
-        let clear_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Clear Slots Shader"),
-            source: wgpu::ShaderSource::Wgsl(vello_sparse_shaders::wgsl::CLEAR_SLOTS.into()),
-        });
-
         let strip_pipeline_layout =
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: Some("Strip Pipeline Layout"),
                 bind_group_layouts: &[
                     Some(&strip_bind_group_layout),
-                    Some(&atlas_bind_group_layout),
-                    Some(&encoded_paints_bind_group_layout),
-                    Some(&gradient_bind_group_layout),
-                ],
-                immediate_size: 0,
-            });
-
-        let clear_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Clear Slots Pipeline Layout"),
-                bind_group_layouts: &[Some(&clear_bind_group_layout)],
-                immediate_size: 0,
-            });
-
-        let depth_format = wgpu::TextureFormat::Depth24Plus;
-        let strip_formats = [render_target_config.format, wgpu::TextureFormat::Rgba8Unorm];
-
-        let strip_vertex_state = wgpu::VertexBufferLayout {
-            array_stride: size_of::<GpuStrip>() as u64,
-            step_mode: wgpu::VertexStepMode::Instance,
-            attributes: &GpuStrip::vertex_attributes(),
-        };
-
-        let create_strip_pipelines =
-            |label, blend, depth_stencil: Option<wgpu::DepthStencilState>| -> [RenderPipeline; 2] {
-                core::array::from_fn(|i| {
-                    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-                        label: Some(label),
-                        layout: Some(&strip_pipeline_layout),
-                        vertex: wgpu::VertexState {
-                            module: &strip_shader,
-                            entry_point: Some("vs_main"),
-                            buffers: core::slice::from_ref(&strip_vertex_state),
-                            compilation_options: PipelineCompilationOptions::default(),
-                        },
-                        fragment: Some(wgpu::FragmentState {
-                            module: &strip_shader,
-                            entry_point: Some("fs_main"),
-                            targets: &[Some(ColorTargetState {
-                                format: strip_formats[i],
-                                blend,
-                                write_mask: ColorWrites::ALL,
-                            })],
-                            compilation_options: PipelineCompilationOptions::default(),
-                        }),
-                        primitive: wgpu::PrimitiveState {
-                            topology: wgpu::PrimitiveTopology::TriangleStrip,
-                            ..Default::default()
-                        },
-                        depth_stencil: depth_stencil.clone(),
-                        multisample: wgpu::MultisampleState::default(),
-                        multiview_mask: None,
-                        cache: None,
-                    })
-                })
-            };
-
-        let depth_stencil = |depth_write_enabled| wgpu::DepthStencilState {
-            format: depth_format,
-            depth_write_enabled: Some(depth_write_enabled),
-            depth_compare: Some(wgpu::CompareFunction::LessEqual),
-            stencil: wgpu::StencilState::default(),
-            bias: wgpu::DepthBiasState::default(),
-        };
-
-        // Slot pipelines: depth test OFF, depth write OFF, blending ON.
-        let slot_strip_pipelines = create_strip_pipelines(
-            "Strip Slot Pipeline",
-            Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
-            None,
-        );
-        // Alpha pipelines: depth test ON (LessEqual), depth write OFF, blending ON.
-        let alpha_strip_pipelines = create_strip_pipelines(
-            "Strip Alpha Pipeline",
-            Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
-            Some(depth_stencil(false)),
-        );
-        // Opaque pipelines: depth test ON (LessEqual), depth write ON, blending OFF.
-        let opaque_strip_pipelines =
-            create_strip_pipelines("Strip Opaque Pipeline", None, Some(depth_stencil(true)));
-
-        let clear_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Clear Slots Pipeline"),
-            layout: Some(&clear_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &clear_shader,
-                entry_point: Some("vs_main"),
-                buffers: &[wgpu::VertexBufferLayout {
-                    array_stride: size_of::<u32>() as u64,
-                    step_mode: wgpu::VertexStepMode::Instance,
-                    attributes: &[wgpu::VertexAttribute {
-                        format: wgpu::VertexFormat::Uint32,
-                        offset: 0,
-                        shader_location: 0,
-                    }],
-                }],
-                compilation_options: PipelineCompilationOptions::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &clear_shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(ColorTargetState {
-                    format: render_target_config.format,
-                    // No blending needed for clearing
-                    blend: None,
-                    write_mask: ColorWrites::ALL,
-                })],
-                compilation_options: PipelineCompilationOptions::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleStrip,
-                ..Default::default()
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview_mask: None,
-            cache: None,
-        });
-
-        // Create atlas clear pipeline
-        let atlas_clear_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Atlas Clear Pipeline Layout"),
-                bind_group_layouts: &[],
-                immediate_size: 0,
-            });
-        let atlas_clear_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Atlas Clear Pipeline"),
-            layout: Some(&atlas_clear_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &clear_shader,
-                // Use a different vertex shader entry point
-                entry_point: Some("vs_main_fullscreen"),
-                buffers: &[],
-                compilation_options: PipelineCompilationOptions::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &clear_shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(ColorTargetState {
-                    format: wgpu::TextureFormat::Rgba8Unorm,
-                    blend: None,
-                    write_mask: ColorWrites::ALL,
-                })],
-                compilation_options: PipelineCompilationOptions::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleStrip,
-                ..Default::default()
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview_mask: None,
-            cache: None,
-        });
+                    Some(&atlas_bind_group_layout),
+                    Some(&encoded_paints_bind_group_layout),
+                    Some(&gradient_bind_group_layout),
+                ],
+                immediate_size: 0,
+            });
+
+        let clear_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Clear Slots Pipeline Layout"),
+                bind_group_layouts: &[Some(&clear_bind_group_layout)],
+                immediate_size: 0,
+            });
+
+        // Create atlas clear pipeline
+        let atlas_clear_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Atlas Clear Pipeline Layout"),
+                bind_group_layouts: &[],
+                immediate_size: 0,
+            });
 
         let filter_texture_entry = wgpu::BindGroupLayoutEntry {
             binding: 0,
@@ -1500,10 +2537,6 @@ impl Programs {
             }),
         ];
 
-        let filter_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Filter Shader"),
-            source: wgpu::ShaderSource::Wgsl(vello_sparse_shaders::wgsl::FILTERS.into()),
-        });
         let filter_pipeline_layout =
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 label: Some("Filter Pipeline Layout"),
@@ -1514,48 +2547,25 @@ impl Programs {
                 ],
                 immediate_size: 0,
             });
-        let filter_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Filter Pipeline"),
-            layout: Some(&filter_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &filter_shader,
-                entry_point: Some("vs_main"),
-                buffers: &[wgpu::VertexBufferLayout {
-                    array_stride: size_of::<FilterInstanceData>() as u64,
-                    step_mode: wgpu::VertexStepMode::Instance,
-                    attributes: &wgpu::vertex_attr_array![
-                        0 => Uint32x2,
-                        1 => Uint32x2,
-                        2 => Uint32x2,
-                        3 => Uint32x2,
-                        4 => Uint32x2,
-                        5 => Uint32,
-                        6 => Uint32x2,
-                        7 => Uint32x2,
-                        8 => Uint32,
-                    ],
-                }],
-                compilation_options: PipelineCompilationOptions::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &filter_shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(ColorTargetState {
-                    format: wgpu::TextureFormat::Rgba8Unorm,
-                    blend: None,
-                    write_mask: ColorWrites::ALL,
-                })],
-                compilation_options: PipelineCompilationOptions::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleStrip,
-                ..Default::default()
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            cache: None,
-            multiview_mask: None,
-        });
+        let unpremultiply_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("Unpremultiply Pipeline Layout"),
+                bind_group_layouts: &[None, Some(&filter_input_bind_group_layouts[1])],
+                immediate_size: 0,
+            });
+        let pipeline_layouts = PipelineLayouts {
+            strip: strip_pipeline_layout,
+            clear: clear_pipeline_layout,
+            atlas_clear: atlas_clear_pipeline_layout,
+            filter: filter_pipeline_layout,
+            unpremultiply: unpremultiply_pipeline_layout,
+        };
+        let pipelines = Pipelines::new(
+            device,
+            &pipeline_layouts,
+            render_target_config.format,
+            &ShaderSources::BUILTIN,
+        );
 
         let slot_texture_views: [TextureView; 2] = core::array::from_fn(|_| {
             device
@@ -1608,6 +2618,7 @@ impl Programs {
                 height: u32::from(Tile::HEIGHT) * slot_count as u32,
             },
             device.limits().max_texture_dimension_2d,
+            render_target_config.output_color.decodes_paints(),
         );
 
         let max_texture_dimension_2d = device.limits().max_texture_dimension_2d;
@@ -1616,6 +2627,7 @@ impl Programs {
             device,
             max_texture_dimension_2d,
             INITIAL_ALPHA_TEXTURE_HEIGHT,
+            MIN_LINEAR_TEXTURE_LAYERS,
         );
         let view_config_buffer = Self::create_config_buffer(
             device,
@@ -1624,6 +2636,7 @@ impl Programs {
                 height: render_target_config.height,
             },
             max_texture_dimension_2d,
+            render_target_config.output_color.decodes_paints(),
         );
 
         let AtlasConfig {
@@ -1660,18 +2673,21 @@ impl Programs {
         const INITIAL_ENCODED_PAINTS_TEXTURE_HEIGHT: u32 = 1;
         let encoded_paints_data = vec![
             0;
-            ((max_texture_dimension_2d * INITIAL_ENCODED_PAINTS_TEXTURE_HEIGHT) << 4)
-                as usize
+            ((max_texture_dimension_2d
+                * INITIAL_ENCODED_PAINTS_TEXTURE_HEIGHT
+                * MIN_LINEAR_TEXTURE_LAYERS)
+                << 4) as usize
         ];
         let encoded_paints_texture = Self::create_encoded_paints_texture(
             device,
             max_texture_dimension_2d,
             INITIAL_ENCODED_PAINTS_TEXTURE_HEIGHT,
+            MIN_LINEAR_TEXTURE_LAYERS,
         );
         let encoded_paints_bind_group = Self::create_encoded_paints_bind_group(
             device,
             &encoded_paints_bind_group_layout,
-            &encoded_paints_texture.create_view(&TextureViewDescriptor::default()),
+            &create_linear_texture_view(&encoded_paints_texture),
         );
 
         const INITIAL_GRADIENT_TEXTURE_HEIGHT: u32 = 1;
@@ -1679,11 +2695,12 @@ impl Programs {
             device,
             max_texture_dimension_2d,
             INITIAL_GRADIENT_TEXTURE_HEIGHT,
+            MIN_LINEAR_TEXTURE_LAYERS,
         );
         let gradient_bind_group = Self::create_gradient_bind_group(
             device,
             &gradient_bind_group_layout,
-            &gradient_texture.create_view(&TextureViewDescriptor::default()),
+            &create_linear_texture_view(&gradient_texture),
         );
 
         let AtlasConfig {
@@ -1712,7 +2729,7 @@ impl Programs {
         let slot_bind_groups = Self::create_strip_bind_groups(
             device,
             &strip_bind_group_layout,
-            &alphas_texture.create_view(&TextureViewDescriptor::default()),
+            &create_linear_texture_view(&alphas_texture),
             &slot_config_buffer,
             &view_config_buffer,
             &slot_texture_views,
@@ -1734,6 +2751,7 @@ impl Programs {
             atlas_texture_array_view,
             atlas_bind_group,
             placeholder_external_texture_view,
+            external_paint_source_bind_groups: HashMap::new(),
             filter_atlas,
             stub_atlas_bind_group,
             encoded_paints_texture,
@@ -1745,57 +2763,52 @@ impl Programs {
             view_config_buffer,
         };
 
-        let depth_texture = Self::create_depth_texture(
+        let render_size = RenderSize {
+            width: render_target_config.width,
+            height: render_target_config.height,
+        };
+        let attachments = TargetAttachments::new(
             device,
-            render_target_config.width,
-            render_target_config.height,
+            render_target_config.format,
+            &render_size,
+            render_target_config
+                .output_color
+                .needs_premultiplied_target()
+                .then_some(&filter_input_bind_group_layouts[1]),
         );
-        let depth_texture_view = depth_texture.create_view(&TextureViewDescriptor::default());
 
         Self {
-            slot_strip_pipelines,
-            alpha_strip_pipelines,
-            opaque_strip_pipelines,
-            depth_texture,
-            depth_texture_view,
+            pipelines,
+            pipeline_layouts,
+            render_target_format: render_target_config.format,
+            custom_paint_kinds: Vec::new(),
+            #[cfg(feature = "hot_reload")]
+            reloaded_shaders: None,
+            output_color: render_target_config.output_color,
+            output_transfer: OutputTransfer::NONE,
+            attachments,
+            spare_attachments: Vec::new(),
+            spare_attachment_limit: 0,
+            viewport: None,
             depth_cleared_this_frame: false,
+            opaque_target: false,
+            profiler: None,
+            upload_belt: UploadBelt::default(),
             strip_bind_group_layout,
             encoded_paints_bind_group_layout,
             gradient_bind_group_layout,
             atlas_bind_group_layout,
             filter_bind_group_layout,
-            filter_pipeline,
             filter_input_bind_group_layouts,
             resources,
             encoded_paints_data,
             filter_data,
-            render_size: RenderSize {
-                width: render_target_config.width,
-                height: render_target_config.height,
-            },
-            clear_pipeline,
-            atlas_clear_pipeline,
+            frame_stats: RenderStats::default(),
+            config_size: render_size.clone(),
+            render_size,
         }
     }
 
-    #[inline]
-    fn create_depth_texture(device: &Device, width: u32, height: u32) -> Texture {
-        device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Depth Texture"),
-            size: Extent3d {
-                width: width.max(1),
-                height: height.max(1),
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Depth24Plus,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            view_formats: &[],
-        })
-    }
-
     fn create_strips_buffer(device: &Device, required_strips_size: u64) -> Buffer {
         device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Strips Buffer"),
@@ -1827,6 +2840,7 @@ impl Programs {
         device: &Device,
         render_size: &RenderSize,
         alpha_texture_width: u32,
+        decode_paints: bool,
     ) -> Buffer {
         device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Config Buffer"),
@@ -1839,18 +2853,22 @@ impl Programs {
                 strip_offset_x: 0,
                 strip_offset_y: 0,
                 negate_ndc: 0,
+                output_transfer: OutputTransfer::NONE.flags,
+                output_scale: OutputTransfer::NONE.scale,
+                decode_paints: u32::from(decode_paints),
+                _padding: 0,
             }),
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         })
     }
 
-    fn create_alphas_texture(device: &Device, width: u32, height: u32) -> Texture {
+    fn create_alphas_texture(device: &Device, width: u32, height: u32, layers: u32) -> Texture {
         device.create_texture(&wgpu::TextureDescriptor {
             label: Some("Alpha Texture"),
             size: Extent3d {
                 width,
                 height,
-                depth_or_array_layers: 1,
+                depth_or_array_layers: layers,
             },
             mip_level_count: 1,
             sample_count: 1,
@@ -1980,13 +2998,18 @@ impl Programs {
         })
     }
 
-    fn create_encoded_paints_texture(device: &Device, width: u32, height: u32) -> Texture {
+    fn create_encoded_paints_texture(
+        device: &Device,
+        width: u32,
+        height: u32,
+        layers: u32,
+    ) -> Texture {
         device.create_texture(&wgpu::TextureDescriptor {
             label: Some("Encoded Paints Texture"),
             size: Extent3d {
                 width,
                 height,
-                depth_or_array_layers: 1,
+                depth_or_array_layers: layers,
             },
             mip_level_count: 1,
             sample_count: 1,
@@ -2012,13 +3035,13 @@ impl Programs {
         })
     }
 
-    fn create_gradient_texture(device: &Device, width: u32, height: u32) -> Texture {
+    fn create_gradient_texture(device: &Device, width: u32, height: u32, layers: u32) -> Texture {
         device.create_texture(&wgpu::TextureDescriptor {
             label: Some("Gradient Texture"),
             size: Extent3d {
                 width,
                 height,
-                depth_or_array_layers: 1,
+                depth_or_array_layers: layers,
             },
             mip_level_count: 1,
             sample_count: 1,
@@ -2112,10 +3135,13 @@ impl Programs {
         &mut self,
         device: &Device,
         queue: &Queue,
+        encoder: &mut CommandEncoder,
         gradient_cache: &mut GradientRampCache,
         encoded_paints: &[GpuEncodedPaint],
         alphas: &mut Vec<u8>,
         new_render_size: &RenderSize,
+        config_size: &RenderSize,
+        output_transfer: OutputTransfer,
         paint_idxs: &[u32],
         filter_context: &FilterContext,
     ) {
@@ -2123,15 +3149,22 @@ impl Programs {
         self.maybe_resize_alphas_tex(device, max_texture_dimension_2d, alphas.len());
         self.maybe_resize_encoded_paints_tex(device, max_texture_dimension_2d, paint_idxs);
         self.maybe_resize_filter_tex(device, max_texture_dimension_2d, filter_context);
-        self.maybe_update_config_buffer(device, queue, max_texture_dimension_2d, new_render_size);
+        self.maybe_update_config_buffer(
+            device,
+            queue,
+            max_texture_dimension_2d,
+            new_render_size,
+            config_size,
+            output_transfer,
+        );
 
-        self.upload_alpha_texture(queue, alphas);
-        self.upload_encoded_paints_texture(queue, encoded_paints);
-        self.upload_filter_texture(queue, filter_context);
+        self.upload_alpha_texture(device, queue, encoder, alphas);
+        self.upload_encoded_paints_texture(device, queue, encoder, encoded_paints);
+        self.upload_filter_texture(device, queue, encoder, filter_context);
 
         if gradient_cache.has_changed() {
             self.maybe_resize_gradient_tex(device, max_texture_dimension_2d, gradient_cache);
-            self.upload_gradient_texture(queue, gradient_cache);
+            self.upload_gradient_texture(device, queue, encoder, gradient_cache);
             gradient_cache.mark_synced();
         }
     }
@@ -2180,7 +3213,7 @@ impl Programs {
         max_texture_dimension_2d: u32,
         alphas_len: usize,
     ) {
-        let required_alpha_height = u32::try_from(alphas_len)
+        let required_alpha_rows = u32::try_from(alphas_len)
             .unwrap()
             // There are 16 1-byte alpha values per texel.
             .div_ceil(max_texture_dimension_2d << 4);
@@ -2188,30 +3221,25 @@ impl Programs {
             self.resources.alphas_texture.width() == max_texture_dimension_2d,
             "Alpha texture width must match max texture dimensions"
         );
-        let current_alpha_height = self.resources.alphas_texture.height();
-        if required_alpha_height > current_alpha_height {
+        if required_alpha_rows > linear_texture_rows(&self.resources.alphas_texture) {
             // We need to resize the alpha texture to fit the new alpha data.
-            assert!(
-                required_alpha_height <= max_texture_dimension_2d,
-                "Alpha texture height exceeds max texture dimensions"
+            let (height, layers) = linear_texture_extent(
+                required_alpha_rows,
+                max_texture_dimension_2d,
+                device.limits().max_texture_array_layers,
+                MIN_LINEAR_TEXTURE_LAYERS,
             );
 
             // The alpha texture encodes 16 1-byte alpha values per texel, with 4 alpha values packed in each channel
-            let alphas_texture = Self::create_alphas_texture(
-                device,
-                max_texture_dimension_2d,
-                required_alpha_height,
-            );
+            let alphas_texture =
+                Self::create_alphas_texture(device, max_texture_dimension_2d, height, layers);
             self.resources.alphas_texture = alphas_texture;
 
             // Since the alpha texture has changed, we need to update the clip bind groups.
             self.resources.slot_bind_groups = Self::create_strip_bind_groups(
                 device,
                 &self.strip_bind_group_layout,
-                &self
-                    .resources
-                    .alphas_texture
-                    .create_view(&TextureViewDescriptor::default()),
+                &create_linear_texture_view(&self.resources.alphas_texture),
                 &self.resources.slot_config_buffer,
                 &self.resources.view_config_buffer,
                 &self.resources.slot_texture_views,
@@ -2227,25 +3255,28 @@ impl Programs {
         paint_idxs: &[u32],
     ) {
         let required_texels = paint_idxs.last().unwrap();
-        let required_encoded_paints_height = required_texels.div_ceil(max_texture_dimension_2d);
+        let required_encoded_paints_rows = required_texels.div_ceil(max_texture_dimension_2d);
         debug_assert!(
             self.resources.encoded_paints_texture.width() == max_texture_dimension_2d,
             "Encoded paints texture width must match max texture dimensions"
         );
-        let current_encoded_paints_height = self.resources.encoded_paints_texture.height();
-        if required_encoded_paints_height > current_encoded_paints_height {
-            assert!(
-                required_encoded_paints_height <= max_texture_dimension_2d,
-                "Encoded paints texture height exceeds max texture dimensions"
+        if required_encoded_paints_rows
+            > linear_texture_rows(&self.resources.encoded_paints_texture)
+        {
+            let (height, layers) = linear_texture_extent(
+                required_encoded_paints_rows,
+                max_texture_dimension_2d,
+                device.limits().max_texture_array_layers,
+                MIN_LINEAR_TEXTURE_LAYERS,
             );
-            let required_encoded_paints_size =
-                (max_texture_dimension_2d * required_encoded_paints_height) << 4;
+            let required_encoded_paints_size = (max_texture_dimension_2d * height * layers) << 4;
             self.encoded_paints_data
                 .resize(required_encoded_paints_size as usize, 0);
             let encoded_paints_texture = Self::create_encoded_paints_texture(
                 device,
                 max_texture_dimension_2d,
-                required_encoded_paints_height,
+                height,
+                layers,
             );
             self.resources.encoded_paints_texture = encoded_paints_texture;
 
@@ -2253,10 +3284,7 @@ impl Programs {
             self.resources.encoded_paints_bind_group = Self::create_encoded_paints_bind_group(
                 device,
                 &self.encoded_paints_bind_group_layout,
-                &self
-                    .resources
-                    .encoded_paints_texture
-                    .create_view(&TextureViewDescriptor::default()),
+                &create_linear_texture_view(&self.resources.encoded_paints_texture),
             );
         }
     }
@@ -2269,65 +3297,91 @@ impl Programs {
         gradient_cache: &GradientRampCache,
     ) {
         let gradient_pixels = (gradient_cache.luts_size() / 4) as u32; // 4 bytes per RGBA8 pixel
-        let required_gradient_height = gradient_pixels.div_ceil(max_texture_dimension_2d);
+        let required_gradient_rows = gradient_pixels.div_ceil(max_texture_dimension_2d);
         debug_assert!(
             self.resources.gradient_texture.width() == max_texture_dimension_2d,
             "Gradient texture width must match max texture dimensions"
         );
-        let current_gradient_height = self.resources.gradient_texture.height();
-        if required_gradient_height > current_gradient_height {
-            assert!(
-                required_gradient_height <= max_texture_dimension_2d,
-                "Gradient texture height exceeds max texture dimensions"
-            );
-            let gradient_texture = Self::create_gradient_texture(
-                device,
+        if required_gradient_rows > linear_texture_rows(&self.resources.gradient_texture) {
+            let (height, layers) = linear_texture_extent(
+                required_gradient_rows,
                 max_texture_dimension_2d,
-                required_gradient_height,
+                device.limits().max_texture_array_layers,
+                MIN_LINEAR_TEXTURE_LAYERS,
             );
+            let gradient_texture =
+                Self::create_gradient_texture(device, max_texture_dimension_2d, height, layers);
             self.resources.gradient_texture = gradient_texture;
 
             // Since the gradient texture has changed, we need to update the gradient bind group.
             self.resources.gradient_bind_group = Self::create_gradient_bind_group(
                 device,
                 &self.gradient_bind_group_layout,
-                &self
-                    .resources
-                    .gradient_texture
-                    .create_view(&TextureViewDescriptor::default()),
+                &create_linear_texture_view(&self.resources.gradient_texture),
             );
         }
     }
 
-    /// Update config buffer if dimensions changed.
+    /// Update config buffer if dimensions or the output transfer changed.
+    ///
+    /// The attachments have the size of the whole target, `new_render_size`, while the
+    /// config buffer has the size of the area that is drawn into, `config_size`.
     fn maybe_update_config_buffer(
         &mut self,
         device: &Device,
         queue: &Queue,
         max_texture_dimension_2d: u32,
         new_render_size: &RenderSize,
+        config_size: &RenderSize,
+        output_transfer: OutputTransfer,
     ) {
-        if self.render_size != *new_render_size {
+        if self.config_size != *config_size || self.output_transfer != output_transfer {
             let config = Config {
-                width: new_render_size.width,
-                height: new_render_size.height,
+                width: config_size.width,
+                height: config_size.height,
                 strip_height: Tile::HEIGHT.into(),
                 alphas_tex_width_bits: max_texture_dimension_2d.trailing_zeros(),
                 encoded_paints_tex_width_bits: max_texture_dimension_2d.trailing_zeros(),
                 strip_offset_x: 0,
                 strip_offset_y: 0,
                 negate_ndc: 0,
+                output_transfer: output_transfer.flags,
+                output_scale: output_transfer.scale,
+                decode_paints: u32::from(self.output_color.decodes_paints()),
+                _padding: 0,
             };
             let mut buffer = queue
                 .write_buffer_with(&self.resources.view_config_buffer, 0, SIZE_OF_CONFIG)
                 .expect("Buffer only ever holds `Config`");
             buffer.copy_from_slice(bytemuck::bytes_of(&config));
+            self.output_transfer = output_transfer;
+            self.config_size = config_size.clone();
+        }
 
-            self.depth_texture =
-                Self::create_depth_texture(device, new_render_size.width, new_render_size.height);
-            self.depth_texture_view = self
-                .depth_texture
-                .create_view(&TextureViewDescriptor::default());
+        if self.render_size != *new_render_size {
+            let spare = self
+                .spare_attachments
+                .iter()
+                .position(|(size, _)| size == new_render_size);
+            let attachments = match spare {
+                Some(ix) => self.spare_attachments.remove(ix).1,
+                None => TargetAttachments::new(
+                    device,
+                    self.render_target_format,
+                    new_render_size,
+                    self.output_color
+                        .needs_premultiplied_target()
+                        .then_some(&self.filter_input_bind_group_layouts[1]),
+                ),
+            };
+            let old_attachments = core::mem::replace(&mut self.attachments, attachments);
+            if self.spare_attachment_limit > 0 {
+                self.spare_attachments
+                    .push((self.render_size.clone(), old_attachments));
+                if self.spare_attachments.len() > self.spare_attachment_limit {
+                    self.spare_attachments.remove(0);
+                }
+            }
 
             self.render_size = new_render_size.clone();
         }
@@ -2373,20 +3427,45 @@ impl Programs {
             resources.atlas_texture_array = new_atlas_texture_array;
             resources.atlas_texture_array_view = new_atlas_texture_array_view;
             resources.atlas_bind_group = new_atlas_bind_group;
+            // The bind groups of external textures still refer to the old texture array.
+            resources.external_paint_source_bind_groups.clear();
         }
     }
 
-    fn create_external_paint_source_bind_group(
-        &self,
+    /// Get the paint-source bind group for an external texture, only creating a new one if
+    /// `texture_id` wasn't bound to the same view before.
+    fn external_paint_source_bind_group(
+        &mut self,
         device: &Device,
+        texture_id: TextureId,
         external_texture_view: &TextureView,
     ) -> BindGroup {
-        Self::create_paint_source_bind_group(
+        let resources = &mut self.resources;
+        if let Some((view, bind_group)) =
+            resources.external_paint_source_bind_groups.get(&texture_id)
+            && view == external_texture_view
+        {
+            return bind_group.clone();
+        }
+
+        let bind_group = Self::create_paint_source_bind_group(
             device,
             &self.atlas_bind_group_layout,
-            &self.resources.atlas_texture_array_view,
+            &resources.atlas_texture_array_view,
             external_texture_view,
-        )
+        );
+        resources.external_paint_source_bind_groups.insert(
+            texture_id,
+            (external_texture_view.clone(), bind_group.clone()),
+        );
+        bind_group
+    }
+
+    /// Release the bind groups of external textures that are no longer bound to the same view.
+    fn release_external_paint_source_bind_groups(&mut self, texture_bindings: &TextureBindings) {
+        self.resources
+            .external_paint_source_bind_groups
+            .retain(|texture_id, (view, _)| texture_bindings.get(*texture_id) == Some(view));
     }
 
     /// Copy texture data from the old atlas texture array to a new one.
@@ -2422,40 +3501,36 @@ impl Programs {
     }
 
     /// Upload alpha data to the texture.
-    fn upload_alpha_texture(&mut self, queue: &Queue, alphas: &mut Vec<u8>) {
+    fn upload_alpha_texture(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        alphas: &mut Vec<u8>,
+    ) {
         if alphas.is_empty() {
             return;
         }
 
         let texture_width = self.resources.alphas_texture.width();
-        let texture_height = self.resources.alphas_texture.height();
-        let total_size = texture_width as usize * texture_height as usize * 16;
+        let texture_rows = linear_texture_rows(&self.resources.alphas_texture);
+        let total_size = texture_width as usize * texture_rows as usize * 16;
 
         let original_len = alphas.len();
+        self.frame_stats.alpha_bytes += original_len as u64;
 
         // Temporarily pad the length of the alphas to the texture size before uploading.
         alphas.resize(total_size, 0);
 
-        queue.write_texture(
-            wgpu::TexelCopyTextureInfo {
-                texture: &self.resources.alphas_texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
+        self.upload_belt.copy_to_texture(
+            device,
+            queue,
+            encoder,
+            &self.resources.alphas_texture,
             alphas,
-            wgpu::TexelCopyBufferLayout {
-                offset: 0,
-                // 16 bytes per RGBA32Uint texel (4 u32s × 4 bytes each), which is equivalent to
-                // a bit shift of 4.
-                bytes_per_row: Some(texture_width << 4),
-                rows_per_image: Some(texture_height),
-            },
-            Extent3d {
-                width: texture_width,
-                height: texture_height,
-                depth_or_array_layers: 1,
-            },
+            // 16 bytes per RGBA32Uint texel (4 u32s × 4 bytes each), which is equivalent to
+            // a bit shift of 4.
+            texture_width << 4,
         );
 
         // Truncate back to the original size.
@@ -2463,99 +3538,80 @@ impl Programs {
     }
 
     /// Upload encoded paints to the texture.
-    fn upload_encoded_paints_texture(&mut self, queue: &Queue, encoded_paints: &[GpuEncodedPaint]) {
+    fn upload_encoded_paints_texture(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        encoded_paints: &[GpuEncodedPaint],
+    ) {
         let encoded_paints_texture = &self.resources.encoded_paints_texture;
-        let encoded_paints_texture_width = encoded_paints_texture.width();
-        let encoded_paints_texture_height = encoded_paints_texture.height();
 
         GpuEncodedPaint::serialize_to_buffer(encoded_paints, &mut self.encoded_paints_data);
-        queue.write_texture(
-            wgpu::TexelCopyTextureInfo {
-                texture: encoded_paints_texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
+        self.upload_belt.copy_to_texture(
+            device,
+            queue,
+            encoder,
+            encoded_paints_texture,
             &self.encoded_paints_data,
-            wgpu::TexelCopyBufferLayout {
-                offset: 0,
-                // 16 bytes per RGBA32Uint texel (4 u32s × 4 bytes each), equivalent to bit shift of 4
-                bytes_per_row: Some(encoded_paints_texture_width << 4),
-                rows_per_image: Some(encoded_paints_texture_height),
-            },
-            Extent3d {
-                width: encoded_paints_texture_width,
-                height: encoded_paints_texture_height,
-                depth_or_array_layers: 1,
-            },
+            // 16 bytes per RGBA32Uint texel (4 u32s × 4 bytes each), equivalent to bit shift of 4
+            encoded_paints_texture.width() << 4,
         );
     }
 
-    fn upload_filter_texture(&mut self, queue: &Queue, filter_context: &FilterContext) {
+    fn upload_filter_texture(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        filter_context: &FilterContext,
+    ) {
         if filter_context.is_empty() {
             return;
         }
 
         let filter_texture = &self.resources.filter_data_texture;
-        let width = filter_texture.width();
-        let height = filter_texture.height();
 
         filter_context.serialize_to_buffer(&mut self.filter_data);
-        queue.write_texture(
-            wgpu::TexelCopyTextureInfo {
-                texture: filter_texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
+        self.upload_belt.copy_to_texture(
+            device,
+            queue,
+            encoder,
+            filter_texture,
             &self.filter_data,
-            wgpu::TexelCopyBufferLayout {
-                offset: 0,
-                bytes_per_row: Some(width << 4),
-                rows_per_image: Some(height),
-            },
-            Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
+            filter_texture.width() << 4,
         );
     }
 
     /// Upload gradient data to the texture.
-    fn upload_gradient_texture(&mut self, queue: &Queue, gradient_cache: &mut GradientRampCache) {
+    fn upload_gradient_texture(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        gradient_cache: &mut GradientRampCache,
+    ) {
         let gradient_texture = &self.resources.gradient_texture;
         let gradient_texture_width = gradient_texture.width();
-        let gradient_texture_height = gradient_texture.height();
+        let gradient_texture_rows = linear_texture_rows(gradient_texture);
 
         // Upload the gradient LUT data
         if !gradient_cache.is_empty() {
-            let total_capacity = (gradient_texture_width * gradient_texture_height * 4) as usize;
+            let total_capacity = (gradient_texture_width * gradient_texture_rows * 4) as usize;
 
             // Take ownership of the luts to avoid copying, then resize for texture padding
             let mut luts = gradient_cache.take_luts();
             let old_luts_len = luts.len();
             luts.resize(total_capacity, 0);
 
-            queue.write_texture(
-                wgpu::TexelCopyTextureInfo {
-                    texture: gradient_texture,
-                    mip_level: 0,
-                    origin: wgpu::Origin3d::ZERO,
-                    aspect: wgpu::TextureAspect::All,
-                },
+            self.upload_belt.copy_to_texture(
+                device,
+                queue,
+                encoder,
+                gradient_texture,
                 &luts,
-                wgpu::TexelCopyBufferLayout {
-                    offset: 0,
-                    // 4 bytes per RGBA8 pixel
-                    bytes_per_row: Some(gradient_texture_width << 2),
-                    rows_per_image: Some(gradient_texture_height),
-                },
-                Extent3d {
-                    width: gradient_texture_width,
-                    height: gradient_texture_height,
-                    depth_or_array_layers: 1,
-                },
+                // 4 bytes per RGBA8 pixel
+                gradient_texture_width << 2,
             );
 
             // Restore the luts back to the cache
@@ -2564,28 +3620,28 @@ impl Programs {
         }
     }
 
-    /// Uploads two strip slices (opaque then alpha) into a single GPU buffer.
+    /// Uploads two strip slices (opaque then alpha) into the strips buffer.
     fn upload_strip_pair(
         &mut self,
         device: &Device,
-        queue: &Queue,
+        encoder: &mut CommandEncoder,
         opaque_strips: &[GpuStrip],
         alpha_strips: &[GpuStrip],
     ) {
-        let opaque_bytes = size_of_val(opaque_strips) as u64;
-        let alpha_bytes = size_of_val(alpha_strips) as u64;
-        let total = opaque_bytes + alpha_bytes;
-        self.resources.strips_buffer = Self::create_strips_buffer(device, total);
-        // TODO: Consider using a staging belt to avoid an extra staging buffer allocation.
-        let mut buffer_view = queue
-            .write_buffer_with(&self.resources.strips_buffer, 0, total.try_into().unwrap())
-            .expect("Capacity handled in creation");
-        buffer_view
-            .slice(..opaque_bytes as usize)
-            .copy_from_slice(bytemuck::cast_slice(opaque_strips));
-        buffer_view
-            .slice(opaque_bytes as usize..)
-            .copy_from_slice(bytemuck::cast_slice(alpha_strips));
+        let total = size_of_val(opaque_strips) as u64 + size_of_val(alpha_strips) as u64;
+        if self.resources.strips_buffer.size() < total {
+            self.resources.strips_buffer =
+                Self::create_strips_buffer(device, total.next_power_of_two());
+        }
+        self.upload_belt.copy_to_buffer(
+            device,
+            encoder,
+            &self.resources.strips_buffer,
+            &[
+                bytemuck::cast_slice(opaque_strips),
+                bytemuck::cast_slice(alpha_strips),
+            ],
+        );
     }
 }
 
@@ -2594,7 +3650,6 @@ impl Programs {
 struct RendererContext<'a> {
     programs: &'a mut Programs,
     device: &'a Device,
-    queue: &'a Queue,
     encoder: &'a mut CommandEncoder,
     view: &'a TextureView,
     image_cache: &'a ImageCache,
@@ -2616,9 +3671,11 @@ impl RendererContext<'_> {
                     .texture_bindings
                     .get(texture_id)
                     .expect("external texture bindings were validated during paint preparation");
-                let bind_group = self
-                    .programs
-                    .create_external_paint_source_bind_group(self.device, texture_view);
+                let bind_group = self.programs.external_paint_source_bind_group(
+                    self.device,
+                    texture_id,
+                    texture_view,
+                );
                 entry.insert(bind_group)
             }
         }
@@ -2639,7 +3696,7 @@ impl RendererContext<'_> {
         // TODO: We currently allocate a new strips buffer for each render pass. A more efficient
         // approach would be to re-use buffers or slices of a larger buffer.
         self.programs
-            .upload_strip_pair(self.device, self.queue, opaque_strips, alpha_strips);
+            .upload_strip_pair(self.device, self.encoder, opaque_strips, alpha_strips);
         let opaque_count = opaque_strips.len() as u32;
         let alpha_count = alpha_strips.len() as u32;
 
@@ -2721,6 +3778,12 @@ impl RendererContext<'_> {
                                 strip_offset_x,
                                 strip_offset_y,
                                 negate_ndc: 0,
+                                output_transfer: OutputTransfer::NONE.flags,
+                                output_scale: OutputTransfer::NONE.scale,
+                                decode_paints: u32::from(
+                                    self.programs.output_color.decodes_paints(),
+                                ),
+                                _padding: 0,
                             }),
                             usage: wgpu::BufferUsages::UNIFORM,
                         });
@@ -2732,11 +3795,9 @@ impl RendererContext<'_> {
                         wgpu::BindGroupEntry {
                             binding: 0,
                             resource: wgpu::BindingResource::TextureView(
-                                &self
-                                    .programs
-                                    .resources
-                                    .alphas_texture
-                                    .create_view(&TextureViewDescriptor::default()),
+                                &create_linear_texture_view(
+                                    &self.programs.resources.alphas_texture,
+                                ),
                             ),
                         },
                         wgpu::BindGroupEntry {
@@ -2793,7 +3854,7 @@ impl RendererContext<'_> {
                 wgpu::LoadOp::Clear(1.0)
             };
             Some(wgpu::RenderPassDepthStencilAttachment {
-                view: &self.programs.depth_texture_view,
+                view: &self.programs.attachments.depth_view,
                 depth_ops: Some(wgpu::Operations {
                     load: depth_load,
                     store: wgpu::StoreOp::Store,
@@ -2804,6 +3865,16 @@ impl RendererContext<'_> {
             None
         };
 
+        let stage = if matches!(target, StripPassRenderTarget::Root(_)) {
+            ProfileStage::Target
+        } else {
+            ProfileStage::Layers
+        };
+        let timestamp_writes = self
+            .programs
+            .profiler
+            .as_mut()
+            .and_then(|profiler| profiler.timestamp_writes(stage));
         let mut render_pass = self.encoder.begin_render_pass(&RenderPassDescriptor {
             label: Some("Render to Texture Pass"),
             color_attachments: &[Some(RenderPassColorAttachment {
@@ -2817,12 +3888,23 @@ impl RendererContext<'_> {
             })],
             depth_stencil_attachment,
             occlusion_query_set: None,
-            timestamp_writes: None,
+            timestamp_writes,
             multiview_mask: None,
         });
         if let Some([x, y, width, height]) = scissor_rect {
             render_pass.set_scissor_rect(x, y, width, height);
         }
+        if let Some(viewport) = self.programs.viewport.filter(|_| is_final_view) {
+            // The config buffer has the size of the viewport, so strips are mapped into it.
+            let Viewport {
+                x,
+                y,
+                width,
+                height,
+            } = viewport;
+            render_pass.set_viewport(x as f32, y as f32, width as f32, height as f32, 0.0, 1.0);
+            render_pass.set_scissor_rect(x, y, width, height);
+        }
 
         render_pass.set_bind_group(0, bind_group.as_ref(), &[]);
         render_pass.set_bind_group(2, &self.programs.resources.encoded_paints_bind_group, &[]);
@@ -2835,23 +3917,28 @@ impl RendererContext<'_> {
                 is_final_view,
                 "The scheduler only allows the final view to have opaque strips"
             );
-            render_pass.set_pipeline(&self.programs.opaque_strip_pipelines[pipeline_idx]);
+            render_pass.set_pipeline(&self.programs.pipelines.opaque_strip[pipeline_idx]);
             render_pass.set_bind_group(1, &self.programs.resources.atlas_bind_group, &[]);
             render_pass.draw(0..4, 0..opaque_count);
+            self.programs.frame_stats.draw_calls += 1;
         }
 
         if alpha_count > 0 {
             // Alpha pass
-            if is_final_view {
-                render_pass.set_pipeline(&self.programs.alpha_strip_pipelines[pipeline_idx]);
+            if is_final_view && self.programs.opaque_target {
+                render_pass
+                    .set_pipeline(&self.programs.pipelines.opaque_target_alpha_strip[pipeline_idx]);
+            } else if is_final_view {
+                render_pass.set_pipeline(&self.programs.pipelines.alpha_strip[pipeline_idx]);
             } else {
-                render_pass.set_pipeline(&self.programs.slot_strip_pipelines[pipeline_idx]);
+                render_pass.set_pipeline(&self.programs.pipelines.slot_strip[pipeline_idx]);
             }
 
             let alpha_start = opaque_count;
             if external_texture_runs.is_empty() {
                 render_pass.set_bind_group(1, &self.programs.resources.atlas_bind_group, &[]);
                 render_pass.draw(0..4, alpha_start..alpha_start + alpha_count);
+                self.programs.frame_stats.draw_calls += 1;
             } else {
                 // Each run is drawn with a different external texture binding. Runs go from
                 // `run.strips_start` to the next run's `strips_start`; the last run goes to the end of
@@ -2869,6 +3956,7 @@ impl RendererContext<'_> {
                             u32::try_from(next.strips_start).unwrap()
                         });
                     render_pass.draw(0..4, alpha_start + start..alpha_start + end);
+                    self.programs.frame_stats.draw_calls += 1;
                 }
             }
         }
@@ -2882,22 +3970,23 @@ impl RendererContext<'_> {
 
         let resources = &mut self.programs.resources;
         let size = size_of_val(slot_indices) as u64;
-        // TODO: We currently allocate a new strips buffer for each render pass. A more efficient
-        // approach would be to re-use buffers or slices of a larger buffer.
-        resources.clear_slot_indices_buffer =
-            Programs::create_clear_slot_indices_buffer(self.device, size);
-        // TODO: Consider using a staging belt to avoid an extra staging buffer allocation.
-        let mut buffer = self
-            .queue
-            .write_buffer_with(
-                &resources.clear_slot_indices_buffer,
-                0,
-                size.try_into().unwrap(),
-            )
-            .expect("Capacity handled in creation");
-        buffer.copy_from_slice(bytemuck::cast_slice(slot_indices));
+        if resources.clear_slot_indices_buffer.size() < size {
+            resources.clear_slot_indices_buffer =
+                Programs::create_clear_slot_indices_buffer(self.device, size.next_power_of_two());
+        }
+        self.programs.upload_belt.copy_to_buffer(
+            self.device,
+            self.encoder,
+            &resources.clear_slot_indices_buffer,
+            &[bytemuck::cast_slice(slot_indices)],
+        );
 
         {
+            let timestamp_writes = self
+                .programs
+                .profiler
+                .as_mut()
+                .and_then(|profiler| profiler.timestamp_writes(ProfileStage::Clear));
             let mut render_pass = self.encoder.begin_render_pass(&RenderPassDescriptor {
                 label: Some("Clear Slots Render Pass"),
                 color_attachments: &[Some(RenderPassColorAttachment {
@@ -2912,14 +4001,15 @@ impl RendererContext<'_> {
                 })],
                 depth_stencil_attachment: None,
                 occlusion_query_set: None,
-                timestamp_writes: None,
+                timestamp_writes,
                 multiview_mask: None,
             });
 
-            render_pass.set_pipeline(&self.programs.clear_pipeline);
+            render_pass.set_pipeline(&self.programs.pipelines.clear);
             render_pass.set_bind_group(0, &resources.clear_bind_group, &[]);
             render_pass.set_vertex_buffer(0, resources.clear_slot_indices_buffer.slice(..));
             render_pass.draw(0..4, 0..u32::try_from(slot_indices.len()).unwrap());
+            self.programs.frame_stats.draw_calls += 1;
         }
     }
 }
@@ -2976,18 +4066,25 @@ impl RendererBackend for RendererContext<'_> {
         let instances = self.filter_pass_state.instances();
         let instance_stride = size_of::<FilterInstanceData>() as u64;
         let total_size = instances.len() as u64 * instance_stride;
-        // TODO: Reuse buffer (https://github.com/linebender/vello/pull/1494#discussion_r2937890819)
-        self.programs.resources.filter_instance_buffer =
-            Programs::create_filter_instance_buffer(self.device, total_size);
-        self.queue.write_buffer(
+        if self.programs.resources.filter_instance_buffer.size() < total_size {
+            self.programs.resources.filter_instance_buffer =
+                Programs::create_filter_instance_buffer(
+                    self.device,
+                    total_size.next_power_of_two(),
+                );
+        }
+        self.programs.upload_belt.copy_to_buffer(
+            self.device,
+            self.encoder,
             &self.programs.resources.filter_instance_buffer,
-            0,
-            bytemuck::cast_slice(instances),
+            &[bytemuck::cast_slice(instances)],
         );
 
-        let programs = &self.programs;
+        let resources = &self.programs.resources;
+        let pipelines = &self.programs.pipelines;
+        let profiler = &mut self.programs.profiler;
         let encoder = &mut self.encoder;
-        let filter_atlas = &programs.resources.filter_atlas;
+        let filter_atlas = &resources.filter_atlas;
         for (i, pass) in filter_passes.iter().enumerate() {
             let input_bg = &filter_atlas.input_bind_groups[pass.input_atlas_idx as usize];
             // If this is `None`, it's unused, so we can just pass anything here.
@@ -3000,15 +4097,18 @@ impl RendererBackend for RendererContext<'_> {
                     (&filter_atlas.views[*idx as usize], size.width, size.height)
                 }
                 FilterPassTarget::MainAtlas(idx) => {
-                    let size = programs.resources.atlas_texture_array.size();
+                    let size = resources.atlas_texture_array.size();
                     (
-                        &create_atlas_layer_view(&programs.resources.atlas_texture_array, *idx),
+                        &create_atlas_layer_view(&resources.atlas_texture_array, *idx),
                         size.width,
                         size.height,
                     )
                 }
             };
 
+            let timestamp_writes = profiler
+                .as_mut()
+                .and_then(|profiler| profiler.timestamp_writes(ProfileStage::Filters));
             let mut render_pass = encoder.begin_render_pass(&RenderPassDescriptor {
                 label: Some("Apply Filter Pass"),
                 color_attachments: &[Some(RenderPassColorAttachment {
@@ -3022,25 +4122,25 @@ impl RendererBackend for RendererContext<'_> {
                 })],
                 depth_stencil_attachment: None,
                 occlusion_query_set: None,
-                timestamp_writes: None,
+                timestamp_writes,
                 multiview_mask: None,
             });
             let instance = &instances[i];
             let [x, y, width, height] = instance.scissor_rect([target_width, target_height]);
             render_pass.set_scissor_rect(x, y, width, height);
-            render_pass.set_pipeline(&programs.filter_pipeline);
-            render_pass.set_bind_group(0, &programs.resources.filter_base_bind_group, &[]);
+            render_pass.set_pipeline(&pipelines.filter);
+            render_pass.set_bind_group(0, &resources.filter_base_bind_group, &[]);
             render_pass.set_bind_group(1, input_bg, &[]);
             render_pass.set_bind_group(2, original_bg, &[]);
             render_pass.set_vertex_buffer(
                 0,
-                programs
-                    .resources
+                resources
                     .filter_instance_buffer
                     .slice((i as u64 * instance_stride)..((i as u64 + 1) * instance_stride)),
             );
             render_pass.draw(0..4, 0..1);
         }
+        self.programs.frame_stats.draw_calls += u32::try_from(filter_passes.len()).unwrap();
     }
 }
 
@@ -3066,6 +4166,19 @@ fn create_filter_input_bind_group(
     })
 }
 
+/// Create a view of all layers of a linear texture, like the alpha texture.
+fn create_linear_texture_view(texture: &Texture) -> TextureView {
+    texture.create_view(&TextureViewDescriptor {
+        dimension: Some(wgpu::TextureViewDimension::D2Array),
+        ..Default::default()
+    })
+}
+
+/// The number of rows of a linear texture, across all of its layers.
+fn linear_texture_rows(texture: &Texture) -> u32 {
+    texture.height() * texture.depth_or_array_layers()
+}
+
 fn create_atlas_layer_view(atlas: &Texture, layer: u32) -> TextureView {
     atlas.create_view(&TextureViewDescriptor {
         label: Some("Atlas Layer View"),
@@ -3080,6 +4193,18 @@ fn create_atlas_layer_view(atlas: &Texture, layer: u32) -> TextureView {
     })
 }
 
+/// Poll a future once, returning its output if it is ready.
+#[cfg(feature = "hot_reload")]
+fn poll_once<F: Future>(future: F) -> Option<F::Output> {
+    let mut future = core::pin::pin!(future);
+    let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+
+    match future.as_mut().poll(&mut cx) {
+        core::task::Poll::Ready(output) => Some(output),
+        core::task::Poll::Pending => None,
+    }
+}
+
 /// Trait for types that can write image data directly to the atlas texture.
 ///
 /// This allows efficient uploading from different sources:
@@ -3174,6 +4299,17 @@ impl AtlasWriter for Pixmap {
         width: u32,
         height: u32,
     ) {
+        // The atlas stores sRGB data, so convert images that are tagged otherwise.
+        let converted;
+        let pixmap = if self.color_space() == ColorSpaceTag::Srgb {
+            self
+        } else {
+            let mut pixmap = self.clone();
+            pixmap.convert_color_space(ColorSpaceTag::Srgb);
+            converted = pixmap;
+            &converted
+        };
+
         queue.write_texture(
             wgpu::TexelCopyTextureInfo {
                 texture: atlas_texture,
@@ -3185,7 +4321,7 @@ impl AtlasWriter for Pixmap {
                 },
                 aspect: wgpu::TextureAspect::All,
             },
-            self.data_as_u8_slice(),
+            pixmap.data_as_u8_slice(),
             wgpu::TexelCopyBufferLayout {
                 offset: 0,
                 bytes_per_row: Some(4 * width),
@@ -6,7 +6,7 @@
 #[cfg(feature = "text")]
 use crate::text::GlyphAtlasResources;
 #[cfg(feature = "text")]
-use glifo::GlyphPrepCache;
+use glifo::{GlyphCacheConfig, GlyphPrepCache};
 use vello_common::image_cache::ImageCache;
 use vello_common::multi_atlas::AtlasConfig;
 
@@ -18,6 +18,8 @@ pub struct Resources {
     pub(crate) glyph_prep_cache: GlyphPrepCache,
     #[cfg(feature = "text")]
     pub(crate) glyph_resources: Option<GlyphAtlasResources>,
+    #[cfg(feature = "text")]
+    pub(crate) glyph_cache_config: GlyphCacheConfig,
 }
 
 impl Resources {
@@ -30,8 +32,18 @@ impl Resources {
             // Will be initialized lazily.
             #[cfg(feature = "text")]
             glyph_resources: None,
+            #[cfg(feature = "text")]
+            glyph_cache_config: GlyphCacheConfig::default(),
         }
     }
+
+    /// Use the given configuration for the glyph atlas cache, for example to change the
+    /// number of subpixel positions that glyphs are cached at.
+    #[cfg(feature = "text")]
+    pub fn with_glyph_cache_config(mut self, config: GlyphCacheConfig) -> Self {
+        self.glyph_cache_config = config;
+        self
+    }
 }
 
 impl Default for Resources {
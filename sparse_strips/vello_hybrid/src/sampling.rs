@@ -3,8 +3,10 @@
 
 //! Sampling helpers for image drawing.
 
+use crate::TextureId;
 use vello_common::geometry::RectU16;
 use vello_common::kurbo::Affine;
+use vello_common::peniko::ImageSampler;
 
 /// A rectangular source region sampled from an image input (e.g., [`crate::TextureId`]), paired
 /// with a transform of the rectangle into the destination.
@@ -19,3 +21,19 @@ pub struct SampleRect {
     /// [`Self::source_region`].
     pub transform: Affine,
 }
+
+/// An image-style paint that samples from an externally owned texture.
+///
+/// Unlike [`Scene::draw_texture_rects`](crate::Scene::draw_texture_rects), which always draws the
+/// transformed source rectangle, a texture paint can be used to fill or stroke arbitrary geometry,
+/// and respects the current clip, transform and paint transform just like an image paint. The
+/// source region is placed at the origin of the paint coordinate system.
+#[derive(Debug, Clone, Copy)]
+pub struct TexturePaint {
+    /// The texture to sample from.
+    pub texture_id: TextureId,
+    /// Source region in texel coordinates.
+    pub source_region: RectU16,
+    /// Sampling parameters, including extend modes and an additional alpha multiplier.
+    pub sampler: ImageSampler,
+}
@@ -5,33 +5,55 @@
 
 #[cfg(feature = "text")]
 use crate::Resources;
-use crate::sampling::SampleRect;
+use crate::sampling::{SampleRect, TexturePaint};
 #[cfg(feature = "text")]
 use crate::text::GlyphRunBuilder;
+use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::cell::RefCell;
 use core::ops::Range;
 use vello_common::TextureId;
+use vello_common::accessibility::{AccessGeometry, AccessTag};
 use vello_common::blurred_rounded_rect::BlurredRoundedRectangle;
+use vello_common::chart::Hairlines;
 use vello_common::clip::ClipContext;
 use vello_common::coarse::{MODE_HYBRID, Wide, WideTilesBbox};
-use vello_common::encode::{EncodeExt, EncodedExternalTexture, EncodedPaint};
+use vello_common::compact_path::CompactPath;
+use vello_common::custom_paint::{
+    CustomPaint, CustomPaintId, CustomPaintKind, CustomPaintRegistry,
+};
+use vello_common::dash::MeasuredPath;
+use vello_common::encode::{
+    EncodeExt, EncodedExternalTexture, EncodedPaint, GradientHints, encode_gradient_interned,
+};
+#[cfg(feature = "multithreading")]
+use vello_common::executor::{Executor, Task};
 use vello_common::fearless_simd::Level;
-use vello_common::filter_effects::Filter;
+use vello_common::filter_effects::{Filter, FilterFunction};
+use vello_common::flatten::{stroke_outline, stroke_tolerance_with};
+use vello_common::fragment::{Fragment, FragmentInstance};
 use vello_common::geometry::RectU16;
-use vello_common::kurbo::{Affine, BezPath, Rect, Shape, Stroke};
-use vello_common::mask::Mask;
+use vello_common::gradient_bands::{GradientBands, stroke_bounds};
+use vello_common::gradient_stops::GradientStopArena;
+use vello_common::kurbo::{Affine, BezPath, Dashes, Rect, Shape, Stroke, Vec2};
+use vello_common::load_op::LoadOp;
+use vello_common::mask::{Mask, MaskKind};
 use vello_common::multi_atlas::AtlasConfig;
 use vello_common::paint::{Paint, PaintType, Tint};
 #[cfg(feature = "text")]
 use vello_common::peniko::FontData;
-use vello_common::peniko::color::palette::css::BLACK;
+use vello_common::peniko::color::palette::css::{BLACK, TRANSPARENT};
 use vello_common::peniko::{BlendMode, Compose, Extend, Fill, ImageQuality, ImageSampler, Mix};
+use vello_common::quality::Quality;
 use vello_common::render_graph::{RenderGraph, RenderNodeKind};
 use vello_common::render_state::RenderState;
-use vello_common::strip_generator::{GenerationMode, StripGenerator, StripStorage};
+use vello_common::render_stats::RenderStats;
+use vello_common::simplify::LodPath;
+use vello_common::strip_debug::{StripDebugView, StripDebugger};
+use vello_common::strip_generator::{Aliasing, GenerationMode, StripGenerator, StripStorage};
 use vello_common::util::is_axis_aligned;
+use vello_common::variable_width::VariableWidthStroke;
 
 /// Default tolerance for curve flattening
 pub(crate) const DEFAULT_TOLERANCE: f64 = 0.1;
@@ -74,6 +96,8 @@ pub(crate) struct FastStripsPath {
     pub(crate) strips: Range<usize>,
     /// The paint of the path.
     pub(crate) paint: Paint,
+    /// The access tag of the path.
+    pub(crate) access_tag: Option<AccessTag>,
 }
 
 /// A rectangle stored in the fast-path buffer.
@@ -84,6 +108,8 @@ pub(crate) struct FastPathRect {
     pub(crate) x1: f32,
     pub(crate) y1: f32,
     pub(crate) paint: Paint,
+    /// The access tag of the rectangle.
+    pub(crate) access_tag: Option<AccessTag>,
 }
 
 /// A command in the fast strips buffer.
@@ -167,6 +193,9 @@ impl SceneConstraints {
 #[derive(Copy, Clone, Debug)]
 pub struct RenderSettings {
     /// The SIMD level that should be used for rendering operations.
+    ///
+    /// By default, the best level supported by the CPU is detected. On WASM, the SIMD128 level
+    /// can only be detected if the crate is compiled with `-Ctarget-feature=+simd128`.
     pub level: Level,
     /// The configuration for the texture atlas.
     ///
@@ -182,6 +211,10 @@ pub struct RenderSettings {
     pub atlas_config: AtlasConfig,
     /// Constraints on the scene that the renderer can exploit for optimisation.
     pub constraints: SceneConstraints,
+    /// The number of worker threads that generate the strips of the paths drawn with
+    /// [`Scene::fill_paths`] and [`Scene::stroke_paths`]. Only has an effect if the
+    /// `multithreading` feature is active, and `0` generates all strips on the calling thread.
+    pub num_threads: u16,
 }
 
 impl Default for RenderSettings {
@@ -190,6 +223,7 @@ impl Default for RenderSettings {
             level: Level::try_detect().unwrap_or(Level::baseline()),
             atlas_config: AtlasConfig::default(),
             constraints: SceneConstraints::new(),
+            num_threads: 0,
         }
     }
 }
@@ -198,6 +232,8 @@ impl Default for RenderSettings {
 ///
 /// This context maintains the state for path rendering and manages the rendering
 /// pipeline from paths to strips that can be rendered by the GPU.
+///
+/// Scenes are [`Send`] but not `Sync`, see the [crate documentation](crate#threads).
 #[derive(Debug)]
 pub struct Scene {
     /// Constraints on the scene that the renderer can exploit for optimisation.
@@ -210,7 +246,9 @@ pub struct Scene {
     pub(crate) wide: Wide<MODE_HYBRID>,
     clip_context: ClipContext,
     pub(crate) render_state: RenderState,
-    pub(crate) aliasing_threshold: Option<u8>,
+    pub(crate) aliasing: Aliasing,
+    /// The preset that trades rendering quality for speed.
+    quality: Quality,
     // The reason we use `RefCell` here is that during `render`, we need
     // mutable access so we can store additional encoded paints for filtered layers,
     // if applicable.
@@ -218,6 +256,8 @@ pub struct Scene {
     pub(crate) encoded_paints: RefCell<Vec<EncodedPaint>>,
     /// Whether the current paint is visible (e.g., alpha > 0).
     paint_visible: bool,
+    /// An external texture paint that overrides `render_state.paint`, if set.
+    texture_paint: Option<TexturePaint>,
     /// Generator for converting paths to strips.
     pub(crate) strip_generator: StripGenerator,
     /// Storage for generated strips and alpha values.
@@ -228,6 +268,8 @@ pub struct Scene {
     pub(crate) render_graph: RenderGraph,
     /// Current filter effect applied to individual draw operations.
     filter: Option<Filter>,
+    /// The kinds of the mask groups whose mask content is currently being drawn.
+    mask_groups: Vec<MaskKind>,
     /// A buffer that stores the strips of path drawing calls that are rendered directly
     /// to the surface, bypassing coarse rasterization.
     pub(crate) fast_strips_buffer: FastStripsBuffer,
@@ -237,6 +279,29 @@ pub struct Scene {
     /// process one coarse batch before processing another fast path strip batch.
     /// Only meaningful in [`StripPathMode::Interleaved`] mode.
     pub(crate) coarse_batch_splits: Vec<usize>,
+    /// The tag that drawn primitives are recorded under, if any.
+    pub(crate) access_tag: Option<AccessTag>,
+    /// The geometry of the tagged primitives drawn since the last reset.
+    pub(crate) access_geometry: AccessGeometry,
+    /// How the render target is initialized before the scene is rendered.
+    load_op: LoadOp,
+    /// Whether a layer in the root layer was composited with an operator that can make an
+    /// opaque backdrop translucent.
+    root_may_reduce_alpha: bool,
+    /// The custom paint kinds that can be drawn with [`Scene::fill_custom_paint`].
+    pub(crate) custom_paints: CustomPaintRegistry,
+    /// The interned color stops of the gradients drawn in recent frames.
+    gradient_stops: GradientStopArena,
+    /// The bounding box of the damaged region, if only that region is redrawn.
+    damage: Option<Rect>,
+    /// Records the generated strips for visualizing them, if enabled.
+    strip_debugger: Option<StripDebugger>,
+    /// The executor that generates the strips of batches of paths, if any.
+    #[cfg(feature = "multithreading")]
+    executor: Option<Arc<dyn Executor>>,
+    /// The statistics of the strips generated on the worker threads of the executor.
+    #[cfg(feature = "multithreading")]
+    parallel_stats: RenderStats,
 }
 
 // We use this macro instead of a method to avoid borrowing issues in the corresponding methods.
@@ -251,12 +316,16 @@ pub struct Scene {
 macro_rules! submit_strips {
     ($self:ident, $strip_storage:expr, $strip_start:expr, $paint:expr) => {
         if $self.strip_path_mode != StripPathMode::CoarseOnly && !$self.wide.has_layers() {
+            if let Some(debugger) = &mut $self.strip_debugger {
+                debugger.record(&$strip_storage.strips[$strip_start..]);
+            }
             $self
                 .fast_strips_buffer
                 .commands
                 .push(FastStripCommand::Path(FastStripsPath {
                     strips: $strip_start..$strip_storage.strips.len(),
                     paint: $paint,
+                    access_tag: $self.wide.access_tag(),
                 }));
         } else {
             // In `ReplaceAfter(n)` mode the fast path prefix lives at `[0..n]`
@@ -265,6 +334,9 @@ macro_rules! submit_strips {
                 GenerationMode::ReplaceAfter(n) => n,
                 _ => 0,
             };
+            if let Some(debugger) = &mut $self.strip_debugger {
+                debugger.record(&$strip_storage.strips[coarse_start..]);
+            }
             $self.wide.generate(
                 &$strip_storage.strips[coarse_start..],
                 $paint,
@@ -279,6 +351,19 @@ macro_rules! submit_strips {
 
 const DEFAULT_BLEND_MODE: BlendMode = BlendMode::new(Mix::Normal, Compose::SrcOver);
 
+/// Whether compositing a layer with `compose` keeps an opaque backdrop opaque.
+fn preserves_opaque_backdrop(compose: Compose) -> bool {
+    matches!(
+        compose,
+        Compose::SrcOver
+            | Compose::Dest
+            | Compose::DestOver
+            | Compose::SrcAtop
+            | Compose::Plus
+            | Compose::PlusLighter
+    )
+}
+
 impl Scene {
     /// Create a new render context with the given width and height in pixels.
     pub fn new(width: u16, height: u16) -> Self {
@@ -310,19 +395,174 @@ impl Scene {
             wide,
             clip_context: ClipContext::new(),
             render_state: RenderState::default(),
-            aliasing_threshold: None,
+            aliasing: Aliasing::default(),
+            quality: Quality::default(),
             encoded_paints: RefCell::new(vec![]),
             paint_visible: true,
+            texture_paint: None,
             strip_generator: StripGenerator::new(width, height, settings.level),
             // Start strip storage in `Append` mode since we enable the fast path by default.
             strip_storage: RefCell::new(StripStorage::new(GenerationMode::Append)),
             layer_id_next: 0,
             render_graph,
             filter: None,
+            mask_groups: Vec::new(),
             fast_strips_buffer: FastStripsBuffer::default(),
             strip_path_mode: StripPathMode::FastOnly,
             coarse_batch_splits: Vec::new(),
+            access_tag: None,
+            access_geometry: AccessGeometry::new(),
+            load_op: LoadOp::default(),
+            root_may_reduce_alpha: false,
+            custom_paints: CustomPaintRegistry::new(),
+            gradient_stops: GradientStopArena::new(),
+            damage: None,
+            strip_debugger: None,
+            #[cfg(feature = "multithreading")]
+            executor: (settings.num_threads > 0)
+                .then(|| vello_common::executor::thread_pool(settings.num_threads)),
+            #[cfg(feature = "multithreading")]
+            parallel_stats: RenderStats::default(),
+        }
+    }
+
+    /// Create a new render context that generates the strips of batches of paths on
+    /// `executor`, instead of on threads of its own.
+    ///
+    /// [`RenderSettings::num_threads`] is ignored, the work is split according to the number
+    /// of threads of the executor.
+    #[cfg(feature = "multithreading")]
+    pub fn new_with_executor(
+        width: u16,
+        height: u16,
+        settings: RenderSettings,
+        executor: Arc<dyn Executor>,
+    ) -> Self {
+        let mut scene = Self::new_with(
+            width,
+            height,
+            RenderSettings {
+                num_threads: 0,
+                ..settings
+            },
+        );
+        scene.executor = Some(executor);
+        scene
+    }
+
+    /// Set the tag that the geometry of subsequently drawn primitives is recorded under, or
+    /// stop recording geometry with `None`.
+    ///
+    /// See the [`accessibility`](vello_common::accessibility) module for details.
+    pub fn set_access_tag(&mut self, tag: Option<AccessTag>) {
+        self.access_tag = tag;
+        self.wide.set_access_tag(tag);
+    }
+
+    /// Get the tag that the geometry of drawn primitives is currently recorded under.
+    pub fn access_tag(&self) -> Option<AccessTag> {
+        self.access_tag
+    }
+
+    /// Get the geometry of the tagged primitives drawn since the last reset.
+    pub fn access_geometry(&self) -> &AccessGeometry {
+        &self.access_geometry
+    }
+
+    /// Take the geometry of the tagged primitives drawn since the last reset, leaving no
+    /// geometry behind.
+    pub fn take_access_geometry(&mut self) -> AccessGeometry {
+        core::mem::take(&mut self.access_geometry)
+    }
+
+    /// Get the tags of the primitives that contributed visible pixels, in ascending order.
+    ///
+    /// Unlike [`access_geometry`](Self::access_geometry), this excludes tagged primitives that
+    /// were clipped away entirely or completely covered by opaque content drawn on top of them.
+    /// The result is conservative: a primitive that is only partly covered, or hidden by a clip
+    /// or a layer at a finer granularity than a wide tile, is still reported. In particular,
+    /// primitives drawn via the fast path are never considered covered.
+    ///
+    /// This is cleared by [`reset`](Self::reset).
+    pub fn visible_access_tags(&self) -> Vec<AccessTag> {
+        let mut tags = self.wide.visible_access_tags();
+        let strip_storage = self.strip_storage.borrow();
+        for cmd in &self.fast_strips_buffer.commands {
+            let tag = match cmd {
+                FastStripCommand::Path(path) => {
+                    if strip_storage.strips[path.strips.clone()].is_empty() {
+                        continue;
+                    }
+                    path.access_tag
+                }
+                FastStripCommand::Rect(rect) => rect.access_tag,
+            };
+            tags.extend(tag);
         }
+        tags.sort_unstable();
+        tags.dedup();
+        tags
+    }
+
+    fn record_access_fill(&mut self, shape: &impl Shape, transform: Affine) {
+        if let Some(tag) = self.access_tag {
+            self.access_geometry.add_fill(tag, shape, transform);
+        }
+    }
+
+    fn record_access_stroke(&mut self, path: &BezPath) {
+        if let Some(tag) = self.access_tag {
+            self.access_geometry.add_stroke(
+                tag,
+                path,
+                &self.render_state.stroke,
+                self.render_state.transform,
+            );
+        }
+    }
+
+    /// Start recording the strips generated for the scene to visualize them with `view`, or
+    /// stop recording them with `None`.
+    ///
+    /// The recorded strips are cleared by [`reset`](Self::reset). Draw them with
+    /// [`draw_strip_debug_overlay`](Self::draw_strip_debug_overlay) once the scene is
+    /// complete.
+    pub fn set_strip_debug_view(&mut self, view: Option<StripDebugView>) {
+        self.strip_debugger = view.map(|view| StripDebugger::new(self.width, self.height, view));
+    }
+
+    /// Get the debugger that records the generated strips, if enabled with
+    /// [`set_strip_debug_view`](Self::set_strip_debug_view).
+    ///
+    /// Its [statistics](StripDebugger::stats) tell how much work the strips of the scene
+    /// cause, which is useful to compare different ways of drawing the same content.
+    pub fn strip_debugger(&self) -> Option<&StripDebugger> {
+        self.strip_debugger.as_ref()
+    }
+
+    /// Draw the visualization of the strips recorded so far on top of the scene.
+    ///
+    /// This should be called outside of any layers and clip paths, so that the overlay
+    /// covers the whole scene. The strips of the overlay itself aren't recorded. Does nothing
+    /// if no strips are recorded.
+    pub fn draw_strip_debug_overlay(&mut self) {
+        let Some(debugger) = self.strip_debugger.take() else {
+            return;
+        };
+
+        let texture_paint = self.texture_paint;
+        let state = self.take_current_state();
+        let access_tag = self.access_tag;
+        self.set_access_tag(None);
+        for (rect, color) in debugger.overlay() {
+            self.set_paint(color);
+            self.fill_rect(&rect);
+        }
+        self.set_access_tag(access_tag);
+        self.texture_paint = texture_paint;
+        self.restore_state(state);
+
+        self.strip_debugger = Some(debugger);
     }
 
     /// Encode the current paint into a `Paint` that can be used for rendering.
@@ -336,13 +576,34 @@ impl Scene {
         // to account for the fact that we want to sample the pixel center instead of the top-left
         // corner. For vello_hybrid, we don't need this, because the GPU itself already applies
         // this shift automatically.
+        if let Some(texture_paint) = self.texture_paint {
+            return self.encode_external_texture_paint(
+                texture_paint.texture_id,
+                texture_paint.source_region,
+                texture_paint.sampler,
+                self.render_state.transform * self.render_state.paint_transform,
+            );
+        }
+
         match self.render_state.paint.clone() {
             PaintType::Solid(s) => s.into(),
-            PaintType::Gradient(g) => g.encode_into(
-                &mut self.encoded_paints.borrow_mut(),
-                self.render_state.transform * self.render_state.paint_transform,
-                None,
-            ),
+            PaintType::Gradient(g) => {
+                let encoded_paints = &mut self.encoded_paints.borrow_mut();
+                let paint = encode_gradient_interned(
+                    &g,
+                    &self.render_state.gradient_hints,
+                    &mut self.gradient_stops,
+                    encoded_paints,
+                    self.render_state.transform * self.render_state.paint_transform,
+                );
+                if let (Paint::Indexed(_), Some(EncodedPaint::Gradient(encoded))) =
+                    (&paint, encoded_paints.last_mut())
+                {
+                    encoded.set_max_lut_size(self.quality.max_gradient_lut_size());
+                }
+
+                paint
+            }
             PaintType::Image(i) => i.encode_into(
                 &mut self.encoded_paints.borrow_mut(),
                 self.render_state.transform * self.render_state.paint_transform,
@@ -356,21 +617,14 @@ impl Scene {
         &mut self,
         texture_id: TextureId,
         source_region: RectU16,
-        quality: ImageQuality,
-        x_extend: Extend,
-        y_extend: Extend,
+        sampler: ImageSampler,
         transform: Affine,
     ) -> Paint {
         let idx = self.encoded_paints.borrow().len();
         let encoded = EncodedExternalTexture {
             texture_id,
             source_region,
-            sampler: ImageSampler {
-                x_extend,
-                y_extend,
-                quality,
-                alpha: 1.0,
-            },
+            sampler,
             may_have_transparency: true,
             transform: transform.inverse(),
             tint: self.render_state.tint,
@@ -381,21 +635,241 @@ impl Scene {
         Paint::Indexed(vello_common::paint::IndexedPaint::new(idx))
     }
 
+    /// Decompose the current paint into solid bands if it is a gradient that only consists of
+    /// hard stops and can be rendered that way.
+    fn hard_stop_bands(&self, bounds: impl FnOnce() -> Rect) -> Option<GradientBands> {
+        let PaintType::Gradient(gradient) = &self.render_state.paint else {
+            return None;
+        };
+
+        // Bands are drawn on top of each other, which only works with plain source-over
+        // compositing.
+        if self.texture_paint.is_some() || self.render_state.blend_mode != DEFAULT_BLEND_MODE {
+            return None;
+        }
+
+        GradientBands::new(
+            gradient,
+            bounds(),
+            self.render_state.transform,
+            self.render_state.paint_transform,
+        )
+    }
+
+    /// Draw `shape` with the colors of `bands`.
+    ///
+    /// The shape is pushed as a clip layer, so that its anti-aliased edges are only applied
+    /// once, and the opaque bands are filled inside of it.
+    fn draw_bands(&mut self, bands: &GradientBands, shape: &BezPath, fill_rule: Fill) {
+        let previous_fill_rule = core::mem::replace(&mut self.render_state.fill_rule, fill_rule);
+        self.push_layer(Some(shape), None, None, None, None);
+        self.render_state.fill_rule = previous_fill_rule;
+
+        let transform = self.render_state.transform;
+        self.fill_path_with(
+            bands.bounds(),
+            transform,
+            Fill::NonZero,
+            bands.base().into(),
+            self.aliasing,
+        );
+        for band in bands.bands() {
+            self.fill_path_with(
+                band.region(),
+                transform,
+                Fill::EvenOdd,
+                band.color().into(),
+                self.aliasing,
+            );
+        }
+
+        self.pop_layer();
+    }
+
+    /// Return the outline of `path` stroked with the current stroke, like it is filled when
+    /// the path is stroked.
+    fn stroke_outline(&self, path: &BezPath) -> BezPath {
+        let tolerance = stroke_tolerance_with(
+            self.render_state.transform,
+            self.quality.flatten_tolerance(),
+        );
+        stroke_outline(path, &self.render_state.stroke, tolerance)
+    }
+
     /// Fill a path with the current paint and fill rule.
     pub fn fill_path(&mut self, path: &BezPath) {
+        self.draw_path(path, false, None);
+    }
+
+    /// Fill several paths, each with its own paint, using the current transform, fill rule
+    /// and clip.
+    ///
+    /// This draws the same as calling [`set_paint`](Self::set_paint) and
+    /// [`fill_path`](Self::fill_path) for each path in order, and leaves the paint of the last
+    /// path set. If the `multithreading` feature is active and
+    /// [`num_threads`](RenderSettings::num_threads) isn't zero or the scene was created with
+    /// an executor, the paths are flattened and their strips are generated on worker threads, which speeds up scenes with many complex
+    /// paths like vector maps. The strips are still submitted in the order of the paths, so
+    /// the result doesn't depend on the number of threads.
+    pub fn fill_paths(&mut self, paths: &[(BezPath, PaintType)]) {
+        self.draw_paths(paths, false);
+    }
+
+    /// Stroke several paths, each with its own paint, using the current transform and stroke
+    /// settings.
+    ///
+    /// Like [`fill_paths`](Self::fill_paths), but draws the same as calling
+    /// [`stroke_path`](Self::stroke_path) for each path.
+    pub fn stroke_paths(&mut self, paths: &[(BezPath, PaintType)]) {
+        self.draw_paths(paths, true);
+    }
+
+    fn draw_paths(&mut self, paths: &[(BezPath, PaintType)], stroke: bool) {
+        #[cfg(feature = "multithreading")]
+        if let Some(path_strips) = self.generate_strips_in_parallel(paths, stroke) {
+            for path_strips in &path_strips {
+                self.parallel_stats += RenderStats {
+                    paths: 1,
+                    strips: path_strips.strips.len() as u64,
+                    alpha_bytes: path_strips.alphas.len() as u64,
+                    draw_calls: 0,
+                };
+            }
+            for ((path, paint), path_strips) in paths.iter().zip(path_strips) {
+                self.set_paint(paint.clone());
+                self.draw_path(path, stroke, Some(&path_strips));
+            }
+            return;
+        }
+
+        for (path, paint) in paths {
+            self.set_paint(paint.clone());
+            if stroke {
+                self.stroke_path(path);
+            } else {
+                self.fill_path(path);
+            }
+        }
+    }
+
+    /// Generate the strips of each path on the worker threads, or return `None` if there
+    /// are no worker threads or not enough paths to make that worthwhile.
+    #[cfg(feature = "multithreading")]
+    fn generate_strips_in_parallel(
+        &self,
+        paths: &[(BezPath, PaintType)],
+        stroke: bool,
+    ) -> Option<Vec<StripStorage>> {
+        /// The number of batches handed to each thread, so that threads that finish their
+        /// simpler paths early can pick up more work.
+        const BATCHES_PER_THREAD: usize = 4;
+
+        use alloc::boxed::Box;
+
+        let executor = self.executor.as_ref()?;
+        if paths.len() < 2 {
+            return None;
+        }
+
+        let (width, height) = (self.width, self.height);
+        let level = self.strip_generator.level();
+        let transform = self.render_state.transform;
+        let fill_rule = self.render_state.fill_rule;
+        let stroke_style = &self.render_state.stroke;
+        let aliasing = self.aliasing;
+        let tolerance = self.strip_generator.tolerance();
+        let clip_path = self.clip_context.get();
+        let paths: Vec<&BezPath> = paths.iter().map(|(path, _)| path).collect();
+
+        let mut path_strips: Vec<StripStorage> =
+            paths.iter().map(|_| StripStorage::default()).collect();
+        let batch_len = paths
+            .len()
+            .div_ceil(executor.num_threads().max(1) * BATCHES_PER_THREAD);
+        let tasks = paths
+            .chunks(batch_len)
+            .zip(path_strips.chunks_mut(batch_len))
+            .map(|(paths, path_strips)| {
+                Box::new(move || {
+                    let mut strip_generator = StripGenerator::new(width, height, level);
+                    strip_generator.set_tolerance(tolerance);
+                    for (path, path_strips) in paths.iter().zip(path_strips) {
+                        if stroke {
+                            strip_generator.generate_stroked_path(
+                                *path,
+                                stroke_style,
+                                transform,
+                                aliasing,
+                                path_strips,
+                                clip_path,
+                            );
+                        } else {
+                            strip_generator.generate_filled_path(
+                                *path,
+                                fill_rule,
+                                transform,
+                                aliasing,
+                                path_strips,
+                                clip_path,
+                            );
+                        }
+                    }
+                }) as Task<'_>
+            })
+            .collect();
+        executor.join(tasks);
+
+        Some(path_strips)
+    }
+
+    /// Fill or stroke `path` with the current paint, using `path_strips` if the strips of the
+    /// path were already generated on a worker thread.
+    fn draw_path(&mut self, path: &BezPath, stroke: bool, path_strips: Option<&StripStorage>) {
+        if stroke {
+            self.record_access_stroke(path);
+        } else {
+            self.record_access_fill(path, self.render_state.transform);
+        }
         if !self.paint_visible {
             return;
         }
 
         self.with_optional_filter(|ctx| {
+            if stroke {
+                if let Some(bands) =
+                    ctx.hard_stop_bands(|| stroke_bounds(path, &ctx.render_state.stroke))
+                {
+                    let outline = ctx.stroke_outline(path);
+                    ctx.draw_bands(&bands, &outline, Fill::NonZero);
+                    return;
+                }
+            } else if let Some(bands) = ctx.hard_stop_bands(|| path.bounding_box()) {
+                ctx.draw_bands(&bands, path, ctx.render_state.fill_rule);
+                return;
+            }
+
             let paint = ctx.encode_current_paint();
-            ctx.fill_path_with(
-                path,
-                ctx.render_state.transform,
-                ctx.render_state.fill_rule,
-                paint,
-                ctx.aliasing_threshold,
-            );
+            match path_strips {
+                Some(path_strips) => {
+                    let strip_storage = &mut ctx.strip_storage.borrow_mut();
+                    let strip_start = strip_storage.strips.len();
+                    strip_storage.push_path(path_strips);
+
+                    submit_strips!(ctx, strip_storage, strip_start, paint);
+                }
+                None if stroke => {
+                    ctx.stroke_path_with(path, ctx.render_state.transform, paint, ctx.aliasing);
+                }
+                None => {
+                    ctx.fill_path_with(
+                        path,
+                        ctx.render_state.transform,
+                        ctx.render_state.fill_rule,
+                        paint,
+                        ctx.aliasing,
+                    );
+                }
+            }
         });
     }
 
@@ -411,7 +885,7 @@ impl Scene {
         transform: Affine,
         fill_rule: Fill,
         paint: Paint,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
     ) {
         let strip_storage = &mut self.strip_storage.borrow_mut();
         let strip_start = strip_storage.strips.len();
@@ -419,7 +893,7 @@ impl Scene {
             path,
             fill_rule,
             transform,
-            aliasing_threshold,
+            aliasing,
             strip_storage,
             self.clip_context.get(),
         );
@@ -437,7 +911,7 @@ impl Scene {
             &mut self.strip_generator,
             self.render_state.fill_rule,
             self.render_state.transform,
-            self.aliasing_threshold,
+            self.aliasing,
         );
     }
 
@@ -451,19 +925,142 @@ impl Scene {
 
     /// Stroke a path with the current paint and stroke settings.
     pub fn stroke_path(&mut self, path: &BezPath) {
-        if !self.paint_visible {
-            return;
+        self.draw_path(path, true, None);
+    }
+
+    /// Stroke a measured path with the current paint and stroke settings.
+    ///
+    /// This renders the same as stroking the original path with
+    /// [`stroke_path`](Self::stroke_path), but the dash pattern of the stroke is applied
+    /// using the segment lengths cached in the [`MeasuredPath`], so that animating the
+    /// dash offset doesn't require measuring the whole path again.
+    pub fn stroke_measured_path(&mut self, path: &MeasuredPath) {
+        let stroke = self.render_state.stroke.clone();
+        let mut dashed = BezPath::new();
+        path.dash(stroke.dash_offset, &stroke.dash_pattern, &mut dashed);
+
+        // The path is already dashed, so stroke it without the dash pattern.
+        self.render_state.stroke.dash_pattern = Dashes::new();
+        self.render_state.stroke.dash_offset = 0.0;
+        self.stroke_path(&dashed);
+        self.render_state.stroke = stroke;
+    }
+
+    /// Fill pixel-snapped hairlines, such as the grid lines and ticks of a chart, with the
+    /// current paint.
+    ///
+    /// The lines are already in device space, so they are drawn independently of the current
+    /// transform, through the fast path for axis-aligned rectangles. The paint is still
+    /// positioned with the current transform.
+    pub fn fill_hairlines(&mut self, lines: &Hairlines) {
+        let transform = self.render_state.transform;
+        let paint_transform = self.render_state.paint_transform;
+        self.render_state.transform = Affine::IDENTITY;
+        self.render_state.paint_transform = transform * paint_transform;
+        for rect in lines.rects() {
+            self.fill_rect(rect);
         }
+        self.render_state.paint_transform = paint_transform;
+        self.render_state.transform = transform;
+    }
+
+    /// Fill a path from a [`CompactPaths`](vello_common::compact_path::CompactPaths) set.
+    pub fn fill_compact_path(&mut self, path: CompactPath<'_>) {
+        let mut decoded = BezPath::new();
+        path.decode_into(&mut decoded);
+        self.fill_path(&decoded);
+    }
+
+    /// Stroke a path from a [`CompactPaths`](vello_common::compact_path::CompactPaths) set.
+    pub fn stroke_compact_path(&mut self, path: CompactPath<'_>) {
+        let mut decoded = BezPath::new();
+        path.decode_into(&mut decoded);
+        self.stroke_path(&decoded);
+    }
+
+    /// Fill the level of detail of a path that suits the current transform.
+    ///
+    /// See the [`simplify`](vello_common::simplify) module for details.
+    pub fn fill_lod_path(&mut self, path: &LodPath) {
+        self.fill_path(path.select(&self.render_state.transform));
+    }
+
+    /// Stroke the level of detail of a path that suits the current transform.
+    ///
+    /// See the [`simplify`](vello_common::simplify) module for details.
+    pub fn stroke_lod_path(&mut self, path: &LodPath) {
+        self.stroke_path(path.select(&self.render_state.transform));
+    }
+
+    /// Stroke a path whose width varies along the path, with the current paint.
+    ///
+    /// The stroke is expanded with round caps and joins and filled with the non-zero fill
+    /// rule, independent of the current stroke settings and fill rule.
+    pub fn stroke_variable_width(&mut self, stroke: &VariableWidthStroke) {
+        let mut path = BezPath::new();
+        stroke.expand(
+            stroke_tolerance_with(
+                self.render_state.transform,
+                self.quality.flatten_tolerance(),
+            ),
+            &mut path,
+        );
+
+        let fill_rule = self.render_state.fill_rule;
+        self.render_state.fill_rule = Fill::NonZero;
+        self.fill_path(&path);
+        self.render_state.fill_rule = fill_rule;
+    }
+
+    /// Draw every instance of a fragment, each with its own transform and optionally its own
+    /// paint.
+    ///
+    /// The fragment is flattened only once, when it is created, so drawing many instances is
+    /// much cheaper than filling or stroking the same path many times. Instance transforms
+    /// are applied after the current transform, and instances that don't override the paint
+    /// are drawn with the current paint. Instances outside of the viewport are skipped.
+    ///
+    /// The current filter effect is applied to all instances together, rather than to each
+    /// instance individually.
+    pub fn fill_fragment(&mut self, fragment: &Fragment, instances: &[FragmentInstance]) {
+        let transform = self.render_state.transform;
+        let paint = self.render_state.paint.clone();
+        let texture_paint = self.texture_paint;
+        let viewport = Rect::new(0.0, 0.0, f64::from(self.width), f64::from(self.height));
 
         self.with_optional_filter(|ctx| {
-            let paint = ctx.encode_current_paint();
-            ctx.stroke_path_with(
-                path,
-                ctx.render_state.transform,
-                paint,
-                ctx.aliasing_threshold,
-            );
+            for instance in instances {
+                let instance_transform = transform * instance.transform;
+                ctx.record_access_fill(&fragment.bounds(), instance_transform);
+
+                let bounds = instance_transform.transform_rect_bbox(fragment.bounds());
+                if fragment.is_empty() || !bounds.overlaps(viewport) {
+                    continue;
+                }
+
+                if let Some(paint) = &instance.paint {
+                    ctx.render_state.paint = paint.clone();
+                    ctx.texture_paint = None;
+                } else {
+                    ctx.render_state.paint = paint.clone();
+                    ctx.texture_paint = texture_paint;
+                }
+                ctx.set_paint_visible();
+                if !ctx.paint_visible {
+                    continue;
+                }
+
+                // Paints are positioned relative to the transform of each instance.
+                ctx.render_state.transform = instance_transform;
+                let encoded = ctx.encode_current_paint();
+                ctx.fill_fragment_with(fragment, instance_transform, encoded, ctx.aliasing);
+            }
         });
+
+        self.render_state.transform = transform;
+        self.render_state.paint = paint;
+        self.texture_paint = texture_paint;
+        self.set_paint_visible();
     }
 
     /// Build strips for a stroked path with the given properties.
@@ -477,7 +1074,7 @@ impl Scene {
         path: &BezPath,
         transform: Affine,
         paint: Paint,
-        aliasing_threshold: Option<u8>,
+        aliasing: Aliasing,
     ) {
         let strip_storage = &mut self.strip_storage.borrow_mut();
         let strip_start = strip_storage.strips.len();
@@ -485,7 +1082,28 @@ impl Scene {
             path,
             &self.render_state.stroke,
             transform,
-            aliasing_threshold,
+            aliasing,
+            strip_storage,
+            self.clip_context.get(),
+        );
+
+        submit_strips!(self, strip_storage, strip_start, paint);
+    }
+
+    /// Build strips for an instance of a fragment and submit them to the coarse rasterizer.
+    fn fill_fragment_with(
+        &mut self,
+        fragment: &Fragment,
+        transform: Affine,
+        paint: Paint,
+        aliasing: Aliasing,
+    ) {
+        let strip_storage = &mut self.strip_storage.borrow_mut();
+        let strip_start = strip_storage.strips.len();
+        self.strip_generator.generate_fragment(
+            fragment,
+            transform,
+            aliasing,
             strip_storage,
             self.clip_context.get(),
         );
@@ -505,20 +1123,65 @@ impl Scene {
     /// Note that there is no performance benefit to disabling anti-aliasing and
     /// this functionality is simply provided for compatibility.
     pub fn set_aliasing_threshold(&mut self, aliasing_threshold: Option<u8>) {
-        self.aliasing_threshold = aliasing_threshold;
+        self.aliasing.threshold = aliasing_threshold;
+    }
+
+    /// Set whether the edges of paths are snapped to the pixel grid.
+    ///
+    /// When enabled, the vertices of paths are moved to the nearest pixel corner after
+    /// transforming and flattening them, and anti-aliasing is disabled. This keeps hairlines,
+    /// dividers and other axis-aligned shapes crisp without having to round their coordinates
+    /// beforehand. Pixels are painted if their coverage reaches the
+    /// [aliasing threshold](Self::set_aliasing_threshold), or if they are at least half covered
+    /// if no threshold is set.
+    ///
+    /// Pixel snapping is disabled by default.
+    pub fn set_pixel_snapping(&mut self, snap_to_pixels: bool) {
+        self.aliasing.snap_to_pixels = snap_to_pixels;
+    }
+
+    /// Set the quality preset, which trades the accuracy of subsequently drawn content for
+    /// speed.
+    ///
+    /// The preset sets the tolerance for flattening curves and the size of the lookup tables of
+    /// gradients, and it sets the [aliasing threshold](Self::set_aliasing_threshold), which can
+    /// be overridden afterwards. Blur filters use the number of taps of the preset the scene has
+    /// when it is rendered. The presets match those of `vello_cpu`.
+    ///
+    /// The default is [`Quality::Balanced`]. The quality is kept when the scene is
+    /// [reset](Self::reset).
+    pub fn set_quality(&mut self, quality: Quality) {
+        self.quality = quality;
+        self.aliasing.threshold = quality.aliasing_threshold();
+        self.strip_generator
+            .set_tolerance(quality.flatten_tolerance());
+    }
+
+    /// Return the quality preset.
+    pub fn quality(&self) -> Quality {
+        self.quality
     }
 
     /// Fill a rectangle with the current paint and fill rule.
     pub fn fill_rect(&mut self, rect: &Rect) {
+        self.record_access_fill(rect, self.render_state.transform);
         if !self.paint_visible {
             return;
         }
 
+        if let Some(bands) = self.hard_stop_bands(|| *rect) {
+            let path = rect.to_path(DEFAULT_TOLERANCE);
+            self.with_optional_filter(|ctx| {
+                ctx.draw_bands(&bands, &path, ctx.render_state.fill_rule);
+            });
+            return;
+        }
+
         if self.try_fast_rect(rect) {
             return;
         }
 
-        if is_axis_aligned(&self.render_state.transform) && self.aliasing_threshold.is_none() {
+        if is_axis_aligned(&self.render_state.transform) && self.aliasing.is_anti_aliased() {
             self.with_optional_filter(|ctx| {
                 let paint = ctx.encode_current_paint();
                 let transformed_rect = ctx.render_state.transform.transform_rect_bbox(*rect);
@@ -580,8 +1243,12 @@ impl Scene {
         // Because in this API the destination drawn is always the transformed input rect, this
         // means extend modes don't currently materially impact rendering. In general drawing with
         // an external texture brush, extend modes would matter, so we still encode them.
-        let x_extend = Extend::Pad;
-        let y_extend = Extend::Pad;
+        let sampler = ImageSampler {
+            x_extend: Extend::Pad,
+            y_extend: Extend::Pad,
+            quality,
+            alpha: 1.0,
+        };
 
         if self.can_emit_fast_strips() {
             for rect in rects {
@@ -592,6 +1259,7 @@ impl Scene {
                 let w = f64::from(rect.source_region.width());
                 let h = f64::from(rect.source_region.height());
                 let transform = self.render_state.transform * rect.transform;
+                self.record_access_fill(&Rect::new(0., 0., w, h), transform);
 
                 if !is_axis_aligned(&transform) {
                     // Non-axis-aligned rects fall back to the strip path (still
@@ -599,9 +1267,7 @@ impl Scene {
                     let paint = self.encode_external_texture_paint(
                         texture_id,
                         rect.source_region,
-                        quality,
-                        x_extend,
-                        y_extend,
+                        sampler,
                         transform,
                     );
                     let dst_rect = Rect::new(0., 0., w, h);
@@ -610,7 +1276,7 @@ impl Scene {
                         transform,
                         self.render_state.fill_rule,
                         paint,
-                        self.aliasing_threshold,
+                        self.aliasing,
                     );
                     continue;
                 }
@@ -631,9 +1297,7 @@ impl Scene {
                 let paint = self.encode_external_texture_paint(
                     texture_id,
                     rect.source_region,
-                    quality,
-                    x_extend,
-                    y_extend,
+                    sampler,
                     transform,
                 );
 
@@ -645,6 +1309,7 @@ impl Scene {
                         x1: x1 as f32,
                         y1: y1 as f32,
                         paint,
+                        access_tag: self.wide.access_tag(),
                     }));
             }
         } else {
@@ -657,12 +1322,11 @@ impl Scene {
                     let w = f64::from(rect.source_region.width());
                     let h = f64::from(rect.source_region.height());
                     let transform = ctx.render_state.transform * rect.transform;
+                    ctx.record_access_fill(&Rect::new(0., 0., w, h), transform);
                     let paint = ctx.encode_external_texture_paint(
                         texture_id,
                         rect.source_region,
-                        quality,
-                        x_extend,
-                        y_extend,
+                        sampler,
                         transform,
                     );
                     let dst_rect = Rect::new(0., 0., w, h);
@@ -671,7 +1335,7 @@ impl Scene {
                         transform,
                         ctx.render_state.fill_rule,
                         paint,
-                        ctx.aliasing_threshold,
+                        ctx.aliasing,
                     );
                 }
             });
@@ -685,6 +1349,7 @@ impl Scene {
         self.strip_path_mode != StripPathMode::CoarseOnly
             && !self.wide.has_layers()
             && self.filter.is_none()
+            && self.render_state.blend_mode == DEFAULT_BLEND_MODE
             && self.clip_context.get().is_none()
     }
 
@@ -693,6 +1358,14 @@ impl Scene {
         reason = "f64→f32 truncation is acceptable for pixel coordinates"
     )]
     fn push_fast_rect(&mut self, bounds: Rect, paint: Paint) {
+        if let Some(debugger) = &mut self.strip_debugger {
+            // The strips of fast rectangles are only generated when rendering, so generate
+            // them separately for the debugger.
+            let mut strip_storage = StripStorage::default();
+            self.strip_generator
+                .generate_filled_rect_fast(&bounds, &mut strip_storage, None);
+            debugger.record(&strip_storage.strips);
+        }
         self.fast_strips_buffer
             .commands
             .push(FastStripCommand::Rect(FastPathRect {
@@ -701,6 +1374,7 @@ impl Scene {
                 x1: bounds.x1 as f32,
                 y1: bounds.y1 as f32,
                 paint,
+                access_tag: self.wide.access_tag(),
             }));
     }
 
@@ -718,7 +1392,17 @@ impl Scene {
             return None;
         }
 
-        let transformed_rect = self.render_state.transform.transform_rect_bbox(*rect);
+        let mut transformed_rect = self.render_state.transform.transform_rect_bbox(*rect);
+        if self.aliasing.snap_to_pixels {
+            // Snap the edges the same way strip generation snaps the vertices of paths.
+            let snap = |v: f64| (v + 0.5).floor();
+            transformed_rect = Rect::new(
+                snap(transformed_rect.x0),
+                snap(transformed_rect.y0),
+                snap(transformed_rect.x1),
+                snap(transformed_rect.y1),
+            );
+        }
 
         let x0 = transformed_rect.x0.max(0.0).min(f64::from(self.width));
         let y0 = transformed_rect.y0.max(0.0).min(f64::from(self.height));
@@ -743,6 +1427,11 @@ impl Scene {
     /// This operation uses the current transform and paint transform. Like Vello CPU, it only
     /// uses solid paints; non-solid paints fall back to black.
     pub fn fill_blurred_rounded_rect(&mut self, rect: &Rect, radius: f32, std_dev: f32) {
+        if self.access_tag.is_some() {
+            let kernel_size = f64::from(2.5 * std_dev);
+            let inflated_rect = rect.abs().inflate(kernel_size, kernel_size);
+            self.record_access_fill(&inflated_rect, self.render_state.transform);
+        }
         if !self.paint_visible {
             return;
         }
@@ -771,7 +1460,7 @@ impl Scene {
                 return;
             }
 
-            if is_axis_aligned(&ctx.render_state.transform) && ctx.aliasing_threshold.is_none() {
+            if is_axis_aligned(&ctx.render_state.transform) && ctx.aliasing.is_anti_aliased() {
                 let transformed_rect = ctx
                     .render_state
                     .transform
@@ -791,12 +1480,97 @@ impl Scene {
                     ctx.render_state.transform,
                     Fill::NonZero,
                     paint,
-                    ctx.aliasing_threshold,
+                    ctx.aliasing,
                 );
             }
         });
     }
 
+    /// Register a custom paint kind and return the identifier that [`CustomPaint`]s of that
+    /// kind refer to.
+    ///
+    /// Registered kinds are kept across [`Scene::reset`]. The renderer rebuilds its pipelines
+    /// with the WGSL of all registered kinds the first time it renders the scene after a kind
+    /// was registered, so kinds should be registered up front. The WebGL renderer can't
+    /// compile additional shader code, and draws custom paints as transparent.
+    pub fn register_custom_paint(&mut self, kind: Arc<dyn CustomPaintKind>) -> CustomPaintId {
+        self.custom_paints.register(kind)
+    }
+
+    /// Fill a path with a custom paint, instead of the current paint.
+    ///
+    /// The paint is positioned with the current transform and paint transform. Paints of
+    /// kinds that weren't registered with [`Scene::register_custom_paint`] are transparent.
+    pub fn fill_custom_paint(&mut self, path: &BezPath, paint: &CustomPaint) {
+        self.record_access_fill(path, self.render_state.transform);
+
+        self.with_optional_filter(|ctx| {
+            let transform = ctx.render_state.transform * ctx.render_state.paint_transform;
+            let paint = paint.encode_into(
+                &ctx.custom_paints,
+                &mut ctx.encoded_paints.borrow_mut(),
+                transform,
+            );
+            ctx.fill_path_with(
+                path,
+                ctx.render_state.transform,
+                ctx.render_state.fill_rule,
+                paint,
+                ctx.aliasing,
+            );
+        });
+    }
+
+    /// Stroke a path with a custom paint and the current stroke settings, instead of the
+    /// current paint.
+    ///
+    /// The paint is positioned like in [`Scene::fill_custom_paint`].
+    pub fn stroke_custom_paint(&mut self, path: &BezPath, paint: &CustomPaint) {
+        self.record_access_stroke(path);
+
+        self.with_optional_filter(|ctx| {
+            let transform = ctx.render_state.transform * ctx.render_state.paint_transform;
+            let paint = paint.encode_into(
+                &ctx.custom_paints,
+                &mut ctx.encoded_paints.borrow_mut(),
+                transform,
+            );
+            ctx.stroke_path_with(path, ctx.render_state.transform, paint, ctx.aliasing);
+        });
+    }
+
+    /// Fill the drop shadow of a path.
+    ///
+    /// The shadow is the silhouette of `path`, filled with the current fill rule, moved by
+    /// `offset` and blurred with a Gaussian blur of standard deviation `std_dev`, both in user
+    /// space. Like [`Scene::fill_blurred_rounded_rect`], it only uses solid paints; non-solid
+    /// paints fall back to black. The path itself isn't drawn.
+    ///
+    /// Unlike Vello CPU, which caches the blurred silhouettes, the silhouette is drawn into a
+    /// blur filter layer, so it is blurred on the GPU every time the scene is rendered.
+    pub fn fill_drop_shadow(&mut self, path: &BezPath, offset: Vec2, std_dev: f32) {
+        if !self.paint_visible {
+            return;
+        }
+
+        let color = match self.render_state.paint {
+            PaintType::Solid(s) => s,
+            _ => BLACK,
+        };
+
+        self.with_optional_filter(|ctx| {
+            ctx.push_blur_layer(std_dev);
+            ctx.fill_path_with(
+                path,
+                ctx.render_state.transform * Affine::translate(offset),
+                ctx.render_state.fill_rule,
+                color.into(),
+                ctx.aliasing,
+            );
+            ctx.pop_layer();
+        });
+    }
+
     /// Creates a builder for drawing a run of glyphs that have the same attributes.
     #[cfg(feature = "text")]
     pub fn glyph_run<'a>(
@@ -826,10 +1600,12 @@ impl Scene {
             return;
         }
 
+        let access_tag = self.wide.access_tag();
         let mut strip_storage = self.strip_storage.borrow_mut();
         for cmd in self.fast_strips_buffer.commands.drain(..) {
             match cmd {
                 FastStripCommand::Path(path) => {
+                    self.wide.set_access_tag(path.access_tag);
                     self.wide.generate(
                         &strip_storage.strips[path.strips],
                         path.paint,
@@ -849,6 +1625,7 @@ impl Scene {
                     let strip_start = strip_storage.strips.len();
                     self.strip_generator
                         .generate_filled_rect_fast(&rect, &mut strip_storage, None);
+                    self.wide.set_access_tag(r.access_tag);
                     self.wide.generate(
                         &strip_storage.strips[strip_start..],
                         r.paint,
@@ -860,6 +1637,7 @@ impl Scene {
                 }
             }
         }
+        self.wide.set_access_tag(access_tag);
 
         strip_storage.set_generation_mode(GenerationMode::Replace);
         self.strip_path_mode = StripPathMode::CoarseOnly;
@@ -875,29 +1653,7 @@ impl Scene {
         filter: Option<Filter>,
     ) {
         let blend_mode_val = blend_mode.unwrap_or(DEFAULT_BLEND_MODE);
-        self.constraints
-            .assert_blend_mode(blend_mode_val, self.wide.has_layers());
-
-        self.layer_id_next += 1;
-
-        let strip_offset;
-        if self.constraints.use_default_blending_only() {
-            // With default blending only we can keep fast path strips alive. Record a
-            // split point so the scheduler knows to process one coarse batch after
-            // processing fast path strips up to this point.
-            if !self.wide.has_layers() {
-                let split = self.fast_strips_buffer.commands.len();
-                self.coarse_batch_splits.push(split);
-            }
-            let mut strip_storage = self.strip_storage.borrow_mut();
-            strip_offset = strip_storage.strips.len();
-            strip_storage.set_generation_mode(GenerationMode::ReplaceAfter(strip_offset));
-            self.strip_path_mode = StripPathMode::Interleaved;
-        } else {
-            strip_offset = 0;
-            self.flush_fast_path();
-        }
-
+        let strip_offset = self.begin_layer(blend_mode_val);
         let mut strip_storage = self.strip_storage.borrow_mut();
 
         let clip = if let Some(c) = clip_path {
@@ -905,7 +1661,7 @@ impl Scene {
                 c,
                 self.render_state.fill_rule,
                 self.render_state.transform,
-                self.aliasing_threshold,
+                self.aliasing,
                 &mut strip_storage,
                 self.clip_context.get(),
             );
@@ -933,8 +1689,45 @@ impl Scene {
         );
     }
 
+    /// Allocate the ID of a new layer with `blend_mode` and prepare the strip storage for it.
+    ///
+    /// Returns the offset at which the strips of the clip path of the layer start.
+    fn begin_layer(&mut self, blend_mode: BlendMode) -> usize {
+        self.constraints
+            .assert_blend_mode(blend_mode, self.wide.has_layers());
+        if !self.wide.has_layers() && !preserves_opaque_backdrop(blend_mode.compose) {
+            self.root_may_reduce_alpha = true;
+        }
+
+        self.layer_id_next += 1;
+
+        let strip_offset;
+        if self.constraints.use_default_blending_only() {
+            // With default blending only we can keep fast path strips alive. Record a
+            // split point so the scheduler knows to process one coarse batch after
+            // processing fast path strips up to this point.
+            if !self.wide.has_layers() {
+                let split = self.fast_strips_buffer.commands.len();
+                self.coarse_batch_splits.push(split);
+            }
+            let mut strip_storage = self.strip_storage.borrow_mut();
+            strip_offset = strip_storage.strips.len();
+            strip_storage.set_generation_mode(GenerationMode::ReplaceAfter(strip_offset));
+            self.strip_path_mode = StripPathMode::Interleaved;
+        } else {
+            strip_offset = 0;
+            self.flush_fast_path();
+        }
+
+        strip_offset
+    }
+
     /// Push a new clip layer.
     ///
+    /// The clip path is filled with the current fill rule and transform. Clip layers can be
+    /// nested arbitrarily deep, in which case content is clipped by the intersection of all
+    /// clip paths on the stack. Each call must be balanced by a call to [`Scene::pop_layer`].
+    ///
     /// See the explanation in the [clipping](https://github.com/linebender/vello/tree/main/sparse_strips/vello_cpu/examples)
     /// example for how this method differs from `push_clip_path`.
     pub fn push_clip_layer(&mut self, path: &BezPath) {
@@ -947,6 +1740,12 @@ impl Scene {
     }
 
     /// Push a new opacity layer.
+    ///
+    /// Everything drawn until the matching [`Scene::pop_layer`] is composited into an
+    /// intermediate layer first, and `opacity` is applied to that layer as a whole. Unlike
+    /// giving each shape a translucent paint, overlapping shapes inside the group therefore don't
+    /// show through each other, which matches the `opacity` property of SVG groups. `opacity` is
+    /// clamped to the range `[0, 1]`.
     pub fn push_opacity_layer(&mut self, opacity: f32) {
         self.push_layer(None, None, Some(opacity), None, None);
     }
@@ -958,13 +1757,95 @@ impl Scene {
         self.push_layer(None, None, None, Some(mask), None);
     }
 
-    /// Push a new filter layer.
+    /// Push a new mask group.
+    ///
+    /// Everything drawn until the matching [`Scene::end_mask`] makes up the mask. Everything
+    /// drawn after that, until the matching [`Scene::pop_layer`], is the content, which is
+    /// composited into an intermediate layer and then modulated by the mask. Depending on
+    /// `kind`, either the alpha or the luminance of the mask is used for that, which correspond
+    /// to the `mask-type` values of SVG masks.
+    pub fn push_mask_group(&mut self, kind: MaskKind) {
+        self.mask_groups.push(kind);
+        self.begin_layer(DEFAULT_BLEND_MODE);
+        self.wide
+            .push_mask_group(self.layer_id_next, &mut self.render_graph, 0);
+    }
+
+    /// Finish drawing the mask of the last-pushed mask group and start drawing its content.
+    ///
+    /// # Panics
     ///
-    /// Note that filters are currently ignored in `vello_hybrid`.
+    /// Panics if there is no mask group whose mask is being drawn, or if a layer that was
+    /// pushed after the mask group hasn't been popped yet.
+    pub fn end_mask(&mut self) {
+        let kind = self
+            .mask_groups
+            .pop()
+            .expect("`end_mask` called without a matching `push_mask_group`");
+        self.layer_id_next += 1;
+        self.wide
+            .end_mask(self.layer_id_next, kind, &mut self.render_graph, 0);
+    }
+
+    /// Push a new filter layer.
     pub fn push_filter_layer(&mut self, filter: Filter) {
         self.push_layer(None, None, None, None, Some(filter));
     }
 
+    /// Push a layer that applies a Gaussian blur to all subsequent drawing operations.
+    ///
+    /// Like the CSS `blur()` filter function, `radius` is the standard deviation of the
+    /// Gaussian, so the blur visibly extends about three times as far. The layer is rendered
+    /// into the filter atlas and blurred with separable horizontal and vertical passes.
+    pub fn push_blur_layer(&mut self, radius: f32) {
+        self.push_filter_layer(Filter::from_function(FilterFunction::Blur { radius }));
+    }
+
+    /// Push a clip layer whose area is first filled with a blurred copy of the content
+    /// drawn so far, like the CSS `backdrop-filter: blur()` property.
+    ///
+    /// The backdrop within `clip` is copied into the filter atlas, blurred, and composited
+    /// back before any subsequent drawing operations, which are clipped to `clip` until the
+    /// matching [`Scene::pop_layer`].
+    ///
+    /// The backdrop is the content of the innermost active layer with a blend mode, opacity,
+    /// mask or filter, or of the root layer if there is none. Clip layers in between don't
+    /// clip the backdrop. In scenes constrained to default blending, the backdrop is not
+    /// blurred and only the clip layer is pushed.
+    pub fn push_backdrop_blur_layer(&mut self, radius: f32, clip: &BezPath) {
+        if self.constraints.use_default_blending_only() {
+            log::warn!(
+                "backdrop blur layers are not supported in scenes constrained to default blending"
+            );
+            self.push_clip_layer(clip);
+            return;
+        }
+        self.flush_fast_path();
+
+        self.layer_id_next += 1;
+        {
+            let mut strip_storage = self.strip_storage.borrow_mut();
+            self.strip_generator.generate_filled_path(
+                clip,
+                self.render_state.fill_rule,
+                self.render_state.transform,
+                self.aliasing,
+                &mut strip_storage,
+                self.clip_context.get(),
+            );
+            self.wide.push_backdrop_filter(
+                self.layer_id_next,
+                strip_storage.strips.as_slice(),
+                Filter::from_function(FilterFunction::Blur { radius }),
+                self.render_state.transform,
+                &mut self.render_graph,
+                0,
+            );
+        }
+
+        self.push_clip_layer(clip);
+    }
+
     /// Pop the last pushed layer.
     pub fn pop_layer(&mut self) {
         self.wide.pop_layer(&mut self.render_graph);
@@ -977,6 +1858,11 @@ impl Scene {
     }
 
     /// Set the blend mode for subsequent rendering operations.
+    ///
+    /// Like in Vello CPU, the blend mode is only applied within the drawn shape, so that
+    /// destructive compose modes like [`Compose::Copy`] or [`Compose::SrcIn`] leave the
+    /// backdrop outside of it untouched. Use [`push_blend_layer`](Self::push_blend_layer) to
+    /// apply them to the whole backdrop instead.
     pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
         self.constraints
             .assert_blend_mode(blend_mode, self.wide.has_layers());
@@ -1005,13 +1891,27 @@ impl Scene {
     //       render time into a texture usable by the renderer backend.
     pub fn set_paint(&mut self, paint: impl Into<PaintType>) {
         self.render_state.paint = paint.into();
+        self.texture_paint = None;
+        self.set_paint_visible();
+    }
+
+    /// Set a paint that samples from an externally owned texture for subsequent rendering
+    /// operations.
+    ///
+    /// The paint stays active until the next call to [`set_paint`](Self::set_paint) or
+    /// [`reset`](Self::reset). Like with [`draw_texture_rects`](Self::draw_texture_rects), a
+    /// texture with the given [`TextureId`] must be supplied at render time, either as part of
+    /// the backend's binding type or by registering it with the renderer.
+    pub fn set_texture_paint(&mut self, paint: TexturePaint) {
+        self.texture_paint = Some(paint);
         self.set_paint_visible();
     }
 
     fn set_paint_visible(&mut self) {
-        self.paint_visible = match &self.render_state.paint {
-            PaintType::Solid(color) => color.components[3] != 0.0,
-            _ => true,
+        self.paint_visible = match (&self.texture_paint, &self.render_state.paint) {
+            (Some(texture_paint), _) => texture_paint.sampler.alpha != 0.0,
+            (None, PaintType::Solid(color)) => color.components[3] != 0.0,
+            (None, _) => true,
         };
     }
 
@@ -1025,6 +1925,16 @@ impl Scene {
         self.render_state.tint = None;
     }
 
+    /// Set the interpolation hints for subsequent gradient paint operations.
+    ///
+    /// The hint at index `i` is the offset between the color stops `i` and `i + 1` at which
+    /// the color is halfway between the colors of the two stops. `None` entries keep the
+    /// transition linear, and an empty slice removes all hints. See [`GradientHints`] for
+    /// details.
+    pub fn set_gradient_hints(&mut self, hints: &[Option<f32>]) {
+        self.render_state.gradient_hints = GradientHints::new(hints);
+    }
+
     /// Get the current paint.
     pub fn paint(&self) -> &PaintType {
         &self.render_state.paint
@@ -1077,6 +1987,11 @@ impl Scene {
             f(self);
             self.pop_layer();
         } else {
+            if !self.wide.has_layers()
+                && !preserves_opaque_backdrop(self.render_state.blend_mode.compose)
+            {
+                self.root_may_reduce_alpha = true;
+            }
             f(self);
         }
     }
@@ -1093,12 +2008,18 @@ impl Scene {
             ss.set_generation_mode(GenerationMode::Append);
         }
         self.encoded_paints.borrow_mut().clear();
+        self.gradient_stops.maintain();
 
         self.render_state.reset();
+        self.texture_paint = None;
+        self.paint_visible = true;
 
         self.fast_strips_buffer.clear();
         self.strip_path_mode = StripPathMode::FastOnly;
         self.coarse_batch_splits.clear();
+        self.access_tag = None;
+        self.access_geometry.clear();
+        self.root_may_reduce_alpha = false;
 
         self.layer_id_next = 0;
         self.render_graph.clear();
@@ -1109,6 +2030,153 @@ impl Scene {
             wtile_bbox,
         });
         self.filter = None;
+        self.mask_groups.clear();
+        self.damage = None;
+        if let Some(debugger) = &mut self.strip_debugger {
+            debugger.clear();
+        }
+        #[cfg(feature = "multithreading")]
+        {
+            self.parallel_stats = RenderStats::default();
+        }
+    }
+
+    /// Return statistics about the paths drawn since the scene was last reset.
+    ///
+    /// Only the paths, strips and alpha bytes are counted here. The renderer adds the draw
+    /// calls and the alpha bytes it actually uploaded when the scene is rendered, see
+    /// `Renderer::render_stats`.
+    pub fn render_stats(&self) -> RenderStats {
+        #[cfg(not(feature = "multithreading"))]
+        let stats = self.strip_generator.stats();
+        #[cfg(feature = "multithreading")]
+        let stats = {
+            let mut stats = self.strip_generator.stats();
+            stats += self.parallel_stats;
+            stats
+        };
+        stats
+    }
+
+    /// Set how the render target is initialized when the scene is rendered.
+    ///
+    /// By default, the target is cleared to transparent black. With [`LoadOp::Load`], the
+    /// existing contents of the target are preserved and the scene is composited on top of
+    /// them, which allows drawing an overlay onto an externally rendered frame without
+    /// copying it first. Note that backdrop filters only see the content of the scene.
+    ///
+    /// The load op is kept when the scene is [reset](Self::reset).
+    pub fn set_load_op(&mut self, load_op: LoadOp) {
+        self.load_op = load_op;
+    }
+
+    /// Get how the render target is initialized when the scene is rendered.
+    pub fn load_op(&self) -> LoadOp {
+        self.load_op
+    }
+
+    /// Only redraw the given regions of the render target, and preserve the rest of it.
+    ///
+    /// The regions are rounded outwards to whole pixels. Everything drawn afterwards is
+    /// clipped to them, so that strips are only generated and rendered for the damaged
+    /// pixels. When the scene is rendered, the damaged pixels are initialized according to
+    /// the [load op](Self::set_load_op), while all other pixels keep the contents of the
+    /// previous frame that was rendered into the same target.
+    ///
+    /// This has to be called after [`reset`](Self::reset) and before anything is drawn, at
+    /// most once per frame. The damage is cleared when the scene is reset.
+    ///
+    /// # Panics
+    ///
+    /// Clearing the damaged region to a color that isn't opaque requires a non-default
+    /// blend mode in the root layer, so this panics if the load op clears to a translucent
+    /// color and the scene is constrained to
+    /// [default blending](SceneConstraints::default_blending_only).
+    pub fn set_damage(&mut self, damage: &[Rect]) {
+        let viewport = Rect::new(0.0, 0.0, f64::from(self.width), f64::from(self.height));
+        let mut path = BezPath::new();
+        let mut bounds = Rect::ZERO;
+        for rect in damage {
+            let rect = rect.abs().expand().intersect(viewport);
+            if rect.is_zero_area() {
+                continue;
+            }
+            path.extend(rect.path_elements(0.1));
+            bounds = if bounds.is_zero_area() {
+                rect
+            } else {
+                bounds.union(rect)
+            };
+        }
+        self.damage = Some(bounds);
+
+        // Rectangles always wind in the same direction, so overlapping ones don't cancel out.
+        self.clip_context.push_clip(
+            &path,
+            &mut self.strip_generator,
+            Fill::NonZero,
+            Affine::IDENTITY,
+            Aliasing::default(),
+        );
+
+        let clear_color = match self.load_op {
+            LoadOp::ClearTransparent => Some(TRANSPARENT),
+            LoadOp::Clear(color) => Some(color),
+            LoadOp::Load => None,
+        };
+        if let Some(color) = clear_color
+            && !path.is_empty()
+        {
+            let state = self.take_current_state();
+            self.set_paint(color);
+            if color.components[3] >= 1.0 {
+                self.fill_path(&path);
+            } else {
+                // Replace the preserved contents of the damaged pixels with the clear color.
+                self.push_layer(
+                    Some(&path),
+                    Some(BlendMode::new(Mix::Normal, Compose::Copy)),
+                    None,
+                    None,
+                    None,
+                );
+                self.fill_path(&path);
+                self.pop_layer();
+            }
+            self.restore_state(state);
+        }
+    }
+
+    /// Get the bounding box of the damaged region, or `None` if the whole target is redrawn.
+    ///
+    /// See [`set_damage`](Self::set_damage) for details.
+    pub fn damage(&self) -> Option<Rect> {
+        self.damage
+    }
+
+    /// How the renderer initializes the render target.
+    ///
+    /// Damaged scenes clear the damaged region themselves and preserve everything else.
+    pub(crate) fn target_load_op(&self) -> LoadOp {
+        if self.damage.is_some() {
+            LoadOp::Load
+        } else {
+            self.load_op
+        }
+    }
+
+    /// Whether every pixel of the render target is guaranteed to be opaque after rendering
+    /// the scene.
+    ///
+    /// This is the case if the target is cleared to an opaque color and nothing is composited
+    /// into the root layer with an operator that can reduce the alpha of the backdrop. The
+    /// renderer then doesn't need to write the alpha channel of the target.
+    pub(crate) fn has_opaque_result(&self) -> bool {
+        let opaque_clear =
+            matches!(self.load_op, LoadOp::Clear(color) if color.components[3] >= 1.0);
+
+        // The preserved pixels of a damaged scene aren't known to be opaque.
+        opaque_clear && !self.root_may_reduce_alpha && self.damage.is_none()
     }
 
     /// Get the width of the render context.
@@ -1124,6 +2192,7 @@ impl Scene {
     /// Take current rendering state and reset the existing state to its default.
     pub fn take_current_state(&mut self) -> RenderState {
         let state = core::mem::take(&mut self.render_state);
+        self.texture_paint = None;
         self.set_paint_visible();
 
         state
@@ -1269,6 +2338,82 @@ mod tests {
         assert!(resources.glyph_resources.is_some());
     }
 
+    #[cfg(feature = "text")]
+    #[test]
+    fn glyph_cache_uses_configured_subpixel_buckets() {
+        const ROBOTO_FONT: &[u8] =
+            include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+
+        let font = FontData::new(Blob::new(Arc::new(ROBOTO_FONT)), 0);
+        let glyphs = (0..8).map(|i| Glyph {
+            id: 1,
+            x: 10.0 * i as f32 + 0.125 * i as f32,
+            y: 0.0,
+        });
+        let cached_entries = |config: glifo::GlyphCacheConfig| {
+            let mut scene = unconstrained();
+            let mut resources = Resources::new().with_glyph_cache_config(config);
+            scene
+                .glyph_run(&mut resources, &font)
+                .atlas_cache(true)
+                .fill_glyphs(glyphs.clone());
+            resources.glyph_resources.unwrap().glyph_atlas.len()
+        };
+
+        assert_eq!(cached_entries(glifo::GlyphCacheConfig::default()), 4);
+        assert_eq!(
+            cached_entries(glifo::GlyphCacheConfig {
+                subpixel_buckets: 8,
+                ..Default::default()
+            }),
+            8
+        );
+        assert_eq!(
+            cached_entries(glifo::GlyphCacheConfig {
+                subpixel_buckets: 1,
+                ..Default::default()
+            }),
+            1
+        );
+    }
+
+    #[cfg(feature = "text")]
+    #[test]
+    fn glyph_cache_quantizes_variation_coords() {
+        const ROBOTO_FONT: &[u8] =
+            include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+
+        let font = FontData::new(Blob::new(Arc::new(ROBOTO_FONT)), 0);
+        let glyphs = [Glyph {
+            id: 1,
+            x: 0.0,
+            y: 0.0,
+        }];
+        let cached_entries = |config: glifo::GlyphCacheConfig| {
+            let mut scene = unconstrained();
+            let mut resources = Resources::new().with_glyph_cache_config(config);
+            // Simulate a slowly animated axis.
+            for i in 0..8 {
+                let coords = [8010 + 10 * i];
+                scene
+                    .glyph_run(&mut resources, &font)
+                    .normalized_coords(&coords)
+                    .atlas_cache(true)
+                    .fill_glyphs(glyphs.into_iter());
+            }
+            resources.glyph_resources.unwrap().glyph_atlas.len()
+        };
+
+        assert_eq!(cached_entries(glifo::GlyphCacheConfig::default()), 1);
+        assert_eq!(
+            cached_entries(glifo::GlyphCacheConfig {
+                variation_steps: glifo::atlas::key::MAX_VARIATION_STEPS,
+                ..Default::default()
+            }),
+            8
+        );
+    }
+
     #[test]
     fn fast_only_stroke_is_path() {
         let mut scene = unconstrained();
@@ -1492,4 +2637,229 @@ mod tests {
         assert_eq!(scene.fast_strips_buffer.commands.len(), 1);
         assert!(is_rect(&scene.fast_strips_buffer.commands[0]));
     }
+
+    #[test]
+    fn batched_paths_match_individual_paths() {
+        let paths: Vec<(BezPath, PaintType)> = (0..8)
+            .map(|i| {
+                let offset = f64::from(i) * 17.5;
+                let mut path = BezPath::new();
+                path.move_to((offset, 10.0));
+                path.line_to((offset + 40.0, 150.0));
+                path.line_to((5.0, offset + 30.0));
+                path.close_path();
+                (path, Color::from_rgba8(i * 30, 0, 0, 255).into())
+            })
+            .collect();
+        let draw = |num_threads, batched: bool, blend_mode, filter: Option<Filter>| {
+            let mut scene = Scene::new_with(
+                200,
+                200,
+                RenderSettings {
+                    num_threads,
+                    ..Default::default()
+                },
+            );
+            scene.set_transform(Affine::rotate(0.1));
+            scene.push_clip_path(&triangle_path());
+            scene.set_blend_mode(blend_mode);
+            if let Some(filter) = filter {
+                scene.set_filter_effect(filter);
+            }
+            for stroke in [false, true] {
+                if batched && stroke {
+                    scene.stroke_paths(&paths);
+                } else if batched {
+                    scene.fill_paths(&paths);
+                } else {
+                    for (path, paint) in &paths {
+                        scene.set_paint(paint.clone());
+                        if stroke {
+                            scene.stroke_path(path);
+                        } else {
+                            scene.fill_path(path);
+                        }
+                    }
+                }
+            }
+            let strip_storage = scene.strip_storage.borrow();
+            let cmds: Vec<_> = scene
+                .wide
+                .tiles()
+                .iter()
+                .map(|tile| tile.cmds.clone())
+                .collect();
+            (
+                strip_storage.strips.clone(),
+                strip_storage.alphas.clone(),
+                cmds,
+                scene.render_stats(),
+            )
+        };
+
+        let multiply = BlendMode::new(Mix::Multiply, Compose::SrcOver);
+        let blur = Filter::from_function(FilterFunction::Blur { radius: 2.0 });
+        for (blend_mode, filter) in [
+            (DEFAULT_BLEND_MODE, None),
+            (multiply, None),
+            (DEFAULT_BLEND_MODE, Some(blur)),
+        ] {
+            let expected = draw(0, false, blend_mode, filter.clone());
+            assert_eq!(draw(0, true, blend_mode, filter.clone()), expected);
+            assert_eq!(draw(3, true, blend_mode, filter), expected);
+        }
+    }
+
+    #[test]
+    fn strip_debugger_records_paths_and_rects_but_not_the_overlay() {
+        use vello_common::strip_debug::StripDebugView;
+
+        let mut scene = unconstrained();
+        scene.set_strip_debug_view(Some(StripDebugView::Coverage));
+        scene.set_paint(Color::from_rgba8(255, 0, 0, 255));
+        scene.fill_rect(&small_rect());
+        scene.fill_path(&triangle_path());
+        let stats = scene.strip_debugger().unwrap().stats();
+        assert_eq!(stats.paths, 2);
+
+        scene.draw_strip_debug_overlay();
+        assert_eq!(scene.strip_debugger().unwrap().stats(), stats);
+        assert!(matches!(
+            scene.paint(),
+            PaintType::Solid(color) if *color == Color::from_rgba8(255, 0, 0, 255)
+        ));
+
+        scene.reset();
+        assert_eq!(scene.strip_debugger().unwrap().stats().paths, 0);
+    }
+
+    #[test]
+    fn render_stats_count_generated_strips() {
+        let mut scene = unconstrained();
+        scene.set_paint(Color::from_rgba8(255, 0, 0, 255));
+        scene.fill_path(&triangle_path());
+        scene.stroke_path(&triangle_path());
+
+        let stats = scene.render_stats();
+        {
+            let strip_storage = scene.strip_storage.borrow();
+            assert_eq!(stats.strips, strip_storage.strips.len() as u64);
+            assert_eq!(stats.alpha_bytes, strip_storage.alphas.len() as u64);
+        }
+        assert_eq!(stats.paths, 2);
+        assert_eq!(stats.draw_calls, 0);
+
+        scene.reset();
+        assert_eq!(scene.render_stats(), RenderStats::default());
+    }
+
+    #[test]
+    fn access_geometry_of_tagged_primitives() {
+        use vello_common::accessibility::AccessTag;
+
+        let mut scene = unconstrained();
+        scene.set_access_tag(Some(AccessTag(1)));
+        scene.set_transform(Affine::translate((10.0, 20.0)));
+        scene.fill_rect(&small_rect());
+        scene.draw_texture_rects(
+            TextureId(0),
+            ImageQuality::Low,
+            [SampleRect {
+                source_region: RectU16::new(0, 0, 4, 8),
+                transform: Affine::scale(2.0),
+            }],
+        );
+        scene.set_access_tag(None);
+        scene.fill_rect(&Rect::new(0.0, 0.0, 100.0, 100.0));
+
+        let geometry = scene.access_geometry();
+        assert_eq!(geometry.nodes().len(), 1);
+        assert_eq!(
+            geometry.get(AccessTag(1)).unwrap().bounds(),
+            Affine::translate((10.0, 20.0))
+                .transform_rect_bbox(small_rect())
+                .union(Rect::new(10.0, 20.0, 18.0, 36.0))
+        );
+
+        scene.reset();
+        assert!(scene.access_geometry().is_empty());
+        assert_eq!(scene.access_tag(), None);
+    }
+
+    #[test]
+    fn visible_access_tags_exclude_covered_primitives() {
+        use vello_common::accessibility::AccessTag;
+
+        let mut scene = Scene::new(512, 8);
+        scene.set_paint(Color::from_rgba8(255, 0, 0, 255));
+        scene.set_access_tag(Some(AccessTag(1)));
+        scene.fill_rect(&Rect::new(300.0, 0.0, 310.0, 4.0));
+        scene.set_access_tag(Some(AccessTag(2)));
+        scene.fill_path(&Rect::new(600.0, 0.0, 610.0, 4.0).to_path(0.1));
+        assert_eq!(scene.visible_access_tags(), [AccessTag(1)]);
+
+        // Pushing a layer moves the fast path commands into the wide tiles.
+        scene.push_layer(None, None, Some(0.5), None, None);
+        scene.pop_layer();
+        assert_eq!(scene.strip_path_mode, StripPathMode::CoarseOnly);
+        assert_eq!(scene.visible_access_tags(), [AccessTag(1)]);
+
+        scene.set_access_tag(Some(AccessTag(3)));
+        scene.fill_path(&Rect::new(-10.0, -10.0, 600.0, 20.0).to_path(0.1));
+        scene.set_paint(Color::from_rgba8(255, 0, 0, 128));
+        scene.set_access_tag(Some(AccessTag(4)));
+        scene.fill_rect(&Rect::new(320.5, 0.0, 330.0, 4.0));
+        scene.set_access_tag(None);
+        scene.fill_rect(&Rect::new(340.0, 0.0, 350.0, 4.0));
+        assert_eq!(scene.visible_access_tags(), [AccessTag(3), AccessTag(4)]);
+
+        scene.reset();
+        assert!(scene.visible_access_tags().is_empty());
+    }
+
+    #[test]
+    fn opaque_result_requires_opaque_clear_and_alpha_preserving_layers() {
+        let opaque = Color::from_rgba8(255, 255, 255, 255);
+        let mut scene = unconstrained();
+        assert!(!scene.has_opaque_result());
+
+        scene.set_load_op(LoadOp::Clear(opaque.with_alpha(0.5)));
+        assert!(!scene.has_opaque_result());
+
+        scene.set_load_op(LoadOp::Clear(opaque));
+        scene.fill_rect(&small_rect());
+        scene.push_blend_layer(BlendMode::new(Mix::Multiply, Compose::SrcOver));
+        // Nested layers only affect the alpha of their own backdrop.
+        scene.push_blend_layer(BlendMode::new(Mix::Normal, Compose::Clear));
+        scene.pop_layer();
+        scene.pop_layer();
+        assert!(scene.has_opaque_result());
+
+        scene.push_blend_layer(BlendMode::new(Mix::Normal, Compose::DestOut));
+        scene.pop_layer();
+        assert!(!scene.has_opaque_result());
+
+        scene.reset();
+        assert!(scene.has_opaque_result());
+    }
+
+    #[test]
+    fn damage_preserves_the_target_outside_of_it() {
+        let mut scene = unconstrained();
+        scene.set_load_op(LoadOp::Clear(Color::from_rgba8(255, 255, 255, 255)));
+        assert_eq!(scene.damage(), None);
+        assert!(scene.has_opaque_result());
+
+        scene.set_damage(&[
+            Rect::new(10.5, 2.2, 20.0, 8.0),
+            Rect::new(-5.0, 30.0, 4.0, 36.0),
+        ]);
+        assert_eq!(scene.damage(), Some(Rect::new(0.0, 2.0, 20.0, 36.0)));
+        assert_eq!(scene.target_load_op(), LoadOp::Load);
+        assert!(!scene.has_opaque_result());
+
+        scene.reset();
+        assert_eq!(scene.damage(), None);
+        assert!(matches!(scene.target_load_op(), LoadOp::Clear(_)));
+    }
 }
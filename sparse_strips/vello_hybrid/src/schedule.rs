@@ -193,7 +193,7 @@ use vello_common::{
     TextureId,
     coarse::{Cmd, WideTile},
     encode::EncodedPaint,
-    paint::{ImageSource, Paint},
+    paint::{ImageSource, Paint, PremulColor},
     tile::Tile,
 };
 
@@ -202,12 +202,17 @@ const COLOR_SOURCE_PAYLOAD: u32 = 0;
 const COLOR_SOURCE_SLOT: u32 = 1;
 const COLOR_SOURCE_BLEND: u32 = 2;
 
+/// Bit 24 of [`GpuStrip::paint_and_rect_flag`] signals that a blend should use the
+/// luminance of the destination as its alpha, see `Cmd::LuminanceToAlpha`.
+const BLEND_DEST_LUMINANCE_FLAG: u32 = 1 << 24;
+
 const PAINT_TYPE_SOLID: u32 = 0;
 const PAINT_TYPE_IMAGE: u32 = 1;
 const PAINT_TYPE_LINEAR_GRADIENT: u32 = 2;
 const PAINT_TYPE_RADIAL_GRADIENT: u32 = 3;
 const PAINT_TYPE_SWEEP_GRADIENT: u32 = 4;
 const PAINT_TYPE_BLURRED_ROUNDED_RECT: u32 = 5;
+const PAINT_TYPE_CUSTOM: u32 = 6;
 
 /// Bit 31 of [`GpuStrip::paint_and_rect_flag`] signals that the strip
 /// represents a full rectangle.
@@ -224,6 +229,17 @@ pub(crate) enum RootRenderTarget {
     /// The root render target is the user-provided surface.
     UserSurface,
     /// The root render target is an atlas layer.
+    #[cfg_attr(
+        not(any(
+            feature = "text",
+            feature = "wgpu",
+            all(target_arch = "wasm32", feature = "webgl")
+        )),
+        expect(
+            dead_code,
+            reason = "only glyphs and pattern cells are rendered into the atlas"
+        )
+    )]
     AtlasLayer,
 }
 
@@ -478,6 +494,9 @@ struct TileEl {
     temporary_slot: TemporarySlot,
     round: usize,
     opacity: f32,
+    /// Whether the luminance of this tile should be converted to alpha before the layer above
+    /// is blended into it.
+    luminance_to_alpha: bool,
 }
 
 impl TileEl {
@@ -620,7 +639,16 @@ impl Scheduler {
                     layer_id,
                     wtile_bbox,
                     ..
+                }
+                | RenderNodeKind::BackdropLayer {
+                    layer_id,
+                    wtile_bbox,
+                    ..
                 } => {
+                    let backdrop = match node.kind {
+                        RenderNodeKind::BackdropLayer { in_root_layer, .. } => Some(in_root_layer),
+                        _ => None,
+                    };
                     self.output_target = StripPassRenderTarget::FilterLayer(*layer_id);
                     self.process_filter_node(
                         state,
@@ -628,6 +656,7 @@ impl Scheduler {
                         scene,
                         *layer_id,
                         *wtile_bbox,
+                        backdrop,
                         paint_idxs,
                         filter_context,
                         encoded_paints,
@@ -790,6 +819,11 @@ impl Scheduler {
     }
 
     /// Process a filter node in the render graph.
+    ///
+    /// For backdrop nodes, `backdrop` holds whether the layer was pushed in the root layer. In
+    /// that case, the render range covers the root layer commands preceding the layer, so the
+    /// tile background is painted as well. Otherwise, it covers the commands of the parent
+    /// layer, which can end inside of clip layers that are still open.
     fn process_filter_node<R: RendererBackend>(
         &mut self,
         state: &mut SchedulerState,
@@ -797,6 +831,7 @@ impl Scheduler {
         scene: &Scene,
         layer_id: LayerId,
         wtile_bbox: WideTilesBbox,
+        backdrop: Option<bool>,
         paint_idxs: &[u32],
         filter_context: &FilterContext,
         encoded_paints: &[EncodedPaint],
@@ -820,8 +855,9 @@ impl Scheduler {
                     self.round,
                     encoded_paints,
                     paint_idxs,
-                    // Background is only ever applied in the root layer.
-                    false,
+                    // Background is only ever applied in the root layer, which is what
+                    // backdrop nodes pushed in the root layer sample from.
+                    backdrop == Some(true),
                 );
 
                 let Some(ranges) = wide_tile.layer_cmd_ranges.get(&layer_id) else {
@@ -829,10 +865,16 @@ impl Scheduler {
                 };
 
                 // TODO: Use enum instead of `wrap_surface`?
-                let wrap_surface = matches!(
-                    wide_tile.cmds[ranges.full_range.start],
-                    Cmd::PushBuf(_, true)
-                );
+                let wrap_surface = if let Some(in_root_layer) = backdrop {
+                    // The buffer of the parent layer might be a blend target, so nested
+                    // backdrops are always wrapped.
+                    !in_root_layer || wide_tile.surface_is_blend_target()
+                } else {
+                    matches!(
+                        wide_tile.cmds[ranges.full_range.start],
+                        Cmd::PushBuf(_, true)
+                    )
+                };
 
                 self.do_tile(
                     state,
@@ -1138,6 +1180,7 @@ impl Scheduler {
             temporary_slot: TemporarySlot::None,
             round: initial_round,
             opacity: 1.,
+            luminance_to_alpha: false,
         });
 
         if paint_bg {
@@ -1188,11 +1231,39 @@ impl Scheduler {
         if surface_is_blend_target {
             self.do_push_buf(state, renderer, true)?;
         }
+        let stack_depth = state.tile_state.stack.len();
 
         let mut cmd_idx = cmd_range.start;
         while cmd_idx < cmd_range.end {
             let cmd = &tile.cmds[cmd_idx];
             match cmd {
+                Cmd::Fill(CmdFill { attrs_idx, .. })
+                | Cmd::AlphaFill(CmdAlphaFill { attrs_idx, .. })
+                    if attrs.fill[*attrs_idx as usize].blend_mode != BlendMode::default() =>
+                {
+                    // The consecutive fills of a single path are blended together.
+                    let attrs_idx = *attrs_idx;
+                    let end = tile.cmds[cmd_idx..cmd_range.end]
+                        .iter()
+                        .position(|cmd| match cmd {
+                            Cmd::Fill(fill) => fill.attrs_idx != attrs_idx,
+                            Cmd::AlphaFill(alpha_fill) => alpha_fill.attrs_idx != attrs_idx,
+                            _ => true,
+                        })
+                        .map_or(cmd_range.end, |len| cmd_idx + len);
+                    self.do_blended_fill(
+                        state,
+                        renderer,
+                        encoded_paints,
+                        &tile.cmds[cmd_idx..end],
+                        paint_idxs,
+                        wide_tile_x,
+                        wide_tile_y,
+                        attrs,
+                    )?;
+                    cmd_idx = end;
+                    continue;
+                }
                 Cmd::Fill(fill) => {
                     self.do_fill(
                         state,
@@ -1319,6 +1390,15 @@ impl Scheduler {
                 Cmd::Blend(mode) => {
                     self.do_blend(state, wide_tile_x, wide_tile_y, mode);
                 }
+                Cmd::LuminanceToAlpha => {
+                    // The conversion happens while the next layer is blended into this one.
+                    state
+                        .tile_state
+                        .stack
+                        .last_mut()
+                        .unwrap()
+                        .luminance_to_alpha = true;
+                }
                 Cmd::Filter(_, _) => {}
                 Cmd::BatchEnd => {
                     return Ok(cmd_idx);
@@ -1329,6 +1409,13 @@ impl Scheduler {
             cmd_idx += 1;
         }
 
+        // The range of a nested backdrop layer can end inside of clip layers that are still
+        // open, which are composited without their clip.
+        while state.tile_state.stack.len() > stack_depth {
+            self.do_blend(state, wide_tile_x, wide_tile_y, &BlendMode::default());
+            self.do_pop_buf(state);
+        }
+
         if surface_is_blend_target {
             // Simple source-over compositing into the final render target.
             self.do_blend(state, wide_tile_x, wide_tile_y, &BlendMode::default());
@@ -1416,6 +1503,7 @@ impl Scheduler {
             temporary_slot,
             round: self.round,
             opacity: 1.,
+            luminance_to_alpha: false,
         });
 
         Ok(())
@@ -1505,6 +1593,7 @@ impl Scheduler {
                     opacity_u8,
                     mix_mode,
                     compose_mode,
+                    nos.luminance_to_alpha,
                     depth_index,
                 ),
                 None,
@@ -1512,6 +1601,7 @@ impl Scheduler {
             // Invalidate the temporary slot after use
             let nos_ptr = state.tile_state.stack.len() - 2;
             state.tile_state.stack[nos_ptr].temporary_slot.invalidate();
+            state.tile_state.stack[nos_ptr].luminance_to_alpha = false;
         } else {
             debug_assert_eq!(
                 *mode,
@@ -1536,6 +1626,123 @@ impl Scheduler {
         }
     }
 
+    /// Draw the fills of a path that uses a non-default blend mode.
+    ///
+    /// Like in `vello_cpu`, the coverage of the path interpolates between the backdrop and the
+    /// blended paint, so that destructive compose modes leave the backdrop outside of the path
+    /// untouched. To do so, the paint is drawn without coverage into a new slot, which is then
+    /// blended into the backdrop with the coverage of each fill, while the rest of the wide
+    /// tile is copied from the backdrop.
+    fn do_blended_fill<R: RendererBackend>(
+        &mut self,
+        state: &mut SchedulerState,
+        renderer: &mut R,
+        encoded_paints: &[EncodedPaint],
+        cmds: &[Cmd],
+        paint_idxs: &[u32],
+        wide_tile_x: u16,
+        wide_tile_y: u16,
+        attrs: &CommandAttrs,
+    ) -> Result<(), RenderError> {
+        let as_fill = |cmd: &Cmd| match cmd {
+            Cmd::Fill(fill) => fill.clone(),
+            Cmd::AlphaFill(alpha_fill) => CmdFill {
+                x: alpha_fill.x,
+                width: alpha_fill.width,
+                attrs_idx: alpha_fill.attrs_idx,
+            },
+            _ => unreachable!(),
+        };
+
+        self.do_push_buf(state, renderer, false)?;
+        for cmd in cmds {
+            let fill = as_fill(cmd);
+            let fill_attrs = &attrs.fill[fill.attrs_idx as usize];
+            let (scene_strip_x, scene_strip_y) = (wide_tile_x + fill.x, wide_tile_y);
+            let processed = Self::process_paint(
+                &fill_attrs.paint,
+                encoded_paints,
+                (scene_strip_x, scene_strip_y),
+                paint_idxs,
+            );
+            let depth_index = self.depth.next(false);
+            self.do_fill_with(
+                state,
+                &fill,
+                scene_strip_x,
+                scene_strip_y,
+                processed,
+                false,
+                depth_index,
+            );
+        }
+
+        let depth = state.tile_state.stack.len();
+        let tos = &state.tile_state.stack[depth - 1];
+        let nos = &state.tile_state.stack[depth - 2];
+        let TemporarySlot::Valid(temp_slot) = nos.temporary_slot else {
+            unreachable!("the buffer of a blended path must be marked as blend target");
+        };
+        let next_round = depth.is_multiple_of(2) && depth > 2;
+        let round = nos.round.max(tos.round + usize::from(next_round));
+        let (src_slot, dest_slot) = (tos.dest_slot, nos.dest_slot);
+
+        let copy_backdrop = |scheduler: &mut Self, x: u16, width: u16| {
+            let depth_index = scheduler.depth.next(false);
+            scheduler
+                .draw_mut(round, dest_slot.get_texture())
+                .push_alpha(
+                    GpuStripBuilder::at_slot(dest_slot.get_idx(), x, width).copy_from_slot(
+                        temp_slot.get_idx(),
+                        0xFF,
+                        depth_index,
+                    ),
+                    None,
+                );
+        };
+
+        let mut x = 0;
+        for cmd in cmds {
+            let fill = as_fill(cmd);
+            debug_assert!(fill.x >= x, "fills of a path must be sorted and disjoint");
+            if fill.x > x {
+                copy_backdrop(self, x, fill.x - x);
+            }
+
+            let fill_attrs = &attrs.fill[fill.attrs_idx as usize];
+            let mut gpu_strip_builder =
+                GpuStripBuilder::at_slot(dest_slot.get_idx(), fill.x, fill.width);
+            if let Cmd::AlphaFill(alpha_fill) = cmd {
+                let alpha_idx = fill_attrs.alpha_idx(alpha_fill.alpha_offset);
+                gpu_strip_builder =
+                    gpu_strip_builder.with_sparse(fill.width, alpha_idx / u32::from(Tile::HEIGHT));
+            }
+            let depth_index = self.depth.next(false);
+            self.draw_mut(round, dest_slot.get_texture()).push_alpha(
+                gpu_strip_builder.blend(
+                    src_slot.get_idx(),
+                    temp_slot.get_idx(),
+                    0xFF,
+                    fill_attrs.blend_mode.mix as u8,
+                    fill_attrs.blend_mode.compose as u8,
+                    false,
+                    depth_index,
+                ),
+                None,
+            );
+            x = fill.x + fill.width;
+        }
+        if x < WideTile::WIDTH {
+            copy_backdrop(self, x, WideTile::WIDTH - x);
+        }
+
+        let nos_ptr = depth - 2;
+        state.tile_state.stack[nos_ptr].temporary_slot.invalidate();
+        self.do_pop_buf(state);
+
+        Ok(())
+    }
+
     #[inline]
     fn do_alpha_fill(
         &mut self,
@@ -1681,6 +1888,7 @@ impl Scheduler {
                     }
                     Some(EncodedPaint::Gradient(g)) => !g.may_have_transparency,
                     Some(EncodedPaint::BlurredRoundedRect(_)) => false,
+                    Some(EncodedPaint::Custom(c)) => !c.may_have_transparency,
                     None => unreachable!("Paint must be in encoded paints"),
                 }
             }
@@ -1819,12 +2027,13 @@ impl Scheduler {
     ) -> ProcessedPaint {
         match paint {
             Paint::Solid(color) => {
-                let rgba = color.as_premul_rgba8().to_u32();
+                let (rgba, intensity) = pack_solid_color(color);
                 debug_assert!(
                     has_non_zero_alpha(rgba),
                     "Color fields with 0 alpha are reserved for clipping"
                 );
-                let paint_packed = (COLOR_SOURCE_PAYLOAD << 30) | (PAINT_TYPE_SOLID << 27);
+                let paint_packed =
+                    (COLOR_SOURCE_PAYLOAD << 30) | (PAINT_TYPE_SOLID << 27) | intensity;
                 ProcessedPaint {
                     payload: rgba,
                     paint: paint_packed,
@@ -1853,7 +2062,7 @@ impl Scheduler {
     ) -> ProcessedPaint {
         match encoded_paint {
             EncodedPaint::Image(encoded_image) => match &encoded_image.source {
-                ImageSource::OpaqueId { .. } => {
+                ImageSource::OpaqueId { .. } | ImageSource::CrossFade(_) => {
                     let paint_packed = (COLOR_SOURCE_PAYLOAD << 29)
                         | (PAINT_TYPE_IMAGE << 26)
                         | (paint_idx & 0x03FF_FFFF);
@@ -1905,6 +2114,17 @@ impl Scheduler {
                     external_texture_id: None,
                 }
             }
+            EncodedPaint::Custom(_) => {
+                let paint_packed = (COLOR_SOURCE_PAYLOAD << 29)
+                    | (PAINT_TYPE_CUSTOM << 26)
+                    | (paint_idx & 0x03FF_FFFF);
+                let scene_strip_xy = ((scene_strip_y as u32) << 16) | (scene_strip_x as u32);
+                ProcessedPaint {
+                    payload: scene_strip_xy,
+                    paint: paint_packed,
+                    external_texture_id: None,
+                }
+            }
         }
     }
 }
@@ -1984,6 +2204,7 @@ impl GpuStripBuilder {
         opacity: u8,
         mix_mode: u8,
         compose_mode: u8,
+        dest_luminance: bool,
         depth_index: u32,
     ) -> GpuStrip {
         GpuStrip {
@@ -1997,12 +2218,36 @@ impl GpuStripBuilder {
             paint_and_rect_flag: (COLOR_SOURCE_BLEND << 29)
                 | ((opacity as u32) << 16)
                 | ((mix_mode as u32) << 8)
-                | (compose_mode as u32),
+                | (compose_mode as u32)
+                | if dest_luminance {
+                    BLEND_DEST_LUMINANCE_FLAG
+                } else {
+                    0
+                },
             depth_index,
         }
     }
 }
 
+/// Pack a solid color into a premultiplied RGBA8 payload and the intensity paint bits.
+///
+/// Colors with components above 1.0, which only show up on HDR targets, are scaled down to fit
+/// into the payload. The intensity, in 8.8 fixed point, scales them back up in the shader. An
+/// intensity of 0 leaves the payload as it is.
+fn pack_solid_color(color: &PremulColor) -> (u32, u32) {
+    let mut premul = color.as_premul_f32();
+    let [r, g, b, a] = premul.components;
+    let max = r.max(g).max(b);
+    if max <= 1.0 {
+        return (color.as_premul_rgba8().to_u32(), 0);
+    }
+
+    let intensity = ((max * 256.0) as u32 + 1).min(0xFFFF);
+    let scale = 256.0 / intensity as f32;
+    premul.components = [r * scale, g * scale, b * scale, a];
+    (premul.to_rgba8().to_u32(), intensity)
+}
+
 #[inline(always)]
 fn has_non_zero_alpha(rgba: u32) -> bool {
     rgba >= 0x1_00_00_00
@@ -2320,6 +2565,7 @@ mod tests {
             x1,
             y1,
             paint: Paint::from(Color::from_rgba8(255, 0, 0, 255)),
+            access_tag: None,
         }
     }
 
@@ -2549,6 +2795,7 @@ mod tests {
             x1: 42.75,
             y1: 52.75,
             paint: Paint::Indexed(IndexedPaint::new(0)),
+            access_tag: None,
         };
         let encoded_paints = vec![vello_common::encode::EncodedPaint::Image(EncodedImage {
             source: ImageSource::opaque_id(ImageId::new(1)),
@@ -62,11 +62,10 @@ impl Resources {
                 let (width, height) = self.image_cache.atlas_manager().config().atlas_size;
                 (width as u16, height as u16)
             };
-            // TODO: Use a config optionally provided by the user!
             self.glyph_resources = Some(GlyphAtlasResources::with_config(
                 atlas_width,
                 atlas_height,
-                GlyphCacheConfig::default(),
+                self.glyph_cache_config.clone(),
             ));
         }
     }
@@ -238,6 +237,30 @@ impl<'a> HybridGlyphRunBackend<'a> {
         );
         render(&mut glyph_run, self.scene);
     }
+
+    /// Record the quads of the glyphs if an access tag is set, and draw them without recording
+    /// the primitives they are made of.
+    fn render_tagged_glyphs<Glyphs>(
+        self,
+        run: glifo::GlyphRun<'a>,
+        glyphs: Glyphs,
+        render: impl FnOnce(&mut glifo::GlyphRunRenderer<'a, 'a, Glyphs>, &mut Scene),
+    ) where
+        Glyphs: Iterator<Item = Glyph> + Clone,
+    {
+        let Some(tag) = self.scene.access_tag.take() else {
+            self.render_glyphs(run, glyphs, render);
+            return;
+        };
+
+        self.scene
+            .access_geometry
+            .add_glyph_quads(tag, run.glyph_quads(glyphs.clone()));
+        self.render_glyphs(run, glyphs, |glyph_run, scene| {
+            render(glyph_run, scene);
+            scene.access_tag = Some(tag);
+        });
+    }
 }
 
 impl<'a> GlyphRunBackend<'a> for HybridGlyphRunBackend<'a> {
@@ -250,14 +273,14 @@ impl<'a> GlyphRunBackend<'a> for HybridGlyphRunBackend<'a> {
     where
         Glyphs: Iterator<Item = Glyph> + Clone,
     {
-        self.render_glyphs(run, glyphs, |glyph_run, scene| glyph_run.fill_glyphs(scene));
+        self.render_tagged_glyphs(run, glyphs, |glyph_run, scene| glyph_run.fill_glyphs(scene));
     }
 
     fn stroke_glyphs<Glyphs>(self, run: glifo::GlyphRun<'a>, glyphs: Glyphs)
     where
         Glyphs: Iterator<Item = Glyph> + Clone,
     {
-        self.render_glyphs(run, glyphs, |glyph_run, scene| {
+        self.render_tagged_glyphs(run, glyphs, |glyph_run, scene| {
             let stroke_adjustment = glyph_run.stroke_adjustment();
             let original_width = scene.stroke().width;
             scene.stroke_mut().width *= stroke_adjustment;
@@ -0,0 +1,93 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// After you edit the crate's doc comment, run this command, then check README.md for any missing links
+// cargo rdme --workspace-project=vello_sparse
+
+//! A stable entry point to the sparse strips renderers, [Vello CPU][vello_cpu] and Vello Hybrid.
+//!
+//! The renderers and their shared foundation, `vello_common`, are still changing quickly,
+//! and their module layout moves along with their internals. This crate re-exports the
+//! subset of their API that is meant to be used by applications, in a layout that follows
+//! semantic versioning: items are only moved or removed here in breaking releases, even if
+//! they move in the underlying crates.
+//!
+//! # Contents
+//!
+//! - Scene building types that are shared by both renderers, at the crate root.
+//! - [`cpu`]: the CPU renderer, built around [`cpu::RenderContext`].
+//! - [`hybrid`]: the hybrid CPU/GPU renderer, built around [`hybrid::Scene`] and
+//!   [`hybrid::Renderer`].
+//! - [`cache`]: configuration of the caches the renderers keep across frames.
+//! - [`stats`]: usage statistics reported by the renderers.
+//!
+//! Anything that isn't reachable through this crate is considered internal, even if it's
+//! public in the underlying crates.
+//!
+//! # Features
+//!
+//! - `cpu` (enabled by default): Re-export the CPU renderer.
+//! - `multithreading`: Enable multi-threaded rendering in the CPU renderer. Implies `cpu`.
+//! - `hybrid`: Re-export the hybrid CPU/GPU renderer, using its default wgpu backend.
+//!
+//! At least one of `cpu` and `hybrid` has to be enabled.
+//!
+//! [vello_cpu]: https://crates.io/crates/vello_cpu
+// LINEBENDER LINT SET - lib.rs - v3
+// See https://linebender.org/wiki/canonical-lints/
+// These lints shouldn't apply to examples or tests.
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+// These lints shouldn't apply to examples.
+#![warn(clippy::print_stdout, clippy::print_stderr)]
+// Targeting e.g. 32-bit means structs containing usize can give false positives for 64-bit.
+#![cfg_attr(target_pointer_width = "64", warn(clippy::trivially_copy_pass_by_ref))]
+// END LINEBENDER LINT SET
+#![cfg_attr(docsrs, feature(doc_cfg))]
+#![forbid(unsafe_code)]
+#![no_std]
+
+#[cfg(all(not(feature = "cpu"), not(feature = "hybrid")))]
+compile_error!("vello_sparse must have at least one of the cpu or hybrid features enabled");
+
+pub use vello_common::alpha_mode::AlphaMode;
+pub use vello_common::custom_paint::{CustomPaint, CustomPaintId, CustomPaintKind};
+pub use vello_common::filter_effects::{Filter, FilterFunction};
+pub use vello_common::fragment::{Fragment, FragmentInstance};
+pub use vello_common::load_op::LoadOp;
+pub use vello_common::mask::{Mask, MaskKind};
+pub use vello_common::paint::{Image, ImageId, ImageSource, PaintType, Tint, TintMode};
+pub use vello_common::pattern::Pattern;
+pub use vello_common::pixmap::Pixmap;
+pub use vello_common::recording::Recording;
+pub use vello_common::{TextureId, color, kurbo, peniko};
+
+/// The CPU renderer.
+#[cfg(feature = "cpu")]
+pub mod cpu {
+    pub use vello_cpu::{Glyph, GlyphRunBuilder, Level, RenderContext, RenderMode};
+    pub use vello_cpu::{RenderSettings, Resources};
+}
+
+/// The hybrid CPU/GPU renderer.
+#[cfg(feature = "hybrid")]
+pub mod hybrid {
+    pub use vello_hybrid::{Config, RenderError, RenderSize, RenderTargetConfig, Renderer};
+    pub use vello_hybrid::{DynamicRange, OutputColorConfig, WorkingSpace};
+    pub use vello_hybrid::{GlyphRunBuilder, RenderSettings, Resources, Scene, SceneConstraints};
+    pub use vello_hybrid::{SampleRect, SurfaceTarget, TextureBindings, TexturePaint};
+}
+
+/// Configuration of the caches that the renderers keep across frames.
+pub mod cache {
+    pub use vello_common::encode::GradientLutCache;
+    pub use vello_common::multi_atlas::{AllocationStrategy, AtlasConfig, AtlasId};
+    #[cfg(feature = "cpu")]
+    pub use vello_cpu::GlyphCacheConfig;
+    #[cfg(all(feature = "hybrid", not(feature = "cpu")))]
+    pub use vello_hybrid::GlyphCacheConfig;
+}
+
+/// Usage statistics reported by the renderers.
+pub mod stats {
+    pub use vello_common::multi_atlas::AtlasUsageStats;
+}
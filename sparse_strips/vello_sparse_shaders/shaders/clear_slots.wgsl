@@ -69,4 +69,26 @@ fn vs_main_fullscreen(
 fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
     // Clear with transparent pixels
     return vec4<f32>(0.0, 0.0, 0.0, 0.0);
-} 
+}
+
+// This fragment shader is used for clearing a viewport of the render target. The clear color
+// is applied by the blend state, which scales this output by the blend constant.
+@fragment
+fn fs_main_blend_constant(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    return vec4<f32>(1.0, 1.0, 1.0, 1.0);
+}
+
+
+// The premultiplied colors that `fs_main_unpremultiply` converts to straight alpha.
+@group(1) @binding(0)
+var premultiplied: texture_2d<f32>;
+
+// This fragment shader copies `premultiplied` into the render target with straight alpha.
+@fragment
+fn fs_main_unpremultiply(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+    let color = textureLoad(premultiplied, vec2<u32>(position.xy), 0);
+    if color.a == 0.0 {
+        return vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    }
+    return vec4<f32>(color.rgb / color.a, color.a);
+}
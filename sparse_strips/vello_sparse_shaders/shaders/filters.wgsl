@@ -13,7 +13,7 @@
 
 // Keep these variables and layouts in sync with the ones in `filter.rs`!
 
-const FILTER_SIZE_BYTES: u32 = 48;
+const FILTER_SIZE_BYTES: u32 = 96;
 const FILTER_SIZE_U32: u32 = FILTER_SIZE_BYTES / 4;
 const TEXELS_PER_FILTER: u32 = FILTER_SIZE_U32 / 4u;
 
@@ -21,6 +21,7 @@ const FILTER_TYPE_OFFSET: u32 = 0u;
 const FILTER_TYPE_FLOOD: u32 = 1u;
 const FILTER_TYPE_GAUSSIAN_BLUR: u32 = 2u;
 const FILTER_TYPE_DROP_SHADOW: u32 = 3u;
+const FILTER_TYPE_COLOR_MATRIX: u32 = 4u;
 
 const PASS_COPY: u32 = 0u;
 const PASS_FLOOD: u32 = 1u;
@@ -30,6 +31,7 @@ const PASS_BLUR_H: u32 = 4u;
 const PASS_BLUR_V: u32 = 5u;
 const PASS_UPSCALE: u32 = 6u;
 const PASS_COMPOSITE_DROP_SHADOW: u32 = 7u;
+const PASS_COLOR_MATRIX: u32 = 8u;
 
 const MAX_TAPS_PER_SIDE: u32 = 3u;
 
@@ -93,6 +95,21 @@ fn get_drop_shadow_dy(texel2: vec4<u32>) -> f32 { return bitcast<f32>(texel2.y);
 /// Drop shadow color packed as RGBA8.
 fn get_drop_shadow_color(texel2: vec4<u32>) -> u32 { return texel2.z; }
 
+// Apply a color matrix filter to a premultiplied color. Texels 1 to 4 hold the columns of
+// the matrix, and texel 5 the offsets.
+fn apply_color_matrix(filter_offset: u32, color: vec4<f32>) -> vec4<f32> {
+    // The matrix operates on unpremultiplied colors.
+    let rgb = select(vec3<f32>(0.0), color.rgb / color.a, color.a > 0.0);
+    let out = bitcast<vec4<f32>>(load_filter_texel(filter_offset, 1u)) * rgb.r
+        + bitcast<vec4<f32>>(load_filter_texel(filter_offset, 2u)) * rgb.g
+        + bitcast<vec4<f32>>(load_filter_texel(filter_offset, 3u)) * rgb.b
+        + bitcast<vec4<f32>>(load_filter_texel(filter_offset, 4u)) * color.a
+        + bitcast<vec4<f32>>(load_filter_texel(filter_offset, 5u));
+    let clamped = clamp(out, vec4<f32>(0.0), vec4<f32>(1.0));
+
+    return vec4<f32>(clamped.rgb * clamped.a, clamped.a);
+}
+
 struct FilterInstanceData {
     @location(0) src_offset: vec2<u32>,
     @location(1) src_size: vec2<u32>,
@@ -388,6 +405,9 @@ fn fs_main(
             // Simple source-over compositing.
             return original + shadow_result * (1.0 - original.a);
         }
+        case PASS_COLOR_MATRIX: {
+            return apply_color_matrix(filter_offset, sample_input(src_offset, rel_coord));
+        }
         // Shouldn't be reached.
         default: {
             return vec4<f32>(0.0);
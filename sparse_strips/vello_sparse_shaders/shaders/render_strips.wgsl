@@ -30,6 +30,8 @@ const COLOR_SOURCE_PAYLOAD: u32 = 0u;
 // Sample from clip texture slot
 const COLOR_SOURCE_SLOT: u32 = 1u;
 const COLOR_SOURCE_BLEND: u32 = 2u;
+// Use the luminance of the destination as its alpha when blending two slots.
+const BLEND_DEST_LUMINANCE_FLAG: u32 = 0x01000000u;
 
 // Paint types
 const PAINT_TYPE_SOLID: u32 = 0u;  
@@ -38,6 +40,7 @@ const PAINT_TYPE_LINEAR_GRADIENT: u32 = 2u;
 const PAINT_TYPE_RADIAL_GRADIENT: u32 = 3u;
 const PAINT_TYPE_SWEEP_GRADIENT: u32 = 4u;
 const PAINT_TYPE_BLURRED_ROUNDED_RECT: u32 = 5u;
+const PAINT_TYPE_CUSTOM: u32 = 6u;
 
 // Paint texture index mask (extracts lower 26 bits from paint field).
 const PAINT_TEXTURE_INDEX_MASK: u32 = 0x03FFFFFFu;
@@ -129,8 +132,35 @@ struct Config {
     strip_offset_y: i32,
     // Whether to flip the y-component of the NDC coordinates.
     ndc_y_negate: u32,
+    // The steps of converting the colors written to the render target, as a combination of
+    // the `OUTPUT_TRANSFER_*` flags.
+    output_transfer: u32,
+    // The factor that linear colors are multiplied with for `OUTPUT_TRANSFER_SCALE`.
+    output_scale: f32,
+    // Whether the colors of paints are decoded from sRGB to linear before they are blended,
+    // so that blending happens in linear light.
+    decode_paints: u32,
+    _padding: u32,
 }
 
+// Colors are written to the render target unchanged.
+const OUTPUT_TRANSFER_NONE: u32 = 0u;
+// Colors are decoded from sRGB to linear.
+const OUTPUT_TRANSFER_DECODE_SRGB: u32 = 1u;
+// Linear colors are tone mapped into [0, 1].
+const OUTPUT_TRANSFER_TONE_MAP: u32 = 2u;
+// Linear colors are converted from BT.709 to BT.2020 primaries.
+const OUTPUT_TRANSFER_BT2020: u32 = 4u;
+// Linear colors are multiplied with `config.output_scale`.
+const OUTPUT_TRANSFER_SCALE: u32 = 8u;
+// Linear colors are encoded with the PQ transfer function.
+const OUTPUT_TRANSFER_ENCODE_PQ: u32 = 16u;
+// Linear colors are encoded to sRGB.
+const OUTPUT_TRANSFER_ENCODE_SRGB: u32 = 32u;
+// Only the coverage (alpha) is written, to all channels. Paints drawn directly to the
+// render target aren't evaluated.
+const OUTPUT_TRANSFER_COVERAGE: u32 = 64u;
+
 // A `StripInstance` can represent either a **normal strip** (representing a sparse fill or alpha fill of height
 // Tile::HEIGHT) or a **rect strip** (an entire rectangle rendered as a single quad, with anti-aliasing support).
 // The two modes are distinguished by RECT_STRIP_FLAG (bit 31 of `paint_and_rect_flag`).
@@ -152,9 +182,9 @@ struct Config {
 //   - Bits 0-28:  Usage depends on color_source:
 //
 //     When color_source = 0 (COLOR_SOURCE_PAYLOAD):
-//       - Bits 26-28: `paint_type` (0 = solid, 1 = image, 2 = linear_gradient, 3 = radial_gradient, 4 = sweep_gradient)
+//       - Bits 26-28: `paint_type` (0 = solid, 1 = image, 2 = linear_gradient, 3 = radial_gradient, 4 = sweep_gradient, 5 = blurred_rounded_rect, 6 = custom)
 //       - Bits 0-25:
-//         - If paint_type = 0: unused
+//         - If paint_type = 0: bits 0-15 are the `intensity` of extended-range colors
 //         - If paint_type >= 1: `paint_texture_idx`
 //
 //     When color_source = 1 (COLOR_SOURCE_SLOT):
@@ -162,7 +192,8 @@ struct Config {
 //       - Bits 8-28: unused
 //
 //     When color_source = 2 (COLOR_SOURCE_BLEND):
-//       - Bits 16-28: `dest_slot` (14 bits)
+//       - Bit 24: `BLEND_DEST_LUMINANCE_FLAG`
+//       - Bits 16-23: opacity (0-255)
 //       - Bits 8-15: `mix_mode` (8 bits)
 //       - Bits 0-7: `compose_mode` (8 bits)
 //
@@ -170,7 +201,8 @@ struct Config {
 //
 // color_source = 0 (COLOR_SOURCE_PAYLOAD) - Use payload data directly
 // ├── paint_type = 0 (PAINT_TYPE_SOLID) - Solid color rendering
-// │   └── payload = [r, g, b, a] RGBA (packed as u8s)
+// │   ├── payload = [r, g, b, a] RGBA (packed as u8s)
+// │   └── bits 0-15 = intensity that r, g and b are multiplied with (8.8 fixed point, 0 = 1.0)
 // │
 // ├── paint_type = 1 (PAINT_TYPE_IMAGE) - Image rendering
 // │   └── payload = packed image parameters
@@ -180,7 +212,10 @@ struct Config {
 // ├── paint_type = 4 (PAINT_TYPE_SWEEP_GRADIENT) - Sweep gradient rendering
 //     ├── payload = [x, y] scene coordinates (packed as u16s)
 //     └── bits 0-25 = paint_texture_idx
-// └── paint_type = 5 (PAINT_TYPE_BLURRED_ROUNDED_RECT) - Analytic blurred rounded rectangle
+// ├── paint_type = 5 (PAINT_TYPE_BLURRED_ROUNDED_RECT) - Analytic blurred rounded rectangle
+// │   ├── payload = [x, y] scene coordinates (packed as u16s)
+// │   └── bits 0-25 = paint_texture_idx
+// └── paint_type = 6 (PAINT_TYPE_CUSTOM) - Custom paint, see `custom_paint`
 //     ├── payload = [x, y] scene coordinates (packed as u16s)
 //     └── bits 0-25 = paint_texture_idx
 //
@@ -188,11 +223,12 @@ struct Config {
 // ├── payload = slot_index (u32)
 // └── bits 0-7 = opacity (0-255, where 255 = fully opaque)
 //
-// color_source = 2 (COLOR_SOURCE_BLEND) - Blend two slots
+// color_source = 2 (COLOR_SOURCE_BLEND) - Blend two slots, weighted by the strip's coverage
 // ├── payload = [src_slot, dest_slot] slot indices (packed as u16s)
 // │   ├── bits 0-15 = src_slot (source slot to blend)
 // │   └── bits 16-31 = dest_slot (destination slot to blend with)
-// └── paint bits 0-23:
+// └── paint bits 0-24:
+//     ├── bit 24 = use the luminance of the destination as its alpha (for luminance masks)
 //     ├── bits 16-23 = opacity (0-255, applied to blend result)
 //     ├── bits 8-15 = mix_mode (blend mixing mode)
 //     └── bits 0-7 = compose_mode (compositing operation)
@@ -253,10 +289,10 @@ var atlas_texture_array: texture_2d_array<f32>;
 var external_texture: texture_2d<f32>;
 
 @group(2) @binding(0)
-var encoded_paints_texture: texture_2d<u32>;
+var encoded_paints_texture: texture_2d_array<u32>;
 
 @group(3) @binding(0)
-var gradient_texture: texture_2d<f32>;
+var gradient_texture: texture_2d_array<f32>;
 
 @vertex
 fn vs_main(
@@ -311,7 +347,7 @@ fn vs_main(
             out.sample_xy = get_image_translate(image_texel1, image_texel2)
                 + get_image_offset(image_texel0)
                 + get_image_transform(image_texel0, image_texel1) * pos;
-        } else if paint_type == PAINT_TYPE_LINEAR_GRADIENT || paint_type == PAINT_TYPE_RADIAL_GRADIENT || paint_type == PAINT_TYPE_SWEEP_GRADIENT || paint_type == PAINT_TYPE_BLURRED_ROUNDED_RECT {
+        } else if paint_type == PAINT_TYPE_LINEAR_GRADIENT || paint_type == PAINT_TYPE_RADIAL_GRADIENT || paint_type == PAINT_TYPE_SWEEP_GRADIENT || paint_type == PAINT_TYPE_BLURRED_ROUNDED_RECT || paint_type == PAINT_TYPE_CUSTOM {
             // Use view coordinates for gradient transform (always in global view space)
             out.sample_xy = vec2<f32>(
                 f32(scene_strip_x) + x * f32(width),
@@ -336,7 +372,7 @@ fn vs_main(
 }
 
 @group(0) @binding(0)
-var alphas_texture: texture_2d<u32>;
+var alphas_texture: texture_2d_array<u32>;
 
 @group(0) @binding(2)
 var clip_input_texture: texture_2d<f32>;
@@ -384,12 +420,15 @@ fn fs_main(
         let texel_index = alphas_index / 4u;
         // Which channel (R,G,B,A) in the texel contains the alpha values for this column
         let channel_index = alphas_index % 4u;
-        // Calculate texel coordinates
+        // Calculate texel coordinates. Rows that don't fit into one layer continue in the
+        // next layer.
         let tex_x = texel_index & (alphas_tex_width - 1u);
-        let tex_y = texel_index >> config.alphas_tex_width_bits;
+        let tex_row = texel_index >> config.alphas_tex_width_bits;
+        let tex_y = tex_row % tex_dimensions.y;
+        let tex_layer = tex_row / tex_dimensions.y;
 
         // Load all 4 channels from the texture
-        let rgba_values = textureLoad(alphas_texture, vec2<u32>(tex_x, tex_y), 0);
+        let rgba_values = textureLoad(alphas_texture, vec2<u32>(tex_x, tex_y), tex_layer, 0);
 
         // Get the column's alphas from the appropriate RGBA channel based on the index
         let alphas_u32 = unpack_alphas_from_channel(rgba_values, channel_index);
@@ -400,12 +439,15 @@ fn fs_main(
     let color_source = (paint_and_rect_flag >> 29u) & 0x3u;
     var final_color: vec4<f32>;
 
-    if color_source == COLOR_SOURCE_PAYLOAD {
+    if color_source == COLOR_SOURCE_PAYLOAD && (config.output_transfer & OUTPUT_TRANSFER_COVERAGE) != 0u {
+        // Only the coverage is written, so the paint doesn't matter.
+        final_color = vec4(alpha);
+    } else if color_source == COLOR_SOURCE_PAYLOAD {
         let paint_type = (paint_and_rect_flag >> 26u) & 0x7u;
 
         // in.payload encodes a color for PAINT_TYPE_SOLID or sample_xy for PAINT_TYPE_IMAGE
         if paint_type == PAINT_TYPE_SOLID {
-            final_color = alpha * unpack4x8unorm(payload);
+            final_color = alpha * unpack_solid_color(payload, paint_and_rect_flag);
         } else if paint_type == PAINT_TYPE_IMAGE {
             let paint_tex_idx = paint_and_rect_flag & PAINT_TEXTURE_INDEX_MASK;
             let image_texel0 = load_encoded_paint_texel(paint_tex_idx, 0u);
@@ -446,43 +488,61 @@ fn fs_main(
                     image_offset,
                     image_size,
                 );
-            } else if image_quality == IMAGE_QUALITY_HIGH {
-                let final_xy = image_offset + extended_xy;
-                sample_color = bicubic_sample(
-                    atlas_texture_array,
-                    final_xy,
-                    i32(image_atlas_index),
-                    image_offset,
-                    image_size,
-                    image_extend_modes,
-                    image_padding,
-                );
-            } else if image_quality == IMAGE_QUALITY_MEDIUM {
-                let final_xy = image_offset + extended_xy - vec2(0.5);
-                sample_color = bilinear_sample(
-                    atlas_texture_array,
-                    final_xy,
+            } else {
+                sample_color = sample_atlas_image(
+                    image_quality,
+                    extended_xy,
                     i32(image_atlas_index),
                     image_offset,
                     image_size,
                     image_extend_modes,
                     image_padding,
                 );
-            } else {
-                let final_xy = image_offset + extended_xy;
-                sample_color = textureLoad(
-                    atlas_texture_array,
-                    vec2<u32>(final_xy),
-                    i32(image_atlas_index),
-                    0,
-                );
+
+                // Trilinear filtering: blend in the next smaller mip level, if any.
+                let image_texel3 = load_encoded_paint_texel(paint_tex_idx, 3u);
+                let mip_blend = get_mip_blend(image_texel3);
+                if mip_blend > 0.0 {
+                    let mip_size = get_mip_size(image_texel3);
+                    let mip_color = sample_atlas_image(
+                        image_quality,
+                        extended_xy * (mip_size / image_size),
+                        i32(get_mip_atlas_index(image_texel3)),
+                        get_mip_offset(image_texel3),
+                        mip_size,
+                        image_extend_modes,
+                        image_padding,
+                    );
+                    sample_color = mix(sample_color, mip_color, mip_blend);
+                }
+
+                // Cross-fade: blend in the second image, stretched to the size of the first.
+                let image_texel4 = load_encoded_paint_texel(paint_tex_idx, 4u);
+                let fade_t = get_fade_t(image_texel4);
+                if fade_t > 0.0 {
+                    let fade_size = get_fade_size(image_texel4);
+                    let fade_color = sample_atlas_image(
+                        image_quality,
+                        extended_xy * (fade_size / image_size),
+                        i32(get_fade_atlas_index(image_texel4)),
+                        get_fade_offset(image_texel4),
+                        fade_size,
+                        image_extend_modes,
+                        image_padding,
+                    );
+                    sample_color = mix(sample_color, fade_color, fade_t);
+                }
             }
 
-            final_color = alpha * select(
+            var tinted_color = select(
                 image_tint * sample_color.a,
                 sample_color * image_tint,
                 is_multiply
             );
+            if has_tint && image_texel2.z == TINT_MODE_INVERSE_COVERAGE {
+                tinted_color = vec4<f32>(1.0 - image_tint.a * sample_color.rgb, 1.0);
+            }
+            final_color = alpha * tinted_color;
         } else if paint_type == PAINT_TYPE_LINEAR_GRADIENT {
             let paint_tex_idx = paint_and_rect_flag & PAINT_TEXTURE_INDEX_MASK;
             let gradient_texel0 = load_encoded_paint_texel(paint_tex_idx, 0u);
@@ -573,6 +633,22 @@ fn fs_main(
                 blurred_texel3,
                 blurred_texel4,
             );
+        } else if paint_type == PAINT_TYPE_CUSTOM {
+            let paint_tex_idx = paint_and_rect_flag & PAINT_TEXTURE_INDEX_MASK;
+            let custom_texel0 = load_encoded_paint_texel(paint_tex_idx, 0u);
+            let custom_texel1 = load_encoded_paint_texel(paint_tex_idx, 1u);
+            let transform = mat2x2<f32>(
+                bitcast<f32>(custom_texel0.z),
+                bitcast<f32>(custom_texel0.w),
+                bitcast<f32>(custom_texel1.x),
+                bitcast<f32>(custom_texel1.y),
+            );
+            let translate = vec2<f32>(bitcast<f32>(custom_texel1.z), bitcast<f32>(custom_texel1.w));
+            let pos = transform * sample_xy + translate;
+            final_color = alpha * custom_paint(custom_texel0.x, pos, paint_tex_idx + 2u);
+        }
+        if config.decode_paints != 0u {
+            final_color = decode_srgb(final_color);
         }
     } else if color_source == COLOR_SOURCE_SLOT {
         // Depending on the value of `ndc_y_negate`, the y position will have a value that either
@@ -614,11 +690,97 @@ fn fs_main(
 
         // Read destination color from slot
         let dest_y = clip_y_in_strip + dest_slot * config.strip_height;
-        let dest_color = textureLoad(clip_input_texture, vec2(clip_x, dest_y), 0);
+        var dest_color = textureLoad(clip_input_texture, vec2(clip_x, dest_y), 0);
+        if (paint_and_rect_flag & BLEND_DEST_LUMINANCE_FLAG) != 0u {
+            let luminance = dot(dest_color.rgb, vec3(0.2126, 0.7152, 0.0722));
+            dest_color = vec4(0.0, 0.0, 0.0, luminance);
+        }
+
+        // The coverage of a blended path interpolates between the destination and the
+        // blended result, so that the backdrop outside of the path stays untouched even
+        // for destructive compose modes.
+        let blended = blend_mix_compose(dest_color, src_color * opacity, compose_mode, mix_mode);
+        final_color = mix(dest_color, blended, alpha);
+    }
+    return apply_output_transfer(final_color);
+}
+
+// Unpack the premultiplied color of a solid paint. The color components of extended-range
+// colors are scaled down to fit into the payload, and scaled back up with the intensity.
+fn unpack_solid_color(payload: u32, paint: u32) -> vec4<f32> {
+    let color = unpack4x8unorm(payload);
+    let intensity = paint & 0xFFFFu;
+    if intensity == 0u {
+        return color;
+    }
+    return vec4(color.rgb * (f32(intensity) / 256.0), color.a);
+}
 
-        final_color = blend_mix_compose(dest_color, src_color * opacity * alpha, compose_mode, mix_mode);
+// Decode a premultiplied color from sRGB to linear.
+fn decode_srgb(color: vec4<f32>) -> vec4<f32> {
+    if color.a == 0.0 {
+        return color;
     }
-    return final_color;
+    let rgb = max(color.rgb / color.a, vec3(0.0));
+    let linear = select(
+        pow((rgb + 0.055) / 1.055, vec3(2.4)),
+        rgb / 12.92,
+        rgb <= vec3(0.04045)
+    );
+    return vec4(linear * color.a, color.a);
+}
+
+// Convert a premultiplied color from the working space to what the render target expects.
+// This matches `OutputTransfer::apply` in `vello_hybrid`.
+fn apply_output_transfer(color: vec4<f32>) -> vec4<f32> {
+    let flags = config.output_transfer;
+    if flags == OUTPUT_TRANSFER_NONE || color.a == 0.0 {
+        return color;
+    }
+    if (flags & OUTPUT_TRANSFER_COVERAGE) != 0u {
+        return vec4(color.a);
+    }
+    var rgb = max(color.rgb / color.a, vec3(0.0));
+    if (flags & OUTPUT_TRANSFER_DECODE_SRGB) != 0u {
+        rgb = select(
+            pow((rgb + 0.055) / 1.055, vec3(2.4)),
+            rgb / 12.92,
+            rgb <= vec3(0.04045)
+        );
+    }
+    if (flags & OUTPUT_TRANSFER_TONE_MAP) != 0u {
+        // Compress the components above the knee so that they approach 1, keeping the hue.
+        let knee = 0.8;
+        let max_c = max(rgb.r, max(rgb.g, rgb.b));
+        if max_c > knee {
+            let excess = max_c - knee;
+            let mapped = knee + (1.0 - knee) * excess / (excess + 1.0 - knee);
+            rgb *= mapped / max_c;
+        }
+    }
+    if (flags & OUTPUT_TRANSFER_BT2020) != 0u {
+        rgb = vec3(
+            dot(rgb, vec3(0.627404, 0.329283, 0.043313)),
+            dot(rgb, vec3(0.069097, 0.919540, 0.011362)),
+            dot(rgb, vec3(0.016391, 0.088013, 0.895595))
+        );
+    }
+    if (flags & OUTPUT_TRANSFER_SCALE) != 0u {
+        rgb *= config.output_scale;
+    }
+    if (flags & OUTPUT_TRANSFER_ENCODE_PQ) != 0u {
+        // SMPTE ST 2084, where 1 corresponds to 10000 nits.
+        let p = pow(clamp(rgb, vec3(0.0), vec3(1.0)), vec3(0.15930176));
+        rgb = pow((0.8359375 + 18.851563 * p) / (1.0 + 18.6875 * p), vec3(78.84375));
+    }
+    if (flags & OUTPUT_TRANSFER_ENCODE_SRGB) != 0u {
+        rgb = select(
+            1.055 * pow(rgb, vec3(1.0 / 2.4)) - 0.055,
+            rgb * 12.92,
+            rgb <= vec3(0.0031308)
+        );
+    }
+    return vec4(rgb * color.a, color.a);
 }
 
 // Apply color mixing and composition. Both input and output colors are premultiplied RGB.
@@ -898,21 +1060,23 @@ fn blend_mix(cb: vec3<f32>, cs: vec3<f32>, mode: u32) -> vec3<f32> {
 /// Tint mode constants.
 const TINT_MODE_ALPHA_MASK: u32 = 0u;
 const TINT_MODE_MULTIPLY: u32 = 1u;
-
-// Convert a flat texel index to 2D texture coordinates for the encoded paints texture.
-fn encoded_paint_coord(flat_idx: u32) -> vec2<u32> {
-    return vec2<u32>(
+const TINT_MODE_INVERSE_COVERAGE: u32 = 2u;
+
+// Convert a flat texel index to texture coordinates and a layer for the encoded paints texture.
+// Rows that don't fit into one layer continue in the next layer.
+fn encoded_paint_coord(flat_idx: u32) -> vec3<u32> {
+    let height = textureDimensions(encoded_paints_texture).y;
+    let row = flat_idx >> config.encoded_paints_tex_width_bits;
+    return vec3<u32>(
         flat_idx & ((1u << config.encoded_paints_tex_width_bits) - 1u),
-        flat_idx >> config.encoded_paints_tex_width_bits
+        row % height,
+        row / height
     );
 }
 
 fn load_encoded_paint_texel(paint_tex_idx: u32, texel_offset: u32) -> vec4<u32> {
-    return textureLoad(
-        encoded_paints_texture,
-        encoded_paint_coord(paint_tex_idx + texel_offset),
-        0,
-    );
+    let coord = encoded_paint_coord(paint_tex_idx + texel_offset);
+    return textureLoad(encoded_paints_texture, coord.xy, coord.z, 0);
 }
 
 // Encoded image layout. Must match `GpuEncodedImage` in `vello_hybrid/src/render/common.rs`.
@@ -930,6 +1094,14 @@ fn load_encoded_paint_texel(paint_tex_idx: u32, texel_offset: u32) -> vec4<u32>
 // texel2.y: premultiplied tint color packed as RGBA8 unorm; 0 means no tint
 // texel2.z: tint mode, only meaningful when texel2.y != 0
 // texel2.w: transparent padding pixels around the image in the atlas
+// texel3.x: atlas index of the next smaller mip level
+// texel3.y: size of the next smaller mip level, packed as [width:16, height:16]
+// texel3.z: offset of the next smaller mip level, packed as [x:16, y:16]
+// texel3.w: weight with which the next smaller mip level is blended in; 0 disables it
+// texel4.x: atlas index of the image that is cross-faded to
+// texel4.y: size of the image that is cross-faded to, packed as [width:16, height:16]
+// texel4.z: offset of the image that is cross-faded to, packed as [x:16, y:16]
+// texel4.w: weight with which the image that is cross-faded to is blended in; 0 disables it
 
 /// The rendering quality of the image.
 fn get_image_quality(texel0: vec4<u32>) -> u32 { return texel0.x & 0x3u; }
@@ -971,6 +1143,38 @@ fn get_image_translate(texel1: vec4<u32>, texel2: vec4<u32>) -> vec2<f32> {
 /// Number of transparent padding pixels around the image in the atlas.
 fn get_image_padding(texel2: vec4<u32>) -> f32 { return f32(texel2.w); }
 
+/// The atlas index containing the next smaller mip level.
+fn get_mip_atlas_index(texel3: vec4<u32>) -> u32 { return texel3.x; }
+
+/// The size of the next smaller mip level in pixels.
+fn get_mip_size(texel3: vec4<u32>) -> vec2<f32> {
+    return vec2<f32>(f32(texel3.y >> 16u), f32(texel3.y & 0xFFFFu));
+}
+
+/// The offset of the next smaller mip level in pixels.
+fn get_mip_offset(texel3: vec4<u32>) -> vec2<f32> {
+    return vec2<f32>(f32(texel3.z >> 16u), f32(texel3.z & 0xFFFFu));
+}
+
+/// The weight with which the next smaller mip level is blended in.
+fn get_mip_blend(texel3: vec4<u32>) -> f32 { return bitcast<f32>(texel3.w); }
+
+/// The atlas index containing the image that is cross-faded to.
+fn get_fade_atlas_index(texel4: vec4<u32>) -> u32 { return texel4.x; }
+
+/// The size of the image that is cross-faded to in pixels.
+fn get_fade_size(texel4: vec4<u32>) -> vec2<f32> {
+    return vec2<f32>(f32(texel4.y >> 16u), f32(texel4.y & 0xFFFFu));
+}
+
+/// The offset of the image that is cross-faded to in pixels.
+fn get_fade_offset(texel4: vec4<u32>) -> vec2<f32> {
+    return vec2<f32>(f32(texel4.z >> 16u), f32(texel4.z & 0xFFFFu));
+}
+
+/// The weight with which the image that is cross-faded to is blended in.
+fn get_fade_t(texel4: vec4<u32>) -> f32 { return bitcast<f32>(texel4.w); }
+
 fn unpack_alphas_from_channel(rgba: vec4<u32>, channel_index: u32) -> u32 {
     switch channel_index {
         case 0u: { return rgba.x; }
@@ -1013,6 +1217,49 @@ fn extend_mode_normalized(t: f32, mode: u32) -> f32 {
     }
 }
 
+// Sample an image from the atlas texture array using the filter selected by `quality`.
+//
+// `extended_xy` is relative to the image offset and has already been mapped into the
+// image by the extend modes.
+fn sample_atlas_image(
+    quality: u32,
+    extended_xy: vec2<f32>,
+    atlas_idx: i32,
+    image_offset: vec2<f32>,
+    image_size: vec2<f32>,
+    extend_modes: vec2<u32>,
+    image_padding: f32,
+) -> vec4<f32> {
+    if quality == IMAGE_QUALITY_HIGH {
+        return bicubic_sample(
+            atlas_texture_array,
+            image_offset + extended_xy,
+            atlas_idx,
+            image_offset,
+            image_size,
+            extend_modes,
+            image_padding,
+        );
+    }
+    if quality == IMAGE_QUALITY_MEDIUM {
+        return bilinear_sample(
+            atlas_texture_array,
+            image_offset + extended_xy - vec2(0.5),
+            atlas_idx,
+            image_offset,
+            image_size,
+            extend_modes,
+            image_padding,
+        );
+    }
+    return textureLoad(
+        atlas_texture_array,
+        vec2<u32>(image_offset + extended_xy),
+        atlas_idx,
+        0,
+    );
+}
+
 // Bilinear filtering
 //
 // Bilinear filtering consists of sampling the 4 surrounding pixels of the target point and
@@ -1247,12 +1494,15 @@ fn sample_gradient_lut(t_value: f32, extend_mode: u32, gradient_start: u32, text
     let t_offset = u32(clamped_t * f32(texture_width - 1u));
     // Calculate absolute position in flat gradient texture
     let flat_coord = gradient_start + t_offset;
-    // Convert flat coordinate to 2D texture coordinate
-    let gradient_tex_width = textureDimensions(gradient_texture).x;
-    let tex_x = flat_coord % gradient_tex_width;
-    let tex_y = flat_coord / gradient_tex_width;
+    // Convert flat coordinate to texture coordinates and a layer. Rows that don't fit into one
+    // layer continue in the next layer.
+    let gradient_tex_dimensions = textureDimensions(gradient_texture);
+    let tex_x = flat_coord % gradient_tex_dimensions.x;
+    let tex_row = flat_coord / gradient_tex_dimensions.x;
+    let tex_y = tex_row % gradient_tex_dimensions.y;
+    let tex_layer = tex_row / gradient_tex_dimensions.y;
     // Sample from the gradient texture at calculated position
-    let gradient_color = textureLoad(gradient_texture, vec2<u32>(tex_x, tex_y), 0);
+    let gradient_color = textureLoad(gradient_texture, vec2<u32>(tex_x, tex_y), tex_layer, 0);
     return gradient_color;
 }
 
@@ -1504,3 +1754,18 @@ fn calculate_blurred_rounded_rect(
 
     return color * blur_alpha;
 }
+
+// Encoded custom paint layout. Must match `GpuCustomPaint` in `vello_hybrid/src/render/common.rs`.
+// The parameters of a custom paint start at `params`, four per texel.
+fn custom_paint_param(params: u32, i: u32) -> f32 {
+    let texel = load_encoded_paint_texel(params, i >> 2u);
+    return bitcast<f32>(texel[i & 3u]);
+}
+
+// The functions between the markers are replaced with the registered custom paint kinds when
+// the pipelines are built, see `compose_custom_paints` in `vello_hybrid/src/render/common.rs`.
+// CUSTOM_PAINTS_START
+fn custom_paint(kind: u32, pos: vec2<f32>, params: u32) -> vec4<f32> {
+    return vec4<f32>(0.0);
+}
+// CUSTOM_PAINTS_END
@@ -12,4 +12,10 @@ mod shader_info;
 #[cfg(feature = "glsl")]
 mod types;
 
+/// The directory that contains the WGSL sources of the shaders.
+///
+/// This is where shaders are read from at build time, which is useful for tools that
+/// reload them at runtime.
+pub const SHADER_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/shaders");
+
 include!(concat!(env!("OUT_DIR"), "/compiled_shaders.rs"));
@@ -8,12 +8,18 @@ use crate::util::{
     circular_star, crossed_line_star, layout_glyphs_roboto, miter_stroke_2, stops_green_blue,
 };
 use std::f64::consts::PI;
+use vello_common::chart::Hairlines;
 use vello_common::coarse::Cmd;
 use vello_common::color::palette::css::{
     BEIGE, BLUE, DARK_BLUE, GREEN, LIME, MAROON, REBECCA_PURPLE, RED, TRANSPARENT,
 };
-use vello_common::kurbo::{Affine, BezPath, Circle, Join, Point, Rect, Shape, Stroke};
+use vello_common::compact_path::{CompactPaths, Quantization};
+use vello_common::dash::MeasuredPath;
+use vello_common::kurbo::{Affine, BezPath, Cap, Circle, Join, Line, Point, Rect, Shape, Stroke};
+use vello_common::load_op::LoadOp;
 use vello_common::peniko::{Fill, Gradient};
+use vello_common::simplify::LodPath;
+use vello_common::variable_width::VariableWidthStroke;
 use vello_cpu::color::palette::css::BLACK;
 use vello_cpu::peniko::LinearGradientPosition;
 use vello_cpu::{Glyph, Level, Pixmap, RenderContext, RenderMode, RenderSettings};
@@ -402,6 +408,21 @@ fn stroked_transformed_rect_4(ctx: &mut impl Renderer) {
     ctx.stroke_rect(&rect);
 }
 
+#[vello_test(width = 100, height = 100)]
+fn stroked_skewed_curve(ctx: &mut impl Renderer) {
+    let mut path = BezPath::new();
+    path.move_to((-4.0, -4.0));
+    path.curve_to((4.0, -4.0), (-4.0, 4.0), (4.0, 4.0));
+    let stroke = Stroke::new(1.5).with_caps(Cap::Round);
+
+    // The diagonal of this transform is small, while the curve is stretched a lot by the
+    // off-diagonal coefficients.
+    ctx.set_transform(Affine::new([0.5, 10.0, -5.0, 0.25, 50.0, 50.0]));
+    ctx.set_paint(REBECCA_PURPLE);
+    ctx.set_stroke(stroke);
+    ctx.stroke_path(&path);
+}
+
 #[vello_test(width = 30, height = 20)]
 fn strip_inscribed_rect(ctx: &mut impl Renderer) {
     let rect = Rect::new(1.5, 9.5, 28.5, 11.5);
@@ -467,6 +488,91 @@ fn no_anti_aliasing_clip_path(ctx: &mut impl Renderer) {
     ctx.pop_layer();
 }
 
+#[vello_test(width = 40, height = 40)]
+fn pixel_snapping_rects(ctx: &mut impl Renderer) {
+    ctx.set_pixel_snapping(true);
+    ctx.set_paint(REBECCA_PURPLE);
+    for i in 0..6 {
+        // Dividers with fractional offsets still cover whole pixel rows.
+        let y = 4.3 + f64::from(i) * 5.45;
+        ctx.fill_rect(&Rect::new(2.4, y, 37.7, y + 1.0));
+    }
+}
+
+#[vello_test(width = 40, height = 40)]
+fn pixel_snapping_stroke(ctx: &mut impl Renderer) {
+    ctx.set_pixel_snapping(true);
+    ctx.set_transform(Affine::scale(1.3));
+    ctx.set_stroke(Stroke::new(1.0));
+    ctx.set_paint(DARK_BLUE);
+    ctx.stroke_path(&Rect::new(3.2, 3.7, 26.6, 26.1).to_path(0.1));
+    ctx.stroke_path(&Circle::new((15.0, 15.0), 8.0).to_path(0.1));
+}
+
+#[vello_test(width = 60, height = 50)]
+fn chart_hairlines(ctx: &mut impl Renderer) {
+    // Data coordinates span 0..10 on both axes, with the y axis pointing upwards.
+    let transform = Affine::new([4.7, 0.0, 0.0, -3.9, 8.3, 44.6]);
+    let ticks = || (0..=10).map(f64::from);
+    let mut lines = Hairlines::new();
+    lines.push_grid(
+        transform,
+        Rect::new(0.0, 0.0, 10.0, 10.0),
+        ticks(),
+        ticks(),
+        1.0,
+    );
+    ctx.set_paint(BEIGE);
+    ctx.fill_hairlines(&lines);
+
+    lines.clear();
+    lines.push_horizontal(transform, 0.0, 0.0, 10.0, 2.0);
+    lines.push_vertical(transform, 0.0, 0.0, 10.0, 2.0);
+    lines.push_x_ticks(transform, 0.0, ticks(), 3.0, 1.0);
+    lines.push_y_ticks(transform, 0.0, ticks(), 3.0, 1.0);
+    ctx.set_paint(DARK_BLUE);
+    ctx.fill_hairlines(&lines);
+}
+
+#[vello_test]
+fn compact_paths(ctx: &mut impl Renderer) {
+    // A coarse grid, so that the quantization is visible.
+    let mut paths = CompactPaths::new(Quantization::new(Point::new(50.0, 50.0), 0.5));
+    let star = paths
+        .push(circular_star(Point::new(50.0, 50.0), 13, 25.0, 45.0))
+        .unwrap();
+    let circle = paths
+        .push(Circle::new((50.0, 50.0), 15.3).path_elements(0.1))
+        .unwrap();
+
+    ctx.set_paint(REBECCA_PURPLE);
+    ctx.fill_compact_path(paths.get(star));
+    ctx.set_paint(LIME);
+    ctx.fill_compact_path(paths.get(circle));
+}
+
+#[vello_test]
+fn lod_path_zoomed_out(ctx: &mut impl Renderer) {
+    // A star with finely serrated edges, which disappear at a small scale.
+    let mut path = BezPath::new();
+    for i in 0..2000 {
+        let angle = f64::from(i) / 2000.0 * 2.0 * PI;
+        let radius = 400.0 + 150.0 * (5.0 * angle).cos() + 2.0 * f64::from(i % 2);
+        let point = Point::new(500.0 + radius * angle.cos(), 500.0 + radius * angle.sin());
+        if i == 0 {
+            path.move_to(point);
+        } else {
+            path.line_to(point);
+        }
+    }
+    path.close_path();
+    let lod = LodPath::new(path, 0.1);
+
+    ctx.set_transform(Affine::scale(0.1));
+    ctx.set_paint(REBECCA_PURPLE);
+    ctx.fill_lod_path(&lod);
+}
+
 #[vello_test(diff_pixels = 1)]
 fn stroke_scaled(ctx: &mut impl Renderer) {
     let mut path = BezPath::new();
@@ -766,3 +872,72 @@ fn left_cull_mask_encloses_viewport(ctx: &mut impl Renderer) {
     ctx.pop_clip_path();
     ctx.pop_clip_path();
 }
+
+#[vello_test(transparent)]
+fn load_op_clear_color(ctx: &mut impl Renderer) {
+    ctx.set_load_op(LoadOp::Clear(BEIGE));
+    ctx.set_paint(REBECCA_PURPLE.with_alpha(0.5));
+    ctx.fill_path(&Circle::new((50.0, 50.0), 30.0).to_path(0.1));
+}
+
+fn dashed_stroke_path() -> BezPath {
+    let mut path = BezPath::new();
+    path.move_to((10.0, 20.0));
+    path.curve_to((40.0, -10.0), (60.0, 50.0), (90.0, 20.0));
+    path.extend(&Circle::new((50.0, 65.0), 25.0).to_path(0.1));
+    path
+}
+
+fn dashed_stroke_settings() -> Stroke {
+    Stroke::new(4.0).with_dashes(7.0, [10.0, 6.0, 0.0, 6.0])
+}
+
+#[vello_test]
+fn dashed_stroke(ctx: &mut impl Renderer) {
+    ctx.set_stroke(dashed_stroke_settings());
+    ctx.set_paint(DARK_BLUE);
+    ctx.stroke_path(&dashed_stroke_path());
+}
+
+#[vello_test]
+fn dashed_stroke_measured_path(ctx: &mut impl Renderer) {
+    let path = MeasuredPath::new(dashed_stroke_path());
+    ctx.set_stroke(dashed_stroke_settings());
+    ctx.set_paint(DARK_BLUE);
+    ctx.stroke_measured_path(&path);
+    // The dash pattern is kept for later strokes.
+    ctx.set_paint(RED);
+    ctx.stroke_path(&Line::new((5.0, 94.0), (95.0, 94.0)).to_path(0.1));
+}
+
+#[vello_test]
+fn variable_width_stroke_samples(ctx: &mut impl Renderer) {
+    let mut stroke = VariableWidthStroke::new();
+    // A loop with increasing and decreasing pressure which crosses itself.
+    for i in 0..=40 {
+        let t = f64::from(i) / 40.0;
+        let angle = t * 2.5 * PI;
+        let point = (50.0 + 30.0 * angle.cos(), 50.0 + 25.0 * (2.0 * angle).sin());
+        let width = 1.0 + 10.0 * (t * PI).sin();
+        stroke.line_to(point, width);
+    }
+    // A single sample is a dot.
+    stroke.move_to((90.0, 90.0), 6.0);
+
+    ctx.set_paint(REBECCA_PURPLE.with_alpha(0.75));
+    // The fill rule doesn't affect the stroke.
+    ctx.set_fill_rule(Fill::EvenOdd);
+    ctx.stroke_variable_width(&stroke);
+}
+
+#[vello_test]
+fn variable_width_stroke_profile(ctx: &mut impl Renderer) {
+    let mut path = BezPath::new();
+    path.move_to((5.0, 40.0));
+    path.curve_to((15.0, 0.0), (30.0, 50.0), (45.0, 10.0));
+    let stroke = VariableWidthStroke::from_profile(&path, 0.1, |t| 8.0 * (t * PI).sin());
+
+    ctx.set_transform(Affine::scale(2.0));
+    ctx.set_paint(DARK_BLUE);
+    ctx.stroke_variable_width(&stroke);
+}
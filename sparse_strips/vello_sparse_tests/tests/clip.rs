@@ -8,9 +8,10 @@ use crate::util::{circular_star, crossed_line_star, stops_green_blue};
 use std::f64::consts::PI;
 use vello_common::coarse::WideTile;
 use vello_common::color::palette::css::{
-    BLACK, BLUE, DARK_BLUE, DARK_GREEN, GREEN, REBECCA_PURPLE, RED,
+    BLACK, BLUE, CORNFLOWER_BLUE, DARK_BLUE, DARK_GREEN, GOLD, GREEN, REBECCA_PURPLE, RED,
+    SEA_GREEN, TOMATO,
 };
-use vello_common::kurbo::{Affine, BezPath, Circle, Point, Rect, Shape, Stroke};
+use vello_common::kurbo::{Affine, BezPath, Circle, Point, Rect, RoundedRect, Shape, Stroke};
 use vello_common::peniko::Color;
 use vello_common::peniko::Fill;
 use vello_common::tile::Tile;
@@ -590,3 +591,79 @@ fn clip_with_sweep_gradient_fill(ctx: &mut impl Renderer) {
 
     ctx.pop_layer();
 }
+
+/// Draw a stack of rounded "scroll containers", each nested inside the previous one and
+/// scrolled by a few pixels, with content that overflows every level.
+fn draw_rounded_containers(ctx: &mut impl Renderer, base: Affine, bounds: Rect, levels: usize) {
+    let colors = [CORNFLOWER_BLUE, GOLD, SEA_GREEN, TOMATO, REBECCA_PURPLE];
+    ctx.set_transform(base);
+    let mut rect = bounds;
+
+    for level in 0..levels {
+        let container = RoundedRect::from_rect(rect, 12.0 - level as f64 * 2.0);
+        ctx.push_clip_layer(&container.to_path(0.1));
+
+        // Content larger than the container, offset as if it was scrolled.
+        let scroll = Affine::translate((-3.0 * level as f64, -5.0 * level as f64));
+        ctx.set_transform(base * scroll);
+        ctx.set_paint(colors[level % colors.len()]);
+        ctx.fill_rect(&rect.inflate(10.0, 10.0));
+        ctx.set_paint(BLACK.with_alpha(0.3));
+        for i in 0..4 {
+            let y = rect.y0 + 8.0 + i as f64 * 12.0;
+            ctx.fill_rect(&Rect::new(rect.x0 - 10.0, y, rect.x1 + 10.0, y + 4.0));
+        }
+        ctx.set_transform(base);
+
+        rect = rect.inset(-8.0);
+    }
+
+    for _ in 0..levels {
+        ctx.pop_layer();
+    }
+}
+
+#[vello_test]
+fn clip_nested_rounded_containers(ctx: &mut impl Renderer) {
+    draw_rounded_containers(ctx, Affine::IDENTITY, Rect::new(5.0, 5.0, 95.0, 95.0), 5);
+}
+
+#[vello_test(cpu_u8_tolerance = 3)]
+fn clip_nested_rounded_containers_transformed(ctx: &mut impl Renderer) {
+    let transform = Affine::rotate_about(20.0 * PI / 180.0, Point::new(50.0, 50.0))
+        * Affine::scale_about(0.8, Point::new(50.0, 50.0));
+    draw_rounded_containers(ctx, transform, Rect::new(5.0, 5.0, 95.0, 95.0), 5);
+}
+
+#[vello_test(hybrid_tolerance = 2)]
+fn clip_nested_overlapping_siblings(ctx: &mut impl Renderer) {
+    let card = RoundedRect::new(5.0, 5.0, 95.0, 95.0, 15.0);
+    ctx.push_clip_layer(&card.to_path(0.1));
+    ctx.set_paint(CORNFLOWER_BLUE);
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 100.0, 100.0));
+
+    // Two sibling containers that overlap each other and the edge of the card.
+    for (rect, color) in [
+        (Rect::new(-10.0, 20.0, 65.0, 70.0), GOLD.with_alpha(0.7)),
+        (
+            Rect::new(35.0, 40.0, 110.0, 110.0),
+            SEA_GREEN.with_alpha(0.7),
+        ),
+    ] {
+        ctx.push_clip_layer(&RoundedRect::from_rect(rect, 10.0).to_path(0.1));
+        ctx.set_paint(color);
+        ctx.fill_rect(&Rect::new(0.0, 0.0, 100.0, 100.0));
+
+        // A rotated container nested inside each sibling.
+        ctx.set_transform(Affine::rotate_about(PI / 6.0, rect.center()));
+        ctx.push_clip_layer(&RoundedRect::from_rect(rect.inset(-12.0), 6.0).to_path(0.1));
+        ctx.set_transform(Affine::IDENTITY);
+        ctx.set_paint(TOMATO);
+        ctx.fill_path(&Circle::new(rect.center(), 30.0).to_path(0.1));
+        ctx.pop_layer();
+
+        ctx.pop_layer();
+    }
+
+    ctx.pop_layer();
+}
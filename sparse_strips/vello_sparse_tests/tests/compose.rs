@@ -443,3 +443,25 @@ fn compose_non_isolated_dest_atop(ctx: &mut impl Renderer) {
 fn compose_non_isolated_plus(ctx: &mut impl Renderer) {
     compose_non_isolated(ctx, Compose::Plus);
 }
+
+// Same as `compose_non_isolated`, but without the surrounding layer, so that the blend mode
+// applies to a draw on the root layer.
+fn compose_root(ctx: &mut impl Renderer, compose: Compose) {
+    let rect1 = Rect::new(10.5, 10.5, 70.5, 70.5);
+    ctx.set_paint(BLUE.with_alpha(0.5));
+    ctx.fill_rect(&rect1);
+    ctx.set_blend_mode(BlendMode::new(Mix::Normal, compose));
+    let rect2 = Rect::new(30.5, 30.5, 90.5, 90.5);
+    ctx.set_paint(LIME.with_alpha(0.5));
+    ctx.fill_rect(&rect2);
+}
+
+#[vello_test(hybrid_tolerance = 2)]
+fn compose_root_dest_out(ctx: &mut impl Renderer) {
+    compose_root(ctx, Compose::DestOut);
+}
+
+#[vello_test(hybrid_tolerance = 2)]
+fn compose_root_xor(ctx: &mut impl Renderer) {
+    compose_root(ctx, Compose::Xor);
+}
@@ -0,0 +1,107 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// The WebGL renderer can't compile the WGSL of custom paints and draws them transparent.
+#[cfg(not(all(target_arch = "wasm32", feature = "webgl")))]
+mod tests {
+    use std::f64::consts::TAU;
+    use std::sync::Arc;
+
+    use crate::renderer::Renderer;
+    use vello_common::custom_paint::{CustomPaint, CustomPaintKind};
+    use vello_common::kurbo::{Affine, Circle, Point, Rect, Shape, Stroke};
+    use vello_dev_macros::vello_test;
+
+    /// Concentric rings that blend between two colors, like the grain of a cut log.
+    ///
+    /// The parameters are the distance between two rings and the two colors as RGB.
+    #[derive(Debug)]
+    struct Rings;
+
+    impl CustomPaintKind for Rings {
+        fn evaluate(&self, params: &[f32], pos: Point) -> [f32; 4] {
+            let t =
+                (0.5 + 0.5 * (TAU * pos.to_vec2().length() / f64::from(params[0])).cos()) as f32;
+            let mix = |i: usize| params[i] + (params[i + 3] - params[i]) * t;
+            [mix(1), mix(2), mix(3), 1.0]
+        }
+
+        fn wgsl(&self) -> &str {
+            "let t = 0.5 + 0.5 * cos(6.2831855 * length(pos) / custom_paint_param(params, 0u));
+            let c0 = vec3(custom_paint_param(params, 1u), custom_paint_param(params, 2u), custom_paint_param(params, 3u));
+            let c1 = vec3(custom_paint_param(params, 4u), custom_paint_param(params, 5u), custom_paint_param(params, 6u));
+            return vec4(mix(c0, c1, t), 1.0);"
+        }
+
+        fn may_have_transparency(&self, _: &[f32]) -> bool {
+            false
+        }
+    }
+
+    /// A checkerboard with squares of size one, in a single premultiplied color.
+    ///
+    /// The parameters are the color of the filled squares as premultiplied RGBA.
+    #[derive(Debug)]
+    struct Checkerboard;
+
+    impl CustomPaintKind for Checkerboard {
+        fn evaluate(&self, params: &[f32], pos: Point) -> [f32; 4] {
+            if (pos.x.floor() + pos.y.floor()).rem_euclid(2.0) < 1.0 {
+                [params[0], params[1], params[2], params[3]]
+            } else {
+                [0.0; 4]
+            }
+        }
+
+        fn wgsl(&self) -> &str {
+            "let cell = floor(pos.x) + floor(pos.y);
+            if cell - 2.0 * floor(cell / 2.0) < 1.0 {
+                return vec4(
+                    custom_paint_param(params, 0u),
+                    custom_paint_param(params, 1u),
+                    custom_paint_param(params, 2u),
+                    custom_paint_param(params, 3u),
+                );
+            }
+            return vec4(0.0);"
+        }
+    }
+
+    const WOOD: [f32; 7] = [6.0, 0.55, 0.35, 0.17, 0.8, 0.6, 0.35];
+
+    #[vello_test(hybrid_tolerance = 1)]
+    fn custom_paint_rings(ctx: &mut impl Renderer) {
+        let rings = ctx.register_custom_paint(Arc::new(Rings));
+        let paint = CustomPaint::new(rings, WOOD);
+
+        ctx.set_paint_transform(Affine::translate((30.0, 40.0)));
+        ctx.fill_custom_paint(&Circle::new((50.0, 50.0), 40.0).to_path(0.1), &paint);
+    }
+
+    #[vello_test(hybrid_tolerance = 1)]
+    fn custom_paint_multiple_kinds(ctx: &mut impl Renderer) {
+        let rings = ctx.register_custom_paint(Arc::new(Rings));
+        let checkerboard = ctx.register_custom_paint(Arc::new(Checkerboard));
+
+        ctx.fill_custom_paint(
+            &Rect::new(10.0, 10.0, 90.0, 90.0).to_path(0.1),
+            &CustomPaint::new(rings, WOOD),
+        );
+        ctx.set_transform(Affine::rotate_about(0.3, (50.0, 50.0)));
+        ctx.set_paint_transform(Affine::scale(8.0));
+        ctx.fill_custom_paint(
+            &Rect::new(20.0, 20.0, 80.0, 80.0).to_path(0.1),
+            &CustomPaint::new(checkerboard, [0.0, 0.2, 0.5, 0.5]),
+        );
+    }
+
+    #[vello_test(hybrid_tolerance = 1)]
+    fn custom_paint_stroke(ctx: &mut impl Renderer) {
+        let rings = ctx.register_custom_paint(Arc::new(Rings));
+        let paint = CustomPaint::new(rings, WOOD);
+
+        ctx.set_stroke(Stroke::new(12.0));
+        ctx.set_paint_transform(Affine::translate((50.0, 50.0)));
+        ctx.stroke_custom_paint(&Circle::new((50.0, 50.0), 30.0).to_path(0.1), &paint);
+    }
+}
@@ -7,8 +7,9 @@ mod tests {
     use vello_common::filter_effects::{EdgeMode, Filter, FilterPrimitive};
     use vello_common::geometry::RectU16;
     use vello_common::kurbo::{Affine, Circle, Rect, Shape};
-    use vello_common::peniko::ImageQuality;
-    use vello_hybrid::SampleRect;
+    use vello_common::peniko::{Extend, ImageQuality, ImageSampler};
+    use vello_common::pixmap::Pixmap;
+    use vello_hybrid::{SampleRect, TexturePaint};
 
     use crate::load_image;
 
@@ -199,4 +200,191 @@ mod tests {
             );
         });
     }
+
+    #[test]
+    fn external_texture_paint_clipped() {
+        hybrid_snapshot_test::<96, 96>("external_texture_paint_clipped", |ctx| {
+            let texture_id = ctx.register_external_texture(load_image!("glyphs_colr_noto"));
+            let clip = Circle::new((48., 48.), 40.).to_path(0.1);
+
+            ctx.push_clip_layer(&clip);
+            ctx.set_paint_transform(Affine::translate((10., 6.)) * Affine::rotate(0.3));
+            ctx.set_texture_paint(TexturePaint {
+                texture_id,
+                source_region: SPRITES[3],
+                sampler: ImageSampler {
+                    x_extend: Extend::Repeat,
+                    y_extend: Extend::Repeat,
+                    quality: ImageQuality::Medium,
+                    alpha: 1.0,
+                },
+            });
+            ctx.fill_rect(&Rect::new(0., 0., 96., 96.));
+            ctx.pop_layer();
+        });
+    }
+
+    #[test]
+    fn external_texture_paint_pad_with_alpha() {
+        hybrid_snapshot_test::<96, 96>("external_texture_paint_pad_with_alpha", |ctx| {
+            let texture_id = ctx.register_external_texture(load_image!("glyphs_colr_noto"));
+
+            ctx.set_paint(color::palette::css::PALE_GOLDENROD);
+            ctx.fill_rect(&Rect::new(8., 8., 88., 88.));
+            ctx.set_transform(Affine::translate((20., 20.)));
+            ctx.set_texture_paint(TexturePaint {
+                texture_id,
+                source_region: SPRITES[0],
+                sampler: ImageSampler {
+                    x_extend: Extend::Pad,
+                    y_extend: Extend::Pad,
+                    quality: ImageQuality::Low,
+                    alpha: 0.6,
+                },
+            });
+            ctx.fill_path(&Circle::new((28., 28.), 30.).to_path(0.1));
+        });
+    }
+
+    #[test]
+    fn external_texture_bind_groups_are_kept_across_frames() {
+        let mut ctx = get_ctx::<HybridRenderer>(
+            32,
+            32,
+            false,
+            0,
+            "fallback",
+            RenderMode::OptimizeQuality,
+            false,
+        );
+        let mut pixmap = Pixmap::new(32, 32);
+        let texture_id = ctx.register_external_texture(load_image!("glyphs_colr_noto"));
+        let texture_paint = TexturePaint {
+            texture_id,
+            source_region: SPRITES[0],
+            sampler: ImageSampler::default(),
+        };
+
+        ctx.set_texture_paint(texture_paint);
+        ctx.fill_rect(&Rect::new(0., 0., 32., 32.));
+        ctx.render_to_pixmap(&mut pixmap);
+        let bind_group = ctx.external_paint_source_bind_group(texture_id).unwrap();
+
+        for _ in 0..2 {
+            ctx.reset();
+            ctx.set_texture_paint(texture_paint);
+            ctx.fill_rect(&Rect::new(0., 0., 16., 16.));
+            ctx.render_to_pixmap(&mut pixmap);
+            assert_eq!(
+                ctx.external_paint_source_bind_group(texture_id),
+                Some(bind_group.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn registered_texture_is_released_when_unused() {
+        let mut ctx = get_ctx::<HybridRenderer>(
+            32,
+            32,
+            false,
+            0,
+            "fallback",
+            RenderMode::OptimizeQuality,
+            false,
+        );
+        let mut pixmap = Pixmap::new(32, 32);
+        let texture_id = ctx.register_texture(load_image!("glyphs_colr_noto"));
+        let texture_paint = TexturePaint {
+            texture_id,
+            source_region: SPRITES[0],
+            sampler: ImageSampler::default(),
+        };
+
+        ctx.set_texture_paint(texture_paint);
+        ctx.fill_rect(&Rect::new(0., 0., 32., 32.));
+        ctx.render_to_pixmap(&mut pixmap);
+        assert!(ctx.is_texture_registered(texture_id));
+
+        ctx.set_texture_paint(texture_paint);
+        ctx.fill_rect(&Rect::new(0., 0., 16., 16.));
+        ctx.render_to_pixmap(&mut pixmap);
+        assert!(ctx.is_texture_registered(texture_id));
+
+        // Skipping the texture for a couple of frames doesn't release it.
+        for _ in 0..2 {
+            ctx.reset();
+            ctx.set_paint(color::palette::css::RED);
+            ctx.fill_rect(&Rect::new(0., 0., 16., 16.));
+            ctx.render_to_pixmap(&mut pixmap);
+            assert!(ctx.is_texture_registered(texture_id));
+        }
+
+        ctx.reset();
+        ctx.set_paint(color::palette::css::RED);
+        ctx.fill_rect(&Rect::new(0., 0., 16., 16.));
+        ctx.render_to_pixmap(&mut pixmap);
+        assert!(!ctx.is_texture_registered(texture_id));
+        assert!(!ctx.unregister_texture(texture_id));
+    }
+
+    #[test]
+    fn registered_texture_can_be_unregistered() {
+        let mut ctx = get_ctx::<HybridRenderer>(
+            32,
+            32,
+            false,
+            0,
+            "fallback",
+            RenderMode::OptimizeQuality,
+            false,
+        );
+        let texture_id = ctx.register_texture(load_image!("glyphs_colr_noto"));
+        assert!(ctx.is_texture_registered(texture_id));
+
+        assert!(ctx.unregister_texture(texture_id));
+        assert!(!ctx.is_texture_registered(texture_id));
+        assert!(!ctx.unregister_texture(texture_id));
+    }
+
+    #[test]
+    fn rendered_texture_is_kept_until_unregistered() {
+        let mut ctx = get_ctx::<HybridRenderer>(
+            32,
+            32,
+            false,
+            0,
+            "fallback",
+            RenderMode::OptimizeQuality,
+            false,
+        );
+        let mut pixmap = Pixmap::new(32, 32);
+        let mut sub_scene = vello_hybrid::Scene::new(16, 16);
+        sub_scene.set_paint(color::palette::css::BLUE);
+        sub_scene.fill_rect(&Rect::new(0., 0., 16., 16.));
+        let texture_id = ctx.render_to_texture(&sub_scene);
+        assert!(ctx.is_texture_registered(texture_id));
+
+        ctx.set_texture_paint(TexturePaint {
+            texture_id,
+            source_region: RectU16::new(0, 0, 16, 16),
+            sampler: ImageSampler::default(),
+        });
+        ctx.fill_rect(&Rect::new(0., 0., 16., 16.));
+        ctx.render_to_pixmap(&mut pixmap);
+        assert!(ctx.is_texture_registered(texture_id));
+
+        // Rendered textures are pinned, so skipping them for any number of frames doesn't
+        // release them.
+        for _ in 0..4 {
+            ctx.reset();
+            ctx.set_paint(color::palette::css::RED);
+            ctx.fill_rect(&Rect::new(0., 0., 16., 16.));
+            ctx.render_to_pixmap(&mut pixmap);
+            assert!(ctx.is_texture_registered(texture_id));
+        }
+
+        assert!(ctx.unregister_texture(texture_id));
+        assert!(!ctx.is_texture_registered(texture_id));
+    }
 }
@@ -8,10 +8,10 @@ use crate::util::{circular_star, stops_blue_green_red_yellow};
 use crate::{renderer::Renderer, util::layout_glyphs_roboto};
 use vello_common::color::AlphaColor;
 use vello_common::color::palette::css::{
-    BLACK, LIME, PURPLE, REBECCA_PURPLE, ROYAL_BLUE, SEA_GREEN, TOMATO, VIOLET,
+    BLACK, LIME, PURPLE, REBECCA_PURPLE, ROYAL_BLUE, SEA_GREEN, TOMATO, VIOLET, WHITE,
 };
-use vello_common::filter_effects::{EdgeMode, Filter, FilterPrimitive};
-use vello_common::kurbo::{Affine, BezPath, Circle, Point, Rect, Shape, Stroke};
+use vello_common::filter_effects::{EdgeMode, Filter, FilterFunction, FilterPrimitive};
+use vello_common::kurbo::{Affine, BezPath, Circle, Point, Rect, RoundedRect, Shape, Stroke, Vec2};
 use vello_common::paint::Image;
 use vello_common::peniko::{
     BlendMode, Compose, Extend, Gradient, ImageQuality, ImageSampler, LinearGradientPosition, Mix,
@@ -115,6 +115,160 @@ fn filter_gaussian_blur_with_decimation(ctx: &mut impl Renderer) {
     ctx.pop_layer();
 }
 
+/// Test a blur layer used as a shadow beneath an arbitrary shape.
+#[vello_test(skip_multithreaded, hybrid_tolerance = 1)]
+fn filter_blur_layer_shape_shadow(ctx: &mut impl Renderer) {
+    let star_path = circular_star(Point::new(50.0, 50.0), 5, 18.0, 36.0);
+
+    ctx.push_blur_layer(3.0);
+    ctx.set_transform(Affine::translate((4.0, 4.0)));
+    ctx.set_paint(BLACK.with_alpha(0.6));
+    ctx.fill_path(&star_path);
+    ctx.pop_layer();
+
+    ctx.set_transform(Affine::IDENTITY);
+    ctx.set_paint(ROYAL_BLUE);
+    ctx.fill_path(&star_path);
+}
+
+/// Test a blur layer containing several overlapping draws, which are blurred as a group.
+#[vello_test(skip_multithreaded, hybrid_tolerance = 1)]
+fn filter_blur_layer_group(ctx: &mut impl Renderer) {
+    ctx.push_blur_layer(5.0);
+    ctx.set_paint(TOMATO);
+    ctx.fill_rect(&Rect::new(15.0, 15.0, 60.0, 60.0));
+    ctx.set_paint(SEA_GREEN);
+    ctx.fill_path(&Circle::new((60.0, 60.0), 25.0).to_path(0.1));
+    ctx.pop_layer();
+}
+
+/// Test a frosted glass panel, which blurs the content behind it and draws a translucent
+/// fill on top.
+#[vello_test(skip_multithreaded, skip_hybrid_constrained, hybrid_tolerance = 1)]
+fn filter_backdrop_blur_panel(ctx: &mut impl Renderer) {
+    for i in 0..5 {
+        let x = f64::from(i) * 20.0;
+        ctx.set_paint(if i % 2 == 0 { TOMATO } else { ROYAL_BLUE });
+        ctx.fill_rect(&Rect::new(x, 0.0, x + 10.0, 100.0));
+    }
+
+    let panel = RoundedRect::new(15.0, 25.0, 85.0, 75.0, 10.0).to_path(0.1);
+    ctx.push_backdrop_blur_layer(4.0, &panel);
+    ctx.set_paint(WHITE.with_alpha(0.3));
+    ctx.fill_path(&panel);
+    ctx.pop_layer();
+}
+
+/// Test a frosted glass panel inside of a clip and an opacity layer, which blurs the content
+/// of the opacity layer behind it.
+#[vello_test(
+    skip_multithreaded,
+    skip_hybrid_constrained,
+    cpu_u8_tolerance = 1,
+    hybrid_tolerance = 1
+)]
+fn filter_backdrop_blur_nested(ctx: &mut impl Renderer) {
+    ctx.set_paint(SEA_GREEN);
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 100.0, 100.0));
+
+    ctx.push_clip_layer(&Rect::new(5.0, 5.0, 95.0, 95.0).to_path(0.1));
+    ctx.push_opacity_layer(0.8);
+    for i in 0..5 {
+        let x = f64::from(i) * 20.0;
+        ctx.set_paint(if i % 2 == 0 { TOMATO } else { ROYAL_BLUE });
+        ctx.fill_rect(&Rect::new(x, 0.0, x + 10.0, 100.0));
+    }
+
+    ctx.push_clip_layer(&Rect::new(0.0, 0.0, 50.0, 100.0).to_path(0.1));
+    let panel = RoundedRect::new(15.0, 25.0, 85.0, 75.0, 10.0).to_path(0.1);
+    ctx.push_backdrop_blur_layer(4.0, &panel);
+    ctx.set_paint(WHITE.with_alpha(0.3));
+    ctx.fill_path(&panel);
+    ctx.pop_layer();
+    ctx.pop_layer();
+
+    ctx.pop_layer();
+    ctx.pop_layer();
+}
+
+fn draw_color_swatches(ctx: &mut impl Renderer, origin: Point) {
+    let colors = [TOMATO, SEA_GREEN, ROYAL_BLUE, VIOLET.with_alpha(0.5)];
+    for (i, color) in colors.into_iter().enumerate() {
+        let x = origin.x + 5.0 + (i % 2) as f64 * 20.0;
+        let y = origin.y + 5.0 + (i / 2) as f64 * 20.0;
+        ctx.set_paint(color);
+        ctx.fill_rect(&Rect::new(x, y, x + 20.0, y + 20.0));
+    }
+}
+
+/// Test the color matrix presets, with the unfiltered swatches in the top-left corner.
+#[vello_test(skip_multithreaded, hybrid_tolerance = 1)]
+fn filter_color_matrix_presets(ctx: &mut impl Renderer) {
+    draw_color_swatches(ctx, Point::new(0.0, 0.0));
+
+    let filters = [
+        (Filter::grayscale(1.0), Point::new(50.0, 0.0)),
+        (Filter::sepia(0.8), Point::new(0.0, 50.0)),
+        (Filter::hue_rotate(120.0), Point::new(50.0, 50.0)),
+    ];
+    for (filter, origin) in filters {
+        ctx.push_filter_layer(filter);
+        draw_color_swatches(ctx, origin);
+        ctx.pop_layer();
+    }
+}
+
+/// Test a custom color matrix that swaps the red and blue channels and halves the alpha, and
+/// the brightness and contrast presets.
+#[vello_test(skip_multithreaded, hybrid_tolerance = 1)]
+fn filter_color_matrix_custom(ctx: &mut impl Renderer) {
+    #[rustfmt::skip]
+    let swap_red_blue = [
+        0.0, 0.0, 1.0, 0.0, 0.0,
+        0.0, 1.0, 0.0, 0.0, 0.0,
+        1.0, 0.0, 0.0, 0.0, 0.0,
+        0.0, 0.0, 0.0, 0.5, 0.0,
+    ];
+
+    let filters = [
+        (Filter::color_matrix(swap_red_blue), Point::new(0.0, 0.0)),
+        (Filter::brightness(1.5), Point::new(50.0, 0.0)),
+        (Filter::contrast(0.5), Point::new(0.0, 50.0)),
+        (
+            Filter::from_function(FilterFunction::Invert { amount: 1.0 }),
+            Point::new(50.0, 50.0),
+        ),
+    ];
+    for (filter, origin) in filters {
+        ctx.push_filter_layer(filter);
+        draw_color_swatches(ctx, origin);
+        ctx.pop_layer();
+    }
+}
+
+/// Test the drop shadow of an arbitrary shape, drawn beneath the shape itself.
+#[vello_test(hybrid_tolerance = 2)]
+fn drop_shadow_shape(ctx: &mut impl Renderer) {
+    let star_path = circular_star(Point::new(50.0, 50.0), 5, 18.0, 36.0);
+
+    ctx.set_paint(BLACK.with_alpha(0.6));
+    ctx.fill_drop_shadow(&star_path, Vec2::new(4.0, 4.0), 3.0);
+    ctx.set_paint(ROYAL_BLUE);
+    ctx.fill_path(&star_path);
+}
+
+/// Test that the offset and the blur of a drop shadow scale with the transform.
+#[vello_test(hybrid_tolerance = 2)]
+fn drop_shadow_transformed(ctx: &mut impl Renderer) {
+    let path = Circle::new((0.0, 0.0), 10.0).to_path(0.1);
+
+    ctx.set_transform(Affine::translate((40.0, 40.0)) * Affine::scale(2.0));
+    ctx.set_paint(PURPLE);
+    ctx.fill_drop_shadow(&path, Vec2::new(5.0, 5.0), 2.0);
+    ctx.set_paint(LIME);
+    ctx.fill_path(&path);
+}
+
 /// Test drop shadow filter on text glyph.
 /// Creates a blurred, offset shadow beneath the original graphic.
 #[vello_test(skip_multithreaded, hybrid_tolerance = 1)]
@@ -7,7 +7,7 @@ use crate::renderer::Renderer;
 #[cfg(target_os = "macos")]
 use crate::util::layout_glyphs_apple_color_emoji;
 use crate::util::{layout_glyphs_noto_cbtf, layout_glyphs_noto_colr, layout_glyphs_roboto};
-use glifo::{FontEmbolden, Glyph};
+use glifo::{DecorationLine, FontEmbolden, Glyph, SubpixelOrder};
 use std::f64::consts::FRAC_PI_4;
 use std::iter;
 use std::sync::Arc;
@@ -910,6 +910,52 @@ fn glyphs_decoration_no_descenders(ctx: &mut impl Renderer, enable_caching: bool
     render_decorated_text(ctx, "HELLO", 50.0, enable_caching, None, -2.0, 2.0, 1.5);
 }
 
+#[vello_test(width = 250, height = 60, glyph)]
+fn glyphs_decoration_lines(ctx: &mut impl Renderer, enable_caching: bool) {
+    let font_size = 30.0_f32;
+    let (font, glyphs) = layout_glyphs_roboto("Typography", font_size);
+    let x_end = glyphs.last().map_or(0.0, |g| g.x + font_size * 0.6);
+
+    ctx.set_transform(Affine::translate((10.0, 40.0)));
+    ctx.set_paint(REBECCA_PURPLE);
+    ctx.glyph_run(&font)
+        .font_size(font_size)
+        .atlas_cache(enable_caching)
+        .fill_glyphs(glyphs.iter().copied());
+
+    ctx.set_paint(BLUE);
+    for line in [
+        DecorationLine::Underline,
+        DecorationLine::Strikethrough,
+        DecorationLine::Overline,
+    ] {
+        ctx.glyph_run(&font)
+            .font_size(font_size)
+            .atlas_cache(enable_caching)
+            .render_decoration_line(glyphs.iter().copied(), line, 0.0..=x_end, 0.0);
+    }
+}
+
+// Subpixel antialiasing blends into the backdrop, which the constrained renderer doesn't support.
+#[vello_test(width = 200, height = 70, glyph, skip_hybrid_constrained)]
+fn glyphs_subpixel_antialiasing(ctx: &mut impl Renderer, enable_caching: bool) {
+    let font_size = 16.0_f32;
+    let (font, glyphs) = layout_glyphs_roboto("Sharper text", font_size);
+
+    ctx.set_paint(BLACK);
+    for (i, order) in [None, Some(SubpixelOrder::Rgb), Some(SubpixelOrder::Bgr)]
+        .into_iter()
+        .enumerate()
+    {
+        ctx.set_transform(Affine::translate((10.3, 18.0 + 20.0 * i as f64)));
+        ctx.glyph_run(&font)
+            .font_size(font_size)
+            .subpixel_order(order)
+            .atlas_cache(enable_caching)
+            .fill_glyphs(glyphs.iter().copied());
+    }
+}
+
 #[vello_test(width = 100, height = 150, glyph, hybrid_tolerance = 1)]
 fn glyphs_decoration_transformed(ctx: &mut impl Renderer, enable_caching: bool) {
     let text = "Happy";
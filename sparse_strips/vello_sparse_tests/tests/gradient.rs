@@ -1108,3 +1108,311 @@ mod sweep {
         gradient_with_transform(ctx, transform, 25.0, 25.0, 75.0, 75.0);
     }
 }
+
+mod hard_stops {
+    use crate::renderer::Renderer;
+    use smallvec::smallvec;
+    use vello_common::color::palette::css::{BLUE, GREEN, ORANGE, RED, YELLOW};
+    use vello_common::color::{AlphaColor, DynamicColor, Srgb};
+    use vello_common::kurbo::{Affine, Circle, Point, Rect, Shape, Stroke};
+    use vello_common::peniko::{ColorStop, ColorStops, Extend, Gradient};
+    use vello_cpu::peniko::{
+        LinearGradientPosition, RadialGradientPosition, SweepGradientPosition,
+    };
+    use vello_dev_macros::vello_test;
+
+    /// Stops that split the gradient into equally sized solid bands of the given colors.
+    fn banded_stops(colors: &[AlphaColor<Srgb>]) -> ColorStops {
+        let mut stops = smallvec![];
+        let n = colors.len() as f32;
+
+        for (i, color) in colors.iter().enumerate() {
+            for offset in [i as f32 / n, (i + 1) as f32 / n] {
+                stops.push(ColorStop {
+                    offset,
+                    color: DynamicColor::from_alpha_color(*color),
+                });
+            }
+        }
+
+        ColorStops(stops)
+    }
+
+    // The pixel where all slices meet deviates slightly in the `u8` pipeline.
+    #[vello_test(diff_pixels = 1)]
+    fn gradient_hard_stops_pie(ctx: &mut impl Renderer) {
+        let gradient = Gradient {
+            kind: SweepGradientPosition {
+                center: Point::new(50.0, 50.0),
+                start_angle: 0.0,
+                end_angle: 360.0_f32.to_radians(),
+            }
+            .into(),
+            stops: banded_stops(&[RED, ORANGE, YELLOW, GREEN, BLUE]),
+            ..Default::default()
+        };
+
+        ctx.set_paint(gradient);
+        ctx.fill_path(&Circle::new((50.0, 50.0), 40.0).to_path(0.1));
+    }
+
+    #[vello_test]
+    fn gradient_hard_stops_donut(ctx: &mut impl Renderer) {
+        let gradient = Gradient {
+            kind: RadialGradientPosition {
+                start_center: Point::new(50.0, 50.0),
+                start_radius: 15.0,
+                end_center: Point::new(50.0, 50.0),
+                end_radius: 45.0,
+            }
+            .into(),
+            stops: banded_stops(&[RED, YELLOW, BLUE]),
+            ..Default::default()
+        };
+
+        ctx.set_paint(gradient);
+        ctx.fill_rect(&Rect::new(5.0, 5.0, 95.0, 95.0));
+    }
+
+    #[vello_test]
+    fn gradient_hard_stops_stripes(ctx: &mut impl Renderer) {
+        let gradient = Gradient {
+            kind: LinearGradientPosition {
+                start: Point::new(0.0, 0.0),
+                end: Point::new(16.0, 0.0),
+            }
+            .into(),
+            stops: banded_stops(&[BLUE, YELLOW]),
+            extend: Extend::Repeat,
+            ..Default::default()
+        };
+
+        ctx.set_transform(Affine::rotate_about(0.4, Point::new(50.0, 50.0)));
+        ctx.set_paint(gradient);
+        ctx.fill_rect(&Rect::new(20.0, 20.0, 80.0, 80.0));
+    }
+
+    #[vello_test]
+    fn gradient_hard_stops_reflect_stroke(ctx: &mut impl Renderer) {
+        let gradient = Gradient {
+            kind: LinearGradientPosition {
+                start: Point::new(0.0, 0.0),
+                end: Point::new(0.0, 20.0),
+            }
+            .into(),
+            stops: banded_stops(&[GREEN, RED, BLUE]),
+            extend: Extend::Reflect,
+            ..Default::default()
+        };
+
+        ctx.set_paint_transform(Affine::translate((0.0, 10.0)));
+        ctx.set_paint(gradient);
+        ctx.set_stroke(Stroke::new(12.0));
+        ctx.stroke_path(&Circle::new((50.0, 50.0), 30.0).to_path(0.1));
+    }
+
+    #[vello_test(width = 400, height = 40)]
+    fn gradient_hard_stops_wide(ctx: &mut impl Renderer) {
+        // A long gradient with a narrow band, which used to be smeared across a few pixels
+        // because of the limited resolution of the lookup table.
+        let gradient = Gradient {
+            kind: LinearGradientPosition {
+                start: Point::new(0.0, 0.0),
+                end: Point::new(4000.0, 0.0),
+            }
+            .into(),
+            stops: ColorStops(smallvec![
+                ColorStop {
+                    offset: 0.0,
+                    color: DynamicColor::from_alpha_color(BLUE),
+                },
+                ColorStop {
+                    offset: 0.0501,
+                    color: DynamicColor::from_alpha_color(BLUE),
+                },
+                ColorStop {
+                    offset: 0.0501,
+                    color: DynamicColor::from_alpha_color(YELLOW),
+                },
+                ColorStop {
+                    offset: 0.0526,
+                    color: DynamicColor::from_alpha_color(YELLOW),
+                },
+                ColorStop {
+                    offset: 0.0526,
+                    color: DynamicColor::from_alpha_color(RED),
+                },
+                ColorStop {
+                    offset: 1.0,
+                    color: DynamicColor::from_alpha_color(RED),
+                },
+            ]),
+            ..Default::default()
+        };
+
+        ctx.set_paint(gradient);
+        ctx.fill_rect(&Rect::new(0.0, 0.0, 400.0, 40.0));
+    }
+}
+
+mod stroke {
+    use crate::renderer::Renderer;
+    use crate::util::{stops_blue_green_red_yellow, stops_green_blue};
+    use vello_common::kurbo::{Affine, BezPath, Cap, Join, Point, Stroke};
+    use vello_common::peniko::{Extend, Gradient};
+    use vello_cpu::peniko::{
+        LinearGradientPosition, RadialGradientPosition, SweepGradientPosition,
+    };
+    use vello_dev_macros::vello_test;
+
+    /// A zig-zag line like the ones in line charts.
+    fn chart_line() -> BezPath {
+        let mut path = BezPath::new();
+        path.move_to((8.0, 80.0));
+        for (i, y) in [55.0, 70.0, 30.0, 45.0, 15.0, 35.0].into_iter().enumerate() {
+            path.line_to((8.0 + 14.0 * (i + 1) as f64, y));
+        }
+        path
+    }
+
+    fn stroke_chart_line(ctx: &mut impl Renderer, gradient: Gradient) {
+        ctx.set_paint(gradient);
+        ctx.set_stroke(
+            Stroke::new(6.0)
+                .with_join(Join::Round)
+                .with_caps(Cap::Round),
+        );
+        ctx.stroke_path(&chart_line());
+    }
+
+    #[vello_test]
+    fn gradient_linear_stroke(ctx: &mut impl Renderer) {
+        let gradient = Gradient {
+            kind: LinearGradientPosition {
+                start: Point::new(0.0, 90.0),
+                end: Point::new(0.0, 10.0),
+            }
+            .into(),
+            stops: stops_blue_green_red_yellow(),
+            ..Default::default()
+        };
+        stroke_chart_line(ctx, gradient);
+    }
+
+    #[vello_test]
+    fn gradient_radial_stroke(ctx: &mut impl Renderer) {
+        let gradient = Gradient {
+            kind: RadialGradientPosition::new(Point::new(50.0, 50.0), 40.0).into(),
+            stops: stops_green_blue(),
+            extend: Extend::Reflect,
+            ..Default::default()
+        };
+        stroke_chart_line(ctx, gradient);
+    }
+
+    #[vello_test]
+    fn gradient_sweep_stroke(ctx: &mut impl Renderer) {
+        let gradient = Gradient {
+            kind: SweepGradientPosition {
+                center: Point::new(50.0, 50.0),
+                start_angle: 0.0,
+                end_angle: 360.0_f32.to_radians(),
+            }
+            .into(),
+            stops: stops_blue_green_red_yellow(),
+            ..Default::default()
+        };
+        stroke_chart_line(ctx, gradient);
+    }
+
+    #[vello_test]
+    fn gradient_linear_stroke_with_transform(ctx: &mut impl Renderer) {
+        let gradient = Gradient {
+            kind: LinearGradientPosition {
+                start: Point::new(0.0, 0.0),
+                end: Point::new(20.0, 0.0),
+            }
+            .into(),
+            stops: stops_green_blue(),
+            extend: Extend::Repeat,
+            ..Default::default()
+        };
+        ctx.set_transform(Affine::rotate_about(0.3, Point::new(50.0, 50.0)));
+        ctx.set_paint_transform(Affine::skew(0.5, 0.0));
+        stroke_chart_line(ctx, gradient);
+    }
+}
+
+mod hints {
+    use crate::renderer::Renderer;
+    use crate::util::{stops_blue_green_red_yellow, stops_green_blue};
+    use vello_common::color::ColorSpaceTag;
+    use vello_common::kurbo::{Point, Rect};
+    use vello_common::peniko::Gradient;
+    use vello_cpu::peniko::{LinearGradientPosition, RadialGradientPosition};
+    use vello_dev_macros::vello_test;
+
+    fn linear() -> Gradient {
+        Gradient {
+            kind: LinearGradientPosition {
+                start: Point::new(10.0, 0.0),
+                end: Point::new(90.0, 0.0),
+            }
+            .into(),
+            stops: stops_blue_green_red_yellow(),
+            ..Default::default()
+        }
+    }
+
+    #[vello_test]
+    fn gradient_linear_hints(ctx: &mut impl Renderer) {
+        ctx.set_gradient_hints(&[Some(0.05), None, Some(0.95)]);
+        ctx.set_paint(linear());
+        ctx.fill_rect(&Rect::new(10.0, 10.0, 90.0, 90.0));
+    }
+
+    #[vello_test]
+    fn gradient_linear_hints_at_stops(ctx: &mut impl Renderer) {
+        // Hints on the stops turn the transitions into hard stops.
+        ctx.set_gradient_hints(&[Some(0.0), Some(0.66), Some(0.8)]);
+        ctx.set_paint(linear());
+        ctx.fill_rect(&Rect::new(10.0, 10.0, 90.0, 90.0));
+    }
+
+    #[vello_test]
+    fn gradient_linear_hint_oklab(ctx: &mut impl Renderer) {
+        let gradient = Gradient {
+            kind: LinearGradientPosition {
+                start: Point::new(10.0, 0.0),
+                end: Point::new(90.0, 0.0),
+            }
+            .into(),
+            stops: stops_green_blue(),
+            interpolation_cs: ColorSpaceTag::Oklab,
+            ..Default::default()
+        };
+
+        ctx.set_gradient_hints(&[Some(0.8)]);
+        ctx.set_paint(gradient);
+        ctx.fill_rect(&Rect::new(10.0, 10.0, 90.0, 90.0));
+    }
+
+    #[vello_test]
+    fn gradient_radial_hint(ctx: &mut impl Renderer) {
+        let gradient = Gradient {
+            kind: RadialGradientPosition {
+                start_center: Point::new(50.0, 50.0),
+                start_radius: 0.0,
+                end_center: Point::new(50.0, 50.0),
+                end_radius: 40.0,
+            }
+            .into(),
+            stops: stops_green_blue(),
+            ..Default::default()
+        };
+
+        ctx.set_gradient_hints(&[Some(0.2)]);
+        ctx.set_paint(gradient);
+        ctx.fill_rect(&Rect::new(10.0, 10.0, 90.0, 90.0));
+    }
+}
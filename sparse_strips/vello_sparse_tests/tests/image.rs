@@ -8,12 +8,14 @@ use crate::util::crossed_line_star;
 use std::f64::consts::PI;
 use std::sync::Arc;
 use vello_common::color::palette::css::REBECCA_PURPLE;
-use vello_common::kurbo::{Affine, Point, Rect};
+use vello_common::kurbo::{Affine, Point, Rect, Stroke};
 use vello_common::kurbo::{Shape, Triangle};
 use vello_common::paint::{Image, ImageSource, Tint, TintMode};
 use vello_common::peniko::Color;
 use vello_common::peniko::ImageSampler;
+use vello_common::peniko::color::{ColorSpaceTag, PremulRgba8};
 use vello_common::peniko::{Extend, ImageQuality};
+use vello_common::pixmap::Pixmap;
 use vello_dev_macros::vello_test;
 
 fn rgb_img_10x10(ctx: &mut impl Renderer) -> ImageSource {
@@ -685,3 +687,79 @@ fn image_spritesheet_tinted(ctx: &mut impl Renderer) {
         cursor_x += glyph.width;
     }
 }
+
+fn color_space_tagged(ctx: &mut impl Renderer, color_space: ColorSpaceTag) {
+    let data = [
+        [128, 128, 128],
+        [200, 120, 60],
+        [60, 160, 200],
+        [128, 64, 192],
+    ]
+    .map(|[r, g, b]| PremulRgba8 { r, g, b, a: 255 })
+    .to_vec();
+    let mut pixmap = Pixmap::from_parts_with_opacity(data, 2, 2, false);
+    pixmap.set_color_space(color_space);
+    let image_source = ctx.get_image_source(Arc::new(pixmap));
+
+    ctx.set_paint_transform(Affine::scale(50.0));
+    ctx.set_paint(Image {
+        image: image_source,
+        sampler: ImageSampler::new().with_quality(ImageQuality::Low),
+    });
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 100.0, 100.0));
+}
+
+#[vello_test]
+fn image_color_space_linear_srgb(ctx: &mut impl Renderer) {
+    color_space_tagged(ctx, ColorSpaceTag::LinearSrgb);
+}
+
+#[vello_test]
+fn image_color_space_display_p3(ctx: &mut impl Renderer) {
+    color_space_tagged(ctx, ColorSpaceTag::DisplayP3);
+}
+
+#[vello_test]
+fn image_stroke(ctx: &mut impl Renderer) {
+    let image_source = rgb_img_10x10(ctx);
+
+    ctx.set_paint_transform(Affine::scale(2.0));
+    ctx.set_paint(Image {
+        image: image_source,
+        sampler: ImageSampler {
+            x_extend: Extend::Repeat,
+            y_extend: Extend::Reflect,
+            quality: ImageQuality::Low,
+            alpha: 1.0,
+        },
+    });
+    ctx.set_stroke(Stroke::new(8.0));
+    ctx.stroke_path(&crossed_line_star());
+}
+
+fn cross_fade(ctx: &mut impl Renderer, quality: ImageQuality) {
+    let from = ctx.register_image(load_image!("rgb_image_10x10"));
+    // The second image is smaller and gets stretched to the size of the first one.
+    let to = ctx.register_image(load_image!("rgb_image_2x3"));
+
+    for (i, t) in [0.0, 0.3, 0.7, 1.0].into_iter().enumerate() {
+        let x = 10.0 + 45.0 * (i % 2) as f64;
+        let y = 10.0 + 45.0 * (i / 2) as f64;
+        ctx.set_paint_transform(Affine::translate((x, y)) * Affine::scale(3.5));
+        ctx.set_paint(Image {
+            image: ImageSource::cross_fade(from, to, t),
+            sampler: ImageSampler::new().with_quality(quality),
+        });
+        ctx.fill_rect(&Rect::new(x, y, x + 35.0, y + 35.0));
+    }
+}
+
+#[vello_test]
+fn image_cross_fade_nearest(ctx: &mut impl Renderer) {
+    cross_fade(ctx, ImageQuality::Low);
+}
+
+#[vello_test]
+fn image_cross_fade_bilinear(ctx: &mut impl Renderer) {
+    cross_fade(ctx, ImageQuality::Medium);
+}
@@ -4,9 +4,9 @@
 use crate::renderer::Renderer;
 use smallvec::smallvec;
 use vello_common::color::DynamicColor;
-use vello_common::color::palette::css::{BLACK, LIME, RED, YELLOW};
-use vello_common::kurbo::{Point, Rect};
-use vello_common::mask::Mask;
+use vello_common::color::palette::css::{BLACK, BLUE, LIME, RED, WHITE, YELLOW};
+use vello_common::kurbo::{Affine, Circle, Point, Rect, Shape};
+use vello_common::mask::{Mask, MaskKind};
 use vello_common::peniko::{ColorStop, ColorStops, Gradient};
 use vello_cpu::peniko::LinearGradientPosition;
 use vello_cpu::{Level, RenderMode, RenderSettings};
@@ -89,3 +89,49 @@ fn mask_non_isolated(ctx: &mut impl Renderer) {
     ctx.set_paint(RED);
     ctx.fill_rect(&Rect::new(10.0, 10.0, 90.0, 90.0));
 }
+
+fn mask_group(ctx: &mut impl Renderer, kind: MaskKind) {
+    ctx.set_paint(BLACK);
+    ctx.fill_rect(&Rect::new(10.0, 10.0, 90.0, 90.0));
+    ctx.push_mask_group(kind);
+    // Opaque white, translucent white and opaque blue have different luminances but
+    // (partially) the same alphas.
+    ctx.set_paint(WHITE);
+    ctx.fill_rect(&Rect::new(10.0, 10.0, 50.0, 50.0));
+    ctx.set_paint(WHITE.with_alpha(0.5));
+    ctx.fill_rect(&Rect::new(50.0, 10.0, 90.0, 50.0));
+    ctx.set_paint(BLUE);
+    ctx.fill_path(&Circle::new((50.0, 70.0), 20.0).to_path(0.1));
+    ctx.end_mask();
+    ctx.set_paint(RED);
+    ctx.fill_rect(&Rect::new(10.0, 10.0, 90.0, 90.0));
+    ctx.pop_layer();
+}
+
+#[vello_test]
+fn mask_group_alpha(ctx: &mut impl Renderer) {
+    mask_group(ctx, MaskKind::Alpha);
+}
+
+#[vello_test]
+fn mask_group_luminance(ctx: &mut impl Renderer) {
+    mask_group(ctx, MaskKind::Luminance);
+}
+
+#[vello_test]
+fn mask_group_nested(ctx: &mut impl Renderer) {
+    ctx.push_mask_group(MaskKind::Alpha);
+    ctx.set_paint(BLACK);
+    ctx.fill_path(&Circle::new((50.0, 50.0), 40.0).to_path(0.1));
+    ctx.end_mask();
+    ctx.push_mask_group(MaskKind::Luminance);
+    ctx.set_transform(Affine::rotate_about(0.5, Point::new(50.0, 50.0)));
+    ctx.set_paint(WHITE);
+    ctx.fill_rect(&Rect::new(0.0, 30.0, 100.0, 70.0));
+    ctx.end_mask();
+    ctx.set_transform(Affine::IDENTITY);
+    ctx.set_paint(LIME);
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 100.0, 100.0));
+    ctx.pop_layer();
+    ctx.pop_layer();
+}
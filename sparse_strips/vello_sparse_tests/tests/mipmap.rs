@@ -0,0 +1,84 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::renderer::Renderer;
+use std::sync::Arc;
+use vello_common::color::PremulRgba8;
+use vello_common::kurbo::{Affine, Rect};
+use vello_common::paint::{Image, ImageSource};
+use vello_common::peniko::{Extend, ImageQuality, ImageSampler};
+use vello_common::pixmap::Pixmap;
+use vello_dev_macros::vello_test;
+
+/// A pixmap with alternating one-pixel columns of two colors, which aliases badly when it is
+/// sampled at full resolution while being minified.
+fn stripes(size: u16) -> Arc<Pixmap> {
+    let dark = PremulRgba8 {
+        r: 40,
+        g: 40,
+        b: 120,
+        a: 255,
+    };
+    let light = PremulRgba8 {
+        r: 250,
+        g: 200,
+        b: 60,
+        a: 255,
+    };
+    let data = (0..u32::from(size) * u32::from(size))
+        .map(|idx| {
+            if (idx % u32::from(size)) % 2 == 0 {
+                dark
+            } else {
+                light
+            }
+        })
+        .collect();
+
+    let mut pixmap = Pixmap::from_parts(data, size, size);
+    pixmap.set_may_have_transparency(false);
+    Arc::new(pixmap)
+}
+
+fn draw_minified(ctx: &mut impl Renderer, quality: ImageQuality, scales: &[f64]) {
+    let image = stripes(128);
+    let id = ctx.register_image_mipmapped(image);
+    let mut x = 4.0;
+
+    for scale in scales {
+        let size = 128.0 * scale;
+        ctx.set_paint_transform(Affine::translate((x, 4.0)) * Affine::scale(*scale));
+        ctx.set_paint(Image {
+            image: ImageSource::opaque_id(id),
+            sampler: ImageSampler {
+                x_extend: Extend::Pad,
+                y_extend: Extend::Pad,
+                quality,
+                alpha: 1.0,
+            },
+        });
+        ctx.fill_rect(&Rect::new(x, 4.0, x + size, 4.0 + size));
+        x += size + 4.0;
+    }
+}
+
+#[vello_test(width = 100, height = 40)]
+fn mipmap_minified_nearest(ctx: &mut impl Renderer) {
+    draw_minified(ctx, ImageQuality::Low, &[0.25, 0.2, 0.125]);
+}
+
+#[vello_test(width = 100, height = 40)]
+fn mipmap_minified_bilinear(ctx: &mut impl Renderer) {
+    draw_minified(ctx, ImageQuality::Medium, &[0.25, 0.2, 0.125]);
+}
+
+#[vello_test(width = 100, height = 40)]
+fn mipmap_magnified_is_unaffected(ctx: &mut impl Renderer) {
+    let id = ctx.register_image_mipmapped(stripes(8));
+    ctx.set_paint_transform(Affine::translate((4.0, 4.0)) * Affine::scale(4.0));
+    ctx.set_paint(Image {
+        image: ImageSource::opaque_id(id),
+        sampler: ImageSampler::new().with_quality(ImageQuality::Low),
+    });
+    ctx.fill_rect(&Rect::new(4.0, 4.0, 36.0, 36.0));
+}
@@ -312,3 +312,21 @@ fn mix_in_inner_layer(ctx: &mut impl Renderer) {
 
     ctx.pop_layer();
 }
+
+fn mix_root(ctx: &mut impl Renderer, mix: Mix) {
+    ctx.set_paint(ORANGE);
+    ctx.fill_rect(&Rect::new(10.0, 10.0, 70.0, 70.0));
+    ctx.set_blend_mode(BlendMode::new(mix, Compose::SrcOver));
+    ctx.set_paint(BLUE.with_alpha(0.8));
+    ctx.fill_rect(&Rect::new(30.0, 30.0, 90.0, 90.0));
+}
+
+#[vello_test]
+fn mix_root_multiply(ctx: &mut impl Renderer) {
+    mix_root(ctx, Mix::Multiply);
+}
+
+#[vello_test]
+fn mix_root_difference(ctx: &mut impl Renderer) {
+    mix_root(ctx, Mix::Difference);
+}
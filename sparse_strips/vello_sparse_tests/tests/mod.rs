@@ -34,6 +34,7 @@ mod basic;
 mod blurred_rounded_rect;
 mod clip;
 mod compose;
+mod custom_paint;
 mod default_blending_only_fast_path;
 mod external_texture;
 mod filter;
@@ -43,8 +44,12 @@ mod image;
 mod issues;
 mod layer;
 mod mask;
+mod mipmap;
 mod mix;
 mod opacity;
+#[cfg(all(target_arch = "wasm32", feature = "webgpu_parity"))]
+mod parity;
+mod pattern;
 mod renderer;
 mod scenes;
 #[macro_use]
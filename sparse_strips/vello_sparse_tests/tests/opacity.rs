@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use crate::renderer::Renderer;
-use vello_common::color::palette::css::{BLUE, GREEN, REBECCA_PURPLE, RED, YELLOW};
+use vello_common::color::palette::css::{BLUE, GREEN, LIGHT_GRAY, REBECCA_PURPLE, RED, YELLOW};
 use vello_common::kurbo::{Circle, Rect, Shape};
 use vello_dev_macros::vello_test;
 
@@ -32,3 +32,41 @@ fn opacity_nested_on_layer(ctx: &mut impl Renderer) {
     ctx.pop_layer();
     ctx.pop_layer();
 }
+
+#[vello_test]
+fn opacity_group_overlapping_shapes(ctx: &mut impl Renderer) {
+    ctx.set_paint(LIGHT_GRAY);
+    ctx.fill_rect(&Rect::new(0.0, 40.0, 100.0, 60.0));
+
+    let circles = |x: f64| [(x, 30.0), (x, 50.0), (x, 70.0)].map(|c| Circle::new(c, 18.0));
+
+    // Translucent shapes blend with each other where they overlap.
+    ctx.set_paint(BLUE.with_alpha(0.5));
+    for circle in circles(25.0) {
+        ctx.fill_path(&circle.to_path(0.1));
+    }
+
+    // In an opacity group, the overlapping shapes are composited first, and alpha is applied
+    // once to the whole group.
+    ctx.push_opacity_layer(0.5);
+    ctx.set_paint(BLUE);
+    for circle in circles(75.0) {
+        ctx.fill_path(&circle.to_path(0.1));
+    }
+    ctx.pop_layer();
+}
+
+#[vello_test]
+fn opacity_layer_out_of_range(ctx: &mut impl Renderer) {
+    // Opacities are clamped, so this is the same as drawing without a layer.
+    ctx.push_opacity_layer(1.5);
+    ctx.set_paint(BLUE.with_alpha(0.5));
+    ctx.fill_rect(&Rect::new(10.0, 10.0, 90.0, 50.0));
+    ctx.pop_layer();
+
+    // And this draws nothing.
+    ctx.push_opacity_layer(-1.0);
+    ctx.set_paint(RED);
+    ctx.fill_rect(&Rect::new(10.0, 50.0, 90.0, 90.0));
+    ctx.pop_layer();
+}
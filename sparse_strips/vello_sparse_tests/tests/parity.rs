@@ -0,0 +1,245 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Parity testing between the WebGL and the WebGPU backend of `vello_hybrid`.
+//!
+//! The two web backends run the same scheduler, but their shaders are written in different
+//! dialects (the GLSL shaders are generated from WGSL, with some manual adjustments). To catch
+//! them drifting apart, the `webgpu_parity` feature adds a `_hybrid_parity` variant to every
+//! `vello_test` that renders the scene with the WebGL renderer, renders the very same scene
+//! again with the `wgpu` renderer on top of WebGPU, and compares the two images pixel by pixel.
+//!
+//! ```sh
+//! wasm-pack test --headless --chrome --features webgpu_parity --release
+//! ```
+//!
+//! Images and patterns that a test registers with the WebGL renderer are registered with the
+//! WebGPU renderer in the same order, so that they end up with the same image ids. Cached glyphs
+//! only reach the atlas while rendering and can't be replayed that way, so tests that draw glyphs
+//! are skipped, as is everything if the browser doesn't support WebGPU.
+
+use crate::renderer::HybridRenderer;
+use crate::util::{append_diff_image_to_browser_document, get_diff, render_pixmap};
+use image::{RgbaImage, load_from_memory};
+use std::sync::Arc;
+use vello_common::paint::ImageId;
+use vello_common::pattern::Pattern;
+use vello_common::pixmap::Pixmap;
+use vello_hybrid::{
+    OutputColorConfig, RenderSize, RenderTargetConfig, Resources, Scene, TextureBindings,
+};
+use wasm_bindgen_futures::js_sys::Promise;
+use wasm_bindgen_test::console_log;
+
+/// The state of the WebGL renderer that needs to be mirrored in the WebGPU renderer.
+#[derive(Default)]
+pub(crate) struct ParityRecord {
+    /// The images and patterns registered with the WebGL renderer, in order.
+    pub(crate) uploads: Vec<ParityUpload>,
+    /// Whether the scene draws any glyphs.
+    pub(crate) draws_glyphs: bool,
+}
+
+/// An image or pattern that was registered with the WebGL renderer.
+pub(crate) enum ParityUpload {
+    Image(Arc<Pixmap>),
+    ImageMipmapped(Arc<Pixmap>),
+    Pattern(Pattern),
+}
+
+/// Render the scene of `ctx` with both web backends and panic if they diverge by more than
+/// `threshold` in more than `diff_pixels` pixels.
+pub(crate) async fn check_parity(
+    ctx: &mut HybridRenderer,
+    specific_name: &str,
+    threshold: u8,
+    diff_pixels: u32,
+) {
+    if ctx.parity_record().draws_glyphs {
+        console_log!("{specific_name}: glyphs can't be mirrored, skipping the parity check");
+        return;
+    }
+    let Some(webgpu) = render_webgpu(ctx.scene(), &ctx.parity_record().uploads).await else {
+        console_log!("{specific_name}: WebGPU is not available, skipping the parity check");
+        return;
+    };
+    let webgl = render_pixmap(ctx);
+
+    if let Some((diff_image, diff_data)) =
+        get_diff(&to_image(webgpu), &to_image(webgl), threshold, diff_pixels)
+    {
+        let max_difference = diff_data.iter().fold([0; 4], |mut max, p| {
+            for (m, d) in max.iter_mut().zip(&p.difference) {
+                *m = (*m).max(d.abs());
+            }
+            max
+        });
+        append_diff_image_to_browser_document(specific_name, &diff_image);
+        panic!(
+            "WebGL output diverges from WebGPU in {} pixels, with a maximum difference of \
+             {max_difference:?} (RGBA). Scroll to bottom of browser to view diff \
+             (WebGPU | Diff | WebGL).",
+            diff_data.len()
+        );
+    }
+}
+
+fn to_image(pixmap: Pixmap) -> RgbaImage {
+    load_from_memory(&pixmap.into_png().unwrap())
+        .unwrap()
+        .into_rgba8()
+}
+
+/// Render `scene` with the `wgpu` renderer on top of WebGPU, returning `None` if WebGPU isn't
+/// supported by the browser.
+async fn render_webgpu(scene: &Scene, uploads: &[ParityUpload]) -> Option<Pixmap> {
+    if !wgpu::util::is_browser_webgpu_supported().await {
+        return None;
+    }
+
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+        backends: wgpu::Backends::BROWSER_WEBGPU,
+        ..wgpu::InstanceDescriptor::new_without_display_handle()
+    });
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions::default())
+        .await
+        .ok()?;
+    let (device, queue) = adapter
+        .request_device(&wgpu::DeviceDescriptor {
+            label: Some("Parity Device"),
+            ..Default::default()
+        })
+        .await
+        .ok()?;
+
+    let width = scene.width();
+    let height = scene.height();
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Parity Render Target"),
+        size: wgpu::Extent3d {
+            width: width.into(),
+            height: height.into(),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    let mut renderer = vello_hybrid::Renderer::new(
+        &device,
+        &RenderTargetConfig {
+            format: texture.format(),
+            width: width.into(),
+            height: height.into(),
+            output_color: OutputColorConfig::default(),
+        },
+    );
+
+    // Replaying the uploads in order yields the same image ids as in the WebGL renderer.
+    let mut resources = Resources::new();
+    for upload in uploads {
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Parity Upload"),
+        });
+        let _: ImageId = match upload {
+            ParityUpload::Image(pixmap) => {
+                renderer.upload_image(&mut resources, &device, &queue, &mut encoder, pixmap)
+            }
+            ParityUpload::ImageMipmapped(pixmap) => renderer.upload_image_mipmapped(
+                &mut resources,
+                &device,
+                &queue,
+                &mut encoder,
+                pixmap.clone(),
+            ),
+            ParityUpload::Pattern(pattern) => renderer
+                .upload_pattern(&mut resources, &device, &queue, pattern)
+                .unwrap(),
+        };
+        queue.submit([encoder.finish()]);
+    }
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Parity Render"),
+    });
+    renderer
+        .render(
+            scene,
+            &mut resources,
+            &device,
+            &queue,
+            &mut encoder,
+            &RenderSize {
+                width: width.into(),
+                height: height.into(),
+            },
+            &texture_view,
+            &TextureBindings::new(),
+        )
+        .unwrap();
+
+    let bytes_per_row = (u32::from(width) * 4).next_multiple_of(256);
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Parity Readback Buffer"),
+        size: u64::from(bytes_per_row) * u64::from(height),
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    encoder.copy_texture_to_buffer(
+        wgpu::TexelCopyTextureInfo {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: None,
+            },
+        },
+        wgpu::Extent3d {
+            width: width.into(),
+            height: height.into(),
+            depth_or_array_layers: 1,
+        },
+    );
+    queue.submit([encoder.finish()]);
+
+    // The browser resolves the mapping asynchronously, so polling the device doesn't help.
+    let mapped = Promise::new(&mut |resolve, reject| {
+        buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let callback = if result.is_ok() { &resolve } else { &reject };
+                callback.call0(&wasm_bindgen::JsValue::NULL).unwrap();
+            });
+    });
+    wasm_bindgen_futures::JsFuture::from(mapped)
+        .await
+        .expect("failed to map the WebGPU readback buffer");
+
+    let mut pixmap = Pixmap::new(width, height);
+    for (row, buf) in buffer
+        .slice(..)
+        .get_mapped_range()
+        .chunks_exact(bytes_per_row as usize)
+        .zip(
+            pixmap
+                .data_as_u8_slice_mut()
+                .chunks_exact_mut(width as usize * 4),
+        )
+    {
+        buf.copy_from_slice(&row[0..width as usize * 4]);
+    }
+    buffer.unmap();
+
+    Some(pixmap)
+}
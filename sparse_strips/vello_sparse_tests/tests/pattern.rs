@@ -0,0 +1,127 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::load_image;
+use crate::renderer::Renderer;
+use crate::util::{circular_star, stops_blue_green_red_yellow};
+use vello_common::color::palette::css::{
+    BLACK, CORNFLOWER_BLUE, DARK_SLATE_BLUE, LIGHT_GRAY, ORANGE, WHITE,
+};
+use vello_common::kurbo::{Affine, BezPath, Circle, Point, Rect, Shape, Stroke, Vec2};
+use vello_common::paint::{Image, ImageSource};
+use vello_common::pattern::Pattern;
+use vello_common::peniko::{Extend, Gradient, ImageQuality, ImageSampler};
+use vello_dev_macros::vello_test;
+
+fn hatching() -> Pattern {
+    let mut pattern = Pattern::new(Rect::new(0.0, 0.0, 8.0, 8.0), Vec2::ZERO);
+    let mut line = BezPath::new();
+    line.move_to((-2.0, 10.0));
+    line.line_to((10.0, -2.0));
+    pattern.set_paint(DARK_SLATE_BLUE);
+    pattern.set_stroke(Stroke::new(2.0));
+    pattern.stroke_path(&line);
+    pattern
+}
+
+fn fill_with_pattern(ctx: &mut impl Renderer, pattern: &Pattern, paint_transform: Affine) {
+    let id = ctx.register_pattern(pattern);
+    ctx.set_paint_transform(paint_transform * pattern.cell_transform());
+    ctx.set_paint(pattern.paint(ImageSource::opaque_id(id)));
+}
+
+#[vello_test]
+fn pattern_hatching(ctx: &mut impl Renderer) {
+    let pattern = hatching();
+    fill_with_pattern(ctx, &pattern, Affine::IDENTITY);
+    ctx.fill_path(&circular_star(Point::new(50.0, 50.0), 13, 25.0, 45.0));
+}
+
+#[vello_test]
+fn pattern_with_spacing(ctx: &mut impl Renderer) {
+    let mut pattern = Pattern::new(Rect::new(0.0, 0.0, 10.0, 10.0), Vec2::new(6.0, 4.0));
+    pattern.set_paint(ORANGE);
+    pattern.fill_path(&Circle::new((5.0, 5.0), 5.0).to_path(0.1));
+    pattern.set_paint(BLACK);
+    pattern.fill_rect(&Rect::new(4.0, 4.0, 6.0, 6.0));
+
+    fill_with_pattern(ctx, &pattern, Affine::IDENTITY);
+    ctx.fill_rect(&Rect::new(4.0, 4.0, 96.0, 96.0));
+}
+
+#[vello_test]
+fn pattern_tile_offset_and_clip(ctx: &mut impl Renderer) {
+    // The tile doesn't start at the origin, and the circle overflows the tile,
+    // so it must be clipped to the tile rectangle and the spacing between tiles
+    // must stay empty.
+    let mut pattern = Pattern::new(Rect::new(20.0, 20.0, 36.0, 36.0), Vec2::new(4.0, 4.0));
+    pattern.set_paint(LIGHT_GRAY);
+    pattern.fill_rect(&Rect::new(20.0, 20.0, 36.0, 36.0));
+    pattern.set_paint(CORNFLOWER_BLUE);
+    pattern.fill_path(&Circle::new((36.0, 36.0), 10.0).to_path(0.1));
+
+    fill_with_pattern(ctx, &pattern, Affine::IDENTITY);
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 100.0, 100.0));
+}
+
+#[vello_test]
+fn pattern_with_transform(ctx: &mut impl Renderer) {
+    let mut pattern = Pattern::new(Rect::new(0.0, 0.0, 12.0, 12.0), Vec2::ZERO);
+    pattern.set_paint(WHITE);
+    pattern.fill_rect(&Rect::new(0.0, 0.0, 12.0, 12.0));
+    pattern.set_transform(Affine::translate((6.0, 6.0)));
+    pattern.set_paint(CORNFLOWER_BLUE);
+    pattern.fill_rect(&Rect::new(-3.0, -3.0, 3.0, 3.0));
+
+    fill_with_pattern(
+        ctx,
+        &pattern,
+        Affine::rotate_about(0.3, Point::new(50.0, 50.0)),
+    );
+    ctx.fill_path(&Circle::new((50.0, 50.0), 40.0).to_path(0.1));
+}
+
+#[vello_test]
+fn pattern_with_gradients(ctx: &mut impl Renderer) {
+    // Hatching whose strokes are painted with a gradient spanning the tile.
+    let mut pattern = Pattern::new(Rect::new(0.0, 0.0, 12.0, 12.0), Vec2::ZERO);
+    pattern.set_paint(
+        Gradient::new_linear((0.0, 0.0), (12.0, 12.0))
+            .with_stops(stops_blue_green_red_yellow().as_slice()),
+    );
+    pattern.fill_rect(&Rect::new(0.0, 0.0, 12.0, 12.0));
+    let mut line = BezPath::new();
+    line.move_to((-2.0, 14.0));
+    line.line_to((14.0, -2.0));
+    pattern.set_paint(
+        Gradient::new_radial((6.0, 6.0), 8.0).with_stops([WHITE, DARK_SLATE_BLUE].as_slice()),
+    );
+    pattern.set_stroke(Stroke::new(3.0));
+    pattern.stroke_path(&line);
+
+    fill_with_pattern(ctx, &pattern, Affine::IDENTITY);
+    ctx.fill_path(&Circle::new((50.0, 50.0), 45.0).to_path(0.1));
+}
+
+#[vello_test]
+fn pattern_with_image(ctx: &mut impl Renderer) {
+    // A textured tile, with the image scaled up and placed with the paint transform.
+    let image = ctx.get_image_source(load_image!("rgb_image_10x10"));
+    let mut pattern = Pattern::new(Rect::new(0.0, 0.0, 24.0, 24.0), Vec2::new(4.0, 4.0));
+    pattern.set_paint(ORANGE);
+    pattern.fill_rect(&Rect::new(0.0, 0.0, 24.0, 24.0));
+    pattern.set_paint_transform(Affine::translate((2.0, 2.0)).then_scale(2.0));
+    pattern.set_paint(Image {
+        image,
+        sampler: ImageSampler {
+            x_extend: Extend::Pad,
+            y_extend: Extend::Pad,
+            quality: ImageQuality::Low,
+            alpha: 1.0,
+        },
+    });
+    pattern.fill_path(&Circle::new((12.0, 12.0), 10.0).to_path(0.1));
+
+    fill_with_pattern(ctx, &pattern, Affine::IDENTITY);
+    ctx.fill_rect(&Rect::new(4.0, 4.0, 96.0, 96.0));
+}
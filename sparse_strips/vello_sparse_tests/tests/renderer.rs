@@ -6,16 +6,24 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use glifo::GlyphRunBackend;
+use vello_common::chart::Hairlines;
+use vello_common::compact_path::CompactPath;
+use vello_common::custom_paint::{CustomPaint, CustomPaintId, CustomPaintKind};
+use vello_common::dash::MeasuredPath;
 use vello_common::filter_effects::Filter;
-use vello_common::kurbo::{Affine, BezPath, Rect, Stroke};
-use vello_common::mask::Mask;
+use vello_common::kurbo::{Affine, BezPath, Rect, Stroke, Vec2};
+use vello_common::load_op::LoadOp;
+use vello_common::mask::{Mask, MaskKind};
 use vello_common::paint::{ImageId, ImageSource, PaintType, Tint};
+use vello_common::pattern::Pattern;
 use vello_common::peniko::{BlendMode, Fill, FontData, ImageQuality};
 use vello_common::pixmap::Pixmap;
+use vello_common::simplify::LodPath;
+use vello_common::variable_width::VariableWidthStroke;
 use vello_cpu::{Level, RenderContext, RenderMode, RenderSettings, Resources};
 use vello_hybrid::{
     RenderSettings as HybridRenderSettings, Resources as HybridResources, SampleRect, Scene,
-    SceneConstraints, TextureId,
+    SceneConstraints, TextureId, TexturePaint,
 };
 #[cfg(all(target_arch = "wasm32", feature = "webgl"))]
 use web_sys::WebGl2RenderingContext;
@@ -35,8 +43,17 @@ pub(crate) trait Renderer: Sized {
     ) -> Self;
     fn fill_path(&mut self, path: &BezPath);
     fn stroke_path(&mut self, path: &BezPath);
+    fn stroke_measured_path(&mut self, path: &MeasuredPath);
+    fn stroke_variable_width(&mut self, stroke: &VariableWidthStroke);
+    fn fill_hairlines(&mut self, lines: &Hairlines);
+    fn fill_compact_path(&mut self, path: CompactPath<'_>);
+    fn fill_lod_path(&mut self, path: &LodPath);
     fn fill_rect(&mut self, rect: &Rect);
     fn fill_blurred_rounded_rect(&mut self, rect: &Rect, radius: f32, std_dev: f32);
+    fn fill_drop_shadow(&mut self, path: &BezPath, offset: Vec2, std_dev: f32);
+    fn register_custom_paint(&mut self, kind: Arc<dyn CustomPaintKind>) -> CustomPaintId;
+    fn fill_custom_paint(&mut self, path: &BezPath, paint: &CustomPaint);
+    fn stroke_custom_paint(&mut self, path: &BezPath, paint: &CustomPaint);
     fn stroke_rect(&mut self, rect: &Rect);
     fn glyph_run(
         &mut self,
@@ -56,17 +73,24 @@ pub(crate) trait Renderer: Sized {
     fn push_blend_layer(&mut self, blend_mode: BlendMode);
     fn push_opacity_layer(&mut self, opacity: f32);
     fn push_mask_layer(&mut self, mask: Mask);
+    fn push_mask_group(&mut self, kind: MaskKind);
+    fn end_mask(&mut self);
     fn push_filter_layer(&mut self, filter: Filter);
+    fn push_blur_layer(&mut self, radius: f32);
+    fn push_backdrop_blur_layer(&mut self, radius: f32, clip: &BezPath);
     fn pop_layer(&mut self);
     fn pop_clip_path(&mut self);
     fn set_stroke(&mut self, stroke: Stroke);
     fn set_mask(&mut self, mask: Mask);
     fn set_paint(&mut self, paint: impl Into<PaintType>);
     fn set_tint(&mut self, tint: Option<Tint>);
+    fn set_gradient_hints(&mut self, hints: &[Option<f32>]);
     fn set_paint_transform(&mut self, affine: Affine);
     fn set_fill_rule(&mut self, fill_rule: Fill);
     fn set_transform(&mut self, transform: Affine);
     fn set_aliasing_threshold(&mut self, aliasing_threshold: Option<u8>);
+    fn set_pixel_snapping(&mut self, snap_to_pixels: bool);
+    fn set_load_op(&mut self, load_op: LoadOp);
     fn set_blend_mode(&mut self, blend_mode: BlendMode);
     fn set_filter_effect(&mut self, filter: Filter);
     fn reset_filter_effect(&mut self);
@@ -95,8 +119,18 @@ pub(crate) trait Renderer: Sized {
         quality: ImageQuality,
         rects: impl IntoIterator<Item = SampleRect>,
     );
+    #[cfg_attr(
+        all(target_arch = "wasm32", feature = "webgl"),
+        expect(
+            dead_code,
+            reason = "external textures are not wired up for the WebGL backend"
+        )
+    )]
+    fn set_texture_paint(&mut self, paint: TexturePaint);
     fn get_image_source(&mut self, pixmap: Arc<Pixmap>) -> ImageSource;
     fn register_image(&mut self, pixmap: Arc<Pixmap>) -> ImageId;
+    fn register_image_mipmapped(&mut self, pixmap: Arc<Pixmap>) -> ImageId;
+    fn register_pattern(&mut self, pattern: &Pattern) -> ImageId;
 }
 
 pub(crate) struct CpuRenderer {
@@ -134,6 +168,26 @@ impl Renderer for CpuRenderer {
         self.ctx.stroke_path(path);
     }
 
+    fn stroke_measured_path(&mut self, path: &MeasuredPath) {
+        self.ctx.stroke_measured_path(path);
+    }
+
+    fn stroke_variable_width(&mut self, stroke: &VariableWidthStroke) {
+        self.ctx.stroke_variable_width(stroke);
+    }
+
+    fn fill_hairlines(&mut self, lines: &Hairlines) {
+        self.ctx.fill_hairlines(lines);
+    }
+
+    fn fill_compact_path(&mut self, path: CompactPath<'_>) {
+        self.ctx.fill_compact_path(path);
+    }
+
+    fn fill_lod_path(&mut self, path: &LodPath) {
+        self.ctx.fill_lod_path(path);
+    }
+
     fn fill_rect(&mut self, rect: &Rect) {
         self.ctx.fill_rect(rect);
     }
@@ -142,6 +196,22 @@ impl Renderer for CpuRenderer {
         self.ctx.fill_blurred_rounded_rect(rect, radius, std_dev);
     }
 
+    fn fill_drop_shadow(&mut self, path: &BezPath, offset: Vec2, std_dev: f32) {
+        self.ctx.fill_drop_shadow(path, offset, std_dev);
+    }
+
+    fn register_custom_paint(&mut self, kind: Arc<dyn CustomPaintKind>) -> CustomPaintId {
+        self.ctx.register_custom_paint(kind)
+    }
+
+    fn fill_custom_paint(&mut self, path: &BezPath, paint: &CustomPaint) {
+        self.ctx.fill_custom_paint(path, paint);
+    }
+
+    fn stroke_custom_paint(&mut self, path: &BezPath, paint: &CustomPaint) {
+        self.ctx.stroke_custom_paint(path, paint);
+    }
+
     fn stroke_rect(&mut self, rect: &Rect) {
         self.ctx.stroke_rect(rect);
     }
@@ -189,10 +259,26 @@ impl Renderer for CpuRenderer {
         self.ctx.push_mask_layer(mask);
     }
 
+    fn push_mask_group(&mut self, kind: MaskKind) {
+        self.ctx.push_mask_group(kind);
+    }
+
+    fn end_mask(&mut self) {
+        self.ctx.end_mask();
+    }
+
     fn push_filter_layer(&mut self, filter: Filter) {
         self.ctx.push_filter_layer(filter);
     }
 
+    fn push_blur_layer(&mut self, radius: f32) {
+        self.ctx.push_blur_layer(radius);
+    }
+
+    fn push_backdrop_blur_layer(&mut self, radius: f32, clip: &BezPath) {
+        self.ctx.push_backdrop_blur_layer(radius, clip);
+    }
+
     fn pop_layer(&mut self) {
         self.ctx.pop_layer();
     }
@@ -217,6 +303,10 @@ impl Renderer for CpuRenderer {
         self.ctx.set_tint(tint);
     }
 
+    fn set_gradient_hints(&mut self, hints: &[Option<f32>]) {
+        self.ctx.set_gradient_hints(hints);
+    }
+
     fn set_paint_transform(&mut self, affine: Affine) {
         self.ctx.set_paint_transform(affine);
     }
@@ -233,6 +323,14 @@ impl Renderer for CpuRenderer {
         self.ctx.set_aliasing_threshold(aliasing_threshold);
     }
 
+    fn set_pixel_snapping(&mut self, snap_to_pixels: bool) {
+        self.ctx.set_pixel_snapping(snap_to_pixels);
+    }
+
+    fn set_load_op(&mut self, load_op: LoadOp) {
+        self.ctx.set_load_op(load_op);
+    }
+
     fn set_blend_mode(&mut self, blend_mode: BlendMode) {
         self.ctx.set_blend_mode(blend_mode);
     }
@@ -274,6 +372,10 @@ impl Renderer for CpuRenderer {
         unimplemented!("external textures are only supported by hybrid renderer tests")
     }
 
+    fn set_texture_paint(&mut self, _: TexturePaint) {
+        unimplemented!("external textures are only supported by hybrid renderer tests")
+    }
+
     fn get_image_source(&mut self, pixmap: Arc<Pixmap>) -> ImageSource {
         let id = self.resources.register_image(Arc::clone(&pixmap));
         ImageSource::opaque_id_with_transparency_hint(id, pixmap.may_have_transparency())
@@ -282,6 +384,14 @@ impl Renderer for CpuRenderer {
     fn register_image(&mut self, pixmap: Arc<Pixmap>) -> ImageId {
         self.resources.register_image(pixmap)
     }
+
+    fn register_image_mipmapped(&mut self, pixmap: Arc<Pixmap>) -> ImageId {
+        self.resources.register_image_mipmapped(pixmap)
+    }
+
+    fn register_pattern(&mut self, pattern: &Pattern) -> ImageId {
+        self.resources.register_pattern(pattern)
+    }
 }
 
 #[cfg(not(all(target_arch = "wasm32", feature = "webgl")))]
@@ -345,6 +455,7 @@ impl HybridRenderer {
                 format: texture.format(),
                 width: width.into(),
                 height: height.into(),
+                output_color: vello_hybrid::OutputColorConfig::default(),
             },
         );
 
@@ -379,6 +490,89 @@ impl HybridRenderer {
         self.queue.submit([encoder.finish()]);
         image_id
     }
+
+    fn create_external_texture(&self, pixmap: &Pixmap) -> wgpu::TextureView {
+        let width = u32::from(pixmap.width());
+        let height = u32::from(pixmap.height());
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Test External Texture"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        self.queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            pixmap.data_as_u8_slice(),
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(width * 4),
+                rows_per_image: None,
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        texture.create_view(&wgpu::TextureViewDescriptor::default())
+    }
+
+    /// Create an external texture from `pixmap` and register it with the renderer, which
+    /// releases it once rendered scenes stop referencing it.
+    pub(crate) fn register_texture(&mut self, pixmap: Arc<Pixmap>) -> TextureId {
+        let view = self.create_external_texture(&pixmap);
+        self.renderer.register_texture(view)
+    }
+
+    /// Render `scene` into a new texture that is registered with the renderer.
+    pub(crate) fn render_to_texture(&mut self, scene: &Scene) -> TextureId {
+        let render_size = vello_hybrid::RenderSize {
+            width: scene.width().into(),
+            height: scene.height().into(),
+        };
+        self.renderer
+            .render_to_texture(
+                scene,
+                &mut self.resources,
+                &self.device,
+                &self.queue,
+                &render_size,
+                &vello_hybrid::TextureBindings::new(),
+            )
+            .unwrap()
+    }
+
+    pub(crate) fn is_texture_registered(&self, texture_id: TextureId) -> bool {
+        self.renderer.is_texture_registered(texture_id)
+    }
+
+    /// Release a registered texture, returning whether it was still registered.
+    pub(crate) fn unregister_texture(&mut self, texture_id: TextureId) -> bool {
+        self.renderer.unregister_texture(texture_id).is_some()
+    }
+
+    /// Return the bind group the renderer keeps for drawing the external texture `texture_id`.
+    pub(crate) fn external_paint_source_bind_group(
+        &self,
+        texture_id: TextureId,
+    ) -> Option<wgpu::BindGroup> {
+        self.renderer
+            .external_paint_source_bind_group(texture_id)
+            .cloned()
+    }
 }
 
 #[cfg(not(all(target_arch = "wasm32", feature = "webgl")))]
@@ -414,6 +608,26 @@ impl Renderer for HybridRenderer {
         self.scene.stroke_path(path);
     }
 
+    fn stroke_measured_path(&mut self, path: &MeasuredPath) {
+        self.scene.stroke_measured_path(path);
+    }
+
+    fn stroke_variable_width(&mut self, stroke: &VariableWidthStroke) {
+        self.scene.stroke_variable_width(stroke);
+    }
+
+    fn fill_hairlines(&mut self, lines: &Hairlines) {
+        self.scene.fill_hairlines(lines);
+    }
+
+    fn fill_compact_path(&mut self, path: CompactPath<'_>) {
+        self.scene.fill_compact_path(path);
+    }
+
+    fn fill_lod_path(&mut self, path: &LodPath) {
+        self.scene.fill_lod_path(path);
+    }
+
     fn fill_rect(&mut self, rect: &Rect) {
         self.scene.fill_rect(rect);
     }
@@ -422,6 +636,22 @@ impl Renderer for HybridRenderer {
         self.scene.fill_blurred_rounded_rect(rect, radius, std_dev);
     }
 
+    fn fill_drop_shadow(&mut self, path: &BezPath, offset: Vec2, std_dev: f32) {
+        self.scene.fill_drop_shadow(path, offset, std_dev);
+    }
+
+    fn register_custom_paint(&mut self, kind: Arc<dyn CustomPaintKind>) -> CustomPaintId {
+        self.scene.register_custom_paint(kind)
+    }
+
+    fn fill_custom_paint(&mut self, path: &BezPath, paint: &CustomPaint) {
+        self.scene.fill_custom_paint(path, paint);
+    }
+
+    fn stroke_custom_paint(&mut self, path: &BezPath, paint: &CustomPaint) {
+        self.scene.stroke_custom_paint(path, paint);
+    }
+
     fn stroke_rect(&mut self, rect: &Rect) {
         self.scene.stroke_rect(rect);
     }
@@ -468,10 +698,26 @@ impl Renderer for HybridRenderer {
         self.scene.push_mask_layer(mask);
     }
 
+    fn push_mask_group(&mut self, kind: MaskKind) {
+        self.scene.push_mask_group(kind);
+    }
+
+    fn end_mask(&mut self) {
+        self.scene.end_mask();
+    }
+
     fn push_filter_layer(&mut self, filter: Filter) {
         self.scene.push_filter_layer(filter);
     }
 
+    fn push_blur_layer(&mut self, radius: f32) {
+        self.scene.push_blur_layer(radius);
+    }
+
+    fn push_backdrop_blur_layer(&mut self, radius: f32, clip: &BezPath) {
+        self.scene.push_backdrop_blur_layer(radius, clip);
+    }
+
     fn pop_layer(&mut self) {
         self.scene.pop_layer();
     }
@@ -496,6 +742,10 @@ impl Renderer for HybridRenderer {
         self.scene.set_tint(tint);
     }
 
+    fn set_gradient_hints(&mut self, hints: &[Option<f32>]) {
+        self.scene.set_gradient_hints(hints);
+    }
+
     fn set_paint_transform(&mut self, affine: Affine) {
         self.scene.set_paint_transform(affine);
     }
@@ -508,14 +758,22 @@ impl Renderer for HybridRenderer {
         self.scene.set_transform(transform);
     }
 
-    fn set_blend_mode(&mut self, _: BlendMode) {
-        unimplemented!()
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.scene.set_blend_mode(blend_mode);
     }
 
     fn set_aliasing_threshold(&mut self, aliasing_threshold: Option<u8>) {
         self.scene.set_aliasing_threshold(aliasing_threshold);
     }
 
+    fn set_pixel_snapping(&mut self, snap_to_pixels: bool) {
+        self.scene.set_pixel_snapping(snap_to_pixels);
+    }
+
+    fn set_load_op(&mut self, load_op: LoadOp) {
+        self.scene.set_load_op(load_op);
+    }
+
     fn set_filter_effect(&mut self, filter: Filter) {
         self.scene.set_filter_effect(filter);
     }
@@ -651,42 +909,7 @@ impl Renderer for HybridRenderer {
         let texture_id = TextureId(self.next_external_texture_id);
         self.next_external_texture_id += 1;
 
-        let width = u32::from(pixmap.width());
-        let height = u32::from(pixmap.height());
-        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Test External Texture"),
-            size: wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8Unorm,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-            view_formats: &[],
-        });
-        self.queue.write_texture(
-            wgpu::TexelCopyTextureInfo {
-                texture: &texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            pixmap.data_as_u8_slice(),
-            wgpu::TexelCopyBufferLayout {
-                offset: 0,
-                bytes_per_row: Some(width * 4),
-                rows_per_image: None,
-            },
-            wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
-        );
-        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let view = self.create_external_texture(&pixmap);
         self.external_textures.insert(texture_id, view);
         texture_id
     }
@@ -700,6 +923,10 @@ impl Renderer for HybridRenderer {
         self.scene.draw_texture_rects(texture_id, quality, rects);
     }
 
+    fn set_texture_paint(&mut self, paint: TexturePaint) {
+        self.scene.set_texture_paint(paint);
+    }
+
     fn get_image_source(&mut self, pixmap: Arc<Pixmap>) -> ImageSource {
         let image_id = self.upload_image_with_resources(&pixmap, "Upload Test Image");
         ImageSource::opaque_id_with_transparency_hint(image_id, pixmap.may_have_transparency())
@@ -708,6 +935,29 @@ impl Renderer for HybridRenderer {
     fn register_image(&mut self, pixmap: Arc<Pixmap>) -> ImageId {
         self.upload_image_with_resources(&pixmap, "Register Test Image")
     }
+
+    fn register_image_mipmapped(&mut self, pixmap: Arc<Pixmap>) -> ImageId {
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Register Mipmapped Test Image"),
+            });
+        let image_id = self.renderer.upload_image_mipmapped(
+            &mut self.resources,
+            &self.device,
+            &self.queue,
+            &mut encoder,
+            pixmap,
+        );
+        self.queue.submit([encoder.finish()]);
+        image_id
+    }
+
+    fn register_pattern(&mut self, pattern: &Pattern) -> ImageId {
+        self.renderer
+            .upload_pattern(&mut self.resources, &self.device, &self.queue, pattern)
+            .unwrap()
+    }
 }
 
 #[cfg(all(target_arch = "wasm32", feature = "webgl"))]
@@ -716,13 +966,29 @@ pub(crate) struct HybridRenderer {
     resources: HybridResources,
     renderer: vello_hybrid::WebGlRenderer,
     gl: WebGl2RenderingContext,
+    #[cfg(feature = "webgpu_parity")]
+    parity_record: crate::parity::ParityRecord,
 }
 
 #[cfg(all(target_arch = "wasm32", feature = "webgl"))]
 impl HybridRenderer {
     fn upload_image(&mut self, pixmap: &Arc<Pixmap>) -> ImageId {
+        #[cfg(feature = "webgpu_parity")]
+        self.parity_record
+            .uploads
+            .push(crate::parity::ParityUpload::Image(pixmap.clone()));
         self.renderer.upload_image(&mut self.resources, pixmap)
     }
+
+    #[cfg(feature = "webgpu_parity")]
+    pub(crate) fn scene(&self) -> &Scene {
+        &self.scene
+    }
+
+    #[cfg(feature = "webgpu_parity")]
+    pub(crate) fn parity_record(&self) -> &crate::parity::ParityRecord {
+        &self.parity_record
+    }
 }
 
 #[cfg(all(target_arch = "wasm32", feature = "webgl"))]
@@ -775,6 +1041,8 @@ impl Renderer for HybridRenderer {
             resources: HybridResources::new(),
             renderer,
             gl,
+            #[cfg(feature = "webgpu_parity")]
+            parity_record: Default::default(),
         }
     }
 
@@ -782,14 +1050,34 @@ impl Renderer for HybridRenderer {
         self.scene.fill_path(path);
     }
 
-    fn set_blend_mode(&mut self, _: BlendMode) {
-        unimplemented!()
+    fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.scene.set_blend_mode(blend_mode);
     }
 
     fn stroke_path(&mut self, path: &BezPath) {
         self.scene.stroke_path(path);
     }
 
+    fn stroke_measured_path(&mut self, path: &MeasuredPath) {
+        self.scene.stroke_measured_path(path);
+    }
+
+    fn stroke_variable_width(&mut self, stroke: &VariableWidthStroke) {
+        self.scene.stroke_variable_width(stroke);
+    }
+
+    fn fill_hairlines(&mut self, lines: &Hairlines) {
+        self.scene.fill_hairlines(lines);
+    }
+
+    fn fill_compact_path(&mut self, path: CompactPath<'_>) {
+        self.scene.fill_compact_path(path);
+    }
+
+    fn fill_lod_path(&mut self, path: &LodPath) {
+        self.scene.fill_lod_path(path);
+    }
+
     fn fill_rect(&mut self, rect: &Rect) {
         self.scene.fill_rect(rect);
     }
@@ -798,6 +1086,22 @@ impl Renderer for HybridRenderer {
         self.scene.fill_blurred_rounded_rect(rect, radius, std_dev);
     }
 
+    fn fill_drop_shadow(&mut self, path: &BezPath, offset: Vec2, std_dev: f32) {
+        self.scene.fill_drop_shadow(path, offset, std_dev);
+    }
+
+    fn register_custom_paint(&mut self, kind: Arc<dyn CustomPaintKind>) -> CustomPaintId {
+        self.scene.register_custom_paint(kind)
+    }
+
+    fn fill_custom_paint(&mut self, path: &BezPath, paint: &CustomPaint) {
+        self.scene.fill_custom_paint(path, paint);
+    }
+
+    fn stroke_custom_paint(&mut self, path: &BezPath, paint: &CustomPaint) {
+        self.scene.stroke_custom_paint(path, paint);
+    }
+
     fn stroke_rect(&mut self, rect: &Rect) {
         self.scene.stroke_rect(rect);
     }
@@ -806,6 +1110,10 @@ impl Renderer for HybridRenderer {
         &mut self,
         font: &FontData,
     ) -> glifo::GlyphRunBuilder<'_, Self::GlyphRunBackend<'_>> {
+        #[cfg(feature = "webgpu_parity")]
+        {
+            self.parity_record.draws_glyphs = true;
+        }
         self.scene.glyph_run(&mut self.resources, font)
     }
 
@@ -843,10 +1151,26 @@ impl Renderer for HybridRenderer {
         unimplemented!()
     }
 
+    fn push_mask_group(&mut self, kind: MaskKind) {
+        self.scene.push_mask_group(kind);
+    }
+
+    fn end_mask(&mut self) {
+        self.scene.end_mask();
+    }
+
     fn push_filter_layer(&mut self, filter: Filter) {
         self.scene.push_filter_layer(filter);
     }
 
+    fn push_blur_layer(&mut self, radius: f32) {
+        self.scene.push_blur_layer(radius);
+    }
+
+    fn push_backdrop_blur_layer(&mut self, radius: f32, clip: &BezPath) {
+        self.scene.push_backdrop_blur_layer(radius, clip);
+    }
+
     fn pop_layer(&mut self) {
         self.scene.pop_layer();
     }
@@ -871,6 +1195,10 @@ impl Renderer for HybridRenderer {
         self.scene.set_tint(tint);
     }
 
+    fn set_gradient_hints(&mut self, hints: &[Option<f32>]) {
+        self.scene.set_gradient_hints(hints);
+    }
+
     fn set_paint_transform(&mut self, affine: Affine) {
         self.scene.set_paint_transform(affine);
     }
@@ -887,6 +1215,14 @@ impl Renderer for HybridRenderer {
         self.scene.set_aliasing_threshold(aliasing_threshold);
     }
 
+    fn set_pixel_snapping(&mut self, snap_to_pixels: bool) {
+        self.scene.set_pixel_snapping(snap_to_pixels);
+    }
+
+    fn set_load_op(&mut self, load_op: LoadOp) {
+        self.scene.set_load_op(load_op);
+    }
+
     fn set_filter_effect(&mut self, filter: Filter) {
         self.scene.set_filter_effect(filter);
     }
@@ -959,6 +1295,10 @@ impl Renderer for HybridRenderer {
         unimplemented!("external textures are not wired up for the WebGL test backend")
     }
 
+    fn set_texture_paint(&mut self, _: TexturePaint) {
+        unimplemented!("external textures are not wired up for the WebGL test backend")
+    }
+
     fn get_image_source(&mut self, pixmap: Arc<Pixmap>) -> ImageSource {
         let image_id = self.upload_image(&pixmap);
         ImageSource::opaque_id_with_transparency_hint(image_id, pixmap.may_have_transparency())
@@ -967,4 +1307,23 @@ impl Renderer for HybridRenderer {
     fn register_image(&mut self, pixmap: Arc<Pixmap>) -> ImageId {
         self.upload_image(&pixmap)
     }
+
+    fn register_image_mipmapped(&mut self, pixmap: Arc<Pixmap>) -> ImageId {
+        #[cfg(feature = "webgpu_parity")]
+        self.parity_record
+            .uploads
+            .push(crate::parity::ParityUpload::ImageMipmapped(pixmap.clone()));
+        self.renderer
+            .upload_image_mipmapped(&mut self.resources, pixmap)
+    }
+
+    fn register_pattern(&mut self, pattern: &Pattern) -> ImageId {
+        #[cfg(feature = "webgpu_parity")]
+        self.parity_record
+            .uploads
+            .push(crate::parity::ParityUpload::Pattern(pattern.clone()));
+        self.renderer
+            .upload_pattern(&mut self.resources, pattern)
+            .unwrap()
+    }
 }
@@ -456,7 +456,7 @@ pub(crate) fn check_ref(
 }
 
 #[cfg(target_arch = "wasm32")]
-fn append_diff_image_to_browser_document(specific_name: &str, diff_image: &RgbaImage) {
+pub(crate) fn append_diff_image_to_browser_document(specific_name: &str, diff_image: &RgbaImage) {
     use image::ImageEncoder;
     use wasm_bindgen::JsCast;
     use web_sys::js_sys::{Array, Uint8Array};
@@ -524,7 +524,7 @@ fn append_diff_image_to_browser_document(specific_name: &str, diff_image: &RgbaI
     body.append_child(&container).unwrap();
 }
 
-fn get_diff(
+pub(crate) fn get_diff(
     expected_image: &RgbaImage,
     actual_image: &RgbaImage,
     threshold: u8,